@@ -0,0 +1,1014 @@
+//! Submodule parsing the access control lists of `PostgreSQL` objects.
+//!
+//! The catalogs store the privileges granted on an object as an array of
+//! `aclitem`s, such as `alice=arw*/bob`: the grantee (empty for `PUBLIC`),
+//! one letter per privilege, each followed by `*` when the grantee may grant
+//! it to others, and the role which granted them. An [`AclItem`] is one such
+//! entry parsed, and an [`Acl`] the whole list.
+//!
+//! A `NULL` list stands for the default privileges of the kind of object,
+//! which [`Acl::resolve`] expands as `acldefault` does: the owner holds every
+//! privilege, and `PUBLIC` may execute functions and use types. The
+//! [`HasAcl`](crate::traits::HasAcl) trait exposes the list of the catalog
+//! models carrying one.
+//!
+//! [`PgDieselDatabase`](crate::PgDieselDatabase) only holds the table and
+//! column grants of the information schema, so the grants on sequences,
+//! functions, types and schemas are loaded as [`ObjectGrant`]s along with
+//! it into a
+//! [`PgDieselDatabaseWithEventTriggers`](crate::database::PgDieselDatabaseWithEventTriggers),
+//! and the `ALTER DEFAULT PRIVILEGES` rules as [`DefaultPrivileges`].
+
+use std::{fmt::Display, str::FromStr};
+
+use diesel::{
+    PgConnection, QueryableByName, RunQueryDsl,
+    sql_types::{Array, Nullable, Text},
+};
+
+use crate::{identity::QualifiedName, models::PgDefaultAcl};
+
+#[derive(Debug, thiserror::Error)]
+/// Errors that can occur when parsing or loading access control lists.
+pub enum AclError {
+    #[error("Invalid ACL item `{0}`, expected `<grantee>=<privileges>/<grantor>`")]
+    /// The item is not made of a grantee, privileges and a grantor.
+    InvalidItem(String),
+    #[error("Unknown privilege `{privilege}` in ACL item `{item}`")]
+    /// The item grants a privilege whose letter is unknown.
+    UnknownPrivilege {
+        /// The letter of the privilege.
+        privilege: char,
+        /// The item granting it.
+        item: String,
+    },
+    #[error("Unknown object type `{0}` of default privileges")]
+    /// The object type of a `pg_default_acl` row is unknown.
+    UnknownObjectKind(String),
+    #[error("Diesel error: {0}")]
+    /// An error occurred while querying the database.
+    Diesel(#[from] diesel::result::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A privilege of an [`AclItem`].
+pub enum Privilege {
+    /// `SELECT` (`r`).
+    Select,
+    /// `INSERT` (`a`).
+    Insert,
+    /// `UPDATE` (`w`).
+    Update,
+    /// `DELETE` (`d`).
+    Delete,
+    /// `TRUNCATE` (`D`).
+    Truncate,
+    /// `REFERENCES` (`x`).
+    References,
+    /// `TRIGGER` (`t`).
+    Trigger,
+    /// `EXECUTE` (`X`).
+    Execute,
+    /// `USAGE` (`U`).
+    Usage,
+    /// `CREATE` (`C`).
+    Create,
+    /// `CONNECT` (`c`).
+    Connect,
+    /// `TEMPORARY` (`T`).
+    Temporary,
+    /// `SET` (`s`), on configuration parameters.
+    Set,
+    /// `ALTER SYSTEM` (`A`), on configuration parameters.
+    AlterSystem,
+    /// `MAINTAIN` (`m`), on tables as of `PostgreSQL` 17.
+    Maintain,
+}
+
+impl Privilege {
+    #[must_use]
+    /// Returns the privilege of the letter, or `None` if it is unknown.
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'r' => Some(Self::Select),
+            'a' => Some(Self::Insert),
+            'w' => Some(Self::Update),
+            'd' => Some(Self::Delete),
+            'D' => Some(Self::Truncate),
+            'x' => Some(Self::References),
+            't' => Some(Self::Trigger),
+            'X' => Some(Self::Execute),
+            'U' => Some(Self::Usage),
+            'C' => Some(Self::Create),
+            'c' => Some(Self::Connect),
+            'T' => Some(Self::Temporary),
+            's' => Some(Self::Set),
+            'A' => Some(Self::AlterSystem),
+            'm' => Some(Self::Maintain),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Returns the letter of the privilege in an `aclitem`.
+    pub fn code(self) -> char {
+        match self {
+            Self::Select => 'r',
+            Self::Insert => 'a',
+            Self::Update => 'w',
+            Self::Delete => 'd',
+            Self::Truncate => 'D',
+            Self::References => 'x',
+            Self::Trigger => 't',
+            Self::Execute => 'X',
+            Self::Usage => 'U',
+            Self::Create => 'C',
+            Self::Connect => 'c',
+            Self::Temporary => 'T',
+            Self::Set => 's',
+            Self::AlterSystem => 'A',
+            Self::Maintain => 'm',
+        }
+    }
+}
+
+impl Display for Privilege {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Select => "SELECT",
+            Self::Insert => "INSERT",
+            Self::Update => "UPDATE",
+            Self::Delete => "DELETE",
+            Self::Truncate => "TRUNCATE",
+            Self::References => "REFERENCES",
+            Self::Trigger => "TRIGGER",
+            Self::Execute => "EXECUTE",
+            Self::Usage => "USAGE",
+            Self::Create => "CREATE",
+            Self::Connect => "CONNECT",
+            Self::Temporary => "TEMPORARY",
+            Self::Set => "SET",
+            Self::AlterSystem => "ALTER SYSTEM",
+            Self::Maintain => "MAINTAIN",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The role an [`AclItem`] grants privileges to.
+pub enum Grantee {
+    /// Every role, written as an empty grantee in an `aclitem`.
+    Public,
+    /// The role with the provided name.
+    Role(String),
+}
+
+impl Grantee {
+    #[must_use]
+    /// Returns whether the grantee is the provided role or `PUBLIC`,
+    /// disregarding the roles the role is a member of.
+    pub fn includes(&self, role: &str) -> bool {
+        match self {
+            Self::Public => true,
+            Self::Role(name) => name == role,
+        }
+    }
+}
+
+impl Display for Grantee {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Public => f.write_str("PUBLIC"),
+            Self::Role(name) => f.write_str(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A privilege granted by an [`AclItem`], with its grant option.
+pub struct GrantedPrivilege {
+    /// The privilege.
+    privilege: Privilege,
+    /// Whether the grantee may grant the privilege to others.
+    grantable: bool,
+}
+
+impl GrantedPrivilege {
+    #[must_use]
+    /// Creates a new `GrantedPrivilege`.
+    pub fn new(privilege: Privilege, grantable: bool) -> Self {
+        Self {
+            privilege,
+            grantable,
+        }
+    }
+
+    /// Returns the privilege.
+    #[must_use]
+    pub fn privilege(self) -> Privilege {
+        self.privilege
+    }
+
+    /// Returns whether the grantee may grant the privilege to others, as
+    /// given by `WITH GRANT OPTION`.
+    #[must_use]
+    pub fn is_grantable(self) -> bool {
+        self.grantable
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An entry of an access control list: the privileges a role granted to
+/// another one, as in `alice=arw*/bob`.
+pub struct AclItem {
+    /// The role the privileges are granted to.
+    grantee: Grantee,
+    /// The role which granted the privileges.
+    grantor: String,
+    /// The privileges, in the order of the `aclitem`.
+    privileges: Vec<GrantedPrivilege>,
+}
+
+impl AclItem {
+    #[must_use]
+    /// Creates a new `AclItem`.
+    ///
+    /// # Arguments
+    ///
+    /// * `grantee` - The role the privileges are granted to.
+    /// * `grantor` - The role which granted the privileges.
+    /// * `privileges` - The privileges.
+    pub fn new<S: AsRef<str>>(
+        grantee: Grantee,
+        grantor: S,
+        privileges: Vec<GrantedPrivilege>,
+    ) -> Self {
+        Self {
+            grantee,
+            grantor: grantor.as_ref().to_owned(),
+            privileges,
+        }
+    }
+
+    /// Returns the role the privileges are granted to.
+    #[must_use]
+    pub fn grantee(&self) -> &Grantee {
+        &self.grantee
+    }
+
+    /// Returns the role which granted the privileges.
+    #[must_use]
+    pub fn grantor(&self) -> &str {
+        &self.grantor
+    }
+
+    /// Returns the privileges, in the order of the `aclitem`.
+    #[must_use]
+    pub fn privileges(&self) -> &[GrantedPrivilege] {
+        &self.privileges
+    }
+
+    #[must_use]
+    /// Returns whether the item grants the privilege.
+    pub fn grants(&self, privilege: Privilege) -> bool {
+        self.privileges
+            .iter()
+            .any(|granted| granted.privilege == privilege)
+    }
+}
+
+/// Parses the role name at the start of the text, double-quoted as
+/// `aclitem` output quotes names with special characters, returning it and
+/// the rest of the text.
+fn parse_role(text: &str) -> Option<(String, &str)> {
+    let Some(quoted) = text.strip_prefix('"') else {
+        let end = text.find(['=', '/']).unwrap_or(text.len());
+        return Some((text[..end].to_owned(), &text[end..]));
+    };
+    let mut role = String::new();
+    let mut chars = quoted.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        if c != '"' {
+            role.push(c);
+        } else if chars.peek().is_some_and(|(_, next)| *next == '"') {
+            role.push('"');
+            chars.next();
+        } else {
+            return Some((role, &quoted[position + 1..]));
+        }
+    }
+    None
+}
+
+/// Writes the role name, double-quoted if it contains characters with a
+/// meaning in an `aclitem`.
+fn write_role(f: &mut std::fmt::Formatter<'_>, role: &str) -> std::fmt::Result {
+    if role
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$'))
+    {
+        f.write_str(role)
+    } else {
+        write!(f, "\"{}\"", role.replace('"', "\"\""))
+    }
+}
+
+impl FromStr for AclItem {
+    type Err = AclError;
+
+    fn from_str(item: &str) -> Result<Self, Self::Err> {
+        let invalid = || AclError::InvalidItem(item.to_owned());
+        let (grantee, rest) = parse_role(item).ok_or_else(invalid)?;
+        let rest = rest.strip_prefix('=').ok_or_else(invalid)?;
+        let (privileges, grantor) = rest.rsplit_once('/').ok_or_else(invalid)?;
+        let (grantor, rest) = parse_role(grantor).ok_or_else(invalid)?;
+        if grantor.is_empty() || !rest.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut granted: Vec<GrantedPrivilege> = Vec::new();
+        for code in privileges.chars() {
+            if code == '*' {
+                granted.last_mut().ok_or_else(invalid)?.grantable = true;
+                continue;
+            }
+            let privilege =
+                Privilege::from_code(code).ok_or_else(|| AclError::UnknownPrivilege {
+                    privilege: code,
+                    item: item.to_owned(),
+                })?;
+            granted.push(GrantedPrivilege::new(privilege, false));
+        }
+
+        Ok(Self {
+            grantee: if grantee.is_empty() {
+                Grantee::Public
+            } else {
+                Grantee::Role(grantee)
+            },
+            grantor,
+            privileges: granted,
+        })
+    }
+}
+
+impl Display for AclItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Grantee::Role(name) = &self.grantee {
+            write_role(f, name)?;
+        }
+        f.write_str("=")?;
+        for granted in &self.privileges {
+            write!(f, "{}", granted.privilege.code())?;
+            if granted.grantable {
+                f.write_str("*")?;
+            }
+        }
+        f.write_str("/")?;
+        write_role(f, &self.grantor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The kinds of objects whose access control lists are interpreted here,
+/// which are also those `ALTER DEFAULT PRIVILEGES` applies to.
+pub enum AclObjectKind {
+    /// A table, view, materialized view or foreign table (`r`).
+    Table,
+    /// A sequence (`S`).
+    Sequence,
+    /// A function, procedure or aggregate (`f`).
+    Function,
+    /// A type or domain (`T`).
+    Type,
+    /// A schema (`n`).
+    Schema,
+}
+
+impl AclObjectKind {
+    #[must_use]
+    /// Returns the kind of the code of `pg_default_acl.defaclobjtype`, or
+    /// `None` if it is not one of `r`, `S`, `f`, `T` or `n`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "r" => Some(Self::Table),
+            "S" => Some(Self::Sequence),
+            "f" => Some(Self::Function),
+            "T" => Some(Self::Type),
+            "n" => Some(Self::Schema),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Returns the privileges which can be granted on objects of the kind,
+    /// as held by their owner by default.
+    pub fn privileges(self) -> &'static [Privilege] {
+        match self {
+            #[cfg(any(feature = "postgres-17", feature = "postgres-18"))]
+            Self::Table => &[
+                Privilege::Insert,
+                Privilege::Select,
+                Privilege::Update,
+                Privilege::Delete,
+                Privilege::Truncate,
+                Privilege::References,
+                Privilege::Trigger,
+                Privilege::Maintain,
+            ],
+            #[cfg(not(any(feature = "postgres-17", feature = "postgres-18")))]
+            Self::Table => &[
+                Privilege::Insert,
+                Privilege::Select,
+                Privilege::Update,
+                Privilege::Delete,
+                Privilege::Truncate,
+                Privilege::References,
+                Privilege::Trigger,
+            ],
+            Self::Sequence => &[Privilege::Select, Privilege::Update, Privilege::Usage],
+            Self::Function => &[Privilege::Execute],
+            Self::Type => &[Privilege::Usage],
+            Self::Schema => &[Privilege::Usage, Privilege::Create],
+        }
+    }
+
+    #[must_use]
+    /// Returns the privileges `PUBLIC` holds by default on objects of the
+    /// kind.
+    pub fn public_privileges(self) -> &'static [Privilege] {
+        match self {
+            Self::Function => &[Privilege::Execute],
+            Self::Type => &[Privilege::Usage],
+            Self::Table | Self::Sequence | Self::Schema => &[],
+        }
+    }
+}
+
+impl Display for AclObjectKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Table => "TABLE",
+            Self::Sequence => "SEQUENCE",
+            Self::Function => "FUNCTION",
+            Self::Type => "TYPE",
+            Self::Schema => "SCHEMA",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An access control list, as stored in the `*acl` columns of the catalogs.
+pub struct Acl {
+    /// The entries of the list.
+    items: Vec<AclItem>,
+}
+
+impl Acl {
+    /// Parses the `aclitem`s of an access control list.
+    ///
+    /// # Errors
+    ///
+    /// * If an item is not a valid `aclitem`.
+    pub fn parse<S: AsRef<str>>(items: &[S]) -> Result<Self, AclError> {
+        Ok(Self {
+            items: items
+                .iter()
+                .map(|item| item.as_ref().parse())
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    #[must_use]
+    /// Returns the default access control list of an object of the kind
+    /// owned by the role, which a `NULL` list stands for.
+    pub fn default_for(kind: AclObjectKind, owner: &str) -> Self {
+        let all = |privileges: &[Privilege]| {
+            privileges
+                .iter()
+                .map(|privilege| GrantedPrivilege::new(*privilege, false))
+                .collect::<Vec<_>>()
+        };
+        let mut items = Vec::new();
+        if !kind.public_privileges().is_empty() {
+            items.push(AclItem::new(
+                Grantee::Public,
+                owner,
+                all(kind.public_privileges()),
+            ));
+        }
+        items.push(AclItem::new(
+            Grantee::Role(owner.to_owned()),
+            owner,
+            all(kind.privileges()),
+        ));
+        Self { items }
+    }
+
+    /// Parses the access control list of an object, or returns the default
+    /// one if it is `NULL`.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The `aclitem`s of the object, or `None` if `NULL`.
+    /// * `kind` - The kind of the object.
+    /// * `owner` - The name of the owner of the object.
+    ///
+    /// # Errors
+    ///
+    /// * If an item is not a valid `aclitem`.
+    pub fn resolve<S: AsRef<str>>(
+        items: Option<&[S]>,
+        kind: AclObjectKind,
+        owner: &str,
+    ) -> Result<Self, AclError> {
+        match items {
+            Some(items) => Self::parse(items),
+            None => Ok(Self::default_for(kind, owner)),
+        }
+    }
+
+    /// Returns the entries of the list.
+    #[must_use]
+    pub fn items(&self) -> &[AclItem] {
+        &self.items
+    }
+
+    #[must_use]
+    /// Returns the privileges granted to the role, directly or through
+    /// `PUBLIC`, disregarding the roles it is a member of. A privilege is
+    /// grantable if any entry grants it with the grant option.
+    pub fn privileges_of(&self, role: &str) -> Vec<GrantedPrivilege> {
+        let mut privileges: Vec<GrantedPrivilege> = Vec::new();
+        for item in self.items.iter().filter(|item| item.grantee.includes(role)) {
+            for granted in &item.privileges {
+                match privileges
+                    .iter_mut()
+                    .find(|known| known.privilege == granted.privilege)
+                {
+                    Some(known) => known.grantable |= granted.grantable,
+                    None => privileges.push(*granted),
+                }
+            }
+        }
+        privileges.sort();
+        privileges
+    }
+}
+
+impl Display for Acl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("{")?;
+        for (position, item) in self.items.iter().enumerate() {
+            if position > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{item}")?;
+        }
+        f.write_str("}")
+    }
+}
+
+#[derive(QueryableByName)]
+/// An object with its owner and raw access control list.
+struct ObjectAclRow {
+    /// The `pg_default_acl` code of the kind of the object.
+    #[diesel(sql_type = Text)]
+    kind: String,
+    /// The schema of the object, or the schema itself.
+    #[diesel(sql_type = Text)]
+    schema_name: String,
+    /// The name of the object, with the argument types of functions.
+    #[diesel(sql_type = Text)]
+    object_name: String,
+    /// The name of the owner of the object.
+    #[diesel(sql_type = Text)]
+    owner_name: String,
+    /// The `aclitem`s of the object, or `NULL` for the default ones.
+    #[diesel(sql_type = Nullable<Array<Text>>)]
+    acl: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A privilege granted on a table, sequence, function, type or schema, with
+/// the default privileges of `NULL` access control lists expanded.
+pub struct ObjectGrant {
+    /// The kind of the object.
+    kind: AclObjectKind,
+    /// The qualified name of the object, whose schema and name are both the
+    /// name of the schema for schemas.
+    object: QualifiedName,
+    /// The role the privilege is granted to.
+    grantee: Grantee,
+    /// The role which granted the privilege.
+    grantor: String,
+    /// The privilege and its grant option.
+    privilege: GrantedPrivilege,
+}
+
+impl ObjectGrant {
+    /// Loads the privileges granted on the tables, sequences, functions and
+    /// types of the provided schemas, and on the schemas themselves, sorted
+    /// by kind and object.
+    ///
+    /// # Arguments
+    ///
+    /// * `schemas` - The schemas to load the grants of.
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    /// * If an access control list is not valid
+    pub fn load_all<S: AsRef<str>>(
+        schemas: &[S],
+        conn: &mut PgConnection,
+    ) -> Result<Vec<Self>, AclError> {
+        let schemas: Vec<&str> = schemas.iter().map(AsRef::as_ref).collect();
+        let rows: Vec<ObjectAclRow> = diesel::sql_query(
+            r"
+            SELECT * FROM (
+                SELECT
+                    CASE WHEN c.relkind = 'S' THEN 'S' ELSE 'r' END AS kind,
+                    n.nspname::text AS schema_name,
+                    c.relname::text AS object_name,
+                    pg_get_userbyid(c.relowner)::text AS owner_name,
+                    c.relacl::text[] AS acl
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE c.relkind IN ('r', 'p', 'v', 'm', 'f', 'S')
+                UNION ALL
+                SELECT
+                    'f',
+                    n.nspname::text,
+                    format('%s(%s)', p.proname, pg_get_function_identity_arguments(p.oid)),
+                    pg_get_userbyid(p.proowner)::text,
+                    p.proacl::text[]
+                FROM pg_proc p
+                JOIN pg_namespace n ON n.oid = p.pronamespace
+                UNION ALL
+                SELECT
+                    'T',
+                    n.nspname::text,
+                    t.typname::text,
+                    pg_get_userbyid(t.typowner)::text,
+                    t.typacl::text[]
+                FROM pg_type t
+                JOIN pg_namespace n ON n.oid = t.typnamespace
+                LEFT JOIN pg_class c ON c.oid = t.typrelid
+                WHERE t.typcategory <> 'A' AND (c.oid IS NULL OR c.relkind = 'c')
+                UNION ALL
+                SELECT
+                    'n',
+                    n.nspname::text,
+                    n.nspname::text,
+                    pg_get_userbyid(n.nspowner)::text,
+                    n.nspacl::text[]
+                FROM pg_namespace n
+            ) AS objects
+            WHERE schema_name = ANY($1)
+            ORDER BY kind, schema_name, object_name
+            ",
+        )
+        .bind::<Array<Text>, _>(&schemas)
+        .load(conn)?;
+
+        let mut grants = Vec::new();
+        for row in rows {
+            let kind = AclObjectKind::from_code(&row.kind)
+                .ok_or_else(|| AclError::UnknownObjectKind(row.kind.clone()))?;
+            let acl = Acl::resolve(row.acl.as_deref(), kind, &row.owner_name)?;
+            let object = QualifiedName::new(&row.schema_name, &row.object_name);
+            grants.extend(Self::from_acl(kind, &object, &acl));
+        }
+        grants.sort();
+        Ok(grants)
+    }
+
+    /// Returns the grants of the access control list of the object.
+    fn from_acl<'acl>(
+        kind: AclObjectKind,
+        object: &'acl QualifiedName,
+        acl: &'acl Acl,
+    ) -> impl Iterator<Item = Self> + 'acl {
+        acl.items().iter().flat_map(move |item| {
+            item.privileges().iter().map(move |privilege| Self {
+                kind,
+                object: object.clone(),
+                grantee: item.grantee().clone(),
+                grantor: item.grantor().to_owned(),
+                privilege: *privilege,
+            })
+        })
+    }
+
+    /// Returns the kind of the object.
+    #[must_use]
+    pub fn kind(&self) -> AclObjectKind {
+        self.kind
+    }
+
+    /// Returns the qualified name of the object, with the argument types of
+    /// functions, as in `public.lookup(integer)`. For schemas, both its
+    /// schema and name are the name of the schema.
+    #[must_use]
+    pub fn object(&self) -> &QualifiedName {
+        &self.object
+    }
+
+    /// Returns the role the privilege is granted to.
+    #[must_use]
+    pub fn grantee(&self) -> &Grantee {
+        &self.grantee
+    }
+
+    /// Returns the role which granted the privilege.
+    #[must_use]
+    pub fn grantor(&self) -> &str {
+        &self.grantor
+    }
+
+    /// Returns the privilege.
+    #[must_use]
+    pub fn privilege(&self) -> Privilege {
+        self.privilege.privilege()
+    }
+
+    /// Returns whether the grantee may grant the privilege to others.
+    #[must_use]
+    pub fn is_grantable(&self) -> bool {
+        self.privilege.is_grantable()
+    }
+}
+
+#[derive(QueryableByName)]
+/// A `pg_default_acl` row along with the names of its role and schema.
+struct DefaultAclRow {
+    /// The default privileges.
+    #[diesel(embed)]
+    default_acl: PgDefaultAcl,
+    /// The name of the role creating the objects.
+    #[diesel(sql_type = Text)]
+    role_name: String,
+    /// The name of the schema, or `NULL` for every schema.
+    #[diesel(sql_type = Nullable<Text>)]
+    schema_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The privileges granted on the objects a role creates, as set by
+/// `ALTER DEFAULT PRIVILEGES`.
+///
+/// Rules for a schema are added to the default privileges of the kind of
+/// object, while rules for every schema replace them: revoking `EXECUTE`
+/// from `PUBLIC` on functions is only possible for every schema.
+pub struct DefaultPrivileges {
+    /// The role creating the objects.
+    role: String,
+    /// The schema the objects are created in, or `None` for every schema.
+    schema: Option<String>,
+    /// The kind of objects.
+    kind: AclObjectKind,
+    /// The privileges granted on the objects.
+    acl: Acl,
+}
+
+impl DefaultPrivileges {
+    /// Loads the default privileges of the database, sorted by role,
+    /// schema and kind of objects.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    /// * If an access control list or object type is not valid
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, AclError> {
+        let rows: Vec<DefaultAclRow> = diesel::sql_query(
+            r"
+            SELECT
+                d.oid,
+                d.defaclrole,
+                d.defaclnamespace,
+                d.defaclobjtype::text AS defaclobjtype,
+                d.defaclacl::text[] AS defaclacl,
+                pg_get_userbyid(d.defaclrole)::text AS role_name,
+                n.nspname::text AS schema_name
+            FROM pg_default_acl d
+            LEFT JOIN pg_namespace n ON n.oid = d.defaclnamespace
+            ORDER BY role_name, schema_name NULLS FIRST, d.defaclobjtype
+            ",
+        )
+        .load(conn)?;
+        rows.into_iter()
+            .map(|row| {
+                Self::new(
+                    row.role_name,
+                    row.schema_name,
+                    &row.default_acl.defaclobjtype,
+                    &row.default_acl.defaclacl,
+                )
+            })
+            .collect()
+    }
+
+    /// Creates a new `DefaultPrivileges` from the columns of a
+    /// `pg_default_acl` row.
+    ///
+    /// # Arguments
+    ///
+    /// * `role` - The role creating the objects.
+    /// * `schema` - The schema the objects are created in, or `None` for
+    ///   every schema.
+    /// * `kind` - The `defaclobjtype` code of the kind of objects.
+    /// * `acl` - The `aclitem`s granted on the objects.
+    ///
+    /// # Errors
+    ///
+    /// * If the kind of objects or an `aclitem` is not valid.
+    pub fn new<S: AsRef<str>>(
+        role: String,
+        schema: Option<String>,
+        kind: &str,
+        acl: &[S],
+    ) -> Result<Self, AclError> {
+        Ok(Self {
+            role,
+            schema,
+            kind: AclObjectKind::from_code(kind)
+                .ok_or_else(|| AclError::UnknownObjectKind(kind.to_owned()))?,
+            acl: Acl::parse(acl)?,
+        })
+    }
+
+    /// Returns the role creating the objects.
+    #[must_use]
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+
+    /// Returns the schema the objects are created in, or `None` for every
+    /// schema.
+    #[must_use]
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    /// Returns the kind of objects.
+    #[must_use]
+    pub fn kind(&self) -> AclObjectKind {
+        self.kind
+    }
+
+    /// Returns the privileges granted on the objects.
+    #[must_use]
+    pub fn acl(&self) -> &Acl {
+        &self.acl
+    }
+
+    #[must_use]
+    /// Returns the `ALTER DEFAULT PRIVILEGES` statements granting the
+    /// privileges to their grantees, other than the creating role.
+    pub fn grant_statements(&self) -> Vec<String> {
+        let mut statements = Vec::new();
+        for item in self.acl.items() {
+            if item.grantee().includes(&self.role) && *item.grantee() != Grantee::Public {
+                continue;
+            }
+            let mut statement = format!("ALTER DEFAULT PRIVILEGES FOR ROLE {}", self.role);
+            if let Some(schema) = &self.schema {
+                statement.push_str(" IN SCHEMA ");
+                statement.push_str(schema);
+            }
+            let privileges: Vec<String> = item
+                .privileges()
+                .iter()
+                .map(|granted| granted.privilege().to_string())
+                .collect();
+            statement.push_str(&format!(
+                " GRANT {} ON {}S TO {}",
+                privileges.join(", "),
+                self.kind,
+                item.grantee()
+            ));
+            statements.push(statement);
+        }
+        statements
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_acl_items() {
+        let item: AclItem = "alice=arw*/bob".parse().unwrap();
+        assert_eq!(item.grantee(), &Grantee::Role("alice".to_owned()));
+        assert_eq!(item.grantor(), "bob");
+        assert_eq!(
+            item.privileges(),
+            [
+                GrantedPrivilege::new(Privilege::Insert, false),
+                GrantedPrivilege::new(Privilege::Select, false),
+                GrantedPrivilege::new(Privilege::Update, true),
+            ]
+        );
+        assert_eq!(item.to_string(), "alice=arw*/bob");
+
+        let public: AclItem = "=X/postgres".parse().unwrap();
+        assert_eq!(public.grantee(), &Grantee::Public);
+        assert!(public.grants(Privilege::Execute));
+        assert_eq!(public.to_string(), "=X/postgres");
+
+        let quoted: AclItem = "\"sales team\"=U/\"a\"\"b\"".parse().unwrap();
+        assert_eq!(quoted.grantee().to_string(), "sales team");
+        assert_eq!(quoted.grantor(), "a\"b");
+        assert_eq!(quoted.to_string(), "\"sales team\"=U/\"a\"\"b\"");
+
+        for invalid in ["alice", "alice=r", "alice=*r/bob", "alice=r/"] {
+            assert!(matches!(
+                invalid.parse::<AclItem>(),
+                Err(AclError::InvalidItem(item)) if item == invalid
+            ));
+        }
+        assert!(matches!(
+            "alice=rz/bob".parse::<AclItem>(),
+            Err(AclError::UnknownPrivilege { privilege: 'z', .. })
+        ));
+    }
+
+    #[test]
+    fn test_acl() {
+        let acl = Acl::parse(&["alice=r/owner", "=r*w/owner", "owner=arwdDxt/owner"]).unwrap();
+        assert_eq!(
+            acl.privileges_of("alice"),
+            [
+                GrantedPrivilege::new(Privilege::Select, true),
+                GrantedPrivilege::new(Privilege::Update, false),
+            ]
+        );
+        assert_eq!(
+            acl.to_string(),
+            "{alice=r/owner,=r*w/owner,owner=arwdDxt/owner}"
+        );
+
+        let function = Acl::resolve::<String>(None, AclObjectKind::Function, "owner").unwrap();
+        assert_eq!(function.to_string(), "{=X/owner,owner=X/owner}");
+        assert_eq!(
+            function.privileges_of("anyone"),
+            [GrantedPrivilege::new(Privilege::Execute, false)]
+        );
+        let schema = Acl::resolve::<String>(None, AclObjectKind::Schema, "owner").unwrap();
+        assert_eq!(schema.to_string(), "{owner=UC/owner}");
+        assert!(schema.privileges_of("anyone").is_empty());
+    }
+
+    #[test]
+    fn test_object_grants() {
+        let object = QualifiedName::new("public", "lookup(integer)");
+        let acl = Acl::default_for(AclObjectKind::Function, "owner");
+        let grants: Vec<ObjectGrant> =
+            ObjectGrant::from_acl(AclObjectKind::Function, &object, &acl).collect();
+        assert_eq!(grants.len(), 2);
+        assert_eq!(grants[0].grantee(), &Grantee::Public);
+        assert_eq!(grants[0].privilege(), Privilege::Execute);
+        assert_eq!(grants[1].grantee().to_string(), "owner");
+        assert_eq!(grants[1].object().name(), "lookup(integer)");
+    }
+
+    #[test]
+    fn test_default_privileges() {
+        let defaults = DefaultPrivileges::new(
+            "app".to_owned(),
+            Some("reports".to_owned()),
+            "r",
+            &["reader=r/app", "writer=arw/app"],
+        )
+        .unwrap();
+        assert_eq!(defaults.kind(), AclObjectKind::Table);
+        assert_eq!(
+            defaults.grant_statements(),
+            [
+                "ALTER DEFAULT PRIVILEGES FOR ROLE app IN SCHEMA reports GRANT SELECT ON TABLES \
+                 TO reader",
+                "ALTER DEFAULT PRIVILEGES FOR ROLE app IN SCHEMA reports GRANT INSERT, SELECT, \
+                 UPDATE ON TABLES TO writer",
+            ]
+        );
+        assert!(matches!(
+            DefaultPrivileges::new("app".to_owned(), None, "L", &["=r/app"]),
+            Err(AclError::UnknownObjectKind(kind)) if kind == "L"
+        ));
+    }
+}
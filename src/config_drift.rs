@@ -0,0 +1,708 @@
+//! Submodule capturing the configuration of a server to detect drift.
+//!
+//! A [`ConfigSnapshot`] holds the run-time parameters of `pg_settings`, with
+//! their values normalized into [`SettingValue`]s, memory in bytes and time
+//! in milliseconds, the errors reported by `pg_file_settings` and the
+//! per-database and per-role overrides of `pg_db_role_setting`. It lists the
+//! parameters which can only be changed by restarting the server, and those
+//! waiting for a restart to apply. Two snapshots, of two servers or of one
+//! server over time, are compared with [`ConfigSnapshot::diff`].
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use bigdecimal::ToPrimitive;
+use diesel::{
+    PgConnection, QueryableByName, RunQueryDsl,
+    sql_types::{Array, Nullable, Text},
+};
+
+use crate::models::{PgFileSetting, PgSetting};
+
+/// The sources of the values set by the session taking the snapshot, rather
+/// than by the configuration of the server.
+const SESSION_SOURCES: [&str; 2] = ["client", "session"];
+
+/// The memory units, from the largest, with their size in bytes.
+const MEMORY_UNITS: [(&str, i64); 5] = [
+    ("TB", 1 << 40),
+    ("GB", 1 << 30),
+    ("MB", 1 << 20),
+    ("kB", 1 << 10),
+    ("B", 1),
+];
+
+/// The time units, from the largest, with their duration in milliseconds.
+const TIME_UNITS: [(&str, f64); 6] = [
+    ("d", 86_400_000.0),
+    ("h", 3_600_000.0),
+    ("min", 60_000.0),
+    ("s", 1_000.0),
+    ("ms", 1.0),
+    ("us", 0.001),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The value of a run-time parameter, normalized to its type and unit.
+///
+/// Negative values of parameters with a unit, which conventionally disable
+/// the feature or defer to another parameter, are kept as unitless numbers.
+pub enum SettingValue {
+    /// A boolean parameter.
+    Bool(bool),
+    /// An integer parameter without unit.
+    Integer(i64),
+    /// A real parameter without unit.
+    Real(f64),
+    /// An amount of memory, in bytes.
+    Memory(i64),
+    /// A duration, in milliseconds.
+    Time(f64),
+    /// One of the allowed values of an enum parameter.
+    Enum(String),
+    /// A string parameter, or a value which could not be parsed.
+    String(String),
+}
+
+/// The kind of the unit of a parameter, with the size of the unit.
+enum UnitKind {
+    /// A memory unit, in bytes.
+    Memory(i64),
+    /// A time unit, in milliseconds.
+    Time(f64),
+}
+
+impl UnitKind {
+    /// Returns the kind of the unit, as in `8kB`, `MB` or `min`.
+    fn parse(unit: &str) -> Option<Self> {
+        if let Some((_, milliseconds)) = TIME_UNITS.iter().find(|(name, _)| *name == unit) {
+            return Some(Self::Time(*milliseconds));
+        }
+        let digits = unit.len() - unit.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let multiplier: i64 = if digits == 0 {
+            1
+        } else {
+            unit[..digits].parse().ok()?
+        };
+        MEMORY_UNITS
+            .iter()
+            .find(|(name, _)| *name == &unit[digits..])
+            .map(|(_, bytes)| Self::Memory(multiplier * bytes))
+    }
+}
+
+impl SettingValue {
+    #[must_use]
+    /// Parses the value of a parameter of the provided type and unit.
+    ///
+    /// Numeric values may carry their own unit, as in `64MB` or `5min`, as
+    /// in `postgresql.conf` or `ALTER ROLE ... SET`; otherwise, they are
+    /// expressed in the unit of the parameter, as in `pg_settings`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to parse.
+    /// * `vartype` - The type of the parameter, as in `pg_settings.vartype`.
+    /// * `unit` - The unit of the parameter, as in `pg_settings.unit`.
+    pub fn parse(value: &str, vartype: &str, unit: Option<&str>) -> Self {
+        let parsed = match vartype {
+            "bool" => parse_bool(value).map(Self::Bool),
+            "integer" | "real" => Self::parse_number(value, vartype, unit),
+            "enum" => Some(Self::Enum(value.to_owned())),
+            _ => None,
+        };
+        parsed.unwrap_or_else(|| Self::String(value.to_owned()))
+    }
+
+    /// Parses a numeric value, with or without its own unit.
+    fn parse_number(value: &str, vartype: &str, unit: Option<&str>) -> Option<Self> {
+        let value = value.trim();
+        let suffix =
+            value.trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '.' | '-' | '+'));
+        let number: f64 = value[..value.len() - suffix.len()].parse().ok()?;
+        let suffix = suffix.trim();
+        let kind = if suffix.is_empty() {
+            unit.and_then(UnitKind::parse)
+        } else {
+            Some(UnitKind::parse(suffix)?)
+        };
+        Some(match kind {
+            Some(UnitKind::Memory(bytes)) if number >= 0.0 => {
+                Self::Memory((number * bytes.to_f64()?).round().to_i64()?)
+            }
+            Some(UnitKind::Time(milliseconds)) if number >= 0.0 => {
+                Self::Time(number * milliseconds)
+            }
+            _ if vartype == "integer" => Self::Integer(number.to_i64()?),
+            _ => Self::Real(number),
+        })
+    }
+}
+
+/// Parses a boolean as the server does, accepting unambiguous prefixes.
+fn parse_bool(value: &str) -> Option<bool> {
+    let value = value.trim().to_ascii_lowercase();
+    let matches =
+        |word: &str, min_length: usize| value.len() >= min_length && word.starts_with(&value);
+    if value == "1" || matches("on", 2) || matches("true", 1) || matches("yes", 1) {
+        Some(true)
+    } else if value == "0" || matches("off", 2) || matches("false", 1) || matches("no", 1) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+impl Display for SettingValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool(value) => f.write_str(if *value { "on" } else { "off" }),
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Real(value) => write!(f, "{value}"),
+            Self::Memory(bytes) => {
+                let (name, size) = MEMORY_UNITS
+                    .iter()
+                    .find(|(_, size)| *bytes != 0 && bytes % size == 0)
+                    .unwrap_or(&("B", 1));
+                write!(f, "{}{name}", bytes / size)
+            }
+            Self::Time(milliseconds) => {
+                let (name, size) = TIME_UNITS
+                    .iter()
+                    .find(|(_, size)| *milliseconds != 0.0 && (milliseconds / size).fract() == 0.0)
+                    .unwrap_or(&("ms", 1.0));
+                write!(f, "{}{name}", milliseconds / size)
+            }
+            Self::Enum(value) | Self::String(value) => f.write_str(value),
+        }
+    }
+}
+
+/// The values of a parameter in the two compared snapshots.
+type ValuePair = (Option<SettingValue>, Option<SettingValue>);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Where a value of a parameter applies.
+pub enum SettingScope {
+    /// The whole server, as configured by `postgresql.conf`,
+    /// `postgresql.auto.conf` or the command line.
+    Server,
+    /// The sessions of a database, as set by `ALTER DATABASE ... SET`.
+    Database(String),
+    /// The sessions of a role, as set by `ALTER ROLE ... SET`.
+    Role(String),
+    /// The sessions of a role in a database, as set by
+    /// `ALTER ROLE ... IN DATABASE ... SET`.
+    DatabaseRole {
+        /// The name of the database.
+        database: String,
+        /// The name of the role.
+        role: String,
+    },
+}
+
+impl Display for SettingScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Server => f.write_str("server"),
+            Self::Database(database) => write!(f, "database {database}"),
+            Self::Role(role) => write!(f, "role {role}"),
+            Self::DatabaseRole { database, role } => {
+                write!(f, "role {role} in database {database}")
+            }
+        }
+    }
+}
+
+#[derive(QueryableByName, Debug, Clone, PartialEq, Eq)]
+/// A row of `pg_db_role_setting`, with the names of its database and role.
+struct DbRoleSettingRow {
+    #[diesel(sql_type = Nullable<Text>)]
+    /// The database the settings apply to, or all databases.
+    database: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    /// The role the settings apply to, or all roles.
+    role: Option<String>,
+    #[diesel(sql_type = Array<Text>)]
+    /// The settings, as in `work_mem=64MB`.
+    settings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A value of a parameter overriding the server-wide one for a database, a
+/// role or both.
+pub struct SettingOverride {
+    /// Where the value applies.
+    scope: SettingScope,
+    /// The name of the parameter.
+    name: String,
+    /// The value of the parameter.
+    value: SettingValue,
+}
+
+impl SettingOverride {
+    #[must_use]
+    /// Returns where the value applies.
+    pub fn scope(&self) -> &SettingScope {
+        &self.scope
+    }
+
+    #[must_use]
+    /// Returns the name of the parameter.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    /// Returns the value of the parameter.
+    pub fn value(&self) -> &SettingValue {
+        &self.value
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A parameter whose value differs between two snapshots.
+pub struct SettingDifference {
+    /// Where the value applies.
+    scope: SettingScope,
+    /// The name of the parameter.
+    name: String,
+    /// The value in the left snapshot, if it is set there.
+    left: Option<SettingValue>,
+    /// The value in the right snapshot, if it is set there.
+    right: Option<SettingValue>,
+    /// Whether aligning the value requires a server restart.
+    requires_restart: bool,
+}
+
+impl SettingDifference {
+    #[must_use]
+    /// Returns where the value applies.
+    pub fn scope(&self) -> &SettingScope {
+        &self.scope
+    }
+
+    #[must_use]
+    /// Returns the name of the parameter.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    /// Returns the value in the left snapshot, if it is set there.
+    pub fn left(&self) -> Option<&SettingValue> {
+        self.left.as_ref()
+    }
+
+    #[must_use]
+    /// Returns the value in the right snapshot, if it is set there.
+    pub fn right(&self) -> Option<&SettingValue> {
+        self.right.as_ref()
+    }
+
+    #[must_use]
+    /// Returns whether aligning the value requires a server restart.
+    pub fn requires_restart(&self) -> bool {
+        self.requires_restart
+    }
+}
+
+impl Display for SettingDifference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = |value: Option<&SettingValue>| {
+            value.map_or_else(|| "(unset)".to_owned(), ToString::to_string)
+        };
+        write!(
+            f,
+            "{} ({}): {} -> {}",
+            self.name,
+            self.scope,
+            value(self.left.as_ref()),
+            value(self.right.as_ref())
+        )?;
+        if self.requires_restart {
+            f.write_str(" (requires restart)")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The configuration of a server at a point in time.
+pub struct ConfigSnapshot {
+    /// The run-time parameters, by name.
+    settings: BTreeMap<String, PgSetting>,
+    /// The rows of `pg_file_settings` which could not be applied.
+    file_errors: Vec<PgFileSetting>,
+    /// The per-database and per-role overrides.
+    overrides: Vec<SettingOverride>,
+}
+
+impl ConfigSnapshot {
+    /// Takes a snapshot of the configuration of the server.
+    ///
+    /// Reading the configuration files and the sources of the parameters
+    /// requires superuser privileges or those of `pg_read_all_settings`.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load(conn: &mut PgConnection) -> Result<Self, diesel::result::Error> {
+        let settings = PgSetting::load_all(conn)?;
+        let file_settings = PgFileSetting::load_all(conn)?;
+        let rows: Vec<DbRoleSettingRow> = diesel::sql_query(
+            r"
+            SELECT d.datname::text AS database, r.rolname::text AS role, s.setconfig AS settings
+            FROM pg_db_role_setting s
+            LEFT JOIN pg_database d ON d.oid = s.setdatabase
+            LEFT JOIN pg_roles r ON r.oid = s.setrole
+            WHERE s.setconfig IS NOT NULL
+            ORDER BY d.datname NULLS FIRST, r.rolname NULLS FIRST
+            ",
+        )
+        .load(conn)?;
+        let overrides = rows
+            .into_iter()
+            .filter_map(|row| {
+                let scope = match (row.database, row.role) {
+                    (Some(database), Some(role)) => SettingScope::DatabaseRole { database, role },
+                    (Some(database), None) => SettingScope::Database(database),
+                    (None, Some(role)) => SettingScope::Role(role),
+                    (None, None) => return None,
+                };
+                Some(
+                    row.settings
+                        .into_iter()
+                        .map(move |setting| (scope.clone(), setting)),
+                )
+            })
+            .flatten();
+        Ok(Self::new(settings, file_settings, overrides))
+    }
+
+    #[must_use]
+    /// Creates a snapshot from the rows of `pg_settings` and
+    /// `pg_file_settings` and from the overrides of `pg_db_role_setting`.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - The rows of `pg_settings`.
+    /// * `file_settings` - The rows of `pg_file_settings`, of which only the
+    ///   ones with an error are kept.
+    /// * `overrides` - The scopes and the entries of the `setconfig` arrays
+    ///   of `pg_db_role_setting`, as in `work_mem=64MB`.
+    pub fn new<I, S>(
+        settings: Vec<PgSetting>,
+        file_settings: Vec<PgFileSetting>,
+        overrides: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = (SettingScope, S)>,
+        S: AsRef<str>,
+    {
+        let settings: BTreeMap<String, PgSetting> = settings
+            .into_iter()
+            .map(|setting| (setting.name.clone(), setting))
+            .collect();
+        let overrides = overrides
+            .into_iter()
+            .filter_map(|(scope, entry)| {
+                let (name, value) = entry.as_ref().split_once('=')?;
+                let value = match settings.get(name) {
+                    Some(setting) => {
+                        SettingValue::parse(value, &setting.vartype, setting.unit.as_deref())
+                    }
+                    None => SettingValue::String(value.to_owned()),
+                };
+                Some(SettingOverride {
+                    scope,
+                    name: name.to_owned(),
+                    value,
+                })
+            })
+            .collect();
+        Self {
+            settings,
+            file_errors: file_settings
+                .into_iter()
+                .filter(|setting| setting.error.is_some())
+                .collect(),
+            overrides,
+        }
+    }
+
+    #[must_use]
+    /// Returns the snapshot without the provided parameters, as the ones
+    /// expected to differ between servers such as `data_directory`.
+    pub fn without<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for name in names {
+            self.settings.remove(name.as_ref());
+            self.overrides
+                .retain(|setting_override| setting_override.name != name.as_ref());
+        }
+        self
+    }
+
+    /// Returns the run-time parameters, sorted by name.
+    pub fn settings(&self) -> impl Iterator<Item = &PgSetting> {
+        self.settings.values()
+    }
+
+    #[must_use]
+    /// Returns the run-time parameter with the provided name.
+    pub fn setting(&self, name: &str) -> Option<&PgSetting> {
+        self.settings.get(name)
+    }
+
+    #[must_use]
+    /// Returns the normalized value of the run-time parameter with the
+    /// provided name.
+    pub fn value(&self, name: &str) -> Option<SettingValue> {
+        self.settings.get(name).map(|setting| {
+            SettingValue::parse(&setting.setting, &setting.vartype, setting.unit.as_deref())
+        })
+    }
+
+    #[must_use]
+    /// Returns the rows of `pg_file_settings` which could not be applied.
+    pub fn file_errors(&self) -> &[PgFileSetting] {
+        &self.file_errors
+    }
+
+    #[must_use]
+    /// Returns the per-database and per-role overrides.
+    pub fn overrides(&self) -> &[SettingOverride] {
+        &self.overrides
+    }
+
+    /// Returns the parameters changed in the configuration files whose new
+    /// value only applies after a server restart.
+    pub fn pending_restart(&self) -> impl Iterator<Item = &PgSetting> {
+        self.settings
+            .values()
+            .filter(|setting| setting.pending_restart == Some(true))
+    }
+
+    /// Returns the parameters which can only be changed by restarting the
+    /// server.
+    pub fn restart_only(&self) -> impl Iterator<Item = &PgSetting> {
+        self.settings
+            .values()
+            .filter(|setting| setting.requires_restart())
+    }
+
+    /// Returns the overrides of the parameter with the provided name.
+    pub fn overrides_of<'snapshot>(
+        &'snapshot self,
+        name: &'snapshot str,
+    ) -> impl Iterator<Item = &'snapshot SettingOverride> {
+        self.overrides
+            .iter()
+            .filter(move |setting_override| setting_override.name == name)
+    }
+
+    #[must_use]
+    /// Returns the parameters whose value differs between this snapshot and
+    /// the provided one, sorted by scope and name.
+    ///
+    /// The values set by the sessions taking the snapshots, such as
+    /// `application_name`, are ignored.
+    pub fn diff(&self, other: &Self) -> Vec<SettingDifference> {
+        let mut values: BTreeMap<(SettingScope, &str), ValuePair> = BTreeMap::new();
+        for (snapshot, is_left) in [(self, true), (other, false)] {
+            let server_values = snapshot
+                .settings
+                .values()
+                .filter(|setting| !SESSION_SOURCES.contains(&setting.source.as_str()))
+                .map(|setting| {
+                    (
+                        SettingScope::Server,
+                        setting.name.as_str(),
+                        snapshot.value(&setting.name),
+                    )
+                });
+            let override_values = snapshot.overrides.iter().map(|setting_override| {
+                (
+                    setting_override.scope.clone(),
+                    setting_override.name.as_str(),
+                    Some(setting_override.value.clone()),
+                )
+            });
+            for (scope, name, value) in server_values.chain(override_values) {
+                let entry = values.entry((scope, name)).or_default();
+                if is_left {
+                    entry.0 = value;
+                } else {
+                    entry.1 = value;
+                }
+            }
+        }
+        let restart_only: BTreeSet<&str> = self
+            .restart_only()
+            .chain(other.restart_only())
+            .map(|setting| setting.name.as_str())
+            .collect();
+        values
+            .into_iter()
+            .filter(|(_, (left, right))| left != right)
+            .map(|((scope, name), (left, right))| SettingDifference {
+                requires_restart: scope == SettingScope::Server && restart_only.contains(name),
+                scope,
+                name: name.to_owned(),
+                left,
+                right,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a parameter set in `postgresql.conf`.
+    fn setting(
+        name: &str,
+        value: &str,
+        vartype: &str,
+        unit: Option<&str>,
+        context: &str,
+    ) -> PgSetting {
+        PgSetting {
+            name: name.to_owned(),
+            setting: value.to_owned(),
+            unit: unit.map(ToOwned::to_owned),
+            category: "Resource Usage / Memory".to_owned(),
+            short_desc: String::new(),
+            extra_desc: None,
+            context: context.to_owned(),
+            vartype: vartype.to_owned(),
+            source: "configuration file".to_owned(),
+            min_val: None,
+            max_val: None,
+            enumvals: None,
+            boot_val: None,
+            reset_val: None,
+            sourcefile: Some("/etc/postgresql/postgresql.conf".to_owned()),
+            sourceline: Some(1),
+            pending_restart: Some(false),
+        }
+    }
+
+    #[test]
+    fn test_setting_values() {
+        let memory = SettingValue::parse("16384", "integer", Some("8kB"));
+        assert_eq!(memory, SettingValue::Memory(128 << 20));
+        assert_eq!(memory.to_string(), "128MB");
+        assert_eq!(
+            SettingValue::parse("64MB", "integer", Some("kB")),
+            SettingValue::Memory(64 << 20)
+        );
+        assert_eq!(
+            SettingValue::parse("-1", "integer", Some("kB")),
+            SettingValue::Integer(-1)
+        );
+        let time = SettingValue::parse("300", "integer", Some("s"));
+        assert_eq!(time, SettingValue::Time(300_000.0));
+        assert_eq!(time.to_string(), "5min");
+        assert_eq!(
+            SettingValue::parse("2", "real", Some("ms")).to_string(),
+            "2ms"
+        );
+        assert_eq!(
+            SettingValue::parse("0.2", "real", None),
+            SettingValue::Real(0.2)
+        );
+        assert_eq!(
+            SettingValue::parse("of", "bool", None),
+            SettingValue::Bool(false)
+        );
+        assert_eq!(
+            SettingValue::parse("o", "bool", None),
+            SettingValue::String("o".to_owned())
+        );
+        assert_eq!(
+            SettingValue::parse("y", "bool", None),
+            SettingValue::Bool(true)
+        );
+        assert_eq!(
+            SettingValue::parse("replica", "enum", None),
+            SettingValue::Enum("replica".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let staging = ConfigSnapshot::new(
+            vec![
+                setting(
+                    "shared_buffers",
+                    "16384",
+                    "integer",
+                    Some("8kB"),
+                    "postmaster",
+                ),
+                setting("work_mem", "4096", "integer", Some("kB"), "user"),
+                setting(
+                    "data_directory",
+                    "/srv/staging",
+                    "string",
+                    None,
+                    "postmaster",
+                ),
+            ],
+            Vec::new(),
+            [(SettingScope::Role("reporting".to_owned()), "work_mem=64MB")],
+        )
+        .without(["data_directory"]);
+        let production = ConfigSnapshot::new(
+            vec![
+                setting(
+                    "shared_buffers",
+                    "131072",
+                    "integer",
+                    Some("8kB"),
+                    "postmaster",
+                ),
+                setting("work_mem", "4MB", "integer", Some("kB"), "user"),
+                setting(
+                    "data_directory",
+                    "/srv/production",
+                    "string",
+                    None,
+                    "postmaster",
+                ),
+            ],
+            Vec::new(),
+            Vec::<(SettingScope, &str)>::new(),
+        )
+        .without(["data_directory"]);
+
+        let differences = staging.diff(&production);
+        assert_eq!(differences.len(), 2);
+        assert_eq!(
+            differences[0].to_string(),
+            "shared_buffers (server): 128MB -> 1GB (requires restart)"
+        );
+        assert_eq!(
+            differences[1].to_string(),
+            "work_mem (role reporting): 64MB -> (unset)"
+        );
+        assert_eq!(staging.overrides_of("work_mem").count(), 1);
+        assert_eq!(staging.restart_only().count(), 1);
+    }
+}
@@ -0,0 +1,571 @@
+//! Submodule extracting simple value bounds from check constraint expressions.
+//!
+//! Check constraints are arbitrary SQL expressions, but most of them follow a
+//! handful of shapes: comparisons against a literal, `BETWEEN`, `IN` lists
+//! (which `PostgreSQL` renders as `= ANY (ARRAY[...])`), limits on
+//! `length(column)` and `IS NOT NULL`. [`ColumnBounds`] collects the
+//! restrictions that conjunctions of such shapes place on a single column.
+//! Anything it does not understand is ignored, so the bounds are always a
+//! relaxation of the actual constraint.
+
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, RoundingMode, ToPrimitive};
+use sqlparser::ast::{
+    BinaryOperator, Expr, FunctionArg, FunctionArgExpr, FunctionArguments, UnaryOperator, Value,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A numeric bound on the values of a column.
+pub struct NumericBound {
+    /// The bounding value.
+    pub value: BigDecimal,
+    /// Whether the bounding value itself is admitted.
+    pub inclusive: bool,
+}
+
+impl NumericBound {
+    /// Returns the smallest integer admitted by this bound when used as a
+    /// lower bound.
+    #[must_use]
+    pub fn smallest_integer(&self) -> Option<i64> {
+        let ceiling = self.value.with_scale_round(0, RoundingMode::Ceiling);
+        let ceiling = ceiling.to_i64()?;
+        if !self.inclusive && self.value.is_integer() {
+            ceiling.checked_add(1)
+        } else {
+            Some(ceiling)
+        }
+    }
+
+    /// Returns the largest integer admitted by this bound when used as an
+    /// upper bound.
+    #[must_use]
+    pub fn largest_integer(&self) -> Option<i64> {
+        let floor = self.value.with_scale_round(0, RoundingMode::Floor);
+        let floor = floor.to_i64()?;
+        if !self.inclusive && self.value.is_integer() {
+            floor.checked_sub(1)
+        } else {
+            Some(floor)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The restrictions that check constraints place on the values of a column.
+pub struct ColumnBounds {
+    /// The lower bound of the values, if any.
+    lower: Option<NumericBound>,
+    /// The upper bound of the values, if any.
+    upper: Option<NumericBound>,
+    /// The only values admitted, if the column is restricted to a list.
+    allowed_values: Option<Vec<String>>,
+    /// The minimum length of the values, if any.
+    min_length: Option<usize>,
+    /// The maximum length of the values, if any.
+    max_length: Option<usize>,
+    /// Whether a check constraint requires the column to be not null.
+    not_null: bool,
+}
+
+impl ColumnBounds {
+    /// Returns the bounds that the provided expression places on the column.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - The check constraint expression.
+    /// * `column_name` - The name of the column to extract the bounds of.
+    #[must_use]
+    pub fn from_expression(expression: &Expr, column_name: &str) -> Self {
+        let mut bounds = Self::default();
+        bounds.restrict(expression, column_name);
+        bounds
+    }
+
+    /// Tightens the bounds with the restrictions that the provided expression
+    /// places on the column.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - The check constraint expression.
+    /// * `column_name` - The name of the column to extract the bounds of.
+    pub fn restrict(&mut self, expression: &Expr, column_name: &str) {
+        match strip(expression) {
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::And,
+                right,
+            } => {
+                self.restrict(left, column_name);
+                self.restrict(right, column_name);
+            }
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Or,
+                right,
+            } => {
+                let left = Self::from_expression(left, column_name);
+                let right = Self::from_expression(right, column_name);
+                if let (Some(left), Some(right)) =
+                    (left.only_allowed_values(), right.only_allowed_values())
+                {
+                    let mut values = left.to_vec();
+                    for value in right {
+                        if !values.contains(value) {
+                            values.push(value.clone());
+                        }
+                    }
+                    self.allow(values);
+                }
+            }
+            Expr::BinaryOp { left, op, right } => {
+                self.restrict_comparison(left, op, right, column_name);
+            }
+            Expr::Between {
+                expr,
+                negated: false,
+                low,
+                high,
+            } if refers_to(expr, column_name) => {
+                if let Some(value) = numeric_literal(low) {
+                    self.raise_lower(NumericBound {
+                        value,
+                        inclusive: true,
+                    });
+                }
+                if let Some(value) = numeric_literal(high) {
+                    self.reduce_upper(NumericBound {
+                        value,
+                        inclusive: true,
+                    });
+                }
+            }
+            Expr::InList {
+                expr,
+                list,
+                negated: false,
+            } if refers_to(expr, column_name) => {
+                if let Some(values) = list.iter().map(literal_text).collect() {
+                    self.allow(values);
+                }
+            }
+            Expr::AnyOp {
+                left,
+                compare_op: BinaryOperator::Eq,
+                right,
+                ..
+            } if refers_to(left, column_name) => {
+                if let Expr::Array(array) = strip(right)
+                    && let Some(values) = array.elem.iter().map(literal_text).collect()
+                {
+                    self.allow(values);
+                }
+            }
+            Expr::IsNotNull(expr) if refers_to(expr, column_name) => {
+                self.not_null = true;
+            }
+            _ => {}
+        }
+    }
+
+    /// Tightens the bounds with a comparison between the column, or its
+    /// length, and a literal.
+    fn restrict_comparison(
+        &mut self,
+        left: &Expr,
+        op: &BinaryOperator,
+        right: &Expr,
+        column_name: &str,
+    ) {
+        let (target, op, literal) = if let Some(target) = Target::of(left, column_name) {
+            (target, op.clone(), right)
+        } else if let Some(target) = Target::of(right, column_name)
+            && let Some(op) = flip(op)
+        {
+            (target, op, left)
+        } else {
+            return;
+        };
+
+        match target {
+            Target::Value => {
+                if op == BinaryOperator::Eq {
+                    if let Some(value) = literal_text(literal) {
+                        self.allow(vec![value]);
+                    }
+                    return;
+                }
+                let Some(value) = numeric_literal(literal) else {
+                    return;
+                };
+                match op {
+                    BinaryOperator::Gt | BinaryOperator::GtEq => {
+                        self.raise_lower(NumericBound {
+                            value,
+                            inclusive: op == BinaryOperator::GtEq,
+                        });
+                    }
+                    BinaryOperator::Lt | BinaryOperator::LtEq => {
+                        self.reduce_upper(NumericBound {
+                            value,
+                            inclusive: op == BinaryOperator::LtEq,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            Target::Length => {
+                let Some(length) =
+                    literal_text(literal).and_then(|text| text.parse::<usize>().ok())
+                else {
+                    return;
+                };
+                match op {
+                    BinaryOperator::Gt => self.raise_min_length(length.saturating_add(1)),
+                    BinaryOperator::GtEq => self.raise_min_length(length),
+                    BinaryOperator::Lt => self.reduce_max_length(length.saturating_sub(1)),
+                    BinaryOperator::LtEq => self.reduce_max_length(length),
+                    BinaryOperator::Eq => {
+                        self.raise_min_length(length);
+                        self.reduce_max_length(length);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Replaces the lower bound if the provided one is tighter.
+    fn raise_lower(&mut self, bound: NumericBound) {
+        let tighter = self.lower.as_ref().is_none_or(|current| {
+            bound.value > current.value || (bound.value == current.value && !bound.inclusive)
+        });
+        if tighter {
+            self.lower = Some(bound);
+        }
+    }
+
+    /// Replaces the upper bound if the provided one is tighter.
+    fn reduce_upper(&mut self, bound: NumericBound) {
+        let tighter = self.upper.as_ref().is_none_or(|current| {
+            bound.value < current.value || (bound.value == current.value && !bound.inclusive)
+        });
+        if tighter {
+            self.upper = Some(bound);
+        }
+    }
+
+    /// Restricts the admitted values to those in the provided list.
+    fn allow(&mut self, values: Vec<String>) {
+        self.allowed_values = Some(match self.allowed_values.take() {
+            None => values,
+            Some(current) => current
+                .into_iter()
+                .filter(|value| values.contains(value))
+                .collect(),
+        });
+    }
+
+    /// Raises the minimum length to the provided one, if larger.
+    fn raise_min_length(&mut self, length: usize) {
+        self.min_length = Some(
+            self.min_length
+                .map_or(length, |current| current.max(length)),
+        );
+    }
+
+    /// Reduces the maximum length to the provided one, if smaller.
+    fn reduce_max_length(&mut self, length: usize) {
+        self.max_length = Some(
+            self.max_length
+                .map_or(length, |current| current.min(length)),
+        );
+    }
+
+    /// Returns the allowed values if they are the only restriction.
+    fn only_allowed_values(&self) -> Option<&[String]> {
+        if self.lower.is_none()
+            && self.upper.is_none()
+            && self.min_length.is_none()
+            && self.max_length.is_none()
+        {
+            self.allowed_values.as_deref()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the lower bound of the values, if any.
+    #[must_use]
+    pub fn lower(&self) -> Option<&NumericBound> {
+        self.lower.as_ref()
+    }
+
+    /// Returns the upper bound of the values, if any.
+    #[must_use]
+    pub fn upper(&self) -> Option<&NumericBound> {
+        self.upper.as_ref()
+    }
+
+    /// Returns the only values admitted, if the column is restricted to a
+    /// list of literals.
+    #[must_use]
+    pub fn allowed_values(&self) -> Option<&[String]> {
+        self.allowed_values.as_deref()
+    }
+
+    /// Returns the minimum length of the values, if any.
+    #[must_use]
+    pub fn min_length(&self) -> Option<usize> {
+        self.min_length
+    }
+
+    /// Returns the maximum length of the values, if any.
+    #[must_use]
+    pub fn max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+
+    /// Returns whether a check constraint requires the column to be not null.
+    #[must_use]
+    pub fn not_null(&self) -> bool {
+        self.not_null
+    }
+
+    /// Returns whether no restriction was extracted.
+    #[must_use]
+    pub fn is_unconstrained(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Returns whether the provided textual value satisfies the bounds.
+    ///
+    /// Numeric bounds are only checked when the value parses as a number.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The textual representation of the value.
+    #[must_use]
+    pub fn admits(&self, value: &str) -> bool {
+        if let Some(allowed_values) = &self.allowed_values
+            && !allowed_values.iter().any(|allowed| allowed == value)
+        {
+            return false;
+        }
+        let length = value.chars().count();
+        if self.min_length.is_some_and(|min| length < min)
+            || self.max_length.is_some_and(|max| length > max)
+        {
+            return false;
+        }
+        let Ok(number) = BigDecimal::from_str(value) else {
+            return true;
+        };
+        let above_lower = self
+            .lower
+            .as_ref()
+            .is_none_or(|bound| number > bound.value || (bound.inclusive && number == bound.value));
+        let below_upper = self
+            .upper
+            .as_ref()
+            .is_none_or(|bound| number < bound.value || (bound.inclusive && number == bound.value));
+        above_lower && below_upper
+    }
+}
+
+/// What a comparison in a check constraint is about.
+enum Target {
+    /// The value of the column itself.
+    Value,
+    /// The length of the value of the column.
+    Length,
+}
+
+impl Target {
+    /// Returns what the provided expression refers to, if it is the column or
+    /// its length.
+    fn of(expression: &Expr, column_name: &str) -> Option<Self> {
+        let expression = strip(expression);
+        if refers_to(expression, column_name) {
+            return Some(Self::Value);
+        }
+        if let Expr::Function(function) = expression
+            && matches!(
+                function_name(&function.name).as_str(),
+                "length" | "char_length" | "character_length"
+            )
+            && let FunctionArguments::List(list) = &function.args
+            && let [FunctionArg::Unnamed(FunctionArgExpr::Expr(argument))] = list.args.as_slice()
+            && refers_to(argument, column_name)
+        {
+            return Some(Self::Length);
+        }
+        None
+    }
+}
+
+/// Returns the unqualified, lowercase name of a function.
+fn function_name(name: &sqlparser::ast::ObjectName) -> String {
+    let name = name.to_string();
+    name.rsplit('.')
+        .next()
+        .unwrap_or_default()
+        .trim_matches('"')
+        .to_lowercase()
+}
+
+/// Removes the parentheses and casts wrapping an expression.
+fn strip(expression: &Expr) -> &Expr {
+    match expression {
+        Expr::Nested(inner) | Expr::Cast { expr: inner, .. } => strip(inner),
+        _ => expression,
+    }
+}
+
+/// Returns whether the expression is a reference to the column.
+fn refers_to(expression: &Expr, column_name: &str) -> bool {
+    match strip(expression) {
+        Expr::Identifier(ident) => ident.value == column_name,
+        Expr::CompoundIdentifier(idents) => idents
+            .last()
+            .is_some_and(|ident| ident.value == column_name),
+        _ => false,
+    }
+}
+
+/// Returns the operator obtained by swapping the operands of a comparison.
+fn flip(op: &BinaryOperator) -> Option<BinaryOperator> {
+    match op {
+        BinaryOperator::Gt => Some(BinaryOperator::Lt),
+        BinaryOperator::GtEq => Some(BinaryOperator::LtEq),
+        BinaryOperator::Lt => Some(BinaryOperator::Gt),
+        BinaryOperator::LtEq => Some(BinaryOperator::GtEq),
+        BinaryOperator::Eq => Some(BinaryOperator::Eq),
+        _ => None,
+    }
+}
+
+/// Returns the textual value of a literal expression.
+fn literal_text(expression: &Expr) -> Option<String> {
+    match strip(expression) {
+        Expr::Value(value) => match &value.value {
+            Value::Number(number, _) => Some(number.clone()),
+            Value::SingleQuotedString(text)
+            | Value::EscapedStringLiteral(text)
+            | Value::DoubleQuotedString(text) => Some(text.clone()),
+            Value::Boolean(boolean) => Some(boolean.to_string()),
+            _ => None,
+        },
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => {
+            let text = literal_text(expr)?;
+            BigDecimal::from_str(&text).ok()?;
+            Some(
+                text.strip_prefix('-')
+                    .map_or_else(|| format!("-{text}"), str::to_owned),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Returns the numeric value of a literal expression.
+fn numeric_literal(expression: &Expr) -> Option<BigDecimal> {
+    BigDecimal::from_str(&literal_text(expression)?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::{dialect::PostgreSqlDialect, parser::Parser};
+
+    use super::*;
+
+    fn bounds(expression: &str, column_name: &str) -> ColumnBounds {
+        let expression = Parser::new(&PostgreSqlDialect {})
+            .try_with_sql(expression)
+            .unwrap()
+            .parse_expr()
+            .unwrap();
+        ColumnBounds::from_expression(&expression, column_name)
+    }
+
+    fn bound(value: &str, inclusive: bool) -> NumericBound {
+        NumericBound {
+            value: BigDecimal::from_str(value).unwrap(),
+            inclusive,
+        }
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let price = bounds("((price > (0)::numeric) AND (price <= 100.5))", "price");
+        assert_eq!(price.lower(), Some(&bound("0", false)));
+        assert_eq!(price.upper(), Some(&bound("100.5", true)));
+        assert_eq!(price.lower().unwrap().smallest_integer(), Some(1));
+        assert_eq!(price.upper().unwrap().largest_integer(), Some(100));
+        assert!(price.admits("50"));
+        assert!(!price.admits("0"));
+
+        let flipped = bounds("(10 >= quantity)", "quantity");
+        assert_eq!(flipped.upper(), Some(&bound("10", true)));
+        assert!(bounds("(10 >= quantity)", "price").is_unconstrained());
+    }
+
+    #[test]
+    fn test_between_and_lists() {
+        let age = bounds("(age BETWEEN 18 AND 130)", "age");
+        assert_eq!(age.lower(), Some(&bound("18", true)));
+        assert_eq!(age.upper(), Some(&bound("130", true)));
+
+        let status = bounds(
+            "((status)::text = ANY ((ARRAY['draft'::character varying, 'published'::character varying])::text[]))",
+            "status",
+        );
+        assert_eq!(
+            status.allowed_values(),
+            Some(["draft".to_string(), "published".to_string()].as_slice())
+        );
+        assert!(status.admits("draft"));
+        assert!(!status.admits("deleted"));
+
+        let either = bounds("((kind = 'a') OR (kind = 'b'))", "kind");
+        assert_eq!(
+            either.allowed_values(),
+            Some(["a".to_string(), "b".to_string()].as_slice())
+        );
+
+        let listed = bounds("(level IN (1, 2, 3))", "level");
+        assert_eq!(listed.allowed_values().map(<[String]>::len), Some(3));
+    }
+
+    #[test]
+    fn test_lengths_and_not_null() {
+        let name = bounds(
+            "((length((name)::text) >= 3) AND (char_length(name) < 65) AND (name IS NOT NULL))",
+            "name",
+        );
+        assert_eq!(name.min_length(), Some(3));
+        assert_eq!(name.max_length(), Some(64));
+        assert!(name.not_null());
+        assert!(!name.admits("ab"));
+        assert!(name.admits("abc"));
+    }
+
+    #[test]
+    fn test_restrict_tightens() {
+        let mut bounds = bounds("(score >= 0)", "score");
+        let expression = Parser::new(&PostgreSqlDialect {})
+            .try_with_sql("(score > 0 AND score < -(-10) AND score < 20)")
+            .unwrap()
+            .parse_expr()
+            .unwrap();
+        bounds.restrict(&expression, "score");
+        assert_eq!(bounds.lower(), Some(&bound("0", false)));
+        assert_eq!(bounds.upper(), Some(&bound("10", false)));
+    }
+}
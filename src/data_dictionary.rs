@@ -0,0 +1,1466 @@
+//! Submodule generating a browsable data dictionary of a
+//! [`PgDieselDatabase`], as Markdown files or a static HTML bundle.
+//!
+//! A [`DataDictionary`] has an index page listing the schemas, a page per
+//! schema listing its tables, and a page per table describing:
+//!
+//! - its kind, row-level security and comment from `pg_description`;
+//! - its columns, with their types, nullability, defaults and comments;
+//! - its primary key, unique, check and foreign key constraints, as SQL;
+//! - the tables it references and the tables referencing it;
+//! - its primary key and unique indexes, or all its indexes with their names
+//!   when provided with [`DataDictionary::indexes`];
+//! - its triggers with the functions they execute, its rewrite rules, its
+//!   row-level security policies and the privileges granted on it.
+//!
+//! The index page also lists the event triggers of the database, and the
+//! schema pages the privileges granted on the sequences, functions, types and
+//! schemas, when the dictionary is created from a
+//! [`PgDieselDatabaseWithEventTriggers`].
+//!
+//! Pages link to each other with relative paths, so the output can be
+//! browsed from any directory, and everything is sorted by name so that
+//! regenerating the dictionary of an unchanged database yields the same
+//! files, and the diff of a changed one is reviewable.
+
+mod render;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use render::{Block, Inline};
+use sql_traits::traits::{DatabaseLike, IndexLike, PolicyLike};
+use sqlparser::ast::Expr;
+
+use crate::{
+    PgDieselDatabase,
+    acl::{AclObjectKind, ObjectGrant},
+    database::{ObjectIndex, PgDieselDatabaseWithEventTriggers},
+    erd::type_name,
+    index_advisor::IndexUsage,
+    model_metadata::{EventTriggerMetadata, TableMetadata},
+    models::Table,
+    quoting::{quote_identifier, quote_identifiers},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+/// Errors that can occur when parsing a [`DictionaryFormat`].
+pub enum DataDictionaryError {
+    #[error("Unknown data dictionary format `{0}`, expected `markdown` or `html`")]
+    /// The format is not one of `markdown` or `html`.
+    UnknownFormat(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The formats a [`DataDictionary`] is written in.
+pub enum DictionaryFormat {
+    /// Markdown files, as rendered by code forges and static site
+    /// generators.
+    Markdown,
+    /// Standalone HTML pages.
+    Html,
+}
+
+impl DictionaryFormat {
+    /// Returns the extension of the files of the format.
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+}
+
+impl Display for DictionaryFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Markdown => "markdown",
+            Self::Html => "html",
+        })
+    }
+}
+
+impl FromStr for DictionaryFormat {
+    type Err = DataDictionaryError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.trim().to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            _ => Err(DataDictionaryError::UnknownFormat(format.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A page of a [`DataDictionary`].
+pub struct DictionaryPage {
+    /// The path of the page, relative to the root of the dictionary.
+    path: String,
+    /// The content of the page.
+    content: String,
+}
+
+impl DictionaryPage {
+    /// Returns the path of the page relative to the root of the dictionary,
+    /// with `/` as separator, as in `public/tables/users.md`.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the content of the page.
+    #[must_use]
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A column of a [`TableEntry`].
+struct ColumnEntry {
+    /// The name of the column.
+    name: String,
+    /// The type of the column, as in `varchar(255)` or `int4[]`.
+    type_name: String,
+    /// Whether the column is nullable.
+    nullable: bool,
+    /// The default expression of the column.
+    default: Option<String>,
+    /// The comment on the column.
+    description: Option<String>,
+    /// Whether the column is part of the primary key.
+    primary_key: bool,
+    /// Whether the column is part of a foreign key.
+    foreign_key: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// A constraint of a [`TableEntry`].
+struct ConstraintEntry {
+    /// The kind of the constraint, as in `PRIMARY KEY`.
+    kind: &'static str,
+    /// The definition of the constraint, in SQL.
+    definition: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A foreign key between two tables of a [`DataDictionary`].
+struct ForeignKeyEntry {
+    /// The name of the constraint.
+    name: String,
+    /// The schema and name of the host table.
+    host: (String, String),
+    /// The host columns.
+    host_columns: Vec<String>,
+    /// The schema and name of the referenced table.
+    referenced: (String, String),
+    /// The referenced columns.
+    referenced_columns: Vec<String>,
+    /// The match option, as in `NONE` or `FULL`.
+    match_option: String,
+    /// The action on update of the referenced row, as in `NO ACTION`.
+    update_rule: String,
+    /// The action on deletion of the referenced row, as in `CASCADE`.
+    delete_rule: String,
+}
+
+impl ForeignKeyEntry {
+    /// Returns the definition of the constraint, in SQL.
+    fn definition(&self) -> String {
+        let mut definition = format!(
+            "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {}.{} ({})",
+            quote_identifier(&self.name),
+            quote_identifiers(&self.host_columns),
+            quote_identifier(&self.referenced.0),
+            quote_identifier(&self.referenced.1),
+            quote_identifiers(&self.referenced_columns),
+        );
+        if matches!(self.match_option.as_str(), "FULL" | "PARTIAL") {
+            definition.push_str(" MATCH ");
+            definition.push_str(&self.match_option);
+        }
+        for (event, rule) in [("UPDATE", &self.update_rule), ("DELETE", &self.delete_rule)] {
+            if rule != "NO ACTION" {
+                let _ = write!(definition, " ON {event} {rule}");
+            }
+        }
+        definition
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A trigger of a [`TableEntry`].
+struct TriggerEntry {
+    /// The name of the trigger.
+    name: String,
+    /// When the trigger fires, as in `BEFORE`.
+    timing: String,
+    /// The events firing the trigger, as in `INSERT`.
+    events: BTreeSet<String>,
+    /// Whether the trigger fires once per `ROW` or `STATEMENT`.
+    orientation: String,
+    /// The `WHEN` condition of the trigger.
+    condition: Option<String>,
+    /// The name of the function executed by the trigger.
+    function: Option<String>,
+    /// The statement executed by the trigger, as in
+    /// `EXECUTE FUNCTION audit()`.
+    statement: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A rewrite rule of a [`TableEntry`].
+struct RuleEntry {
+    /// The name of the rule.
+    name: String,
+    /// The event the rule applies to, as in `DELETE`.
+    event: String,
+    /// Whether the rule replaces the original statement.
+    instead: bool,
+    /// When the rule fires, as in `origin`.
+    firing_mode: String,
+    /// The definition of the rule, in SQL.
+    definition: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An event trigger of a [`DataDictionary`].
+struct EventTriggerEntry {
+    /// The name of the event trigger.
+    name: String,
+    /// The event firing the trigger, as in `ddl_command_end`.
+    event: String,
+    /// The command tags the trigger is restricted to, empty for all.
+    tags: Vec<String>,
+    /// The qualified name of the function executed by the trigger.
+    function: String,
+    /// When the trigger fires, as in `origin`.
+    firing_mode: String,
+}
+
+impl From<&EventTriggerMetadata> for EventTriggerEntry {
+    fn from(event_trigger: &EventTriggerMetadata) -> Self {
+        Self {
+            name: event_trigger.name().to_owned(),
+            event: event_trigger.event().to_owned(),
+            tags: event_trigger.tags().to_vec(),
+            function: event_trigger.function_name().to_string(),
+            firing_mode: event_trigger.firing_mode().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// A privilege granted on a sequence, function, type or schema, listed on
+/// the page of its schema.
+struct ObjectGrantEntry {
+    /// The schema of the object, or the schema itself.
+    schema: String,
+    /// The kind of the object, as in `SEQUENCE`.
+    kind: String,
+    /// The name of the object, with the argument types of functions.
+    object: String,
+    /// The role the privilege is granted to.
+    grantee: String,
+    /// The privilege, as in `USAGE`.
+    privilege: String,
+    /// Whether the grantee may grant the privilege to others.
+    grantable: bool,
+}
+
+impl From<&ObjectGrant> for ObjectGrantEntry {
+    fn from(grant: &ObjectGrant) -> Self {
+        Self {
+            schema: grant.object().schema().to_owned(),
+            kind: grant.kind().to_string(),
+            object: grant.object().name().to_owned(),
+            grantee: grant.grantee().to_string(),
+            privilege: grant.privilege().to_string(),
+            grantable: grant.is_grantable(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A row-level security policy of a [`TableEntry`].
+struct PolicyEntry {
+    /// The name of the policy.
+    name: String,
+    /// The command the policy applies to, as in `SELECT`.
+    command: &'static str,
+    /// Whether the policy is permissive rather than restrictive.
+    permissive: bool,
+    /// The roles the policy applies to.
+    roles: Vec<String>,
+    /// The `USING` expression of the policy.
+    using: Option<String>,
+    /// The `WITH CHECK` expression of the policy.
+    check: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// A privilege granted on a [`TableEntry`] or on one of its columns.
+struct GrantEntry {
+    /// The role the privilege is granted to.
+    grantee: String,
+    /// The privilege, as in `SELECT`.
+    privilege: String,
+    /// The column the privilege is granted on, if not the whole table.
+    column: Option<String>,
+    /// Whether the grantee may grant the privilege to others.
+    grantable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// An index of a [`TableEntry`].
+struct IndexEntry {
+    /// The name of the index, only known for the indexes provided with
+    /// [`DataDictionary::indexes`].
+    name: Option<String>,
+    /// Whether the index is the primary key of the table.
+    primary_key: bool,
+    /// The definition of the index, in SQL.
+    definition: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A table of a [`DataDictionary`].
+struct TableEntry {
+    /// The schema of the table.
+    schema: String,
+    /// The name of the table.
+    name: String,
+    /// The kind of the table, as in `BASE TABLE` or `VIEW`.
+    kind: String,
+    /// The comment on the table.
+    description: Option<String>,
+    /// The foreign server of a foreign table.
+    server: Option<String>,
+    /// Whether row-level security is enabled.
+    row_security: bool,
+    /// Whether row-level security also applies to the owner of the table.
+    forced_row_security: bool,
+    /// The columns of the table, in order.
+    columns: Vec<ColumnEntry>,
+    /// The constraints of the table.
+    constraints: Vec<ConstraintEntry>,
+    /// The foreign keys of the table.
+    foreign_keys: Vec<ForeignKeyEntry>,
+    /// The indexes of the table.
+    indexes: Vec<IndexEntry>,
+    /// The triggers of the table.
+    triggers: Vec<TriggerEntry>,
+    /// The rewrite rules of the table, sorted by name.
+    rules: Vec<RuleEntry>,
+    /// The row-level security policies of the table.
+    policies: Vec<PolicyEntry>,
+    /// The privileges granted on the table and its columns.
+    grants: Vec<GrantEntry>,
+}
+
+impl TableEntry {
+    /// Creates a new `TableEntry` for the table of the database.
+    fn new(database: &PgDieselDatabase, index: &ObjectIndex<'_>, table: &Table) -> Self {
+        let metadata = database
+            .table_metadata(table)
+            .expect("Table must exist in database");
+        let foreign_keys = Self::foreign_keys(database, table, metadata);
+        let mut constraints = Self::constraints(metadata);
+        constraints.extend(foreign_keys.iter().map(|foreign_key| ConstraintEntry {
+            kind: "FOREIGN KEY",
+            definition: foreign_key.definition(),
+        }));
+        Self {
+            schema: table.table_schema.clone(),
+            name: table.table_name.clone(),
+            kind: table.table_type.clone(),
+            description: metadata
+                .description()
+                .map(|description| description.description.clone()),
+            server: metadata
+                .foreign_table()
+                .map(|foreign_table| foreign_table.server().srvname.clone()),
+            row_security: metadata.row_security(),
+            forced_row_security: metadata.forced_row_security(),
+            columns: Self::columns(database, metadata, &foreign_keys),
+            constraints,
+            foreign_keys,
+            indexes: Self::indexes(database, table, metadata),
+            triggers: Self::triggers(index, metadata),
+            rules: metadata
+                .rules()
+                .map(|rule| RuleEntry {
+                    name: rule.name().to_owned(),
+                    event: rule.event().to_string(),
+                    instead: rule.is_instead(),
+                    firing_mode: rule.firing_mode().to_string(),
+                    definition: rule.definition().to_owned(),
+                })
+                .collect(),
+            policies: Self::policies(database, metadata),
+            grants: Self::grants(database, table),
+        }
+    }
+
+    /// Returns the columns of the table, in order.
+    fn columns(
+        database: &PgDieselDatabase,
+        metadata: &TableMetadata,
+        foreign_keys: &[ForeignKeyEntry],
+    ) -> Vec<ColumnEntry> {
+        let primary_key: BTreeSet<&str> = metadata
+            .primary_key_columns()
+            .map(|column| column.column_name.as_str())
+            .collect();
+        let mut columns: Vec<_> = metadata.columns().collect();
+        columns.sort_by_key(|column| column.ordinal_position);
+        columns
+            .into_iter()
+            .map(|column| {
+                let column_metadata = database
+                    .column_metadata(column)
+                    .expect("Column must exist in database");
+                let pg_type = column_metadata.pg_type();
+                let mut type_name = type_name(pg_type);
+                if let Some(length) = column.character_maximum_length
+                    && pg_type.typcategory != "A"
+                {
+                    type_name = format!("{type_name}({length})");
+                }
+                ColumnEntry {
+                    name: column.column_name.clone(),
+                    type_name,
+                    nullable: column.__is_nullable == "YES",
+                    default: column.column_default.clone(),
+                    description: column_metadata
+                        .description()
+                        .map(|description| description.description.clone()),
+                    primary_key: primary_key.contains(column.column_name.as_str()),
+                    foreign_key: foreign_keys
+                        .iter()
+                        .any(|foreign_key| foreign_key.host_columns.contains(&column.column_name)),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the primary key, unique and check constraints of the table.
+    fn constraints(metadata: &TableMetadata) -> Vec<ConstraintEntry> {
+        let column_name = |attnum: &i16| {
+            metadata
+                .columns()
+                .find(|column| i32::from(*attnum) == column.ordinal_position)
+                .map(|column| column.column_name.clone())
+        };
+        let primary_key: Vec<String> = metadata
+            .primary_key_columns()
+            .map(|column| column.column_name.clone())
+            .collect();
+        let mut constraints: Vec<ConstraintEntry> = metadata
+            .unique_indices()
+            .filter(|index| !index.indisprimary)
+            .filter_map(|index| index.indkey.iter().map(column_name).collect())
+            .map(|columns: Vec<String>| ConstraintEntry {
+                kind: "UNIQUE",
+                definition: format!("UNIQUE ({})", quote_identifiers(&columns)),
+            })
+            .chain(
+                metadata
+                    .check_constraints()
+                    .filter(|check| {
+                        !(check.constraint_name.ends_with("_not_null")
+                            && check.check_clause.ends_with(" IS NOT NULL"))
+                    })
+                    .map(|check| {
+                        let clause = check.check_clause.trim();
+                        ConstraintEntry {
+                            kind: "CHECK",
+                            definition: if clause.starts_with('(') {
+                                format!(
+                                    "CONSTRAINT {} CHECK {clause}",
+                                    quote_identifier(&check.constraint_name)
+                                )
+                            } else {
+                                format!(
+                                    "CONSTRAINT {} CHECK ({clause})",
+                                    quote_identifier(&check.constraint_name)
+                                )
+                            },
+                        }
+                    }),
+            )
+            .collect();
+        constraints.sort();
+        if !primary_key.is_empty() {
+            constraints.insert(
+                0,
+                ConstraintEntry {
+                    kind: "PRIMARY KEY",
+                    definition: format!("PRIMARY KEY ({})", quote_identifiers(&primary_key)),
+                },
+            );
+        }
+        constraints
+    }
+
+    /// Returns the foreign keys of the table, sorted by name.
+    fn foreign_keys(
+        database: &PgDieselDatabase,
+        table: &Table,
+        metadata: &TableMetadata,
+    ) -> Vec<ForeignKeyEntry> {
+        let mut foreign_keys: Vec<ForeignKeyEntry> = metadata
+            .foreign_keys()
+            .map(|foreign_key| {
+                let foreign_key_metadata = database
+                    .foreign_key_metadata(foreign_key)
+                    .expect("Foreign key must exist in database");
+                let referenced = foreign_key_metadata.referenced_table();
+                let rule = foreign_key_metadata.referential_constraint();
+                ForeignKeyEntry {
+                    name: foreign_key.constraint_name.clone(),
+                    host: (table.table_schema.clone(), table.table_name.clone()),
+                    host_columns: foreign_key_metadata
+                        .host_columns()
+                        .iter()
+                        .map(|column| column.column_name.clone())
+                        .collect(),
+                    referenced: (
+                        referenced.table_schema.clone(),
+                        referenced.table_name.clone(),
+                    ),
+                    referenced_columns: foreign_key_metadata
+                        .referenced_columns()
+                        .iter()
+                        .map(|column| column.column_name.clone())
+                        .collect(),
+                    match_option: rule.match_option.clone(),
+                    update_rule: rule.update_rule.clone(),
+                    delete_rule: rule.delete_rule.clone(),
+                }
+            })
+            .collect();
+        foreign_keys.sort_by(|left, right| left.name.cmp(&right.name));
+        foreign_keys
+    }
+
+    /// Returns the triggers of the table, sorted by name.
+    fn triggers(index: &ObjectIndex<'_>, metadata: &TableMetadata) -> Vec<TriggerEntry> {
+        // `information_schema.triggers` has a row per event of each trigger.
+        let mut triggers: BTreeMap<String, TriggerEntry> = BTreeMap::new();
+        for (trigger, function_oid) in metadata.triggers() {
+            let function_oid = *function_oid;
+            let name = trigger.trigger_name.clone().unwrap_or_default();
+            let entry = triggers
+                .entry(name.clone())
+                .or_insert_with(|| TriggerEntry {
+                    name,
+                    timing: trigger.action_timing.clone().unwrap_or_default(),
+                    events: BTreeSet::new(),
+                    orientation: trigger.action_orientation.clone().unwrap_or_default(),
+                    condition: trigger.action_condition.clone(),
+                    function: function_oid
+                        .and_then(|oid| index.function(oid))
+                        .map(|function| function.proname.clone()),
+                    statement: trigger.action_statement.clone().unwrap_or_default(),
+                });
+            if let Some(event) = &trigger.event_manipulation {
+                entry.events.insert(event.clone());
+            }
+        }
+        triggers.into_values().collect()
+    }
+
+    /// Returns the primary key and unique indexes of the table, primary key
+    /// first, as held by the [`PgDieselDatabase`].
+    fn indexes(
+        database: &PgDieselDatabase,
+        table: &Table,
+        metadata: &TableMetadata,
+    ) -> Vec<IndexEntry> {
+        let column_name = |attnum: &i16| {
+            metadata
+                .columns()
+                .find(|column| i32::from(*attnum) == column.ordinal_position)
+                .map(|column| column.column_name.clone())
+        };
+        let mut indexes: Vec<IndexEntry> = metadata
+            .unique_indices()
+            .map(|index| {
+                let key = match index
+                    .indkey
+                    .iter()
+                    .map(column_name)
+                    .collect::<Option<Vec<String>>>()
+                {
+                    Some(columns) => format!("({})", quote_identifiers(&columns)),
+                    None => match IndexLike::expression(index, database) {
+                        expression @ (Expr::Nested(_) | Expr::Tuple(_)) => expression.to_string(),
+                        expression => format!("({expression})"),
+                    },
+                };
+                IndexEntry {
+                    name: None,
+                    primary_key: index.indisprimary,
+                    definition: format!(
+                        "CREATE UNIQUE INDEX ON {}.{} {key}",
+                        quote_identifier(&table.table_schema),
+                        quote_identifier(&table.table_name)
+                    ),
+                }
+            })
+            .collect();
+        indexes.sort_by(|left, right| {
+            right
+                .primary_key
+                .cmp(&left.primary_key)
+                .then_with(|| left.definition.cmp(&right.definition))
+        });
+        indexes
+    }
+
+    /// Returns the row-level security policies of the table, sorted by name.
+    fn policies(database: &PgDieselDatabase, metadata: &TableMetadata) -> Vec<PolicyEntry> {
+        let mut policies: Vec<PolicyEntry> = metadata
+            .policies()
+            .map(|policy| {
+                let policy = policy.as_ref();
+                PolicyEntry {
+                    name: policy.polname.clone(),
+                    command: match policy.polcmd.as_str() {
+                        "r" => "SELECT",
+                        "a" => "INSERT",
+                        "w" => "UPDATE",
+                        "d" => "DELETE",
+                        _ => "ALL",
+                    },
+                    permissive: policy.polpermissive,
+                    roles: PolicyLike::roles(policy, database)
+                        .map(ToString::to_string)
+                        .collect(),
+                    using: PolicyLike::using_expression(policy, database).map(ToString::to_string),
+                    check: PolicyLike::check_expression(policy, database).map(ToString::to_string),
+                }
+            })
+            .collect();
+        policies.sort_by(|left, right| left.name.cmp(&right.name));
+        policies
+    }
+
+    /// Returns the privileges granted on the table and its columns, sorted
+    /// by grantee.
+    fn grants(database: &PgDieselDatabase, table: &Table) -> Vec<GrantEntry> {
+        let on_table = |schema: Option<&str>, name: Option<&str>| {
+            schema == Some(table.table_schema.as_str()) && name == Some(table.table_name.as_str())
+        };
+        let mut grants: Vec<GrantEntry> = database
+            .table_grants()
+            .filter(|grant| on_table(grant.table_schema.as_deref(), grant.table_name.as_deref()))
+            .map(|grant| GrantEntry {
+                grantee: grant.grantee.clone().unwrap_or_default(),
+                privilege: grant.privilege_type.clone().unwrap_or_default(),
+                column: None,
+                grantable: grant.is_grantable.as_deref() == Some("YES"),
+            })
+            .chain(
+                database
+                    .column_grants()
+                    .filter(|grant| {
+                        on_table(grant.table_schema.as_deref(), grant.table_name.as_deref())
+                    })
+                    .map(|grant| GrantEntry {
+                        grantee: grant.grantee.clone().unwrap_or_default(),
+                        privilege: grant.privilege_type.clone().unwrap_or_default(),
+                        column: grant.column_name.clone(),
+                        grantable: grant.is_grantable.as_deref() == Some("YES"),
+                    }),
+            )
+            .collect();
+        grants.sort();
+        grants.dedup();
+        grants
+    }
+}
+
+/// Returns the name encoded as a file name portable across file systems and
+/// URLs, keeping ASCII letters, digits, `_` and `-` and writing every other
+/// byte as `~` followed by its hexadecimal value.
+fn file_name(name: &str) -> String {
+    let mut file_name = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-') {
+            file_name.push(char::from(byte));
+        } else {
+            let _ = write!(file_name, "~{byte:02X}");
+        }
+    }
+    file_name
+}
+
+/// Returns the path of the page of the schema, without extension.
+fn schema_path(schema: &str) -> String {
+    format!("{}/index", file_name(schema))
+}
+
+/// Returns the path of the page of the table, without extension.
+fn table_path(schema: &str, table: &str) -> String {
+    format!("{}/tables/{}", file_name(schema), file_name(table))
+}
+
+/// Returns a link to the page with the provided path from a page nested in
+/// the provided number of directories.
+fn link<S: AsRef<str>>(text: S, depth: usize, path: &str) -> Inline {
+    Inline::Link {
+        text: text.as_ref().to_owned(),
+        target: format!("{}{path}", "../".repeat(depth)),
+    }
+}
+
+/// Returns the first line of the comment, for the listings.
+fn summary(description: Option<&str>) -> Vec<Inline> {
+    description
+        .and_then(|description| description.lines().next())
+        .map(|line| vec![Inline::Text(line.to_owned())])
+        .unwrap_or_default()
+}
+
+/// Returns the text as spans.
+fn text<S: AsRef<str>>(text: S) -> Vec<Inline> {
+    vec![Inline::Text(text.as_ref().to_owned())]
+}
+
+/// Returns the code as spans, empty if there is no code.
+fn code<S: AsRef<str>>(code: Option<S>) -> Vec<Inline> {
+    code.map(|code| vec![Inline::Code(code.as_ref().to_owned())])
+        .unwrap_or_default()
+}
+
+/// Returns a heading of the second level.
+fn section(title: &str) -> Block {
+    Block::Heading(2, text(title))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A data dictionary of the tables of a database, written as Markdown files
+/// or a static HTML bundle.
+pub struct DataDictionary {
+    /// The title of the dictionary.
+    title: String,
+    /// The tables of the dictionary, sorted by schema and name.
+    tables: Vec<TableEntry>,
+    /// The event triggers of the database, sorted by name.
+    event_triggers: Vec<EventTriggerEntry>,
+    /// The privileges granted on the sequences, functions, types and
+    /// schemas, sorted by schema, kind and object.
+    object_grants: Vec<ObjectGrantEntry>,
+}
+
+impl From<&PgDieselDatabaseWithEventTriggers> for DataDictionary {
+    /// Creates a new `DataDictionary` of the tables and views of the
+    /// database, listing its event triggers on the index page and the
+    /// privileges granted on the sequences, functions, types and schemas on
+    /// the pages of their schemas.
+    fn from(database: &PgDieselDatabaseWithEventTriggers) -> Self {
+        let mut object_grants: Vec<ObjectGrantEntry> = database
+            .object_grants()
+            .iter()
+            .filter(|grant| grant.kind() != AclObjectKind::Table)
+            .map(ObjectGrantEntry::from)
+            .collect();
+        object_grants.sort();
+        Self {
+            event_triggers: database
+                .event_triggers()
+                .iter()
+                .map(EventTriggerEntry::from)
+                .collect(),
+            object_grants,
+            ..Self::new(database)
+        }
+    }
+}
+
+impl DataDictionary {
+    #[must_use]
+    /// Creates a new `DataDictionary` of the tables and views of the
+    /// database.
+    pub fn new(database: &PgDieselDatabase) -> Self {
+        let index = ObjectIndex::new(database);
+        let mut tables: Vec<TableEntry> = database
+            .tables()
+            .map(|table| TableEntry::new(database, &index, table))
+            .collect();
+        tables.sort_by(|left, right| (&left.schema, &left.name).cmp(&(&right.schema, &right.name)));
+        Self {
+            title: "Data dictionary".to_owned(),
+            tables,
+            event_triggers: Vec::new(),
+            object_grants: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    /// Sets the title of the dictionary, `Data dictionary` by default.
+    pub fn title<S: AsRef<str>>(mut self, title: S) -> Self {
+        title.as_ref().clone_into(&mut self.title);
+        self
+    }
+
+    #[must_use]
+    /// Replaces the primary key and unique indexes, which the
+    /// [`PgDieselDatabase`] holds without their names, with all the indexes
+    /// of the tables, on the pages of their tables.
+    pub fn indexes(mut self, indexes: &[IndexUsage]) -> Self {
+        for index in indexes {
+            let Some(table) = self
+                .tables
+                .iter_mut()
+                .find(|table| table.schema == index.schema_name && table.name == index.table_name)
+            else {
+                continue;
+            };
+            let mut definition = format!(
+                "CREATE {}INDEX {} ON {}.{} USING {} ({})",
+                if index.is_unique { "UNIQUE " } else { "" },
+                quote_identifier(&index.index_name),
+                quote_identifier(&index.schema_name),
+                quote_identifier(&index.table_name),
+                index.access_method,
+                index.key_columns.join(", ")
+            );
+            if !index.include_columns.is_empty() {
+                let _ = write!(
+                    definition,
+                    " INCLUDE ({})",
+                    index.include_columns.join(", ")
+                );
+            }
+            if let Some(predicate) = &index.predicate {
+                let _ = write!(definition, " WHERE {predicate}");
+            }
+            table.indexes.retain(|entry| entry.name.is_some());
+            table.indexes.push(IndexEntry {
+                name: Some(index.index_name.clone()),
+                primary_key: false,
+                definition,
+            });
+            table.indexes.sort();
+        }
+        self
+    }
+
+    /// Returns the schemas of the dictionary with their tables, sorted by
+    /// name.
+    fn schemas(&self) -> BTreeMap<&str, Vec<&TableEntry>> {
+        let mut schemas: BTreeMap<&str, Vec<&TableEntry>> = BTreeMap::new();
+        for table in &self.tables {
+            schemas.entry(&table.schema).or_default().push(table);
+        }
+        schemas
+    }
+
+    #[must_use]
+    /// Returns the pages of the dictionary in the provided format: the index
+    /// page `index`, then for each schema the page `<schema>/index` followed
+    /// by the pages `<schema>/tables/<table>` of its tables.
+    pub fn pages(&self, format: DictionaryFormat) -> Vec<DictionaryPage> {
+        let page = |path: String, title: String, blocks: Vec<Block>| DictionaryPage {
+            path: format!("{path}.{}", format.extension()),
+            content: match format {
+                DictionaryFormat::Markdown => render::markdown(&blocks),
+                DictionaryFormat::Html => render::html(&title, &blocks),
+            },
+        };
+        let mut pages = vec![page(
+            "index".to_owned(),
+            self.title.clone(),
+            self.index_blocks(),
+        )];
+        for (schema, tables) in self.schemas() {
+            pages.push(page(
+                schema_path(schema),
+                format!("{schema} - {}", self.title),
+                self.schema_blocks(schema, &tables),
+            ));
+            for table in tables {
+                pages.push(page(
+                    table_path(schema, &table.name),
+                    format!("{schema}.{} - {}", table.name, self.title),
+                    self.table_blocks(table),
+                ));
+            }
+        }
+        pages
+    }
+
+    /// Writes the pages of the dictionary in the provided format under the
+    /// directory, creating the directories as needed and overwriting
+    /// existing pages.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The root directory of the dictionary.
+    /// * `format` - The format of the pages.
+    ///
+    /// # Errors
+    ///
+    /// * If a directory or a page cannot be written.
+    pub fn write<P: AsRef<Path>>(
+        &self,
+        directory: P,
+        format: DictionaryFormat,
+    ) -> std::io::Result<()> {
+        for page in self.pages(format) {
+            let path = directory.as_ref().join(&page.path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, page.content)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the blocks of the index page.
+    fn index_blocks(&self) -> Vec<Block> {
+        let mut blocks = vec![
+            Block::Heading(1, text(&self.title)),
+            Block::Table {
+                header: vec!["Schema", "Tables"],
+                rows: self
+                    .schemas()
+                    .into_iter()
+                    .map(|(schema, tables)| {
+                        vec![
+                            vec![link(schema, 0, &schema_path(schema))],
+                            text(tables.len().to_string()),
+                        ]
+                    })
+                    .collect(),
+            },
+        ];
+        if !self.event_triggers.is_empty() {
+            blocks.push(section("Event triggers"));
+            blocks.push(Block::Table {
+                header: vec!["Event trigger", "Event", "Tags", "Function", "Firing mode"],
+                rows: self
+                    .event_triggers
+                    .iter()
+                    .map(|event_trigger| {
+                        vec![
+                            code(Some(&event_trigger.name)),
+                            code(Some(&event_trigger.event)),
+                            text(event_trigger.tags.join(", ")),
+                            code(Some(&event_trigger.function)),
+                            text(&event_trigger.firing_mode),
+                        ]
+                    })
+                    .collect(),
+            });
+        }
+        blocks
+    }
+
+    /// Returns the blocks of the page of the schema.
+    fn schema_blocks(&self, schema: &str, tables: &[&TableEntry]) -> Vec<Block> {
+        let mut blocks = vec![
+            Block::Heading(1, text(schema)),
+            Block::Paragraph(vec![link(&self.title, 1, "index")]),
+            Block::Table {
+                header: vec!["Table", "Kind", "Description"],
+                rows: tables
+                    .iter()
+                    .map(|table| {
+                        vec![
+                            vec![link(&table.name, 1, &table_path(schema, &table.name))],
+                            text(&table.kind),
+                            summary(table.description.as_deref()),
+                        ]
+                    })
+                    .collect(),
+            },
+        ];
+        let grants: Vec<&ObjectGrantEntry> = self
+            .object_grants
+            .iter()
+            .filter(|grant| grant.schema == schema)
+            .collect();
+        if !grants.is_empty() {
+            blocks.push(section("Grants"));
+            blocks.push(Block::Table {
+                header: vec!["Object", "Kind", "Grantee", "Privilege", "Grantable"],
+                rows: grants
+                    .into_iter()
+                    .map(|grant| {
+                        vec![
+                            code(Some(&grant.object)),
+                            text(&grant.kind),
+                            text(&grant.grantee),
+                            text(&grant.privilege),
+                            text(if grant.grantable { "yes" } else { "no" }),
+                        ]
+                    })
+                    .collect(),
+            });
+        }
+        blocks
+    }
+
+    /// Returns the blocks of the page of the table.
+    fn table_blocks(&self, table: &TableEntry) -> Vec<Block> {
+        let mut properties = format!("Kind: {}.", table.kind);
+        if let Some(server) = &table.server {
+            let _ = write!(properties, " Foreign server: {server}.");
+        }
+        if table.row_security {
+            properties.push_str(if table.forced_row_security {
+                " Row-level security: enabled and forced."
+            } else {
+                " Row-level security: enabled."
+            });
+        }
+        let mut blocks = vec![
+            Block::Heading(1, text(format!("{}.{}", table.schema, table.name))),
+            Block::Paragraph(vec![
+                link(&self.title, 2, "index"),
+                Inline::Text(" / ".to_owned()),
+                link(&table.schema, 2, &schema_path(&table.schema)),
+            ]),
+            Block::Paragraph(text(properties)),
+        ];
+        if let Some(description) = &table.description {
+            blocks.push(Block::Paragraph(text(description)));
+        }
+        blocks.push(section("Columns"));
+        blocks.push(Block::Table {
+            header: vec![
+                "Column",
+                "Type",
+                "Nullable",
+                "Default",
+                "Keys",
+                "Description",
+            ],
+            rows: table
+                .columns
+                .iter()
+                .map(|column| {
+                    let keys: Vec<&str> = [(column.primary_key, "PK"), (column.foreign_key, "FK")]
+                        .into_iter()
+                        .filter_map(|(set, key)| set.then_some(key))
+                        .collect();
+                    vec![
+                        code(Some(&column.name)),
+                        code(Some(&column.type_name)),
+                        text(if column.nullable { "yes" } else { "no" }),
+                        code(column.default.as_ref()),
+                        text(keys.join(", ")),
+                        text(column.description.as_deref().unwrap_or_default()),
+                    ]
+                })
+                .collect(),
+        });
+        if !table.constraints.is_empty() {
+            blocks.push(section("Constraints"));
+            blocks.push(Block::Table {
+                header: vec!["Type", "Definition"],
+                rows: table
+                    .constraints
+                    .iter()
+                    .map(|constraint| {
+                        vec![text(constraint.kind), code(Some(&constraint.definition))]
+                    })
+                    .collect(),
+            });
+        }
+        blocks.extend(self.relationship_blocks(table));
+        blocks.extend(Self::object_blocks(table));
+        blocks
+    }
+
+    /// Returns the blocks of the foreign keys of the table and of the foreign
+    /// keys referencing it.
+    fn relationship_blocks(&self, table: &TableEntry) -> Vec<Block> {
+        let mut blocks = Vec::new();
+        if !table.foreign_keys.is_empty() {
+            blocks.push(section("References"));
+            blocks.push(Block::Table {
+                header: vec![
+                    "Foreign key",
+                    "Columns",
+                    "Referenced table",
+                    "Referenced columns",
+                ],
+                rows: table
+                    .foreign_keys
+                    .iter()
+                    .map(|foreign_key| self.foreign_key_row(foreign_key, &foreign_key.referenced))
+                    .collect(),
+            });
+        }
+        let mut incoming: Vec<&ForeignKeyEntry> = self
+            .tables
+            .iter()
+            .flat_map(|host| &host.foreign_keys)
+            .filter(|foreign_key| {
+                foreign_key.referenced.0 == table.schema && foreign_key.referenced.1 == table.name
+            })
+            .collect();
+        incoming.sort_by(|left, right| (&left.host, &left.name).cmp(&(&right.host, &right.name)));
+        if !incoming.is_empty() {
+            blocks.push(section("Referenced by"));
+            blocks.push(Block::Table {
+                header: vec!["Foreign key", "Columns", "Table", "Referenced columns"],
+                rows: incoming
+                    .into_iter()
+                    .map(|foreign_key| self.foreign_key_row(foreign_key, &foreign_key.host))
+                    .collect(),
+            });
+        }
+        blocks
+    }
+
+    /// Returns the row of the foreign key, linking to the other table unless
+    /// it is not part of the dictionary.
+    fn foreign_key_row(
+        &self,
+        foreign_key: &ForeignKeyEntry,
+        other: &(String, String),
+    ) -> Vec<Vec<Inline>> {
+        let qualified_name = format!("{}.{}", other.0, other.1);
+        let other_table = if self
+            .tables
+            .iter()
+            .any(|table| table.schema == other.0 && table.name == other.1)
+        {
+            vec![link(qualified_name, 2, &table_path(&other.0, &other.1))]
+        } else {
+            text(qualified_name)
+        };
+        vec![
+            code(Some(&foreign_key.name)),
+            code(Some(foreign_key.host_columns.join(", "))),
+            other_table,
+            code(Some(foreign_key.referenced_columns.join(", "))),
+        ]
+    }
+
+    /// Returns the blocks of the indexes, triggers, rules, policies and
+    /// grants of the table.
+    #[allow(clippy::too_many_lines)]
+    fn object_blocks(table: &TableEntry) -> Vec<Block> {
+        let mut blocks = Vec::new();
+        if !table.indexes.is_empty() {
+            blocks.push(section("Indexes"));
+            blocks.push(Block::Table {
+                header: vec!["Index", "Definition"],
+                rows: table
+                    .indexes
+                    .iter()
+                    .map(|index| {
+                        let name = match &index.name {
+                            Some(name) => code(Some(name)),
+                            None if index.primary_key => text("primary key"),
+                            None => text("unique"),
+                        };
+                        vec![name, code(Some(&index.definition))]
+                    })
+                    .collect(),
+            });
+        }
+        if !table.triggers.is_empty() {
+            blocks.push(section("Triggers"));
+            blocks.push(Block::Table {
+                header: vec!["Trigger", "Fires", "Condition", "Function", "Action"],
+                rows: table
+                    .triggers
+                    .iter()
+                    .map(|trigger| {
+                        let events: Vec<&str> = trigger.events.iter().map(String::as_str).collect();
+                        vec![
+                            code(Some(&trigger.name)),
+                            text(format!(
+                                "{} {} FOR EACH {}",
+                                trigger.timing,
+                                events.join(" OR "),
+                                trigger.orientation
+                            )),
+                            code(trigger.condition.as_ref()),
+                            code(trigger.function.as_ref()),
+                            code(Some(&trigger.statement)),
+                        ]
+                    })
+                    .collect(),
+            });
+        }
+        if !table.rules.is_empty() {
+            blocks.push(section("Rules"));
+            blocks.push(Block::Table {
+                header: vec!["Rule", "Fires", "Firing mode", "Definition"],
+                rows: table
+                    .rules
+                    .iter()
+                    .map(|rule| {
+                        vec![
+                            code(Some(&rule.name)),
+                            text(format!(
+                                "ON {} DO {}",
+                                rule.event,
+                                if rule.instead { "INSTEAD" } else { "ALSO" }
+                            )),
+                            text(&rule.firing_mode),
+                            code(Some(&rule.definition)),
+                        ]
+                    })
+                    .collect(),
+            });
+        }
+        if !table.policies.is_empty() {
+            blocks.push(section("Policies"));
+            blocks.push(Block::Table {
+                header: vec!["Policy", "Command", "Type", "Roles", "Using", "With check"],
+                rows: table
+                    .policies
+                    .iter()
+                    .map(|policy| {
+                        vec![
+                            code(Some(&policy.name)),
+                            text(policy.command),
+                            text(if policy.permissive {
+                                "PERMISSIVE"
+                            } else {
+                                "RESTRICTIVE"
+                            }),
+                            text(policy.roles.join(", ")),
+                            code(policy.using.as_ref()),
+                            code(policy.check.as_ref()),
+                        ]
+                    })
+                    .collect(),
+            });
+        }
+        if !table.grants.is_empty() {
+            blocks.push(section("Grants"));
+            blocks.push(Block::Table {
+                header: vec!["Grantee", "Privilege", "Column", "Grantable"],
+                rows: table
+                    .grants
+                    .iter()
+                    .map(|grant| {
+                        vec![
+                            text(&grant.grantee),
+                            text(&grant.privilege),
+                            code(grant.column.as_ref()),
+                            text(if grant.grantable { "yes" } else { "no" }),
+                        ]
+                    })
+                    .collect(),
+            });
+        }
+        blocks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, type_name: &str, primary_key: bool, foreign_key: bool) -> ColumnEntry {
+        ColumnEntry {
+            name: name.to_owned(),
+            type_name: type_name.to_owned(),
+            nullable: !primary_key,
+            default: None,
+            description: None,
+            primary_key,
+            foreign_key,
+        }
+    }
+
+    fn table(schema: &str, name: &str, columns: Vec<ColumnEntry>) -> TableEntry {
+        TableEntry {
+            schema: schema.to_owned(),
+            name: name.to_owned(),
+            kind: "BASE TABLE".to_owned(),
+            description: None,
+            server: None,
+            row_security: false,
+            forced_row_security: false,
+            columns,
+            constraints: Vec::new(),
+            foreign_keys: Vec::new(),
+            indexes: Vec::new(),
+            triggers: Vec::new(),
+            rules: Vec::new(),
+            policies: Vec::new(),
+            grants: Vec::new(),
+        }
+    }
+
+    fn dictionary() -> DataDictionary {
+        let mut users = table(
+            "public",
+            "users",
+            vec![
+                column("id", "int4", true, false),
+                column("email", "varchar(255)", false, false),
+            ],
+        );
+        users.description = Some("Registered users.\nOne row per account.".to_owned());
+        users.constraints.push(ConstraintEntry {
+            kind: "PRIMARY KEY",
+            definition: "PRIMARY KEY (\"id\")".to_owned(),
+        });
+        users.indexes.push(IndexEntry {
+            name: None,
+            primary_key: true,
+            definition: "CREATE UNIQUE INDEX ON public.users (id)".to_owned(),
+        });
+        users.grants.push(GrantEntry {
+            grantee: "reporting".to_owned(),
+            privilege: "SELECT".to_owned(),
+            column: None,
+            grantable: false,
+        });
+        users.rules.push(RuleEntry {
+            name: "protect_users".to_owned(),
+            event: "DELETE".to_owned(),
+            instead: true,
+            firing_mode: "origin".to_owned(),
+            definition:
+                "CREATE RULE protect_users AS ON DELETE TO public.users DO INSTEAD NOTHING;"
+                    .to_owned(),
+        });
+        let mut orders = table(
+            "sales",
+            "order items",
+            vec![column("user_id", "int4", false, true)],
+        );
+        orders.foreign_keys.push(ForeignKeyEntry {
+            name: "orders_user_id_fkey".to_owned(),
+            host: ("sales".to_owned(), "order items".to_owned()),
+            host_columns: vec!["user_id".to_owned()],
+            referenced: ("public".to_owned(), "users".to_owned()),
+            referenced_columns: vec!["id".to_owned()],
+            match_option: "NONE".to_owned(),
+            update_rule: "NO ACTION".to_owned(),
+            delete_rule: "CASCADE".to_owned(),
+        });
+        orders.triggers.push(TriggerEntry {
+            name: "orders_audit".to_owned(),
+            timing: "AFTER".to_owned(),
+            events: ["INSERT".to_owned(), "UPDATE".to_owned()].into(),
+            orientation: "ROW".to_owned(),
+            condition: None,
+            function: Some("audit".to_owned()),
+            statement: "EXECUTE FUNCTION audit()".to_owned(),
+        });
+        DataDictionary {
+            title: "Shop".to_owned(),
+            tables: vec![users, orders],
+            event_triggers: vec![EventTriggerEntry {
+                name: "audit_ddl".to_owned(),
+                event: "ddl_command_end".to_owned(),
+                tags: vec!["CREATE TABLE".to_owned()],
+                function: "audit.log_ddl".to_owned(),
+                firing_mode: "origin".to_owned(),
+            }],
+            object_grants: vec![ObjectGrantEntry {
+                schema: "sales".to_owned(),
+                kind: "SEQUENCE".to_owned(),
+                object: "orders_id_seq".to_owned(),
+                grantee: "reporting".to_owned(),
+                privilege: "USAGE".to_owned(),
+                grantable: false,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_pages() {
+        let dictionary = dictionary();
+        let pages = dictionary.pages(DictionaryFormat::Markdown);
+        assert_eq!(
+            pages.iter().map(DictionaryPage::path).collect::<Vec<_>>(),
+            [
+                "index.md",
+                "public/index.md",
+                "public/tables/users.md",
+                "sales/index.md",
+                "sales/tables/order~20items.md",
+            ]
+        );
+        assert_eq!(pages, dictionary.pages(DictionaryFormat::Markdown));
+        assert_eq!(
+            pages[0].content(),
+            "# Shop\n\n| Schema | Tables |\n| --- | --- |\n\
+             | [public](public/index.md) | 1 |\n| [sales](sales/index.md) | 1 |\n\n\
+             ## Event triggers\n\n| Event trigger | Event | Tags | Function | Firing mode |\n\
+             | --- | --- | --- | --- | --- |\n\
+             | `audit_ddl` | `ddl_command_end` | CREATE TABLE | `audit.log_ddl` | origin |\n"
+        );
+        assert!(
+            pages[1].content().contains(
+                "| [users](../public/tables/users.md) | BASE TABLE | Registered users. |\n"
+            )
+        );
+
+        assert!(pages[3].content().ends_with(
+            "## Grants\n\n| Object | Kind | Grantee | Privilege | Grantable |\n\
+             | --- | --- | --- | --- | --- |\n\
+             | `orders_id_seq` | SEQUENCE | reporting | USAGE | no |\n"
+        ));
+
+        let users = pages[2].content();
+        assert!(users.starts_with(
+            "# public.users\n\n[Shop](../../index.md) / [public](../../public/index.md)\n"
+        ));
+        assert!(users.contains("Registered users.  \nOne row per account.\n"));
+        assert!(users.contains("| `id` | `int4` | no |  | PK |  |\n"));
+        assert!(users.contains("| PRIMARY KEY | `PRIMARY KEY (\"id\")` |\n"));
+        assert!(users.contains("| primary key | `CREATE UNIQUE INDEX ON public.users (id)` |\n"));
+        assert!(users.contains(
+            "## Referenced by\n\n| Foreign key | Columns | Table | Referenced columns |\n\
+             | --- | --- | --- | --- |\n\
+             | `orders_user_id_fkey` | `user_id` | [sales.order items](../../sales/tables/order~20items.md) | `id` |\n"
+        ));
+        assert!(users.contains(
+            "| `protect_users` | ON DELETE DO INSTEAD | origin | \
+             `CREATE RULE protect_users AS ON DELETE TO public.users DO INSTEAD NOTHING;` |\n"
+        ));
+        assert!(users.contains("| reporting | SELECT |  | no |\n"));
+
+        let orders = pages[4].content();
+        assert!(orders.contains("[public.users](../../public/tables/users.md)"));
+        assert!(orders.contains(
+            "| `orders_audit` | AFTER INSERT OR UPDATE FOR EACH ROW |  | `audit` | `EXECUTE FUNCTION audit()` |\n"
+        ));
+        assert!(!orders.contains("## Referenced by"));
+
+        let html = dictionary.pages(DictionaryFormat::Html);
+        assert_eq!(html[4].path(), "sales/tables/order~20items.html");
+        assert!(
+            html[4]
+                .content()
+                .contains("<a href=\"../../public/tables/users.html\">public.users</a>")
+        );
+    }
+
+    #[test]
+    fn test_foreign_key_definition() {
+        let foreign_key = &dictionary().tables[1].foreign_keys[0];
+        assert_eq!(
+            foreign_key.definition(),
+            "CONSTRAINT \"orders_user_id_fkey\" FOREIGN KEY (\"user_id\") \
+             REFERENCES \"public\".\"users\" (\"id\") ON DELETE CASCADE"
+        );
+        assert_eq!(file_name("Café.v2"), "Caf~C3~A9~2Ev2");
+        assert_eq!("md".parse(), Ok(DictionaryFormat::Markdown));
+    }
+}
@@ -0,0 +1,225 @@
+//! Submodule rendering the pages of a data dictionary, described as
+//! [`Block`]s, as Markdown or HTML.
+
+use std::fmt::Write;
+
+use crate::quoting::escape_html;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A span of text of a [`Block`].
+pub(super) enum Inline {
+    /// Plain text, possibly spanning several lines.
+    Text(String),
+    /// Code, such as an identifier or a SQL definition.
+    Code(String),
+    /// A link to another page.
+    Link {
+        /// The text of the link.
+        text: String,
+        /// The path of the page relative to the linking page, without
+        /// extension.
+        target: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A block of a page.
+pub(super) enum Block {
+    /// A heading of the provided level, from 1.
+    Heading(usize, Vec<Inline>),
+    /// A paragraph.
+    Paragraph(Vec<Inline>),
+    /// A table with a header row, whose cells are spans of text.
+    Table {
+        /// The titles of the columns.
+        header: Vec<&'static str>,
+        /// The cells of each row.
+        rows: Vec<Vec<Vec<Inline>>>,
+    },
+}
+
+/// Returns the text escaped for Markdown.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Returns the spans as Markdown, with line breaks and pipes escaped when
+/// in a table cell.
+fn inline_markdown(spans: &[Inline], in_table: bool) -> String {
+    let mut markdown = String::new();
+    for span in spans {
+        match span {
+            Inline::Text(text) => {
+                let text = escape_markdown(text);
+                if in_table {
+                    markdown.push_str(&text.replace('\n', "<br>"));
+                } else {
+                    markdown.push_str(&text.replace('\n', "  \n"));
+                }
+            }
+            Inline::Code(code) => {
+                let mut code = code.replace('\n', " ");
+                if in_table {
+                    code = code.replace('|', "\\|");
+                }
+                let mut longest = 0;
+                let mut current = 0;
+                for c in code.chars() {
+                    current = if c == '`' { current + 1 } else { 0 };
+                    longest = longest.max(current);
+                }
+                let fence = "`".repeat(longest + 1);
+                let padding = if code.starts_with('`') || code.ends_with('`') {
+                    " "
+                } else {
+                    ""
+                };
+                let _ = write!(markdown, "{fence}{padding}{code}{padding}{fence}");
+            }
+            Inline::Link { text, target } => {
+                let _ = write!(markdown, "[{}]({target}.md)", escape_markdown(text));
+            }
+        }
+    }
+    markdown
+}
+
+/// Returns the blocks as a Markdown document.
+pub(super) fn markdown(blocks: &[Block]) -> String {
+    let mut markdown = String::new();
+    for block in blocks {
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+        match block {
+            Block::Heading(level, spans) => {
+                let _ = writeln!(
+                    markdown,
+                    "{} {}",
+                    "#".repeat(*level),
+                    inline_markdown(spans, false)
+                );
+            }
+            Block::Paragraph(spans) => {
+                let _ = writeln!(markdown, "{}", inline_markdown(spans, false));
+            }
+            Block::Table { header, rows } => {
+                let _ = writeln!(markdown, "| {} |", header.join(" | "));
+                let _ = writeln!(markdown, "|{}", " --- |".repeat(header.len()));
+                for row in rows {
+                    let cells: Vec<String> =
+                        row.iter().map(|cell| inline_markdown(cell, true)).collect();
+                    let _ = writeln!(markdown, "| {} |", cells.join(" | "));
+                }
+            }
+        }
+    }
+    markdown
+}
+
+/// Returns the spans as HTML.
+fn inline_html(spans: &[Inline]) -> String {
+    let mut html = String::new();
+    for span in spans {
+        match span {
+            Inline::Text(text) => html.push_str(&escape_html(text).replace('\n', "<br>")),
+            Inline::Code(code) => {
+                let _ = write!(html, "<code>{}</code>", escape_html(code));
+            }
+            Inline::Link { text, target } => {
+                let _ = write!(
+                    html,
+                    "<a href=\"{}.html\">{}</a>",
+                    escape_html(target),
+                    escape_html(text)
+                );
+            }
+        }
+    }
+    html
+}
+
+/// The style sheet embedded in every HTML page.
+const STYLE: &str = "body{font-family:sans-serif;margin:2em auto;max-width:72em;padding:0 1em}\
+table{border-collapse:collapse;margin-bottom:1.5em}\
+th,td{border:1px solid #ccc;padding:.3em .6em;text-align:left;vertical-align:top}\
+th{background:#f3f3f3}code{font-size:.95em}";
+
+/// Returns the blocks as a standalone HTML document with the provided title.
+pub(super) fn html(title: &str, blocks: &[Block]) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n",
+        escape_html(title)
+    );
+    for block in blocks {
+        match block {
+            Block::Heading(level, spans) => {
+                let _ = writeln!(html, "<h{level}>{}</h{level}>", inline_html(spans));
+            }
+            Block::Paragraph(spans) => {
+                let _ = writeln!(html, "<p>{}</p>", inline_html(spans));
+            }
+            Block::Table { header, rows } => {
+                html.push_str("<table>\n<thead><tr>");
+                for title in header {
+                    let _ = write!(html, "<th>{}</th>", escape_html(title));
+                }
+                html.push_str("</tr></thead>\n<tbody>\n");
+                for row in rows {
+                    html.push_str("<tr>");
+                    for cell in row {
+                        let _ = write!(html, "<td>{}</td>", inline_html(cell));
+                    }
+                    html.push_str("</tr>\n");
+                }
+                html.push_str("</tbody>\n</table>\n");
+            }
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_blocks() {
+        let blocks = vec![
+            Block::Heading(1, vec![Inline::Text("public.user_roles".to_owned())]),
+            Block::Table {
+                header: vec!["Name", "Definition"],
+                rows: vec![vec![
+                    vec![Inline::Link {
+                        text: "a|b".to_owned(),
+                        target: "../tables/a".to_owned(),
+                    }],
+                    vec![Inline::Code("CHECK ((a || `b`) <> '')".to_owned())],
+                ]],
+            },
+        ];
+        assert_eq!(
+            markdown(&blocks),
+            "# public.user\\_roles\n\n| Name | Definition |\n| --- | --- |\n\
+             | [a\\|b](../tables/a.md) | ``CHECK ((a \\|\\| `b`) <> '')`` |\n"
+        );
+        let html = html("<title>", &blocks);
+        assert!(html.contains("<title>&lt;title&gt;</title>"));
+        assert!(html.contains(
+            "<tr><td><a href=\"../tables/a.html\">a|b</a></td>\
+             <td><code>CHECK ((a || `b`) &lt;&gt; '')</code></td></tr>"
+        ));
+    }
+}
@@ -13,9 +13,13 @@ mod key_column_usage_metadata;
 pub use key_column_usage_metadata::KeyColumnUsageMetadata;
 mod pg_proc_metadata;
 pub use pg_proc_metadata::PgProcMetadata;
+mod lookup;
+pub use lookup::{ConstraintRef, ObjectIndex};
 
 mod builder;
 pub use builder::{PgDatabaseBuildError, PgDieselDatabaseBuilder};
+mod with_event_triggers;
+pub use with_event_triggers::PgDieselDatabaseWithEventTriggers;
 
 /// Type alias representing a `PostgreSQL` database with loaded metadata.
 ///
@@ -1,6 +1,6 @@
 //! Builder pattern for constructing a [`PgDieselDatabase`] instance.
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use diesel::PgConnection;
 use sql_traits::{
@@ -15,9 +15,13 @@ use sqlparser::{
 
 use crate::{
     PgDieselDatabase,
+    acl::{AclError, ObjectGrant},
+    database::{PgDieselDatabaseWithEventTriggers, PgProcMetadata},
+    identity::{ProcOid, QualifiedName},
     impls::{RoleColumnGrantsMetadata, RoleTableGrantsMetadata, string_to_action},
-    model_metadata::TriggerMetadata,
+    model_metadata::{EventTriggerMetadata, StorageMetadata, TriggerMetadata},
     models::{Column, PgProc, PgRole, RoleColumnGrants, RoleTableGrants, Table},
+    traits::{HasProcOid, HasQualifiedName},
 };
 
 #[derive(Default)]
@@ -31,6 +35,10 @@ pub struct PgDieselDatabaseBuilder<'conn> {
     schemas: Vec<String>,
     /// Types denylist.
     denylist_types: Vec<String>,
+    /// Whether to skip the functions that belong to an extension.
+    exclude_extension_functions: bool,
+    /// Whether to load the physical storage of the tables.
+    load_storage: bool,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -51,6 +59,9 @@ pub enum PgDatabaseBuildError {
     #[error("Duplicate denylisted type: {0}")]
     /// A deny-listed type was inserted multiple times.
     DuplicateDenylistedType(String),
+    #[error("ACL error: {0}")]
+    /// An access control list of an object could not be parsed.
+    Acl(#[from] AclError),
 }
 
 impl<'conn> PgDieselDatabaseBuilder<'conn> {
@@ -119,6 +130,52 @@ impl<'conn> PgDieselDatabaseBuilder<'conn> {
         }
         Ok(self)
     }
+
+    /// Sets whether the functions that belong to an extension (i.e. that have
+    /// an `e` dependency in `pg_depend`) should be excluded when loading the
+    /// functions of the selected schemas.
+    ///
+    /// Extension functions referenced by check constraints, triggers or
+    /// policies are still loaded, as they are needed to describe those objects.
+    #[must_use]
+    pub fn exclude_extension_functions(mut self, exclude: bool) -> Self {
+        self.exclude_extension_functions = exclude;
+        self
+    }
+
+    /// Sets whether the physical storage of the tables and their indexes,
+    /// such as their tablespace, storage parameters and size, should be
+    /// loaded and attached to their
+    /// [`TableMetadata`](crate::model_metadata::TableMetadata).
+    ///
+    /// It is not loaded by default, as computing the size of every relation
+    /// requires a scan of their files.
+    #[must_use]
+    pub fn load_storage(mut self, load: bool) -> Self {
+        self.load_storage = load;
+        self
+    }
+}
+
+/// Returns the function with the provided OID, loading it from the database if
+/// it is not among the already loaded functions.
+///
+/// When the function is loaded, it is inserted in the provided map and its
+/// metadata is returned alongside it so that it can be registered in the
+/// database builder.
+fn resolve_function(
+    oid: ProcOid,
+    functions: &mut HashMap<ProcOid, Arc<PgProc>>,
+    connection: &mut PgConnection,
+) -> Result<(Arc<PgProc>, Option<PgProcMetadata>), diesel::result::Error> {
+    if let Some(function) = functions.get(&oid) {
+        return Ok((Arc::clone(function), None));
+    }
+    let (function, schema) = PgProc::from_oid(oid.get(), connection)?;
+    let metadata = PgProcMetadata::new(&function, &schema, connection)?;
+    let function = Arc::new(function);
+    functions.insert(oid, Arc::clone(&function));
+    Ok((function, Some(metadata)))
 }
 
 impl<'a> TryFrom<PgDieselDatabaseBuilder<'a>> for PgDieselDatabase {
@@ -144,10 +201,19 @@ impl<'a> TryFrom<PgDieselDatabaseBuilder<'a>> for PgDieselDatabase {
 
         let mut generic_builder = GenericDBBuilder::new(table_catalog.clone());
 
-        // Load all functions first as they may be referenced by other objects
-        for function in PgProc::load_all(connection)? {
-            let metadata = crate::database::PgProcMetadata::new(&function, connection)?;
-            generic_builder = generic_builder.add_function(std::sync::Arc::new(function), metadata);
+        // Load the functions of the selected schemas first as they may be
+        // referenced by other objects. Functions from other schemas (e.g. the
+        // `pg_catalog` built-ins) are only loaded when they are referenced.
+        let mut functions: HashMap<ProcOid, Arc<PgProc>> = HashMap::new();
+        for (function, schema) in PgProc::load_all_in_schemas(
+            &table_schemas,
+            value.exclude_extension_functions,
+            connection,
+        )? {
+            let metadata = PgProcMetadata::new(&function, &schema, connection)?;
+            let function = Arc::new(function);
+            functions.insert(function.proc_oid(), Arc::clone(&function));
+            generic_builder = generic_builder.add_function(function, metadata);
         }
 
         // Load all roles
@@ -175,49 +241,52 @@ impl<'a> TryFrom<PgDieselDatabaseBuilder<'a>> for PgDieselDatabase {
         });
 
         // Create lookup maps for tables and columns for grant metadata
-        let mut tables_by_schema_name: std::collections::HashMap<(String, String), Arc<Table>> =
-            std::collections::HashMap::new();
-        let mut columns_by_table_column: std::collections::HashMap<
-            (String, String, String),
-            Arc<Column>,
-        > = std::collections::HashMap::new();
+        let mut tables_by_name: HashMap<QualifiedName, Arc<Table>> = HashMap::new();
+        let mut columns_by_table_column: HashMap<(QualifiedName, String), Arc<Column>> =
+            HashMap::new();
 
         // For each table, we determine all of the foreign keys and for each foreign key
         // we determine which table it references.
         for table in tables {
             // Add to lookup map
-            tables_by_schema_name.insert(
-                (
-                    table.table_schema().unwrap_or("").to_string(),
-                    table.table_name().to_string(),
-                ),
-                Arc::clone(&table),
-            );
+            tables_by_name.insert(table.qualified_name(), Arc::clone(&table));
 
-            let table_metadata = table.metadata(connection, &value.denylist_types)?;
+            let mut table_metadata = table.metadata(connection, &value.denylist_types)?;
+            if value.load_storage {
+                let storage = StorageMetadata::load(table_metadata.rel_oid(), connection)?;
+                table_metadata = table_metadata.with_storage(storage);
+            }
 
+            let mut collations = table.column_collations(connection)?;
             for column in table_metadata.column_arcs() {
                 // Add to column lookup map
                 columns_by_table_column.insert(
-                    (
-                        table.table_schema().unwrap_or("").to_string(),
-                        table.table_name().to_string(),
-                        column.column_name().to_string(),
-                    ),
+                    (table.qualified_name(), column.column_name().to_owned()),
                     Arc::clone(&column),
                 );
 
                 generic_builder = generic_builder.add_column(
                     Arc::clone(&column),
-                    column.metadata(Arc::clone(&table), connection)?,
+                    column.metadata(
+                        Arc::clone(&table),
+                        collations.remove(column.column_name()),
+                        connection,
+                    )?,
                 );
             }
 
             for check_constraint in table_metadata.check_constraint_arcs() {
+                for function in check_constraint.functions(connection)? {
+                    let (function, metadata) =
+                        resolve_function(function.proc_oid(), &mut functions, connection)?;
+                    if let Some(metadata) = metadata {
+                        generic_builder = generic_builder.add_function(function, metadata);
+                    }
+                }
                 let metadata = check_constraint.metadata(
                     Arc::clone(&table),
                     &table_metadata,
-                    generic_builder.function_arc_vec().as_slice(),
+                    &functions,
                     connection,
                 )?;
                 generic_builder =
@@ -239,10 +308,23 @@ impl<'a> TryFrom<PgDieselDatabaseBuilder<'a>> for PgDieselDatabase {
             }
 
             for (trigger, function_oid) in table_metadata.triggers() {
+                let function = match function_oid {
+                    Some(oid) => {
+                        let (function, metadata) =
+                            resolve_function(*oid, &mut functions, connection)?;
+                        if let Some(metadata) = metadata {
+                            generic_builder =
+                                generic_builder.add_function(Arc::clone(&function), metadata);
+                        }
+                        Some(function)
+                    }
+                    None => None,
+                };
                 let metadata = TriggerMetadata::new(
                     trigger.as_ref().clone(),
                     Arc::clone(&table),
                     *function_oid,
+                    function,
                 );
                 generic_builder = generic_builder.add_trigger(Arc::new(metadata), ());
             }
@@ -258,8 +340,12 @@ impl<'a> TryFrom<PgDieselDatabaseBuilder<'a>> for PgDieselDatabase {
                     })
                 };
 
-                let using_expression = parse_expr(&policy.polqual);
-                let check_expression = parse_expr(&policy.polwithcheck);
+                let (using_sql, check_sql) =
+                    crate::models::pg_policy_table::cached_queries::expressions(
+                        policy, connection,
+                    )?;
+                let using_expression = parse_expr(&using_sql);
+                let check_expression = parse_expr(&check_sql);
 
                 let roles: Vec<Owner> =
                     crate::models::pg_policy_table::cached_queries::roles(policy, connection)?
@@ -267,11 +353,32 @@ impl<'a> TryFrom<PgDieselDatabaseBuilder<'a>> for PgDieselDatabase {
                         .map(|r| Owner::Ident(Ident::new(r.rolname)))
                         .collect();
 
-                // Initialize Metadata with empty dependencies and table (functions will be filled later if we had logic for it)
+                let mut using_functions = Vec::new();
+                for oid in policy.using_function_oids() {
+                    let (function, metadata) =
+                        resolve_function(ProcOid::new(oid), &mut functions, connection)?;
+                    if let Some(metadata) = metadata {
+                        generic_builder =
+                            generic_builder.add_function(Arc::clone(&function), metadata);
+                    }
+                    using_functions.push(function);
+                }
+
+                let mut check_functions = Vec::new();
+                for oid in policy.check_function_oids() {
+                    let (function, metadata) =
+                        resolve_function(ProcOid::new(oid), &mut functions, connection)?;
+                    if let Some(metadata) = metadata {
+                        generic_builder =
+                            generic_builder.add_function(Arc::clone(&function), metadata);
+                    }
+                    check_functions.push(function);
+                }
+
                 let metadata = crate::model_metadata::PolicyMetadata::new(
                     Arc::clone(&table),
-                    Vec::new(), // using_functions
-                    Vec::new(), // check_functions
+                    using_functions,
+                    check_functions,
                     using_expression,
                     check_expression,
                     roles,
@@ -330,10 +437,10 @@ impl<'a> TryFrom<PgDieselDatabaseBuilder<'a>> for PgDieselDatabase {
         let table_grants = RoleTableGrants::load_all(&table_catalog, &table_schemas, connection)?;
         for grant in table_grants {
             // Find the table this grant applies to
-            let table_rc = tables_by_schema_name
-                .get(&(
-                    grant.table_schema.clone().unwrap_or_default(),
-                    grant.table_name.clone().unwrap_or_default(),
+            let table_rc = tables_by_name
+                .get(&QualifiedName::new(
+                    grant.table_schema.as_deref().unwrap_or_default(),
+                    grant.table_name.as_deref().unwrap_or_default(),
                 ))
                 .cloned();
 
@@ -356,10 +463,10 @@ impl<'a> TryFrom<PgDieselDatabaseBuilder<'a>> for PgDieselDatabase {
         let column_grants = RoleColumnGrants::load_all(&table_catalog, &table_schemas, connection)?;
         for grant in column_grants {
             // Find the table this grant applies to
-            let table_rc = tables_by_schema_name
-                .get(&(
-                    grant.table_schema.clone().unwrap_or_default(),
-                    grant.table_name.clone().unwrap_or_default(),
+            let table_rc = tables_by_name
+                .get(&QualifiedName::new(
+                    grant.table_schema.as_deref().unwrap_or_default(),
+                    grant.table_name.as_deref().unwrap_or_default(),
                 ))
                 .cloned();
 
@@ -367,8 +474,7 @@ impl<'a> TryFrom<PgDieselDatabaseBuilder<'a>> for PgDieselDatabase {
             let column_rc = table_rc.as_ref().and_then(|t| {
                 columns_by_table_column
                     .get(&(
-                        t.table_schema().unwrap_or_default().to_string(),
-                        t.table_name().to_string(),
+                        t.qualified_name(),
                         grant.column_name.clone().unwrap_or_default(),
                     ))
                     .cloned()
@@ -393,6 +499,24 @@ impl<'a> TryFrom<PgDieselDatabaseBuilder<'a>> for PgDieselDatabase {
     }
 }
 
+impl<'a> TryFrom<PgDieselDatabaseBuilder<'a>> for PgDieselDatabaseWithEventTriggers {
+    type Error = PgDatabaseBuildError;
+
+    fn try_from(mut value: PgDieselDatabaseBuilder<'a>) -> Result<Self, Self::Error> {
+        let connection = value
+            .connection
+            .as_deref_mut()
+            .ok_or(PgDatabaseBuildError::MissingAttribute("connection"))?;
+        let event_triggers = EventTriggerMetadata::load_all(connection)?;
+        let object_grants = ObjectGrant::load_all(&value.schemas, connection)?;
+        Ok(Self {
+            database: value.try_into()?,
+            event_triggers,
+            object_grants,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -469,6 +593,22 @@ mod tests {
         assert_eq!(builder.schemas, vec!["public", "public"]);
     }
 
+    #[test]
+    fn test_exclude_extension_functions() {
+        let builder = PgDieselDatabaseBuilder::default();
+        assert!(!builder.exclude_extension_functions);
+        let builder = builder.exclude_extension_functions(true);
+        assert!(builder.exclude_extension_functions);
+    }
+
+    #[test]
+    fn test_load_storage() {
+        let builder = PgDieselDatabaseBuilder::default();
+        assert!(!builder.load_storage);
+        let builder = builder.load_storage(true);
+        assert!(builder.load_storage);
+    }
+
     #[test]
     fn test_try_from_missing_connection() {
         let builder = PgDieselDatabaseBuilder::default();
@@ -0,0 +1,232 @@
+//! Submodule providing the [`ObjectIndex`] struct, which looks up the objects
+//! of a [`PgDieselDatabase`] by OID or by qualified name.
+//!
+//! [`PgDieselDatabase`] stores its objects in insertion order, so finding one
+//! by identifier requires a linear scan such as
+//! `database.functions().find(|f| f.oid == oid)`. An [`ObjectIndex`] is built
+//! once from the database and answers those lookups from hash maps, for its
+//! tables, columns, types, functions and constraints.
+//!
+//! The index is a separate value rather than part of the database:
+//! [`PgDieselDatabase`] is the `GenericDB` of `sql_traits`, which has no room
+//! for extra state, and the index holds references into the database, so
+//! storing it in a struct owning the database, such as
+//! [`PgDieselDatabaseWithEventTriggers`](crate::database::PgDieselDatabaseWithEventTriggers),
+//! would make that struct self-referential. The exporters needing lookups
+//! build their own index instead, in a single pass over the database.
+
+use std::collections::HashMap;
+
+use sql_traits::traits::DatabaseLike;
+
+use crate::{
+    PgDieselDatabase,
+    identity::{ProcOid, QualifiedName, RelOid, TypeOid},
+    models::{CheckConstraint, Column, KeyColumnUsage, PgProc, PgType, Table},
+    traits::{HasProcOid, HasQualifiedName, HasTypeOid},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A constraint of a [`PgDieselDatabase`], as found by
+/// [`ObjectIndex::constraint`].
+pub enum ConstraintRef<'db> {
+    /// A check constraint.
+    Check(&'db CheckConstraint),
+    /// A foreign key constraint.
+    ForeignKey(&'db KeyColumnUsage),
+}
+
+#[derive(Debug, Clone)]
+/// Index of the objects of a [`PgDieselDatabase`] by OID and by qualified
+/// name.
+///
+/// The index borrows the database, so it reflects the objects loaded when
+/// the database was built. Constraints are indexed by the qualified name of
+/// their table and their name, as constraint names are only unique per table
+/// and the information schema models representing them carry no OID.
+pub struct ObjectIndex<'db> {
+    /// The tables by OID.
+    tables_by_oid: HashMap<RelOid, &'db Table>,
+    /// The tables by qualified name.
+    tables_by_name: HashMap<QualifiedName, &'db Table>,
+    /// The columns by table OID and attribute number.
+    columns_by_oid: HashMap<(RelOid, i16), &'db Column>,
+    /// The columns by table qualified name and column name.
+    columns_by_name: HashMap<QualifiedName, HashMap<&'db str, &'db Column>>,
+    /// The types of the columns and functions by OID.
+    types_by_oid: HashMap<TypeOid, &'db PgType>,
+    /// The OIDs of the types of the columns by qualified name.
+    types_by_name: HashMap<QualifiedName, TypeOid>,
+    /// The functions by OID.
+    functions_by_oid: HashMap<ProcOid, &'db PgProc>,
+    /// The overloads of the functions by qualified name.
+    functions_by_name: HashMap<QualifiedName, Vec<&'db PgProc>>,
+    /// The check and foreign key constraints by table qualified name and
+    /// constraint name.
+    constraints_by_name: HashMap<QualifiedName, HashMap<&'db str, ConstraintRef<'db>>>,
+}
+
+impl<'db> ObjectIndex<'db> {
+    #[must_use]
+    /// Creates a new `ObjectIndex` of the objects of the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The database whose objects are indexed.
+    ///
+    /// # Panics
+    ///
+    /// * If the metadata of an object of the database is missing, which
+    ///   should not happen if the database was built by
+    ///   [`PgDieselDatabaseBuilder`](crate::database::PgDieselDatabaseBuilder).
+    pub fn new(database: &'db PgDieselDatabase) -> Self {
+        let mut index = Self {
+            tables_by_oid: HashMap::new(),
+            tables_by_name: HashMap::new(),
+            columns_by_oid: HashMap::new(),
+            columns_by_name: HashMap::new(),
+            types_by_oid: HashMap::new(),
+            types_by_name: HashMap::new(),
+            functions_by_oid: HashMap::new(),
+            functions_by_name: HashMap::new(),
+            constraints_by_name: HashMap::new(),
+        };
+
+        for table in database.tables() {
+            let metadata = database
+                .table_metadata(table)
+                .expect("Table must exist in database");
+            let name = table.qualified_name();
+            index.tables_by_oid.insert(metadata.rel_oid(), table);
+
+            let columns = index.columns_by_name.entry(name.clone()).or_default();
+            for column in metadata.columns() {
+                columns.insert(column.column_name.as_str(), column);
+                if let Ok(attnum) = i16::try_from(column.ordinal_position) {
+                    index
+                        .columns_by_oid
+                        .insert((metadata.rel_oid(), attnum), column);
+                }
+                let pg_type = database
+                    .column_metadata(column)
+                    .expect("Column must exist in database")
+                    .pg_type();
+                index.types_by_oid.insert(pg_type.type_oid(), pg_type);
+                if let (Some(schema), Some(udt_name)) = (&column.udt_schema, &column.udt_name) {
+                    index
+                        .types_by_name
+                        .insert(QualifiedName::new(schema, udt_name), pg_type.type_oid());
+                }
+            }
+
+            let constraints = index.constraints_by_name.entry(name.clone()).or_default();
+            for check_constraint in metadata.check_constraints() {
+                constraints.insert(
+                    check_constraint.constraint_name.as_str(),
+                    ConstraintRef::Check(check_constraint),
+                );
+            }
+            for foreign_key in metadata.foreign_keys() {
+                constraints.insert(
+                    foreign_key.constraint_name.as_str(),
+                    ConstraintRef::ForeignKey(foreign_key),
+                );
+            }
+            index.tables_by_name.insert(name, table);
+        }
+
+        for function in database.functions() {
+            let metadata = database
+                .function_metadata(function)
+                .expect("Function must exist in database");
+            index.functions_by_oid.insert(function.proc_oid(), function);
+            index
+                .functions_by_name
+                .entry(metadata.qualified_name().clone())
+                .or_default()
+                .push(function);
+            for pg_type in metadata
+                .argument_types()
+                .iter()
+                .chain(metadata.return_type())
+            {
+                index.types_by_oid.insert(pg_type.type_oid(), pg_type);
+            }
+        }
+
+        index
+    }
+
+    #[must_use]
+    /// Returns the table with the provided OID, if any.
+    pub fn table(&self, oid: RelOid) -> Option<&'db Table> {
+        self.tables_by_oid.get(&oid).copied()
+    }
+
+    #[must_use]
+    /// Returns the table with the provided qualified name, if any.
+    pub fn table_by_name(&self, name: &QualifiedName) -> Option<&'db Table> {
+        self.tables_by_name.get(name).copied()
+    }
+
+    #[must_use]
+    /// Returns the column with the provided attribute number of the table
+    /// with the provided OID, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The OID of the table, as in `pg_attribute.attrelid`.
+    /// * `attnum` - The attribute number of the column, as in
+    ///   `pg_attribute.attnum`.
+    pub fn column(&self, table: RelOid, attnum: i16) -> Option<&'db Column> {
+        self.columns_by_oid.get(&(table, attnum)).copied()
+    }
+
+    #[must_use]
+    /// Returns the column with the provided name of the table with the
+    /// provided qualified name, if any.
+    pub fn column_by_name(&self, table: &QualifiedName, column: &str) -> Option<&'db Column> {
+        self.columns_by_name.get(table)?.get(column).copied()
+    }
+
+    #[must_use]
+    /// Returns the type with the provided OID, if it is the type of a column
+    /// or of an argument or the return value of a function.
+    pub fn pg_type(&self, oid: TypeOid) -> Option<&'db PgType> {
+        self.types_by_oid.get(&oid).copied()
+    }
+
+    #[must_use]
+    /// Returns the type with the provided qualified name, if it is the type
+    /// of a column.
+    pub fn pg_type_by_name(&self, name: &QualifiedName) -> Option<&'db PgType> {
+        self.pg_type(*self.types_by_name.get(name)?)
+    }
+
+    #[must_use]
+    /// Returns the function with the provided OID, if any.
+    pub fn function(&self, oid: ProcOid) -> Option<&'db PgProc> {
+        self.functions_by_oid.get(&oid).copied()
+    }
+
+    #[must_use]
+    /// Returns the overloads of the function with the provided qualified
+    /// name, which is empty if there is no such function.
+    pub fn functions_by_name(&self, name: &QualifiedName) -> &[&'db PgProc] {
+        self.functions_by_name.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    #[must_use]
+    /// Returns the check or foreign key constraint with the provided name of
+    /// the table with the provided qualified name, if any.
+    pub fn constraint(
+        &self,
+        table: &QualifiedName,
+        constraint: &str,
+    ) -> Option<ConstraintRef<'db>> {
+        self.constraints_by_name
+            .get(table)?
+            .get(constraint)
+            .copied()
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! This module provides [`PgProcMetadata`], which encapsulates type information
 //! for a `PostgreSQL` function or procedure, including:
+//! - Name qualified by its schema (from `pg_proc.pronamespace`)
 //! - Argument types (from `pg_proc.proargtypes`)
 //! - Return type (from `pg_proc.prorettype`)
 //!
@@ -10,12 +11,17 @@
 
 use diesel::PgConnection;
 
-use crate::models::{PgProc, PgType};
+use crate::{
+    identity::QualifiedName,
+    models::{PgProc, PgType},
+};
 
 #[derive(Debug, Clone)]
 /// Struct collecting metadata about a `PostgreSQL` function represented by a
 /// [`PgProc`] entry.
 pub struct PgProcMetadata {
+    /// The name of the function qualified by its schema.
+    qualified_name: QualifiedName,
     /// The argument types.
     argument_types: Vec<PgType>,
     /// The return type.
@@ -23,19 +29,26 @@ pub struct PgProcMetadata {
 }
 
 impl PgProcMetadata {
-    /// Creates a new `PgProcMetadata` instance from a `PgProc` and database
-    /// connection.
+    /// Creates a new `PgProcMetadata` instance from a `PgProc`, the name of
+    /// its schema and database connection.
     ///
     /// # Arguments
     ///
     /// * `pg_proc` - The `PostgreSQL` function to get metadata for.
+    /// * `schema` - The name of the schema of the function, as loaded with
+    ///   it by [`PgProc::load_all_in_schemas`] or [`PgProc::from_oid`].
     /// * `conn` - A mutable reference to a `PostgreSQL` connection.
     ///
     /// # Errors
     ///
     /// Returns an error if the type information cannot be retrieved from the
     /// database.
-    pub fn new(pg_proc: &PgProc, conn: &mut PgConnection) -> Result<Self, diesel::result::Error> {
+    pub fn new(
+        pg_proc: &PgProc,
+        schema: &str,
+        conn: &mut PgConnection,
+    ) -> Result<Self, diesel::result::Error> {
+        let qualified_name = QualifiedName::new(schema, &pg_proc.proname);
         let argument_types = pg_proc.argument_types(conn)?;
 
         let return_type = if pg_proc.prorettype == 0 {
@@ -45,11 +58,18 @@ impl PgProcMetadata {
         };
 
         Ok(Self {
+            qualified_name,
             argument_types,
             return_type,
         })
     }
 
+    /// Returns the name of the function qualified by its schema.
+    #[must_use]
+    pub fn qualified_name(&self) -> &QualifiedName {
+        &self.qualified_name
+    }
+
     /// Returns the argument types.
     #[must_use]
     pub fn argument_types(&self) -> &[PgType] {
@@ -117,10 +137,12 @@ mod tests {
         let ret_type = dummy_pg_type();
 
         let metadata = PgProcMetadata {
+            qualified_name: QualifiedName::new("public", "add"),
             argument_types: vec![arg_type],
             return_type: Some(ret_type),
         };
 
+        assert_eq!(metadata.qualified_name().to_string(), "public.add");
         assert_eq!(metadata.argument_types().len(), 1);
         assert_eq!(metadata.argument_types()[0].typname, "int4");
         assert_eq!(metadata.return_type().unwrap().typname, "int4");
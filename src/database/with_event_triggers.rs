@@ -0,0 +1,63 @@
+//! Submodule providing the [`PgDieselDatabaseWithEventTriggers`] struct,
+//! which holds a [`PgDieselDatabase`] together with its event triggers and
+//! the grants on its objects.
+
+use std::ops::Deref;
+
+use crate::{PgDieselDatabase, acl::ObjectGrant, model_metadata::EventTriggerMetadata};
+
+/// A [`PgDieselDatabase`] together with its event triggers and the grants
+/// on its objects.
+///
+/// Event triggers belong to the database rather than to a table, and the
+/// [`PgDieselDatabase`] only holds the grants on tables and columns, so they
+/// are held next to the [`PgDieselDatabase`], which dereferences from this
+/// struct. It is built from a
+/// [`PgDieselDatabaseBuilder`](crate::database::PgDieselDatabaseBuilder) like
+/// the database itself.
+pub struct PgDieselDatabaseWithEventTriggers {
+    /// The database.
+    pub(super) database: PgDieselDatabase,
+    /// The event triggers of the database, sorted by name.
+    pub(super) event_triggers: Vec<EventTriggerMetadata>,
+    /// The privileges granted on the tables, sequences, functions and types
+    /// of the schemas of the database and on the schemas themselves, sorted
+    /// by kind and object.
+    pub(super) object_grants: Vec<ObjectGrant>,
+}
+
+impl PgDieselDatabaseWithEventTriggers {
+    /// Returns the database.
+    #[must_use]
+    pub fn database(&self) -> &PgDieselDatabase {
+        &self.database
+    }
+
+    /// Returns the event triggers of the database, sorted by name.
+    #[must_use]
+    pub fn event_triggers(&self) -> &[EventTriggerMetadata] {
+        &self.event_triggers
+    }
+
+    /// Returns the privileges granted on the tables, sequences, functions
+    /// and types of the schemas of the database and on the schemas
+    /// themselves, sorted by kind and object.
+    #[must_use]
+    pub fn object_grants(&self) -> &[ObjectGrant] {
+        &self.object_grants
+    }
+
+    /// Returns the database, dropping the event triggers and object grants.
+    #[must_use]
+    pub fn into_database(self) -> PgDieselDatabase {
+        self.database
+    }
+}
+
+impl Deref for PgDieselDatabaseWithEventTriggers {
+    type Target = PgDieselDatabase;
+
+    fn deref(&self) -> &Self::Target {
+        &self.database
+    }
+}
@@ -0,0 +1,913 @@
+//! Submodule rendering entity-relationship diagrams of a
+//! [`PgDieselDatabase`].
+//!
+//! An [`ErDiagram`] holds the tables of the database as [`Entity`]s, with the
+//! type of each column from [`ColumnMetadata::pg_type`](crate::model_metadata::ColumnMetadata::pg_type)
+//! and its primary key, foreign key and unique markers, and the foreign keys
+//! as [`Relationship`]s. The cardinality of a relationship is inferred from
+//! its host columns:
+//!
+//! - a referencing row has a parent if all the host columns are `NOT NULL`,
+//!   and possibly none otherwise;
+//! - a parent row has at most one referencing row if the host columns are
+//!   covered by a unique index, and any number otherwise.
+//!
+//! The diagram may be restricted to the neighbourhood of a table with
+//! [`ErDiagram::subgraph`], and is rendered as a Mermaid `erDiagram`, a
+//! Graphviz DOT graph or a `PlantUML` diagram, optionally grouping the tables
+//! by schema.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt::{Display, Write},
+    str::FromStr,
+};
+
+use sql_traits::traits::DatabaseLike;
+
+use crate::{
+    PgDieselDatabase,
+    models::{Column, PgType, Table},
+    quoting::escape_html,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+/// Errors that can occur when parsing an [`ErdFormat`].
+pub enum ErdError {
+    #[error("Unknown diagram format `{0}`, expected `mermaid`, `dot` or `plantuml`")]
+    /// The format is not one of `mermaid`, `dot` or `plantuml`.
+    UnknownFormat(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The languages an [`ErDiagram`] is rendered in.
+pub enum ErdFormat {
+    /// A Mermaid `erDiagram`.
+    Mermaid,
+    /// A Graphviz DOT graph, with crow's foot arrows.
+    Dot,
+    /// A `PlantUML` diagram, in information engineering notation.
+    PlantUml,
+}
+
+impl Display for ErdFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Mermaid => "mermaid",
+            Self::Dot => "dot",
+            Self::PlantUml => "plantuml",
+        })
+    }
+}
+
+impl FromStr for ErdFormat {
+    type Err = ErdError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.trim().to_ascii_lowercase().as_str() {
+            "mermaid" => Ok(Self::Mermaid),
+            "dot" | "graphviz" => Ok(Self::Dot),
+            "plantuml" | "puml" => Ok(Self::PlantUml),
+            _ => Err(ErdError::UnknownFormat(format.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// How many rows of one side of a [`Relationship`] match a row of the other.
+pub enum Cardinality {
+    /// At most one row.
+    ZeroOrOne,
+    /// Exactly one row.
+    ExactlyOne,
+    /// Any number of rows.
+    ZeroOrMany,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A column of an [`Entity`].
+pub struct Attribute {
+    /// The name of the column.
+    name: String,
+    /// The name of the type of the column, as in `int4` or `text[]`.
+    type_name: String,
+    /// Whether the column is part of the primary key.
+    primary_key: bool,
+    /// Whether the column is part of a foreign key.
+    foreign_key: bool,
+    /// Whether the column alone is the key of a unique index other than the
+    /// primary key.
+    unique: bool,
+    /// Whether the column is nullable.
+    nullable: bool,
+}
+
+impl Attribute {
+    /// Returns the name of the column.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the name of the type of the column, as in `int4` or `text[]`.
+    #[must_use]
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// Returns whether the column is part of the primary key.
+    #[must_use]
+    pub fn is_primary_key(&self) -> bool {
+        self.primary_key
+    }
+
+    /// Returns whether the column is part of a foreign key.
+    #[must_use]
+    pub fn is_foreign_key(&self) -> bool {
+        self.foreign_key
+    }
+
+    /// Returns whether the column alone is the key of a unique index other
+    /// than the primary key.
+    #[must_use]
+    pub fn is_unique(&self) -> bool {
+        self.unique
+    }
+
+    /// Returns whether the column is nullable.
+    #[must_use]
+    pub fn is_nullable(&self) -> bool {
+        self.nullable
+    }
+
+    /// Returns the markers of the column, among `PK`, `FK` and `UK`.
+    fn markers(&self) -> Vec<&'static str> {
+        [
+            (self.primary_key, "PK"),
+            (self.foreign_key, "FK"),
+            (self.unique, "UK"),
+        ]
+        .into_iter()
+        .filter_map(|(set, marker)| set.then_some(marker))
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A table of an [`ErDiagram`].
+pub struct Entity {
+    /// The schema of the table.
+    schema: String,
+    /// The name of the table.
+    name: String,
+    /// The columns of the table, in order.
+    attributes: Vec<Attribute>,
+}
+
+impl Entity {
+    /// Returns the schema of the table.
+    #[must_use]
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// Returns the name of the table.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the columns of the table, in order.
+    #[must_use]
+    pub fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
+    /// Returns the qualified name of the table, as in `public.users`.
+    #[must_use]
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.schema, self.name)
+    }
+
+    /// Returns an identifier of the table made of ASCII letters, digits and
+    /// underscores, for the languages which require one.
+    fn identifier(&self) -> String {
+        identifier(&self.schema, &self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A foreign key of an [`ErDiagram`], from the host table to the referenced
+/// table.
+pub struct Relationship {
+    /// The name of the foreign key constraint.
+    name: String,
+    /// The schema and name of the host table.
+    host: (String, String),
+    /// The host columns.
+    host_columns: Vec<String>,
+    /// The schema and name of the referenced table.
+    referenced: (String, String),
+    /// The referenced columns.
+    referenced_columns: Vec<String>,
+    /// Whether any of the host columns is nullable.
+    optional: bool,
+    /// Whether the host columns are covered by a unique index.
+    unique: bool,
+}
+
+impl Relationship {
+    /// Returns the name of the foreign key constraint.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the schema and name of the host table.
+    #[must_use]
+    pub fn host(&self) -> (&str, &str) {
+        (&self.host.0, &self.host.1)
+    }
+
+    /// Returns the host columns.
+    #[must_use]
+    pub fn host_columns(&self) -> &[String] {
+        &self.host_columns
+    }
+
+    /// Returns the schema and name of the referenced table.
+    #[must_use]
+    pub fn referenced(&self) -> (&str, &str) {
+        (&self.referenced.0, &self.referenced.1)
+    }
+
+    /// Returns the referenced columns.
+    #[must_use]
+    pub fn referenced_columns(&self) -> &[String] {
+        &self.referenced_columns
+    }
+
+    #[must_use]
+    /// Returns how many referenced rows match a row of the host table.
+    pub fn referenced_cardinality(&self) -> Cardinality {
+        if self.optional {
+            Cardinality::ZeroOrOne
+        } else {
+            Cardinality::ExactlyOne
+        }
+    }
+
+    #[must_use]
+    /// Returns how many rows of the host table match a referenced row.
+    pub fn host_cardinality(&self) -> Cardinality {
+        if self.unique {
+            Cardinality::ZeroOrOne
+        } else {
+            Cardinality::ZeroOrMany
+        }
+    }
+}
+
+/// Returns an identifier made of ASCII letters, digits and underscores for
+/// the table, distinct for distinct tables.
+///
+/// The bytes of the schema and table names other than ASCII letters and
+/// digits are escaped as `_` followed by their two hexadecimal digits, so
+/// that the names never contain `__`, which separates them.
+fn identifier(schema: &str, name: &str) -> String {
+    let escape = |name: &str| {
+        let mut escaped = String::with_capacity(name.len());
+        for byte in name.bytes() {
+            if byte.is_ascii_alphanumeric() {
+                escaped.push(char::from(byte));
+            } else {
+                let _ = write!(escaped, "_{byte:02X}");
+            }
+        }
+        escaped
+    };
+    format!("{}__{}", escape(schema), escape(name))
+}
+
+/// Returns the name of the type, with the element type of arrays followed by
+/// `[]`.
+pub(crate) fn type_name(pg_type: &PgType) -> String {
+    match pg_type.typname.strip_prefix('_') {
+        Some(element) if pg_type.typcategory == "A" => format!("{element}[]"),
+        _ => pg_type.typname.clone(),
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An entity-relationship diagram of the tables of a database.
+pub struct ErDiagram {
+    /// The tables of the diagram.
+    entities: Vec<Entity>,
+    /// The foreign keys between the tables of the diagram.
+    relationships: Vec<Relationship>,
+    /// Whether the tables are grouped by schema.
+    cluster_by_schema: bool,
+}
+
+impl ErDiagram {
+    #[must_use]
+    /// Creates a new `ErDiagram` of the tables of the database, excluding
+    /// views.
+    pub fn new(database: &PgDieselDatabase) -> Self {
+        let tables: Vec<&Table> = database
+            .tables()
+            .filter(|table| table.table_type != "VIEW")
+            .collect();
+        let entities = tables
+            .iter()
+            .map(|table| Self::entity(database, table))
+            .collect();
+        let relationships = tables
+            .iter()
+            .flat_map(|table| Self::relationships(database, table))
+            .collect();
+        Self {
+            entities,
+            relationships,
+            cluster_by_schema: false,
+        }
+    }
+
+    /// Returns the entity of the table.
+    fn entity(database: &PgDieselDatabase, table: &Table) -> Entity {
+        let metadata = database
+            .table_metadata(table)
+            .expect("Table must exist in database");
+        let primary_key: HashSet<&Column> = metadata.primary_key_columns().collect();
+        let foreign_key: HashSet<&Column> = metadata
+            .foreign_keys()
+            .flat_map(|foreign_key| {
+                database
+                    .foreign_key_metadata(foreign_key)
+                    .expect("Foreign key must exist in database")
+                    .host_columns()
+            })
+            .collect();
+        // Only the columns which are a unique key on their own are marked, as
+        // a column of a composite unique key may hold duplicates.
+        let unique: HashSet<i16> = metadata
+            .unique_indices()
+            .filter(|index| !index.indisprimary)
+            .filter_map(|index| {
+                let keys = usize::try_from(index.indnkeyatts).ok()?;
+                match index.indkey.get(..keys)? {
+                    [attnum] if *attnum > 0 => Some(*attnum),
+                    _ => None,
+                }
+            })
+            .collect();
+        Entity {
+            schema: table.table_schema.clone(),
+            name: table.table_name.clone(),
+            attributes: metadata
+                .columns()
+                .map(|column| Attribute {
+                    name: column.column_name.clone(),
+                    type_name: type_name(
+                        database
+                            .column_metadata(column)
+                            .expect("Column must exist in database")
+                            .pg_type(),
+                    ),
+                    primary_key: primary_key.contains(column),
+                    foreign_key: foreign_key.contains(column),
+                    unique: i16::try_from(column.ordinal_position)
+                        .is_ok_and(|attnum| unique.contains(&attnum)),
+                    nullable: column.__is_nullable == "YES",
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the relationships of the foreign keys of the table.
+    fn relationships(database: &PgDieselDatabase, table: &Table) -> Vec<Relationship> {
+        let metadata = database
+            .table_metadata(table)
+            .expect("Table must exist in database");
+        let primary_key: Vec<i16> = metadata
+            .primary_key_columns()
+            .filter_map(|column| i16::try_from(column.ordinal_position).ok())
+            .collect();
+        let unique_keys: Vec<Vec<i16>> = metadata
+            .unique_indices()
+            .filter(|index| index.indkey.iter().all(|attnum| *attnum > 0))
+            .map(|index| index.indkey.clone())
+            .chain((!primary_key.is_empty()).then_some(primary_key))
+            .collect();
+        metadata
+            .foreign_keys()
+            .map(|foreign_key| {
+                let foreign_key_metadata = database
+                    .foreign_key_metadata(foreign_key)
+                    .expect("Foreign key must exist in database");
+                let host_columns = foreign_key_metadata.host_columns();
+                let referenced_table = foreign_key_metadata.referenced_table();
+                let host_attnums: Vec<i16> = host_columns
+                    .iter()
+                    .filter_map(|column| i16::try_from(column.ordinal_position).ok())
+                    .collect();
+                Relationship {
+                    name: foreign_key.constraint_name.clone(),
+                    host: (table.table_schema.clone(), table.table_name.clone()),
+                    host_columns: host_columns
+                        .iter()
+                        .map(|column| column.column_name.clone())
+                        .collect(),
+                    referenced: (
+                        referenced_table.table_schema.clone(),
+                        referenced_table.table_name.clone(),
+                    ),
+                    referenced_columns: foreign_key_metadata
+                        .referenced_columns()
+                        .iter()
+                        .map(|column| column.column_name.clone())
+                        .collect(),
+                    optional: host_columns
+                        .iter()
+                        .any(|column| column.__is_nullable == "YES"),
+                    unique: unique_keys
+                        .iter()
+                        .any(|key| key.iter().all(|attnum| host_attnums.contains(attnum))),
+                }
+            })
+            .collect()
+    }
+
+    #[must_use]
+    /// Sets whether the tables are grouped by schema.
+    pub fn cluster_by_schema(mut self, cluster_by_schema: bool) -> Self {
+        self.cluster_by_schema = cluster_by_schema;
+        self
+    }
+
+    /// Returns the tables of the diagram.
+    #[must_use]
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Returns the foreign keys between the tables of the diagram.
+    #[must_use]
+    pub fn relationships(&self) -> &[Relationship] {
+        &self.relationships
+    }
+
+    #[must_use]
+    /// Returns the diagram restricted to the table and the tables within the
+    /// provided number of foreign keys from it, in either direction. The
+    /// diagram is empty if the table is not part of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the table.
+    /// * `table` - The name of the table.
+    /// * `hops` - The number of foreign keys to follow from the table.
+    pub fn subgraph(&self, schema: &str, table: &str, hops: usize) -> Self {
+        let mut reached: HashSet<(&str, &str)> = HashSet::new();
+        let mut queue = VecDeque::new();
+        if self
+            .entities
+            .iter()
+            .any(|entity| entity.schema == schema && entity.name == table)
+        {
+            reached.insert((schema, table));
+            queue.push_back(((schema, table), 0));
+        }
+        while let Some((current, distance)) = queue.pop_front() {
+            if distance == hops {
+                continue;
+            }
+            for relationship in &self.relationships {
+                let neighbour = if relationship.host() == current {
+                    relationship.referenced()
+                } else if relationship.referenced() == current {
+                    relationship.host()
+                } else {
+                    continue;
+                };
+                if reached.insert(neighbour) {
+                    queue.push_back((neighbour, distance + 1));
+                }
+            }
+        }
+        Self {
+            entities: self
+                .entities
+                .iter()
+                .filter(|entity| reached.contains(&(entity.schema(), entity.name())))
+                .cloned()
+                .collect(),
+            relationships: self
+                .relationships
+                .iter()
+                .filter(|relationship| {
+                    reached.contains(&relationship.host())
+                        && reached.contains(&relationship.referenced())
+                })
+                .cloned()
+                .collect(),
+            cluster_by_schema: self.cluster_by_schema,
+        }
+    }
+
+    /// Returns the schemas of the tables, in order of first appearance.
+    fn schemas(&self) -> Vec<&str> {
+        let mut schemas: Vec<&str> = Vec::new();
+        for entity in &self.entities {
+            if !schemas.contains(&entity.schema.as_str()) {
+                schemas.push(&entity.schema);
+            }
+        }
+        schemas
+    }
+
+    /// Returns the entities grouped by schema when clustering, or in a single
+    /// group without schema otherwise.
+    fn groups(&self) -> Vec<(Option<&str>, Vec<&Entity>)> {
+        if !self.cluster_by_schema {
+            return vec![(None, self.entities.iter().collect())];
+        }
+        self.schemas()
+            .into_iter()
+            .map(|schema| {
+                (
+                    Some(schema),
+                    self.entities
+                        .iter()
+                        .filter(|entity| entity.schema == schema)
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[must_use]
+    /// Renders the diagram in the provided language.
+    pub fn render(&self, format: ErdFormat) -> String {
+        match format {
+            ErdFormat::Mermaid => self.to_mermaid(),
+            ErdFormat::Dot => self.to_dot(),
+            ErdFormat::PlantUml => self.to_plantuml(),
+        }
+    }
+
+    #[must_use]
+    /// Renders the diagram as a Mermaid `erDiagram`. Mermaid has no groups:
+    /// when clustering, the tables are ordered by schema.
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("erDiagram\n");
+        for (_, entities) in self.groups() {
+            for entity in entities {
+                let _ = writeln!(mermaid, "    \"{}\" {{", entity.qualified_name());
+                for attribute in &entity.attributes {
+                    let type_name: String = attribute
+                        .type_name
+                        .chars()
+                        .map(|c| {
+                            if c.is_ascii_alphanumeric() || matches!(c, '_' | '[' | ']') {
+                                c
+                            } else {
+                                '_'
+                            }
+                        })
+                        .collect();
+                    let _ = write!(mermaid, "        {type_name} {}", attribute.name);
+                    let markers = attribute.markers();
+                    if !markers.is_empty() {
+                        let _ = write!(mermaid, " {}", markers.join(", "));
+                    }
+                    mermaid.push('\n');
+                }
+                mermaid.push_str("    }\n");
+            }
+        }
+        for relationship in &self.relationships {
+            let referenced = match relationship.referenced_cardinality() {
+                Cardinality::ExactlyOne => "||",
+                Cardinality::ZeroOrOne | Cardinality::ZeroOrMany => "|o",
+            };
+            let host = match relationship.host_cardinality() {
+                Cardinality::ZeroOrOne | Cardinality::ExactlyOne => "o|",
+                Cardinality::ZeroOrMany => "o{",
+            };
+            let _ = writeln!(
+                mermaid,
+                "    \"{}.{}\" {referenced}--{host} \"{}.{}\" : \"{}\"",
+                relationship.referenced.0,
+                relationship.referenced.1,
+                relationship.host.0,
+                relationship.host.1,
+                relationship.name.replace('"', "'"),
+            );
+        }
+        mermaid
+    }
+
+    #[must_use]
+    /// Renders the diagram as a Graphviz DOT graph, drawing each table as an
+    /// HTML-like table and each foreign key as an edge from the host columns
+    /// to the referenced columns, with crow's foot arrows. When clustering,
+    /// each schema is drawn as a cluster.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from(
+            "digraph erd {\n    rankdir=LR;\n    node [shape=plaintext];\n    edge [dir=both];\n",
+        );
+        for (schema, entities) in self.groups() {
+            let indent = if let Some(schema) = schema {
+                let _ = writeln!(
+                    dot,
+                    "    subgraph \"cluster_{}\" {{\n        label=\"{}\";",
+                    schema.replace('"', "\\\""),
+                    schema.replace('"', "\\\"")
+                );
+                "        "
+            } else {
+                "    "
+            };
+            for entity in entities {
+                let _ = write!(
+                    dot,
+                    "{indent}{} [label=<<TABLE BORDER=\"0\" CELLBORDER=\"1\" CELLSPACING=\"0\">\
+                     <TR><TD COLSPAN=\"3\" BGCOLOR=\"lightgrey\"><B>{}</B></TD></TR>",
+                    entity.identifier(),
+                    escape_html(&entity.qualified_name())
+                );
+                for attribute in &entity.attributes {
+                    let _ = write!(
+                        dot,
+                        "<TR><TD PORT=\"{}\" ALIGN=\"LEFT\">{}</TD><TD ALIGN=\"LEFT\">{}</TD><TD>{}</TD></TR>",
+                        escape_html(&attribute.name),
+                        escape_html(&attribute.name),
+                        escape_html(&attribute.type_name),
+                        attribute.markers().join(" ")
+                    );
+                }
+                dot.push_str("</TABLE>>];\n");
+            }
+            if schema.is_some() {
+                dot.push_str("    }\n");
+            }
+        }
+        for relationship in &self.relationships {
+            let arrowtail = match relationship.host_cardinality() {
+                Cardinality::ZeroOrOne | Cardinality::ExactlyOne => "teeodot",
+                Cardinality::ZeroOrMany => "crowodot",
+            };
+            let arrowhead = match relationship.referenced_cardinality() {
+                Cardinality::ExactlyOne => "teetee",
+                Cardinality::ZeroOrOne | Cardinality::ZeroOrMany => "teeodot",
+            };
+            let port = |columns: &[String]| {
+                columns
+                    .first()
+                    .map(|column| format!(":\"{}\"", escape_html(column)))
+                    .unwrap_or_default()
+            };
+            let _ = writeln!(
+                dot,
+                "    {}{} -> {}{} [arrowtail={arrowtail}, arrowhead={arrowhead}, label=\"{}\"];",
+                identifier(&relationship.host.0, &relationship.host.1),
+                port(&relationship.host_columns),
+                identifier(&relationship.referenced.0, &relationship.referenced.1),
+                port(&relationship.referenced_columns),
+                relationship.name.replace('"', "\\\""),
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    #[must_use]
+    /// Renders the diagram as a `PlantUML` diagram in information engineering
+    /// notation, marking `NOT NULL` columns with `*`. When clustering, each
+    /// schema is drawn as a package.
+    pub fn to_plantuml(&self) -> String {
+        let mut plantuml = String::from("@startuml\nhide circle\nskinparam linetype ortho\n");
+        for (schema, entities) in self.groups() {
+            if let Some(schema) = schema {
+                let _ = writeln!(plantuml, "package \"{schema}\" {{");
+            }
+            for entity in entities {
+                let _ = writeln!(
+                    plantuml,
+                    "entity \"{}\" as {} {{",
+                    entity.qualified_name(),
+                    entity.identifier()
+                );
+                let (keys, others): (Vec<&Attribute>, Vec<&Attribute>) = entity
+                    .attributes
+                    .iter()
+                    .partition(|attribute| attribute.primary_key);
+                for (position, attributes) in [keys, others].into_iter().enumerate() {
+                    if position == 1 {
+                        plantuml.push_str("  --\n");
+                    }
+                    for attribute in attributes {
+                        let mandatory = if attribute.nullable { "" } else { "* " };
+                        let _ = write!(
+                            plantuml,
+                            "  {mandatory}{} : {}",
+                            attribute.name, attribute.type_name
+                        );
+                        for marker in attribute.markers() {
+                            let _ = write!(plantuml, " <<{marker}>>");
+                        }
+                        plantuml.push('\n');
+                    }
+                }
+                plantuml.push_str("}\n");
+            }
+            if schema.is_some() {
+                plantuml.push_str("}\n");
+            }
+        }
+        for relationship in &self.relationships {
+            let referenced = match relationship.referenced_cardinality() {
+                Cardinality::ExactlyOne => "||",
+                Cardinality::ZeroOrOne | Cardinality::ZeroOrMany => "|o",
+            };
+            let host = match relationship.host_cardinality() {
+                Cardinality::ZeroOrOne | Cardinality::ExactlyOne => "o|",
+                Cardinality::ZeroOrMany => "o{",
+            };
+            let _ = writeln!(
+                plantuml,
+                "{} {referenced}--{host} {} : {}",
+                identifier(&relationship.referenced.0, &relationship.referenced.1),
+                identifier(&relationship.host.0, &relationship.host.1),
+                relationship.name,
+            );
+        }
+        plantuml.push_str("@enduml\n");
+        plantuml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(name: &str, type_name: &str, primary_key: bool, foreign_key: bool) -> Attribute {
+        Attribute {
+            name: name.to_owned(),
+            type_name: type_name.to_owned(),
+            primary_key,
+            foreign_key,
+            unique: false,
+            nullable: !primary_key,
+        }
+    }
+
+    fn entity(schema: &str, name: &str, attributes: Vec<Attribute>) -> Entity {
+        Entity {
+            schema: schema.to_owned(),
+            name: name.to_owned(),
+            attributes,
+        }
+    }
+
+    fn relationship(name: &str, host: &str, referenced: &str, unique: bool) -> Relationship {
+        let table = |qualified: &str| {
+            let (schema, table) = qualified.split_once('.').unwrap_or_default();
+            (schema.to_owned(), table.to_owned())
+        };
+        Relationship {
+            name: name.to_owned(),
+            host: table(host),
+            host_columns: vec!["user_id".to_owned()],
+            referenced: table(referenced),
+            referenced_columns: vec!["id".to_owned()],
+            optional: false,
+            unique,
+        }
+    }
+
+    fn diagram() -> ErDiagram {
+        ErDiagram {
+            entities: vec![
+                entity(
+                    "public",
+                    "users",
+                    vec![
+                        attribute("id", "int4", true, false),
+                        attribute("tags", "text[]", false, false),
+                    ],
+                ),
+                entity(
+                    "public",
+                    "orders",
+                    vec![
+                        attribute("id", "int4", true, false),
+                        attribute("user_id", "int4", false, true),
+                    ],
+                ),
+                entity(
+                    "billing",
+                    "invoices",
+                    vec![attribute("order_id", "int4", true, true)],
+                ),
+                entity("public", "settings", vec![]),
+            ],
+            relationships: vec![
+                relationship(
+                    "orders_user_id_fkey",
+                    "public.orders",
+                    "public.users",
+                    false,
+                ),
+                relationship(
+                    "invoices_order_id_fkey",
+                    "billing.invoices",
+                    "public.orders",
+                    true,
+                ),
+            ],
+            cluster_by_schema: false,
+        }
+    }
+
+    #[test]
+    fn test_subgraph() {
+        let diagram = diagram();
+        let names = |diagram: &ErDiagram| {
+            diagram
+                .entities()
+                .iter()
+                .map(Entity::qualified_name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(&diagram.subgraph("public", "users", 1)),
+            ["public.users", "public.orders"]
+        );
+        let two_hops = diagram.subgraph("public", "users", 2);
+        assert_eq!(
+            names(&two_hops),
+            ["public.users", "public.orders", "billing.invoices"]
+        );
+        assert_eq!(two_hops.relationships().len(), 2);
+        assert_eq!(
+            diagram.subgraph("public", "users", 0).relationships().len(),
+            0
+        );
+        assert!(
+            diagram
+                .subgraph("public", "missing", 3)
+                .entities()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let diagram = diagram().subgraph("public", "orders", 1);
+        let mermaid = diagram.to_mermaid();
+        assert!(mermaid.starts_with("erDiagram\n    \"public.users\" {\n        int4 id PK\n"));
+        assert!(mermaid.contains("        text[] tags\n"));
+        assert!(
+            mermaid.contains(
+                "    \"public.users\" ||--o{ \"public.orders\" : \"orders_user_id_fkey\"\n"
+            )
+        );
+        assert!(mermaid.contains(
+            "    \"public.orders\" ||--o| \"billing.invoices\" : \"invoices_order_id_fkey\"\n"
+        ));
+
+        let dot = diagram.clone().cluster_by_schema(true).to_dot();
+        assert!(dot.contains("    subgraph \"cluster_billing\" {\n        label=\"billing\";\n"));
+        assert!(dot.contains(
+            "    public__orders:\"user_id\" -> public__users:\"id\" \
+             [arrowtail=crowodot, arrowhead=teetee, label=\"orders_user_id_fkey\"];\n"
+        ));
+        assert!(dot.contains("<TD>FK</TD>"));
+
+        let plantuml = diagram.cluster_by_schema(true).render(ErdFormat::PlantUml);
+        assert!(plantuml.contains(
+            "package \"public\" {\nentity \"public.users\" as public__users {\n  * id : int4 <<PK>>\n  --\n  tags : text[]\n}\n"
+        ));
+        assert!(plantuml.contains("public__users ||--o{ public__orders : orders_user_id_fkey\n"));
+        assert!(plantuml.ends_with("@enduml\n"));
+        assert_eq!("graphviz".parse::<ErdFormat>(), Ok(ErdFormat::Dot));
+    }
+
+    #[test]
+    fn test_identifier() {
+        assert_eq!(identifier("public", "users"), "public__users");
+        assert_eq!(identifier("public", "user_roles"), "public__user_5Froles");
+        assert_eq!(identifier("my-app", "Users"), "my_2Dapp__Users");
+        assert_ne!(identifier("a_b", "c"), identifier("a", "b_c"));
+        assert_ne!(identifier("a.b", "c"), identifier("a", "b.c"));
+        assert_ne!(identifier("a", "b-c"), identifier("a", "b_c"));
+    }
+}
@@ -0,0 +1,831 @@
+//! Submodule evaluating parsed check and policy expressions in memory.
+//!
+//! The [`Evaluator`] interprets the `Expr`s stored for check constraints and
+//! row-level security policies against a row, given as a map from column names
+//! to [`SqlValue`]s, and a [`SessionContext`] providing `current_user` and the
+//! values of `current_setting(...)`. This makes it possible to unit-test
+//! constraints and policies without a running server.
+//!
+//! The evaluation follows the three-valued logic of SQL and supports literals,
+//! casts, boolean logic, comparisons, arithmetic, `||`, `IS [NOT] NULL`,
+//! `IS [NOT] DISTINCT FROM`, `BETWEEN`, `IN`, `ANY`/`ALL` over arrays,
+//! `LIKE`/`ILIKE` and `CASE`. Function calls are resolved in a function table,
+//! which comes with common built-ins and can be extended with
+//! [`Evaluator::function`].
+
+use std::{cmp::Ordering, collections::HashMap, sync::Arc};
+
+use sqlparser::ast::{
+    BinaryOperator, CaseWhen, Expr, Function, FunctionArg, FunctionArgExpr, FunctionArguments,
+    Owner, UnaryOperator, Value,
+};
+
+use crate::model_metadata::PolicyMetadata;
+
+mod functions;
+mod value;
+
+use value::ArithmeticOperator;
+pub use value::SqlValue;
+
+/// A function callable from evaluated expressions, receiving the evaluated
+/// arguments and the session.
+pub type SqlFunction =
+    Arc<dyn Fn(&[SqlValue], &SessionContext) -> Result<SqlValue, EvaluationError> + Send + Sync>;
+
+#[derive(Debug, thiserror::Error)]
+/// Errors that can occur when evaluating an expression.
+pub enum EvaluationError {
+    #[error("Column `{0}` is not provided in the row")]
+    /// The expression refers to a column missing from the row.
+    UnknownColumn(String),
+    #[error("Function `{0}` is not registered in the evaluator")]
+    /// The expression calls a function missing from the function table.
+    UnknownFunction(String),
+    #[error("Setting `{0}` is not defined in the session")]
+    /// The expression reads a setting missing from the session.
+    UnknownSetting(String),
+    #[error("Invalid arguments for function `{function}`: {reason}")]
+    /// A function was called with invalid arguments.
+    InvalidArguments {
+        /// The name of the function.
+        function: String,
+        /// Why the arguments are invalid.
+        reason: String,
+    },
+    #[error("Operator `{operator}` cannot be applied to {left} and {right}")]
+    /// An operator was applied to values of incompatible types.
+    TypeMismatch {
+        /// The operator.
+        operator: String,
+        /// The type of the left operand.
+        left: &'static str,
+        /// The type of the right operand.
+        right: &'static str,
+    },
+    #[error("Cannot cast `{value}` to `{type_name}`")]
+    /// A value could not be converted to the requested type.
+    InvalidCast {
+        /// The value, in its text representation.
+        value: String,
+        /// The name of the requested type.
+        type_name: String,
+    },
+    #[error("Division by zero")]
+    /// A division or modulo by zero.
+    DivisionByZero,
+    #[error("Value out of range")]
+    /// An arithmetic operation overflowed.
+    Overflow,
+    #[error("Expression `{0}` is not supported")]
+    /// The expression uses a construct the evaluator does not support.
+    Unsupported(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The session an expression is evaluated in.
+pub struct SessionContext {
+    /// The role returned by `current_user` and `current_role`.
+    current_user: String,
+    /// The role returned by `session_user`, when it differs from the current
+    /// one.
+    session_user: Option<String>,
+    /// The settings returned by `current_setting`, by lowercase name.
+    settings: HashMap<String, String>,
+}
+
+impl SessionContext {
+    /// Creates a new `SessionContext` for the provided role.
+    #[must_use]
+    pub fn new<S: AsRef<str>>(current_user: S) -> Self {
+        Self {
+            current_user: current_user.as_ref().to_string(),
+            session_user: None,
+            settings: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    /// Sets the role returned by `session_user`, which otherwise is the
+    /// current role.
+    pub fn session_user<S: AsRef<str>>(mut self, session_user: S) -> Self {
+        self.session_user = Some(session_user.as_ref().to_string());
+        self
+    }
+
+    #[must_use]
+    /// Sets the value returned by `current_setting` for the provided setting.
+    pub fn setting<S: AsRef<str>, V: AsRef<str>>(mut self, name: S, value: V) -> Self {
+        self.settings
+            .insert(name.as_ref().to_lowercase(), value.as_ref().to_string());
+        self
+    }
+
+    /// Returns the role returned by `current_user`.
+    #[must_use]
+    pub fn current_user(&self) -> &str {
+        &self.current_user
+    }
+
+    /// Returns the value of the provided setting, if defined.
+    #[must_use]
+    pub fn current_setting(&self, name: &str) -> Option<&str> {
+        self.settings.get(&name.to_lowercase()).map(String::as_str)
+    }
+
+    /// Returns the value of the special identifiers referring to roles, such
+    /// as `current_user`, if the identifier is one.
+    fn role_identifier(&self, identifier: &str) -> Option<&str> {
+        match identifier.to_lowercase().as_str() {
+            "current_user" | "current_role" | "user" => Some(&self.current_user),
+            "session_user" => Some(self.session_user.as_deref().unwrap_or(&self.current_user)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+/// Interpreter of parsed SQL expressions over a row.
+pub struct Evaluator {
+    /// The session the expressions are evaluated in.
+    session: SessionContext,
+    /// The functions callable from the expressions, by lowercase name.
+    functions: HashMap<String, SqlFunction>,
+}
+
+impl Evaluator {
+    /// Creates a new `Evaluator` for the provided session, with the built-in
+    /// functions `lower`, `upper`, `length`, `char_length`, `octet_length`,
+    /// `btrim`, `ltrim`, `rtrim`, `concat`, `abs`, `coalesce`, `nullif`,
+    /// `greatest`, `least` and `current_setting`.
+    #[must_use]
+    pub fn new(session: SessionContext) -> Self {
+        Self {
+            session,
+            functions: functions::builtins()
+                .map(|(name, function)| (name.to_owned(), function))
+                .collect(),
+        }
+    }
+
+    #[must_use]
+    /// Registers a function, replacing any function with the same name.
+    ///
+    /// Calls to the function, with or without the `pg_catalog` schema, are
+    /// resolved case-insensitively.
+    pub fn function<F>(mut self, name: &str, function: F) -> Self
+    where
+        F: Fn(&[SqlValue], &SessionContext) -> Result<SqlValue, EvaluationError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.functions
+            .insert(name.to_lowercase(), Arc::new(function));
+        self
+    }
+
+    /// Returns the session the expressions are evaluated in.
+    #[must_use]
+    pub fn session(&self) -> &SessionContext {
+        &self.session
+    }
+
+    /// Evaluates the expression over the provided row.
+    ///
+    /// # Arguments
+    ///
+    /// * `expr` - The expression to evaluate.
+    /// * `row` - The values of the columns, by column name.
+    ///
+    /// # Errors
+    ///
+    /// * If the expression refers to a missing column, function or setting.
+    /// * If an operation fails, as with a division by zero.
+    /// * If the expression uses an unsupported construct.
+    pub fn evaluate(
+        &self,
+        expr: &Expr,
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<SqlValue, EvaluationError> {
+        match expr {
+            Expr::Nested(inner) => self.evaluate(inner, row),
+            Expr::Identifier(ident) => self.identifier(&ident.value, row),
+            Expr::CompoundIdentifier(idents) => self.identifier(
+                idents
+                    .last()
+                    .map(|ident| ident.value.as_str())
+                    .unwrap_or_default(),
+                row,
+            ),
+            Expr::Value(value) => literal(&value.value),
+            Expr::Cast {
+                expr: inner,
+                data_type,
+                ..
+            } => self.evaluate(inner, row)?.cast(&data_type.to_string()),
+            Expr::UnaryOp { op, expr: inner } => unary(expr, op, self.evaluate(inner, row)?),
+            Expr::BinaryOp { left, op, right } => self.binary(expr, left, op, right, row),
+            Expr::IsNull(inner) => Ok(SqlValue::Boolean(self.evaluate(inner, row)?.is_null())),
+            Expr::IsNotNull(inner) => Ok(SqlValue::Boolean(!self.evaluate(inner, row)?.is_null())),
+            Expr::IsTrue(inner) => self.truth_test(inner, Some(true), false, row),
+            Expr::IsNotTrue(inner) => self.truth_test(inner, Some(true), true, row),
+            Expr::IsFalse(inner) => self.truth_test(inner, Some(false), false, row),
+            Expr::IsNotFalse(inner) => self.truth_test(inner, Some(false), true, row),
+            Expr::IsDistinctFrom(left, right) => {
+                Ok(SqlValue::Boolean(!self.not_distinct(left, right, row)?))
+            }
+            Expr::IsNotDistinctFrom(left, right) => {
+                Ok(SqlValue::Boolean(self.not_distinct(left, right, row)?))
+            }
+            Expr::Between {
+                expr: inner,
+                negated,
+                low,
+                high,
+            } => {
+                let value = self.evaluate(inner, row)?;
+                let above = compare(&self.evaluate(low, row)?, &BinaryOperator::LtEq, &value)?;
+                let below = compare(&value, &BinaryOperator::LtEq, &self.evaluate(high, row)?)?;
+                Ok(negate_if(and(above, below), *negated))
+            }
+            Expr::InList {
+                expr: inner,
+                list,
+                negated,
+            } => Ok(negate_if(
+                quantified(
+                    &self.evaluate(inner, row)?,
+                    &BinaryOperator::Eq,
+                    &self.evaluate_all(list, row)?,
+                    true,
+                )?,
+                *negated,
+            )),
+            Expr::AnyOp {
+                left,
+                compare_op,
+                right,
+                ..
+            } => self.quantified_expression(left, compare_op, right, true, row),
+            Expr::AllOp {
+                left,
+                compare_op,
+                right,
+            } => self.quantified_expression(left, compare_op, right, false, row),
+            Expr::Array(array) => Ok(SqlValue::Array(self.evaluate_all(&array.elem, row)?)),
+            Expr::Like {
+                negated,
+                any: false,
+                expr: inner,
+                pattern,
+                escape_char: None,
+            } => self.like(inner, pattern, *negated, false, row),
+            Expr::ILike {
+                negated,
+                any: false,
+                expr: inner,
+                pattern,
+                escape_char: None,
+            } => self.like(inner, pattern, *negated, true, row),
+            Expr::Case {
+                operand,
+                conditions,
+                else_result,
+                ..
+            } => self.case(operand.as_deref(), conditions, else_result.as_deref(), row),
+            Expr::Function(function) => self.call(expr, function, row),
+            _ => Err(EvaluationError::Unsupported(expr.to_string())),
+        }
+    }
+
+    /// Returns whether the row satisfies the check constraint expression:
+    /// as in `PostgreSQL`, a `NULL` result satisfies the constraint.
+    ///
+    /// # Errors
+    ///
+    /// * If the evaluation fails, or its result is not a boolean.
+    pub fn satisfies_check(
+        &self,
+        expr: &Expr,
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<bool, EvaluationError> {
+        Ok(self.evaluate(expr, row)?.to_bool()? != Some(false))
+    }
+
+    /// Returns whether the row passes the policy expression: as in
+    /// `PostgreSQL`, a `NULL` result rejects the row.
+    ///
+    /// # Errors
+    ///
+    /// * If the evaluation fails, or its result is not a boolean.
+    pub fn satisfies_policy(
+        &self,
+        expr: &Expr,
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<bool, EvaluationError> {
+        Ok(self.evaluate(expr, row)?.to_bool()? == Some(true))
+    }
+
+    /// Returns whether the policy applies to the current user, which is the
+    /// case when it applies to `PUBLIC` or names the current user.
+    ///
+    /// Role memberships are not taken into account.
+    #[must_use]
+    pub fn policy_applies(&self, policy: &PolicyMetadata) -> bool {
+        let mut roles = policy.roles().peekable();
+        roles.peek().is_none()
+            || roles.any(|role| match role {
+                Owner::Ident(ident) => {
+                    ident.value == self.session.current_user
+                        || ident.value.eq_ignore_ascii_case("public")
+                }
+                Owner::CurrentUser | Owner::CurrentRole => true,
+                Owner::SessionUser => {
+                    self.session.role_identifier("session_user")
+                        == Some(self.session.current_user.as_str())
+                }
+            })
+    }
+
+    /// Returns whether the existing row is visible through the `USING`
+    /// expression of the policy, which admits every row when absent.
+    ///
+    /// # Errors
+    ///
+    /// * If the evaluation fails, or its result is not a boolean.
+    pub fn policy_admits_row(
+        &self,
+        policy: &PolicyMetadata,
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<bool, EvaluationError> {
+        policy
+            .using_expression()
+            .map_or(Ok(true), |expr| self.satisfies_policy(expr, row))
+    }
+
+    /// Returns whether the new row passes the `WITH CHECK` expression of the
+    /// policy, falling back to the `USING` expression when absent, as
+    /// `PostgreSQL` does.
+    ///
+    /// # Errors
+    ///
+    /// * If the evaluation fails, or its result is not a boolean.
+    pub fn policy_admits_write(
+        &self,
+        policy: &PolicyMetadata,
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<bool, EvaluationError> {
+        policy
+            .check_expression()
+            .or_else(|| policy.using_expression())
+            .map_or(Ok(true), |expr| self.satisfies_policy(expr, row))
+    }
+
+    /// Evaluates every expression of the list.
+    fn evaluate_all(
+        &self,
+        exprs: &[Expr],
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<Vec<SqlValue>, EvaluationError> {
+        exprs.iter().map(|expr| self.evaluate(expr, row)).collect()
+    }
+
+    /// Returns the value of a column, or of a special role identifier.
+    fn identifier(
+        &self,
+        name: &str,
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<SqlValue, EvaluationError> {
+        if let Some(value) = row.get(name) {
+            return Ok(value.clone());
+        }
+        self.session
+            .role_identifier(name)
+            .map(|role| SqlValue::Text(role.to_owned()))
+            .ok_or_else(|| EvaluationError::UnknownColumn(name.to_owned()))
+    }
+
+    /// Evaluates a function call, or one of the special role identifiers
+    /// parsed as a function without arguments such as `CURRENT_USER`.
+    fn call(
+        &self,
+        expr: &Expr,
+        function: &Function,
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<SqlValue, EvaluationError> {
+        let name = function.name.to_string().to_lowercase();
+        let name = name.trim_start_matches("pg_catalog.");
+        let FunctionArguments::List(list) = &function.args else {
+            return self
+                .session
+                .role_identifier(name)
+                .filter(|_| matches!(function.args, FunctionArguments::None))
+                .map(|role| SqlValue::Text(role.to_owned()))
+                .ok_or_else(|| EvaluationError::Unsupported(expr.to_string()));
+        };
+        let arguments = list
+            .args
+            .iter()
+            .map(|argument| match argument {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(argument)) => {
+                    self.evaluate(argument, row)
+                }
+                _ => Err(EvaluationError::Unsupported(expr.to_string())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let function = self
+            .functions
+            .get(name)
+            .ok_or_else(|| EvaluationError::UnknownFunction(name.to_owned()))?;
+        function(&arguments, &self.session)
+    }
+
+    /// Evaluates a binary operation.
+    fn binary(
+        &self,
+        expr: &Expr,
+        left: &Expr,
+        op: &BinaryOperator,
+        right: &Expr,
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<SqlValue, EvaluationError> {
+        let left = self.evaluate(left, row)?;
+        // Short-circuit boolean logic, so that guards such as
+        // `x <> 0 AND y / x > 1` do not fail.
+        match (op, left.to_bool()) {
+            (BinaryOperator::And, Ok(Some(false))) => return Ok(SqlValue::Boolean(false)),
+            (BinaryOperator::Or, Ok(Some(true))) => return Ok(SqlValue::Boolean(true)),
+            _ => {}
+        }
+        let right = self.evaluate(right, row)?;
+        let arithmetic = |operator| left.arithmetic(operator, &right);
+        match op {
+            BinaryOperator::And => Ok(and(left.to_bool()?, right.to_bool()?)),
+            BinaryOperator::Or => Ok(or(left.to_bool()?, right.to_bool()?)),
+            BinaryOperator::Eq
+            | BinaryOperator::NotEq
+            | BinaryOperator::Lt
+            | BinaryOperator::LtEq
+            | BinaryOperator::Gt
+            | BinaryOperator::GtEq => Ok(boolean(compare(&left, op, &right)?)),
+            BinaryOperator::Plus => arithmetic(ArithmeticOperator::Add),
+            BinaryOperator::Minus => arithmetic(ArithmeticOperator::Subtract),
+            BinaryOperator::Multiply => arithmetic(ArithmeticOperator::Multiply),
+            BinaryOperator::Divide => arithmetic(ArithmeticOperator::Divide),
+            BinaryOperator::Modulo => arithmetic(ArithmeticOperator::Modulo),
+            BinaryOperator::StringConcat => Ok(match (left.to_text(), right.to_text()) {
+                (Some(left), Some(right)) => SqlValue::Text(left + &right),
+                _ => SqlValue::Null,
+            }),
+            _ => Err(EvaluationError::Unsupported(expr.to_string())),
+        }
+    }
+
+    /// Evaluates a `IS [NOT] TRUE` or `IS [NOT] FALSE` test.
+    fn truth_test(
+        &self,
+        inner: &Expr,
+        expected: Option<bool>,
+        negated: bool,
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<SqlValue, EvaluationError> {
+        let matches = self.evaluate(inner, row)?.to_bool()? == expected;
+        Ok(SqlValue::Boolean(matches != negated))
+    }
+
+    /// Returns whether the two expressions are not distinct, with `NULL`
+    /// not distinct from itself.
+    fn not_distinct(
+        &self,
+        left: &Expr,
+        right: &Expr,
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<bool, EvaluationError> {
+        let left = self.evaluate(left, row)?;
+        let right = self.evaluate(right, row)?;
+        Ok(match (left.is_null(), right.is_null()) {
+            (true, true) => true,
+            (true, false) | (false, true) => false,
+            (false, false) => {
+                left.compare(&right, "IS NOT DISTINCT FROM")? == Some(Ordering::Equal)
+            }
+        })
+    }
+
+    /// Evaluates a comparison quantified with `ANY` or `ALL` over an array.
+    fn quantified_expression(
+        &self,
+        left: &Expr,
+        op: &BinaryOperator,
+        right: &Expr,
+        any: bool,
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<SqlValue, EvaluationError> {
+        let value = self.evaluate(left, row)?;
+        match self.evaluate(right, row)? {
+            SqlValue::Null => Ok(SqlValue::Null),
+            SqlValue::Array(items) => quantified(&value, op, &items, any),
+            other => Err(EvaluationError::TypeMismatch {
+                operator: op.to_string(),
+                left: value.type_name(),
+                right: other.type_name(),
+            }),
+        }
+    }
+
+    /// Evaluates a `LIKE` or `ILIKE` match.
+    fn like(
+        &self,
+        inner: &Expr,
+        pattern: &Expr,
+        negated: bool,
+        case_insensitive: bool,
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<SqlValue, EvaluationError> {
+        let (Some(text), Some(pattern)) = (
+            self.evaluate(inner, row)?.to_text(),
+            self.evaluate(pattern, row)?.to_text(),
+        ) else {
+            return Ok(SqlValue::Null);
+        };
+        let (text, pattern) = if case_insensitive {
+            (text.to_lowercase(), pattern.to_lowercase())
+        } else {
+            (text, pattern)
+        };
+        let text: Vec<char> = text.chars().collect();
+        let pattern: Vec<char> = pattern.chars().collect();
+        Ok(SqlValue::Boolean(like_matches(&text, &pattern) != negated))
+    }
+
+    /// Evaluates a `CASE` expression.
+    fn case(
+        &self,
+        operand: Option<&Expr>,
+        conditions: &[CaseWhen],
+        else_result: Option<&Expr>,
+        row: &HashMap<String, SqlValue>,
+    ) -> Result<SqlValue, EvaluationError> {
+        let operand = operand
+            .map(|operand| self.evaluate(operand, row))
+            .transpose()?;
+        for when in conditions {
+            let condition = self.evaluate(&when.condition, row)?;
+            let matched = match &operand {
+                Some(operand) => operand.compare(&condition, "CASE")? == Some(Ordering::Equal),
+                None => condition.to_bool()? == Some(true),
+            };
+            if matched {
+                return self.evaluate(&when.result, row);
+            }
+        }
+        else_result.map_or(Ok(SqlValue::Null), |result| self.evaluate(result, row))
+    }
+}
+
+/// Returns the value of a literal.
+fn literal(value: &Value) -> Result<SqlValue, EvaluationError> {
+    match value {
+        Value::Null => Ok(SqlValue::Null),
+        Value::Boolean(value) => Ok(SqlValue::Boolean(*value)),
+        Value::Number(number, _) => number
+            .parse::<i64>()
+            .map(SqlValue::Integer)
+            .or_else(|_| SqlValue::Text(number.clone()).cast("numeric")),
+        Value::SingleQuotedString(text)
+        | Value::EscapedStringLiteral(text)
+        | Value::NationalStringLiteral(text) => Ok(SqlValue::Text(text.clone())),
+        Value::DollarQuotedString(text) => Ok(SqlValue::Text(text.value.clone())),
+        _ => Err(EvaluationError::Unsupported(value.to_string())),
+    }
+}
+
+/// Applies a unary operator to the evaluated operand.
+fn unary(expr: &Expr, op: &UnaryOperator, operand: SqlValue) -> Result<SqlValue, EvaluationError> {
+    match op {
+        UnaryOperator::Not => Ok(operand
+            .to_bool()?
+            .map_or(SqlValue::Null, |value| SqlValue::Boolean(!value))),
+        UnaryOperator::Minus => operand.negate(),
+        UnaryOperator::Plus => Ok(operand),
+        _ => Err(EvaluationError::Unsupported(expr.to_string())),
+    }
+}
+
+/// Returns the result of a comparison, or `None` when either operand is
+/// `NULL`.
+fn compare(
+    left: &SqlValue,
+    op: &BinaryOperator,
+    right: &SqlValue,
+) -> Result<Option<bool>, EvaluationError> {
+    let Some(ordering) = left.compare(right, &op.to_string())? else {
+        return Ok(None);
+    };
+    Ok(Some(match op {
+        BinaryOperator::Eq => ordering == Ordering::Equal,
+        BinaryOperator::NotEq => ordering != Ordering::Equal,
+        BinaryOperator::Lt => ordering == Ordering::Less,
+        BinaryOperator::LtEq => ordering != Ordering::Greater,
+        BinaryOperator::Gt => ordering == Ordering::Greater,
+        BinaryOperator::GtEq => ordering != Ordering::Less,
+        _ => return Err(EvaluationError::Unsupported(op.to_string())),
+    }))
+}
+
+/// Returns the comparison of the value with every item, combined with `OR`
+/// for `ANY` and with `AND` for `ALL`.
+fn quantified(
+    value: &SqlValue,
+    op: &BinaryOperator,
+    items: &[SqlValue],
+    any: bool,
+) -> Result<SqlValue, EvaluationError> {
+    let mut result = Some(!any);
+    for item in items {
+        let comparison = compare(value, op, item)?;
+        let combined = if any {
+            or(result, comparison)
+        } else {
+            and(result, comparison)
+        };
+        result = match combined {
+            SqlValue::Boolean(value) => Some(value),
+            _ => None,
+        };
+    }
+    Ok(boolean(result))
+}
+
+/// Returns the three-valued conjunction.
+fn and(left: Option<bool>, right: Option<bool>) -> SqlValue {
+    match (left, right) {
+        (Some(false), _) | (_, Some(false)) => SqlValue::Boolean(false),
+        (Some(true), Some(true)) => SqlValue::Boolean(true),
+        _ => SqlValue::Null,
+    }
+}
+
+/// Returns the three-valued disjunction.
+fn or(left: Option<bool>, right: Option<bool>) -> SqlValue {
+    match (left, right) {
+        (Some(true), _) | (_, Some(true)) => SqlValue::Boolean(true),
+        (Some(false), Some(false)) => SqlValue::Boolean(false),
+        _ => SqlValue::Null,
+    }
+}
+
+/// Returns the boolean value, or `NULL` for `None`.
+fn boolean(value: Option<bool>) -> SqlValue {
+    value.map_or(SqlValue::Null, SqlValue::Boolean)
+}
+
+/// Negates the boolean value if requested, leaving `NULL` unchanged.
+fn negate_if(value: SqlValue, negated: bool) -> SqlValue {
+    match value {
+        SqlValue::Boolean(value) => SqlValue::Boolean(value != negated),
+        other => other,
+    }
+}
+
+/// Returns whether the text matches the `LIKE` pattern, where `%` matches any
+/// sequence of characters, `_` any single character and `\` escapes the
+/// following character.
+fn like_matches(text: &[char], pattern: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('%', rest)) => (0..=text.len()).any(|skip| like_matches(&text[skip..], rest)),
+        Some(('_', rest)) => !text.is_empty() && like_matches(&text[1..], rest),
+        Some(('\\', [escaped, rest @ ..]) | (escaped, rest)) => {
+            text.first() == Some(escaped) && like_matches(&text[1..], rest)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::{dialect::PostgreSqlDialect, parser::Parser};
+
+    use super::*;
+
+    fn parse(sql: &str) -> Expr {
+        Parser::new(&PostgreSqlDialect {})
+            .try_with_sql(sql)
+            .unwrap()
+            .parse_expr()
+            .unwrap()
+    }
+
+    fn row() -> HashMap<String, SqlValue> {
+        HashMap::from([
+            ("age".to_owned(), SqlValue::Integer(42)),
+            ("owner".to_owned(), SqlValue::from("alice")),
+            ("tenant_id".to_owned(), SqlValue::Integer(7)),
+            ("email".to_owned(), SqlValue::Null),
+        ])
+    }
+
+    #[test]
+    fn test_check_constraints() {
+        let evaluator = Evaluator::new(SessionContext::new("alice"));
+        assert!(
+            evaluator
+                .satisfies_check(&parse("age BETWEEN 0 AND 150"), &row())
+                .unwrap()
+        );
+        assert!(
+            !evaluator
+                .satisfies_check(&parse("age > 100 OR age < 0"), &row())
+                .unwrap()
+        );
+        // A NULL result satisfies a check constraint but not a policy.
+        let expr = parse("email LIKE '%@%'");
+        assert!(evaluator.satisfies_check(&expr, &row()).unwrap());
+        assert!(!evaluator.satisfies_policy(&expr, &row()).unwrap());
+        assert!(
+            evaluator
+                .satisfies_check(
+                    &parse("(age % 2) = 0 AND lower(owner) IN ('alice', 'bob')"),
+                    &row()
+                )
+                .unwrap()
+        );
+        assert!(matches!(
+            evaluator.evaluate(&parse("missing > 0"), &row()),
+            Err(EvaluationError::UnknownColumn(_))
+        ));
+    }
+
+    #[test]
+    fn test_policies() {
+        let evaluator = Evaluator::new(SessionContext::new("alice").setting("app.tenant_id", "7"));
+        assert!(
+            evaluator
+                .satisfies_policy(&parse("owner = CURRENT_USER"), &row())
+                .unwrap()
+        );
+        assert!(
+            evaluator
+                .satisfies_policy(
+                    &parse("tenant_id = (current_setting('app.tenant_id'::text))::integer"),
+                    &row()
+                )
+                .unwrap()
+        );
+        assert!(matches!(
+            evaluator.evaluate(&parse("current_setting('app.missing')"), &row()),
+            Err(EvaluationError::UnknownSetting(_))
+        ));
+        assert_eq!(
+            evaluator
+                .evaluate(&parse("current_setting('app.missing', true)"), &row())
+                .unwrap(),
+            SqlValue::Null
+        );
+    }
+
+    #[test]
+    fn test_custom_functions() {
+        let evaluator = Evaluator::new(SessionContext::new("bob")).function(
+            "is_admin",
+            |_: &[SqlValue], session: &SessionContext| {
+                Ok(SqlValue::Boolean(session.current_user() == "admin"))
+            },
+        );
+        assert!(
+            !evaluator
+                .satisfies_policy(&parse("is_admin() OR owner = current_user"), &row())
+                .unwrap()
+        );
+        assert!(matches!(
+            evaluator.evaluate(&parse("unknown_function(1)"), &row()),
+            Err(EvaluationError::UnknownFunction(_))
+        ));
+        assert_eq!(
+            evaluator
+                .evaluate(
+                    &parse("CASE WHEN age > 40 THEN 'old' ELSE 'young' END"),
+                    &row()
+                )
+                .unwrap(),
+            SqlValue::from("old")
+        );
+        assert_eq!(
+            evaluator
+                .evaluate(&parse("owner = ANY (ARRAY['bob', NULL])"), &row())
+                .unwrap(),
+            SqlValue::Null
+        );
+    }
+
+    #[test]
+    fn test_like_matches() {
+        let chars = |text: &str| text.chars().collect::<Vec<_>>();
+        assert!(like_matches(&chars("alice@example.org"), &chars("%@%.org")));
+        assert!(like_matches(&chars("a_b"), &chars("a\\_b")));
+        assert!(!like_matches(&chars("axb"), &chars("a\\_b")));
+        assert!(like_matches(&chars("abc"), &chars("a_c")));
+    }
+}
@@ -0,0 +1,227 @@
+//! Submodule providing the built-in functions of the [`Evaluator`](super::Evaluator).
+
+use std::{cmp::Ordering, ops::RangeInclusive, sync::Arc};
+
+use super::{EvaluationError, SessionContext, SqlFunction, SqlValue};
+
+/// The signature of the built-in functions.
+type Builtin = fn(&[SqlValue], &SessionContext) -> Result<SqlValue, EvaluationError>;
+
+/// Returns the built-in functions, by name.
+pub(super) fn builtins() -> impl Iterator<Item = (&'static str, SqlFunction)> {
+    let builtins: [(&'static str, Builtin); 16] = [
+        ("lower", lower),
+        ("upper", upper),
+        ("length", char_length),
+        ("char_length", char_length),
+        ("character_length", char_length),
+        ("octet_length", octet_length),
+        ("btrim", btrim),
+        ("ltrim", ltrim),
+        ("rtrim", rtrim),
+        ("concat", concat),
+        ("abs", abs),
+        ("coalesce", coalesce),
+        ("nullif", nullif),
+        ("greatest", greatest),
+        ("least", least),
+        ("current_setting", current_setting),
+    ];
+    builtins
+        .into_iter()
+        .map(|(name, function)| -> (&'static str, SqlFunction) { (name, Arc::new(function)) })
+}
+
+/// Checks the number of arguments of a function.
+fn arity(
+    function: &str,
+    arguments: &[SqlValue],
+    expected: RangeInclusive<usize>,
+) -> Result<(), EvaluationError> {
+    if expected.contains(&arguments.len()) {
+        Ok(())
+    } else {
+        Err(EvaluationError::InvalidArguments {
+            function: function.to_owned(),
+            reason: match (*expected.start(), *expected.end()) {
+                (start, end) if start == end => format!("expected {start} arguments"),
+                (start, usize::MAX) => format!("expected at least {start} arguments"),
+                (start, end) => format!("expected {start} to {end} arguments"),
+            },
+        })
+    }
+}
+
+/// Applies a text transformation to the single argument of a function.
+fn map_text(
+    function: &str,
+    arguments: &[SqlValue],
+    transform: impl Fn(&str) -> SqlValue,
+) -> Result<SqlValue, EvaluationError> {
+    arity(function, arguments, 1..=1)?;
+    Ok(arguments[0]
+        .to_text()
+        .map_or(SqlValue::Null, |text| transform(&text)))
+}
+
+/// The `lower` function.
+fn lower(arguments: &[SqlValue], _: &SessionContext) -> Result<SqlValue, EvaluationError> {
+    map_text("lower", arguments, |text| {
+        SqlValue::Text(text.to_lowercase())
+    })
+}
+
+/// The `upper` function.
+fn upper(arguments: &[SqlValue], _: &SessionContext) -> Result<SqlValue, EvaluationError> {
+    map_text("upper", arguments, |text| {
+        SqlValue::Text(text.to_uppercase())
+    })
+}
+
+/// The `char_length` function and its aliases.
+fn char_length(arguments: &[SqlValue], _: &SessionContext) -> Result<SqlValue, EvaluationError> {
+    map_text("char_length", arguments, |text| {
+        i64::try_from(text.chars().count()).map_or(SqlValue::Null, SqlValue::Integer)
+    })
+}
+
+/// The `octet_length` function.
+fn octet_length(arguments: &[SqlValue], _: &SessionContext) -> Result<SqlValue, EvaluationError> {
+    map_text("octet_length", arguments, |text| {
+        i64::try_from(text.len()).map_or(SqlValue::Null, SqlValue::Integer)
+    })
+}
+
+/// The `btrim` function, with the optional set of trimmed characters.
+fn btrim(arguments: &[SqlValue], _: &SessionContext) -> Result<SqlValue, EvaluationError> {
+    trim("btrim", arguments, |text, characters| {
+        text.trim_matches(|c| characters.contains(c)).to_owned()
+    })
+}
+
+/// The `ltrim` function, with the optional set of trimmed characters.
+fn ltrim(arguments: &[SqlValue], _: &SessionContext) -> Result<SqlValue, EvaluationError> {
+    trim("ltrim", arguments, |text, characters| {
+        text.trim_start_matches(|c| characters.contains(c))
+            .to_owned()
+    })
+}
+
+/// The `rtrim` function, with the optional set of trimmed characters.
+fn rtrim(arguments: &[SqlValue], _: &SessionContext) -> Result<SqlValue, EvaluationError> {
+    trim("rtrim", arguments, |text, characters| {
+        text.trim_end_matches(|c| characters.contains(c)).to_owned()
+    })
+}
+
+/// Trims the first argument with the characters of the optional second one,
+/// which default to a space.
+fn trim(
+    function: &str,
+    arguments: &[SqlValue],
+    trim: impl Fn(&str, &str) -> String,
+) -> Result<SqlValue, EvaluationError> {
+    arity(function, arguments, 1..=2)?;
+    let characters = match arguments.get(1) {
+        Some(characters) => match characters.to_text() {
+            Some(characters) => characters,
+            None => return Ok(SqlValue::Null),
+        },
+        None => " ".to_owned(),
+    };
+    Ok(arguments[0].to_text().map_or(SqlValue::Null, |text| {
+        SqlValue::Text(trim(&text, &characters))
+    }))
+}
+
+/// The `concat` function, which ignores `NULL` arguments.
+fn concat(arguments: &[SqlValue], _: &SessionContext) -> Result<SqlValue, EvaluationError> {
+    arity("concat", arguments, 1..=usize::MAX)?;
+    Ok(SqlValue::Text(
+        arguments.iter().filter_map(SqlValue::to_text).collect(),
+    ))
+}
+
+/// The `abs` function.
+fn abs(arguments: &[SqlValue], _: &SessionContext) -> Result<SqlValue, EvaluationError> {
+    arity("abs", arguments, 1..=1)?;
+    match &arguments[0] {
+        SqlValue::Integer(value) => value
+            .checked_abs()
+            .map(SqlValue::Integer)
+            .ok_or(EvaluationError::Overflow),
+        SqlValue::Float(value) => Ok(SqlValue::Float(value.abs())),
+        SqlValue::Numeric(value) => Ok(SqlValue::Numeric(value.abs())),
+        SqlValue::Null => Ok(SqlValue::Null),
+        other => Err(EvaluationError::InvalidArguments {
+            function: "abs".to_owned(),
+            reason: format!("expected a number, got {}", other.type_name()),
+        }),
+    }
+}
+
+/// The `coalesce` function.
+fn coalesce(arguments: &[SqlValue], _: &SessionContext) -> Result<SqlValue, EvaluationError> {
+    arity("coalesce", arguments, 1..=usize::MAX)?;
+    Ok(arguments
+        .iter()
+        .find(|argument| !argument.is_null())
+        .cloned()
+        .unwrap_or(SqlValue::Null))
+}
+
+/// The `nullif` function.
+fn nullif(arguments: &[SqlValue], _: &SessionContext) -> Result<SqlValue, EvaluationError> {
+    arity("nullif", arguments, 2..=2)?;
+    if arguments[0].compare(&arguments[1], "=")? == Some(Ordering::Equal) {
+        Ok(SqlValue::Null)
+    } else {
+        Ok(arguments[0].clone())
+    }
+}
+
+/// Returns the extreme non-`NULL` argument according to the ordering.
+fn extreme(
+    function: &str,
+    arguments: &[SqlValue],
+    wanted: Ordering,
+) -> Result<SqlValue, EvaluationError> {
+    arity(function, arguments, 1..=usize::MAX)?;
+    let mut extreme = SqlValue::Null;
+    for argument in arguments.iter().filter(|argument| !argument.is_null()) {
+        if extreme.is_null() || argument.compare(&extreme, function)? == Some(wanted) {
+            extreme = argument.clone();
+        }
+    }
+    Ok(extreme)
+}
+
+/// The `greatest` function.
+fn greatest(arguments: &[SqlValue], _: &SessionContext) -> Result<SqlValue, EvaluationError> {
+    extreme("greatest", arguments, Ordering::Greater)
+}
+
+/// The `least` function.
+fn least(arguments: &[SqlValue], _: &SessionContext) -> Result<SqlValue, EvaluationError> {
+    extreme("least", arguments, Ordering::Less)
+}
+
+/// The `current_setting` function, with the optional `missing_ok` flag.
+fn current_setting(
+    arguments: &[SqlValue],
+    session: &SessionContext,
+) -> Result<SqlValue, EvaluationError> {
+    arity("current_setting", arguments, 1..=2)?;
+    let Some(name) = arguments[0].to_text() else {
+        return Ok(SqlValue::Null);
+    };
+    let missing_ok = match arguments.get(1) {
+        Some(missing_ok) => missing_ok.to_bool()?.unwrap_or(false),
+        None => false,
+    };
+    match session.current_setting(&name) {
+        Some(value) => Ok(SqlValue::Text(value.to_owned())),
+        None if missing_ok => Ok(SqlValue::Null),
+        None => Err(EvaluationError::UnknownSetting(name)),
+    }
+}
@@ -0,0 +1,550 @@
+//! Submodule providing the [`SqlValue`] enum and the SQL operators on it.
+
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+use bigdecimal::{BigDecimal, FromPrimitive, RoundingMode, ToPrimitive};
+
+use super::EvaluationError;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A SQL value, as found in a row or produced by an expression.
+pub enum SqlValue {
+    /// The `NULL` value.
+    Null,
+    /// A boolean value.
+    Boolean(bool),
+    /// An integer value, of any width.
+    Integer(i64),
+    /// A floating point value, of any width.
+    Float(f64),
+    /// An arbitrary precision numeric value.
+    Numeric(BigDecimal),
+    /// A textual value, also used for the types without a variant such as
+    /// `uuid` or `date`.
+    Text(String),
+    /// An array value.
+    Array(Vec<SqlValue>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An arithmetic operator.
+pub(super) enum ArithmeticOperator {
+    /// The `+` operator.
+    Add,
+    /// The `-` operator.
+    Subtract,
+    /// The `*` operator.
+    Multiply,
+    /// The `/` operator.
+    Divide,
+    /// The `%` operator.
+    Modulo,
+}
+
+impl Display for ArithmeticOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::Multiply => "*",
+            Self::Divide => "/",
+            Self::Modulo => "%",
+        })
+    }
+}
+
+/// Two non-null values converted to a common type.
+enum Pair {
+    /// Two booleans.
+    Booleans(bool, bool),
+    /// Two integers.
+    Integers(i64, i64),
+    /// Two floating point numbers.
+    Floats(f64, f64),
+    /// Two numerics.
+    Numerics(BigDecimal, BigDecimal),
+    /// Two strings.
+    Texts(String, String),
+    /// Two arrays.
+    Arrays(Vec<SqlValue>, Vec<SqlValue>),
+}
+
+impl SqlValue {
+    /// Returns whether the value is `NULL`.
+    #[must_use]
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Returns the name of the type of the value, as used in errors.
+    #[must_use]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Boolean(_) => "boolean",
+            Self::Integer(_) => "integer",
+            Self::Float(_) => "double precision",
+            Self::Numeric(_) => "numeric",
+            Self::Text(_) => "text",
+            Self::Array(_) => "array",
+        }
+    }
+
+    /// Returns the text representation of the value, or `None` for `NULL`.
+    #[must_use]
+    pub fn to_text(&self) -> Option<String> {
+        (!self.is_null()).then(|| self.to_string())
+    }
+
+    /// Returns the boolean value, or `None` for `NULL`.
+    ///
+    /// # Errors
+    ///
+    /// * If the value is neither a boolean nor a string spelling one.
+    pub fn to_bool(&self) -> Result<Option<bool>, EvaluationError> {
+        match self {
+            Self::Null => Ok(None),
+            Self::Boolean(value) => Ok(Some(*value)),
+            Self::Text(text) => parse_bool(text)
+                .map(Some)
+                .ok_or_else(|| invalid_cast(self, "boolean")),
+            _ => Err(invalid_cast(self, "boolean")),
+        }
+    }
+
+    /// Compares two values, returning `None` when either is `NULL`.
+    ///
+    /// Strings compared to other types are parsed as literals of that type,
+    /// and numbers of different types are compared as the widest of them.
+    pub(super) fn compare(
+        &self,
+        other: &Self,
+        operator: &str,
+    ) -> Result<Option<Ordering>, EvaluationError> {
+        Ok(match self.pair(other, operator)? {
+            None => None,
+            Some(Pair::Booleans(left, right)) => Some(left.cmp(&right)),
+            Some(Pair::Integers(left, right)) => Some(left.cmp(&right)),
+            Some(Pair::Floats(left, right)) => Some(compare_floats(left, right)),
+            Some(Pair::Numerics(left, right)) => Some(left.cmp(&right)),
+            Some(Pair::Texts(left, right)) => Some(left.cmp(&right)),
+            Some(Pair::Arrays(left, right)) => {
+                for (left, right) in left.iter().zip(&right) {
+                    let ordering = match (left.is_null(), right.is_null()) {
+                        (true, true) => Ordering::Equal,
+                        (true, false) => Ordering::Greater,
+                        (false, true) => Ordering::Less,
+                        (false, false) => left.compare(right, operator)?.unwrap_or(Ordering::Equal),
+                    };
+                    if ordering != Ordering::Equal {
+                        return Ok(Some(ordering));
+                    }
+                }
+                Some(left.len().cmp(&right.len()))
+            }
+        })
+    }
+
+    /// Applies the arithmetic operator, returning `NULL` when either operand
+    /// is `NULL`.
+    pub(super) fn arithmetic(
+        &self,
+        operator: ArithmeticOperator,
+        other: &Self,
+    ) -> Result<Self, EvaluationError> {
+        let Some(pair) = self.pair(other, &operator.to_string())? else {
+            return Ok(Self::Null);
+        };
+        match pair {
+            Pair::Integers(left, right) => {
+                if right == 0
+                    && matches!(
+                        operator,
+                        ArithmeticOperator::Divide | ArithmeticOperator::Modulo
+                    )
+                {
+                    return Err(EvaluationError::DivisionByZero);
+                }
+                match operator {
+                    ArithmeticOperator::Add => left.checked_add(right),
+                    ArithmeticOperator::Subtract => left.checked_sub(right),
+                    ArithmeticOperator::Multiply => left.checked_mul(right),
+                    ArithmeticOperator::Divide => left.checked_div(right),
+                    ArithmeticOperator::Modulo => left.checked_rem(right),
+                }
+                .map(Self::Integer)
+                .ok_or(EvaluationError::Overflow)
+            }
+            Pair::Floats(left, right) => match operator {
+                ArithmeticOperator::Add => Ok(Self::Float(left + right)),
+                ArithmeticOperator::Subtract => Ok(Self::Float(left - right)),
+                ArithmeticOperator::Multiply => Ok(Self::Float(left * right)),
+                ArithmeticOperator::Divide if right == 0.0 => Err(EvaluationError::DivisionByZero),
+                ArithmeticOperator::Divide => Ok(Self::Float(left / right)),
+                ArithmeticOperator::Modulo => Err(self.mismatch(other, "%")),
+            },
+            Pair::Numerics(left, right) => {
+                let zero = BigDecimal::from(0);
+                match operator {
+                    ArithmeticOperator::Add => Ok(Self::Numeric(left + right)),
+                    ArithmeticOperator::Subtract => Ok(Self::Numeric(left - right)),
+                    ArithmeticOperator::Multiply => Ok(Self::Numeric(left * right)),
+                    ArithmeticOperator::Divide | ArithmeticOperator::Modulo if right == zero => {
+                        Err(EvaluationError::DivisionByZero)
+                    }
+                    ArithmeticOperator::Divide => Ok(Self::Numeric(left / right)),
+                    ArithmeticOperator::Modulo => Ok(Self::Numeric(left % right)),
+                }
+            }
+            Pair::Booleans(..) | Pair::Texts(..) | Pair::Arrays(..) => {
+                Err(self.mismatch(other, &operator.to_string()))
+            }
+        }
+    }
+
+    /// Returns the arithmetic negation of the value.
+    pub(super) fn negate(&self) -> Result<Self, EvaluationError> {
+        match self {
+            Self::Null => Ok(Self::Null),
+            Self::Integer(value) => value
+                .checked_neg()
+                .map(Self::Integer)
+                .ok_or(EvaluationError::Overflow),
+            Self::Float(value) => Ok(Self::Float(-value)),
+            Self::Numeric(value) => Ok(Self::Numeric(-value)),
+            Self::Text(_) => self.cast("numeric")?.negate(),
+            Self::Boolean(_) | Self::Array(_) => Err(self.mismatch(&Self::Null, "-")),
+        }
+    }
+
+    /// Casts the value to the provided `PostgreSQL` type.
+    ///
+    /// Types without a corresponding variant, such as `uuid` or `date`, leave
+    /// the value unchanged.
+    pub(super) fn cast(&self, type_name: &str) -> Result<Self, EvaluationError> {
+        let normalized = type_name.to_lowercase();
+        let normalized = normalized.split('(').next().unwrap_or_default().trim();
+        if self.is_null() || normalized.ends_with("[]") {
+            return Ok(self.clone());
+        }
+        match normalized {
+            "bool" | "boolean" => match self {
+                Self::Integer(value) => Ok(Self::Boolean(*value != 0)),
+                _ => self
+                    .to_bool()
+                    .map(|value| value.map_or(Self::Null, Self::Boolean)),
+            },
+            "int2" | "int4" | "int8" | "smallint" | "integer" | "int" | "bigint" => match self {
+                Self::Integer(_) => Ok(self.clone()),
+                Self::Boolean(value) => Ok(Self::Integer(i64::from(*value))),
+                Self::Float(value) => value
+                    .round_ties_even()
+                    .to_i64()
+                    .map(Self::Integer)
+                    .ok_or(EvaluationError::Overflow),
+                Self::Numeric(value) => value
+                    .with_scale_round(0, RoundingMode::HalfUp)
+                    .to_i64()
+                    .map(Self::Integer)
+                    .ok_or(EvaluationError::Overflow),
+                Self::Text(text) => text
+                    .trim()
+                    .parse()
+                    .map(Self::Integer)
+                    .map_err(|_| invalid_cast(self, type_name)),
+                _ => Err(invalid_cast(self, type_name)),
+            },
+            "float4" | "float8" | "real" | "double precision" | "float" => match self {
+                Self::Float(_) => Ok(self.clone()),
+                Self::Integer(value) => value
+                    .to_f64()
+                    .map(Self::Float)
+                    .ok_or(EvaluationError::Overflow),
+                Self::Numeric(value) => value
+                    .to_f64()
+                    .map(Self::Float)
+                    .ok_or(EvaluationError::Overflow),
+                Self::Text(text) => text
+                    .trim()
+                    .parse()
+                    .map(Self::Float)
+                    .map_err(|_| invalid_cast(self, type_name)),
+                _ => Err(invalid_cast(self, type_name)),
+            },
+            "numeric" | "decimal" => match self {
+                Self::Numeric(_) => Ok(self.clone()),
+                Self::Integer(value) => Ok(Self::Numeric(BigDecimal::from(*value))),
+                Self::Float(value) => BigDecimal::from_f64(*value)
+                    .map(Self::Numeric)
+                    .ok_or_else(|| invalid_cast(self, type_name)),
+                Self::Text(text) => BigDecimal::from_str(text.trim())
+                    .map(Self::Numeric)
+                    .map_err(|_| invalid_cast(self, type_name)),
+                _ => Err(invalid_cast(self, type_name)),
+            },
+            "text" | "varchar" | "character varying" | "char" | "character" | "bpchar" | "name" => {
+                Ok(Self::Text(self.to_string()))
+            }
+            _ => Ok(self.clone()),
+        }
+    }
+
+    /// Converts two values to a common type, returning `None` when either is
+    /// `NULL`.
+    fn pair(&self, other: &Self, operator: &str) -> Result<Option<Pair>, EvaluationError> {
+        Ok(Some(match (self, other) {
+            (Self::Null, _) | (_, Self::Null) => return Ok(None),
+            (Self::Boolean(left), Self::Boolean(right)) => Pair::Booleans(*left, *right),
+            (Self::Integer(left), Self::Integer(right)) => Pair::Integers(*left, *right),
+            (Self::Text(left), Self::Text(right)) => Pair::Texts(left.clone(), right.clone()),
+            (Self::Array(left), Self::Array(right)) => Pair::Arrays(left.clone(), right.clone()),
+            (Self::Float(_), _) | (_, Self::Float(_)) => {
+                let (Self::Float(left), Self::Float(right)) =
+                    (self.cast("float8")?, other.cast("float8")?)
+                else {
+                    return Err(self.mismatch(other, operator));
+                };
+                Pair::Floats(left, right)
+            }
+            (Self::Numeric(_), Self::Integer(_) | Self::Numeric(_) | Self::Text(_))
+            | (Self::Integer(_) | Self::Text(_), Self::Numeric(_)) => {
+                let (Self::Numeric(left), Self::Numeric(right)) =
+                    (self.cast("numeric")?, other.cast("numeric")?)
+                else {
+                    return Err(self.mismatch(other, operator));
+                };
+                Pair::Numerics(left, right)
+            }
+            (Self::Text(_), Self::Boolean(_) | Self::Integer(_)) => {
+                return self.cast(other.type_name())?.pair(other, operator);
+            }
+            (Self::Boolean(_) | Self::Integer(_), Self::Text(_)) => {
+                return self.pair(&other.cast(self.type_name())?, operator);
+            }
+            _ => return Err(self.mismatch(other, operator)),
+        }))
+    }
+
+    /// Returns the error for an operator applied to incompatible values.
+    fn mismatch(&self, other: &Self, operator: &str) -> EvaluationError {
+        EvaluationError::TypeMismatch {
+            operator: operator.to_owned(),
+            left: self.type_name(),
+            right: other.type_name(),
+        }
+    }
+}
+
+impl Display for SqlValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Null => f.write_str("NULL"),
+            Self::Boolean(value) => write!(f, "{value}"),
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Float(value) if value.is_nan() => f.write_str("NaN"),
+            Self::Float(value) if value.is_infinite() => f.write_str(if value.is_sign_positive() {
+                "Infinity"
+            } else {
+                "-Infinity"
+            }),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Numeric(value) => write!(f, "{value}"),
+            Self::Text(value) => f.write_str(value),
+            Self::Array(values) => {
+                f.write_str("{")?;
+                for (position, value) in values.iter().enumerate() {
+                    if position > 0 {
+                        f.write_str(",")?;
+                    }
+                    match value {
+                        Self::Text(text)
+                            if text.is_empty()
+                                || text.eq_ignore_ascii_case("null")
+                                || text.contains(|c: char| {
+                                    c.is_whitespace() || ",{}\"\\".contains(c)
+                                }) =>
+                        {
+                            write!(f, "\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))?;
+                        }
+                        _ => write!(f, "{value}")?,
+                    }
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+impl From<bool> for SqlValue {
+    fn from(value: bool) -> Self {
+        Self::Boolean(value)
+    }
+}
+
+impl From<i16> for SqlValue {
+    fn from(value: i16) -> Self {
+        Self::Integer(i64::from(value))
+    }
+}
+
+impl From<i32> for SqlValue {
+    fn from(value: i32) -> Self {
+        Self::Integer(i64::from(value))
+    }
+}
+
+impl From<i64> for SqlValue {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<f32> for SqlValue {
+    fn from(value: f32) -> Self {
+        Self::Float(f64::from(value))
+    }
+}
+
+impl From<f64> for SqlValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<BigDecimal> for SqlValue {
+    fn from(value: BigDecimal) -> Self {
+        Self::Numeric(value)
+    }
+}
+
+impl From<String> for SqlValue {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for SqlValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_owned())
+    }
+}
+
+impl<T: Into<SqlValue>> From<Option<T>> for SqlValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+impl<T: Into<SqlValue>> From<Vec<T>> for SqlValue {
+    fn from(values: Vec<T>) -> Self {
+        Self::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Parses the spellings of booleans accepted by `PostgreSQL`.
+fn parse_bool(text: &str) -> Option<bool> {
+    match text.trim().to_lowercase().as_str() {
+        "t" | "true" | "y" | "yes" | "on" | "1" => Some(true),
+        "f" | "false" | "n" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Compares two floating point numbers, with `NaN` greater than any number
+/// and equal to itself, as in `PostgreSQL`.
+fn compare_floats(left: f64, right: f64) -> Ordering {
+    left.partial_cmp(&right)
+        .unwrap_or_else(|| left.is_nan().cmp(&right.is_nan()))
+}
+
+/// Returns the error for a value which cannot be cast to the type.
+fn invalid_cast(value: &SqlValue, type_name: &str) -> EvaluationError {
+    EvaluationError::InvalidCast {
+        value: value.to_string(),
+        type_name: type_name.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(
+            SqlValue::Integer(1)
+                .compare(&SqlValue::Numeric(BigDecimal::from(2)), "<")
+                .unwrap(),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            SqlValue::Text("10".into())
+                .compare(&SqlValue::Integer(10), "=")
+                .unwrap(),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            SqlValue::Null.compare(&SqlValue::Integer(1), "=").unwrap(),
+            None
+        );
+        assert_eq!(
+            SqlValue::Float(f64::NAN)
+                .compare(&SqlValue::Float(1.0), ">")
+                .unwrap(),
+            Some(Ordering::Greater)
+        );
+        assert!(matches!(
+            SqlValue::Boolean(true).compare(&SqlValue::Integer(1), "="),
+            Err(EvaluationError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(
+            SqlValue::Integer(7)
+                .arithmetic(ArithmeticOperator::Divide, &SqlValue::Integer(2))
+                .unwrap(),
+            SqlValue::Integer(3)
+        );
+        assert!(matches!(
+            SqlValue::Integer(1).arithmetic(ArithmeticOperator::Modulo, &SqlValue::Integer(0)),
+            Err(EvaluationError::DivisionByZero)
+        ));
+        assert!(matches!(
+            SqlValue::Integer(i64::MAX).arithmetic(ArithmeticOperator::Add, &SqlValue::Integer(1)),
+            Err(EvaluationError::Overflow)
+        ));
+        assert_eq!(
+            SqlValue::Null
+                .arithmetic(ArithmeticOperator::Add, &SqlValue::Integer(1))
+                .unwrap(),
+            SqlValue::Null
+        );
+    }
+
+    #[test]
+    fn test_cast_and_display() {
+        assert_eq!(
+            SqlValue::Text(" 42 ".into()).cast("integer").unwrap(),
+            SqlValue::Integer(42)
+        );
+        assert_eq!(
+            SqlValue::Float(2.5).cast("int4").unwrap(),
+            SqlValue::Integer(2)
+        );
+        assert_eq!(
+            SqlValue::Text("yes".into()).cast("boolean").unwrap(),
+            SqlValue::Boolean(true)
+        );
+        assert_eq!(
+            SqlValue::Integer(3).cast("text").unwrap(),
+            SqlValue::Text("3".into())
+        );
+        assert_eq!(
+            SqlValue::from(vec![Some("a b"), None]).to_string(),
+            "{\"a b\",NULL}"
+        );
+    }
+}
@@ -0,0 +1,1056 @@
+//! Submodule reporting the extensions of a database and planning their
+//! installation and upgrades.
+//!
+//! An [`ExtensionInfo`] gathers what the server knows about an extension:
+//! the versions available from `pg_available_extension_versions`, with the
+//! extensions each requires, whether it is relocatable and the schema it
+//! must be installed in, the installed version and schema from
+//! `pg_extension`, and the versions the installed one can be updated to
+//! through `pg_extension_update_paths`.
+//!
+//! An [`ExtensionManifest`] lists the extensions a deployment expects, as in
+//! `postgis >= 3.4, pgcrypto`, and [`ExtensionManifest::check`] compares it
+//! with the extensions of a database, producing an [`ExtensionPlan`] of the
+//! extensions to install or upgrade and of the requirements the server
+//! cannot satisfy.
+
+use std::{cmp::Ordering, collections::BTreeMap, fmt::Display, str::FromStr};
+
+use diesel::{
+    PgConnection, QueryableByName, RunQueryDsl,
+    sql_types::{Nullable, Text},
+};
+
+use crate::{
+    models::{PgAvailableExtensionVersions, PgAvailableExtensions},
+    quoting::{quote_identifier, quote_literal},
+};
+
+#[derive(Debug, thiserror::Error)]
+/// Errors that can occur when parsing an extension manifest.
+pub enum ExtensionError {
+    #[error("Invalid extension requirement `{0}`, expected `<name> [<operator> <version>]`")]
+    /// A requirement of a manifest is not an extension name optionally
+    /// followed by a version constraint.
+    InvalidRequirement(String),
+}
+
+/// Splits a version into its numeric and alphabetic segments, as in `3`,
+/// `4`, `0`, `beta` and `1` for `3.4.0beta1`, dropping the separators.
+fn version_segments(version: &str) -> Vec<Result<u64, &str>> {
+    let mut segments = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut previous_is_digit = false;
+    for (position, c) in version.char_indices() {
+        let is_digit = c.is_ascii_digit();
+        if let Some(segment_start) = start
+            && (!c.is_ascii_alphanumeric() || is_digit != previous_is_digit)
+        {
+            let segment = &version[segment_start..position];
+            segments.push(segment.parse().map_err(|_| segment));
+            start = None;
+        }
+        if start.is_none() && c.is_ascii_alphanumeric() {
+            start = Some(position);
+        }
+        previous_is_digit = is_digit;
+    }
+    if let Some(segment_start) = start {
+        let segment = &version[segment_start..];
+        segments.push(segment.parse().map_err(|_| segment));
+    }
+    segments
+}
+
+#[must_use]
+/// Compares two extension versions segment by segment, numbers by value and
+/// words alphabetically.
+///
+/// Extension versions are free-form strings, so the comparison follows the
+/// usual conventions rather than a specification: `1.10` is newer than
+/// `1.9`, a missing numeric segment counts as `0` so that `3.4` equals
+/// `3.4.0`, and a pre-release such as `3.4.0beta1` or `3.4beta1` is older
+/// than `3.4.0`.
+pub fn compare_versions(left: &str, right: &str) -> Ordering {
+    let left = version_segments(left);
+    let right = version_segments(right);
+    for position in 0..left.len().max(right.len()) {
+        let ordering = match (left.get(position), right.get(position)) {
+            (Some(Ok(left)), Some(Ok(right))) => left.cmp(right),
+            (Some(Ok(left)), None) => left.cmp(&0),
+            (None, Some(Ok(right))) => 0.cmp(right),
+            (Some(Err(left)), Some(Err(right))) => left.cmp(right),
+            (Some(Ok(_)), Some(Err(_))) | (None, Some(Err(_))) => Ordering::Greater,
+            (Some(Err(_)), Some(Ok(_)) | None) => Ordering::Less,
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The operator of a [`VersionConstraint`].
+pub enum VersionOperator {
+    /// `=`: exactly the version.
+    Equal,
+    /// `>=`: the version or a newer one.
+    GreaterOrEqual,
+    /// `>`: a newer version.
+    Greater,
+    /// `<=`: the version or an older one.
+    LessOrEqual,
+    /// `<`: an older version.
+    Less,
+}
+
+impl Display for VersionOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Equal => "=",
+            Self::GreaterOrEqual => ">=",
+            Self::Greater => ">",
+            Self::LessOrEqual => "<=",
+            Self::Less => "<",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A constraint on the version of an extension, as in `>= 3.4`.
+pub struct VersionConstraint {
+    /// The operator comparing the versions.
+    operator: VersionOperator,
+    /// The version compared to.
+    version: String,
+}
+
+impl VersionConstraint {
+    #[must_use]
+    /// Creates a new `VersionConstraint`.
+    pub fn new<S: AsRef<str>>(operator: VersionOperator, version: S) -> Self {
+        Self {
+            operator,
+            version: version.as_ref().to_owned(),
+        }
+    }
+
+    /// Returns the operator comparing the versions.
+    #[must_use]
+    pub fn operator(&self) -> VersionOperator {
+        self.operator
+    }
+
+    /// Returns the version compared to.
+    #[must_use]
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    #[must_use]
+    /// Returns whether the version satisfies the constraint, as compared by
+    /// [`compare_versions`].
+    pub fn matches(&self, version: &str) -> bool {
+        let ordering = compare_versions(version, &self.version);
+        match self.operator {
+            VersionOperator::Equal => ordering == Ordering::Equal,
+            VersionOperator::GreaterOrEqual => ordering != Ordering::Less,
+            VersionOperator::Greater => ordering == Ordering::Greater,
+            VersionOperator::LessOrEqual => ordering != Ordering::Greater,
+            VersionOperator::Less => ordering == Ordering::Less,
+        }
+    }
+}
+
+impl Display for VersionConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.operator, self.version)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An extension expected by a deployment, with an optional constraint on its
+/// version, as in `postgis >= 3.4` or `pgcrypto`.
+pub struct ExtensionRequirement {
+    /// The name of the extension.
+    name: String,
+    /// The constraint on the version, if any.
+    constraint: Option<VersionConstraint>,
+}
+
+impl ExtensionRequirement {
+    #[must_use]
+    /// Creates a new `ExtensionRequirement`.
+    pub fn new<S: AsRef<str>>(name: S, constraint: Option<VersionConstraint>) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            constraint,
+        }
+    }
+
+    /// Returns the name of the extension.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the constraint on the version, if any.
+    #[must_use]
+    pub fn constraint(&self) -> Option<&VersionConstraint> {
+        self.constraint.as_ref()
+    }
+
+    #[must_use]
+    /// Returns whether the version satisfies the requirement.
+    pub fn matches(&self, version: &str) -> bool {
+        self.constraint
+            .as_ref()
+            .is_none_or(|constraint| constraint.matches(version))
+    }
+}
+
+impl FromStr for ExtensionRequirement {
+    type Err = ExtensionError;
+
+    fn from_str(requirement: &str) -> Result<Self, Self::Err> {
+        let invalid = || ExtensionError::InvalidRequirement(requirement.to_owned());
+        let trimmed = requirement.trim();
+        let end = trimmed
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '<' | '>'))
+            .unwrap_or(trimmed.len());
+        let (name, rest) = trimmed.split_at(end);
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        let rest = rest.trim_start();
+        if rest.is_empty() {
+            return Ok(Self::new(name, None));
+        }
+        let (operator, version) = [
+            (">=", VersionOperator::GreaterOrEqual),
+            ("<=", VersionOperator::LessOrEqual),
+            ("=", VersionOperator::Equal),
+            (">", VersionOperator::Greater),
+            ("<", VersionOperator::Less),
+        ]
+        .into_iter()
+        .find_map(|(symbol, operator)| {
+            rest.strip_prefix(symbol)
+                .map(|version| (operator, version.trim()))
+        })
+        .ok_or_else(invalid)?;
+        if version.is_empty() || version.contains(char::is_whitespace) {
+            return Err(invalid());
+        }
+        Ok(Self::new(
+            name,
+            Some(VersionConstraint::new(operator, version)),
+        ))
+    }
+}
+
+impl Display for ExtensionRequirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)?;
+        if let Some(constraint) = &self.constraint {
+            write!(f, " {constraint}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A way to update an extension from a version to another, through the
+/// update scripts of its intermediate versions.
+pub struct UpdatePath {
+    /// The version updated from.
+    source: String,
+    /// The version updated to.
+    target: String,
+    /// The versions the update goes through, from the source to the target.
+    versions: Vec<String>,
+}
+
+impl UpdatePath {
+    #[must_use]
+    /// Creates a new `UpdatePath` from a row of `pg_extension_update_paths`,
+    /// whose path is written as in `1.0--1.1--1.2`.
+    pub fn new(source: String, target: String, path: &str) -> Self {
+        Self {
+            source,
+            target,
+            versions: path.split("--").map(str::to_owned).collect(),
+        }
+    }
+
+    /// Returns the version updated from.
+    #[must_use]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the version updated to.
+    #[must_use]
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Returns the versions the update goes through, from the source to the
+    /// target included.
+    #[must_use]
+    pub fn versions(&self) -> &[String] {
+        &self.versions
+    }
+
+    /// Returns the number of update scripts run by the update.
+    #[must_use]
+    pub fn steps(&self) -> usize {
+        self.versions.len().saturating_sub(1)
+    }
+}
+
+impl Display for UpdatePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.versions.join("--"))
+    }
+}
+
+#[derive(QueryableByName)]
+/// An installed extension with the name of its schema.
+struct InstalledExtensionRow {
+    /// The name of the extension.
+    #[diesel(sql_type = Text)]
+    name: String,
+    /// The installed version.
+    #[diesel(sql_type = Text)]
+    version: String,
+    /// The schema the objects of the extension are in.
+    #[diesel(sql_type = Text)]
+    schema_name: String,
+}
+
+#[derive(QueryableByName)]
+/// A row of `pg_extension_update_paths`.
+struct UpdatePathRow {
+    /// The version updated from.
+    #[diesel(sql_type = Text)]
+    source: String,
+    /// The version updated to.
+    #[diesel(sql_type = Text)]
+    target: String,
+    /// The versions of the update, or `NULL` if there is no path.
+    #[diesel(sql_type = Nullable<Text>)]
+    path: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An extension of the server, with its available versions and, if it is
+/// installed in the database, its installed version and update paths.
+pub struct ExtensionInfo {
+    /// The name of the extension.
+    name: String,
+    /// The comment describing the extension, if any.
+    comment: Option<String>,
+    /// The version installed by `CREATE EXTENSION` without `VERSION`, if the
+    /// extension is available.
+    default_version: Option<String>,
+    /// The available versions, sorted from the oldest.
+    versions: Vec<PgAvailableExtensionVersions>,
+    /// The installed version, if installed.
+    installed_version: Option<String>,
+    /// The schema the extension is installed in, if installed.
+    installed_schema: Option<String>,
+    /// The paths updating the installed version to the other versions,
+    /// sorted by target version.
+    update_paths: Vec<UpdatePath>,
+}
+
+impl ExtensionInfo {
+    /// Loads the extensions available on the server or installed in the
+    /// database, sorted by name, with the update paths of the installed ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        let mut extensions: BTreeMap<String, Self> = BTreeMap::new();
+        for available in PgAvailableExtensions::load_all(conn)? {
+            let Some(name) = available.name else {
+                continue;
+            };
+            let mut extension = Self::empty(name.clone());
+            extension.comment = available.comment;
+            extension.default_version = available.default_version;
+            extensions.insert(name, extension);
+        }
+        for version in PgAvailableExtensionVersions::load_all(conn)? {
+            if let Some(extension) = version
+                .name
+                .as_ref()
+                .and_then(|name| extensions.get_mut(name))
+            {
+                extension.versions.push(version);
+            }
+        }
+
+        let installed: Vec<InstalledExtensionRow> = diesel::sql_query(
+            r"
+            SELECT
+                e.extname::text AS name,
+                e.extversion AS version,
+                n.nspname::text AS schema_name
+            FROM pg_extension e
+            JOIN pg_namespace n ON n.oid = e.extnamespace
+            ",
+        )
+        .load(conn)?;
+        for row in installed {
+            let update_paths: Vec<UpdatePathRow> = if extensions.contains_key(&row.name) {
+                diesel::sql_query(
+                    r"
+                    SELECT source, target, path
+                    FROM pg_extension_update_paths($1)
+                    WHERE source = $2 AND path IS NOT NULL
+                    ",
+                )
+                .bind::<Text, _>(&row.name)
+                .bind::<Text, _>(&row.version)
+                .load(conn)?
+            } else {
+                Vec::new()
+            };
+            let extension = extensions
+                .entry(row.name.clone())
+                .or_insert_with(|| Self::empty(row.name));
+            extension.installed_version = Some(row.version);
+            extension.installed_schema = Some(row.schema_name);
+            extension.update_paths = update_paths
+                .into_iter()
+                .filter_map(|path| Some(UpdatePath::new(path.source, path.target, &path.path?)))
+                .collect();
+            extension
+                .update_paths
+                .sort_by(|left, right| compare_versions(&left.target, &right.target));
+        }
+
+        Ok(extensions
+            .into_values()
+            .map(|mut extension| {
+                extension.versions.sort_by(|left, right| {
+                    compare_versions(
+                        left.version.as_deref().unwrap_or_default(),
+                        right.version.as_deref().unwrap_or_default(),
+                    )
+                });
+                extension
+            })
+            .collect())
+    }
+
+    /// Returns an extension with the provided name and nothing known about
+    /// it yet.
+    fn empty(name: String) -> Self {
+        Self {
+            name,
+            comment: None,
+            default_version: None,
+            versions: Vec::new(),
+            installed_version: None,
+            installed_schema: None,
+            update_paths: Vec::new(),
+        }
+    }
+
+    /// Returns the name of the extension.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the comment describing the extension, if any.
+    #[must_use]
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Returns the version installed by `CREATE EXTENSION` without
+    /// `VERSION`, or `None` if the extension is not available on the server.
+    #[must_use]
+    pub fn default_version(&self) -> Option<&str> {
+        self.default_version.as_deref()
+    }
+
+    /// Returns an iterator over the available versions, from the oldest.
+    pub fn available_versions(&self) -> impl Iterator<Item = &str> {
+        self.versions
+            .iter()
+            .filter_map(|version| version.version.as_deref())
+    }
+
+    /// Returns the newest available version, if any.
+    #[must_use]
+    pub fn latest_version(&self) -> Option<&str> {
+        self.available_versions().next_back()
+    }
+
+    /// Returns the row of `pg_available_extension_versions` of the version,
+    /// if it is available.
+    #[must_use]
+    pub fn version(&self, version: &str) -> Option<&PgAvailableExtensionVersions> {
+        self.versions
+            .iter()
+            .find(|available| available.version.as_deref() == Some(version))
+    }
+
+    /// Returns whether the extension is available on the server.
+    #[must_use]
+    pub fn is_available(&self) -> bool {
+        !self.versions.is_empty()
+    }
+
+    /// Returns the installed version, if the extension is installed.
+    #[must_use]
+    pub fn installed_version(&self) -> Option<&str> {
+        self.installed_version.as_deref()
+    }
+
+    /// Returns the schema the extension is installed in, if installed.
+    #[must_use]
+    pub fn installed_schema(&self) -> Option<&str> {
+        self.installed_schema.as_deref()
+    }
+
+    /// Returns whether the extension is installed in the database.
+    #[must_use]
+    pub fn is_installed(&self) -> bool {
+        self.installed_version.is_some()
+    }
+
+    /// Returns whether the installed version differs from the default one,
+    /// which `ALTER EXTENSION ... UPDATE` updates to.
+    #[must_use]
+    pub fn is_outdated(&self) -> bool {
+        matches!(
+            (&self.installed_version, &self.default_version),
+            (Some(installed), Some(default)) if installed != default
+        )
+    }
+
+    /// Returns the version whose properties describe the extension: the
+    /// installed version, or else the default one.
+    fn current(&self) -> Option<&PgAvailableExtensionVersions> {
+        self.installed_version
+            .as_deref()
+            .or(self.default_version.as_deref())
+            .and_then(|version| self.version(version))
+    }
+
+    /// Returns the extensions required by the installed version, or else by
+    /// the default one.
+    #[must_use]
+    pub fn requires(&self) -> &[String] {
+        self.current()
+            .and_then(|version| version.requires.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Returns whether the objects of the installed version, or else of the
+    /// default one, can be moved to another schema with `ALTER EXTENSION ...
+    /// SET SCHEMA`, or `None` if unknown.
+    #[must_use]
+    pub fn is_relocatable(&self) -> Option<bool> {
+        self.current().and_then(|version| version.relocatable)
+    }
+
+    /// Returns the schema the installed version, or else the default one,
+    /// must be installed in, or `None` if it may be installed in any schema.
+    #[must_use]
+    pub fn target_schema(&self) -> Option<&str> {
+        self.current().and_then(|version| version.schema.as_deref())
+    }
+
+    /// Returns the paths updating the installed version to the other
+    /// available versions, sorted by target version.
+    #[must_use]
+    pub fn update_paths(&self) -> &[UpdatePath] {
+        &self.update_paths
+    }
+
+    /// Returns the path updating the installed version to the target one,
+    /// if there is one.
+    #[must_use]
+    pub fn update_path(&self, target: &str) -> Option<&UpdatePath> {
+        self.update_paths.iter().find(|path| path.target == target)
+    }
+
+    /// Returns the version matching the requirement to install or update to
+    /// among the candidates: the default version if it matches, or else the
+    /// newest matching one.
+    fn best_version<'a, I>(
+        &self,
+        requirement: &ExtensionRequirement,
+        candidates: I,
+    ) -> Option<&'a str>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        let matching: Vec<&str> = candidates
+            .filter(|version| requirement.matches(version))
+            .collect();
+        matching
+            .iter()
+            .find(|version| Some(**version) == self.default_version.as_deref())
+            .or_else(|| {
+                matching
+                    .iter()
+                    .max_by(|left, right| compare_versions(left, right))
+            })
+            .copied()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// What a deployment needs to do about a requirement of an
+/// [`ExtensionManifest`].
+pub enum ExtensionAction {
+    /// The extension is installed at a version matching the requirement.
+    Satisfied {
+        /// The name of the extension.
+        name: String,
+        /// The installed version.
+        version: String,
+    },
+    /// The extension must be installed.
+    Install {
+        /// The name of the extension.
+        name: String,
+        /// The version to install.
+        version: String,
+        /// The schema the extension must be installed in, if any.
+        schema: Option<String>,
+        /// The extensions it requires which are not installed, installed
+        /// along with it by `CASCADE`.
+        requires: Vec<String>,
+    },
+    /// The installed version must be updated.
+    Upgrade {
+        /// The name of the extension.
+        name: String,
+        /// The path from the installed version to the one to update to.
+        path: UpdatePath,
+    },
+    /// The requirement cannot be satisfied by the server.
+    Unsatisfiable {
+        /// The requirement.
+        requirement: ExtensionRequirement,
+        /// Why the requirement cannot be satisfied.
+        reason: String,
+    },
+}
+
+impl ExtensionAction {
+    #[must_use]
+    /// Returns the name of the extension.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Satisfied { name, .. }
+            | Self::Install { name, .. }
+            | Self::Upgrade { name, .. } => name,
+            Self::Unsatisfiable { requirement, .. } => requirement.name(),
+        }
+    }
+
+    #[must_use]
+    /// Returns the statement performing the action, if there is one to run.
+    pub fn statement(&self) -> Option<String> {
+        match self {
+            Self::Install {
+                name,
+                version,
+                schema,
+                ..
+            } => {
+                let mut statement = format!(
+                    "CREATE EXTENSION IF NOT EXISTS {} VERSION {}",
+                    quote_identifier(name),
+                    quote_literal(version)
+                );
+                if let Some(schema) = schema {
+                    statement.push_str(&format!(" SCHEMA {}", quote_identifier(schema)));
+                }
+                statement.push_str(" CASCADE;");
+                Some(statement)
+            }
+            Self::Upgrade { name, path } => Some(format!(
+                "ALTER EXTENSION {} UPDATE TO {};",
+                quote_identifier(name),
+                quote_literal(path.target())
+            )),
+            Self::Satisfied { .. } | Self::Unsatisfiable { .. } => None,
+        }
+    }
+}
+
+impl Display for ExtensionAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Satisfied { name, version } => write!(f, "ok {name} {version}"),
+            Self::Install {
+                name,
+                version,
+                schema,
+                requires,
+            } => {
+                write!(f, "install {name} {version}")?;
+                if let Some(schema) = schema {
+                    write!(f, " in schema {schema}")?;
+                }
+                if !requires.is_empty() {
+                    write!(f, " with {}", requires.join(", "))?;
+                }
+                Ok(())
+            }
+            Self::Upgrade { name, path } => write!(
+                f,
+                "upgrade {name} {} -> {} via {path}",
+                path.source(),
+                path.target()
+            ),
+            Self::Unsatisfiable {
+                requirement,
+                reason,
+            } => write!(f, "unsatisfiable {requirement}: {reason}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The extensions a deployment expects, as in `postgis >= 3.4, pgcrypto`.
+pub struct ExtensionManifest {
+    /// The requirements, in the order of the manifest.
+    requirements: Vec<ExtensionRequirement>,
+}
+
+impl ExtensionManifest {
+    #[must_use]
+    /// Creates an empty `ExtensionManifest`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    /// Adds a requirement to the manifest.
+    pub fn require(mut self, requirement: ExtensionRequirement) -> Self {
+        self.requirements.push(requirement);
+        self
+    }
+
+    /// Returns the requirements, in the order of the manifest.
+    #[must_use]
+    pub fn requirements(&self) -> &[ExtensionRequirement] {
+        &self.requirements
+    }
+
+    #[must_use]
+    /// Compares the manifest with the extensions of a database, as loaded by
+    /// [`ExtensionInfo::load_all`].
+    pub fn check(&self, extensions: &[ExtensionInfo]) -> ExtensionPlan {
+        let actions = self
+            .requirements
+            .iter()
+            .map(|requirement| {
+                let extension = extensions
+                    .iter()
+                    .find(|extension| extension.name == requirement.name);
+                Self::action(requirement, extension, extensions)
+            })
+            .collect();
+        ExtensionPlan { actions }
+    }
+
+    /// Loads the extensions of the database and compares the manifest with
+    /// them.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn check_database(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<ExtensionPlan, diesel::result::Error> {
+        Ok(self.check(&ExtensionInfo::load_all(conn)?))
+    }
+
+    /// Returns what to do about the requirement given the extension.
+    fn action(
+        requirement: &ExtensionRequirement,
+        extension: Option<&ExtensionInfo>,
+        extensions: &[ExtensionInfo],
+    ) -> ExtensionAction {
+        let unsatisfiable = |reason: String| ExtensionAction::Unsatisfiable {
+            requirement: requirement.clone(),
+            reason,
+        };
+        let Some(extension) =
+            extension.filter(|extension| extension.is_available() || extension.is_installed())
+        else {
+            return unsatisfiable("not available on the server".to_owned());
+        };
+
+        if let Some(installed) = extension.installed_version() {
+            if requirement.matches(installed) {
+                return ExtensionAction::Satisfied {
+                    name: extension.name.clone(),
+                    version: installed.to_owned(),
+                };
+            }
+            let targets = extension.update_paths.iter().map(|path| path.target());
+            return match extension
+                .best_version(requirement, targets)
+                .and_then(|target| extension.update_path(target))
+            {
+                Some(path) => ExtensionAction::Upgrade {
+                    name: extension.name.clone(),
+                    path: path.clone(),
+                },
+                None => unsatisfiable(format!(
+                    "installed version {installed} has no update path to a matching version"
+                )),
+            };
+        }
+
+        match extension.best_version(requirement, extension.available_versions()) {
+            Some(version) => {
+                let available = extension.version(version);
+                let installed = |name: &String| {
+                    extensions
+                        .iter()
+                        .any(|other| other.name == *name && other.is_installed())
+                };
+                ExtensionAction::Install {
+                    name: extension.name.clone(),
+                    version: version.to_owned(),
+                    schema: available.and_then(|available| available.schema.clone()),
+                    requires: available
+                        .and_then(|available| available.requires.as_deref())
+                        .unwrap_or_default()
+                        .iter()
+                        .filter(|name| !installed(name))
+                        .cloned()
+                        .collect(),
+                }
+            }
+            None => unsatisfiable(format!(
+                "no available version matches, the newest is {}",
+                extension.latest_version().unwrap_or("unknown")
+            )),
+        }
+    }
+}
+
+impl FromStr for ExtensionManifest {
+    type Err = ExtensionError;
+
+    /// Parses a manifest whose requirements are separated by commas or
+    /// newlines, ignoring blank lines and `#` comments.
+    fn from_str(manifest: &str) -> Result<Self, Self::Err> {
+        let requirements = manifest
+            .lines()
+            .map(|line| line.split_once('#').map_or(line, |(line, _)| line))
+            .flat_map(|line| line.split(','))
+            .filter(|requirement| !requirement.trim().is_empty())
+            .map(str::parse::<ExtensionRequirement>)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { requirements })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The result of checking an [`ExtensionManifest`] against a database.
+pub struct ExtensionPlan {
+    /// The action for each requirement, in the order of the manifest.
+    actions: Vec<ExtensionAction>,
+}
+
+impl ExtensionPlan {
+    /// Returns the action for each requirement, in the order of the manifest.
+    #[must_use]
+    pub fn actions(&self) -> &[ExtensionAction] {
+        &self.actions
+    }
+
+    /// Returns whether every requirement is already satisfied.
+    #[must_use]
+    pub fn is_satisfied(&self) -> bool {
+        self.actions
+            .iter()
+            .all(|action| matches!(action, ExtensionAction::Satisfied { .. }))
+    }
+
+    /// Returns whether some requirement cannot be satisfied by the server.
+    #[must_use]
+    pub fn has_unsatisfiable(&self) -> bool {
+        self.actions
+            .iter()
+            .any(|action| matches!(action, ExtensionAction::Unsatisfiable { .. }))
+    }
+
+    #[must_use]
+    /// Returns the statements installing and upgrading the extensions, in
+    /// the order of the manifest.
+    pub fn statements(&self) -> Vec<String> {
+        self.actions
+            .iter()
+            .filter_map(ExtensionAction::statement)
+            .collect()
+    }
+}
+
+impl Display for ExtensionPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for action in &self.actions {
+            writeln!(f, "{action}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns an available version of the extension.
+    fn available(name: &str, version: &str, requires: &[&str]) -> PgAvailableExtensionVersions {
+        PgAvailableExtensionVersions {
+            name: Some(name.to_owned()),
+            version: Some(version.to_owned()),
+            installed: Some(false),
+            superuser: Some(true),
+            trusted: Some(false),
+            relocatable: Some(false),
+            schema: None,
+            requires: (!requires.is_empty())
+                .then(|| requires.iter().map(|name| (*name).to_owned()).collect()),
+            comment: None,
+        }
+    }
+
+    /// Returns an extension with the available versions, the last being the
+    /// default one.
+    fn extension(name: &str, versions: &[&str]) -> ExtensionInfo {
+        let mut extension = ExtensionInfo::empty(name.to_owned());
+        extension.default_version = versions.last().map(|version| (*version).to_owned());
+        extension.versions = versions
+            .iter()
+            .map(|version| available(name, version, &[]))
+            .collect();
+        extension
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("3.4", "3.4.0"), Ordering::Equal);
+        assert_eq!(compare_versions("3.4.1", "3.4"), Ordering::Greater);
+        assert_eq!(compare_versions("3.4.0beta1", "3.4.0"), Ordering::Less);
+        assert_eq!(compare_versions("3.4beta1", "3.4"), Ordering::Less);
+        assert_eq!(compare_versions("3.4beta1", "3.4.0"), Ordering::Less);
+        assert_eq!(compare_versions("3.4.0beta1", "3.4.0beta2"), Ordering::Less);
+        assert_eq!(compare_versions("1.3", "1.3"), Ordering::Equal);
+        assert!(VersionConstraint::new(VersionOperator::GreaterOrEqual, "3.4").matches("3.4.2"));
+        assert!(!VersionConstraint::new(VersionOperator::Less, "3.4").matches("3.4.2"));
+        assert!(VersionConstraint::new(VersionOperator::Equal, "1.8.0").matches("1.8"));
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest: ExtensionManifest = "postgis >= 3.4, pgcrypto\n# comment\nhstore=1.8\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            manifest
+                .requirements()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["postgis >= 3.4", "pgcrypto", "hstore = 1.8"]
+        );
+        for invalid in [">= 3.4", "postgis >=", "postgis ~ 3.4", "postgis >= 3 4"] {
+            assert!(matches!(
+                invalid.parse::<ExtensionRequirement>(),
+                Err(ExtensionError::InvalidRequirement(requirement)) if requirement == invalid
+            ));
+        }
+    }
+
+    #[test]
+    fn test_check_manifest() {
+        let mut postgis = extension("postgis", &["3.3.0", "3.4.0", "3.4.2"]);
+        postgis.installed_version = Some("3.3.0".to_owned());
+        postgis.installed_schema = Some("public".to_owned());
+        postgis.update_paths = vec![
+            UpdatePath::new("3.3.0".to_owned(), "3.4.0".to_owned(), "3.3.0--3.4.0"),
+            UpdatePath::new(
+                "3.3.0".to_owned(),
+                "3.4.2".to_owned(),
+                "3.3.0--3.4.0--3.4.2",
+            ),
+        ];
+        let mut pgcrypto = extension("pgcrypto", &["1.3"]);
+        pgcrypto.versions[0].requires = Some(vec!["postgis".to_owned(), "citext".to_owned()]);
+        let mut hstore = extension("hstore", &["1.8"]);
+        hstore.installed_version = Some("1.8".to_owned());
+        let extensions = [postgis, pgcrypto, hstore, extension("citext", &["1.6"])];
+
+        let plan: ExtensionManifest = "postgis >= 3.4, pgcrypto, hstore, missing, citext > 2"
+            .parse()
+            .unwrap();
+        let plan = plan.check(&extensions);
+        assert_eq!(
+            plan.to_string(),
+            "upgrade postgis 3.3.0 -> 3.4.2 via 3.3.0--3.4.0--3.4.2\n\
+             install pgcrypto 1.3 with citext\n\
+             ok hstore 1.8\n\
+             unsatisfiable missing: not available on the server\n\
+             unsatisfiable citext > 2: no available version matches, the newest is 1.6\n"
+        );
+        assert!(!plan.is_satisfied());
+        assert!(plan.has_unsatisfiable());
+        assert_eq!(
+            plan.statements(),
+            [
+                "ALTER EXTENSION \"postgis\" UPDATE TO '3.4.2';",
+                "CREATE EXTENSION IF NOT EXISTS \"pgcrypto\" VERSION '1.3' CASCADE;",
+            ]
+        );
+
+        let pinned: ExtensionManifest = "postgis = 3.4.0".parse().unwrap();
+        assert_eq!(
+            pinned.check(&extensions).statements(),
+            ["ALTER EXTENSION \"postgis\" UPDATE TO '3.4.0';"]
+        );
+        let downgrade: ExtensionManifest = "hstore < 1.8".parse().unwrap();
+        assert!(matches!(
+            &downgrade.check(&extensions).actions()[0],
+            ExtensionAction::Unsatisfiable { reason, .. } if reason.contains("no update path")
+        ));
+    }
+}
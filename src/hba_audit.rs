@@ -0,0 +1,1275 @@
+//! Submodule auditing the client authentication rules of `pg_hba.conf`.
+//!
+//! The rows of `pg_hba_file_rules` are decoded into typed [`HbaRule`]s, which
+//! an [`HbaAudit`] checks for:
+//!
+//! - rules the server could not parse, which make it refuse to reload the
+//!   file;
+//! - rules never applied, as an earlier rule matches all their connections;
+//! - `trust` rules reachable from addresses other than loopback ones;
+//! - rules referring to a user name map missing from `pg_ident.conf`.
+//!
+//! [`HbaAudit::matching_rule`] returns the rule the server applies to a
+//! connection, described by an [`HbaConnection`], as an [`HbaMatch`] telling
+//! whether rules with regular expressions or host names leave it undetermined.
+
+use std::{collections::BTreeSet, fmt::Display, net::IpAddr};
+
+use diesel::PgConnection;
+use ipnetwork::IpNetwork;
+
+use crate::models::{PgHbaFileRule, PgIdentFileMapping};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Errors that can occur when decoding a row of `pg_hba_file_rules`.
+pub enum HbaRuleError {
+    #[error("{0}")]
+    /// The server could not parse the rule.
+    Server(String),
+    #[error("Missing {0}")]
+    /// A field required by the connection type of the rule is missing.
+    MissingField(String),
+    #[error("Unknown connection type `{0}`")]
+    /// The connection type of the rule is not known.
+    UnknownConnectionType(String),
+    #[error("Invalid address `{address}` with netmask `{netmask}`")]
+    /// The netmask of the rule does not apply to its address.
+    InvalidAddress {
+        /// The address of the rule.
+        address: String,
+        /// The netmask of the rule.
+        netmask: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The kind of connections an [`HbaRule`] applies to.
+pub enum HbaConnectionType {
+    /// Connections over Unix-domain sockets.
+    Local,
+    /// TCP/IP connections, encrypted or not.
+    Host,
+    /// TCP/IP connections encrypted with SSL.
+    HostSsl,
+    /// TCP/IP connections not encrypted with SSL.
+    HostNoSsl,
+    /// TCP/IP connections encrypted with GSSAPI.
+    HostGssEnc,
+    /// TCP/IP connections not encrypted with GSSAPI.
+    HostNoGssEnc,
+}
+
+impl HbaConnectionType {
+    #[must_use]
+    /// Returns the connection type with the provided keyword, as in
+    /// `hostssl`.
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        Some(match keyword {
+            "local" => Self::Local,
+            "host" => Self::Host,
+            "hostssl" => Self::HostSsl,
+            "hostnossl" => Self::HostNoSsl,
+            "hostgssenc" => Self::HostGssEnc,
+            "hostnogssenc" => Self::HostNoGssEnc,
+            _ => return None,
+        })
+    }
+
+    #[must_use]
+    /// Returns the keyword of the connection type, as in `hostssl`.
+    pub fn keyword(self) -> &'static str {
+        match self {
+            Self::Local => "local",
+            Self::Host => "host",
+            Self::HostSsl => "hostssl",
+            Self::HostNoSsl => "hostnossl",
+            Self::HostGssEnc => "hostgssenc",
+            Self::HostNoGssEnc => "hostnogssenc",
+        }
+    }
+
+    #[must_use]
+    /// Returns whether every connection of the other type is also of this
+    /// type.
+    pub fn covers(self, other: Self) -> bool {
+        self == other || (self == Self::Host && other != Self::Local)
+    }
+}
+
+impl Display for HbaConnectionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.keyword())
+    }
+}
+
+/// Removes the double quotes around a token, which make keywords be read as
+/// names.
+fn unquote(token: &str) -> String {
+    token
+        .strip_prefix('"')
+        .and_then(|token| token.strip_suffix('"'))
+        .map_or_else(|| token.to_owned(), |token| token.replace("\"\"", "\""))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An entry of the database list of an [`HbaRule`].
+pub enum HbaDatabase {
+    /// The `all` keyword, matching every database.
+    All,
+    /// The `sameuser` keyword, matching the database named as the user.
+    SameUser,
+    /// The `samerole` keyword, or its `samegroup` alias, matching the
+    /// databases named as a role the user is a member of.
+    SameRole,
+    /// The `replication` keyword, matching physical replication connections.
+    Replication,
+    /// A regular expression, introduced by a slash.
+    Regex(String),
+    /// The name of a database.
+    Name(String),
+}
+
+impl HbaDatabase {
+    #[must_use]
+    /// Parses an entry of the database list, as in `pg_hba_file_rules`.
+    pub fn parse(token: &str) -> Self {
+        match token {
+            "all" => Self::All,
+            "sameuser" => Self::SameUser,
+            "samerole" | "samegroup" => Self::SameRole,
+            "replication" => Self::Replication,
+            _ => token.strip_prefix('/').map_or_else(
+                || Self::Name(unquote(token)),
+                |regex| Self::Regex(regex.to_owned()),
+            ),
+        }
+    }
+
+    /// Returns whether every database matched by the other entry is also
+    /// matched by this one.
+    fn covers(&self, other: &Self) -> bool {
+        self == other || (*self == Self::All && *other != Self::Replication)
+    }
+
+    /// Returns whether the entry matches the database of the connection.
+    ///
+    /// Physical replication connections are only matched by the
+    /// `replication` keyword, and regular expressions, which require the
+    /// server to evaluate, are assumed to match as provided.
+    fn matches(&self, connection: &HbaConnection, regex: bool) -> bool {
+        if connection.replication {
+            return *self == Self::Replication;
+        }
+        match self {
+            Self::All => true,
+            Self::SameUser => connection.database == connection.user,
+            Self::SameRole => connection.is_member_of(&connection.database),
+            Self::Replication => false,
+            Self::Regex(_) => regex,
+            Self::Name(name) => *name == connection.database,
+        }
+    }
+}
+
+impl Display for HbaDatabase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::All => f.write_str("all"),
+            Self::SameUser => f.write_str("sameuser"),
+            Self::SameRole => f.write_str("samerole"),
+            Self::Replication => f.write_str("replication"),
+            Self::Regex(regex) => write!(f, "/{regex}"),
+            Self::Name(name) if matches!(Self::parse(name), Self::Name(_)) => f.write_str(name),
+            Self::Name(name) => write!(f, "\"{name}\""),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An entry of the user list of an [`HbaRule`].
+pub enum HbaUser {
+    /// The `all` keyword, matching every user.
+    All,
+    /// A role prefixed by `+`, matching its members.
+    Group(String),
+    /// A regular expression, introduced by a slash.
+    Regex(String),
+    /// The name of a user.
+    Name(String),
+}
+
+impl HbaUser {
+    #[must_use]
+    /// Parses an entry of the user list, as in `pg_hba_file_rules`.
+    pub fn parse(token: &str) -> Self {
+        if token == "all" {
+            Self::All
+        } else if let Some(group) = token.strip_prefix('+') {
+            Self::Group(unquote(group))
+        } else if let Some(regex) = token.strip_prefix('/') {
+            Self::Regex(regex.to_owned())
+        } else {
+            Self::Name(unquote(token))
+        }
+    }
+
+    /// Returns whether every user matched by the other entry is also matched
+    /// by this one.
+    fn covers(&self, other: &Self) -> bool {
+        self == other || *self == Self::All
+    }
+
+    /// Returns whether the entry matches the user of the connection.
+    ///
+    /// Regular expressions, which require the server to evaluate, are assumed
+    /// to match as provided.
+    fn matches(&self, connection: &HbaConnection, regex: bool) -> bool {
+        match self {
+            Self::All => true,
+            Self::Group(group) => connection.is_member_of(group),
+            Self::Regex(_) => regex,
+            Self::Name(name) => *name == connection.user,
+        }
+    }
+}
+
+impl Display for HbaUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::All => f.write_str("all"),
+            Self::Group(group) => write!(f, "+{group}"),
+            Self::Regex(regex) => write!(f, "/{regex}"),
+            Self::Name(name) if name == "all" => f.write_str("\"all\""),
+            Self::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The client addresses an [`HbaRule`] applies to.
+pub enum HbaAddress {
+    /// The `all` keyword, matching every address.
+    Any,
+    /// The `samehost` keyword, matching the addresses of the server.
+    SameHost,
+    /// The `samenet` keyword, matching the subnets of the server.
+    SameNet,
+    /// An IP network.
+    Network(IpNetwork),
+    /// A host name, or a domain suffix when starting with a dot.
+    Hostname(String),
+}
+
+impl HbaAddress {
+    /// Parses the address of a rule, as split into an address and a netmask
+    /// by `pg_hba_file_rules`.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address, as in `10.0.0.0` or `samenet`.
+    /// * `netmask` - The netmask of an IP address, as in `255.0.0.0`.
+    ///
+    /// # Errors
+    ///
+    /// * If the netmask is not valid for the address.
+    pub fn parse(address: &str, netmask: Option<&str>) -> Result<Self, HbaRuleError> {
+        Ok(match address {
+            "all" => Self::Any,
+            "samehost" => Self::SameHost,
+            "samenet" => Self::SameNet,
+            _ => {
+                let Ok(ip) = address.parse::<IpAddr>() else {
+                    return Ok(Self::Hostname(address.to_owned()));
+                };
+                let Some(netmask) = netmask else {
+                    return Ok(Self::Network(IpNetwork::from(ip)));
+                };
+                let invalid = || HbaRuleError::InvalidAddress {
+                    address: address.to_owned(),
+                    netmask: netmask.to_owned(),
+                };
+                let mask = netmask.parse::<IpAddr>().map_err(|_| invalid())?;
+                let network = IpNetwork::with_netmask(ip, mask).map_err(|_| invalid())?;
+                Self::Network(network)
+            }
+        })
+    }
+
+    #[must_use]
+    /// Returns whether the address can only be reached from the server
+    /// itself.
+    pub fn is_loopback(&self) -> bool {
+        match self {
+            Self::SameHost => true,
+            Self::Network(network) => {
+                network.network().is_loopback() && network.broadcast().is_loopback()
+            }
+            Self::Hostname(hostname) => hostname == "localhost",
+            Self::Any | Self::SameNet => false,
+        }
+    }
+
+    /// Returns whether every address matched by the other address is also
+    /// matched by this one.
+    fn covers(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Any, _) => true,
+            (Self::Network(network), Self::Network(other)) => {
+                network.prefix() <= other.prefix() && network.contains(other.network())
+            }
+            _ => self == other,
+        }
+    }
+
+    /// Returns whether the address matches the client address, given the
+    /// networks of the server.
+    ///
+    /// Host names, which require name resolution, are assumed to match as
+    /// provided.
+    fn matches(&self, client: IpAddr, server_networks: &[IpNetwork], hostname: bool) -> bool {
+        match self {
+            Self::Any => true,
+            Self::SameHost => server_networks.iter().any(|network| network.ip() == client),
+            Self::SameNet => server_networks
+                .iter()
+                .any(|network| network.contains(client)),
+            Self::Network(network) => network.contains(client),
+            Self::Hostname(_) => hostname,
+        }
+    }
+}
+
+impl Display for HbaAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Any => f.write_str("all"),
+            Self::SameHost => f.write_str("samehost"),
+            Self::SameNet => f.write_str("samenet"),
+            Self::Network(network) => write!(f, "{}/{}", network.network(), network.prefix()),
+            Self::Hostname(hostname) => f.write_str(hostname),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The authentication method of an [`HbaRule`].
+pub enum HbaAuthMethod {
+    /// Allows the connection without any password or authentication.
+    Trust,
+    /// Rejects the connection.
+    Reject,
+    /// SCRAM-SHA-256 password authentication.
+    ScramSha256,
+    /// MD5 or SCRAM-SHA-256 password authentication, depending on the stored
+    /// password.
+    Md5,
+    /// Clear-text password authentication.
+    Password,
+    /// GSSAPI authentication.
+    Gss,
+    /// SSPI authentication.
+    Sspi,
+    /// Authentication by the ident server of the client.
+    Ident,
+    /// Authentication by the operating system user of the client.
+    Peer,
+    /// PAM authentication.
+    Pam,
+    /// LDAP authentication.
+    Ldap,
+    /// RADIUS authentication.
+    Radius,
+    /// SSL client certificate authentication.
+    Cert,
+    /// BSD authentication.
+    Bsd,
+    /// OAuth bearer token authentication.
+    OAuth,
+    /// Any other method.
+    Other(String),
+}
+
+impl HbaAuthMethod {
+    #[must_use]
+    /// Returns the method with the provided keyword, as in `scram-sha-256`.
+    pub fn from_keyword(keyword: &str) -> Self {
+        match keyword {
+            "trust" => Self::Trust,
+            "reject" => Self::Reject,
+            "scram-sha-256" => Self::ScramSha256,
+            "md5" => Self::Md5,
+            "password" => Self::Password,
+            "gss" => Self::Gss,
+            "sspi" => Self::Sspi,
+            "ident" => Self::Ident,
+            "peer" => Self::Peer,
+            "pam" => Self::Pam,
+            "ldap" => Self::Ldap,
+            "radius" => Self::Radius,
+            "cert" => Self::Cert,
+            "bsd" => Self::Bsd,
+            "oauth" => Self::OAuth,
+            _ => Self::Other(keyword.to_owned()),
+        }
+    }
+
+    #[must_use]
+    /// Returns the keyword of the method, as in `scram-sha-256`.
+    pub fn keyword(&self) -> &str {
+        match self {
+            Self::Trust => "trust",
+            Self::Reject => "reject",
+            Self::ScramSha256 => "scram-sha-256",
+            Self::Md5 => "md5",
+            Self::Password => "password",
+            Self::Gss => "gss",
+            Self::Sspi => "sspi",
+            Self::Ident => "ident",
+            Self::Peer => "peer",
+            Self::Pam => "pam",
+            Self::Ldap => "ldap",
+            Self::Radius => "radius",
+            Self::Cert => "cert",
+            Self::Bsd => "bsd",
+            Self::OAuth => "oauth",
+            Self::Other(keyword) => keyword,
+        }
+    }
+}
+
+impl Display for HbaAuthMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.keyword())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Where a rule or a mapping is defined.
+pub struct HbaLocation {
+    /// The file defining the rule, not reported before `PostgreSQL` 16.
+    file_name: Option<String>,
+    /// The line of the rule in the file.
+    line_number: Option<i32>,
+}
+
+impl HbaLocation {
+    /// Returns the file defining the rule, not reported before `PostgreSQL`
+    /// 16.
+    #[must_use]
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// Returns the line of the rule in the file.
+    #[must_use]
+    pub fn line_number(&self) -> Option<i32> {
+        self.line_number
+    }
+}
+
+impl From<&PgHbaFileRule> for HbaLocation {
+    fn from(rule: &PgHbaFileRule) -> Self {
+        Self {
+            #[cfg(not(any(feature = "postgres-15", feature = "postgres-14")))]
+            file_name: rule.file_name.clone(),
+            #[cfg(any(feature = "postgres-15", feature = "postgres-14"))]
+            file_name: None,
+            line_number: rule.line_number,
+        }
+    }
+}
+
+impl From<&PgIdentFileMapping> for HbaLocation {
+    fn from(mapping: &PgIdentFileMapping) -> Self {
+        Self {
+            #[cfg(not(any(feature = "postgres-15", feature = "postgres-14")))]
+            file_name: mapping.file_name.clone(),
+            #[cfg(any(feature = "postgres-15", feature = "postgres-14"))]
+            file_name: None,
+            line_number: mapping.line_number,
+        }
+    }
+}
+
+impl Display for HbaLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file_name) = &self.file_name {
+            write!(f, "{file_name}:")?;
+        }
+        match self.line_number {
+            Some(line_number) => write!(f, "line {line_number}"),
+            None => f.write_str("unknown line"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A decoded rule of `pg_hba.conf`.
+pub struct HbaRule {
+    /// Where the rule is defined.
+    location: HbaLocation,
+    /// The kind of connections the rule applies to.
+    connection_type: HbaConnectionType,
+    /// The databases the rule applies to.
+    databases: Vec<HbaDatabase>,
+    /// The users the rule applies to.
+    users: Vec<HbaUser>,
+    /// The client addresses the rule applies to, absent for `local` rules.
+    address: Option<HbaAddress>,
+    /// The authentication method of the rule.
+    auth_method: HbaAuthMethod,
+    /// The options of the authentication method, as in `map=omicron`.
+    options: Vec<String>,
+}
+
+impl TryFrom<&PgHbaFileRule> for HbaRule {
+    type Error = HbaRuleError;
+
+    fn try_from(rule: &PgHbaFileRule) -> Result<Self, Self::Error> {
+        if let Some(error) = &rule.error {
+            return Err(HbaRuleError::Server(error.clone()));
+        }
+        let keyword = rule
+            .r#type
+            .as_deref()
+            .ok_or_else(|| HbaRuleError::MissingField("connection type".to_owned()))?;
+        let connection_type = HbaConnectionType::from_keyword(keyword)
+            .ok_or_else(|| HbaRuleError::UnknownConnectionType(keyword.to_owned()))?;
+        let address = match (connection_type, rule.address.as_deref()) {
+            (HbaConnectionType::Local, _) => None,
+            (_, Some(address)) => Some(HbaAddress::parse(address, rule.netmask.as_deref())?),
+            (_, None) => return Err(HbaRuleError::MissingField("address".to_owned())),
+        };
+        Ok(Self {
+            location: HbaLocation::from(rule),
+            connection_type,
+            databases: rule
+                .database
+                .iter()
+                .flatten()
+                .map(|token| HbaDatabase::parse(token))
+                .collect(),
+            users: rule
+                .user_name
+                .iter()
+                .flatten()
+                .map(|token| HbaUser::parse(token))
+                .collect(),
+            address,
+            auth_method: HbaAuthMethod::from_keyword(
+                rule.auth_method.as_deref().ok_or_else(|| {
+                    HbaRuleError::MissingField("authentication method".to_owned())
+                })?,
+            ),
+            options: rule.options.clone().unwrap_or_default(),
+        })
+    }
+}
+
+impl HbaRule {
+    /// Returns where the rule is defined.
+    #[must_use]
+    pub fn location(&self) -> &HbaLocation {
+        &self.location
+    }
+
+    /// Returns the kind of connections the rule applies to.
+    #[must_use]
+    pub fn connection_type(&self) -> HbaConnectionType {
+        self.connection_type
+    }
+
+    /// Returns the databases the rule applies to.
+    #[must_use]
+    pub fn databases(&self) -> &[HbaDatabase] {
+        &self.databases
+    }
+
+    /// Returns the users the rule applies to.
+    #[must_use]
+    pub fn users(&self) -> &[HbaUser] {
+        &self.users
+    }
+
+    /// Returns the client addresses the rule applies to, absent for `local`
+    /// rules.
+    #[must_use]
+    pub fn address(&self) -> Option<&HbaAddress> {
+        self.address.as_ref()
+    }
+
+    /// Returns the authentication method of the rule.
+    #[must_use]
+    pub fn auth_method(&self) -> &HbaAuthMethod {
+        &self.auth_method
+    }
+
+    /// Returns the options of the authentication method, as in
+    /// `map=omicron`.
+    #[must_use]
+    pub fn options(&self) -> &[String] {
+        &self.options
+    }
+
+    /// Returns the value of the option with the provided name.
+    #[must_use]
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.iter().find_map(|option| {
+            option
+                .split_once('=')
+                .filter(|(option_name, _)| *option_name == name)
+                .map(|(_, value)| value)
+        })
+    }
+
+    #[must_use]
+    /// Returns whether the rule trusts connections from addresses other than
+    /// loopback ones.
+    pub fn is_remote_trust(&self) -> bool {
+        self.auth_method == HbaAuthMethod::Trust
+            && self
+                .address
+                .as_ref()
+                .is_some_and(|address| !address.is_loopback())
+    }
+
+    #[must_use]
+    /// Returns whether every connection matched by the other rule is also
+    /// matched by this one.
+    ///
+    /// The check is conservative: regular expressions, host names and
+    /// group memberships only cover identical entries.
+    pub fn covers(&self, other: &Self) -> bool {
+        let addresses = match (&self.address, &other.address) {
+            (None, None) => true,
+            (Some(address), Some(other)) => address.covers(other),
+            _ => false,
+        };
+        addresses
+            && self.connection_type.covers(other.connection_type)
+            && other.databases.iter().all(|database| {
+                self.databases
+                    .iter()
+                    .any(|covering| covering.covers(database))
+            })
+            && other
+                .users
+                .iter()
+                .all(|user| self.users.iter().any(|covering| covering.covers(user)))
+    }
+
+    #[must_use]
+    /// Returns whether the rule matches the provided connection.
+    ///
+    /// Regular expressions and host names are never matched, as evaluating
+    /// them requires the server or a name resolution.
+    pub fn matches(&self, connection: &HbaConnection) -> bool {
+        self.matches_assuming(connection, false)
+    }
+
+    /// Returns whether the rule may match the provided connection, that is
+    /// whether it matches when its regular expressions and host names do.
+    fn may_match(&self, connection: &HbaConnection) -> bool {
+        self.matches_assuming(connection, true)
+    }
+
+    /// Returns whether the rule matches the provided connection, assuming
+    /// its regular expressions and host names match as provided.
+    fn matches_assuming(&self, connection: &HbaConnection, undecidable: bool) -> bool {
+        let transport = match (connection.address, &self.address) {
+            (None, _) => self.connection_type == HbaConnectionType::Local,
+            (Some(client), Some(address)) => {
+                address.matches(client, &connection.server_networks, undecidable)
+                    && match self.connection_type {
+                        HbaConnectionType::Local => false,
+                        HbaConnectionType::Host => true,
+                        HbaConnectionType::HostSsl => connection.ssl,
+                        HbaConnectionType::HostNoSsl => !connection.ssl,
+                        HbaConnectionType::HostGssEnc => connection.gss_encrypted,
+                        HbaConnectionType::HostNoGssEnc => !connection.gss_encrypted,
+                    }
+            }
+            (Some(_), None) => false,
+        };
+        transport
+            && self
+                .databases
+                .iter()
+                .any(|database| database.matches(connection, undecidable))
+            && self
+                .users
+                .iter()
+                .any(|user| user.matches(connection, undecidable))
+    }
+}
+
+impl Display for HbaRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        /// Writes the entries of a list separated by commas.
+        fn join<T: Display>(entries: &[T]) -> String {
+            entries
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+        write!(
+            f,
+            "{} {} {}",
+            self.connection_type,
+            join(&self.databases),
+            join(&self.users)
+        )?;
+        if let Some(address) = &self.address {
+            write!(f, " {address}")?;
+        }
+        write!(f, " {}", self.auth_method)?;
+        for option in &self.options {
+            write!(f, " {option}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A connection attempt, to find the rule of `pg_hba.conf` applied to it.
+pub struct HbaConnection {
+    /// The user connecting.
+    user: String,
+    /// The database connected to.
+    database: String,
+    /// The address of the client, absent for Unix-domain sockets.
+    address: Option<IpAddr>,
+    /// Whether the connection is encrypted with SSL.
+    ssl: bool,
+    /// Whether the connection is encrypted with GSSAPI.
+    gss_encrypted: bool,
+    /// Whether the connection is a physical replication connection.
+    replication: bool,
+    /// The roles the user is a member of, directly or not.
+    roles: BTreeSet<String>,
+    /// The networks of the interfaces of the server, matched by `samehost`
+    /// and `samenet`.
+    server_networks: Vec<IpNetwork>,
+}
+
+impl HbaConnection {
+    /// Creates a new `HbaConnection` over a Unix-domain socket.
+    #[must_use]
+    pub fn local<U: AsRef<str>, D: AsRef<str>>(user: U, database: D) -> Self {
+        Self {
+            user: user.as_ref().to_string(),
+            database: database.as_ref().to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new `HbaConnection` over TCP/IP from the provided address.
+    #[must_use]
+    pub fn host<U: AsRef<str>, D: AsRef<str>>(user: U, database: D, address: IpAddr) -> Self {
+        Self {
+            address: Some(address),
+            ..Self::local(user, database)
+        }
+    }
+
+    #[must_use]
+    /// Sets whether the connection is encrypted with SSL.
+    pub fn ssl(mut self, ssl: bool) -> Self {
+        self.ssl = ssl;
+        self
+    }
+
+    #[must_use]
+    /// Sets whether the connection is encrypted with GSSAPI.
+    pub fn gss_encrypted(mut self, gss_encrypted: bool) -> Self {
+        self.gss_encrypted = gss_encrypted;
+        self
+    }
+
+    #[must_use]
+    /// Sets whether the connection is a physical replication connection,
+    /// which only rules listing the `replication` keyword match.
+    pub fn replication(mut self, replication: bool) -> Self {
+        self.replication = replication;
+        self
+    }
+
+    #[must_use]
+    /// Adds a role the user is a member of, directly or not, matched by
+    /// `+role` entries and `samerole`.
+    pub fn member_of<S: AsRef<str>>(mut self, role: S) -> Self {
+        self.roles.insert(role.as_ref().to_string());
+        self
+    }
+
+    #[must_use]
+    /// Adds a network of an interface of the server, matched by `samehost`
+    /// and `samenet`.
+    pub fn server_network(mut self, network: IpNetwork) -> Self {
+        self.server_networks.push(network);
+        self
+    }
+
+    /// Returns whether the user is the provided role or one of its members.
+    fn is_member_of(&self, role: &str) -> bool {
+        self.user == role || self.roles.contains(role)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An issue found in the client authentication configuration.
+pub enum HbaFinding {
+    /// A rule which could not be parsed or decoded, and makes the server
+    /// refuse to reload `pg_hba.conf`.
+    InvalidRule {
+        /// Where the rule is defined.
+        location: HbaLocation,
+        /// Why the rule is invalid.
+        error: HbaRuleError,
+    },
+    /// A rule never applied, as every connection it matches is matched by an
+    /// earlier rule first.
+    ShadowedRule {
+        /// Where the shadowed rule is defined.
+        location: HbaLocation,
+        /// Where the earlier rule is defined.
+        shadowed_by: HbaLocation,
+    },
+    /// A `trust` rule reachable from addresses other than loopback ones.
+    RemoteTrust {
+        /// Where the rule is defined.
+        location: HbaLocation,
+        /// The addresses the rule trusts.
+        address: HbaAddress,
+    },
+    /// A rule referring to a user name map missing from `pg_ident.conf`.
+    MissingIdentMap {
+        /// Where the rule is defined.
+        location: HbaLocation,
+        /// The name of the map.
+        map: String,
+    },
+    /// A mapping of `pg_ident.conf` which could not be parsed.
+    InvalidIdentMapping {
+        /// Where the mapping is defined.
+        location: HbaLocation,
+        /// Why the mapping is invalid.
+        error: String,
+    },
+}
+
+impl HbaFinding {
+    /// Returns where the rule or mapping the finding is about is defined.
+    #[must_use]
+    pub fn location(&self) -> &HbaLocation {
+        match self {
+            Self::InvalidRule { location, .. }
+            | Self::ShadowedRule { location, .. }
+            | Self::RemoteTrust { location, .. }
+            | Self::MissingIdentMap { location, .. }
+            | Self::InvalidIdentMapping { location, .. } => location,
+        }
+    }
+}
+
+impl Display for HbaFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidRule { location, error } => {
+                write!(f, "{location}: invalid rule: {error}")
+            }
+            Self::ShadowedRule {
+                location,
+                shadowed_by,
+            } => {
+                write!(f, "{location}: rule shadowed by the rule at {shadowed_by}")
+            }
+            Self::RemoteTrust { location, address } => {
+                write!(f, "{location}: trust authentication from {address}")
+            }
+            Self::MissingIdentMap { location, map } => {
+                write!(
+                    f,
+                    "{location}: user name map `{map}` is not defined in pg_ident.conf"
+                )
+            }
+            Self::InvalidIdentMapping { location, error } => {
+                write!(f, "{location}: invalid user name mapping: {error}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The rule of an [`HbaAudit`] applying to a connection.
+pub enum HbaMatch<'audit> {
+    /// The rule matches the connection, and the server applies it.
+    Matched(&'audit HbaRule),
+    /// The rule may match the connection, depending on its regular
+    /// expressions or host names: the server applies it if it does, and one
+    /// of the following rules otherwise.
+    Undetermined(&'audit HbaRule),
+}
+
+impl<'audit> HbaMatch<'audit> {
+    #[must_use]
+    /// Returns the rule, whether it surely matches or not.
+    pub fn rule(self) -> &'audit HbaRule {
+        match self {
+            Self::Matched(rule) | Self::Undetermined(rule) => rule,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The client authentication configuration of a server, from
+/// `pg_hba_file_rules` and `pg_ident_file_mappings`.
+pub struct HbaAudit {
+    /// The valid rules, in the order the server checks them.
+    rules: Vec<HbaRule>,
+    /// The rules which could not be parsed or decoded.
+    invalid_rules: Vec<(HbaLocation, HbaRuleError)>,
+    /// The names of the valid user name maps.
+    ident_maps: BTreeSet<String>,
+    /// The mappings which could not be parsed.
+    invalid_mappings: Vec<(HbaLocation, String)>,
+}
+
+impl HbaAudit {
+    /// Loads the client authentication configuration of the server.
+    ///
+    /// Reading `pg_hba_file_rules` and `pg_ident_file_mappings` requires
+    /// superuser privileges.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load(conn: &mut PgConnection) -> Result<Self, diesel::result::Error> {
+        Ok(Self::new(
+            &PgHbaFileRule::load_all(conn)?,
+            &PgIdentFileMapping::load_all(conn)?,
+        ))
+    }
+
+    #[must_use]
+    /// Creates a new `HbaAudit` from the rows of `pg_hba_file_rules`, in the
+    /// order of the file, and of `pg_ident_file_mappings`.
+    pub fn new(rules: &[PgHbaFileRule], mappings: &[PgIdentFileMapping]) -> Self {
+        let mut audit = Self {
+            rules: Vec::new(),
+            invalid_rules: Vec::new(),
+            ident_maps: BTreeSet::new(),
+            invalid_mappings: Vec::new(),
+        };
+        for rule in rules {
+            match HbaRule::try_from(rule) {
+                Ok(decoded) => audit.rules.push(decoded),
+                Err(error) => audit.invalid_rules.push((HbaLocation::from(rule), error)),
+            }
+        }
+        for mapping in mappings {
+            match (&mapping.error, &mapping.map_name) {
+                (Some(error), _) => {
+                    audit
+                        .invalid_mappings
+                        .push((HbaLocation::from(mapping), error.clone()));
+                }
+                (None, Some(map_name)) => {
+                    audit.ident_maps.insert(map_name.clone());
+                }
+                (None, None) => {}
+            }
+        }
+        audit
+    }
+
+    /// Returns the valid rules, in the order the server checks them.
+    #[must_use]
+    pub fn rules(&self) -> &[HbaRule] {
+        &self.rules
+    }
+
+    /// Returns the names of the valid user name maps of `pg_ident.conf`.
+    pub fn ident_maps(&self) -> impl Iterator<Item = &str> {
+        self.ident_maps.iter().map(String::as_str)
+    }
+
+    #[must_use]
+    /// Returns the rule the server applies to the provided connection, if
+    /// any: connections matching no rule are rejected.
+    ///
+    /// Regular expressions and host names are not evaluated, as this requires
+    /// the server or a name resolution. When a rule using them may match the
+    /// connection before any rule surely does, it is returned as
+    /// [`HbaMatch::Undetermined`].
+    pub fn matching_rule(&self, connection: &HbaConnection) -> Option<HbaMatch<'_>> {
+        self.rules
+            .iter()
+            .find(|rule| rule.may_match(connection))
+            .map(|rule| {
+                if rule.matches(connection) {
+                    HbaMatch::Matched(rule)
+                } else {
+                    HbaMatch::Undetermined(rule)
+                }
+            })
+    }
+
+    #[must_use]
+    /// Returns the issues found in the configuration, invalid rules and
+    /// mappings first, then by rule.
+    pub fn findings(&self) -> Vec<HbaFinding> {
+        let mut findings: Vec<HbaFinding> = self
+            .invalid_rules
+            .iter()
+            .map(|(location, error)| HbaFinding::InvalidRule {
+                location: location.clone(),
+                error: error.clone(),
+            })
+            .chain(self.invalid_mappings.iter().map(|(location, error)| {
+                HbaFinding::InvalidIdentMapping {
+                    location: location.clone(),
+                    error: error.clone(),
+                }
+            }))
+            .collect();
+        for (position, rule) in self.rules.iter().enumerate() {
+            if let Some(earlier) = self.rules[..position]
+                .iter()
+                .find(|earlier| earlier.covers(rule))
+            {
+                findings.push(HbaFinding::ShadowedRule {
+                    location: rule.location.clone(),
+                    shadowed_by: earlier.location.clone(),
+                });
+            }
+            if rule.is_remote_trust()
+                && let Some(address) = &rule.address
+            {
+                findings.push(HbaFinding::RemoteTrust {
+                    location: rule.location.clone(),
+                    address: address.clone(),
+                });
+            }
+            if let Some(map) = rule.option("map")
+                && !self.ident_maps.contains(map)
+            {
+                findings.push(HbaFinding::MissingIdentMap {
+                    location: rule.location.clone(),
+                    map: map.to_owned(),
+                });
+            }
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a row of `pg_hba_file_rules` at the provided line.
+    fn rule(line_number: i32, fields: &str) -> PgHbaFileRule {
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+        let list = |field: &str| Some(field.split(',').map(str::to_owned).collect());
+        let (address, netmask, rest) = if fields[0] == "local" {
+            (None, None, &fields[3..])
+        } else {
+            let (address, netmask) = match fields[3].split_once('/') {
+                Some((address, "8")) => (address, Some("255.0.0.0")),
+                Some((address, "16")) => (address, Some("255.255.0.0")),
+                Some((address, "24")) => (address, Some("255.255.255.0")),
+                Some((address, "32")) => (address, Some("255.255.255.255")),
+                Some((address, "0")) => (address, Some("0.0.0.0")),
+                Some((address, "128")) => {
+                    (address, Some("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"))
+                }
+                _ => (fields[3], None),
+            };
+            (
+                Some(address.to_owned()),
+                netmask.map(str::to_owned),
+                &fields[4..],
+            )
+        };
+        PgHbaFileRule {
+            #[cfg(not(any(feature = "postgres-15", feature = "postgres-14")))]
+            rule_number: Some(line_number),
+            #[cfg(not(any(feature = "postgres-15", feature = "postgres-14")))]
+            file_name: Some("pg_hba.conf".to_owned()),
+            line_number: Some(line_number),
+            r#type: Some(fields[0].to_owned()),
+            database: list(fields[1]),
+            user_name: list(fields[2]),
+            address,
+            netmask,
+            auth_method: Some(rest[0].to_owned()),
+            options: Some(
+                rest[1..]
+                    .iter()
+                    .map(|option| (*option).to_owned())
+                    .collect(),
+            ),
+            error: None,
+        }
+    }
+
+    /// Returns the audit of the test configuration.
+    fn audit() -> HbaAudit {
+        let mut invalid = rule(11, "local all all trust");
+        invalid.r#type = None;
+        invalid.error = Some("invalid authentication method \"foo\"".to_owned());
+        let rules = [
+            rule(1, "local all postgres peer"),
+            rule(2, "local all all scram-sha-256"),
+            rule(3, "local mydb alice trust"),
+            rule(4, "host all all 127.0.0.1/32 trust"),
+            rule(5, "host all all ::1/128 trust"),
+            rule(6, "host all all 10.0.0.0/8 scram-sha-256"),
+            rule(7, "hostssl app +staff 10.1.0.0/16 md5"),
+            rule(8, "hostnossl all bob 172.16.0.0/16 ident map=office"),
+            rule(9, "hostssl all +staff samenet cert map=staff"),
+            rule(10, "host all all 0.0.0.0/0 trust"),
+            invalid,
+            rule(12, "host replication repl 192.168.0.0/24 trust"),
+        ];
+        let mapping = PgIdentFileMapping {
+            #[cfg(not(any(feature = "postgres-15", feature = "postgres-14")))]
+            map_number: Some(1),
+            #[cfg(not(any(feature = "postgres-15", feature = "postgres-14")))]
+            file_name: Some("pg_ident.conf".to_owned()),
+            line_number: Some(1),
+            map_name: Some("staff".to_owned()),
+            sys_name: Some("carol".to_owned()),
+            pg_username: Some("carol".to_owned()),
+            error: None,
+        };
+        HbaAudit::new(&rules, &[mapping])
+    }
+
+    #[test]
+    fn test_decoding() {
+        let audit = audit();
+        assert_eq!(audit.rules().len(), 11);
+        let rule = &audit.rules()[6];
+        assert_eq!(rule.connection_type(), HbaConnectionType::HostSsl);
+        assert_eq!(rule.databases(), [HbaDatabase::Name("app".to_owned())]);
+        assert_eq!(rule.users(), [HbaUser::Group("staff".to_owned())]);
+        assert_eq!(
+            rule.address(),
+            Some(&HbaAddress::Network("10.1.0.0/16".parse().unwrap()))
+        );
+        assert_eq!(rule.auth_method(), &HbaAuthMethod::Md5);
+        assert_eq!(rule.to_string(), "hostssl app +staff 10.1.0.0/16 md5");
+        assert_eq!(audit.rules()[7].option("map"), Some("office"));
+        assert_eq!(
+            HbaDatabase::parse("\"all\""),
+            HbaDatabase::Name("all".to_owned())
+        );
+        assert_eq!(HbaDatabase::parse("samegroup"), HbaDatabase::SameRole);
+        assert!(matches!(
+            HbaAddress::parse("10.0.0.1", Some("255.0.255.0")),
+            Err(HbaRuleError::InvalidAddress { .. })
+        ));
+    }
+
+    #[test]
+    fn test_findings() {
+        let findings: Vec<String> = audit().findings().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            findings,
+            [
+                "pg_hba.conf:line 11: invalid rule: invalid authentication method \"foo\"",
+                "pg_hba.conf:line 3: rule shadowed by the rule at pg_hba.conf:line 2",
+                "pg_hba.conf:line 7: rule shadowed by the rule at pg_hba.conf:line 6",
+                "pg_hba.conf:line 8: user name map `office` is not defined in pg_ident.conf",
+                "pg_hba.conf:line 10: trust authentication from 0.0.0.0/0",
+                "pg_hba.conf:line 12: trust authentication from 192.168.0.0/24",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matching_rule() {
+        let audit = audit();
+        let line = |connection: &HbaConnection| match audit.matching_rule(connection) {
+            Some(HbaMatch::Matched(rule)) => rule.location().line_number(),
+            Some(HbaMatch::Undetermined(rule)) => panic!("Undetermined match by `{rule}`"),
+            None => None,
+        };
+        let localhost = "127.0.0.1".parse().unwrap();
+        let remote = "203.0.113.5".parse().unwrap();
+        assert_eq!(line(&HbaConnection::local("postgres", "mydb")), Some(1));
+        assert_eq!(line(&HbaConnection::local("alice", "mydb")), Some(2));
+        assert_eq!(
+            line(&HbaConnection::host("alice", "mydb", localhost)),
+            Some(4)
+        );
+        assert_eq!(
+            line(&HbaConnection::host("carol", "app", "10.1.2.3".parse().unwrap()).ssl(true)),
+            Some(6)
+        );
+        let staff = HbaConnection::host("carol", "app", "192.0.2.7".parse().unwrap())
+            .ssl(true)
+            .member_of("staff");
+        assert_eq!(line(&staff), Some(10));
+        assert_eq!(
+            line(&staff.server_network("192.0.2.1/24".parse().unwrap())),
+            Some(9)
+        );
+        assert_eq!(line(&HbaConnection::host("dave", "foo", remote)), Some(10));
+        let replication =
+            |address| HbaConnection::host("repl", "postgres", address).replication(true);
+        assert_eq!(line(&replication("192.168.0.7".parse().unwrap())), Some(12));
+        assert_eq!(line(&replication(remote)), None);
+    }
+
+    #[test]
+    fn test_undetermined_rule() {
+        let rules = [
+            rule(1, "host all all .example.com trust"),
+            rule(2, "local all postgres peer"),
+            rule(3, "local all /^app_ peer"),
+            rule(4, "local all all scram-sha-256"),
+            rule(5, "host all all 0.0.0.0/0 scram-sha-256"),
+        ];
+        let audit = HbaAudit::new(&rules, &[]);
+        let line = |connection: &HbaConnection| {
+            audit
+                .matching_rule(connection)
+                .map(|found| (found, found.rule().location().line_number()))
+        };
+        assert!(matches!(
+            line(&HbaConnection::host(
+                "alice",
+                "mydb",
+                "203.0.113.5".parse().unwrap()
+            )),
+            Some((HbaMatch::Undetermined(_), Some(1)))
+        ));
+        assert!(matches!(
+            line(&HbaConnection::local("postgres", "mydb")),
+            Some((HbaMatch::Matched(_), Some(2)))
+        ));
+        assert!(matches!(
+            line(&HbaConnection::local("app_web", "mydb")),
+            Some((HbaMatch::Undetermined(_), Some(3)))
+        ));
+    }
+}
@@ -0,0 +1,234 @@
+//! Submodule providing typed identifiers of `PostgreSQL` objects.
+//!
+//! The system catalogs identify objects by OIDs, plain `u32`s which are easily
+//! mixed up: the OID of a table, of its row type and of a function are all
+//! numbers. [`RelOid`], [`TypeOid`], [`ProcOid`] and [`TsConfigOid`] wrap
+//! the OIDs of relations (`pg_class`), types (`pg_type`), functions
+//! (`pg_proc`) and text search configurations (`pg_ts_config`) so that they
+//! cannot be confused, while [`QualifiedName`] identifies the objects of
+//! the information schema, which carry no OIDs, by their schema and name.
+//!
+//! The models carrying such identifiers implement
+//! [`HasRelOid`](crate::traits::HasRelOid),
+//! [`HasTypeOid`](crate::traits::HasTypeOid),
+//! [`HasProcOid`](crate::traits::HasProcOid) and
+//! [`HasQualifiedName`](crate::traits::HasQualifiedName), and
+//! [`ObjectIndex`](crate::database::ObjectIndex) looks up the objects of a
+//! [`PgDieselDatabase`](crate::PgDieselDatabase) by either.
+
+use std::{fmt::Display, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+/// Errors that can occur when parsing a [`QualifiedName`].
+pub enum IdentityError {
+    #[error("Invalid qualified name `{0}`, expected `<schema>.<name>`")]
+    /// The name is not made of a schema and a name separated by a dot.
+    InvalidQualifiedName(String),
+}
+
+/// Defines a newtype wrapping the OID of a kind of object.
+macro_rules! typed_oid {
+    ($(#[$doc:meta])* $name:ident, $catalog:literal) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        $(#[$doc])*
+        pub struct $name(u32);
+
+        impl $name {
+            #[must_use]
+            #[doc = concat!("Wraps the OID of a row of `", $catalog, "`.")]
+            pub const fn new(oid: u32) -> Self {
+                Self(oid)
+            }
+
+            #[must_use]
+            /// Returns the OID as a plain `u32`.
+            pub const fn get(self) -> u32 {
+                self.0
+            }
+        }
+
+        impl From<u32> for $name {
+            fn from(oid: u32) -> Self {
+                Self(oid)
+            }
+        }
+
+        impl From<$name> for u32 {
+            fn from(oid: $name) -> Self {
+                oid.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+    };
+}
+
+typed_oid!(
+    /// The OID of a relation, such as a table, view, index or sequence, as in
+    /// `pg_class.oid`.
+    RelOid,
+    "pg_class"
+);
+
+typed_oid!(
+    /// The OID of a type, as in `pg_type.oid`.
+    TypeOid,
+    "pg_type"
+);
+
+typed_oid!(
+    /// The OID of a function, procedure or aggregate, as in `pg_proc.oid`.
+    ProcOid,
+    "pg_proc"
+);
+
+typed_oid!(
+    /// The OID of a text search configuration, as in `pg_ts_config.oid`.
+    TsConfigOid,
+    "pg_ts_config"
+);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The name of an object qualified by its schema, as in `public.users`.
+pub struct QualifiedName {
+    /// The schema of the object.
+    schema: String,
+    /// The name of the object.
+    name: String,
+}
+
+impl QualifiedName {
+    #[must_use]
+    /// Creates a new `QualifiedName` of the object of the schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the object.
+    /// * `name` - The name of the object.
+    pub fn new<S: AsRef<str>, N: AsRef<str>>(schema: S, name: N) -> Self {
+        Self {
+            schema: schema.as_ref().to_owned(),
+            name: name.as_ref().to_owned(),
+        }
+    }
+
+    /// Returns the schema of the object.
+    #[must_use]
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// Returns the name of the object.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Returns whether the identifier can be written without quotes.
+fn is_plain_identifier(identifier: &str) -> bool {
+    identifier
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && identifier
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '$'))
+}
+
+/// Writes the identifier, quoted if it cannot be written without quotes.
+fn write_identifier(f: &mut std::fmt::Formatter<'_>, identifier: &str) -> std::fmt::Result {
+    if is_plain_identifier(identifier) {
+        f.write_str(identifier)
+    } else {
+        write!(f, "\"{}\"", identifier.replace('"', "\"\""))
+    }
+}
+
+impl Display for QualifiedName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_identifier(f, &self.schema)?;
+        f.write_str(".")?;
+        write_identifier(f, &self.name)
+    }
+}
+
+/// Parses the identifier at the start of the text, returning it and the
+/// rest of the text, or `None` if it is empty or an unterminated quoted
+/// identifier.
+fn parse_identifier(text: &str) -> Option<(String, &str)> {
+    let Some(quoted) = text.strip_prefix('"') else {
+        let end = text.find('.').unwrap_or(text.len());
+        let identifier = text[..end].trim();
+        return (!identifier.is_empty()).then(|| (identifier.to_owned(), &text[end..]));
+    };
+    let mut identifier = String::new();
+    let mut chars = quoted.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        if c != '"' {
+            identifier.push(c);
+        } else if chars.peek().is_some_and(|(_, next)| *next == '"') {
+            identifier.push('"');
+            chars.next();
+        } else {
+            return Some((identifier, &quoted[position + 1..]));
+        }
+    }
+    None
+}
+
+impl FromStr for QualifiedName {
+    type Err = IdentityError;
+
+    /// Parses a name of the form `schema.name`, where either part may be
+    /// double-quoted to contain dots or quotes.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || IdentityError::InvalidQualifiedName(text.to_owned());
+        let (schema, rest) = parse_identifier(text.trim()).ok_or_else(invalid)?;
+        let rest = rest.strip_prefix('.').ok_or_else(invalid)?;
+        let (name, rest) = parse_identifier(rest).ok_or_else(invalid)?;
+        if rest.is_empty() {
+            Ok(Self { schema, name })
+        } else {
+            Err(invalid())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_oids() {
+        let oid = RelOid::new(16_384);
+        assert_eq!(oid.get(), 16_384);
+        assert_eq!(u32::from(oid), 16_384);
+        assert_eq!(ProcOid::from(745).to_string(), "745");
+        assert!(TypeOid::new(23) < TypeOid::new(25));
+    }
+
+    #[test]
+    fn test_qualified_names() {
+        let users = QualifiedName::new("public", "users");
+        assert_eq!(users.to_string(), "public.users");
+        assert_eq!("public.users".parse(), Ok(users));
+
+        let quoted = QualifiedName::new("Sales", "order.\"items\"");
+        assert_eq!(quoted.to_string(), "\"Sales\".\"order.\"\"items\"\"\"");
+        assert_eq!(quoted.to_string().parse(), Ok(quoted));
+
+        for invalid in ["users", "public.", ".users", "a.b.c", "\"public.users"] {
+            assert_eq!(
+                invalid.parse::<QualifiedName>(),
+                Err(IdentityError::InvalidQualifiedName(invalid.to_owned()))
+            );
+        }
+    }
+}
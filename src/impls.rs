@@ -18,14 +18,23 @@
 //! - `UniqueIndexLike`: Implemented for [`PgIndex`](crate::models::PgIndex)
 //! - `SchemaLike`: Implemented for [`Schemata`](crate::models::Schemata)
 //! - [`HasOid`](crate::traits::HasOid): Implemented for various catalog types
+//! - [`HasRelOid`](crate::traits::HasRelOid),
+//!   [`HasTypeOid`](crate::traits::HasTypeOid),
+//!   [`HasProcOid`](crate::traits::HasProcOid) and
+//!   [`HasQualifiedName`](crate::traits::HasQualifiedName): Implemented for
+//!   the models carrying typed identifiers
 //! - [`PostgresType`](crate::traits::PostgresType): Implemented for types that
 //!   resolve their Postgres type
+//! - [`HasAcl`](crate::traits::HasAcl): Implemented for the catalog types
+//!   with an `*acl` column
 
+mod acl;
 mod check_constraint_like;
 mod column_grant_like;
 mod column_like;
 mod foreign_key_like;
 mod function_like;
+mod identity;
 mod index_like;
 mod oid;
 mod policy_like;
@@ -0,0 +1,41 @@
+//! Implementations of [`HasAcl`] for `PostgreSQL` catalog types.
+//!
+//! Each implementation returns the `*acl` column of the catalog, such as
+//! `relacl` for [`PGClass`](crate::models::PGClass) or `defaclacl` for
+//! [`PgDefaultAcl`](crate::models::PgDefaultAcl).
+
+use crate::traits::HasAcl;
+
+/// Implements [`HasAcl`] for catalog types with a nullable `*acl` column.
+macro_rules! impl_has_acl {
+    ($($model:ty => $field:ident),* $(,)?) => {
+        $(
+            impl HasAcl for $model {
+                fn acl_items(&self) -> Option<&[String]> {
+                    self.$field.as_deref()
+                }
+            }
+        )*
+    };
+}
+
+impl_has_acl!(
+    crate::models::PGClass => relacl,
+    crate::models::PgAttribute => attacl,
+    crate::models::PgProc => proacl,
+    crate::models::PgType => typacl,
+    crate::models::PgNamespace => nspacl,
+    crate::models::PgDatabase => datacl,
+    crate::models::PgLanguage => lanacl,
+    crate::models::PgForeignServer => srvacl,
+    crate::models::PgForeignDataWrapper => fdwacl,
+    crate::models::PgTablespace => spcacl,
+    crate::models::PgLargeobjectMetadatum => lomacl,
+    crate::models::PgParameterAcl => paracl,
+);
+
+impl HasAcl for crate::models::PgDefaultAcl {
+    fn acl_items(&self) -> Option<&[String]> {
+        Some(&self.defaclacl)
+    }
+}
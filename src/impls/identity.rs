@@ -0,0 +1,220 @@
+//! Implementations of the typed identifier traits for model structs.
+//!
+//! This module implements [`HasRelOid`], [`HasTypeOid`], [`HasProcOid`] and
+//! [`HasQualifiedName`] for the models carrying the corresponding identifiers.
+//!
+//! ## Implemented Types
+//!
+//! - [`HasRelOid`]: The catalog rows of a relation, such as
+//!   [`PGClass`](crate::models::PGClass) (`oid`),
+//!   [`PgIndex`](crate::models::PgIndex) (`indexrelid`) and
+//!   [`PgAttribute`](crate::models::PgAttribute) (`attrelid`), and
+//!   [`TableMetadata`](crate::model_metadata::TableMetadata)
+//! - [`HasTypeOid`]: [`PgType`](crate::models::PgType) (`oid`), the rows
+//!   describing a type such as [`PgEnum`](crate::models::PgEnum)
+//!   (`enumtypid`), and the columns through
+//!   [`PgAttribute`](crate::models::PgAttribute) (`atttypid`) and
+//!   [`ColumnMetadata`](crate::model_metadata::ColumnMetadata)
+//! - [`HasProcOid`]: [`PgProc`](crate::models::PgProc) (`oid`),
+//!   [`PgAggregate`](crate::models::PgAggregate) (`aggfnoid`) and the
+//!   function executed by [`PgTrigger`](crate::models::PgTrigger) (`tgfoid`)
+//!   and [`PgEventTrigger`](crate::models::PgEventTrigger) (`evtfoid`)
+//! - [`HasQualifiedName`]: The information schema models
+//!   [`Table`](crate::models::Table),
+//!   [`TableConstraint`](crate::models::TableConstraint),
+//!   [`CheckConstraint`](crate::models::CheckConstraint) and
+//!   [`KeyColumnUsage`](crate::models::KeyColumnUsage)
+
+use crate::{
+    identity::{ProcOid, QualifiedName, RelOid, TypeOid},
+    traits::{HasProcOid, HasQualifiedName, HasRelOid, HasTypeOid},
+};
+
+impl HasRelOid for crate::models::PGClass {
+    fn rel_oid(&self) -> RelOid {
+        RelOid::new(self.oid)
+    }
+}
+
+impl HasRelOid for crate::models::PgIndex {
+    fn rel_oid(&self) -> RelOid {
+        RelOid::new(self.indexrelid)
+    }
+}
+
+impl HasRelOid for crate::models::PgAttribute {
+    fn rel_oid(&self) -> RelOid {
+        RelOid::new(self.attrelid)
+    }
+}
+
+impl HasRelOid for crate::models::PgAttrdef {
+    fn rel_oid(&self) -> RelOid {
+        RelOid::new(self.adrelid)
+    }
+}
+
+impl HasRelOid for crate::models::PgTrigger {
+    fn rel_oid(&self) -> RelOid {
+        RelOid::new(self.tgrelid)
+    }
+}
+
+impl HasRelOid for crate::models::PgPolicyTable {
+    fn rel_oid(&self) -> RelOid {
+        RelOid::new(self.polrelid)
+    }
+}
+
+impl HasRelOid for crate::models::PgForeignTable {
+    fn rel_oid(&self) -> RelOid {
+        RelOid::new(self.ftrelid)
+    }
+}
+
+impl HasRelOid for crate::models::PgStatistic {
+    fn rel_oid(&self) -> RelOid {
+        RelOid::new(self.starelid)
+    }
+}
+
+impl HasRelOid for crate::models::PgPublicationRel {
+    fn rel_oid(&self) -> RelOid {
+        RelOid::new(self.prrelid)
+    }
+}
+
+impl HasRelOid for crate::models::PgSubscriptionRel {
+    fn rel_oid(&self) -> RelOid {
+        RelOid::new(self.srrelid)
+    }
+}
+
+impl HasRelOid for crate::models::PgSequence {
+    fn rel_oid(&self) -> RelOid {
+        RelOid::new(self.seqrelid)
+    }
+}
+
+impl HasRelOid for crate::model_metadata::TableMetadata {
+    fn rel_oid(&self) -> RelOid {
+        self.rel_oid()
+    }
+}
+
+impl HasTypeOid for crate::models::PgType {
+    fn type_oid(&self) -> TypeOid {
+        TypeOid::new(self.oid)
+    }
+}
+
+impl HasTypeOid for crate::models::PgAttribute {
+    fn type_oid(&self) -> TypeOid {
+        TypeOid::new(self.atttypid)
+    }
+}
+
+impl HasTypeOid for crate::models::PgEnum {
+    fn type_oid(&self) -> TypeOid {
+        TypeOid::new(self.enumtypid)
+    }
+}
+
+impl HasTypeOid for crate::models::PgRange {
+    fn type_oid(&self) -> TypeOid {
+        TypeOid::new(self.rngtypid)
+    }
+}
+
+impl HasTypeOid for crate::models::PgSequence {
+    fn type_oid(&self) -> TypeOid {
+        TypeOid::new(self.seqtypid)
+    }
+}
+
+impl HasTypeOid for crate::model_metadata::ColumnMetadata {
+    fn type_oid(&self) -> TypeOid {
+        self.pg_type().type_oid()
+    }
+}
+
+impl HasProcOid for crate::models::PgProc {
+    fn proc_oid(&self) -> ProcOid {
+        ProcOid::new(self.oid)
+    }
+}
+
+impl HasProcOid for crate::models::PgAggregate {
+    fn proc_oid(&self) -> ProcOid {
+        ProcOid::new(self.aggfnoid)
+    }
+}
+
+impl HasProcOid for crate::models::PgTrigger {
+    fn proc_oid(&self) -> ProcOid {
+        ProcOid::new(self.tgfoid)
+    }
+}
+
+impl HasProcOid for crate::models::PgEventTrigger {
+    fn proc_oid(&self) -> ProcOid {
+        ProcOid::new(self.evtfoid)
+    }
+}
+
+impl HasQualifiedName for crate::models::Table {
+    fn qualified_name(&self) -> QualifiedName {
+        QualifiedName::new(&self.table_schema, &self.table_name)
+    }
+}
+
+impl HasQualifiedName for crate::models::TableConstraint {
+    fn qualified_name(&self) -> QualifiedName {
+        QualifiedName::new(&self.constraint_schema, &self.constraint_name)
+    }
+}
+
+impl HasQualifiedName for crate::models::CheckConstraint {
+    fn qualified_name(&self) -> QualifiedName {
+        QualifiedName::new(&self.constraint_schema, &self.constraint_name)
+    }
+}
+
+impl HasQualifiedName for crate::models::KeyColumnUsage {
+    fn qualified_name(&self) -> QualifiedName {
+        QualifiedName::new(&self.constraint_schema, &self.constraint_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CheckConstraint, PgSequence};
+
+    #[test]
+    fn test_typed_identifiers() {
+        let sequence = PgSequence {
+            seqrelid: 16_390,
+            seqtypid: 20,
+            seqstart: 1,
+            seqincrement: 1,
+            seqmax: i64::MAX,
+            seqmin: 1,
+            seqcache: 1,
+            seqcycle: false,
+        };
+        assert_eq!(sequence.rel_oid(), RelOid::new(16_390));
+        assert_eq!(sequence.type_oid(), TypeOid::new(20));
+
+        let constraint = CheckConstraint {
+            constraint_catalog: "db".to_string(),
+            constraint_schema: "public".to_string(),
+            constraint_name: "positive_id".to_string(),
+            check_clause: "CHECK (id > 0)".to_string(),
+        };
+        assert_eq!(
+            constraint.qualified_name(),
+            QualifiedName::new("public", "positive_id")
+        );
+    }
+}
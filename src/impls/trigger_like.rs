@@ -37,13 +37,12 @@ impl TriggerLike for TriggerMetadata {
 
     fn function<'db>(
         &'db self,
-        database: &'db Self::DB,
+        _database: &'db Self::DB,
     ) -> Option<&'db <Self::DB as DatabaseLike>::Function>
     where
         Self: 'db,
     {
-        let oid = self.function_oid?;
-        database.functions().find(|f| f.oid == oid)
+        self.function.as_deref()
     }
 
     fn function_name(&self) -> Option<&str> {
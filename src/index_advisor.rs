@@ -0,0 +1,772 @@
+//! Submodule advising on the indexes of the user tables, from their
+//! definitions and the cumulative statistics of the server.
+//!
+//! The [`IndexAdvisor`] reports:
+//!
+//! - invalid indexes, left behind by a failed `CREATE INDEX CONCURRENTLY`;
+//! - indexes that were never scanned;
+//! - indexes duplicating another index of the same table;
+//! - indexes whose key columns are a prefix of another index of the same
+//!   table;
+//! - foreign keys whose columns are not the leading columns of any index;
+//! - large tables mostly read by sequential scans.
+//!
+//! Where it applies, a finding suggests the `CREATE INDEX CONCURRENTLY` or
+//! `DROP INDEX CONCURRENTLY` statement addressing it.
+//!
+//! The scan counters are cumulative since the last reset of the statistics,
+//! and only cover the server they are read from: an index unused on the
+//! primary may well be used by the queries of a replica.
+
+use std::fmt::Display;
+
+use diesel::{
+    PgConnection, QueryableByName, RunQueryDsl,
+    sql_types::{Array, BigInt, Bool, Nullable, Oid, Text},
+};
+
+use crate::{models::PgStatUserTable, quoting::quote_identifier, severity::Severity};
+
+#[derive(QueryableByName, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The definition and usage of an index of a user table.
+pub struct IndexUsage {
+    #[diesel(sql_type = Oid)]
+    /// The OID of the index.
+    pub index_oid: u32,
+    #[diesel(sql_type = Oid)]
+    /// The OID of the indexed table.
+    pub table_oid: u32,
+    #[diesel(sql_type = Text)]
+    /// The schema of the indexed table.
+    pub schema_name: String,
+    #[diesel(sql_type = Text)]
+    /// The name of the indexed table.
+    pub table_name: String,
+    #[diesel(sql_type = Text)]
+    /// The name of the index.
+    pub index_name: String,
+    #[diesel(sql_type = Text)]
+    /// The access method of the index, as in `btree`.
+    pub access_method: String,
+    #[diesel(sql_type = Array<Text>)]
+    /// The key columns or expressions of the index, quoted as needed.
+    pub key_columns: Vec<String>,
+    #[diesel(sql_type = Array<Text>)]
+    /// The non-key columns of the `INCLUDE` clause, quoted as needed.
+    pub include_columns: Vec<String>,
+    #[diesel(sql_type = Array<Oid>)]
+    /// The operator classes of the key columns.
+    pub operator_classes: Vec<u32>,
+    #[diesel(sql_type = Nullable<Text>)]
+    /// The predicate of a partial index.
+    pub predicate: Option<String>,
+    #[diesel(sql_type = Bool)]
+    /// Whether the index is unique.
+    pub is_unique: bool,
+    #[diesel(sql_type = Bool)]
+    /// Whether the index is valid for queries.
+    pub is_valid: bool,
+    #[diesel(sql_type = Bool)]
+    /// Whether the index is the replica identity of the table.
+    pub is_replica_identity: bool,
+    #[diesel(sql_type = Bool)]
+    /// Whether the index enforces a primary key, unique or exclusion
+    /// constraint.
+    pub backs_constraint: bool,
+    #[diesel(sql_type = Nullable<BigInt>)]
+    /// The number of scans of the index since the statistics were reset.
+    pub idx_scan: Option<i64>,
+    #[diesel(sql_type = BigInt)]
+    /// The size of the index, in bytes.
+    pub size_bytes: i64,
+}
+
+impl IndexUsage {
+    /// Loads the indexes of the tables outside of the system schemas.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        diesel::sql_query(
+            r"
+            SELECT
+                i.indexrelid AS index_oid,
+                i.indrelid AS table_oid,
+                n.nspname::text AS schema_name,
+                t.relname::text AS table_name,
+                c.relname::text AS index_name,
+                am.amname::text AS access_method,
+                ARRAY(
+                    SELECT pg_get_indexdef(i.indexrelid, k, true)
+                    FROM generate_series(1, i.indnkeyatts) AS k
+                    ORDER BY k
+                ) AS key_columns,
+                ARRAY(
+                    SELECT pg_get_indexdef(i.indexrelid, k, true)
+                    FROM generate_series(i.indnkeyatts + 1, i.indnatts) AS k
+                    ORDER BY k
+                ) AS include_columns,
+                i.indclass::oid[] AS operator_classes,
+                pg_get_expr(i.indpred, i.indrelid) AS predicate,
+                i.indisunique AS is_unique,
+                i.indisvalid AS is_valid,
+                i.indisreplident AS is_replica_identity,
+                EXISTS (
+                    SELECT 1 FROM pg_constraint con
+                    WHERE con.conindid = i.indexrelid AND con.contype IN ('p', 'u', 'x')
+                ) AS backs_constraint,
+                s.idx_scan,
+                pg_relation_size(i.indexrelid) AS size_bytes
+            FROM pg_index i
+            JOIN pg_class c ON c.oid = i.indexrelid
+            JOIN pg_class t ON t.oid = i.indrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            JOIN pg_am am ON am.oid = c.relam
+            LEFT JOIN pg_stat_user_indexes s ON s.indexrelid = i.indexrelid
+            WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+                AND n.nspname NOT LIKE 'pg\_toast%'
+            ORDER BY n.nspname, t.relname, c.relname
+            ",
+        )
+        .load(conn)
+    }
+
+    #[must_use]
+    /// Returns whether dropping the index would change the behavior of the
+    /// database, rather than only its performance.
+    pub fn is_required(&self) -> bool {
+        self.is_unique || self.backs_constraint || self.is_replica_identity
+    }
+
+    /// Returns whether the index is interchangeable with the provided one.
+    fn duplicates(&self, other: &Self) -> bool {
+        self.table_oid == other.table_oid
+            && self.access_method == other.access_method
+            && self.key_columns == other.key_columns
+            && self.include_columns == other.include_columns
+            && self.operator_classes == other.operator_classes
+            && self.predicate == other.predicate
+    }
+
+    /// Returns whether the key columns of the index are a strict prefix of
+    /// those of the provided B-tree index, making the index redundant.
+    fn is_prefix_of(&self, other: &Self) -> bool {
+        self.table_oid == other.table_oid
+            && self.access_method == "btree"
+            && other.access_method == "btree"
+            && !self.is_unique
+            && self.predicate.is_none()
+            && other.predicate.is_none()
+            && self.include_columns.is_empty()
+            && self.key_columns.len() < other.key_columns.len()
+            && other.key_columns.starts_with(&self.key_columns)
+            && other.operator_classes.starts_with(&self.operator_classes)
+    }
+
+    /// Returns whether the leading key columns of the index are the provided
+    /// columns, in any order.
+    fn supports(&self, columns: &[String]) -> bool {
+        self.is_valid
+            && self.predicate.is_none()
+            && self.key_columns.len() >= columns.len()
+            && self.key_columns[..columns.len()]
+                .iter()
+                .all(|key| columns.contains(key))
+    }
+
+    /// Returns the statement dropping the index without blocking writes.
+    fn drop_statement(&self) -> String {
+        format!(
+            "DROP INDEX CONCURRENTLY {}.{};",
+            quote_identifier(&self.schema_name),
+            quote_identifier(&self.index_name)
+        )
+    }
+}
+
+#[derive(QueryableByName, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The columns of a foreign key of a user table.
+pub struct ForeignKeyColumns {
+    #[diesel(sql_type = Text)]
+    /// The name of the foreign key constraint.
+    pub constraint_name: String,
+    #[diesel(sql_type = Oid)]
+    /// The OID of the referencing table.
+    pub table_oid: u32,
+    #[diesel(sql_type = Text)]
+    /// The schema of the referencing table.
+    pub schema_name: String,
+    #[diesel(sql_type = Text)]
+    /// The name of the referencing table.
+    pub table_name: String,
+    #[diesel(sql_type = Array<Text>)]
+    /// The referencing columns, quoted as needed.
+    pub columns: Vec<String>,
+    #[diesel(sql_type = Text)]
+    /// The referenced table, qualified as needed.
+    pub referenced_table: String,
+}
+
+impl ForeignKeyColumns {
+    /// Loads the foreign keys of the tables outside of the system schemas.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        diesel::sql_query(
+            r"
+            SELECT
+                con.conname::text AS constraint_name,
+                con.conrelid AS table_oid,
+                n.nspname::text AS schema_name,
+                t.relname::text AS table_name,
+                ARRAY(
+                    SELECT quote_ident(a.attname)
+                    FROM unnest(con.conkey) WITH ORDINALITY AS k(attnum, position)
+                    JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+                    ORDER BY k.position
+                ) AS columns,
+                con.confrelid::regclass::text AS referenced_table
+            FROM pg_constraint con
+            JOIN pg_class t ON t.oid = con.conrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            WHERE con.contype = 'f'
+                AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+            ORDER BY n.nspname, t.relname, con.conname
+            ",
+        )
+        .load(conn)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The kind of a finding of the [`IndexAdvisor`].
+pub enum IndexFindingKind {
+    /// The index is invalid, as left behind by a failed concurrent build, yet
+    /// still maintained on every write.
+    Invalid,
+    /// The index was never scanned since the statistics were reset.
+    Unused {
+        /// The size of the index, in bytes.
+        size_bytes: i64,
+    },
+    /// The index has the same definition as another index of the table.
+    Duplicate {
+        /// The name of the index being kept.
+        of: String,
+    },
+    /// The key columns of the index are a prefix of another index of the
+    /// table, which can serve the same queries.
+    Prefix {
+        /// The name of the longer index.
+        of: String,
+    },
+    /// No index starts with the columns of a foreign key, so deleting or
+    /// updating a referenced row scans the whole referencing table.
+    UnindexedForeignKey {
+        /// The name of the foreign key constraint.
+        constraint: String,
+        /// The referencing columns.
+        columns: Vec<String>,
+    },
+    /// The table is large and read by sequential scans more than by index
+    /// scans.
+    SequentialScans {
+        /// The number of sequential scans.
+        seq_scan: i64,
+        /// The number of rows read by sequential scans.
+        seq_tup_read: i64,
+        /// The number of index scans.
+        idx_scan: i64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A finding of the [`IndexAdvisor`] on a table or one of its indexes.
+pub struct IndexFinding {
+    /// The kind of the finding.
+    kind: IndexFindingKind,
+    /// How urgently the finding should be acted upon.
+    severity: Severity,
+    /// The schema of the table.
+    schema: String,
+    /// The name of the table.
+    table: String,
+    /// The name of the index the finding is about, if any.
+    index: Option<String>,
+    /// The statement addressing the finding, if any.
+    suggestion: Option<String>,
+}
+
+impl IndexFinding {
+    #[must_use]
+    /// Returns the kind of the finding.
+    pub fn kind(&self) -> &IndexFindingKind {
+        &self.kind
+    }
+
+    #[must_use]
+    /// Returns how urgently the finding should be acted upon.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    #[must_use]
+    /// Returns the schema of the table.
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    #[must_use]
+    /// Returns the name of the table.
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    #[must_use]
+    /// Returns the name of the index the finding is about, if any.
+    pub fn index(&self) -> Option<&str> {
+        self.index.as_deref()
+    }
+
+    #[must_use]
+    /// Returns the `CREATE INDEX` or `DROP INDEX` statement addressing the
+    /// finding, if any.
+    ///
+    /// The statements use `CONCURRENTLY`, so they cannot run in a
+    /// transaction block.
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref()
+    }
+
+    /// Returns a finding about the provided index.
+    fn on_index(
+        index: &IndexUsage,
+        kind: IndexFindingKind,
+        severity: Severity,
+        suggestion: Option<String>,
+    ) -> Self {
+        Self {
+            kind,
+            severity,
+            schema: index.schema_name.clone(),
+            table: index.table_name.clone(),
+            index: Some(index.index_name.clone()),
+            suggestion,
+        }
+    }
+}
+
+impl Display for IndexFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let table = format!("{}.{}", self.schema, self.table);
+        let index = self.index.as_deref().unwrap_or_default();
+        write!(f, "[{}] ", self.severity)?;
+        match &self.kind {
+            IndexFindingKind::Invalid => write!(f, "index {index} on {table} is invalid"),
+            IndexFindingKind::Unused { size_bytes } => {
+                write!(
+                    f,
+                    "index {index} on {table} ({size_bytes} bytes) was never scanned"
+                )
+            }
+            IndexFindingKind::Duplicate { of } => {
+                write!(f, "index {index} on {table} duplicates {of}")
+            }
+            IndexFindingKind::Prefix { of } => {
+                write!(f, "index {index} on {table} is a prefix of {of}")
+            }
+            IndexFindingKind::UnindexedForeignKey {
+                constraint,
+                columns,
+            } => write!(
+                f,
+                "foreign key {constraint} on {table} ({}) has no supporting index",
+                columns.join(", ")
+            ),
+            IndexFindingKind::SequentialScans {
+                seq_scan,
+                seq_tup_read,
+                idx_scan,
+            } => write!(
+                f,
+                "table {table} had {seq_scan} sequential scans reading {seq_tup_read} rows, \
+                 against {idx_scan} index scans"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Advisor reporting unused, duplicate and missing indexes.
+pub struct IndexAdvisor {
+    /// The number of live rows from which sequential scans are reported.
+    min_table_rows: i64,
+    /// The size in bytes from which unused indexes are reported as warnings.
+    min_unused_size: i64,
+}
+
+impl Default for IndexAdvisor {
+    fn default() -> Self {
+        Self {
+            min_table_rows: 10_000,
+            min_unused_size: 8 * 1024 * 1024,
+        }
+    }
+}
+
+impl IndexAdvisor {
+    /// Sets the number of live rows from which the sequential scans of a
+    /// table are reported, which defaults to 10 000.
+    #[must_use]
+    pub fn min_table_rows(mut self, min_table_rows: i64) -> Self {
+        self.min_table_rows = min_table_rows;
+        self
+    }
+
+    /// Sets the size in bytes from which unused indexes are reported as
+    /// warnings rather than as information, which defaults to 8 MiB.
+    #[must_use]
+    pub fn min_unused_size(mut self, min_unused_size: i64) -> Self {
+        self.min_unused_size = min_unused_size;
+        self
+    }
+
+    /// Loads the indexes, foreign keys and table statistics of the database
+    /// and returns the findings on them.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn advise(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<Vec<IndexFinding>, diesel::result::Error> {
+        let indexes = IndexUsage::load_all(conn)?;
+        let foreign_keys = ForeignKeyColumns::load_all(conn)?;
+        let tables = PgStatUserTable::load_all(conn)?;
+        Ok(self.findings(&indexes, &foreign_keys, &tables))
+    }
+
+    #[must_use]
+    /// Returns the findings on the provided indexes, foreign keys and table
+    /// statistics, sorted by decreasing severity and then by table.
+    ///
+    /// # Arguments
+    ///
+    /// * `indexes` - The indexes of the user tables.
+    /// * `foreign_keys` - The foreign keys of the user tables.
+    /// * `tables` - The rows of `pg_stat_user_tables`.
+    pub fn findings(
+        &self,
+        indexes: &[IndexUsage],
+        foreign_keys: &[ForeignKeyColumns],
+        tables: &[PgStatUserTable],
+    ) -> Vec<IndexFinding> {
+        let mut findings = Vec::new();
+        self.index_findings(indexes, &mut findings);
+        for foreign_key in foreign_keys {
+            if !indexes.iter().any(|index| {
+                index.table_oid == foreign_key.table_oid && index.supports(&foreign_key.columns)
+            }) {
+                findings.push(IndexFinding {
+                    kind: IndexFindingKind::UnindexedForeignKey {
+                        constraint: foreign_key.constraint_name.clone(),
+                        columns: foreign_key.columns.clone(),
+                    },
+                    severity: Severity::Warning,
+                    schema: foreign_key.schema_name.clone(),
+                    table: foreign_key.table_name.clone(),
+                    index: None,
+                    suggestion: Some(format!(
+                        "CREATE INDEX CONCURRENTLY ON {}.{} ({});",
+                        quote_identifier(&foreign_key.schema_name),
+                        quote_identifier(&foreign_key.table_name),
+                        foreign_key.columns.join(", ")
+                    )),
+                });
+            }
+        }
+        findings.extend(
+            tables
+                .iter()
+                .filter_map(|table| self.sequential_scans(table)),
+        );
+        findings.sort_by(|left, right| {
+            right.severity.cmp(&left.severity).then_with(|| {
+                (&left.schema, &left.table, &left.index).cmp(&(
+                    &right.schema,
+                    &right.table,
+                    &right.index,
+                ))
+            })
+        });
+        findings
+    }
+
+    /// Appends the findings on the indexes themselves.
+    fn index_findings(&self, indexes: &[IndexUsage], findings: &mut Vec<IndexFinding>) {
+        for index in indexes {
+            if !index.is_valid {
+                findings.push(IndexFinding::on_index(
+                    index,
+                    IndexFindingKind::Invalid,
+                    Severity::Critical,
+                    Some(index.drop_statement()),
+                ));
+                continue;
+            }
+            let valid_others = indexes
+                .iter()
+                .filter(|other| other.index_oid != index.index_oid && other.is_valid);
+            // Of two duplicates, the required one or else the first by name is kept.
+            if let Some(kept) = valid_others.clone().find(|other| {
+                index.duplicates(other)
+                    && !index.is_required()
+                    && (other.is_required() || other.index_name < index.index_name)
+            }) {
+                findings.push(IndexFinding::on_index(
+                    index,
+                    IndexFindingKind::Duplicate {
+                        of: kept.index_name.clone(),
+                    },
+                    Severity::Warning,
+                    Some(index.drop_statement()),
+                ));
+            } else if let Some(longer) =
+                valid_others.clone().find(|other| index.is_prefix_of(other))
+            {
+                findings.push(IndexFinding::on_index(
+                    index,
+                    IndexFindingKind::Prefix {
+                        of: longer.index_name.clone(),
+                    },
+                    Severity::Info,
+                    (!index.is_required()).then(|| index.drop_statement()),
+                ));
+            } else if index.idx_scan == Some(0) && !index.is_required() {
+                let severity = if index.size_bytes >= self.min_unused_size {
+                    Severity::Warning
+                } else {
+                    Severity::Info
+                };
+                findings.push(IndexFinding::on_index(
+                    index,
+                    IndexFindingKind::Unused {
+                        size_bytes: index.size_bytes,
+                    },
+                    severity,
+                    Some(index.drop_statement()),
+                ));
+            }
+        }
+    }
+
+    /// Returns the finding on the sequential scans of the table, if they are
+    /// worth reporting.
+    ///
+    /// Sequential scans are reported as warnings when they outnumber index
+    /// scans tenfold, and as information when they merely outnumber them.
+    fn sequential_scans(&self, table: &PgStatUserTable) -> Option<IndexFinding> {
+        let seq_scan = table.seq_scan.unwrap_or_default();
+        let idx_scan = table.idx_scan.unwrap_or_default();
+        if table.n_live_tup.unwrap_or_default() < self.min_table_rows
+            || seq_scan == 0
+            || seq_scan <= idx_scan
+        {
+            return None;
+        }
+        Some(IndexFinding {
+            kind: IndexFindingKind::SequentialScans {
+                seq_scan,
+                seq_tup_read: table.seq_tup_read.unwrap_or_default(),
+                idx_scan,
+            },
+            severity: if seq_scan > idx_scan.saturating_mul(10) {
+                Severity::Warning
+            } else {
+                Severity::Info
+            },
+            schema: table.schemaname.clone()?,
+            table: table.relname.clone()?,
+            index: None,
+            suggestion: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a valid, plain B-tree index on the `orders` table.
+    fn index(oid: u32, name: &str, key_columns: &[&str], idx_scan: i64) -> IndexUsage {
+        IndexUsage {
+            index_oid: oid,
+            table_oid: 1,
+            schema_name: "public".to_owned(),
+            table_name: "orders".to_owned(),
+            index_name: name.to_owned(),
+            access_method: "btree".to_owned(),
+            key_columns: key_columns.iter().map(ToString::to_string).collect(),
+            include_columns: Vec::new(),
+            operator_classes: key_columns.iter().map(|_| 3124).collect(),
+            predicate: None,
+            is_unique: false,
+            is_valid: true,
+            is_replica_identity: false,
+            backs_constraint: false,
+            idx_scan: Some(idx_scan),
+            size_bytes: 16 * 1024 * 1024,
+        }
+    }
+
+    /// Returns the statistics of the `orders` table.
+    fn table(seq_scan: i64, idx_scan: i64, n_live_tup: i64) -> PgStatUserTable {
+        PgStatUserTable {
+            relid: Some(1),
+            schemaname: Some("public".to_owned()),
+            relname: Some("orders".to_owned()),
+            seq_scan: Some(seq_scan),
+            #[cfg(not(any(feature = "postgres-15", feature = "postgres-14")))]
+            last_seq_scan: None,
+            seq_tup_read: Some(seq_scan * n_live_tup),
+            idx_scan: Some(idx_scan),
+            #[cfg(not(any(feature = "postgres-15", feature = "postgres-14")))]
+            last_idx_scan: None,
+            idx_tup_fetch: None,
+            n_tup_ins: None,
+            n_tup_upd: None,
+            n_tup_del: None,
+            n_tup_hot_upd: None,
+            #[cfg(not(any(feature = "postgres-15", feature = "postgres-14")))]
+            n_tup_newpage_upd: None,
+            n_live_tup: Some(n_live_tup),
+            n_dead_tup: None,
+            n_mod_since_analyze: None,
+            n_ins_since_vacuum: None,
+            last_vacuum: None,
+            last_autovacuum: None,
+            last_analyze: None,
+            last_autoanalyze: None,
+            vacuum_count: None,
+            autovacuum_count: None,
+            analyze_count: None,
+            autoanalyze_count: None,
+        }
+    }
+
+    #[test]
+    fn test_index_findings() {
+        let mut primary_key = index(10, "orders_pkey", &["id"], 500);
+        primary_key.is_unique = true;
+        primary_key.backs_constraint = true;
+        let mut invalid = index(14, "orders_note_idx", &["note"], 0);
+        invalid.is_valid = false;
+        let indexes = [
+            primary_key,
+            index(11, "orders_id_idx", &["id"], 3),
+            index(12, "orders_customer_idx", &["customer_id"], 7),
+            index(
+                13,
+                "orders_customer_created_idx",
+                &["customer_id", "created_at"],
+                0,
+            ),
+            invalid,
+        ];
+
+        let findings = IndexAdvisor::default().findings(&indexes, &[], &[]);
+        let kinds: Vec<(&str, &IndexFindingKind)> = findings
+            .iter()
+            .map(|finding| (finding.index().unwrap_or_default(), finding.kind()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("orders_note_idx", &IndexFindingKind::Invalid),
+                (
+                    "orders_customer_created_idx",
+                    &IndexFindingKind::Unused {
+                        size_bytes: 16 * 1024 * 1024
+                    }
+                ),
+                (
+                    "orders_id_idx",
+                    &IndexFindingKind::Duplicate {
+                        of: "orders_pkey".to_owned()
+                    }
+                ),
+                (
+                    "orders_customer_idx",
+                    &IndexFindingKind::Prefix {
+                        of: "orders_customer_created_idx".to_owned()
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            findings[2].suggestion(),
+            Some(r#"DROP INDEX CONCURRENTLY "public"."orders_id_idx";"#)
+        );
+    }
+
+    #[test]
+    fn test_foreign_key_and_sequential_scan_findings() {
+        let foreign_key = |name: &str, columns: &[&str]| ForeignKeyColumns {
+            constraint_name: name.to_owned(),
+            table_oid: 1,
+            schema_name: "public".to_owned(),
+            table_name: "orders".to_owned(),
+            columns: columns.iter().map(ToString::to_string).collect(),
+            referenced_table: "customers".to_owned(),
+        };
+        let indexes = [index(
+            12,
+            "orders_customer_idx",
+            &["customer_id", "created_at"],
+            7,
+        )];
+        let foreign_keys = [
+            foreign_key("orders_customer_fkey", &["customer_id"]),
+            foreign_key("orders_product_fkey", &["product_id", "variant"]),
+        ];
+        let tables = [table(400, 20, 50_000)];
+
+        let findings = IndexAdvisor::default().findings(&indexes, &foreign_keys, &tables);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(
+            findings[0].suggestion(),
+            Some(r#"CREATE INDEX CONCURRENTLY ON "public"."orders" (product_id, variant);"#)
+        );
+        assert!(matches!(
+            findings[1].kind(),
+            IndexFindingKind::SequentialScans { seq_scan: 400, .. }
+        ));
+        assert_eq!(findings[1].severity(), Severity::Warning);
+
+        let findings =
+            IndexAdvisor::default()
+                .min_table_rows(100_000)
+                .findings(&indexes, &[], &tables);
+        assert!(findings.is_empty());
+    }
+}
@@ -0,0 +1,911 @@
+//! Submodule exporting the tables and user-defined types of a
+//! [`PgDieselDatabase`] as JSON Schema (draft 2020-12) and as `OpenAPI` 3.1
+//! component schemas.
+//!
+//! Each table is exported as an object schema in two variants:
+//!
+//! - the [`SchemaVariant::Read`] variant describes the rows as returned by the
+//!   database, with generated columns marked as `readOnly` and every
+//!   `NOT NULL` column `required`;
+//! - the [`SchemaVariant::Write`] variant describes the rows as submitted to
+//!   the database, without the generated columns and only requiring the
+//!   `NOT NULL` columns without a default.
+//!
+//! The columns are mapped from their [`PgType`]: `uuid` becomes a string of
+//! format `uuid`, `timestamptz` a string of format `date-time`, `numeric` a
+//! string with a decimal pattern so that no precision is lost, and so on.
+//! Enum and composite types, resolved with
+//! [`JsonSchemaExporter::resolve_types`], are exported as schemas of their
+//! own, referenced by the columns using them. The simple check constraints
+//! understood by [`ColumnBounds`] become `minimum`, `maximum`, `enum`,
+//! `minLength` and `maxLength`, and the comments on tables and columns become
+//! `description`s.
+//!
+//! The documents are built as `serde_json` values, so this module requires
+//! the `serde` feature.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    str::FromStr,
+};
+
+use bigdecimal::BigDecimal;
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+use serde_json::{Map, Number, Value, json};
+use sql_traits::traits::{CheckConstraintLike, DatabaseLike};
+
+use crate::{
+    PgDieselDatabase,
+    constraint_bounds::ColumnBounds,
+    database::ObjectIndex,
+    identity::QualifiedName,
+    models::{PgType, Table},
+};
+
+/// The JSON Schema dialect of the exported schemas.
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// The pattern of the textual representation of `numeric` values.
+const NUMERIC_PATTERN: &str = r"^-?(0|[1-9][0-9]*)(\.[0-9]+)?$";
+
+/// The pattern of the textual representation of `bytea` values.
+const BYTEA_PATTERN: &str = r"^\\x([0-9a-fA-F]{2})*$";
+
+/// The members of a JSON object, as in a schema.
+type Members = Map<String, Value>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The variants of the schema of a table.
+pub enum SchemaVariant {
+    /// The rows as returned by the database, including generated columns.
+    Read,
+    /// The rows as submitted to the database, excluding generated columns.
+    Write,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The type of a column or of an attribute of a composite type.
+enum TypeKind {
+    /// A built-in type, by name.
+    Scalar(String),
+    /// An enum, composite or table row type, by component name.
+    Reference(String),
+    /// An array of the element type.
+    Array(Box<TypeKind>),
+}
+
+impl TypeKind {
+    /// Adds the component names referenced by the type to the set.
+    fn references<'a>(&'a self, references: &mut BTreeSet<&'a str>) {
+        match self {
+            Self::Scalar(_) => {}
+            Self::Reference(name) => {
+                references.insert(name);
+            }
+            Self::Array(element) => element.references(references),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A column of a [`Component::Table`].
+struct PropertyShape {
+    /// The name of the column.
+    name: String,
+    /// The type of the column.
+    kind: TypeKind,
+    /// The bounds placed on the column by check constraints.
+    bounds: ColumnBounds,
+    /// The maximum length of the column, as in `varchar(255)`.
+    max_length: Option<usize>,
+    /// Whether the column is nullable.
+    nullable: bool,
+    /// Whether the column has a default value.
+    has_default: bool,
+    /// Whether the column is generated by the database.
+    generated: bool,
+    /// The comment on the column.
+    description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The shape of an exported schema, independent of the output format.
+enum Component {
+    /// An enum type, with its labels.
+    Enum(Vec<String>),
+    /// A composite type, with its attributes.
+    Composite(Vec<(String, TypeKind)>),
+    /// A table, with its comment and columns.
+    Table(Option<String>, Vec<PropertyShape>),
+}
+
+/// Returns the members describing values of the built-in type, or none when
+/// any JSON value is admitted.
+fn scalar_schema(typname: &str) -> Members {
+    let schema = match typname {
+        "bool" => json!({"type": "boolean"}),
+        "int2" => json!({"type": "integer", "minimum": -32768, "maximum": 32767}),
+        "oid" => json!({"type": "integer", "minimum": 0, "maximum": 4_294_967_295_u32}),
+        "int4" => json!({"type": "integer", "format": "int32"}),
+        "int8" => json!({"type": "integer", "format": "int64"}),
+        "float4" => json!({"type": "number", "format": "float"}),
+        "float8" => json!({"type": "number", "format": "double"}),
+        "numeric" => json!({"type": "string", "pattern": NUMERIC_PATTERN}),
+        "uuid" => json!({"type": "string", "format": "uuid"}),
+        "date" => json!({"type": "string", "format": "date"}),
+        "timestamp" | "timestamptz" => json!({"type": "string", "format": "date-time"}),
+        "time" | "timetz" => json!({"type": "string", "format": "time"}),
+        "bytea" => json!({"type": "string", "pattern": BYTEA_PATTERN}),
+        "text" | "varchar" | "bpchar" | "char" | "name" | "citext" | "money" | "interval"
+        | "inet" | "cidr" | "macaddr" | "macaddr8" | "bit" | "varbit" | "xml" | "tsvector" => {
+            json!({"type": "string"})
+        }
+        _ => return Members::new(),
+    };
+    let Value::Object(members) = schema else {
+        return Members::new();
+    };
+    members
+}
+
+/// Returns the decimal as a JSON number, or `None` if it is not written as
+/// one, as in `.5`.
+fn number(value: &str) -> Option<Value> {
+    value.parse::<Number>().ok().map(Value::Number)
+}
+
+/// Sets the numeric member of the schema, unless it already holds a tighter
+/// bound.
+fn tighten(members: &mut Members, key: &str, value: &BigDecimal, lower: bool) {
+    let previous = members
+        .get(key)
+        .and_then(Value::as_number)
+        .and_then(|previous| BigDecimal::from_str(&previous.to_string()).ok());
+    let tighter = previous.is_none_or(|previous| {
+        if lower {
+            *value > previous
+        } else {
+            *value < previous
+        }
+    });
+    if tighter && let Some(value) = number(&value.to_string()) {
+        members.insert(key.to_owned(), value);
+    }
+}
+
+/// Adds the restrictions of the check constraints and of the declared
+/// length to the schema of a column.
+fn apply_bounds(members: &mut Members, bounds: &ColumnBounds, max_length: Option<usize>) {
+    let json_type = members
+        .get("type")
+        .and_then(Value::as_str)
+        .map(str::to_owned);
+    match json_type.as_deref() {
+        Some("integer" | "number") => {
+            if let Some(lower) = bounds.lower() {
+                if lower.inclusive {
+                    tighten(members, "minimum", &lower.value, true);
+                } else if let Some(value) = number(&lower.value.to_string()) {
+                    members.insert("exclusiveMinimum".to_owned(), value);
+                }
+            }
+            if let Some(upper) = bounds.upper() {
+                if upper.inclusive {
+                    tighten(members, "maximum", &upper.value, false);
+                } else if let Some(value) = number(&upper.value.to_string()) {
+                    members.insert("exclusiveMaximum".to_owned(), value);
+                }
+            }
+        }
+        Some("string") => {
+            if let Some(min_length) = bounds.min_length().filter(|min_length| *min_length > 0) {
+                members.insert("minLength".to_owned(), min_length.into());
+            }
+            let max_length = match (bounds.max_length(), max_length) {
+                (Some(left), Some(right)) => Some(left.min(right)),
+                (left, right) => left.or(right),
+            };
+            if let Some(max_length) = max_length {
+                members.insert("maxLength".to_owned(), max_length.into());
+            }
+        }
+        _ => {}
+    }
+    if let Some(allowed_values) = bounds.allowed_values() {
+        let values = allowed_values
+            .iter()
+            .map(|value| match json_type.as_deref() {
+                Some("integer" | "number") => {
+                    number(value).unwrap_or_else(|| Value::String(value.clone()))
+                }
+                Some("boolean") if matches!(value.as_str(), "true" | "false") => {
+                    Value::Bool(value == "true")
+                }
+                _ => Value::String(value.clone()),
+            })
+            .collect();
+        members.insert("enum".to_owned(), Value::Array(values));
+    }
+}
+
+/// Returns the members of the schema admitting `null` besides the values of
+/// the provided schema.
+fn nullable(mut members: Members) -> Members {
+    if members.contains_key("$ref") {
+        let mut any_of = Members::new();
+        any_of.insert(
+            "anyOf".to_owned(),
+            Value::Array(vec![Value::Object(members), json!({"type": "null"})]),
+        );
+        return any_of;
+    }
+    if let Some(json_type @ Value::String(_)) = members.get_mut("type") {
+        *json_type = Value::Array(vec![json_type.take(), Value::from("null")]);
+    }
+    if let Some(Value::Array(values)) = members.get_mut("enum") {
+        values.push(Value::Null);
+    }
+    members
+}
+
+/// Returns the name of the component of the object of the schema, keeping
+/// the characters admitted in `OpenAPI` component names.
+fn component_name(schema: &str, name: &str) -> String {
+    let sanitize = |name: &str| -> String {
+        name.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '_' | '-') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    };
+    format!("{}.{}", sanitize(schema), sanitize(name))
+}
+
+/// Returns the name of the component of the variant of the table.
+fn variant_name(name: &str, variant: SchemaVariant) -> String {
+    match variant {
+        SchemaVariant::Read => name.to_owned(),
+        SchemaVariant::Write => format!("{name}.write"),
+    }
+}
+
+/// Returns the members of the schema of the type, referencing components
+/// under the provided prefix.
+fn type_schema(kind: &TypeKind, prefix: &str) -> Members {
+    match kind {
+        TypeKind::Scalar(typname) => scalar_schema(typname),
+        TypeKind::Reference(name) => {
+            let mut members = Members::new();
+            members.insert("$ref".to_owned(), Value::String(format!("{prefix}{name}")));
+            members
+        }
+        TypeKind::Array(element) => {
+            let mut members = Members::new();
+            members.insert("type".to_owned(), Value::from("array"));
+            members.insert(
+                "items".to_owned(),
+                Value::Object(type_schema(element, prefix)),
+            );
+            members
+        }
+    }
+}
+
+/// Returns the schema of the column.
+fn property_schema(property: &PropertyShape, prefix: &str) -> Value {
+    let mut members = type_schema(&property.kind, prefix);
+    if !matches!(property.kind, TypeKind::Array(_)) {
+        apply_bounds(&mut members, &property.bounds, property.max_length);
+    }
+    if property.nullable {
+        members = nullable(members);
+    }
+    if let Some(description) = &property.description {
+        members.insert("description".to_owned(), Value::from(description.as_str()));
+    }
+    if property.generated {
+        members.insert("readOnly".to_owned(), Value::Bool(true));
+    }
+    Value::Object(members)
+}
+
+impl Component {
+    /// Returns the schema of the component, referencing the other
+    /// components under the provided prefix.
+    fn schema(&self, prefix: &str, variant: SchemaVariant) -> Value {
+        match self {
+            Self::Enum(labels) => json!({"type": "string", "enum": labels}),
+            Self::Composite(attributes) => {
+                let properties: Members = attributes
+                    .iter()
+                    .map(|(name, kind)| {
+                        (
+                            name.clone(),
+                            Value::Object(nullable(type_schema(kind, prefix))),
+                        )
+                    })
+                    .collect();
+                json!({"type": "object", "properties": properties})
+            }
+            Self::Table(description, properties) => {
+                let properties: Vec<&PropertyShape> = properties
+                    .iter()
+                    .filter(|property| variant == SchemaVariant::Read || !property.generated)
+                    .collect();
+                let required: Vec<&str> = properties
+                    .iter()
+                    .filter(|property| {
+                        !property.nullable
+                            && (variant == SchemaVariant::Read || !property.has_default)
+                    })
+                    .map(|property| property.name.as_str())
+                    .collect();
+                let mut schema = json!({
+                    "type": "object",
+                    "properties": properties
+                        .iter()
+                        .map(|property| (property.name.clone(), property_schema(property, prefix)))
+                        .collect::<Members>(),
+                    "additionalProperties": false,
+                });
+                if let Some(description) = description {
+                    schema["description"] = Value::from(description.as_str());
+                }
+                if !required.is_empty() {
+                    schema["required"] = json!(required);
+                }
+                schema
+            }
+        }
+    }
+
+    /// Adds the component names referenced by the component to the set.
+    fn references<'a>(&'a self, references: &mut BTreeSet<&'a str>) {
+        match self {
+            Self::Enum(_) => {}
+            Self::Composite(attributes) => {
+                for (_, kind) in attributes {
+                    kind.references(references);
+                }
+            }
+            Self::Table(_, properties) => {
+                for property in properties {
+                    property.kind.references(references);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A user-defined or array type resolved by
+/// [`JsonSchemaExporter::resolve_types`].
+enum ResolvedType {
+    /// An enum type, with its component name and labels.
+    Enum(String, Vec<String>),
+    /// A composite type, with its component name and its attributes with
+    /// their type OIDs.
+    Composite(String, Vec<(String, u32)>),
+    /// The row type of a table, with the component name of the table.
+    Table(String),
+    /// A domain, with the OID of its base type.
+    Domain(u32),
+    /// An array, with the OID of its element type.
+    Array(u32),
+    /// A built-in type, with its name.
+    Scalar(String),
+}
+
+/// Exports the tables and user-defined types of a [`PgDieselDatabase`] as
+/// JSON Schema and as `OpenAPI` 3.1 component schemas.
+pub struct JsonSchemaExporter<'db> {
+    /// The database whose tables are exported.
+    database: &'db PgDieselDatabase,
+    /// The index of the objects of the database.
+    index: ObjectIndex<'db>,
+    /// The types used by the columns, by OID.
+    types: HashMap<u32, ResolvedType>,
+}
+
+impl<'db> JsonSchemaExporter<'db> {
+    #[must_use]
+    /// Creates a new `JsonSchemaExporter` of the tables and views of the
+    /// database.
+    ///
+    /// Until [`resolve_types`](Self::resolve_types) is called, columns of
+    /// enum, composite and domain types admit any value.
+    pub fn new(database: &'db PgDieselDatabase) -> Self {
+        Self {
+            database,
+            index: ObjectIndex::new(database),
+            types: HashMap::new(),
+        }
+    }
+
+    /// Loads the labels of the enum types, the attributes of the composite
+    /// types, the base types of the domains and the element types of the
+    /// arrays used by the columns.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn resolve_types(mut self, conn: &mut PgConnection) -> Result<Self, diesel::result::Error> {
+        let database = self.database;
+        let mut pending: Vec<PgType> = Vec::new();
+        for table in database.tables() {
+            for column in database
+                .table_metadata(table)
+                .expect("Table must exist in database")
+                .columns()
+            {
+                pending.push(
+                    database
+                        .column_metadata(column)
+                        .expect("Column must exist in database")
+                        .pg_type()
+                        .clone(),
+                );
+            }
+        }
+        while let Some(pg_type) = pending.pop() {
+            if self.types.contains_key(&pg_type.oid) {
+                continue;
+            }
+            let resolved = if pg_type.typbasetype != 0 {
+                pending.push(PgType::from_oid(pg_type.typbasetype, conn)?);
+                ResolvedType::Domain(pg_type.typbasetype)
+            } else if pg_type.typcategory == "A" && pg_type.typelem != 0 {
+                pending.push(PgType::from_oid(pg_type.typelem, conn)?);
+                ResolvedType::Array(pg_type.typelem)
+            } else if pg_type.is_enum() || pg_type.is_composite() {
+                self.user_defined_type(&pg_type, &mut pending, conn)?
+            } else {
+                ResolvedType::Scalar(pg_type.typname.clone())
+            };
+            self.types.insert(pg_type.oid, resolved);
+        }
+        Ok(self)
+    }
+
+    /// Resolves the enum or composite type, queueing the types of the
+    /// attributes of composite types.
+    fn user_defined_type(
+        &self,
+        pg_type: &PgType,
+        pending: &mut Vec<PgType>,
+        conn: &mut PgConnection,
+    ) -> Result<ResolvedType, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_namespace::pg_namespace;
+
+        let schema: String = pg_namespace::table
+            .filter(pg_namespace::oid.eq(pg_type.typnamespace))
+            .select(pg_namespace::nspname)
+            .first(conn)?;
+        let name = component_name(&schema, &pg_type.typname);
+        if pg_type.is_enum() {
+            let labels = pg_type
+                .variants(conn)?
+                .into_iter()
+                .map(|variant| variant.enumlabel)
+                .collect();
+            return Ok(ResolvedType::Enum(name, labels));
+        }
+        if self
+            .index
+            .table_by_name(&QualifiedName::new(&schema, &pg_type.typname))
+            .is_some()
+        {
+            return Ok(ResolvedType::Table(name));
+        }
+        let mut attributes = pg_type.attributes(conn)?;
+        attributes.sort_by_key(|attribute| attribute.attnum);
+        for attribute in &attributes {
+            pending.push(attribute.pg_type(conn)?);
+        }
+        Ok(ResolvedType::Composite(
+            name,
+            attributes
+                .into_iter()
+                .map(|attribute| (attribute.attname, attribute.atttypid))
+                .collect(),
+        ))
+    }
+
+    /// Returns the kind of the type with the provided OID, falling back on
+    /// the provided type when it was not resolved.
+    fn kind(&self, oid: u32, fallback: Option<&PgType>) -> TypeKind {
+        match self.types.get(&oid) {
+            Some(
+                ResolvedType::Enum(name, _)
+                | ResolvedType::Composite(name, _)
+                | ResolvedType::Table(name),
+            ) => TypeKind::Reference(name.clone()),
+            Some(ResolvedType::Domain(base)) => self.kind(*base, None),
+            Some(ResolvedType::Array(element)) => {
+                TypeKind::Array(Box::new(self.kind(*element, None)))
+            }
+            Some(ResolvedType::Scalar(typname)) => TypeKind::Scalar(typname.clone()),
+            None => match fallback {
+                Some(pg_type) if pg_type.typcategory == "A" => TypeKind::Array(Box::new(
+                    TypeKind::Scalar(pg_type.typname.trim_start_matches('_').to_owned()),
+                )),
+                Some(pg_type)
+                    if pg_type.typbasetype == 0
+                        && !pg_type.is_enum()
+                        && !pg_type.is_composite() =>
+                {
+                    TypeKind::Scalar(pg_type.typname.clone())
+                }
+                _ => TypeKind::Scalar(String::new()),
+            },
+        }
+    }
+
+    /// Returns the component of the table.
+    fn table_component(&self, table: &Table) -> Component {
+        let metadata = self
+            .database
+            .table_metadata(table)
+            .expect("Table must exist in database");
+        let mut columns: Vec<_> = metadata.columns().collect();
+        columns.sort_by_key(|column| column.ordinal_position);
+        let properties = columns
+            .into_iter()
+            .map(|column| {
+                let column_metadata = self
+                    .database
+                    .column_metadata(column)
+                    .expect("Column must exist in database");
+                let mut bounds = ColumnBounds::default();
+                for check_constraint in metadata.check_constraints() {
+                    bounds.restrict(
+                        CheckConstraintLike::expression(check_constraint, self.database),
+                        &column.column_name,
+                    );
+                }
+                let pg_type = column_metadata.pg_type();
+                PropertyShape {
+                    name: column.column_name.clone(),
+                    kind: self.kind(pg_type.oid, Some(pg_type)),
+                    max_length: column
+                        .character_maximum_length
+                        .and_then(|length| usize::try_from(length).ok()),
+                    nullable: column.__is_nullable == "YES" && !bounds.not_null(),
+                    bounds,
+                    has_default: column.column_default.is_some()
+                        || column.is_identity.as_deref() == Some("YES"),
+                    generated: column.is_generated == "ALWAYS"
+                        || column.generation_expression.is_some()
+                        || column.identity_generation.as_deref() == Some("ALWAYS"),
+                    description: column_metadata
+                        .description()
+                        .map(|description| description.description.clone()),
+                }
+            })
+            .collect();
+        Component::Table(
+            metadata
+                .description()
+                .map(|description| description.description.clone()),
+            properties,
+        )
+    }
+
+    /// Returns the components of the tables and of the enum and composite
+    /// types, by name.
+    fn components(&self) -> BTreeMap<String, Component> {
+        let mut components: BTreeMap<String, Component> = self
+            .database
+            .tables()
+            .map(|table| {
+                (
+                    component_name(&table.table_schema, &table.table_name),
+                    self.table_component(table),
+                )
+            })
+            .collect();
+        for resolved in self.types.values() {
+            match resolved {
+                ResolvedType::Enum(name, labels) => {
+                    components.insert(name.clone(), Component::Enum(labels.clone()));
+                }
+                ResolvedType::Composite(name, attributes) => {
+                    components.insert(
+                        name.clone(),
+                        Component::Composite(
+                            attributes
+                                .iter()
+                                .map(|(attribute, oid)| (attribute.clone(), self.kind(*oid, None)))
+                                .collect(),
+                        ),
+                    );
+                }
+                _ => {}
+            }
+        }
+        components
+    }
+
+    #[must_use]
+    /// Returns a JSON Schema document whose `$defs` hold the read and write
+    /// variants of every table, the latter suffixed with `.write`, and the
+    /// enum and composite types, all named `<schema>.<name>`.
+    pub fn json_schema(&self) -> String {
+        let document = json!({
+            "$schema": DIALECT,
+            "$defs": definitions(&self.components(), "#/$defs/"),
+        });
+        format!("{document:#}")
+    }
+
+    #[must_use]
+    /// Returns the JSON Schema document of the provided variant of the
+    /// table, with the enum and composite types it uses in its `$defs`, or
+    /// `None` if the table does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the table.
+    /// * `table` - The name of the table.
+    /// * `variant` - The variant of the schema.
+    pub fn table_json_schema(
+        &self,
+        schema: &str,
+        table: &str,
+        variant: SchemaVariant,
+    ) -> Option<String> {
+        let table = self
+            .index
+            .table_by_name(&QualifiedName::new(schema, table))?;
+        let name = component_name(&table.table_schema, &table.table_name);
+        let components = self.components();
+        Some(format!("{:#}", standalone(&components, &name, variant)))
+    }
+
+    #[must_use]
+    /// Returns an `OpenAPI` 3.1 `components` object whose `schemas` hold the
+    /// read and write variants of every table, the latter suffixed with
+    /// `.write`, and the enum and composite types, all named
+    /// `<schema>.<name>`, to be merged into an `OpenAPI` document.
+    pub fn openapi_components(&self) -> String {
+        let document = json!({
+            "components": {
+                "schemas": definitions(&self.components(), "#/components/schemas/"),
+            },
+        });
+        format!("{document:#}")
+    }
+}
+
+/// Returns the schemas of the components, with both variants of the tables,
+/// referencing each other under the provided prefix.
+fn definitions(components: &BTreeMap<String, Component>, prefix: &str) -> Members {
+    let mut definitions = Vec::new();
+    for (name, component) in components {
+        definitions.push((name.clone(), component.schema(prefix, SchemaVariant::Read)));
+        if matches!(component, Component::Table(..)) {
+            definitions.push((
+                variant_name(name, SchemaVariant::Write),
+                component.schema(prefix, SchemaVariant::Write),
+            ));
+        }
+    }
+    definitions.sort_by(|left, right| left.0.cmp(&right.0));
+    definitions.into_iter().collect()
+}
+
+/// Returns the JSON Schema document of the variant of the component, with
+/// the components it transitively references in its `$defs`.
+fn standalone(
+    components: &BTreeMap<String, Component>,
+    name: &str,
+    variant: SchemaVariant,
+) -> Value {
+    let mut members = Members::new();
+    members.insert("$schema".to_owned(), Value::from(DIALECT));
+    members.insert(
+        "title".to_owned(),
+        Value::String(variant_name(name, variant)),
+    );
+    let Some(Value::Object(schema)) = components
+        .get(name)
+        .map(|component| component.schema("#/$defs/", variant))
+    else {
+        return Value::Object(members);
+    };
+    members.extend(schema);
+
+    let mut referenced: BTreeSet<&str> = BTreeSet::new();
+    let mut pending = vec![name];
+    while let Some(current) = pending.pop() {
+        let Some(component) = components.get(current) else {
+            continue;
+        };
+        let mut direct = BTreeSet::new();
+        component.references(&mut direct);
+        for reference in direct {
+            if reference != name && referenced.insert(reference) {
+                pending.push(reference);
+            }
+        }
+    }
+    if !referenced.is_empty() {
+        let defs: BTreeMap<String, Component> = referenced
+            .into_iter()
+            .filter_map(|reference| {
+                components
+                    .get(reference)
+                    .map(|component| (reference.to_owned(), component.clone()))
+            })
+            .collect();
+        members.insert(
+            "$defs".to_owned(),
+            Value::Object(definitions(&defs, "#/$defs/")),
+        );
+    }
+    Value::Object(members)
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::{dialect::PostgreSqlDialect, parser::Parser};
+
+    use super::*;
+
+    fn bounds(expression: &str, column_name: &str) -> ColumnBounds {
+        let expression = Parser::new(&PostgreSqlDialect {})
+            .try_with_sql(expression)
+            .unwrap()
+            .parse_expr()
+            .unwrap();
+        ColumnBounds::from_expression(&expression, column_name)
+    }
+
+    fn property(name: &str, kind: TypeKind, nullable: bool) -> PropertyShape {
+        PropertyShape {
+            name: name.to_owned(),
+            kind,
+            bounds: ColumnBounds::default(),
+            max_length: None,
+            nullable,
+            has_default: false,
+            generated: false,
+            description: None,
+        }
+    }
+
+    fn components() -> BTreeMap<String, Component> {
+        let mut id = property("id", TypeKind::Scalar("int8".to_owned()), false);
+        id.generated = true;
+        let mut email = property("email", TypeKind::Scalar("varchar".to_owned()), false);
+        email.max_length = Some(255);
+        email.description = Some("Login of the user.".to_owned());
+        let age = property("age", TypeKind::Scalar("int2".to_owned()), true);
+        let mut created_at = property(
+            "created_at",
+            TypeKind::Scalar("timestamptz".to_owned()),
+            false,
+        );
+        created_at.has_default = true;
+        let mood = property("mood", TypeKind::Reference("public.mood".to_owned()), true);
+        let tags = property(
+            "tags",
+            TypeKind::Array(Box::new(TypeKind::Scalar("uuid".to_owned()))),
+            false,
+        );
+        [
+            (
+                "public.users".to_owned(),
+                Component::Table(
+                    Some("Registered users.".to_owned()),
+                    vec![id, email, age, created_at, mood, tags],
+                ),
+            ),
+            (
+                "public.mood".to_owned(),
+                Component::Enum(vec!["happy".to_owned(), "sad".to_owned()]),
+            ),
+            (
+                "public.unused".to_owned(),
+                Component::Enum(vec!["unused".to_owned()]),
+            ),
+        ]
+        .into()
+    }
+
+    #[test]
+    fn test_apply_bounds() {
+        let mut members = scalar_schema("int2");
+        let level = bounds(
+            "((level > 0) AND (level <= 100000) AND (level IN (1, 2)))",
+            "level",
+        );
+        apply_bounds(&mut members, &level, None);
+        assert_eq!(
+            Value::Object(nullable(members)),
+            json!({
+                "type": ["integer", "null"],
+                "minimum": -32768,
+                "maximum": 32767,
+                "exclusiveMinimum": 0,
+                "enum": [1, 2, null],
+            })
+        );
+
+        let mut members = scalar_schema("varchar");
+        let name = bounds(
+            "((length((name)::text) >= 3) AND (length((name)::text) <= 300))",
+            "name",
+        );
+        apply_bounds(&mut members, &name, Some(255));
+        assert_eq!(
+            Value::Object(members),
+            json!({"type": "string", "minLength": 3, "maxLength": 255})
+        );
+        assert_eq!(scalar_schema("numeric")["pattern"], NUMERIC_PATTERN);
+    }
+
+    #[test]
+    fn test_table_variants() {
+        let components = components();
+        let users = &components["public.users"];
+        let read = users.schema("#/$defs/", SchemaVariant::Read);
+        assert_eq!(
+            read["properties"]["id"],
+            json!({"type": "integer", "format": "int64", "readOnly": true})
+        );
+        assert_eq!(
+            read["properties"]["email"],
+            json!({"type": "string", "maxLength": 255, "description": "Login of the user."})
+        );
+        assert_eq!(
+            read["properties"]["mood"],
+            json!({"anyOf": [{"$ref": "#/$defs/public.mood"}, {"type": "null"}]})
+        );
+        assert_eq!(
+            read["properties"]["tags"]["items"],
+            json!({"type": "string", "format": "uuid"})
+        );
+        assert_eq!(
+            read["required"],
+            json!(["id", "email", "created_at", "tags"])
+        );
+        assert_eq!(read["description"], "Registered users.");
+        assert_eq!(read["additionalProperties"], false);
+
+        let write = users.schema("#/$defs/", SchemaVariant::Write);
+        assert!(write["properties"].get("id").is_none());
+        assert_eq!(write["required"], json!(["email", "tags"]));
+
+        let document = standalone(&components, "public.users", SchemaVariant::Write);
+        assert_eq!(document["$schema"], DIALECT);
+        assert_eq!(document["title"], "public.users.write");
+        assert_eq!(document["type"], "object");
+        assert_eq!(
+            document["$defs"],
+            json!({"public.mood": {"type": "string", "enum": ["happy", "sad"]}})
+        );
+
+        let definitions = definitions(&components, "#/components/schemas/");
+        let names: Vec<&String> = definitions.keys().collect();
+        assert_eq!(
+            names,
+            [
+                "public.mood",
+                "public.unused",
+                "public.users",
+                "public.users.write"
+            ]
+        );
+        assert_eq!(component_name("my schema", "user.v2"), "my_schema.user_v2");
+    }
+}
@@ -1,9 +1,33 @@
 #![doc = include_str!("../README.md")]
 
+pub mod acl;
+pub mod config_drift;
+pub mod constraint_bounds;
+pub mod data_dictionary;
 pub mod database;
+pub mod erd;
+pub mod evaluator;
+pub mod extensions;
+pub mod hba_audit;
+pub mod identity;
 pub mod impls;
+pub mod index_advisor;
+#[cfg(feature = "serde")]
+pub mod json_schema;
+pub mod lint;
+pub mod lock_graph;
 pub mod models;
+mod quoting;
+pub mod replication;
 pub mod schema;
+pub mod security_audit;
+pub mod severity;
+pub mod snapshot;
+pub mod statement_analytics;
+pub mod text_search;
 pub mod traits;
+pub mod vacuum_report;
 pub use database::PgDieselDatabase;
 pub mod model_metadata;
+pub mod synthesis;
+pub mod validators;
@@ -0,0 +1,568 @@
+//! Submodule linting the tables of a [`PgDieselDatabase`] against
+//! configurable rules.
+//!
+//! A [`SchemaLinter`] runs [`LintRule`]s over the base tables of the
+//! database, through the `sql_traits` traits. The built-in rules, listed in
+//! [`rules`], cover primary keys, indexes supporting foreign keys, column
+//! types, comments, naming conventions and reserved key words; custom rules
+//! are added by implementing [`LintRule`]. Each rule may be allowed, reported
+//! as a warning or denied.
+//!
+//! Findings are suppressed for an object by annotating its comment with
+//! `lint:allow(<rule>, ...)`, or `lint:allow(all)`: an annotation on a table
+//! applies to the table and its columns. For instance:
+//!
+//! ```sql
+//! COMMENT ON TABLE audit_log IS 'Append-only log. lint:allow(missing-primary-key)';
+//! ```
+//!
+//! The [`LintReport`] is rendered as text by its `Display` implementation, and
+//! as JSON by `LintReport::to_json` with the `serde` feature.
+
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use sql_traits::traits::{ColumnLike, DatabaseLike, TableLike};
+
+use crate::{PgDieselDatabase, index_advisor::IndexUsage, models::Table};
+
+pub mod rules;
+
+use rules::{
+    ForeignKeySuffix, JsonColumn, MissingColumnComment, MissingPrimaryKey, MissingTableComment,
+    NullableBoolean, PluralTableName, ReservedWord, SnakeCase, TimestampWithoutTimeZone,
+    UnindexedForeignKey, VarcharLength,
+};
+
+/// The annotation introducing the rules suppressed by a comment.
+const SUPPRESSION_PREFIX: &str = "lint:allow(";
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+/// Errors that can occur when configuring a [`SchemaLinter`].
+pub enum LintError {
+    #[error("Unknown lint level `{0}`, expected `allow`, `warn` or `deny`")]
+    /// The level is not one of `allow`, `warn` or `deny`.
+    UnknownLevel(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+/// How the findings of a rule are reported.
+pub enum LintLevel {
+    /// The rule is not run.
+    Allow,
+    /// The findings are reported as warnings.
+    Warn,
+    /// The findings are reported as errors.
+    Deny,
+}
+
+impl Display for LintLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Allow => "allow",
+            Self::Warn => "warn",
+            Self::Deny => "deny",
+        })
+    }
+}
+
+impl FromStr for LintLevel {
+    type Err = LintError;
+
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level.trim().to_ascii_lowercase().as_str() {
+            "allow" => Ok(Self::Allow),
+            "warn" | "warning" => Ok(Self::Warn),
+            "deny" | "error" => Ok(Self::Deny),
+            _ => Err(LintError::UnknownLevel(level.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A violation of a rule found in a table, before the engine attaches the
+/// rule, the level and the table to it.
+pub struct LintFinding {
+    /// The column the violation is about, if not the whole table.
+    column: Option<String>,
+    /// The description of the violation.
+    message: String,
+}
+
+impl LintFinding {
+    /// Creates a new `LintFinding` about the whole table.
+    #[must_use]
+    pub fn table<M: AsRef<str>>(message: M) -> Self {
+        Self {
+            column: None,
+            message: message.as_ref().to_string(),
+        }
+    }
+
+    /// Creates a new `LintFinding` about a column of the table.
+    #[must_use]
+    pub fn column<C: AsRef<str>, M: AsRef<str>>(column: C, message: M) -> Self {
+        Self {
+            column: Some(column.as_ref().to_string()),
+            message: message.as_ref().to_string(),
+        }
+    }
+}
+
+/// The database being linted, with the indexes known to the linter.
+pub struct LintContext<'db> {
+    /// The database being linted.
+    database: &'db PgDieselDatabase,
+    /// The key columns of the indexes loaded into the linter, by schema and
+    /// table name.
+    indexes: &'db HashMap<(String, String), Vec<Vec<String>>>,
+}
+
+impl<'db> LintContext<'db> {
+    /// Returns the database being linted.
+    #[must_use]
+    pub fn database(&self) -> &'db PgDieselDatabase {
+        self.database
+    }
+
+    /// Returns the key columns of the indexes of the table: its primary key,
+    /// its unique indexes and the indexes loaded with
+    /// [`SchemaLinter::indexes`]. Expressions end the key columns of an index.
+    #[must_use]
+    pub fn index_key_columns(&self, table: &Table) -> Vec<Vec<String>> {
+        let columns: Vec<&crate::models::Column> =
+            TableLike::columns(table, self.database).collect();
+        let mut indexes: Vec<Vec<String>> = vec![
+            TableLike::primary_key_columns(table, self.database)
+                .map(|column| column.column_name.clone())
+                .collect(),
+        ];
+        indexes.extend(
+            TableLike::unique_indices(table, self.database).map(|index| {
+                index
+                    .indkey
+                    .iter()
+                    .map_while(|attnum| {
+                        columns
+                            .iter()
+                            .find(|column| i32::from(*attnum) == column.ordinal_position)
+                            .map(|column| column.column_name.clone())
+                    })
+                    .collect()
+            }),
+        );
+        if let Some(loaded) = self
+            .indexes
+            .get(&(table.table_schema.clone(), table.table_name.clone()))
+        {
+            indexes.extend(loaded.iter().cloned());
+        }
+        indexes.retain(|key| !key.is_empty());
+        indexes
+    }
+}
+
+/// A check run by the [`SchemaLinter`] over each base table.
+pub trait LintRule {
+    /// Returns the stable identifier of the rule, as in
+    /// `missing-primary-key`, used to configure and suppress it.
+    fn id(&self) -> &str;
+
+    /// Returns a short description of what the rule expects.
+    fn description(&self) -> &str;
+
+    /// Returns the level of the rule unless configured otherwise.
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Warn
+    }
+
+    /// Returns the violations of the rule by the table.
+    fn check(&self, table: &Table, context: &LintContext<'_>) -> Vec<LintFinding>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A violation of a rule reported by the [`SchemaLinter`].
+pub struct LintViolation {
+    /// The identifier of the violated rule.
+    rule: String,
+    /// The level the rule is configured with.
+    level: LintLevel,
+    /// The schema of the table.
+    schema: String,
+    /// The name of the table.
+    table: String,
+    /// The column the violation is about, if not the whole table.
+    column: Option<String>,
+    /// The description of the violation.
+    message: String,
+}
+
+impl LintViolation {
+    /// Returns the identifier of the violated rule.
+    #[must_use]
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    /// Returns the level the rule is configured with.
+    #[must_use]
+    pub fn level(&self) -> LintLevel {
+        self.level
+    }
+
+    /// Returns the schema of the table.
+    #[must_use]
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// Returns the name of the table.
+    #[must_use]
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Returns the column the violation is about, if not the whole table.
+    #[must_use]
+    pub fn column(&self) -> Option<&str> {
+        self.column.as_deref()
+    }
+
+    /// Returns the description of the violation.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for LintViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}] {}.{}",
+            self.level, self.rule, self.schema, self.table
+        )?;
+        if let Some(column) = &self.column {
+            write!(f, ".{column}")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The violations found by the [`SchemaLinter`].
+pub struct LintReport {
+    /// The violations which are not suppressed, by table.
+    violations: Vec<LintViolation>,
+    /// The number of violations suppressed by comment annotations.
+    suppressed: usize,
+}
+
+impl LintReport {
+    /// Returns the violations which are not suppressed, by table.
+    #[must_use]
+    pub fn violations(&self) -> &[LintViolation] {
+        &self.violations
+    }
+
+    /// Returns the number of violations suppressed by comment annotations.
+    #[must_use]
+    pub fn suppressed(&self) -> usize {
+        self.suppressed
+    }
+
+    #[must_use]
+    /// Returns whether any violation is of a denied rule.
+    pub fn has_errors(&self) -> bool {
+        self.violations
+            .iter()
+            .any(|violation| violation.level == LintLevel::Deny)
+    }
+
+    #[cfg(feature = "serde")]
+    #[must_use]
+    /// Returns the report as a JSON object, with a `violations` array and a
+    /// `suppressed` count, for tools and CI jobs.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Lint reports must be serializable")
+    }
+}
+
+impl Display for LintReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for violation in &self.violations {
+            writeln!(f, "{violation}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the rules suppressed by the `lint:allow(...)` annotations of the
+/// comment.
+fn suppressed_rules(comment: Option<&str>) -> Vec<&str> {
+    let mut rules = Vec::new();
+    let mut rest = comment.unwrap_or_default();
+    while let Some(start) = rest.find(SUPPRESSION_PREFIX) {
+        rest = &rest[start + SUPPRESSION_PREFIX.len()..];
+        let Some(end) = rest.find(')') else {
+            break;
+        };
+        rules.extend(
+            rest[..end]
+                .split(',')
+                .map(str::trim)
+                .filter(|rule| !rule.is_empty()),
+        );
+        rest = &rest[end..];
+    }
+    rules
+}
+
+/// Returns whether the suppressed rules include the provided one.
+fn is_suppressed(suppressed: &[&str], rule: &str) -> bool {
+    suppressed
+        .iter()
+        .any(|suppressed| *suppressed == rule || *suppressed == "all")
+}
+
+/// Linter running [`LintRule`]s over the base tables of a database.
+pub struct SchemaLinter {
+    /// The rules to run, in order.
+    rules: Vec<Box<dyn LintRule>>,
+    /// The configured levels, by rule identifier.
+    levels: HashMap<String, LintLevel>,
+    /// The key columns of the indexes loaded with [`SchemaLinter::indexes`],
+    /// by schema and table name.
+    indexes: HashMap<(String, String), Vec<Vec<String>>>,
+}
+
+impl Default for SchemaLinter {
+    fn default() -> Self {
+        Self::empty()
+            .rule(MissingPrimaryKey)
+            .rule(UnindexedForeignKey)
+            .rule(TimestampWithoutTimeZone)
+            .rule(VarcharLength::default())
+            .rule(JsonColumn)
+            .rule(NullableBoolean)
+            .rule(MissingTableComment)
+            .rule(MissingColumnComment)
+            .rule(SnakeCase)
+            .rule(PluralTableName)
+            .rule(ForeignKeySuffix)
+            .rule(ReservedWord)
+    }
+}
+
+impl SchemaLinter {
+    /// Creates a new `SchemaLinter` with the built-in rules.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new `SchemaLinter` without any rule.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            rules: Vec::new(),
+            levels: HashMap::new(),
+            indexes: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    /// Adds a rule, replacing any rule with the same identifier, as to
+    /// reconfigure the [`VarcharLength`] built-in rule.
+    pub fn rule<R: LintRule + 'static>(mut self, rule: R) -> Self {
+        self.rules.retain(|existing| existing.id() != rule.id());
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    #[must_use]
+    /// Sets the level of the rule with the provided identifier.
+    pub fn level<S: AsRef<str>>(mut self, rule: S, level: LintLevel) -> Self {
+        self.levels.insert(rule.as_ref().to_string(), level);
+        self
+    }
+
+    #[must_use]
+    /// Loads the non-unique indexes, which the [`PgDieselDatabase`] does not
+    /// hold, so that they are known to [`LintContext::index_key_columns`].
+    pub fn indexes(mut self, indexes: &[IndexUsage]) -> Self {
+        for index in indexes.iter().filter(|index| index.is_valid) {
+            let key_columns = index
+                .key_columns
+                .iter()
+                .map(|column| {
+                    column
+                        .strip_prefix('"')
+                        .and_then(|column| column.strip_suffix('"'))
+                        .map_or_else(|| column.clone(), |column| column.replace("\"\"", "\""))
+                })
+                .collect();
+            self.indexes
+                .entry((index.schema_name.clone(), index.table_name.clone()))
+                .or_default()
+                .push(key_columns);
+        }
+        self
+    }
+
+    /// Returns the rules, in the order they are run.
+    pub fn rules(&self) -> impl Iterator<Item = &dyn LintRule> {
+        self.rules.iter().map(Box::as_ref)
+    }
+
+    #[must_use]
+    /// Returns the level the rule is run with.
+    pub fn rule_level(&self, rule: &dyn LintRule) -> LintLevel {
+        self.levels
+            .get(rule.id())
+            .copied()
+            .unwrap_or_else(|| rule.default_level())
+    }
+
+    #[must_use]
+    /// Lints the base tables of the database.
+    pub fn lint(&self, database: &PgDieselDatabase) -> LintReport {
+        let context = LintContext {
+            database,
+            indexes: &self.indexes,
+        };
+        let mut report = LintReport::default();
+        for table in database
+            .tables()
+            .filter(|table| table.table_type == "BASE TABLE")
+        {
+            let table_suppressed = suppressed_rules(TableLike::table_doc(table, database));
+            let column_suppressed: HashMap<&str, Vec<&str>> = TableLike::columns(table, database)
+                .map(|column| {
+                    (
+                        column.column_name.as_str(),
+                        suppressed_rules(ColumnLike::column_doc(column, database)),
+                    )
+                })
+                .collect();
+            for rule in &self.rules {
+                let level = self.rule_level(rule.as_ref());
+                if level == LintLevel::Allow {
+                    continue;
+                }
+                for finding in rule.check(table, &context) {
+                    let suppressed = is_suppressed(&table_suppressed, rule.id())
+                        || finding
+                            .column
+                            .as_deref()
+                            .and_then(|column| column_suppressed.get(column))
+                            .is_some_and(|suppressed| is_suppressed(suppressed, rule.id()));
+                    if suppressed {
+                        report.suppressed += 1;
+                        continue;
+                    }
+                    report.violations.push(LintViolation {
+                        rule: rule.id().to_owned(),
+                        level,
+                        schema: table.table_schema.clone(),
+                        table: table.table_name.clone(),
+                        column: finding.column,
+                        message: finding.message,
+                    });
+                }
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suppressed_rules() {
+        assert_eq!(
+            suppressed_rules(Some(
+                "Append-only log. lint:allow(missing-primary-key, snake-case) lint:allow(all)"
+            )),
+            ["missing-primary-key", "snake-case", "all"]
+        );
+        assert!(suppressed_rules(Some("lint:allow(unterminated")).is_empty());
+        assert!(suppressed_rules(None).is_empty());
+        assert!(is_suppressed(&["all"], "reserved-word"));
+        assert!(!is_suppressed(&["snake-case"], "reserved-word"));
+    }
+
+    #[test]
+    fn test_levels() {
+        assert_eq!("Deny".parse::<LintLevel>(), Ok(LintLevel::Deny));
+        assert_eq!("warning".parse::<LintLevel>(), Ok(LintLevel::Warn));
+        assert!(matches!(
+            "loud".parse::<LintLevel>(),
+            Err(LintError::UnknownLevel(_))
+        ));
+        let linter = SchemaLinter::new()
+            .level("snake-case", LintLevel::Deny)
+            .rule(rules::VarcharLength::new(
+                rules::VarcharPolicy::RequireLength,
+            ));
+        assert_eq!(linter.rules().count(), 12);
+        let levels: HashMap<&str, LintLevel> = linter
+            .rules()
+            .map(|rule| (rule.id(), linter.rule_level(rule)))
+            .collect();
+        assert_eq!(levels["snake-case"], LintLevel::Deny);
+        assert_eq!(levels["reserved-word"], LintLevel::Deny);
+        assert_eq!(levels["json-column"], LintLevel::Warn);
+        assert_eq!(
+            linter.rules().last().map(LintRule::description),
+            Some("Character columns should have a length limit")
+        );
+    }
+
+    #[test]
+    fn test_report_output() {
+        let report = LintReport {
+            violations: vec![
+                LintViolation {
+                    rule: "missing-primary-key".to_owned(),
+                    level: LintLevel::Warn,
+                    schema: "public".to_owned(),
+                    table: "audit_log".to_owned(),
+                    column: None,
+                    message: "table has no primary key".to_owned(),
+                },
+                LintViolation {
+                    rule: "reserved-word".to_owned(),
+                    level: LintLevel::Deny,
+                    schema: "public".to_owned(),
+                    table: "events".to_owned(),
+                    column: Some("user".to_owned()),
+                    message: "column name is a \"reserved\" key word".to_owned(),
+                },
+            ],
+            suppressed: 2,
+        };
+        assert!(report.has_errors());
+        assert_eq!(
+            report.to_string(),
+            "warn[missing-primary-key] public.audit_log: table has no primary key\n\
+             deny[reserved-word] public.events.user: column name is a \"reserved\" key word\n"
+        );
+        #[cfg(feature = "serde")]
+        assert_eq!(
+            report.to_json(),
+            "{\"violations\":[\
+             {\"rule\":\"missing-primary-key\",\"level\":\"warn\",\"schema\":\"public\",\
+             \"table\":\"audit_log\",\"column\":null,\"message\":\"table has no primary key\"},\
+             {\"rule\":\"reserved-word\",\"level\":\"deny\",\"schema\":\"public\",\
+             \"table\":\"events\",\"column\":\"user\",\
+             \"message\":\"column name is a \\\"reserved\\\" key word\"}\
+             ],\"suppressed\":2}"
+        );
+    }
+}
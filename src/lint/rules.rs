@@ -0,0 +1,579 @@
+//! Submodule providing the built-in rules of the
+//! [`SchemaLinter`](super::SchemaLinter).
+
+use sql_traits::traits::{ColumnLike, ForeignKeyLike, TableLike};
+
+use super::{LintContext, LintFinding, LintLevel, LintRule};
+use crate::models::{Column, Table};
+
+/// The reserved key words of `PostgreSQL`, including those which may be used
+/// as function or type names, which must be quoted when used as identifiers.
+const RESERVED_WORDS: &[&str] = &[
+    "all",
+    "analyse",
+    "analyze",
+    "and",
+    "any",
+    "array",
+    "as",
+    "asc",
+    "asymmetric",
+    "authorization",
+    "binary",
+    "both",
+    "case",
+    "cast",
+    "check",
+    "collate",
+    "collation",
+    "column",
+    "concurrently",
+    "constraint",
+    "create",
+    "cross",
+    "current_catalog",
+    "current_date",
+    "current_role",
+    "current_schema",
+    "current_time",
+    "current_timestamp",
+    "current_user",
+    "default",
+    "deferrable",
+    "desc",
+    "distinct",
+    "do",
+    "else",
+    "end",
+    "except",
+    "false",
+    "fetch",
+    "for",
+    "foreign",
+    "freeze",
+    "from",
+    "full",
+    "grant",
+    "group",
+    "having",
+    "ilike",
+    "in",
+    "initially",
+    "inner",
+    "intersect",
+    "into",
+    "is",
+    "isnull",
+    "join",
+    "lateral",
+    "leading",
+    "left",
+    "like",
+    "limit",
+    "localtime",
+    "localtimestamp",
+    "natural",
+    "not",
+    "notnull",
+    "null",
+    "offset",
+    "on",
+    "only",
+    "or",
+    "order",
+    "outer",
+    "overlaps",
+    "placing",
+    "primary",
+    "references",
+    "returning",
+    "right",
+    "select",
+    "session_user",
+    "similar",
+    "some",
+    "symmetric",
+    "system_user",
+    "table",
+    "tablesample",
+    "then",
+    "to",
+    "trailing",
+    "true",
+    "union",
+    "unique",
+    "user",
+    "using",
+    "variadic",
+    "verbose",
+    "when",
+    "where",
+    "window",
+    "with",
+];
+
+/// Nouns whose plural does not end with an `s`, or which have no plural.
+const IRREGULAR_PLURALS: &[&str] = &[
+    "children",
+    "criteria",
+    "data",
+    "equipment",
+    "feedback",
+    "information",
+    "media",
+    "men",
+    "metadata",
+    "people",
+    "staff",
+    "women",
+];
+
+/// Returns the name of the element type of array types, which `PostgreSQL`
+/// prefixes by an underscore.
+fn element_type(type_name: &str) -> &str {
+    type_name.strip_prefix('_').unwrap_or(type_name)
+}
+
+/// Returns the findings of the columns of the table whose element type is the
+/// provided one.
+fn columns_of_type(
+    table: &Table,
+    context: &LintContext<'_>,
+    type_name: &str,
+    message: &str,
+) -> Vec<LintFinding> {
+    let database = context.database();
+    TableLike::columns(table, database)
+        .filter(|column| element_type(ColumnLike::data_type(*column, database)) == type_name)
+        .map(|column| LintFinding::column(&column.column_name, message))
+        .collect()
+}
+
+/// Returns whether the identifier is in `snake_case`: lowercase ASCII letters,
+/// digits and single underscores between words.
+#[must_use]
+pub fn is_snake_case(identifier: &str) -> bool {
+    identifier.starts_with(|c: char| c.is_ascii_lowercase())
+        && !identifier.ends_with('_')
+        && !identifier.contains("__")
+        && identifier
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Returns whether the last word of the `snake_case` identifier is plural,
+/// as judged from its ending.
+#[must_use]
+pub fn is_plural(identifier: &str) -> bool {
+    let word = identifier.rsplit('_').next().unwrap_or(identifier);
+    IRREGULAR_PLURALS.contains(&word)
+        || (word.ends_with('s') && !word.ends_with("ss") && !word.ends_with("us"))
+}
+
+/// Returns whether the identifier is a reserved key word of `PostgreSQL`.
+#[must_use]
+pub fn is_reserved_word(identifier: &str) -> bool {
+    RESERVED_WORDS.contains(&identifier.to_ascii_lowercase().as_str())
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Reports tables without a primary key, whose rows cannot be reliably
+/// addressed nor replicated logically.
+pub struct MissingPrimaryKey;
+
+impl LintRule for MissingPrimaryKey {
+    fn id(&self) -> &str {
+        "missing-primary-key"
+    }
+
+    fn description(&self) -> &str {
+        "Tables should have a primary key"
+    }
+
+    fn check(&self, table: &Table, context: &LintContext<'_>) -> Vec<LintFinding> {
+        if TableLike::primary_key_columns(table, context.database())
+            .next()
+            .is_some()
+        {
+            return Vec::new();
+        }
+        vec![LintFinding::table("table has no primary key")]
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Reports foreign keys whose columns are not the leading columns of an
+/// index, making deletions of the referenced rows scan the whole table.
+pub struct UnindexedForeignKey;
+
+impl LintRule for UnindexedForeignKey {
+    fn id(&self) -> &str {
+        "unindexed-foreign-key"
+    }
+
+    fn description(&self) -> &str {
+        "Foreign keys should be supported by an index"
+    }
+
+    fn check(&self, table: &Table, context: &LintContext<'_>) -> Vec<LintFinding> {
+        let database = context.database();
+        let indexes = context.index_key_columns(table);
+        let mut findings = Vec::new();
+        for foreign_key in TableLike::foreign_keys(table, database) {
+            let columns: Vec<&str> = ForeignKeyLike::host_columns(foreign_key, database)
+                .map(|column| column.column_name.as_str())
+                .collect();
+            let supported = indexes.iter().any(|key| {
+                key.len() >= columns.len()
+                    && columns
+                        .iter()
+                        .all(|column| key[..columns.len()].iter().any(|key| key == column))
+            });
+            if supported {
+                continue;
+            }
+            let message = format!(
+                "foreign key `{}` on ({}) has no supporting index",
+                ForeignKeyLike::foreign_key_name(foreign_key).unwrap_or_default(),
+                columns.join(", ")
+            );
+            findings.push(match columns.as_slice() {
+                [column] => LintFinding::column(column, message),
+                _ => LintFinding::table(message),
+            });
+        }
+        findings
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Reports `timestamp` columns, which store no time zone and are
+/// interpreted according to the session.
+pub struct TimestampWithoutTimeZone;
+
+impl LintRule for TimestampWithoutTimeZone {
+    fn id(&self) -> &str {
+        "timestamp-without-time-zone"
+    }
+
+    fn description(&self) -> &str {
+        "Timestamps should be stored as `timestamptz`"
+    }
+
+    fn check(&self, table: &Table, context: &LintContext<'_>) -> Vec<LintFinding> {
+        columns_of_type(
+            table,
+            context,
+            "timestamp",
+            "`timestamp` stores no time zone, use `timestamptz`",
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Reports `json` columns, which are stored as text and reparsed on every
+/// access.
+pub struct JsonColumn;
+
+impl LintRule for JsonColumn {
+    fn id(&self) -> &str {
+        "json-column"
+    }
+
+    fn description(&self) -> &str {
+        "JSON documents should be stored as `jsonb`"
+    }
+
+    fn check(&self, table: &Table, context: &LintContext<'_>) -> Vec<LintFinding> {
+        columns_of_type(
+            table,
+            context,
+            "json",
+            "`json` is reparsed on every access and cannot be indexed, use `jsonb`",
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The policy of the [`VarcharLength`] rule on character columns.
+pub enum VarcharPolicy {
+    #[default]
+    /// Prefer `text`, reporting `varchar(n)` columns: the length limit is
+    /// better expressed by a check constraint, which can be changed without
+    /// rewriting the table.
+    PreferText,
+    /// Require a length limit, reporting `text` and unbounded `varchar`
+    /// columns.
+    RequireLength,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Reports character columns against the configured [`VarcharPolicy`].
+pub struct VarcharLength {
+    /// The policy to enforce.
+    policy: VarcharPolicy,
+}
+
+impl VarcharLength {
+    /// Creates a new `VarcharLength` rule enforcing the provided policy.
+    #[must_use]
+    pub fn new(policy: VarcharPolicy) -> Self {
+        Self { policy }
+    }
+}
+
+impl LintRule for VarcharLength {
+    fn id(&self) -> &str {
+        "varchar-length"
+    }
+
+    fn description(&self) -> &str {
+        match self.policy {
+            VarcharPolicy::PreferText => "Character columns should be `text`",
+            VarcharPolicy::RequireLength => "Character columns should have a length limit",
+        }
+    }
+
+    fn check(&self, table: &Table, context: &LintContext<'_>) -> Vec<LintFinding> {
+        let database = context.database();
+        TableLike::columns(table, database)
+            .filter_map(|column: &Column| {
+                let type_name = element_type(ColumnLike::data_type(column, database));
+                let message = match (self.policy, type_name, column.character_maximum_length) {
+                    (VarcharPolicy::PreferText, "varchar", Some(length)) => format!(
+                        "`varchar({length})` should be `text`, with a check constraint if needed"
+                    ),
+                    (VarcharPolicy::RequireLength, "text", _)
+                    | (VarcharPolicy::RequireLength, "varchar", None) => {
+                        format!("`{type_name}` has no length limit")
+                    }
+                    _ => return None,
+                };
+                Some(LintFinding::column(&column.column_name, message))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Reports nullable boolean columns, which hold three states.
+pub struct NullableBoolean;
+
+impl LintRule for NullableBoolean {
+    fn id(&self) -> &str {
+        "nullable-boolean"
+    }
+
+    fn description(&self) -> &str {
+        "Boolean columns should be `NOT NULL`"
+    }
+
+    fn check(&self, table: &Table, context: &LintContext<'_>) -> Vec<LintFinding> {
+        let database = context.database();
+        TableLike::columns(table, database)
+            .filter(|column| {
+                ColumnLike::data_type(*column, database) == "bool"
+                    && ColumnLike::is_nullable(*column, database)
+            })
+            .map(|column| {
+                LintFinding::column(
+                    &column.column_name,
+                    "nullable boolean has three states, add `NOT NULL`",
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Reports tables without a comment.
+pub struct MissingTableComment;
+
+impl LintRule for MissingTableComment {
+    fn id(&self) -> &str {
+        "missing-table-comment"
+    }
+
+    fn description(&self) -> &str {
+        "Tables should be documented with `COMMENT ON TABLE`"
+    }
+
+    fn check(&self, table: &Table, context: &LintContext<'_>) -> Vec<LintFinding> {
+        if TableLike::table_doc(table, context.database()).is_some_and(|doc| !doc.trim().is_empty())
+        {
+            return Vec::new();
+        }
+        vec![LintFinding::table("table has no comment")]
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Reports columns without a comment.
+pub struct MissingColumnComment;
+
+impl LintRule for MissingColumnComment {
+    fn id(&self) -> &str {
+        "missing-column-comment"
+    }
+
+    fn description(&self) -> &str {
+        "Columns should be documented with `COMMENT ON COLUMN`"
+    }
+
+    fn check(&self, table: &Table, context: &LintContext<'_>) -> Vec<LintFinding> {
+        let database = context.database();
+        TableLike::columns(table, database)
+            .filter(|column| {
+                !ColumnLike::column_doc(*column, database).is_some_and(|doc| !doc.trim().is_empty())
+            })
+            .map(|column| LintFinding::column(&column.column_name, "column has no comment"))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Reports table and column names which are not in `snake_case`, and must
+/// therefore be quoted.
+pub struct SnakeCase;
+
+impl LintRule for SnakeCase {
+    fn id(&self) -> &str {
+        "snake-case"
+    }
+
+    fn description(&self) -> &str {
+        "Table and column names should be in snake_case"
+    }
+
+    fn check(&self, table: &Table, context: &LintContext<'_>) -> Vec<LintFinding> {
+        let mut findings = Vec::new();
+        if !is_snake_case(&table.table_name) {
+            findings.push(LintFinding::table("table name is not in snake_case"));
+        }
+        findings.extend(
+            TableLike::columns(table, context.database())
+                .filter(|column| !is_snake_case(&column.column_name))
+                .map(|column| {
+                    LintFinding::column(&column.column_name, "column name is not in snake_case")
+                }),
+        );
+        findings
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Reports table names whose last word is not plural.
+pub struct PluralTableName;
+
+impl LintRule for PluralTableName {
+    fn id(&self) -> &str {
+        "plural-table-name"
+    }
+
+    fn description(&self) -> &str {
+        "Table names should be plural"
+    }
+
+    fn check(&self, table: &Table, _context: &LintContext<'_>) -> Vec<LintFinding> {
+        if is_plural(&table.table_name) {
+            return Vec::new();
+        }
+        vec![LintFinding::table("table name is not plural")]
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Reports single-column foreign keys whose column is neither named `id` nor
+/// suffixed by `_id`.
+pub struct ForeignKeySuffix;
+
+impl LintRule for ForeignKeySuffix {
+    fn id(&self) -> &str {
+        "foreign-key-suffix"
+    }
+
+    fn description(&self) -> &str {
+        "Foreign key columns should be suffixed by `_id`"
+    }
+
+    fn check(&self, table: &Table, context: &LintContext<'_>) -> Vec<LintFinding> {
+        let database = context.database();
+        TableLike::foreign_keys(table, database)
+            .filter_map(|foreign_key| {
+                let mut columns = ForeignKeyLike::host_columns(foreign_key, database);
+                match (columns.next(), columns.next()) {
+                    (Some(column), None)
+                        if column.column_name != "id" && !column.column_name.ends_with("_id") =>
+                    {
+                        Some(LintFinding::column(
+                            &column.column_name,
+                            "foreign key column is not suffixed by `_id`",
+                        ))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Reports table and column names which are reserved key words, and must
+/// therefore always be quoted.
+pub struct ReservedWord;
+
+impl LintRule for ReservedWord {
+    fn id(&self) -> &str {
+        "reserved-word"
+    }
+
+    fn description(&self) -> &str {
+        "Table and column names should not be reserved key words"
+    }
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Deny
+    }
+
+    fn check(&self, table: &Table, context: &LintContext<'_>) -> Vec<LintFinding> {
+        let mut findings = Vec::new();
+        if is_reserved_word(&table.table_name) {
+            findings.push(LintFinding::table("table name is a reserved key word"));
+        }
+        findings.extend(
+            TableLike::columns(table, context.database())
+                .filter(|column| is_reserved_word(&column.column_name))
+                .map(|column| {
+                    LintFinding::column(&column.column_name, "column name is a reserved key word")
+                }),
+        );
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_naming() {
+        assert!(is_snake_case("order_items"));
+        assert!(is_snake_case("address2"));
+        assert!(!is_snake_case("OrderItems"));
+        assert!(!is_snake_case("order__items"));
+        assert!(!is_snake_case("_order"));
+        assert!(!is_snake_case("order-items"));
+        assert!(is_plural("order_items"));
+        assert!(is_plural("people"));
+        assert!(is_plural("sensor_data"));
+        assert!(!is_plural("order_item"));
+        assert!(!is_plural("address"));
+        assert!(!is_plural("status"));
+        assert!(is_reserved_word("user"));
+        assert!(is_reserved_word("ORDER"));
+        assert!(!is_reserved_word("users"));
+        assert_eq!(element_type("_timestamp"), "timestamp");
+        assert_eq!(element_type("timestamptz"), "timestamptz");
+    }
+}
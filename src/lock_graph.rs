@@ -0,0 +1,707 @@
+//! Submodule building the wait-for graph of the server from a snapshot of
+//! `pg_locks` and `pg_stat_activity`.
+//!
+//! The [`LockWaitGraph`] records, for every lock a process is waiting for,
+//! which processes hold a conflicting lock on the same object, the relation
+//! involved, the lock modes, how long the wait lasted and the queries of the
+//! processes. From it, the root blockers, the transitive blocking chains and
+//! the deadlock cycles are derived.
+//!
+//! The blockers are those reported by `pg_blocking_pids`: the processes
+//! holding a conflicting lock, and those queued ahead with a conflicting
+//! request, whose order `pg_locks` does not expose.
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+    fmt::Write,
+    time::{Duration, SystemTime},
+};
+
+use diesel::{
+    ExpressionMethods, JoinOnDsl, PgConnection, QueryDsl, QueryableByName, RunQueryDsl, dsl::now,
+};
+
+use crate::{
+    identity::{QualifiedName, RelOid},
+    models::{LockMode, PgLock, PgStatActivity},
+};
+
+/// Maximal number of characters of the queries shown in the report.
+const REPORT_QUERY_LENGTH: usize = 80;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A process waiting for a lock held by another process.
+pub struct LockWait {
+    /// The process waiting for the lock.
+    waiting_pid: i32,
+    /// The process holding, or queued ahead with, a conflicting lock.
+    blocking_pid: i32,
+    /// The type of the lockable object, as in `relation` or `transactionid`.
+    locktype: String,
+    /// The OID of the relation involved in the wait, if any.
+    relation_oid: Option<RelOid>,
+    /// The qualified name of the relation involved in the wait, if any.
+    relation: Option<QualifiedName>,
+    /// The lock mode requested by the waiting process.
+    requested_mode: String,
+    /// The lock mode held, or requested, by the blocking process.
+    held_mode: String,
+    /// When the waiting process started waiting, if known.
+    waiting_since: Option<SystemTime>,
+    /// How long the process had been waiting when the snapshot was taken.
+    wait_duration: Option<Duration>,
+    /// The query of the waiting process.
+    waiting_query: Option<String>,
+    /// The query of the blocking process.
+    blocking_query: Option<String>,
+}
+
+impl LockWait {
+    #[must_use]
+    /// Returns the process waiting for the lock.
+    pub fn waiting_pid(&self) -> i32 {
+        self.waiting_pid
+    }
+
+    #[must_use]
+    /// Returns the process holding a conflicting lock, or queued ahead of
+    /// the waiting process with a conflicting request.
+    pub fn blocking_pid(&self) -> i32 {
+        self.blocking_pid
+    }
+
+    #[must_use]
+    /// Returns the type of the lockable object, as in `relation`, `tuple` or
+    /// `transactionid`.
+    pub fn locktype(&self) -> &str {
+        &self.locktype
+    }
+
+    #[must_use]
+    /// Returns the OID of the relation involved in the wait.
+    ///
+    /// For waits on a transaction ID, which is how row locks are awaited, this
+    /// is the relation of the tuple lock held by the waiting process, if any.
+    pub fn relation_oid(&self) -> Option<RelOid> {
+        self.relation_oid
+    }
+
+    #[must_use]
+    /// Returns the qualified name of the relation involved in the wait, as in
+    /// `public.users`.
+    pub fn relation(&self) -> Option<&QualifiedName> {
+        self.relation.as_ref()
+    }
+
+    #[must_use]
+    /// Returns the lock mode requested by the waiting process.
+    pub fn requested_mode(&self) -> &str {
+        &self.requested_mode
+    }
+
+    #[must_use]
+    /// Returns the lock mode held by the blocking process, or requested by it
+    /// when it is queued ahead of the waiting process.
+    pub fn held_mode(&self) -> &str {
+        &self.held_mode
+    }
+
+    #[must_use]
+    /// Returns when the waiting process started waiting.
+    ///
+    /// This is the `waitstart` of the lock or, when it is not yet set, the
+    /// last state change of the process if it is waiting on a lock.
+    pub fn waiting_since(&self) -> Option<SystemTime> {
+        self.waiting_since
+    }
+
+    #[must_use]
+    /// Returns how long the process had been waiting when the snapshot was
+    /// taken.
+    pub fn wait_duration(&self) -> Option<Duration> {
+        self.wait_duration
+    }
+
+    #[must_use]
+    /// Returns the query of the waiting process.
+    pub fn waiting_query(&self) -> Option<&str> {
+        self.waiting_query.as_deref()
+    }
+
+    #[must_use]
+    /// Returns the query of the blocking process.
+    pub fn blocking_query(&self) -> Option<&str> {
+        self.blocking_query.as_deref()
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The wait-for graph of the server processes at a point in time.
+pub struct LockWaitGraph {
+    /// When the snapshot was taken, according to the server clock.
+    taken_at: SystemTime,
+    /// The waits, sorted by waiting and blocking process.
+    waits: Vec<LockWait>,
+    /// The activity of the processes, by process ID.
+    activities: HashMap<i32, PgStatActivity>,
+}
+
+#[derive(QueryableByName)]
+/// A process blocked by another, as reported by `pg_blocking_pids`.
+struct BlockingPid {
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    /// The blocked process.
+    pid: i32,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    /// The process blocking it.
+    blocking_pid: i32,
+}
+
+impl LockWaitGraph {
+    /// Takes a snapshot of `pg_locks`, `pg_stat_activity` and
+    /// `pg_blocking_pids` and builds the wait-for graph from it.
+    ///
+    /// The views are read separately, so a process may have changed state
+    /// between the two reads; the graph is only ever an approximation of a
+    /// moving target.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load(conn: &mut PgConnection) -> Result<Self, diesel::result::Error> {
+        use crate::schema::pg_catalog::{pg_class::pg_class, pg_namespace::pg_namespace};
+
+        let taken_at: SystemTime = diesel::select(now).get_result(conn)?;
+        let locks = PgLock::load_all(conn)?;
+        let activities = PgStatActivity::load_all(conn)?;
+        let blocking: Vec<(i32, i32)> = diesel::sql_query(
+            r"
+            SELECT pid, unnest(pg_catalog.pg_blocking_pids(pid)) AS blocking_pid
+            FROM pg_catalog.pg_stat_activity
+            WHERE pid IS NOT NULL
+            ",
+        )
+        .load::<BlockingPid>(conn)?
+        .into_iter()
+        .map(|row| (row.pid, row.blocking_pid))
+        .collect();
+        let mut relations: Vec<u32> = locks.iter().filter_map(|lock| lock.relation).collect();
+        relations.sort_unstable();
+        relations.dedup();
+        let relation_names = pg_class::table
+            .inner_join(pg_namespace::table.on(pg_class::relnamespace.eq(pg_namespace::oid)))
+            .filter(pg_class::oid.eq_any(relations))
+            .select((pg_class::oid, pg_namespace::nspname, pg_class::relname))
+            .load::<(u32, String, String)>(conn)?
+            .into_iter()
+            .map(|(oid, schema, name)| (RelOid::new(oid), QualifiedName::new(schema, name)))
+            .collect();
+        Ok(Self::from_snapshot(
+            &locks,
+            &blocking,
+            activities,
+            &relation_names,
+            taken_at,
+        ))
+    }
+
+    #[must_use]
+    /// Builds the wait-for graph from rows of `pg_locks`, `pg_stat_activity`
+    /// and `pg_blocking_pids`.
+    ///
+    /// Pairs whose waiting process no longer awaits a lock in `locks`, as
+    /// the views were read at different times, are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `locks` - The rows of `pg_locks`.
+    /// * `blocking` - The pairs of waiting and blocking processes, as
+    ///   reported by `pg_blocking_pids`.
+    /// * `activities` - The rows of `pg_stat_activity`.
+    /// * `relation_names` - The qualified names of the relations, by OID.
+    /// * `taken_at` - When the snapshot was taken.
+    pub fn from_snapshot(
+        locks: &[PgLock],
+        blocking: &[(i32, i32)],
+        activities: Vec<PgStatActivity>,
+        relation_names: &HashMap<RelOid, QualifiedName>,
+        taken_at: SystemTime,
+    ) -> Self {
+        let activities: HashMap<i32, PgStatActivity> = activities
+            .into_iter()
+            .filter_map(|activity| Some((activity.pid?, activity)))
+            .collect();
+        // `pg_blocking_pids` reports the leaders of parallel groups, while
+        // the locks may be held or awaited by their workers.
+        let group = |pid: i32| {
+            activities
+                .get(&pid)
+                .and_then(|activity| activity.leader_pid)
+                .unwrap_or(pid)
+        };
+        let locks_of = |pid: i32| {
+            locks
+                .iter()
+                .filter(move |lock| lock.pid.is_some_and(|lock_pid| group(lock_pid) == pid))
+        };
+
+        let mut waits: BTreeMap<(i32, i32), LockWait> = BTreeMap::new();
+        for &(waiting_pid, blocking_pid) in blocking {
+            let Some(waiting) = locks_of(waiting_pid).find(|lock| !lock.is_granted()) else {
+                continue;
+            };
+            let held = locks_of(blocking_pid)
+                .filter(|lock| lock.conflicts_with(waiting))
+                .min_by_key(|lock| !lock.is_granted());
+            let relation_oid = waiting
+                .relation
+                .or_else(|| {
+                    locks_of(waiting_pid)
+                        .filter(|lock| lock.locktype.as_deref() == Some("tuple"))
+                        .find_map(|lock| lock.relation)
+                })
+                .map(RelOid::new);
+            let waiting_activity = activities.get(&waiting_pid);
+            let waiting_since = waiting.waitstart.or_else(|| {
+                waiting_activity
+                    .filter(|activity| activity.wait_event_type.as_deref() == Some("Lock"))
+                    .and_then(|activity| activity.state_change)
+            });
+            let wait = LockWait {
+                waiting_pid,
+                blocking_pid,
+                locktype: waiting.locktype.clone().unwrap_or_default(),
+                relation_oid,
+                relation: relation_oid.and_then(|oid| relation_names.get(&oid).cloned()),
+                requested_mode: waiting.mode.clone().unwrap_or_default(),
+                held_mode: held.and_then(|held| held.mode.clone()).unwrap_or_default(),
+                waiting_since,
+                wait_duration: waiting_since.and_then(|since| taken_at.duration_since(since).ok()),
+                waiting_query: waiting_activity.and_then(|activity| activity.query.clone()),
+                blocking_query: activities
+                    .get(&blocking_pid)
+                    .and_then(|activity| activity.query.clone()),
+            };
+            waits.insert((waiting_pid, blocking_pid), wait);
+        }
+
+        Self {
+            taken_at,
+            waits: waits.into_values().collect(),
+            activities,
+        }
+    }
+
+    #[must_use]
+    /// Returns when the snapshot was taken.
+    pub fn taken_at(&self) -> SystemTime {
+        self.taken_at
+    }
+
+    #[must_use]
+    /// Returns the waits, sorted by waiting and blocking process.
+    pub fn waits(&self) -> &[LockWait] {
+        &self.waits
+    }
+
+    #[must_use]
+    /// Returns the activity of the provided process, if it was found.
+    pub fn activity(&self, pid: i32) -> Option<&PgStatActivity> {
+        self.activities.get(&pid)
+    }
+
+    #[must_use]
+    /// Returns whether the provided process is waiting for another one.
+    pub fn is_waiting(&self, pid: i32) -> bool {
+        self.waits.iter().any(|wait| wait.waiting_pid == pid)
+    }
+
+    /// Returns the processes directly blocking the provided one.
+    pub fn blockers_of(&self, pid: i32) -> impl Iterator<Item = i32> + '_ {
+        self.waits
+            .iter()
+            .filter(move |wait| wait.waiting_pid == pid)
+            .map(LockWait::blocking_pid)
+    }
+
+    /// Returns the processes directly waiting for the provided one.
+    pub fn waiters_of(&self, pid: i32) -> impl Iterator<Item = i32> + '_ {
+        self.waits
+            .iter()
+            .filter(move |wait| wait.blocking_pid == pid)
+            .map(LockWait::waiting_pid)
+    }
+
+    #[must_use]
+    /// Returns the processes directly or transitively waiting for the
+    /// provided one, sorted by process ID.
+    pub fn blocked_by(&self, pid: i32) -> Vec<i32> {
+        let mut blocked = BTreeSet::new();
+        let mut queue = VecDeque::from([pid]);
+        while let Some(blocker) = queue.pop_front() {
+            for waiter in self.waiters_of(blocker) {
+                if waiter != pid && blocked.insert(waiter) {
+                    queue.push_back(waiter);
+                }
+            }
+        }
+        blocked.into_iter().collect()
+    }
+
+    #[must_use]
+    /// Returns the processes blocking others without waiting themselves,
+    /// sorted by decreasing number of processes they transitively block.
+    ///
+    /// Terminating or committing the root blockers is what unblocks the
+    /// chains; processes only blocked by a deadlock cycle have no root blocker.
+    pub fn root_blockers(&self) -> Vec<i32> {
+        let blockers: BTreeSet<i32> = self
+            .waits
+            .iter()
+            .map(LockWait::blocking_pid)
+            .filter(|&pid| !self.is_waiting(pid))
+            .collect();
+        let mut blockers: Vec<(usize, i32)> = blockers
+            .into_iter()
+            .map(|pid| (self.blocked_by(pid).len(), pid))
+            .collect();
+        blockers.sort_by_key(|&(blocked, pid)| (Reverse(blocked), pid));
+        blockers.into_iter().map(|(_, pid)| pid).collect()
+    }
+
+    #[must_use]
+    /// Returns the deadlock cycles, as the sorted processes of each strongly
+    /// connected component of the graph with more than one process.
+    ///
+    /// The deadlock detector of the server aborts one of the transactions of
+    /// a cycle after `deadlock_timeout`, so a cycle is only ever observed
+    /// briefly.
+    pub fn deadlocks(&self) -> Vec<Vec<i32>> {
+        let mut edges: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+        for wait in &self.waits {
+            edges
+                .entry(wait.waiting_pid)
+                .or_default()
+                .push(wait.blocking_pid);
+            edges.entry(wait.blocking_pid).or_default();
+        }
+        let mut tarjan = Tarjan::new(&edges);
+        for &pid in edges.keys() {
+            if !tarjan.indices.contains_key(&pid) {
+                tarjan.visit(pid);
+            }
+        }
+        tarjan.components.retain(|component| component.len() > 1);
+        tarjan.components.sort();
+        tarjan.components
+    }
+
+    #[must_use]
+    /// Returns a human-readable report of the blocking chains and deadlocks,
+    /// starting from the root blockers.
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        if self.waits.is_empty() {
+            report.push_str("No process is waiting for a lock.\n");
+            return report;
+        }
+        for root in self.root_blockers() {
+            let _ = writeln!(report, "pid {root}{}", self.describe(root));
+            let mut visited = BTreeSet::from([root]);
+            self.report_waiters(root, 1, &mut visited, &mut report);
+        }
+        for cycle in self.deadlocks() {
+            let cycle: Vec<String> = cycle.iter().map(ToString::to_string).collect();
+            let _ = writeln!(report, "deadlock between pids {}", cycle.join(", "));
+        }
+        report
+    }
+
+    /// Appends the processes waiting for the provided one to the report.
+    fn report_waiters(
+        &self,
+        pid: i32,
+        depth: usize,
+        visited: &mut BTreeSet<i32>,
+        report: &mut String,
+    ) {
+        for wait in self.waits.iter().filter(|wait| wait.blocking_pid == pid) {
+            let waiter = wait.waiting_pid;
+            let _ = write!(
+                report,
+                "{}└ pid {waiter} waits for {} on {}",
+                "  ".repeat(depth),
+                wait.requested_mode,
+                wait.relation
+                    .as_ref()
+                    .map_or_else(|| wait.locktype.clone(), ToString::to_string)
+            );
+            if let Some(duration) = wait.wait_duration {
+                let _ = write!(report, " for {}", format_duration(duration));
+            }
+            let _ = writeln!(report, "{}", self.describe(waiter));
+            if visited.insert(waiter) {
+                self.report_waiters(waiter, depth + 1, visited, report);
+            }
+        }
+    }
+
+    /// Returns the state and truncated query of the provided process.
+    fn describe(&self, pid: i32) -> String {
+        let Some(activity) = self.activities.get(&pid) else {
+            return String::new();
+        };
+        let mut description = String::new();
+        if let Some(state) = &activity.state {
+            let _ = write!(description, " ({state})");
+        }
+        if let Some(query) = &activity.query {
+            let query = query.split_whitespace().collect::<Vec<_>>().join(" ");
+            let truncated: String = query.chars().take(REPORT_QUERY_LENGTH).collect();
+            let ellipsis = if truncated.len() < query.len() {
+                "…"
+            } else {
+                ""
+            };
+            let _ = write!(description, ": {truncated}{ellipsis}");
+        }
+        description
+    }
+}
+
+/// Formats a duration as `HH:MM:SS`.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// State of Tarjan's strongly connected components algorithm.
+struct Tarjan<'graph> {
+    /// The outgoing edges of each process.
+    edges: &'graph BTreeMap<i32, Vec<i32>>,
+    /// The discovery index of each visited process.
+    indices: HashMap<i32, usize>,
+    /// The lowest index reachable from each visited process.
+    lowlinks: HashMap<i32, usize>,
+    /// The stack of the processes of the components being built.
+    stack: Vec<i32>,
+    /// The processes on the stack.
+    on_stack: BTreeSet<i32>,
+    /// The strongly connected components found so far.
+    components: Vec<Vec<i32>>,
+}
+
+impl<'graph> Tarjan<'graph> {
+    /// Creates the state for the provided graph.
+    fn new(edges: &'graph BTreeMap<i32, Vec<i32>>) -> Self {
+        Self {
+            edges,
+            indices: HashMap::new(),
+            lowlinks: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: BTreeSet::new(),
+            components: Vec::new(),
+        }
+    }
+
+    /// Visits the provided process and its successors.
+    fn visit(&mut self, pid: i32) {
+        let index = self.indices.len();
+        self.indices.insert(pid, index);
+        self.lowlinks.insert(pid, index);
+        self.stack.push(pid);
+        self.on_stack.insert(pid);
+
+        let edges = self.edges;
+        for &successor in edges.get(&pid).into_iter().flatten() {
+            if !self.indices.contains_key(&successor) {
+                self.visit(successor);
+                let lowlink = self.lowlinks[&pid].min(self.lowlinks[&successor]);
+                self.lowlinks.insert(pid, lowlink);
+            } else if self.on_stack.contains(&successor) {
+                let lowlink = self.lowlinks[&pid].min(self.indices[&successor]);
+                self.lowlinks.insert(pid, lowlink);
+            }
+        }
+
+        if self.lowlinks[&pid] == index {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(&member);
+                component.push(member);
+                if member == pid {
+                    break;
+                }
+            }
+            component.sort_unstable();
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a relation lock of the provided process.
+    fn relation_lock(pid: i32, relation: u32, mode: LockMode, granted: bool) -> PgLock {
+        PgLock {
+            locktype: Some("relation".to_owned()),
+            database: Some(5),
+            relation: Some(relation),
+            page: None,
+            tuple: None,
+            virtualxid: None,
+            transactionid: None,
+            classid: None,
+            objid: None,
+            objsubid: None,
+            virtualtransaction: Some(format!("3/{pid}")),
+            pid: Some(pid),
+            mode: Some(mode.name().to_owned()),
+            granted: Some(granted),
+            fastpath: Some(false),
+            waitstart: (!granted).then_some(SystemTime::UNIX_EPOCH + Duration::from_secs(100)),
+        }
+    }
+
+    /// Returns the activity of the provided process.
+    fn activity(pid: i32, query: &str) -> PgStatActivity {
+        PgStatActivity {
+            datid: Some(5),
+            datname: Some("postgres".to_owned()),
+            pid: Some(pid),
+            leader_pid: None,
+            usesysid: Some(10),
+            usename: Some("postgres".to_owned()),
+            application_name: None,
+            client_addr: None,
+            client_hostname: None,
+            client_port: None,
+            backend_start: None,
+            xact_start: None,
+            query_start: None,
+            state_change: None,
+            wait_event_type: None,
+            wait_event: None,
+            state: Some("active".to_owned()),
+            backend_xid: None,
+            backend_xmin: None,
+            query_id: None,
+            query: Some(query.to_owned()),
+            backend_type: Some("client backend".to_owned()),
+        }
+    }
+
+    /// Builds a graph from the provided locks and blocking processes.
+    fn graph(locks: &[PgLock], blocking: &[(i32, i32)]) -> LockWaitGraph {
+        let activities = vec![
+            activity(1, "ALTER TABLE users ADD COLUMN age int"),
+            activity(2, "SELECT * FROM users"),
+            activity(3, "SELECT * FROM users"),
+            activity(4, "UPDATE orders SET total = 0"),
+        ];
+        let relation_names = HashMap::from([
+            (RelOid::new(100), QualifiedName::new("public", "users")),
+            (RelOid::new(200), QualifiedName::new("public", "orders")),
+        ]);
+        LockWaitGraph::from_snapshot(
+            locks,
+            blocking,
+            activities,
+            &relation_names,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(3825),
+        )
+    }
+
+    #[test]
+    fn test_blocking_chain() {
+        let graph = graph(
+            &[
+                relation_lock(4, 100, LockMode::AccessShare, true),
+                relation_lock(1, 100, LockMode::AccessExclusive, false),
+                relation_lock(2, 100, LockMode::AccessShare, true),
+                relation_lock(4, 200, LockMode::ShareRowExclusive, true),
+                relation_lock(3, 200, LockMode::RowExclusive, false),
+            ],
+            &[(1, 2), (1, 4), (3, 4)],
+        );
+
+        let waits = graph.waits();
+        assert_eq!(waits.len(), 3);
+        assert_eq!((waits[0].waiting_pid(), waits[0].blocking_pid()), (1, 2));
+        assert_eq!((waits[1].waiting_pid(), waits[1].blocking_pid()), (1, 4));
+        assert_eq!(
+            waits[0].relation(),
+            Some(&QualifiedName::new("public", "users"))
+        );
+        assert_eq!(waits[0].relation_oid(), Some(RelOid::new(100)));
+        assert_eq!(waits[0].requested_mode(), "AccessExclusiveLock");
+        assert_eq!(waits[0].held_mode(), "AccessShareLock");
+        assert_eq!(waits[0].wait_duration(), Some(Duration::from_secs(3725)));
+        assert_eq!(waits[0].blocking_query(), Some("SELECT * FROM users"));
+        assert!(graph.is_waiting(1));
+        assert!(!graph.is_waiting(4));
+        assert_eq!(
+            waits[2].relation(),
+            Some(&QualifiedName::new("public", "orders"))
+        );
+        assert_eq!(graph.root_blockers(), vec![4, 2]);
+        assert_eq!(graph.blocked_by(4), vec![1, 3]);
+        assert!(graph.deadlocks().is_empty());
+
+        let report = graph.report();
+        assert!(
+            report.contains("└ pid 1 waits for AccessExclusiveLock on public.users for 01:02:05")
+        );
+    }
+
+    #[test]
+    fn test_deadlock() {
+        let graph = graph(
+            &[
+                relation_lock(1, 100, LockMode::AccessExclusive, true),
+                relation_lock(1, 200, LockMode::AccessExclusive, false),
+                relation_lock(4, 200, LockMode::RowExclusive, true),
+                relation_lock(4, 100, LockMode::AccessShare, false),
+                relation_lock(2, 100, LockMode::AccessShare, false),
+            ],
+            &[(1, 4), (4, 1), (2, 1)],
+        );
+
+        assert_eq!(graph.deadlocks(), vec![vec![1, 4]]);
+        assert!(graph.root_blockers().is_empty());
+        assert_eq!(graph.blocked_by(1), vec![2, 4]);
+        assert_eq!(graph.blockers_of(2).collect::<Vec<_>>(), vec![1]);
+        assert!(graph.report().contains("deadlock between pids 1, 4"));
+    }
+
+    #[test]
+    fn test_queued_ahead() {
+        let graph = graph(
+            &[
+                relation_lock(2, 100, LockMode::AccessShare, true),
+                relation_lock(1, 100, LockMode::AccessExclusive, false),
+                relation_lock(3, 100, LockMode::AccessShare, false),
+            ],
+            &[(1, 2), (3, 1)],
+        );
+
+        let waits = graph.waits();
+        assert_eq!(waits.len(), 2);
+        assert_eq!((waits[1].waiting_pid(), waits[1].blocking_pid()), (3, 1));
+        assert_eq!(waits[1].held_mode(), "AccessExclusiveLock");
+        assert_eq!(graph.root_blockers(), vec![2]);
+        assert_eq!(graph.blocked_by(2), vec![1, 3]);
+    }
+}
@@ -5,8 +5,22 @@
 //!
 //! - [`TableMetadata`]: Wraps a table with its columns, constraints, indices,
 //!   and description
-//! - [`ColumnMetadata`]: Wraps a column with its table, type information, and
-//!   description
+//! - [`ColumnMetadata`]: Wraps a column with its table, type information,
+//!   description and collation
+//! - [`CollationMetadata`]: The collation of a column, with its provider and
+//!   locale resolved against the database for the `default` collation
+//! - [`ForeignTableMetadata`]: The server, foreign data wrapper, user
+//!   mappings and parsed `OPTIONS` of a foreign table
+//! - [`CatalogOptions`]: The `name=value` options of foreign objects and
+//!   relations, as stored by the catalogs
+//! - [`RuleMetadata`]: A rewrite rule of a table, with its event, firing
+//!   mode and parsed actions
+//! - [`EventTriggerMetadata`]: An event trigger of the database, with the
+//!   function it executes
+//! - [`StorageMetadata`]: The tablespace, persistence, access method,
+//!   storage parameters, TOAST table and size of a table and its indexes
+//! - [`ColumnStatistics`]: The planner statistics of a column from `pg_stats`,
+//!   decoded according to the type of the column
 //!
 //! These metadata types are used by the
 //! [`PgDieselDatabase`](crate::database::PgDieselDatabase) to provide rich introspection
@@ -16,9 +30,29 @@ mod table_metadata;
 pub use table_metadata::TableMetadata;
 mod column_metadata;
 pub use column_metadata::ColumnMetadata;
+mod collation_metadata;
+pub use collation_metadata::{CollationMetadata, CollationProvider};
 mod trigger_metadata;
 pub use trigger_metadata::TriggerMetadata;
 mod policy_metadata;
 pub use policy_metadata::PolicyMetadata;
 mod role_metadata;
 pub use role_metadata::RoleMetadata;
+mod foreign_table_metadata;
+pub use foreign_table_metadata::{ForeignTableMetadata, ForeignUserMapping};
+mod catalog_options;
+pub use catalog_options::CatalogOptions;
+mod firing_mode;
+pub use firing_mode::FiringMode;
+mod rule_metadata;
+pub use rule_metadata::{RuleEvent, RuleMetadata};
+mod event_trigger_metadata;
+pub use event_trigger_metadata::EventTriggerMetadata;
+mod storage_metadata;
+pub use storage_metadata::{
+    ColumnStorage, Compression, IndexStorage, Persistence, StorageMetadata, StorageMode,
+    StorageParameters, StorageValue, ToastStorage,
+};
+mod column_statistics;
+pub(crate) use column_statistics::PgStatText;
+pub use column_statistics::{ColumnStatistics, MostCommonValue, StatisticValue};
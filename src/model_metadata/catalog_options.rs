@@ -0,0 +1,92 @@
+//! Submodule providing the `CatalogOptions` struct, the `name=value` options
+//! stored by the catalogs, as in `pg_class.reloptions` or
+//! `pg_foreign_table.ftoptions`.
+//!
+//! The options are parsed once here: the `OPTIONS` of foreign objects are
+//! exposed as they are, while
+//! [`StorageParameters`](crate::model_metadata::StorageParameters) types the
+//! values of the storage parameters of relations.
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Options stored by the catalogs as `name=value` strings, as key/value pairs
+/// in the order they were declared.
+pub struct CatalogOptions {
+    /// The options, by name.
+    options: Vec<(String, String)>,
+}
+
+impl CatalogOptions {
+    /// Parses the options as stored by the catalogs, where each option is a
+    /// `name=value` string. An option without `=` has an empty value.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The options, as in `pg_foreign_table.ftoptions` or
+    ///   `pg_class.reloptions`.
+    #[must_use]
+    pub fn parse(options: Option<&[String]>) -> Self {
+        Self {
+            options: options
+                .unwrap_or_default()
+                .iter()
+                .map(|option| match option.split_once('=') {
+                    Some((name, value)) => (name.to_owned(), value.to_owned()),
+                    None => (option.clone(), String::new()),
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the value of the option with the provided name, if set.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns an iterator over the names and values of the options.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.options
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Returns the number of options.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.options.len()
+    }
+
+    /// Returns whether no option is set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_options() {
+        let options = CatalogOptions::parse(Some(&[
+            "host=db.example.com".to_owned(),
+            "options=-c search_path=remote".to_owned(),
+            "updatable".to_owned(),
+        ]));
+        assert_eq!(options.len(), 3);
+        assert_eq!(options.get("host"), Some("db.example.com"));
+        assert_eq!(options.get("options"), Some("-c search_path=remote"));
+        assert_eq!(options.get("updatable"), Some(""));
+        assert_eq!(options.get("port"), None);
+        assert_eq!(
+            options.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            ["host", "options", "updatable"]
+        );
+        assert!(CatalogOptions::parse(None).is_empty());
+    }
+}
@@ -0,0 +1,214 @@
+//! Submodule providing the `CollationMetadata` struct for a [`PgCollation`]
+//! resolved for a column.
+//!
+//! The `default` collation takes its provider and locale from the database,
+//! so that the collation of every column is described by the library and
+//! locale actually sorting it, along with its determinism.
+
+use std::fmt::Display;
+
+use crate::{identity::QualifiedName, models::PgCollation};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The library implementing a collation, as in `pg_collation.collprovider`
+/// and `pg_database.datlocprovider`.
+pub enum CollationProvider {
+    /// The collation of the database (`d`), only found on the `default`
+    /// collation before it is resolved.
+    Default,
+    /// The C library of the operating system (`c`).
+    Libc,
+    /// The ICU library (`i`).
+    Icu,
+    /// The provider built into `PostgreSQL` 17 and later (`b`).
+    Builtin,
+}
+
+impl CollationProvider {
+    #[must_use]
+    /// Returns the provider of the catalog code, or `None` if it is not one
+    /// of `d`, `c`, `i` or `b`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "d" => Some(Self::Default),
+            "c" => Some(Self::Libc),
+            "i" => Some(Self::Icu),
+            "b" => Some(Self::Builtin),
+            _ => None,
+        }
+    }
+}
+
+impl Display for CollationProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Default => "default",
+            Self::Libc => "libc",
+            Self::Icu => "icu",
+            Self::Builtin => "builtin",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Wrapper around the [`PgCollation`] of a column, with its provider and
+/// locale resolved.
+///
+/// Columns declared without a `COLLATE` clause use the `default` collation,
+/// which stands for the collation of the database: its provider and locale
+/// are then those of `pg_database` rather than the empty ones of the
+/// `default` row of `pg_collation`.
+pub struct CollationMetadata {
+    /// The underlying collation model.
+    model: PgCollation,
+    /// The schema of the collation.
+    schema: String,
+    /// The resolved provider of the collation.
+    provider: CollationProvider,
+    /// The resolved locale of the collation.
+    locale: Option<String>,
+}
+
+impl CollationMetadata {
+    /// Creates a new `CollationMetadata` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The collation.
+    /// * `schema` - The schema of the collation.
+    /// * `provider` - The provider of the collation, or of the database for
+    ///   the `default` collation.
+    /// * `locale` - The locale of the collation, or of the database for the
+    ///   `default` collation.
+    #[must_use]
+    pub fn new(
+        model: PgCollation,
+        schema: String,
+        provider: CollationProvider,
+        locale: Option<String>,
+    ) -> Self {
+        Self {
+            model,
+            schema,
+            provider,
+            locale,
+        }
+    }
+
+    /// Returns the underlying collation model.
+    #[must_use]
+    pub fn model(&self) -> &PgCollation {
+        &self.model
+    }
+
+    /// Returns the name of the collation.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.model.collname
+    }
+
+    /// Returns the schema of the collation.
+    #[must_use]
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// Returns the qualified name of the collation, as used in `COLLATE`
+    /// clauses.
+    #[must_use]
+    pub fn qualified_name(&self) -> QualifiedName {
+        QualifiedName::new(&self.schema, &self.model.collname)
+    }
+
+    /// Returns whether the collation is the `default` collation of the
+    /// database.
+    #[must_use]
+    pub fn is_database_default(&self) -> bool {
+        self.model.collprovider == "d"
+    }
+
+    /// Returns the provider of the collation, resolved to the provider of
+    /// the database for the `default` collation.
+    #[must_use]
+    pub fn provider(&self) -> CollationProvider {
+        self.provider
+    }
+
+    /// Returns the locale of the collation, as in `en-US` or `en_US.UTF-8`,
+    /// resolved to the locale of the database for the `default` collation.
+    #[must_use]
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    /// Returns whether strings comparing equal under the collation are
+    /// always byte-wise equal, which case or accent insensitive collations
+    /// are not.
+    #[must_use]
+    pub fn is_deterministic(&self) -> bool {
+        self.model.collisdeterministic
+    }
+
+    /// Returns the version of the collation recorded when it was created,
+    /// whose mismatch with the version of the provider calls for a reindex.
+    #[must_use]
+    pub fn version(&self) -> Option<&str> {
+        self.model.collversion.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collation(name: &str, provider: &str, deterministic: bool) -> PgCollation {
+        PgCollation {
+            oid: 100,
+            collname: name.to_owned(),
+            collnamespace: 11,
+            collowner: 10,
+            collprovider: provider.to_owned(),
+            collisdeterministic: deterministic,
+            collencoding: -1,
+            collcollate: None,
+            collctype: None,
+            #[cfg(any(feature = "postgres-15", feature = "postgres-16"))]
+            colliculocale: None,
+            #[cfg(any(feature = "postgres-17", feature = "postgres-18"))]
+            colllocale: None,
+            #[cfg(not(any(feature = "postgres-15", feature = "postgres-14")))]
+            collicurules: None,
+            collversion: None,
+        }
+    }
+
+    #[test]
+    fn test_collation_metadata() {
+        let default = CollationMetadata::new(
+            collation("default", "d", true),
+            "pg_catalog".to_owned(),
+            CollationProvider::Libc,
+            Some("en_US.UTF-8".to_owned()),
+        );
+        assert!(default.is_database_default());
+        assert_eq!(default.provider().to_string(), "libc");
+        assert_eq!(default.locale(), Some("en_US.UTF-8"));
+        assert_eq!(default.qualified_name().to_string(), "pg_catalog.default");
+
+        let case_insensitive = CollationMetadata::new(
+            collation("case_insensitive", "i", false),
+            "public".to_owned(),
+            CollationProvider::Icu,
+            Some("und-u-ks-level2".to_owned()),
+        );
+        assert!(!case_insensitive.is_database_default());
+        assert!(!case_insensitive.is_deterministic());
+        assert_eq!(
+            CollationProvider::from_code("i"),
+            Some(case_insensitive.provider())
+        );
+        assert_eq!(CollationProvider::from_code("x"), None);
+    }
+}
@@ -2,7 +2,10 @@
 
 use std::sync::Arc;
 
-use crate::models::{PgDescription, PgType, Table};
+use crate::{
+    model_metadata::CollationMetadata,
+    models::{PgDescription, PgType, Table},
+};
 
 #[derive(Clone, Debug)]
 /// Rich metadata about a `PostgreSQL` table column.
@@ -12,6 +15,7 @@ use crate::models::{PgDescription, PgType, Table};
 /// - The table that owns the column
 /// - The resolved `PostgreSQL` type ([`PgType`]) for the column
 /// - Column description from `pg_catalog.pg_description`
+/// - The resolved collation ([`CollationMetadata`]) of collatable columns
 ///
 /// This metadata is constructed during
 /// [`PgDieselDatabase`](crate::database::PgDieselDatabase) building and cached for
@@ -33,16 +37,24 @@ pub struct ColumnMetadata {
     description: Option<PgDescription>,
     /// The associated `PgType`.
     pg_type: PgType,
+    /// The collation of the column, if its type is collatable.
+    collation: Option<CollationMetadata>,
 }
 
 impl ColumnMetadata {
     /// Creates a new `ColumnMetadata` instance.
     #[must_use]
-    pub fn new(table: Arc<Table>, description: Option<PgDescription>, pg_type: PgType) -> Self {
+    pub fn new(
+        table: Arc<Table>,
+        description: Option<PgDescription>,
+        pg_type: PgType,
+        collation: Option<CollationMetadata>,
+    ) -> Self {
         Self {
             table,
             description,
             pg_type,
+            collation,
         }
     }
 
@@ -64,6 +76,14 @@ impl ColumnMetadata {
         &self.pg_type
     }
 
+    /// Returns the collation of the column, resolved to the collation of the
+    /// database for columns using the `default` collation, or `None` if the
+    /// type of the column is not collatable.
+    #[must_use]
+    pub fn collation(&self) -> Option<&CollationMetadata> {
+        self.collation.as_ref()
+    }
+
     /// Returns the normalized data type of the column.
     #[must_use]
     pub fn normalized_data_type(&self) -> String {
@@ -147,11 +167,12 @@ mod tests {
             description: "desc".to_string(),
         });
 
-        let metadata = ColumnMetadata::new(Arc::clone(&table), description, pg_type);
+        let metadata = ColumnMetadata::new(Arc::clone(&table), description, pg_type, None);
 
         assert_eq!(metadata.table().table_name, "table");
         assert_eq!(metadata.description().unwrap().description, "desc");
         assert_eq!(metadata.pg_type().typname, "int4");
         assert_eq!(metadata.normalized_data_type(), "int4");
+        assert!(metadata.collation().is_none());
     }
 }
@@ -0,0 +1,670 @@
+//! Submodule providing the [`ColumnStatistics`] struct, the typed counterpart of
+//! a row of the `pg_stats` view.
+//!
+//! The `anyarray` columns of `pg_stats` (most common values, histogram bounds,
+//! etc.) cannot be mapped by Diesel, so they are selected as their text
+//! representation and decoded back using the [`PgType`] of the column.
+
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use diesel::QueryableByName;
+
+use crate::models::PgType;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A value stored in the statistics of a column, decoded according to the
+/// [`PgType`] of the column.
+pub enum StatisticValue {
+    /// A boolean value (`bool`).
+    Boolean(bool),
+    /// An integer value (`int2`, `int4`, `int8`, `oid`).
+    Integer(i64),
+    /// A floating point value (`float4`, `float8`).
+    Float(f64),
+    /// An arbitrary precision value (`numeric`).
+    Numeric(BigDecimal),
+    /// A value of the string category (`text`, `varchar`, `bpchar`, `name`).
+    Text(String),
+    /// A value of any other type, kept in its textual representation.
+    Other(String),
+}
+
+impl StatisticValue {
+    /// Decodes the textual representation of a value of the provided type.
+    ///
+    /// Values which cannot be decoded as the expected type are returned as
+    /// [`StatisticValue::Other`].
+    #[must_use]
+    pub fn parse(text: &str, pg_type: &PgType) -> Self {
+        match pg_type.typname.as_str() {
+            "bool" => Self::parse_boolean(text),
+            "int2" | "int4" | "int8" | "oid" => Self::parse_integer(text),
+            "float4" | "float8" => Self::parse_float(text),
+            "numeric" => Self::parse_numeric(text),
+            _ => {
+                match pg_type.typcategory.as_str() {
+                    "B" => Self::parse_boolean(text),
+                    // Domains over numeric types and other numeric types.
+                    "N" => match Self::parse_integer(text) {
+                        Self::Integer(value) => Self::Integer(value),
+                        _ => Self::parse_numeric(text),
+                    },
+                    "S" => Self::Text(text.to_owned()),
+                    _ => Self::Other(text.to_owned()),
+                }
+            }
+        }
+    }
+
+    /// Decodes a boolean from its textual representation.
+    fn parse_boolean(text: &str) -> Self {
+        match text {
+            "t" | "true" => Self::Boolean(true),
+            "f" | "false" => Self::Boolean(false),
+            _ => Self::Other(text.to_owned()),
+        }
+    }
+
+    /// Decodes an integer from its textual representation.
+    fn parse_integer(text: &str) -> Self {
+        text.parse::<i64>()
+            .map_or_else(|_| Self::Other(text.to_owned()), Self::Integer)
+    }
+
+    /// Decodes a floating point number from its textual representation.
+    fn parse_float(text: &str) -> Self {
+        text.parse::<f64>()
+            .map_or_else(|_| Self::Other(text.to_owned()), Self::Float)
+    }
+
+    /// Decodes an arbitrary precision number from its textual representation.
+    fn parse_numeric(text: &str) -> Self {
+        BigDecimal::from_str(text).map_or_else(|_| Self::Other(text.to_owned()), Self::Numeric)
+    }
+
+    /// Returns the value as a floating point number, if it is numeric.
+    #[must_use]
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            #[allow(clippy::cast_precision_loss, clippy::as_conversions)]
+            Self::Integer(value) => Some(*value as f64),
+            Self::Float(value) => Some(*value),
+            Self::Numeric(value) => value.to_string().parse().ok(),
+            Self::Boolean(_) | Self::Text(_) | Self::Other(_) => None,
+        }
+    }
+
+    /// Returns the textual representation of the value, as it would be
+    /// returned by `PostgreSQL`.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        match self {
+            Self::Boolean(value) => if *value { "t" } else { "f" }.to_owned(),
+            Self::Integer(value) => value.to_string(),
+            Self::Float(value) => value.to_string(),
+            Self::Numeric(value) => value.to_string(),
+            Self::Text(value) | Self::Other(value) => value.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A most common value of a column, along with its frequency.
+pub struct MostCommonValue {
+    /// The value.
+    pub value: StatisticValue,
+    /// The fraction of the rows (or, for elements, of the non-null rows)
+    /// containing the value.
+    pub frequency: f32,
+}
+
+#[derive(QueryableByName, Debug)]
+/// Row of the `pg_stats` view with the `anyarray` columns cast to text.
+pub(crate) struct PgStatText {
+    /// Whether the statistics include values from child tables.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Bool>)]
+    pub(crate) inherited: Option<bool>,
+    /// Null fraction.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Float>)]
+    pub(crate) null_frac: Option<f32>,
+    /// Average width.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Integer>)]
+    pub(crate) avg_width: Option<i32>,
+    /// Number of distinct values.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Float>)]
+    pub(crate) n_distinct: Option<f32>,
+    /// Most common values, as an array literal.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub(crate) most_common_vals: Option<String>,
+    /// Most common value frequencies.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Array<diesel::sql_types::Float>>)]
+    pub(crate) most_common_freqs: Option<Vec<f32>>,
+    /// Histogram bounds, as an array literal.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub(crate) histogram_bounds: Option<String>,
+    /// Correlation coefficient.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Float>)]
+    pub(crate) correlation: Option<f32>,
+    /// Most common elements, as an array literal.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub(crate) most_common_elems: Option<String>,
+    /// Most common element frequencies.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Array<diesel::sql_types::Float>>)]
+    pub(crate) most_common_elem_freqs: Option<Vec<f32>>,
+    /// Element count histogram.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Array<diesel::sql_types::Float>>)]
+    pub(crate) elem_count_histogram: Option<Vec<f32>>,
+    /// Range length histogram, as an array literal.
+    #[cfg(any(feature = "postgres-17", feature = "postgres-18"))]
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub(crate) range_length_histogram: Option<String>,
+    /// Range empty fraction.
+    #[cfg(any(feature = "postgres-17", feature = "postgres-18"))]
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Float>)]
+    pub(crate) range_empty_frac: Option<f32>,
+    /// Range bounds histogram, as an array literal.
+    #[cfg(any(feature = "postgres-17", feature = "postgres-18"))]
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub(crate) range_bounds_histogram: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Typed statistics of a column, as collected by `ANALYZE` and exposed by the
+/// `pg_stats` view.
+///
+/// The values are decoded using the [`PgType`] of the column, while the most
+/// common elements are decoded using its element type. Range histograms are
+/// kept in their textual representation.
+pub struct ColumnStatistics {
+    /// Whether the statistics include values from child tables.
+    inherited: bool,
+    /// Fraction of the entries that are null.
+    null_fraction: f32,
+    /// Average width in bytes of the entries.
+    average_width: i32,
+    /// Number of distinct values, or the negated ratio of distinct values
+    /// over the number of rows.
+    n_distinct: f32,
+    /// The most common values, with their frequencies.
+    most_common_values: Vec<MostCommonValue>,
+    /// The bounds dividing the remaining values in equally populated groups.
+    histogram_bounds: Vec<StatisticValue>,
+    /// Correlation between physical and logical ordering of the values.
+    correlation: Option<f32>,
+    /// The most common elements, with their frequencies.
+    most_common_elements: Vec<MostCommonValue>,
+    /// Histogram of the counts of distinct elements per row.
+    element_count_histogram: Vec<f32>,
+    /// Histogram of the lengths of the non-empty ranges.
+    range_length_histogram: Vec<StatisticValue>,
+    /// Fraction of the ranges that are empty.
+    range_empty_fraction: Option<f32>,
+    /// Histogram of the bounds of the non-empty ranges.
+    range_bounds_histogram: Vec<StatisticValue>,
+}
+
+impl ColumnStatistics {
+    /// Decodes the provided `pg_stats` row using the type of the column and,
+    /// if any, the type of its elements.
+    pub(crate) fn from_text(
+        row: PgStatText,
+        pg_type: &PgType,
+        element_type: Option<&PgType>,
+    ) -> Self {
+        let delimiter = pg_type.typdelim.chars().next().unwrap_or(',');
+        let decode = |literal: Option<&str>, pg_type: Option<&PgType>, delimiter: char| {
+            literal
+                .map(|literal| parse_array_literal(literal, delimiter))
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(|text| match pg_type {
+                    Some(pg_type) => StatisticValue::parse(&text, pg_type),
+                    None => StatisticValue::Other(text),
+                })
+                .collect::<Vec<_>>()
+        };
+        let with_frequencies = |values: Vec<StatisticValue>, frequencies: Option<Vec<f32>>| {
+            values
+                .into_iter()
+                .zip(frequencies.unwrap_or_default())
+                .map(|(value, frequency)| MostCommonValue { value, frequency })
+                .collect::<Vec<_>>()
+        };
+
+        let most_common_values = with_frequencies(
+            decode(row.most_common_vals.as_deref(), Some(pg_type), delimiter),
+            row.most_common_freqs,
+        );
+        let histogram_bounds = decode(row.histogram_bounds.as_deref(), Some(pg_type), delimiter);
+        let element_delimiter = element_type
+            .and_then(|element_type| element_type.typdelim.chars().next())
+            .unwrap_or(',');
+        let most_common_elements = with_frequencies(
+            decode(
+                row.most_common_elems.as_deref(),
+                // Elements of types without an element type (e.g. the lexemes
+                // of a `tsvector`) are text.
+                element_type,
+                element_delimiter,
+            )
+            .into_iter()
+            .map(|value| match (value, element_type) {
+                (StatisticValue::Other(text), None) => StatisticValue::Text(text),
+                (value, _) => value,
+            })
+            .collect(),
+            row.most_common_elem_freqs,
+        );
+
+        #[cfg(any(feature = "postgres-17", feature = "postgres-18"))]
+        let (range_length_histogram, range_empty_fraction, range_bounds_histogram) = (
+            decode(row.range_length_histogram.as_deref(), None, ','),
+            row.range_empty_frac,
+            decode(row.range_bounds_histogram.as_deref(), None, ','),
+        );
+        #[cfg(not(any(feature = "postgres-17", feature = "postgres-18")))]
+        let (range_length_histogram, range_empty_fraction, range_bounds_histogram) =
+            (Vec::new(), None, Vec::new());
+
+        Self {
+            inherited: row.inherited.unwrap_or(false),
+            null_fraction: row.null_frac.unwrap_or(0.0),
+            average_width: row.avg_width.unwrap_or(0),
+            n_distinct: row.n_distinct.unwrap_or(0.0),
+            most_common_values,
+            histogram_bounds,
+            correlation: row.correlation,
+            most_common_elements,
+            element_count_histogram: row.elem_count_histogram.unwrap_or_default(),
+            range_length_histogram,
+            range_empty_fraction,
+            range_bounds_histogram,
+        }
+    }
+
+    /// Returns whether the statistics include values from child tables.
+    #[must_use]
+    pub fn inherited(&self) -> bool {
+        self.inherited
+    }
+
+    /// Returns the fraction of the entries that are null.
+    #[must_use]
+    pub fn null_fraction(&self) -> f32 {
+        self.null_fraction
+    }
+
+    /// Returns the average width in bytes of the entries.
+    #[must_use]
+    pub fn average_width(&self) -> i32 {
+        self.average_width
+    }
+
+    /// Returns the raw `n_distinct` value: when positive, the estimated number
+    /// of distinct values; when negative, the negated ratio of distinct values
+    /// over the number of rows.
+    #[must_use]
+    pub fn n_distinct(&self) -> f32 {
+        self.n_distinct
+    }
+
+    /// Returns the estimated number of distinct non-null values for a table
+    /// with the provided number of rows.
+    #[must_use]
+    pub fn estimated_distinct_values(&self, row_count: f64) -> f64 {
+        if self.n_distinct >= 0.0 {
+            f64::from(self.n_distinct)
+        } else {
+            -f64::from(self.n_distinct) * row_count
+        }
+    }
+
+    /// Returns the most common values, with their frequencies.
+    #[must_use]
+    pub fn most_common_values(&self) -> &[MostCommonValue] {
+        &self.most_common_values
+    }
+
+    /// Returns the histogram bounds of the values not among the most common
+    /// values.
+    #[must_use]
+    pub fn histogram_bounds(&self) -> &[StatisticValue] {
+        &self.histogram_bounds
+    }
+
+    /// Returns the correlation between physical and logical ordering.
+    #[must_use]
+    pub fn correlation(&self) -> Option<f32> {
+        self.correlation
+    }
+
+    /// Returns the most common elements, with their frequencies.
+    #[must_use]
+    pub fn most_common_elements(&self) -> &[MostCommonValue] {
+        &self.most_common_elements
+    }
+
+    /// Returns the histogram of the counts of distinct elements per row.
+    #[must_use]
+    pub fn element_count_histogram(&self) -> &[f32] {
+        &self.element_count_histogram
+    }
+
+    /// Returns the histogram of the lengths of the non-empty ranges.
+    ///
+    /// Always empty before `PostgreSQL` 17.
+    #[must_use]
+    pub fn range_length_histogram(&self) -> &[StatisticValue] {
+        &self.range_length_histogram
+    }
+
+    /// Returns the fraction of the ranges that are empty.
+    ///
+    /// Always `None` before `PostgreSQL` 17.
+    #[must_use]
+    pub fn range_empty_fraction(&self) -> Option<f32> {
+        self.range_empty_fraction
+    }
+
+    /// Returns the histogram of the bounds of the non-empty ranges.
+    ///
+    /// Always empty before `PostgreSQL` 17.
+    #[must_use]
+    pub fn range_bounds_histogram(&self) -> &[StatisticValue] {
+        &self.range_bounds_histogram
+    }
+
+    /// Returns the estimated fraction of the rows whose value equals the
+    /// provided one, using the most common values when possible and assuming
+    /// a uniform distribution of the remaining values otherwise.
+    #[must_use]
+    pub fn equality_selectivity(&self, value: &StatisticValue, row_count: f64) -> f64 {
+        if let Some(most_common) = self
+            .most_common_values
+            .iter()
+            .find(|mcv| &mcv.value == value)
+        {
+            return f64::from(most_common.frequency);
+        }
+        let mcv_fraction: f64 = self
+            .most_common_values
+            .iter()
+            .map(|mcv| f64::from(mcv.frequency))
+            .sum();
+        #[allow(clippy::cast_precision_loss, clippy::as_conversions)]
+        let remaining_distinct =
+            self.estimated_distinct_values(row_count) - self.most_common_values.len() as f64;
+        if remaining_distinct <= 0.0 {
+            return 0.0;
+        }
+        ((1.0 - f64::from(self.null_fraction) - mcv_fraction) / remaining_distinct).max(0.0)
+    }
+}
+
+/// Splits the textual representation of a one-dimensional array into its
+/// elements, returning `None` for the `NULL` elements.
+///
+/// Elements which are themselves arrays (i.e. the values of array columns) are
+/// returned verbatim, including their braces.
+pub(crate) fn parse_array_literal(literal: &str, delimiter: char) -> Vec<Option<String>> {
+    // Arrays with non-default bounds are prefixed by their dimensions, as in
+    // `[0:1]={a,b}`.
+    let literal = literal.trim();
+    let literal = literal
+        .find('{')
+        .map_or(literal, |position| &literal[position..]);
+    let Some(inner) = literal
+        .strip_prefix('{')
+        .and_then(|inner| inner.strip_suffix('}'))
+    else {
+        return Vec::new();
+    };
+    if inner.is_empty() {
+        return Vec::new();
+    }
+
+    let mut elements = Vec::new();
+    let mut current = String::new();
+    let mut was_quoted = false;
+    let mut in_quotes = false;
+    let mut depth = 0_usize;
+    let mut chars = inner.chars();
+
+    while let Some(character) = chars.next() {
+        match character {
+            '\\' => {
+                if depth > 0 {
+                    current.push(character);
+                }
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            '"' => {
+                in_quotes = !in_quotes;
+                if depth > 0 {
+                    current.push(character);
+                } else {
+                    was_quoted = true;
+                }
+            }
+            '{' if !in_quotes => {
+                depth += 1;
+                current.push(character);
+            }
+            '}' if !in_quotes => {
+                depth = depth.saturating_sub(1);
+                current.push(character);
+            }
+            character if character == delimiter && !in_quotes && depth == 0 => {
+                elements.push(finish_element(std::mem::take(&mut current), was_quoted));
+                was_quoted = false;
+            }
+            character => current.push(character),
+        }
+    }
+    elements.push(finish_element(current, was_quoted));
+    elements
+}
+
+/// Returns the element, or `None` if it is an unquoted `NULL`.
+fn finish_element(element: String, was_quoted: bool) -> Option<String> {
+    if !was_quoted && element.eq_ignore_ascii_case("NULL") {
+        None
+    } else {
+        Some(element)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dummy_pg_type(typname: &str, typcategory: &str) -> PgType {
+        PgType {
+            oid: 1,
+            typname: typname.to_string(),
+            typnamespace: 1,
+            typowner: 1,
+            typlen: -1,
+            typbyval: false,
+            typtype: "b".to_string(),
+            typcategory: typcategory.to_string(),
+            typispreferred: false,
+            typisdefined: true,
+            typdelim: ",".to_string(),
+            typrelid: 0,
+            typelem: 0,
+            typarray: 0,
+            typinput: 0,
+            typoutput: 0,
+            typreceive: 0,
+            typsend: 0,
+            typmodin: 0,
+            typmodout: 0,
+            typanalyze: 0,
+            #[cfg(any(
+                feature = "postgres-14",
+                feature = "postgres-15",
+                feature = "postgres-16",
+                feature = "postgres-17",
+                feature = "postgres-18"
+            ))]
+            typsubscript: 0,
+            typalign: "i".to_string(),
+            typstorage: "x".to_string(),
+            typnotnull: false,
+            typbasetype: 0,
+            typtypmod: -1,
+            typndims: 0,
+            typcollation: 0,
+            typdefaultbin: None,
+            typdefault: None,
+            typacl: None,
+        }
+    }
+
+    fn dummy_row() -> PgStatText {
+        PgStatText {
+            inherited: Some(false),
+            null_frac: Some(0.1),
+            avg_width: Some(4),
+            n_distinct: Some(-0.5),
+            most_common_vals: Some("{1,2}".to_string()),
+            most_common_freqs: Some(vec![0.3, 0.2]),
+            histogram_bounds: Some("{3,10,100}".to_string()),
+            correlation: Some(0.9),
+            most_common_elems: None,
+            most_common_elem_freqs: None,
+            elem_count_histogram: None,
+            #[cfg(any(feature = "postgres-17", feature = "postgres-18"))]
+            range_length_histogram: None,
+            #[cfg(any(feature = "postgres-17", feature = "postgres-18"))]
+            range_empty_frac: None,
+            #[cfg(any(feature = "postgres-17", feature = "postgres-18"))]
+            range_bounds_histogram: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_array_literal() {
+        assert_eq!(
+            parse_array_literal(r#"{a,"b c",NULL,"NULL","d\"e"}"#, ','),
+            vec![
+                Some("a".to_string()),
+                Some("b c".to_string()),
+                None,
+                Some("NULL".to_string()),
+                Some("d\"e".to_string()),
+            ]
+        );
+        assert!(parse_array_literal("{}", ',').is_empty());
+        assert_eq!(
+            parse_array_literal("[0:1]={1,2}", ','),
+            vec![Some("1".to_string()), Some("2".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_nested_array_literal() {
+        assert_eq!(
+            parse_array_literal(r#"{{1,2},{"a,b",3}}"#, ','),
+            vec![Some("{1,2}".to_string()), Some(r#"{"a,b",3}"#.to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_array_literal_with_custom_delimiter() {
+        assert_eq!(
+            parse_array_literal("{(1,1),(0,0);(2,2),(1,1)}", ';'),
+            vec![
+                Some("(1,1),(0,0)".to_string()),
+                Some("(2,2),(1,1)".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_statistic_value_parse() {
+        assert_eq!(
+            StatisticValue::parse("t", &dummy_pg_type("bool", "B")),
+            StatisticValue::Boolean(true)
+        );
+        assert_eq!(
+            StatisticValue::parse("42", &dummy_pg_type("int8", "N")),
+            StatisticValue::Integer(42)
+        );
+        assert_eq!(
+            StatisticValue::parse("1.5", &dummy_pg_type("float8", "N")),
+            StatisticValue::Float(1.5)
+        );
+        assert_eq!(
+            StatisticValue::parse("1.25", &dummy_pg_type("numeric", "N")),
+            StatisticValue::Numeric(BigDecimal::from_str("1.25").unwrap())
+        );
+        assert_eq!(
+            StatisticValue::parse("abc", &dummy_pg_type("varchar", "S")),
+            StatisticValue::Text("abc".to_string())
+        );
+        assert_eq!(
+            StatisticValue::parse("2024-01-01", &dummy_pg_type("date", "D")),
+            StatisticValue::Other("2024-01-01".to_string())
+        );
+        assert_eq!(
+            StatisticValue::parse("abc", &dummy_pg_type("int4", "N")),
+            StatisticValue::Other("abc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_column_statistics_from_text() {
+        let statistics =
+            ColumnStatistics::from_text(dummy_row(), &dummy_pg_type("int4", "N"), None);
+        assert!(!statistics.inherited());
+        assert!((statistics.null_fraction() - 0.1).abs() < f32::EPSILON);
+        assert_eq!(statistics.average_width(), 4);
+        assert_eq!(statistics.most_common_values().len(), 2);
+        assert_eq!(
+            statistics.most_common_values()[0].value,
+            StatisticValue::Integer(1)
+        );
+        assert_eq!(
+            statistics.histogram_bounds(),
+            &[
+                StatisticValue::Integer(3),
+                StatisticValue::Integer(10),
+                StatisticValue::Integer(100)
+            ]
+        );
+        assert!((statistics.estimated_distinct_values(1000.0) - 500.0).abs() < 1e-6);
+        assert!(
+            (statistics.equality_selectivity(&StatisticValue::Integer(1), 1000.0) - 0.3).abs()
+                < 1e-6
+        );
+        assert!(statistics.equality_selectivity(&StatisticValue::Integer(7), 1000.0) > 0.0);
+    }
+
+    #[test]
+    fn test_column_statistics_elements_without_element_type() {
+        let mut row = dummy_row();
+        row.most_common_elems = Some("{cat,dog}".to_string());
+        row.most_common_elem_freqs = Some(vec![0.5, 0.25, 0.25, 0.5, 0.0]);
+        let statistics = ColumnStatistics::from_text(row, &dummy_pg_type("tsvector", "U"), None);
+        assert_eq!(
+            statistics
+                .most_common_elements()
+                .iter()
+                .map(|mce| mce.value.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                StatisticValue::Text("cat".to_string()),
+                StatisticValue::Text("dog".to_string())
+            ]
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Submodule providing the `EventTriggerMetadata` struct for a
+//! [`PgEventTrigger`] model.
+
+use diesel::PgConnection;
+
+use crate::{
+    identity::QualifiedName,
+    model_metadata::FiringMode,
+    models::{PgEventTrigger, PgProc},
+};
+
+#[derive(Debug, Clone)]
+/// Wrapper around a [`PgEventTrigger`] with the function it executes.
+///
+/// Event triggers belong to the database rather than to a table, so they are
+/// loaded by the
+/// [`PgDieselDatabaseBuilder`](crate::database::PgDieselDatabaseBuilder) into
+/// a [`PgDieselDatabaseWithEventTriggers`](crate::database::PgDieselDatabaseWithEventTriggers)
+/// alongside the database.
+pub struct EventTriggerMetadata {
+    /// The underlying event trigger model.
+    model: PgEventTrigger,
+    /// When the event trigger fires.
+    firing_mode: FiringMode,
+    /// The function executed by the event trigger.
+    function: PgProc,
+    /// The name of the function qualified by its schema.
+    function_name: QualifiedName,
+}
+
+impl EventTriggerMetadata {
+    /// Creates a new `EventTriggerMetadata` instance, loading the function
+    /// executed by the event trigger.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The event trigger.
+    /// * `conn` - A mutable reference to a `PgConnection`.
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn new(
+        model: PgEventTrigger,
+        conn: &mut PgConnection,
+    ) -> Result<Self, diesel::result::Error> {
+        let (function, schema) = PgProc::from_oid(model.evtfoid, conn)?;
+        let function_name = QualifiedName::new(schema, &function.proname);
+        Ok(Self {
+            firing_mode: FiringMode::from_code(&model.evtenabled).unwrap_or(FiringMode::Origin),
+            model,
+            function,
+            function_name,
+        })
+    }
+
+    /// Loads the event triggers of the database, sorted by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`.
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        PgEventTrigger::load_all(conn)?
+            .into_iter()
+            .map(|event_trigger| Self::new(event_trigger, conn))
+            .collect()
+    }
+
+    /// Returns the underlying event trigger model.
+    #[must_use]
+    pub fn model(&self) -> &PgEventTrigger {
+        &self.model
+    }
+
+    /// Returns the name of the event trigger.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.model.evtname
+    }
+
+    /// Returns the event firing the event trigger, as in
+    /// `ddl_command_start`.
+    #[must_use]
+    pub fn event(&self) -> &str {
+        &self.model.evtevent
+    }
+
+    /// Returns the command tags the event trigger is restricted to, as in
+    /// `CREATE TABLE`, empty if it fires for every command.
+    #[must_use]
+    pub fn tags(&self) -> &[String] {
+        self.model.evttags.as_deref().unwrap_or_default()
+    }
+
+    /// Returns when the event trigger fires.
+    #[must_use]
+    pub fn firing_mode(&self) -> FiringMode {
+        self.firing_mode
+    }
+
+    /// Returns the function executed by the event trigger.
+    #[must_use]
+    pub fn function(&self) -> &PgProc {
+        &self.function
+    }
+
+    /// Returns the name of the function executed by the event trigger,
+    /// qualified by its schema.
+    #[must_use]
+    pub fn function_name(&self) -> &QualifiedName {
+        &self.function_name
+    }
+}
@@ -0,0 +1,53 @@
+//! Submodule providing the `FiringMode` of triggers, event triggers and
+//! rewrite rules.
+
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// When a trigger, event trigger or rewrite rule fires with respect to the
+/// `session_replication_role` setting, as in `pg_event_trigger.evtenabled`
+/// or `pg_rewrite.ev_enabled`.
+pub enum FiringMode {
+    /// Fires in the `origin` and `local` modes, the default (`O`).
+    Origin,
+    /// Fires only in the `replica` mode (`R`), as set by
+    /// `ENABLE REPLICA`.
+    Replica,
+    /// Fires in every mode (`A`), as set by `ENABLE ALWAYS`.
+    Always,
+    /// Never fires (`D`), as set by `DISABLE`.
+    Disabled,
+}
+
+impl FiringMode {
+    #[must_use]
+    /// Returns the firing mode of the catalog code, or `None` if it is not
+    /// one of `O`, `R`, `A` or `D`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "O" => Some(Self::Origin),
+            "R" => Some(Self::Replica),
+            "A" => Some(Self::Always),
+            "D" => Some(Self::Disabled),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Returns whether the object fires in some mode.
+    pub fn is_enabled(self) -> bool {
+        self != Self::Disabled
+    }
+}
+
+impl Display for FiringMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Origin => "origin",
+            Self::Replica => "replica",
+            Self::Always => "always",
+            Self::Disabled => "disabled",
+        })
+    }
+}
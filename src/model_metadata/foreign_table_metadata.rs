@@ -0,0 +1,173 @@
+//! Submodule providing the `ForeignTableMetadata` struct for foreign tables,
+//! along with the user mappings of their server.
+//!
+//! A foreign table is attached to its
+//! [`TableMetadata`](crate::model_metadata::TableMetadata) with the foreign
+//! server and data wrapper it is accessed through and the user mappings of
+//! the server, so that every remote dependency of a database can be listed.
+
+use crate::{
+    model_metadata::CatalogOptions,
+    models::{PgForeignDataWrapper, PgForeignServer, PgForeignTable, PgUserMappings},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A user mapping of a foreign server, with its options.
+pub struct ForeignUserMapping {
+    /// The OID of the local role, or 0 for the `PUBLIC` mapping.
+    user_oid: u32,
+    /// The name of the local role, or `public`.
+    user_name: String,
+    /// The options of the mapping, empty unless readable by the current role.
+    options: CatalogOptions,
+}
+
+impl From<&PgUserMappings> for ForeignUserMapping {
+    fn from(mapping: &PgUserMappings) -> Self {
+        Self {
+            user_oid: mapping.umuser.unwrap_or_default(),
+            user_name: mapping.usename.clone().unwrap_or_default(),
+            options: CatalogOptions::parse(mapping.umoptions.as_deref()),
+        }
+    }
+}
+
+impl ForeignUserMapping {
+    /// Returns the OID of the local role, or 0 for the `PUBLIC` mapping.
+    #[must_use]
+    pub fn user_oid(&self) -> u32 {
+        self.user_oid
+    }
+
+    /// Returns the name of the local role, or `public`.
+    #[must_use]
+    pub fn user_name(&self) -> &str {
+        &self.user_name
+    }
+
+    /// Returns whether the mapping applies to every role.
+    #[must_use]
+    pub fn is_public(&self) -> bool {
+        self.user_oid == 0
+    }
+
+    /// Returns the options of the mapping, empty unless readable by the
+    /// current role.
+    #[must_use]
+    pub fn options(&self) -> &CatalogOptions {
+        &self.options
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Metadata of a foreign table: the server and foreign data wrapper it is
+/// accessed through, and the options of the table, its columns, the server,
+/// the wrapper and the user mappings of the server.
+pub struct ForeignTableMetadata {
+    /// The row of `pg_foreign_table`.
+    foreign_table: PgForeignTable,
+    /// The foreign server of the table.
+    server: PgForeignServer,
+    /// The foreign data wrapper of the server.
+    wrapper: PgForeignDataWrapper,
+    /// The options of the table.
+    options: CatalogOptions,
+    /// The options of the server.
+    server_options: CatalogOptions,
+    /// The options of the foreign data wrapper.
+    wrapper_options: CatalogOptions,
+    /// The options of the columns of the table, by column name.
+    column_options: Vec<(String, CatalogOptions)>,
+    /// The user mappings of the server.
+    user_mappings: Vec<ForeignUserMapping>,
+}
+
+impl ForeignTableMetadata {
+    /// Creates a new `ForeignTableMetadata`.
+    ///
+    /// # Arguments
+    ///
+    /// * `foreign_table` - The row of `pg_foreign_table`.
+    /// * `server` - The foreign server of the table.
+    /// * `wrapper` - The foreign data wrapper of the server.
+    /// * `column_options` - The names of the columns of the table with their
+    ///   `pg_attribute.attfdwoptions`.
+    /// * `user_mappings` - The rows of `pg_user_mappings` of the server.
+    #[must_use]
+    pub fn new(
+        foreign_table: PgForeignTable,
+        server: PgForeignServer,
+        wrapper: PgForeignDataWrapper,
+        column_options: Vec<(String, Option<Vec<String>>)>,
+        user_mappings: &[PgUserMappings],
+    ) -> Self {
+        Self {
+            options: CatalogOptions::parse(foreign_table.ftoptions.as_deref()),
+            server_options: CatalogOptions::parse(server.srvoptions.as_deref()),
+            wrapper_options: CatalogOptions::parse(wrapper.fdwoptions.as_deref()),
+            foreign_table,
+            server,
+            wrapper,
+            column_options: column_options
+                .into_iter()
+                .map(|(column, options)| (column, CatalogOptions::parse(options.as_deref())))
+                .collect(),
+            user_mappings: user_mappings.iter().map(ForeignUserMapping::from).collect(),
+        }
+    }
+
+    /// Returns the row of `pg_foreign_table`.
+    #[must_use]
+    pub fn foreign_table(&self) -> &PgForeignTable {
+        &self.foreign_table
+    }
+
+    /// Returns the foreign server of the table.
+    #[must_use]
+    pub fn server(&self) -> &PgForeignServer {
+        &self.server
+    }
+
+    /// Returns the foreign data wrapper of the server, as in `postgres_fdw`.
+    #[must_use]
+    pub fn wrapper(&self) -> &PgForeignDataWrapper {
+        &self.wrapper
+    }
+
+    /// Returns the options of the table, as in `schema_name` and
+    /// `table_name` for `postgres_fdw`.
+    #[must_use]
+    pub fn options(&self) -> &CatalogOptions {
+        &self.options
+    }
+
+    /// Returns the options of the server, as in `host` and `dbname` for
+    /// `postgres_fdw`.
+    #[must_use]
+    pub fn server_options(&self) -> &CatalogOptions {
+        &self.server_options
+    }
+
+    /// Returns the options of the foreign data wrapper.
+    #[must_use]
+    pub fn wrapper_options(&self) -> &CatalogOptions {
+        &self.wrapper_options
+    }
+
+    /// Returns the options of the column with the provided name, if it is a
+    /// column of the table.
+    #[must_use]
+    pub fn column_options(&self, column_name: &str) -> Option<&CatalogOptions> {
+        self.column_options
+            .iter()
+            .find(|(column, _)| column == column_name)
+            .map(|(_, options)| options)
+    }
+
+    /// Returns the user mappings of the server.
+    #[must_use]
+    pub fn user_mappings(&self) -> &[ForeignUserMapping] {
+        &self.user_mappings
+    }
+}
@@ -0,0 +1,279 @@
+//! Submodule providing the `RuleMetadata` struct for a [`PgRewrite`] rule,
+//! with its condition and actions parsed from its definition.
+
+use std::fmt::Display;
+
+use sqlparser::{ast::Statement, dialect::PostgreSqlDialect, parser::Parser};
+
+use crate::{model_metadata::FiringMode, models::PgRewrite};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The event a rewrite rule applies to, as in `pg_rewrite.ev_type`.
+pub enum RuleEvent {
+    /// `ON SELECT` (`1`), only found on views.
+    Select,
+    /// `ON UPDATE` (`2`).
+    Update,
+    /// `ON INSERT` (`3`).
+    Insert,
+    /// `ON DELETE` (`4`).
+    Delete,
+}
+
+impl RuleEvent {
+    #[must_use]
+    /// Returns the event of the catalog code, or `None` if it is not one of
+    /// `1` to `4`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "1" => Some(Self::Select),
+            "2" => Some(Self::Update),
+            "3" => Some(Self::Insert),
+            "4" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+impl Display for RuleEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Select => "SELECT",
+            Self::Update => "UPDATE",
+            Self::Insert => "INSERT",
+            Self::Delete => "DELETE",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Wrapper around a [`PgRewrite`] rule of a table, other than the `_RETURN`
+/// rule of views, with its definition and parsed actions.
+///
+/// Such rules rewrite the `INSERT`, `UPDATE` or `DELETE` statements run on
+/// their table, in addition to (`ALSO`) or in place of (`INSTEAD`) the
+/// original statement.
+pub struct RuleMetadata {
+    /// The underlying rule model.
+    model: PgRewrite,
+    /// The event the rule applies to.
+    event: RuleEvent,
+    /// When the rule fires.
+    firing_mode: FiringMode,
+    /// The definition of the rule, as returned by `pg_get_ruledef`.
+    definition: String,
+    /// The `WHERE` condition of the rule.
+    condition: Option<String>,
+    /// The parsed actions of the rule, or `None` if they could not be
+    /// parsed.
+    actions: Option<Vec<Statement>>,
+}
+
+impl RuleMetadata {
+    /// Creates a new `RuleMetadata` instance, parsing the condition and
+    /// actions of the rule from its definition.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The rule.
+    /// * `definition` - The definition of the rule, as returned by
+    ///   `pg_get_ruledef`.
+    #[must_use]
+    pub fn new(model: PgRewrite, definition: String) -> Self {
+        let event = RuleEvent::from_code(&model.ev_type).unwrap_or(RuleEvent::Select);
+        let firing_mode = FiringMode::from_code(&model.ev_enabled).unwrap_or(FiringMode::Origin);
+        let (condition, actions) = match split_definition(&definition) {
+            Some((condition, actions)) => (
+                condition.map(str::to_owned),
+                parse_actions(actions, model.is_instead),
+            ),
+            None => (None, None),
+        };
+        Self {
+            model,
+            event,
+            firing_mode,
+            definition,
+            condition,
+            actions,
+        }
+    }
+
+    /// Returns the underlying rule model.
+    #[must_use]
+    pub fn model(&self) -> &PgRewrite {
+        &self.model
+    }
+
+    /// Returns the name of the rule.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.model.rulename
+    }
+
+    /// Returns the event the rule applies to.
+    #[must_use]
+    pub fn event(&self) -> RuleEvent {
+        self.event
+    }
+
+    /// Returns whether the rule replaces the original statement rather than
+    /// running alongside it.
+    #[must_use]
+    pub fn is_instead(&self) -> bool {
+        self.model.is_instead
+    }
+
+    /// Returns when the rule fires.
+    #[must_use]
+    pub fn firing_mode(&self) -> FiringMode {
+        self.firing_mode
+    }
+
+    /// Returns the definition of the rule, as returned by `pg_get_ruledef`.
+    #[must_use]
+    pub fn definition(&self) -> &str {
+        &self.definition
+    }
+
+    /// Returns the `WHERE` condition of the rule, if any.
+    #[must_use]
+    pub fn condition(&self) -> Option<&str> {
+        self.condition.as_deref()
+    }
+
+    /// Returns the parsed actions of the rule, empty for `DO INSTEAD
+    /// NOTHING`, or `None` if they could not be parsed.
+    #[must_use]
+    pub fn actions(&self) -> Option<&[Statement]> {
+        self.actions.as_deref()
+    }
+}
+
+/// Splits the definition of a rule into its `WHERE` condition, if any, and
+/// its actions, from the `DO` keyword onwards, or returns `None` if the
+/// definition has no `DO` keyword.
+///
+/// Keywords are only looked for outside of parentheses, string literals and
+/// quoted identifiers.
+fn split_definition(definition: &str) -> Option<(Option<&str>, &str)> {
+    let mut depth = 0_usize;
+    let mut quote: Option<char> = None;
+    let mut condition_start = None;
+    let mut previous = ' ';
+    for (position, c) in definition.char_indices() {
+        if let Some(open) = quote {
+            if c == open {
+                quote = None;
+            }
+        } else if matches!(c, '\'' | '"') {
+            quote = Some(c);
+        } else if c == '(' {
+            depth += 1;
+        } else if c == ')' {
+            depth = depth.saturating_sub(1);
+        } else if depth == 0 && previous.is_whitespace() {
+            let rest = &definition[position..];
+            let keyword = |keyword: &str| {
+                rest.get(..keyword.len())
+                    .is_some_and(|word| word.eq_ignore_ascii_case(keyword))
+                    && rest[keyword.len()..].starts_with(char::is_whitespace)
+            };
+            if keyword("WHERE") && condition_start.is_none() {
+                condition_start = Some(position + "WHERE".len());
+            } else if keyword("DO") {
+                let condition = condition_start
+                    .map(|start| strip_parentheses(definition[start..position].trim()));
+                return Some((condition, &rest["DO".len()..]));
+            }
+        }
+        previous = c;
+    }
+    None
+}
+
+/// Returns the text without the parentheses enclosing it, if any, as
+/// `pg_get_ruledef` encloses conditions and lists of actions.
+fn strip_parentheses(text: &str) -> &str {
+    text.strip_prefix('(')
+        .and_then(|text| text.strip_suffix(')'))
+        .map_or(text, str::trim)
+}
+
+/// Parses the actions following the `DO` keyword of a rule, returning
+/// `None` if they cannot be parsed.
+fn parse_actions(actions: &str, is_instead: bool) -> Option<Vec<Statement>> {
+    let mut actions = actions.trim();
+    let keyword = if is_instead { "INSTEAD" } else { "ALSO" };
+    if actions
+        .get(..keyword.len())
+        .is_some_and(|word| word.eq_ignore_ascii_case(keyword))
+    {
+        actions = actions[keyword.len()..].trim_start();
+    }
+    let actions = actions.trim_end_matches(';').trim();
+    if actions.eq_ignore_ascii_case("NOTHING") {
+        return Some(Vec::new());
+    }
+    Parser::parse_sql(&PostgreSqlDialect {}, strip_parentheses(actions)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, ev_type: &str, is_instead: bool) -> PgRewrite {
+        PgRewrite {
+            oid: 16_400,
+            rulename: name.to_owned(),
+            ev_class: 16_384,
+            ev_type: ev_type.to_owned(),
+            ev_enabled: "O".to_owned(),
+            is_instead,
+            ev_qual: String::new(),
+            ev_action: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_split_definition() {
+        assert_eq!(
+            split_definition(
+                "CREATE RULE log_price AS\n    ON UPDATE TO public.items\n   WHERE (new.price \
+                 <> old.price) DO  INSERT INTO price_log (price) VALUES (new.price);"
+            ),
+            Some((
+                Some("new.price <> old.price"),
+                "  INSERT INTO price_log (price) VALUES (new.price);"
+            ))
+        );
+        assert_eq!(
+            split_definition("CREATE RULE \"DO it\" AS ON DELETE TO t DO INSTEAD NOTHING;"),
+            Some((None, " INSTEAD NOTHING;"))
+        );
+        assert_eq!(split_definition("CREATE RULE r AS ON DELETE TO t"), None);
+    }
+
+    #[test]
+    fn test_rule_metadata() {
+        let nothing = RuleMetadata::new(
+            rule("protect", "4", true),
+            "CREATE RULE protect AS\n    ON DELETE TO public.users DO INSTEAD NOTHING;".to_owned(),
+        );
+        assert_eq!(nothing.event(), RuleEvent::Delete);
+        assert!(nothing.is_instead());
+        assert!(nothing.firing_mode().is_enabled());
+        assert_eq!(nothing.condition(), None);
+        assert_eq!(nothing.actions().map(<[Statement]>::len), Some(0));
+
+        let redirect = RuleMetadata::new(
+            rule("redirect", "3", true),
+            "CREATE RULE redirect AS\n    ON INSERT TO public.users DO INSTEAD ( INSERT INTO \
+             archive (id) VALUES (new.id);\n UPDATE counters SET n = n + 1;\n);"
+                .to_owned(),
+        );
+        assert_eq!(redirect.event().to_string(), "INSERT");
+        assert_eq!(redirect.actions().map(<[Statement]>::len), Some(2));
+    }
+}
@@ -0,0 +1,756 @@
+//! Submodule providing the `StorageMetadata` struct describing the physical
+//! storage of a table and its indexes: tablespace, persistence, access
+//! method, storage parameters, per-column storage, TOAST table and sizes.
+//!
+//! The storage parameters are typed, from `fillfactor` to the autovacuum
+//! overrides and `toast_tuple_target`, and each column has its storage mode
+//! and compression. It is only loaded when requested with
+//! [`PgDieselDatabaseBuilder::load_storage`](crate::database::PgDieselDatabaseBuilder::load_storage).
+
+use std::{collections::HashMap, fmt::Display};
+
+use diesel::{
+    ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, QueryableByName, RunQueryDsl,
+    SelectableHelper,
+    sql_types::{Array, BigInt, Nullable, Oid, Text},
+};
+
+use crate::{identity::RelOid, model_metadata::CatalogOptions, models::PgTablespace};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The persistence of a relation, as in `pg_class.relpersistence`.
+pub enum Persistence {
+    /// A regular, WAL-logged relation (`p`).
+    Permanent,
+    /// An `UNLOGGED` relation, truncated after a crash (`u`).
+    Unlogged,
+    /// A `TEMPORARY` relation, dropped at the end of the session (`t`).
+    Temporary,
+}
+
+impl Persistence {
+    #[must_use]
+    /// Returns the persistence of the catalog code, or `None` if it is not
+    /// one of `p`, `u` or `t`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "p" => Some(Self::Permanent),
+            "u" => Some(Self::Unlogged),
+            "t" => Some(Self::Temporary),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Returns whether changes to the relation are written to the WAL, and
+    /// thus survive a crash and reach the standbys.
+    pub fn is_logged(self) -> bool {
+        self == Self::Permanent
+    }
+}
+
+impl Display for Persistence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Permanent => "permanent",
+            Self::Unlogged => "unlogged",
+            Self::Temporary => "temporary",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The storage mode of a column, as in `pg_attribute.attstorage` and
+/// `ALTER TABLE ... ALTER COLUMN ... SET STORAGE`.
+pub enum StorageMode {
+    /// Stored inline and uncompressed, for fixed-length types (`p`).
+    Plain,
+    /// Moved out of line to the TOAST table, uncompressed (`e`).
+    External,
+    /// Compressed inline, moved out of line only as a last resort (`m`).
+    Main,
+    /// Compressed, then moved out of line if still too large (`x`).
+    Extended,
+}
+
+impl StorageMode {
+    #[must_use]
+    /// Returns the storage mode of the catalog code, or `None` if it is not
+    /// one of `p`, `e`, `m` or `x`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "p" => Some(Self::Plain),
+            "e" => Some(Self::External),
+            "m" => Some(Self::Main),
+            "x" => Some(Self::Extended),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Returns whether values of the column may be moved to the TOAST table.
+    pub fn is_toastable(self) -> bool {
+        self != Self::Plain
+    }
+}
+
+impl Display for StorageMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Plain => "PLAIN",
+            Self::External => "EXTERNAL",
+            Self::Main => "MAIN",
+            Self::Extended => "EXTENDED",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The compression method of a column, as in `pg_attribute.attcompression`.
+pub enum Compression {
+    /// The built-in `pglz` method (`p`).
+    Pglz,
+    /// The `lz4` method (`l`).
+    Lz4,
+}
+
+impl Compression {
+    #[must_use]
+    /// Returns the compression method of the catalog code, or `None` if it
+    /// is empty, for the `default_toast_compression` setting, or unknown.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "p" => Some(Self::Pglz),
+            "l" => Some(Self::Lz4),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Pglz => "pglz",
+            Self::Lz4 => "lz4",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The value of a storage parameter.
+pub enum StorageValue {
+    /// An integer, as for `fillfactor`.
+    Integer(i64),
+    /// A real number, as for `autovacuum_vacuum_scale_factor`.
+    Real(f64),
+    /// A boolean, as for `autovacuum_enabled`.
+    Boolean(bool),
+    /// Any other value, as for `vacuum_index_cleanup=auto`.
+    Text(String),
+}
+
+impl StorageValue {
+    #[must_use]
+    /// Parses the value as written in `reloptions`, trying integers, real
+    /// numbers and the boolean spellings accepted by `PostgreSQL` in turn.
+    pub fn parse(text: &str) -> Self {
+        if let Ok(value) = text.parse() {
+            return Self::Integer(value);
+        }
+        if let Ok(value) = text.parse() {
+            return Self::Real(value);
+        }
+        match text.to_ascii_lowercase().as_str() {
+            "true" | "on" | "yes" => Self::Boolean(true),
+            "false" | "off" | "no" => Self::Boolean(false),
+            _ => Self::Text(text.to_owned()),
+        }
+    }
+
+    #[must_use]
+    /// Returns the value as an integer, if it is one.
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Self::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Returns the value as a real number, if it is a number.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn as_real(&self) -> Option<f64> {
+        match self {
+            Self::Integer(value) => Some(*value as f64),
+            Self::Real(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Returns the value as a boolean, reading `1` and `0` as booleans as
+    /// `PostgreSQL` does.
+    pub fn as_boolean(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(value) => Some(*value),
+            Self::Integer(1) => Some(true),
+            Self::Integer(0) => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl Display for StorageValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Real(value) => write!(f, "{value}"),
+            Self::Boolean(value) => write!(f, "{value}"),
+            Self::Text(value) => f.write_str(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The storage parameters of a relation, set with `WITH (...)`, as typed
+/// key/value pairs in the order they are stored in `reloptions`.
+pub struct StorageParameters {
+    /// The parameters, by name.
+    parameters: Vec<(String, StorageValue)>,
+}
+
+impl StorageParameters {
+    /// Parses the parameters as stored by the catalogs, where each parameter
+    /// is a `name=value` string.
+    ///
+    /// # Arguments
+    ///
+    /// * `reloptions` - The parameters, as in `pg_class.reloptions`.
+    #[must_use]
+    pub fn parse(reloptions: Option<&[String]>) -> Self {
+        Self::from(&CatalogOptions::parse(reloptions))
+    }
+
+    /// Returns the value of the parameter with the provided name, if set.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&StorageValue> {
+        self.parameters
+            .iter()
+            .find(|(parameter, _)| parameter == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over the names and values of the parameters.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &StorageValue)> {
+        self.parameters
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
+    /// Returns the number of parameters.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.parameters.len()
+    }
+
+    /// Returns whether no parameter is set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+
+    /// Returns the `fillfactor` of the relation, as a percentage, if set.
+    #[must_use]
+    pub fn fillfactor(&self) -> Option<u8> {
+        self.get("fillfactor")?.as_integer()?.try_into().ok()
+    }
+
+    /// Returns the `toast_tuple_target` of the table, in bytes, if set.
+    #[must_use]
+    pub fn toast_tuple_target(&self) -> Option<u32> {
+        self.get("toast_tuple_target")?
+            .as_integer()?
+            .try_into()
+            .ok()
+    }
+
+    /// Returns whether autovacuum is enabled for the table, if overridden by
+    /// `autovacuum_enabled`.
+    #[must_use]
+    pub fn autovacuum_enabled(&self) -> Option<bool> {
+        self.get("autovacuum_enabled")?.as_boolean()
+    }
+
+    /// Returns an iterator over the parameters overriding the autovacuum
+    /// settings of the server, as in `autovacuum_vacuum_scale_factor`.
+    pub fn autovacuum_overrides(&self) -> impl Iterator<Item = (&str, &StorageValue)> {
+        self.iter()
+            .filter(|(name, _)| name.starts_with("autovacuum_"))
+    }
+}
+
+impl From<&CatalogOptions> for StorageParameters {
+    fn from(options: &CatalogOptions) -> Self {
+        Self {
+            parameters: options
+                .iter()
+                .map(|(name, value)| (name.to_owned(), StorageValue::parse(value)))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The storage mode and compression method of a column.
+pub struct ColumnStorage {
+    /// The name of the column.
+    name: String,
+    /// The storage mode of the column.
+    mode: StorageMode,
+    /// The compression method of the column, or `None` for the
+    /// `default_toast_compression` setting.
+    compression: Option<Compression>,
+}
+
+impl ColumnStorage {
+    /// Creates a new `ColumnStorage` from the columns of a `pg_attribute`
+    /// row, falling back to `EXTENDED` for an unknown storage mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the column.
+    /// * `attstorage` - The storage mode code of the column.
+    /// * `attcompression` - The compression method code of the column.
+    #[must_use]
+    pub fn new(name: String, attstorage: &str, attcompression: &str) -> Self {
+        Self {
+            name,
+            mode: StorageMode::from_code(attstorage).unwrap_or(StorageMode::Extended),
+            compression: Compression::from_code(attcompression),
+        }
+    }
+
+    /// Returns the name of the column.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the storage mode of the column.
+    #[must_use]
+    pub fn mode(&self) -> StorageMode {
+        self.mode
+    }
+
+    /// Returns the compression method of the column, or `None` if it uses
+    /// the `default_toast_compression` setting.
+    #[must_use]
+    pub fn compression(&self) -> Option<Compression> {
+        self.compression
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The TOAST table holding the out-of-line values of a table.
+pub struct ToastStorage {
+    /// The name of the TOAST table, as in `pg_toast_16384`.
+    name: String,
+    /// The storage parameters of the TOAST table, set on the table with the
+    /// `toast.` prefix.
+    parameters: StorageParameters,
+    /// The size of the TOAST table, in bytes.
+    size_bytes: i64,
+}
+
+impl ToastStorage {
+    /// Returns the name of the TOAST table, in the `pg_toast` schema.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the storage parameters of the TOAST table.
+    #[must_use]
+    pub fn parameters(&self) -> &StorageParameters {
+        &self.parameters
+    }
+
+    /// Returns the size of the TOAST table, in bytes.
+    #[must_use]
+    pub fn size_bytes(&self) -> i64 {
+        self.size_bytes
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The physical storage of an index.
+pub struct IndexStorage {
+    /// The name of the index.
+    name: String,
+    /// The tablespace of the index, or `None` for the default one.
+    tablespace: Option<PgTablespace>,
+    /// The access method of the index, as in `btree`.
+    access_method: String,
+    /// The storage parameters of the index.
+    parameters: StorageParameters,
+    /// The size of the index, in bytes.
+    size_bytes: i64,
+}
+
+impl IndexStorage {
+    /// Returns the name of the index.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the tablespace of the index, or `None` if it is stored in the
+    /// default tablespace of the database.
+    #[must_use]
+    pub fn tablespace(&self) -> Option<&PgTablespace> {
+        self.tablespace.as_ref()
+    }
+
+    /// Returns the access method of the index, as in `btree` or `gin`.
+    #[must_use]
+    pub fn access_method(&self) -> &str {
+        &self.access_method
+    }
+
+    /// Returns the storage parameters of the index, as in `fillfactor` or
+    /// `deduplicate_items`.
+    #[must_use]
+    pub fn parameters(&self) -> &StorageParameters {
+        &self.parameters
+    }
+
+    /// Returns the size of the index, in bytes.
+    #[must_use]
+    pub fn size_bytes(&self) -> i64 {
+        self.size_bytes
+    }
+}
+
+#[derive(QueryableByName)]
+/// The storage columns of a `pg_class` row.
+struct RelationStorageRow {
+    /// The name of the relation.
+    #[diesel(sql_type = Text)]
+    name: String,
+    /// The `relpersistence` code of the relation.
+    #[diesel(sql_type = Text)]
+    persistence: String,
+    /// The name of the access method, `NULL` for relations without storage.
+    #[diesel(sql_type = Nullable<Text>)]
+    access_method: Option<String>,
+    /// The OID of the tablespace, `0` for the default one.
+    #[diesel(sql_type = Oid)]
+    tablespace_oid: u32,
+    /// The storage parameters of the relation.
+    #[diesel(sql_type = Nullable<Array<Text>>)]
+    reloptions: Option<Vec<String>>,
+    /// The size of the main fork of the relation, in bytes.
+    #[diesel(sql_type = BigInt)]
+    size_bytes: i64,
+    /// The size of the relation with its indexes and TOAST table, in bytes.
+    #[diesel(sql_type = BigInt)]
+    total_size_bytes: i64,
+    /// The OID of the TOAST table, `0` if there is none.
+    #[diesel(sql_type = Oid)]
+    toast_oid: u32,
+}
+
+/// Loads the storage columns of the relations with the provided OIDs, sorted
+/// by name.
+fn relation_rows(
+    oids: &[u32],
+    conn: &mut PgConnection,
+) -> Result<Vec<RelationStorageRow>, diesel::result::Error> {
+    diesel::sql_query(
+        r"
+        SELECT
+            c.relname::text AS name,
+            c.relpersistence::text AS persistence,
+            am.amname::text AS access_method,
+            c.reltablespace AS tablespace_oid,
+            c.reloptions::text[] AS reloptions,
+            pg_relation_size(c.oid) AS size_bytes,
+            pg_total_relation_size(c.oid) AS total_size_bytes,
+            c.reltoastrelid AS toast_oid
+        FROM pg_class c
+        LEFT JOIN pg_am am ON am.oid = c.relam
+        WHERE c.oid = ANY($1)
+        ORDER BY c.relname
+        ",
+    )
+    .bind::<Array<Oid>, _>(oids)
+    .load(conn)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The physical storage of a table: where and how its rows are stored, how
+/// large it is, and the storage of its columns, TOAST table and indexes.
+///
+/// Loading it queries the size of every relation of the table, so it is only
+/// attached to the tables of a [`PgDieselDatabase`](crate::PgDieselDatabase)
+/// when requested with
+/// [`PgDieselDatabaseBuilder::load_storage`](crate::database::PgDieselDatabaseBuilder::load_storage).
+pub struct StorageMetadata {
+    /// The tablespace of the table, or `None` for the default one.
+    tablespace: Option<PgTablespace>,
+    /// The persistence of the table.
+    persistence: Persistence,
+    /// The access method of the table, as in `heap`, or `None` for views and
+    /// foreign tables.
+    access_method: Option<String>,
+    /// The storage parameters of the table.
+    parameters: StorageParameters,
+    /// The storage of the columns, in the order of the table.
+    columns: Vec<ColumnStorage>,
+    /// The TOAST table of the table, if any.
+    toast: Option<ToastStorage>,
+    /// The storage of the indexes of the table, sorted by name.
+    indexes: Vec<IndexStorage>,
+    /// The size of the main fork of the table, in bytes.
+    size_bytes: i64,
+    /// The size of the table with its indexes and TOAST table, in bytes.
+    total_size_bytes: i64,
+}
+
+impl StorageMetadata {
+    /// Loads the storage of the table with the provided OID.
+    ///
+    /// # Arguments
+    ///
+    /// * `rel_oid` - The OID of the table in `pg_class`.
+    /// * `conn` - A mutable reference to a `PgConnection`.
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load(rel_oid: RelOid, conn: &mut PgConnection) -> Result<Self, diesel::result::Error> {
+        use crate::schema::pg_catalog::{pg_attribute::pg_attribute, pg_index::pg_index};
+
+        let table = relation_rows(&[rel_oid.get()], conn)?
+            .pop()
+            .ok_or(diesel::result::Error::NotFound)?;
+        let mut tablespaces: HashMap<u32, Option<PgTablespace>> = HashMap::new();
+        let tablespace = tablespace(table.tablespace_oid, &mut tablespaces, conn)?;
+
+        let columns = pg_attribute::table
+            .filter(pg_attribute::attrelid.eq(rel_oid.get()))
+            .filter(pg_attribute::attnum.gt(0))
+            .filter(pg_attribute::attisdropped.eq(false))
+            .order_by(pg_attribute::attnum)
+            .select((
+                pg_attribute::attname,
+                pg_attribute::attstorage,
+                pg_attribute::attcompression,
+            ))
+            .load::<(String, String, String)>(conn)?
+            .into_iter()
+            .map(|(name, storage, compression)| ColumnStorage::new(name, &storage, &compression))
+            .collect();
+
+        let toast = if table.toast_oid == 0 {
+            None
+        } else {
+            relation_rows(&[table.toast_oid], conn)?
+                .pop()
+                .map(|toast| ToastStorage {
+                    name: toast.name,
+                    parameters: StorageParameters::parse(toast.reloptions.as_deref()),
+                    size_bytes: toast.size_bytes,
+                })
+        };
+
+        let index_oids: Vec<u32> = pg_index::table
+            .filter(pg_index::indrelid.eq(rel_oid.get()))
+            .select(pg_index::indexrelid)
+            .load(conn)?;
+        let mut indexes = Vec::new();
+        for index in relation_rows(&index_oids, conn)? {
+            indexes.push(IndexStorage {
+                tablespace: tablespace(index.tablespace_oid, &mut tablespaces, conn)?,
+                access_method: index.access_method.unwrap_or_default(),
+                parameters: StorageParameters::parse(index.reloptions.as_deref()),
+                size_bytes: index.size_bytes,
+                name: index.name,
+            });
+        }
+
+        Ok(Self {
+            tablespace,
+            persistence: Persistence::from_code(&table.persistence)
+                .unwrap_or(Persistence::Permanent),
+            access_method: table.access_method,
+            parameters: StorageParameters::parse(table.reloptions.as_deref()),
+            columns,
+            toast,
+            indexes,
+            size_bytes: table.size_bytes,
+            total_size_bytes: table.total_size_bytes,
+        })
+    }
+
+    /// Returns the tablespace of the table, or `None` if it is stored in the
+    /// default tablespace of the database.
+    #[must_use]
+    pub fn tablespace(&self) -> Option<&PgTablespace> {
+        self.tablespace.as_ref()
+    }
+
+    /// Returns the persistence of the table.
+    #[must_use]
+    pub fn persistence(&self) -> Persistence {
+        self.persistence
+    }
+
+    /// Returns the access method of the table, as in `heap`, or `None` if it
+    /// has no storage of its own, as for views and foreign tables.
+    #[must_use]
+    pub fn access_method(&self) -> Option<&str> {
+        self.access_method.as_deref()
+    }
+
+    /// Returns the storage parameters of the table.
+    #[must_use]
+    pub fn parameters(&self) -> &StorageParameters {
+        &self.parameters
+    }
+
+    /// Returns the storage of the columns, in the order of the table.
+    #[must_use]
+    pub fn columns(&self) -> &[ColumnStorage] {
+        &self.columns
+    }
+
+    /// Returns the storage of the column with the provided name, if any.
+    #[must_use]
+    pub fn column(&self, name: &str) -> Option<&ColumnStorage> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// Returns the TOAST table of the table, if any.
+    #[must_use]
+    pub fn toast(&self) -> Option<&ToastStorage> {
+        self.toast.as_ref()
+    }
+
+    /// Returns the storage of the indexes of the table, sorted by name.
+    #[must_use]
+    pub fn indexes(&self) -> &[IndexStorage] {
+        &self.indexes
+    }
+
+    /// Returns the storage of the index with the provided name, if any.
+    #[must_use]
+    pub fn index(&self, name: &str) -> Option<&IndexStorage> {
+        self.indexes.iter().find(|index| index.name == name)
+    }
+
+    /// Returns the size of the main fork of the table, as given by
+    /// `pg_relation_size`, in bytes.
+    #[must_use]
+    pub fn size_bytes(&self) -> i64 {
+        self.size_bytes
+    }
+
+    /// Returns the size of the table with its indexes and TOAST table, as
+    /// given by `pg_total_relation_size`, in bytes.
+    #[must_use]
+    pub fn total_size_bytes(&self) -> i64 {
+        self.total_size_bytes
+    }
+}
+
+/// Returns the tablespace with the provided OID, or `None` for the default
+/// tablespace, caching the tablespaces already loaded.
+fn tablespace(
+    oid: u32,
+    tablespaces: &mut HashMap<u32, Option<PgTablespace>>,
+    conn: &mut PgConnection,
+) -> Result<Option<PgTablespace>, diesel::result::Error> {
+    use crate::schema::pg_catalog::pg_tablespace::pg_tablespace;
+
+    if oid == 0 {
+        return Ok(None);
+    }
+    if let Some(tablespace) = tablespaces.get(&oid) {
+        return Ok(tablespace.clone());
+    }
+    let tablespace = pg_tablespace::table
+        .filter(pg_tablespace::oid.eq(oid))
+        .select(PgTablespace::as_select())
+        .first(conn)
+        .optional()?;
+    tablespaces.insert(oid, tablespace.clone());
+    Ok(tablespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_parameters() {
+        let parameters = StorageParameters::parse(Some(&[
+            "fillfactor=70".to_owned(),
+            "autovacuum_enabled=off".to_owned(),
+            "autovacuum_vacuum_scale_factor=0.01".to_owned(),
+            "toast_tuple_target=4080".to_owned(),
+            "vacuum_index_cleanup=auto".to_owned(),
+        ]));
+        assert_eq!(parameters.len(), 5);
+        assert_eq!(parameters.fillfactor(), Some(70));
+        assert_eq!(parameters.toast_tuple_target(), Some(4080));
+        assert_eq!(parameters.autovacuum_enabled(), Some(false));
+        assert_eq!(
+            parameters
+                .autovacuum_overrides()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+            ["autovacuum_enabled", "autovacuum_vacuum_scale_factor"]
+        );
+        assert_eq!(
+            parameters
+                .get("autovacuum_vacuum_scale_factor")
+                .and_then(StorageValue::as_real),
+            Some(0.01)
+        );
+        assert_eq!(
+            parameters.get("vacuum_index_cleanup"),
+            Some(&StorageValue::Text("auto".to_owned()))
+        );
+        assert!(StorageParameters::parse(None).is_empty());
+        assert_eq!(
+            StorageParameters::parse(Some(&["autovacuum_enabled=1".to_owned()]))
+                .autovacuum_enabled(),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_codes() {
+        assert_eq!(Persistence::from_code("u"), Some(Persistence::Unlogged));
+        assert!(!Persistence::Unlogged.is_logged());
+        assert_eq!(Persistence::from_code("x"), None);
+
+        let column = ColumnStorage::new("body".to_owned(), "m", "l");
+        assert_eq!(column.mode(), StorageMode::Main);
+        assert_eq!(column.compression(), Some(Compression::Lz4));
+        assert_eq!(column.mode().to_string(), "MAIN");
+        let default = ColumnStorage::new("id".to_owned(), "p", "");
+        assert!(!default.mode().is_toastable());
+        assert_eq!(default.compression(), None);
+    }
+}
@@ -2,8 +2,12 @@
 
 use std::sync::Arc;
 
-use crate::models::{
-    CheckConstraint, Column, KeyColumnUsage, PgDescription, PgIndex, PgPolicyTable, Triggers,
+use crate::{
+    identity::{ProcOid, RelOid},
+    model_metadata::{ForeignTableMetadata, RuleMetadata, StorageMetadata},
+    models::{
+        CheckConstraint, Column, KeyColumnUsage, PgDescription, PgIndex, PgPolicyTable, Triggers,
+    },
 };
 
 #[derive(Clone, Debug)]
@@ -16,47 +20,93 @@ use crate::models::{
 /// - Unique indexes (including primary key)
 /// - Foreign keys referencing other tables
 /// - Table description from `pg_catalog.pg_description`
+/// - Owner of the table from `pg_catalog.pg_class`
 /// - Row Security Policies
+/// - Foreign server, wrapper and options of foreign tables
+/// - Rewrite rules, other than the `_RETURN` rule of views
+/// - Physical storage, when requested from the builder
 ///
 /// This metadata is constructed during
 /// [`PgDieselDatabase`](crate::database::PgDieselDatabase) building and cached for
 /// efficient access via the `TableLike` trait.
 pub struct TableMetadata {
+    /// The OID of the table in `pg_class`.
+    rel_oid: RelOid,
+    /// The OID of the role owning the table, as in `pg_class.relowner`.
+    owner: u32,
     /// The underlying table metadata.
     metadata: sql_traits::structs::TableMetadata<crate::models::Table>,
     /// The description of the table, if any.
     description: Option<PgDescription>,
     /// The triggers defined on the table, along with the OID of the function they call.
-    triggers: Vec<(Arc<Triggers>, Option<u32>)>,
+    triggers: Vec<(Arc<Triggers>, Option<ProcOid>)>,
     /// The policies defined on the table.
     policies: Vec<Arc<PgPolicyTable>>,
     /// Whether the table has row-level security enabled.
     row_security: bool,
     /// Whether the table has row-level security forced.
     forced_row_security: bool,
+    /// The foreign server, wrapper and options of the table, if it is a
+    /// foreign table.
+    foreign_table: Option<ForeignTableMetadata>,
+    /// The rewrite rules of the table, other than the `_RETURN` rule of
+    /// views, sorted by name.
+    rules: Vec<RuleMetadata>,
+    /// The physical storage of the table, if loaded.
+    storage: Option<StorageMetadata>,
 }
 
 impl TableMetadata {
     /// Creates a new `TableMetadata` instance.
     #[must_use]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
+        rel_oid: RelOid,
+        owner: u32,
         metadata: sql_traits::structs::TableMetadata<crate::models::Table>,
         description: Option<PgDescription>,
-        triggers: Vec<(Arc<Triggers>, Option<u32>)>,
+        triggers: Vec<(Arc<Triggers>, Option<ProcOid>)>,
         policies: Vec<Arc<PgPolicyTable>>,
         row_security: bool,
         forced_row_security: bool,
+        foreign_table: Option<ForeignTableMetadata>,
+        rules: Vec<RuleMetadata>,
     ) -> Self {
         Self {
+            rel_oid,
+            owner,
             metadata,
             description,
             triggers,
             policies,
             row_security,
             forced_row_security,
+            foreign_table,
+            rules,
+            storage: None,
         }
     }
 
+    /// Attaches the physical storage of the table.
+    #[must_use]
+    pub fn with_storage(mut self, storage: StorageMetadata) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Returns the OID of the table in `pg_class`.
+    #[must_use]
+    pub fn rel_oid(&self) -> RelOid {
+        self.rel_oid
+    }
+
+    /// Returns the OID of the role owning the table, as in
+    /// `pg_class.relowner`.
+    #[must_use]
+    pub fn owner(&self) -> u32 {
+        self.owner
+    }
+
     /// Returns an iterator over the references of columns of the table.
     pub fn columns(&self) -> impl Iterator<Item = &Column> {
         self.metadata.columns()
@@ -126,7 +176,7 @@ impl TableMetadata {
     }
 
     /// Returns an iterator over the triggers of the table.
-    pub fn triggers(&self) -> impl Iterator<Item = &(Arc<Triggers>, Option<u32>)> {
+    pub fn triggers(&self) -> impl Iterator<Item = &(Arc<Triggers>, Option<ProcOid>)> {
         self.triggers.iter()
     }
 
@@ -146,4 +196,30 @@ impl TableMetadata {
     pub fn forced_row_security(&self) -> bool {
         self.forced_row_security
     }
+
+    /// Returns the foreign server, wrapper and options of the table, if it is
+    /// a foreign table.
+    #[must_use]
+    pub fn foreign_table(&self) -> Option<&ForeignTableMetadata> {
+        self.foreign_table.as_ref()
+    }
+
+    /// Returns whether the table is a foreign table.
+    #[must_use]
+    pub fn is_foreign(&self) -> bool {
+        self.foreign_table.is_some()
+    }
+
+    /// Returns an iterator over the rewrite rules of the table, other than
+    /// the `_RETURN` rule of views, sorted by name.
+    pub fn rules(&self) -> impl Iterator<Item = &RuleMetadata> {
+        self.rules.iter()
+    }
+
+    /// Returns the physical storage of the table, if it was loaded with
+    /// [`PgDieselDatabaseBuilder::load_storage`](crate::database::PgDieselDatabaseBuilder::load_storage).
+    #[must_use]
+    pub fn storage(&self) -> Option<&StorageMetadata> {
+        self.storage.as_ref()
+    }
 }
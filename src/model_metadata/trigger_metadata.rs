@@ -4,7 +4,10 @@ use std::sync::Arc;
 
 use sqlparser::ast::{TriggerEvent, TriggerObject, TriggerObjectKind, TriggerPeriod};
 
-use crate::models::{Table, Triggers};
+use crate::{
+    identity::ProcOid,
+    models::{PgProc, Table, Triggers},
+};
 
 #[derive(Clone, Debug)]
 /// Wrapper around [`Triggers`] model that holds parsed metadata.
@@ -20,13 +23,20 @@ pub struct TriggerMetadata {
     /// The orientation of the trigger.
     pub orientation: Option<TriggerObjectKind>,
     /// The OID of the function called by the trigger.
-    pub function_oid: Option<u32>,
+    pub function_oid: Option<ProcOid>,
+    /// The function called by the trigger, if it was loaded.
+    pub function: Option<Arc<PgProc>>,
 }
 
 impl TriggerMetadata {
     /// Creates a new `TriggerMetadata` instance.
     #[must_use]
-    pub fn new(model: Triggers, table: Arc<Table>, function_oid: Option<u32>) -> Self {
+    pub fn new(
+        model: Triggers,
+        table: Arc<Table>,
+        function_oid: Option<ProcOid>,
+        function: Option<Arc<PgProc>>,
+    ) -> Self {
         let events = parse_events(&model);
         let timing = parse_timing(&model);
         let orientation = parse_orientation(&model);
@@ -38,6 +48,7 @@ impl TriggerMetadata {
             timing,
             orientation,
             function_oid,
+            function,
         }
     }
 
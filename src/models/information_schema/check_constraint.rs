@@ -1,6 +1,7 @@
 //! Check constraint model.
 
 use std::{
+    collections::HashMap,
     fmt::{Debug, Display},
     sync::Arc,
 };
@@ -9,13 +10,13 @@ use diesel::{
     BoolExpressionMethods, ExpressionMethods, JoinOnDsl, OptionalExtension, PgConnection, QueryDsl,
     Queryable, QueryableByName, Selectable, SelectableHelper,
 };
-use sql_traits::{
-    structs::metadata::CheckMetadata, traits::FunctionLike, utils::columns_in_expression,
-};
+use sql_traits::{structs::metadata::CheckMetadata, utils::columns_in_expression};
 
 use crate::{
+    identity::ProcOid,
     model_metadata::TableMetadata,
     models::{PgConstraint, PgOperator, PgProc, Table, TableConstraint},
+    traits::HasProcOid,
 };
 
 mod cached_queries;
@@ -154,6 +155,9 @@ impl CheckConstraint {
     ///
     /// * `table` - The table this check constraint belongs to
     /// * `table_metadata` - The metadata of the table
+    /// * `functions` - The functions loaded in the database by OID, which
+    ///   must include the functions used in the check clause for them to be
+    ///   associated with the constraint
     /// * `conn` - A mutable reference to a `PgConnection`
     ///
     /// # Errors
@@ -168,7 +172,7 @@ impl CheckConstraint {
         &self,
         table: Arc<Table>,
         table_metadata: &TableMetadata,
-        functions: &[Arc<PgProc>],
+        functions: &HashMap<ProcOid, Arc<PgProc>>,
         conn: &mut PgConnection,
     ) -> Result<CheckMetadata<CheckConstraint>, diesel::result::Error> {
         use sqlparser::parser::Parser;
@@ -191,12 +195,7 @@ impl CheckConstraint {
             columns,
             self.functions(conn)?
                 .into_iter()
-                .filter_map(|func| {
-                    functions
-                        .iter()
-                        .find(|table_func| table_func.name() == func.proname.as_str())
-                        .cloned()
-                })
+                .filter_map(|func| functions.get(&func.proc_oid()).cloned())
                 .collect(),
         ))
     }
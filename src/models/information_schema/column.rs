@@ -8,7 +8,7 @@ mod cached_queries;
 
 use super::check_constraint::CheckConstraint;
 use crate::{
-    model_metadata::ColumnMetadata,
+    model_metadata::{CollationMetadata, ColumnMetadata, ColumnStatistics},
     models::{GeographyColumn, GeometryColumn, KeyColumnUsage, PgType, Table},
 };
 
@@ -132,6 +132,8 @@ impl Column {
     /// # Arguments
     ///
     /// * `table` - The table the column belongs to.
+    /// * `collation` - The collation of the column, as loaded for the whole
+    ///   table by [`Table::column_collations`].
     /// * `conn` - A mutable reference to a `PgConnection`
     ///
     /// # Errors
@@ -140,15 +142,47 @@ impl Column {
     pub fn metadata(
         &self,
         table: Arc<Table>,
+        collation: Option<CollationMetadata>,
         conn: &mut PgConnection,
     ) -> Result<ColumnMetadata, diesel::result::Error> {
         Ok(ColumnMetadata::new(
             table,
             cached_queries::pg_description(self, conn).optional()?,
             self.pg_type(conn)?,
+            collation,
         ))
     }
 
+    /// Returns the planner statistics of the column, decoded according to the
+    /// type of the column, or `None` if the table was never analyzed.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn statistics(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<Option<ColumnStatistics>, diesel::result::Error> {
+        let Some(row) = cached_queries::statistics(self, conn)? else {
+            return Ok(None);
+        };
+        let pg_type = self.pg_type(conn)?;
+        let element_type = if pg_type.typelem == 0 {
+            None
+        } else {
+            Some(PgType::from_oid(pg_type.typelem, conn)?)
+        };
+        Ok(Some(ColumnStatistics::from_text(
+            row,
+            &pg_type,
+            element_type.as_ref(),
+        )))
+    }
+
     #[must_use]
     /// Returns the column as a nullable column
     pub fn into_nullable(self) -> Self {
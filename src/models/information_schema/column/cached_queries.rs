@@ -5,9 +5,12 @@ use diesel::{
     RunQueryDsl, SelectableHelper,
 };
 
-use crate::models::{
-    CheckConstraint, Column, GeographyColumn, GeometryColumn, KeyColumnUsage, PgDescription,
-    PgType, Table,
+use crate::{
+    model_metadata::PgStatText,
+    models::{
+        CheckConstraint, Column, GeographyColumn, GeometryColumn, KeyColumnUsage, PgDescription,
+        PgType, Table,
+    },
 };
 
 /// Returns the foreign keys that reference this column.
@@ -184,3 +187,47 @@ pub(super) fn pg_description(
         .select(PgDescription::as_select())
         .first::<PgDescription>(conn)
 }
+
+/// Returns the `pg_stats` row of this column, with the `anyarray` columns cast
+/// to text. When statistics were collected both with and without the child
+/// tables, the statistics of the table alone are returned.
+pub(super) fn statistics(
+    column: &Column,
+    conn: &mut PgConnection,
+) -> Result<Option<PgStatText>, diesel::result::Error> {
+    use diesel::sql_types::Text;
+
+    #[cfg(any(feature = "postgres-17", feature = "postgres-18"))]
+    const RANGE_COLUMNS: &str = ",
+            s.range_length_histogram::text AS range_length_histogram,
+            s.range_empty_frac,
+            s.range_bounds_histogram::text AS range_bounds_histogram";
+    #[cfg(not(any(feature = "postgres-17", feature = "postgres-18")))]
+    const RANGE_COLUMNS: &str = "";
+
+    diesel::sql_query(format!(
+        r"
+        SELECT
+            s.inherited,
+            s.null_frac,
+            s.avg_width,
+            s.n_distinct,
+            s.most_common_vals::text AS most_common_vals,
+            s.most_common_freqs,
+            s.histogram_bounds::text AS histogram_bounds,
+            s.correlation,
+            s.most_common_elems::text AS most_common_elems,
+            s.most_common_elem_freqs,
+            s.elem_count_histogram{RANGE_COLUMNS}
+        FROM pg_catalog.pg_stats s
+        WHERE s.schemaname = $1 AND s.tablename = $2 AND s.attname = $3
+        ORDER BY s.inherited
+        LIMIT 1
+        "
+    ))
+    .bind::<Text, _>(&column.table_schema)
+    .bind::<Text, _>(&column.table_name)
+    .bind::<Text, _>(&column.column_name)
+    .get_result::<PgStatText>(conn)
+    .optional()
+}
@@ -1,11 +1,11 @@
 //! Table model and related cached queries.
 
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display};
 
 use diesel::{OptionalExtension, PgConnection, Queryable, QueryableByName, Selectable};
 
 use crate::{
-    model_metadata::TableMetadata,
+    model_metadata::{CollationMetadata, TableMetadata},
     models::{CheckConstraint, Column, PgIndex, Triggers},
 };
 
@@ -89,7 +89,9 @@ impl Table {
             primary_key_columns.extend(
                 sql_metadata
                     .column_arcs()
-                    .filter(|col: &&std::sync::Arc<Column>| col.as_ref() == &pk_column)
+                    .filter(|col: &&std::sync::Arc<Column>| {
+                        col.column_name == pk_column.column_name
+                    })
                     .cloned(),
             );
         }
@@ -105,20 +107,40 @@ impl Table {
             .map(std::sync::Arc::new)
             .collect();
 
-        let (row_security, forced_row_security) = cached_queries::pg_class(self, conn)?;
+        let (rel_oid, owner, row_security, forced_row_security) =
+            cached_queries::pg_class(self, conn)?;
+
+        let rules = cached_queries::rules(rel_oid, conn)?;
+
+        let foreign_table = if self.is_foreign() {
+            Some(cached_queries::foreign_table(rel_oid, conn)?)
+        } else {
+            None
+        };
 
         let metadata = TableMetadata::new(
+            rel_oid,
+            owner,
             sql_metadata,
             cached_queries::pg_description(self, conn).optional()?,
             triggers,
             policies,
             row_security,
             forced_row_security,
+            foreign_table,
+            rules,
         );
 
         Ok(metadata)
     }
 
+    #[must_use]
+    /// Returns whether the table is a foreign table, whose rows are held by a
+    /// foreign server.
+    pub fn is_foreign(&self) -> bool {
+        self.table_type == "FOREIGN"
+    }
+
     #[must_use]
     /// Returns whether the table is temporary.
     pub fn is_temporary(&self) -> bool {
@@ -158,6 +180,23 @@ impl Table {
         primary_key_columns(self, conn)
     }
 
+    /// Returns the collations of the collatable columns of the table, keyed
+    /// by column name.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - The database connection.
+    ///
+    /// # Errors
+    ///
+    /// * If the collations cannot be loaded from the database.
+    pub fn column_collations(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<HashMap<String, CollationMetadata>, diesel::result::Error> {
+        column_collations(self, conn)
+    }
+
     /// Returns the UNIQUE constraint indices for the table.
     ///
     /// # Arguments
@@ -1,12 +1,20 @@
 //! Submodule defining the cached queries methods used in the [`Table`] struct.
 
+use std::collections::HashMap;
+
 use diesel::{
     BoolExpressionMethods, ExpressionMethods, JoinOnDsl, NullableExpressionMethods, PgConnection,
     QueryDsl, RunQueryDsl, SelectableHelper,
 };
 
-use crate::models::{
-    CheckConstraint, Column, KeyColumnUsage, PgDescription, PgIndex, PgTrigger, Table, Triggers,
+use crate::{
+    identity::{ProcOid, RelOid},
+    model_metadata::{CollationMetadata, CollationProvider, ForeignTableMetadata, RuleMetadata},
+    models::{
+        CheckConstraint, Column, KeyColumnUsage, PgCollation, PgDescription, PgForeignDataWrapper,
+        PgForeignServer, PgForeignTable, PgIndex, PgRewrite, PgTrigger, PgUserMappings, Table,
+        Triggers,
+    },
 };
 
 /// Loads all tables from the information schema for the given catalog and schema.
@@ -283,7 +291,7 @@ pub(super) fn pg_description(
 pub(crate) fn triggers(
     table: &Table,
     conn: &mut PgConnection,
-) -> Result<Vec<(Triggers, Option<u32>)>, diesel::result::Error> {
+) -> Result<Vec<(Triggers, Option<ProcOid>)>, diesel::result::Error> {
     use crate::schema::information_schema::triggers::triggers;
     use crate::schema::pg_catalog::{
         pg_class::pg_class, pg_namespace::pg_namespace, pg_trigger::pg_trigger,
@@ -310,7 +318,7 @@ pub(crate) fn triggers(
         let oid = pg_triggers
             .iter()
             .find(|t| t.tgname == name)
-            .map(|t| t.tgfoid);
+            .map(|t| ProcOid::new(t.tgfoid));
         result.push((trig, oid));
     }
     Ok(result)
@@ -339,17 +347,226 @@ pub(crate) fn policies(
         .load(conn)
 }
 
-/// Returns the Row Level Security settings for the table.
+/// Returns the OID, the OID of the owner and the Row Level Security settings
+/// of the table.
 pub(crate) fn pg_class(
     table: &Table,
     conn: &mut PgConnection,
-) -> Result<(bool, bool), diesel::result::Error> {
+) -> Result<(RelOid, u32, bool, bool), diesel::result::Error> {
     use crate::schema::pg_catalog::{pg_class::pg_class, pg_namespace::pg_namespace};
 
     pg_class::table
         .inner_join(pg_namespace::table.on(pg_class::relnamespace.eq(pg_namespace::oid)))
         .filter(pg_class::relname.eq(&table.table_name))
         .filter(pg_namespace::nspname.eq(&table.table_schema))
-        .select((pg_class::relrowsecurity, pg_class::relforcerowsecurity))
-        .first(conn)
+        .select((
+            pg_class::oid,
+            pg_class::relowner,
+            pg_class::relrowsecurity,
+            pg_class::relforcerowsecurity,
+        ))
+        .first::<(u32, u32, bool, bool)>(conn)
+        .map(|(oid, owner, row_security, forced_row_security)| {
+            (RelOid::new(oid), owner, row_security, forced_row_security)
+        })
+}
+
+#[derive(diesel::QueryableByName)]
+/// A rewrite rule along with its definition.
+struct RuleRow {
+    /// The rule.
+    #[diesel(embed)]
+    rule: PgRewrite,
+    /// The definition of the rule, as returned by `pg_get_ruledef`.
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    definition: String,
+}
+
+/// Returns the rewrite rules of the table with the provided OID, other than
+/// the `_RETURN` rule of views, sorted by name.
+pub(super) fn rules(
+    rel_oid: RelOid,
+    conn: &mut PgConnection,
+) -> Result<Vec<RuleMetadata>, diesel::result::Error> {
+    Ok(diesel::sql_query(
+        r"
+        SELECT
+            r.oid,
+            r.rulename::text AS rulename,
+            r.ev_class,
+            r.ev_type::text AS ev_type,
+            r.ev_enabled::text AS ev_enabled,
+            r.is_instead,
+            r.ev_qual::text AS ev_qual,
+            r.ev_action::text AS ev_action,
+            pg_get_ruledef(r.oid, true) AS definition
+        FROM pg_rewrite r
+        WHERE r.ev_class = $1 AND r.rulename <> '_RETURN'
+        ORDER BY r.rulename
+        ",
+    )
+    .bind::<diesel::sql_types::Oid, _>(rel_oid.get())
+    .load::<RuleRow>(conn)?
+    .into_iter()
+    .map(|row| RuleMetadata::new(row.rule, row.definition))
+    .collect())
+}
+
+/// Returns the foreign server, wrapper, options and user mappings of the
+/// foreign table.
+pub(super) fn foreign_table(
+    rel_oid: RelOid,
+    conn: &mut PgConnection,
+) -> Result<ForeignTableMetadata, diesel::result::Error> {
+    use crate::schema::pg_catalog::{
+        pg_attribute::pg_attribute, pg_foreign_data_wrapper::pg_foreign_data_wrapper,
+        pg_foreign_server::pg_foreign_server, pg_foreign_table::pg_foreign_table,
+        pg_user_mappings::pg_user_mappings,
+    };
+
+    let foreign_table = pg_foreign_table::table
+        .filter(pg_foreign_table::ftrelid.eq(rel_oid.get()))
+        .select(PgForeignTable::as_select())
+        .first::<PgForeignTable>(conn)?;
+
+    let server = pg_foreign_server::table
+        .filter(pg_foreign_server::oid.eq(foreign_table.ftserver))
+        .select(PgForeignServer::as_select())
+        .first::<PgForeignServer>(conn)?;
+
+    let wrapper = pg_foreign_data_wrapper::table
+        .filter(pg_foreign_data_wrapper::oid.eq(server.srvfdw))
+        .select(PgForeignDataWrapper::as_select())
+        .first::<PgForeignDataWrapper>(conn)?;
+
+    let column_options = pg_attribute::table
+        .filter(pg_attribute::attrelid.eq(rel_oid.get()))
+        .filter(pg_attribute::attnum.gt(0))
+        .filter(pg_attribute::attisdropped.eq(false))
+        .order_by(pg_attribute::attnum)
+        .select((pg_attribute::attname, pg_attribute::attfdwoptions))
+        .load::<(String, Option<Vec<String>>)>(conn)?;
+
+    let user_mappings = pg_user_mappings::table
+        .filter(pg_user_mappings::srvid.eq(server.oid))
+        .select(PgUserMappings::as_select())
+        .load::<PgUserMappings>(conn)?;
+
+    Ok(ForeignTableMetadata::new(
+        foreign_table,
+        server,
+        wrapper,
+        column_options,
+        &user_mappings,
+    ))
+}
+
+#[derive(diesel::QueryableByName)]
+/// A collation of a column along with its schema and its provider and locale
+/// resolved against the database.
+struct ColumnCollationRow {
+    /// The name of the column.
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    column_name: String,
+    /// The collation.
+    #[diesel(embed)]
+    collation: PgCollation,
+    /// The schema of the collation.
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    schema_name: String,
+    /// The provider of the collation, or of the database for the `default`
+    /// collation.
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    provider: String,
+    /// The locale of the collation, or of the database for the `default`
+    /// collation.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    locale: Option<String>,
+}
+
+/// Returns the collations of the collatable columns of this table, keyed by
+/// column name.
+pub(crate) fn column_collations(
+    table: &Table,
+    conn: &mut PgConnection,
+) -> Result<HashMap<String, CollationMetadata>, diesel::result::Error> {
+    use diesel::sql_types::Text;
+
+    // The locale moved from `colliculocale` and `daticulocale`, which only
+    // hold the ICU locale, to `colllocale` and `datlocale` in `PostgreSQL` 17,
+    // while `PostgreSQL` 14 only has the libc locales.
+    #[cfg(any(feature = "postgres-17", feature = "postgres-18"))]
+    const RESOLVED_COLUMNS: &str = "
+            d.datlocprovider::text AS database_provider,
+            COALESCE(d.datlocale, d.datcollate::text) AS database_locale,
+            COALESCE(c.colllocale, c.collcollate::text) AS collation_locale";
+    #[cfg(any(feature = "postgres-15", feature = "postgres-16"))]
+    const RESOLVED_COLUMNS: &str = "
+            d.datlocprovider::text AS database_provider,
+            COALESCE(d.daticulocale, d.datcollate::text) AS database_locale,
+            COALESCE(c.colliculocale, c.collcollate::text) AS collation_locale";
+    #[cfg(feature = "postgres-14")]
+    const RESOLVED_COLUMNS: &str = "
+            'c' AS database_provider,
+            d.datcollate::text AS database_locale,
+            c.collcollate::text AS collation_locale";
+
+    let version_columns: &[&str] = &[
+        #[cfg(any(feature = "postgres-15", feature = "postgres-16"))]
+        ", c.colliculocale",
+        #[cfg(any(feature = "postgres-17", feature = "postgres-18"))]
+        ", c.colllocale",
+        #[cfg(not(any(feature = "postgres-14", feature = "postgres-15")))]
+        ", c.collicurules",
+    ];
+    let version_columns = version_columns.concat();
+
+    let rows = diesel::sql_query(format!(
+        r"
+        WITH resolved AS (
+            SELECT
+                a.attname::text AS column_name,
+                c.oid,
+                c.collname::text AS collname,
+                c.collnamespace,
+                c.collowner,
+                c.collprovider::text AS collprovider,
+                c.collisdeterministic,
+                c.collencoding,
+                c.collcollate::text AS collcollate,
+                c.collctype::text AS collctype{version_columns},
+                c.collversion,
+                n.nspname::text AS schema_name,{RESOLVED_COLUMNS}
+            FROM pg_catalog.pg_attribute a
+            JOIN pg_catalog.pg_class t ON t.oid = a.attrelid
+            JOIN pg_catalog.pg_namespace tn ON tn.oid = t.relnamespace
+            JOIN pg_catalog.pg_collation c ON c.oid = a.attcollation
+            JOIN pg_catalog.pg_namespace n ON n.oid = c.collnamespace
+            JOIN pg_catalog.pg_database d ON d.datname = current_database()
+            WHERE tn.nspname = $1 AND t.relname = $2 AND a.attnum > 0 AND NOT a.attisdropped
+        )
+        SELECT
+            *,
+            CASE WHEN collprovider = 'd' THEN database_provider ELSE collprovider END
+                AS provider,
+            CASE WHEN collprovider = 'd' THEN database_locale ELSE collation_locale END
+                AS locale
+        FROM resolved
+        "
+    ))
+    .bind::<Text, _>(&table.table_schema)
+    .bind::<Text, _>(&table.table_name)
+    .load::<ColumnCollationRow>(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let provider =
+                CollationProvider::from_code(&row.provider).unwrap_or(CollationProvider::Default);
+            (
+                row.column_name,
+                CollationMetadata::new(row.collation, row.schema_name, provider, row.locale),
+            )
+        })
+        .collect())
 }
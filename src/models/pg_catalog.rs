@@ -72,6 +72,7 @@ mod pg_largeobject;
 mod pg_largeobject_metadata;
 mod pg_lock;
 mod pg_matview;
+mod pg_namespace;
 mod pg_opclass;
 mod pg_operator;
 mod pg_opfamily;
@@ -214,8 +215,9 @@ pub use pg_init_priv::PgInitPriv;
 pub use pg_language::PgLanguage;
 pub use pg_largeobject::PgLargeobject;
 pub use pg_largeobject_metadata::PgLargeobjectMetadatum;
-pub use pg_lock::PgLock;
+pub use pg_lock::{LockMode, PgLock};
 pub use pg_matview::PgMatview;
+pub use pg_namespace::PgNamespace;
 pub use pg_opclass::PgOpclass;
 pub use pg_operator::PgOperator;
 pub use pg_opfamily::PgOpfamily;
@@ -269,7 +271,7 @@ pub use pg_stat_replication::PgStatReplication;
 pub use pg_stat_replication_slot::PgStatReplicationSlot;
 pub use pg_stat_slru::PgStatSlru;
 pub use pg_stat_ssl::PgStatSsl;
-pub use pg_stat_statements::PgStatStatement;
+pub use pg_stat_statements::{PgStatStatement, StatementRanking};
 pub use pg_stat_subscription::PgStatSubscription;
 pub use pg_stat_subscription_stat::PgStatSubscriptionStat;
 pub use pg_stat_sys_index::PgStatSysIndex;
@@ -3,6 +3,8 @@
 //! This module provides the `PgAvailableExtensionVersions` struct for working
 //! with the `pg_catalog.pg_available_extension_versions` system view.
 
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+
 /// Represents a row from the `pg_catalog.pg_available_extension_versions` view.
 ///
 /// Shows all extension versions that are available for installation.
@@ -32,3 +34,41 @@ pub struct PgAvailableExtensionVersions {
     /// Comment describing the extension.
     pub comment: Option<String>,
 }
+
+impl PgAvailableExtensionVersions {
+    /// Loads the versions of every extension available on the server, sorted
+    /// by extension name.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_available_extension_versions::pg_available_extension_versions;
+        pg_available_extension_versions::table
+            .select(Self::as_select())
+            .order_by(pg_available_extension_versions::name)
+            .load(conn)
+    }
+
+    /// Loads the available versions of the extension with the provided name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the extension
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load(name: &str, conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_available_extension_versions::pg_available_extension_versions;
+        pg_available_extension_versions::table
+            .filter(pg_available_extension_versions::name.eq(name))
+            .select(Self::as_select())
+            .load(conn)
+    }
+}
@@ -3,6 +3,8 @@
 //! This module provides the `PgAvailableExtensions` struct for working with the
 //! `pg_catalog.pg_available_extensions` system view.
 
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+
 /// Represents a row from the `pg_catalog.pg_available_extensions` view.
 ///
 /// Shows extensions that are available for installation.
@@ -22,3 +24,41 @@ pub struct PgAvailableExtensions {
     /// Comment describing the extension.
     pub comment: Option<String>,
 }
+
+impl PgAvailableExtensions {
+    /// Loads the extensions available on the server, sorted by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_available_extensions::pg_available_extensions;
+        pg_available_extensions::table
+            .select(Self::as_select())
+            .order_by(pg_available_extensions::name)
+            .load(conn)
+    }
+
+    /// Loads the available extension with the provided name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the extension
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database, including if the
+    ///   extension is not available
+    pub fn load(name: &str, conn: &mut PgConnection) -> Result<Self, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_available_extensions::pg_available_extensions;
+        pg_available_extensions::table
+            .filter(pg_available_extensions::name.eq(name))
+            .select(Self::as_select())
+            .first(conn)
+    }
+}
@@ -1,7 +1,9 @@
 //! Submodule providing the `PgEventTrigger` struct representing a row of the
 //! `pg_event_trigger` table in `PostgreSQL`.
 
-use diesel::{Queryable, QueryableByName, Selectable};
+use diesel::{
+    PgConnection, QueryDsl, Queryable, QueryableByName, RunQueryDsl, Selectable, SelectableHelper,
+};
 
 /// Represents a row from the `pg_event_trigger` table.
 ///
@@ -29,3 +31,23 @@ pub struct PgEventTrigger {
     /// Command tags for which this trigger fires.
     pub evttags: Option<Vec<String>>,
 }
+
+impl PgEventTrigger {
+    /// Loads all of the [`PgEventTrigger`]s from the database, sorted by
+    /// name.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_event_trigger::pg_event_trigger;
+        pg_event_trigger::table
+            .order_by(pg_event_trigger::evtname)
+            .select(Self::as_select())
+            .load(conn)
+    }
+}
@@ -1,7 +1,9 @@
 //! Submodule providing the `PgFileSetting` struct representing a row of the
 //! `pg_file_settings` view in `PostgreSQL`.
 
-use diesel::{Queryable, QueryableByName, Selectable};
+use diesel::{
+    PgConnection, QueryDsl, Queryable, QueryableByName, RunQueryDsl, Selectable, SelectableHelper,
+};
 
 /// Represents a row from the `pg_file_settings` view.
 ///
@@ -29,3 +31,23 @@ pub struct PgFileSetting {
     /// Error message if the setting failed to apply.
     pub error: Option<String>,
 }
+
+impl PgFileSetting {
+    /// Loads all the rows of the `pg_file_settings` view, in the order the
+    /// configuration files are processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_file_settings::pg_file_settings;
+        pg_file_settings::table
+            .select(Self::as_select())
+            .order_by(pg_file_settings::seqno)
+            .load(conn)
+    }
+}
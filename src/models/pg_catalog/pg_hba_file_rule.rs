@@ -1,7 +1,9 @@
 //! Submodule providing the `PgHbaFileRule` struct representing a row of the
 //! `pg_hba_file_rules` view in `PostgreSQL`.
 
-use diesel::{Queryable, QueryableByName, Selectable};
+use diesel::{
+    PgConnection, QueryDsl, Queryable, QueryableByName, RunQueryDsl, Selectable, SelectableHelper,
+};
 
 /// Represents a row from the `pg_hba_file_rules` view.
 ///
@@ -70,3 +72,22 @@ pub struct PgHbaFileRule {
     /// Error message if the rule is invalid.
     pub error: Option<String>,
 }
+
+impl PgHbaFileRule {
+    /// Loads all the rows of the `pg_hba_file_rules` view, in the order the
+    /// rules appear in `pg_hba.conf` and its included files.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_hba_file_rules::pg_hba_file_rules;
+        pg_hba_file_rules::table
+            .select(Self::as_select())
+            .load(conn)
+    }
+}
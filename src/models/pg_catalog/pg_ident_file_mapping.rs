@@ -1,7 +1,9 @@
 //! Submodule providing the `PgIdentFileMapping` struct representing a row of
 //! the `pg_ident_file_mappings` view in `PostgreSQL`.
 
-use diesel::{Queryable, QueryableByName, Selectable};
+use diesel::{
+    PgConnection, QueryDsl, Queryable, QueryableByName, RunQueryDsl, Selectable, SelectableHelper,
+};
 
 /// Represents a row from the `pg_ident_file_mappings` view.
 ///
@@ -54,3 +56,22 @@ pub struct PgIdentFileMapping {
     /// Error message if the mapping is invalid.
     pub error: Option<String>,
 }
+
+impl PgIdentFileMapping {
+    /// Loads all the rows of the `pg_ident_file_mappings` view, in the order
+    /// the mappings appear in `pg_ident.conf` and its included files.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_ident_file_mappings::pg_ident_file_mappings;
+        pg_ident_file_mappings::table
+            .select(Self::as_select())
+            .load(conn)
+    }
+}
@@ -1,7 +1,11 @@
 //! Submodule providing the `PgLock` struct representing a row of the
 //! `pg_locks` view in `PostgreSQL`.
 
-use diesel::{Queryable, QueryableByName, Selectable};
+use std::fmt::Display;
+
+use diesel::{
+    PgConnection, QueryDsl, Queryable, QueryableByName, RunQueryDsl, Selectable, SelectableHelper,
+};
 
 /// Represents a row from the `pg_locks` view.
 ///
@@ -50,3 +54,166 @@ pub struct PgLock {
     /// Time when waiting for the lock started.
     pub waitstart: Option<std::time::SystemTime>,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A table-level lock mode, as reported in the `mode` column of `pg_locks`.
+///
+/// The same modes are used for the locks on transaction IDs, objects and
+/// advisory locks. Variants are ordered from the weakest to the strongest.
+///
+/// For more information, see the [PostgreSQL documentation](https://www.postgresql.org/docs/current/explicit-locking.html#LOCKING-TABLES).
+pub enum LockMode {
+    /// `ACCESS SHARE`, acquired by `SELECT`.
+    AccessShare,
+    /// `ROW SHARE`, acquired by `SELECT FOR UPDATE` and `FOR SHARE`.
+    RowShare,
+    /// `ROW EXCLUSIVE`, acquired by `INSERT`, `UPDATE` and `DELETE`.
+    RowExclusive,
+    /// `SHARE UPDATE EXCLUSIVE`, acquired by `VACUUM` and `ANALYZE`.
+    ShareUpdateExclusive,
+    /// `SHARE`, acquired by `CREATE INDEX`.
+    Share,
+    /// `SHARE ROW EXCLUSIVE`, acquired by `CREATE TRIGGER`.
+    ShareRowExclusive,
+    /// `EXCLUSIVE`, acquired by `REFRESH MATERIALIZED VIEW CONCURRENTLY`.
+    Exclusive,
+    /// `ACCESS EXCLUSIVE`, acquired by most `ALTER TABLE` and `DROP`.
+    AccessExclusive,
+}
+
+impl LockMode {
+    /// Returns the lock mode with the provided name, as in `RowExclusiveLock`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "AccessShareLock" => Self::AccessShare,
+            "RowShareLock" => Self::RowShare,
+            "RowExclusiveLock" => Self::RowExclusive,
+            "ShareUpdateExclusiveLock" => Self::ShareUpdateExclusive,
+            "ShareLock" => Self::Share,
+            "ShareRowExclusiveLock" => Self::ShareRowExclusive,
+            "ExclusiveLock" => Self::Exclusive,
+            "AccessExclusiveLock" => Self::AccessExclusive,
+            _ => return None,
+        })
+    }
+
+    /// Returns the name of the lock mode, as in `RowExclusiveLock`.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::AccessShare => "AccessShareLock",
+            Self::RowShare => "RowShareLock",
+            Self::RowExclusive => "RowExclusiveLock",
+            Self::ShareUpdateExclusive => "ShareUpdateExclusiveLock",
+            Self::Share => "ShareLock",
+            Self::ShareRowExclusive => "ShareRowExclusiveLock",
+            Self::Exclusive => "ExclusiveLock",
+            Self::AccessExclusive => "AccessExclusiveLock",
+        }
+    }
+
+    /// Returns whether the two lock modes conflict, according to the
+    /// conflict table of `PostgreSQL`.
+    #[must_use]
+    pub fn conflicts_with(self, other: Self) -> bool {
+        let (weaker, stronger) = if self <= other {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        match weaker {
+            Self::AccessShare => stronger == Self::AccessExclusive,
+            Self::RowShare => stronger >= Self::Exclusive,
+            Self::RowExclusive => stronger >= Self::Share,
+            Self::ShareUpdateExclusive => stronger >= Self::ShareUpdateExclusive,
+            Self::Share => stronger >= Self::ShareRowExclusive,
+            Self::ShareRowExclusive | Self::Exclusive | Self::AccessExclusive => true,
+        }
+    }
+}
+
+impl Display for LockMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl PgLock {
+    /// Loads all the rows of the `pg_locks` view.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_locks::pg_locks;
+        pg_locks::table.select(Self::as_select()).load(conn)
+    }
+
+    #[must_use]
+    /// Returns whether the lock is held, rather than awaited.
+    pub fn is_granted(&self) -> bool {
+        self.granted == Some(true)
+    }
+
+    #[must_use]
+    /// Returns the mode of the lock, unless it is a predicate lock
+    /// (`SIReadLock`), which never blocks.
+    pub fn lock_mode(&self) -> Option<LockMode> {
+        self.mode.as_deref().and_then(LockMode::from_name)
+    }
+
+    #[must_use]
+    /// Returns whether the two locks are on the same lockable object.
+    pub fn same_object(&self, other: &Self) -> bool {
+        self.locktype == other.locktype
+            && self.database == other.database
+            && self.relation == other.relation
+            && self.page == other.page
+            && self.tuple == other.tuple
+            && self.virtualxid == other.virtualxid
+            && self.transactionid == other.transactionid
+            && self.classid == other.classid
+            && self.objid == other.objid
+            && self.objsubid == other.objsubid
+    }
+
+    #[must_use]
+    /// Returns whether the two locks, held or awaited by different processes,
+    /// are on the same object with conflicting modes.
+    pub fn conflicts_with(&self, other: &Self) -> bool {
+        self.pid != other.pid
+            && self.same_object(other)
+            && match (self.lock_mode(), other.lock_mode()) {
+                (Some(mode), Some(other_mode)) => mode.conflicts_with(other_mode),
+                _ => false,
+            }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_mode_conflicts() {
+        use LockMode::{AccessExclusive, AccessShare, RowExclusive, Share, ShareUpdateExclusive};
+        assert!(AccessShare.conflicts_with(AccessExclusive));
+        assert!(AccessExclusive.conflicts_with(AccessShare));
+        assert!(!AccessShare.conflicts_with(RowExclusive));
+        assert!(!RowExclusive.conflicts_with(RowExclusive));
+        assert!(RowExclusive.conflicts_with(Share));
+        assert!(!Share.conflicts_with(Share));
+        assert!(ShareUpdateExclusive.conflicts_with(ShareUpdateExclusive));
+        assert_eq!(
+            LockMode::from_name("ExclusiveLock"),
+            Some(LockMode::Exclusive)
+        );
+        assert_eq!(LockMode::from_name("SIReadLock"), None);
+    }
+}
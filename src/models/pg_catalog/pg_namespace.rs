@@ -0,0 +1,25 @@
+//! Submodule providing the `PgNamespace` struct representing a row of the
+//! `pg_namespace` table in `PostgreSQL`.
+
+use diesel::{Queryable, QueryableByName, Selectable};
+
+/// Represents a row from the `pg_namespace` table.
+///
+/// The `pg_namespace` system catalog stores namespaces. A namespace is the
+/// structure underlying SQL schemas: each namespace can have a separate
+/// collection of relations, types, etc. without name conflicts.
+///
+/// For more information, see the [PostgreSQL documentation](https://www.postgresql.org/docs/current/catalog-pg-namespace.html).
+#[derive(Queryable, QueryableByName, Selectable, Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[diesel(table_name = crate::schema::pg_catalog::pg_namespace::pg_namespace)]
+pub struct PgNamespace {
+    /// OID of the namespace.
+    pub oid: u32,
+    /// Name of the namespace.
+    pub nspname: String,
+    /// OID of the role that owns the namespace.
+    pub nspowner: u32,
+    /// Access privileges.
+    pub nspacl: Option<Vec<String>>,
+}
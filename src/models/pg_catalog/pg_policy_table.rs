@@ -35,3 +35,84 @@ pub struct PgPolicyTable {
     /// WITH CHECK clause expression.
     pub polwithcheck: Option<String>,
 }
+
+impl PgPolicyTable {
+    /// Returns the OIDs of the functions called in the USING expression.
+    ///
+    /// The OIDs are extracted from the `FUNCEXPR` nodes of the stored
+    /// expression tree, in order of first appearance.
+    #[must_use]
+    pub fn using_function_oids(&self) -> Vec<u32> {
+        self.polqual
+            .as_deref()
+            .map(function_oids)
+            .unwrap_or_default()
+    }
+
+    /// Returns the OIDs of the functions called in the WITH CHECK expression.
+    ///
+    /// The OIDs are extracted from the `FUNCEXPR` nodes of the stored
+    /// expression tree, in order of first appearance.
+    #[must_use]
+    pub fn check_function_oids(&self) -> Vec<u32> {
+        self.polwithcheck
+            .as_deref()
+            .map(function_oids)
+            .unwrap_or_default()
+    }
+}
+
+/// Extracts the `:funcid` values from a `pg_node_tree` textual representation.
+fn function_oids(node_tree: &str) -> Vec<u32> {
+    let mut oids = Vec::new();
+    for (position, _) in node_tree.match_indices(":funcid ") {
+        let digits: String = node_tree[position + ":funcid ".len()..]
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        if let Ok(oid) = digits.parse::<u32>()
+            && !oids.contains(&oid)
+        {
+            oids.push(oid);
+        }
+    }
+    oids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dummy_policy(polqual: Option<&str>, polwithcheck: Option<&str>) -> PgPolicyTable {
+        PgPolicyTable {
+            oid: 1,
+            polname: "policy".to_string(),
+            polrelid: 2,
+            polcmd: "*".to_string(),
+            polpermissive: true,
+            polroles: vec![0],
+            polqual: polqual.map(str::to_string),
+            polwithcheck: polwithcheck.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_function_oids() {
+        let policy = dummy_policy(
+            Some(
+                "{OPEXPR :opno 98 :opfuncid 67 :args ({FUNCEXPR :funcid 745 :funcresulttype 19} \
+                 {FUNCEXPR :funcid 16390 :funcresulttype 25} {FUNCEXPR :funcid 745})}",
+            ),
+            None,
+        );
+        assert_eq!(policy.using_function_oids(), vec![745, 16390]);
+        assert!(policy.check_function_oids().is_empty());
+    }
+
+    #[test]
+    fn test_function_oids_without_functions() {
+        let policy = dummy_policy(None, Some("{VAR :varno 1 :varattno 2}"));
+        assert!(policy.using_function_oids().is_empty());
+        assert!(policy.check_function_oids().is_empty());
+    }
+}
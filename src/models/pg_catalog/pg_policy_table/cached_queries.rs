@@ -21,3 +21,38 @@ pub fn roles(
         .select(crate::models::PgAuthid::as_select())
         .load(conn)
 }
+
+/// The expressions of a policy, deparsed into SQL.
+#[derive(diesel::QueryableByName)]
+struct PolicyExpressions {
+    /// The deparsed USING expression.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    using_expression: Option<String>,
+    /// The deparsed WITH CHECK expression.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    check_expression: Option<String>,
+}
+
+/// Returns the USING and WITH CHECK expressions of the policy, deparsed into
+/// SQL rather than as the stored expression trees.
+///
+/// # Errors
+///
+/// Returns a `diesel::result::Error` if the query fails.
+pub fn expressions(
+    policy: &PgPolicyTable,
+    conn: &mut PgConnection,
+) -> Result<(Option<String>, Option<String>), diesel::result::Error> {
+    let expressions = diesel::sql_query(
+        r"
+        SELECT
+            pg_get_expr(p.polqual, p.polrelid) AS using_expression,
+            pg_get_expr(p.polwithcheck, p.polrelid) AS check_expression
+        FROM pg_policy p
+        WHERE p.oid = $1
+        ",
+    )
+    .bind::<diesel::sql_types::Oid, _>(policy.oid)
+    .get_result::<PolicyExpressions>(conn)?;
+    Ok((expressions.using_expression, expressions.check_expression))
+}
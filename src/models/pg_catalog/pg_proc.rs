@@ -134,6 +134,19 @@ impl PgProc {
         cached_queries::extension(self, conn)
     }
 
+    /// Returns the name of the schema that contains this function.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`.
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn schema(&self, conn: &mut PgConnection) -> Result<String, diesel::result::Error> {
+        cached_queries::schema(self, conn)
+    }
+
     /// Loads all the functions from the `pg_proc` table, excluding procedures,
     /// aggregates, non-strict functions, set-returning functions, and functions
     /// returning "void".
@@ -148,6 +161,45 @@ impl PgProc {
     pub fn load_all(conn: &mut PgConnection) -> Result<Vec<PgProc>, diesel::result::Error> {
         cached_queries::load_all(conn)
     }
+
+    /// Loads the functions defined in the provided schemas, with the names of
+    /// their schemas, applying the same filters as [`PgProc::load_all`].
+    ///
+    /// # Arguments
+    ///
+    /// * `schemas` - The names of the schemas to load the functions from.
+    /// * `exclude_extension_functions` - Whether to skip the functions that
+    ///   belong to an extension (i.e. that have an `e` dependency in
+    ///   `pg_depend`).
+    /// * `conn` - A mutable reference to a `PgConnection`.
+    ///
+    /// # Errors
+    ///
+    /// * If the database query fails.
+    pub fn load_all_in_schemas(
+        schemas: &[String],
+        exclude_extension_functions: bool,
+        conn: &mut PgConnection,
+    ) -> Result<Vec<(PgProc, String)>, diesel::result::Error> {
+        cached_queries::load_all_in_schemas(schemas, exclude_extension_functions, conn)
+    }
+
+    /// Returns the [`PgProc`] with the given OID, with the name of its schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `oid` - The OID of the function.
+    /// * `conn` - A mutable reference to a `PgConnection`.
+    ///
+    /// # Errors
+    ///
+    /// * If no function with the given OID exists.
+    pub fn from_oid(
+        oid: u32,
+        conn: &mut PgConnection,
+    ) -> Result<(PgProc, String), diesel::result::Error> {
+        cached_queries::from_oid(oid, conn)
+    }
 }
 
 // Manual implementations of Eq, Ord, PartialOrd, and Hash
@@ -1,9 +1,23 @@
 //! Submodule defining the cached queries methods used in the [`PgProc`] struct.
 
-use diesel::{ExpressionMethods, JoinOnDsl, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, JoinOnDsl, PgConnection, QueryDsl, RunQueryDsl,
+    SelectableHelper,
+};
 
 use crate::models::{PgExtension, PgProc};
 
+/// OID of the `pg_extension` system catalog, as recorded in
+/// `pg_depend.refclassid` for extension membership.
+const PG_EXTENSION_CLASS_OID: u32 = 3079;
+
+/// OID of the `pg_proc` system catalog, as recorded in `pg_depend.classid`
+/// for the dependencies of functions.
+const PG_PROC_CLASS_OID: u32 = 1255;
+
+/// OID of the `void` pseudo-type, returned by functions without a result.
+const VOID_TYPE_OID: u32 = 2278;
+
 /// Returns the extension that defines the procedure.
 pub(super) fn extension(
     pg_proc: &PgProc,
@@ -17,6 +31,18 @@ pub(super) fn extension(
         .first::<PgExtension>(conn)
 }
 
+/// Returns the name of the schema that contains the procedure.
+pub(super) fn schema(
+    pg_proc: &PgProc,
+    conn: &mut PgConnection,
+) -> Result<String, diesel::result::Error> {
+    use crate::schema::pg_catalog::pg_namespace::pg_namespace;
+    pg_namespace::table
+        .filter(pg_namespace::oid.eq(pg_proc.pronamespace))
+        .select(pg_namespace::nspname)
+        .first::<String>(conn)
+}
+
 /// Loads all procedures that are functions (not procedures or aggregates), strict, non-set-returning, and non-void-returning.
 pub(super) fn load_all(conn: &mut PgConnection) -> Result<Vec<PgProc>, diesel::result::Error> {
     use crate::schema::pg_catalog::pg_proc::pg_proc;
@@ -24,9 +50,67 @@ pub(super) fn load_all(conn: &mut PgConnection) -> Result<Vec<PgProc>, diesel::r
         .filter(pg_proc::prokind.ne_all(vec!["p", "a"])) // Exclude procedures and aggregates
         .filter(pg_proc::proisstrict.eq(true)) // Exclude non-strict functions
         .filter(pg_proc::proretset.eq(false)) // Exclude set-returning functions
-        .filter(pg_proc::prorettype.ne(0)) // Exclude functions returning "void"
+        .filter(pg_proc::prorettype.ne(VOID_TYPE_OID)) // Exclude functions returning "void"
         .order_by(pg_proc::proname.asc())
         .then_order_by(pg_proc::oid.asc())
         .select(PgProc::as_select())
         .load::<PgProc>(conn)
 }
+
+/// Loads the functions defined in the given schemas, with the names of their
+/// schemas, applying the same filters as [`load_all`] and optionally excluding
+/// the functions owned by extensions.
+pub(super) fn load_all_in_schemas(
+    schemas: &[String],
+    exclude_extension_functions: bool,
+    conn: &mut PgConnection,
+) -> Result<Vec<(PgProc, String)>, diesel::result::Error> {
+    use crate::schema::pg_catalog::{
+        pg_depend::pg_depend, pg_namespace::pg_namespace, pg_proc::pg_proc,
+    };
+
+    let mut query = pg_proc::table
+        .inner_join(pg_namespace::table.on(pg_proc::pronamespace.eq(pg_namespace::oid)))
+        .filter(pg_namespace::nspname.eq_any(schemas))
+        .filter(pg_proc::prokind.ne_all(vec!["p", "a"])) // Exclude procedures and aggregates
+        .filter(pg_proc::proisstrict.eq(true)) // Exclude non-strict functions
+        .filter(pg_proc::proretset.eq(false)) // Exclude set-returning functions
+        .filter(pg_proc::prorettype.ne(VOID_TYPE_OID)) // Exclude functions returning "void"
+        .into_boxed();
+
+    if exclude_extension_functions {
+        // Functions which are members of an extension are recorded in
+        // `pg_depend` with the `e` dependency type.
+        query = query.filter(diesel::dsl::not(
+            pg_proc::oid.eq_any(
+                pg_depend::table
+                    .filter(
+                        pg_depend::deptype
+                            .eq("e")
+                            .and(pg_depend::classid.eq(PG_PROC_CLASS_OID))
+                            .and(pg_depend::refclassid.eq(PG_EXTENSION_CLASS_OID)),
+                    )
+                    .select(pg_depend::objid),
+            ),
+        ));
+    }
+
+    query
+        .order_by(pg_proc::proname.asc())
+        .then_order_by(pg_proc::oid.asc())
+        .select((PgProc::as_select(), pg_namespace::nspname))
+        .load::<(PgProc, String)>(conn)
+}
+
+/// Loads a function by its OID, with the name of its schema.
+pub(super) fn from_oid(
+    oid: u32,
+    conn: &mut PgConnection,
+) -> Result<(PgProc, String), diesel::result::Error> {
+    use crate::schema::pg_catalog::{pg_namespace::pg_namespace, pg_proc::pg_proc};
+    pg_proc::table
+        .inner_join(pg_namespace::table.on(pg_proc::pronamespace.eq(pg_namespace::oid)))
+        .filter(pg_proc::oid.eq(oid))
+        .select((PgProc::as_select(), pg_namespace::nspname))
+        .first::<(PgProc, String)>(conn)
+}
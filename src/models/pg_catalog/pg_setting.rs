@@ -3,6 +3,7 @@
 
 use diesel::{
     ExpressionMethods, PgConnection, QueryDsl, Queryable, QueryableByName, RunQueryDsl, Selectable,
+    SelectableHelper,
 };
 
 #[derive(Queryable, QueryableByName, Selectable, Debug, PartialEq, Clone)]
@@ -59,6 +60,30 @@ pub struct PgSetting {
 }
 
 impl PgSetting {
+    /// Loads all the rows of the `pg_settings` view, sorted by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A reference to a `PostgreSQL` connection.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the query to fetch the settings
+    /// fails.
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_settings::pg_settings;
+        pg_settings::table
+            .select(Self::as_select())
+            .order_by(pg_settings::dsl::name)
+            .load(conn)
+    }
+
+    #[must_use]
+    /// Returns whether changing the parameter requires a server restart.
+    pub fn requires_restart(&self) -> bool {
+        self.context == "postmaster"
+    }
+
     /// Returns the TIME ZONE setting for the provided `PostgreSQL` connection.
     ///
     /// # Arguments
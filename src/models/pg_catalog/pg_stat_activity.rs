@@ -3,7 +3,9 @@
 
 use std::time::SystemTime;
 
-use diesel::{Queryable, QueryableByName, Selectable};
+use diesel::{
+    PgConnection, QueryDsl, Queryable, QueryableByName, RunQueryDsl, Selectable, SelectableHelper,
+};
 
 /// Represents a row from the `pg_stat_activity` view.
 ///
@@ -60,3 +62,19 @@ pub struct PgStatActivity {
     /// Backend type.
     pub backend_type: Option<String>,
 }
+
+impl PgStatActivity {
+    /// Loads all the rows of the `pg_stat_activity` view.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_stat_activity::pg_stat_activity;
+        pg_stat_activity::table.select(Self::as_select()).load(conn)
+    }
+}
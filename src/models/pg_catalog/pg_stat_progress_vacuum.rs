@@ -1,7 +1,10 @@
 //! Submodule providing the `PgStatProgressVacuum` struct representing a row of
 //! the `pg_stat_progress_vacuum` view in `PostgreSQL`.
 
-use diesel::{Queryable, QueryableByName, Selectable};
+use bigdecimal::ToPrimitive;
+use diesel::{
+    PgConnection, QueryDsl, Queryable, QueryableByName, RunQueryDsl, Selectable, SelectableHelper,
+};
 
 /// Represents a row from the `pg_stat_progress_vacuum` view.
 ///
@@ -64,3 +67,37 @@ pub struct PgStatProgressVacuum {
     #[cfg(feature = "postgres-18")]
     pub delay_time: Option<f64>,
 }
+
+impl PgStatProgressVacuum {
+    /// Loads all the rows of the `pg_stat_progress_vacuum` view.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_stat_progress_vacuum::pg_stat_progress_vacuum;
+        pg_stat_progress_vacuum::table
+            .select(Self::as_select())
+            .load(conn)
+    }
+
+    #[must_use]
+    /// Returns the percentage of the heap blocks scanned so far, which is how
+    /// the first and longest phase of a vacuum progresses.
+    ///
+    /// Once the heap is scanned, the percentage of the heap blocks vacuumed
+    /// is returned instead during the `vacuuming heap` phase.
+    pub fn percent_complete(&self) -> Option<f64> {
+        let total = self.heap_blks_total?.to_f64()?;
+        let done = if self.phase.as_deref() == Some("vacuuming heap") {
+            self.heap_blks_vacuumed?
+        } else {
+            self.heap_blks_scanned?
+        };
+        (total > 0.0).then(|| 100.0 * done.to_f64().unwrap_or_default() / total)
+    }
+}
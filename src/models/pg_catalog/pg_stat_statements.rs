@@ -1,6 +1,11 @@
-//! Submodule
+//! Submodule providing the `PgStatStatement` struct representing a row of the
+//! `pg_stat_statements` view, and the metrics by which statements are ranked.
+
+use std::cmp::Ordering;
+
+use bigdecimal::ToPrimitive;
 use diesel::{
-    Selectable,
+    ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, Selectable, SelectableHelper,
     prelude::{Queryable, QueryableByName},
 };
 
@@ -91,12 +96,108 @@ pub struct PgStatStatement {
     pub local_blk_write_time: f64,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A metric by which statements are ranked, in decreasing order.
+pub enum StatementRanking {
+    /// The total time spent executing the statement.
+    TotalTime,
+    /// The mean time spent executing the statement.
+    MeanTime,
+    /// The number of times the statement was executed.
+    Calls,
+    /// The number of shared blocks read from outside the buffer cache.
+    SharedBlocksRead,
+    /// The time spent reading shared blocks, if `track_io_timing` is enabled.
+    BlockReadTime,
+    /// The number of rows retrieved or affected.
+    Rows,
+}
+
+impl StatementRanking {
+    #[must_use]
+    /// Compares the two statements so that the highest ranked comes first.
+    pub fn compare(self, left: &PgStatStatement, right: &PgStatStatement) -> Ordering {
+        match self {
+            Self::TotalTime => right.total_exec_time.total_cmp(&left.total_exec_time),
+            Self::MeanTime => right.mean_exec_time.total_cmp(&left.mean_exec_time),
+            Self::Calls => right.calls.cmp(&left.calls),
+            Self::SharedBlocksRead => right.shared_blks_read.cmp(&left.shared_blks_read),
+            Self::BlockReadTime => right
+                .shared_blk_read_time
+                .total_cmp(&left.shared_blk_read_time),
+            Self::Rows => right.rows.cmp(&left.rows),
+        }
+    }
+
+    /// Sorts the statements so that the highest ranked come first.
+    pub fn sort(self, statements: &mut [PgStatStatement]) {
+        statements.sort_by(|left, right| self.compare(left, right));
+    }
+}
+
 impl PgStatStatement {
-    /// Returns the statements that caused the most compute time expenditure,
-    /// meaning the total time * number of calls.
+    /// Loads all the rows of the `pg_stat_statements` view.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection` - A reference to the database connection.
+    ///
+    /// # Errors
+    ///
+    /// * If the query fails to execute, an error is returned.
+    pub fn load_all(connection: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_stat_statements::pg_stat_statements;
+
+        pg_stat_statements::table
+            .select(PgStatStatement::as_select())
+            .load(connection)
+    }
+
+    /// Returns the highest ranked statements according to the provided
+    /// metric, sorted in the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection` - A reference to the database connection.
+    /// * `ranking` - The metric by which the statements are ranked.
+    /// * `limit` - The maximal number of statements to return.
+    ///
+    /// # Errors
+    ///
+    /// * If the query fails to execute, an error is returned.
+    pub fn ranked(
+        connection: &mut PgConnection,
+        ranking: StatementRanking,
+        limit: i64,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_stat_statements::pg_stat_statements;
+
+        let query = pg_stat_statements::table
+            .select(PgStatStatement::as_select())
+            .into_boxed();
+        let query = match ranking {
+            StatementRanking::TotalTime => query.order(pg_stat_statements::total_exec_time.desc()),
+            StatementRanking::MeanTime => query.order(pg_stat_statements::mean_exec_time.desc()),
+            StatementRanking::Calls => query.order(pg_stat_statements::calls.desc()),
+            StatementRanking::SharedBlocksRead => {
+                query.order(pg_stat_statements::shared_blks_read.desc())
+            }
+            StatementRanking::BlockReadTime => {
+                query.order(pg_stat_statements::shared_blk_read_time.desc())
+            }
+            StatementRanking::Rows => query.order(pg_stat_statements::rows.desc()),
+        };
+        query.limit(limit).load(connection)
+    }
+
+    /// Returns the statements that took the most execution time overall,
+    /// sorted by decreasing `total_exec_time`.
     ///
     /// This is useful for identifying the most expensive queries in terms of
-    /// CPU usage which are likely to benefit from optimization.
+    /// CPU usage which are likely to benefit from optimization. The total
+    /// already accounts for the number of calls; use
+    /// [`StatementRanking::MeanTime`] to find the individually slow queries.
     ///
     /// # Arguments
     ///
@@ -106,23 +207,86 @@ impl PgStatStatement {
     ///
     /// * If the query fails to execute, an error is returned.
     pub fn most_expensive_queries(
-        connection: &mut diesel::PgConnection,
+        connection: &mut PgConnection,
     ) -> Result<Vec<Self>, diesel::result::Error> {
-        use diesel::{QueryDsl, RunQueryDsl, SelectableHelper};
+        let mut statements = Self::load_all(connection)?;
+        StatementRanking::TotalTime.sort(&mut statements);
+        Ok(statements)
+    }
 
-        use crate::schema::pg_catalog::pg_stat_statements::pg_stat_statements;
+    #[must_use]
+    /// Returns the mean time spent reading a shared block from outside the
+    /// buffer cache, in milliseconds, if any block was read.
+    pub fn mean_block_read_time(&self) -> Option<f64> {
+        let blocks = self.shared_blks_read.to_f64()?;
+        (blocks > 0.0).then(|| self.shared_blk_read_time / blocks)
+    }
+}
 
-        let mut statements = pg_stat_statements::table
-            .select(PgStatStatement::as_select())
-            .load::<PgStatStatement>(connection)?;
+#[cfg(test)]
+impl PgStatStatement {
+    /// Returns a statement with the provided calls and execution time, the
+    /// other statistics being zero.
+    pub(crate) fn fixture(queryid: i64, calls: i64, total_exec_time: f64) -> Self {
+        Self {
+            userid: 10,
+            dbid: 5,
+            toplevel: true,
+            queryid,
+            query: format!("SELECT {queryid}"),
+            calls,
+            plans: 0,
+            total_plan_time: 0.0,
+            min_plan_time: 0.0,
+            max_plan_time: 0.0,
+            mean_plan_time: 0.0,
+            stddev_plan_time: 0.0,
+            total_exec_time,
+            min_exec_time: 0.0,
+            max_exec_time: 0.0,
+            mean_exec_time: total_exec_time / calls.to_f64().unwrap_or(1.0),
+            stddev_exec_time: 0.0,
+            rows: calls,
+            shared_blks_hit: 0,
+            shared_blks_read: 0,
+            shared_blks_dirtied: 0,
+            shared_blks_written: 0,
+            local_blks_hit: 0,
+            local_blks_read: 0,
+            local_blks_dirtied: 0,
+            local_blks_written: 0,
+            temp_blks_read: 0,
+            temp_blks_written: 0,
+            shared_blk_read_time: 0.0,
+            shared_blk_write_time: 0.0,
+            local_blk_read_time: 0.0,
+            local_blk_write_time: 0.0,
+        }
+    }
+}
 
-        // Sort by total_exec_time * calls in descending order
-        statements.sort_by(|a, b| {
-            (b.total_exec_time * f64::from(u32::try_from(b.calls).unwrap()))
-                .partial_cmp(&(a.total_exec_time * f64::from(u32::try_from(a.calls).unwrap())))
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Ok(statements)
+    #[test]
+    fn test_ranking_does_not_overflow() {
+        let mut statements = vec![
+            PgStatStatement::fixture(1, 10, 5_000.0),
+            PgStatStatement::fixture(2, 5_000_000_000, 1_000.0),
+            PgStatStatement::fixture(3, 1, 2_000.0),
+        ];
+        StatementRanking::TotalTime.sort(&mut statements);
+        assert_eq!(
+            statements.iter().map(|s| s.queryid).collect::<Vec<_>>(),
+            vec![1, 3, 2]
+        );
+        StatementRanking::MeanTime.sort(&mut statements);
+        assert_eq!(
+            statements.iter().map(|s| s.queryid).collect::<Vec<_>>(),
+            vec![3, 1, 2]
+        );
+        StatementRanking::Calls.sort(&mut statements);
+        assert_eq!(statements[0].queryid, 2);
     }
 }
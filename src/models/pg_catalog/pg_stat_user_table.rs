@@ -3,7 +3,9 @@
 
 use std::time::SystemTime;
 
-use diesel::{Queryable, QueryableByName, Selectable};
+use diesel::{
+    PgConnection, QueryDsl, Queryable, QueryableByName, RunQueryDsl, Selectable, SelectableHelper,
+};
 
 /// Represents a row from the `pg_stat_user_tables` view.
 ///
@@ -71,3 +73,21 @@ pub struct PgStatUserTable {
     /// Autoanalyze count.
     pub autoanalyze_count: Option<i64>,
 }
+
+impl PgStatUserTable {
+    /// Loads all the rows of the `pg_stat_user_tables` view.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::pg_stat_user_tables::pg_stat_user_tables;
+        pg_stat_user_tables::table
+            .select(Self::as_select())
+            .load(conn)
+    }
+}
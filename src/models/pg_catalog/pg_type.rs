@@ -5,6 +5,7 @@ use diesel::{PgConnection, Queryable, QueryableByName, Selectable};
 mod cached_queries;
 
 use super::{PgAttribute, PgEnum, PgExtension};
+use crate::models::CheckConstraint;
 
 /// Represents a `PostgreSQL` type.
 ///
@@ -246,6 +247,24 @@ impl PgType {
     pub fn variants(&self, conn: &mut PgConnection) -> Result<Vec<PgEnum>, diesel::result::Error> {
         cached_queries::variants(self, conn)
     }
+
+    /// Returns the check constraints of the type, if it is a domain.
+    ///
+    /// The check clauses refer to the checked value as `VALUE`.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - The Postgres connection.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the provided database connection fails.
+    pub fn domain_check_constraints(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<Vec<CheckConstraint>, diesel::result::Error> {
+        cached_queries::domain_check_constraints(self, conn)
+    }
 }
 
 #[cfg(test)]
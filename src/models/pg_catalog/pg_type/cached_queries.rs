@@ -2,10 +2,10 @@
 
 use diesel::{
     BoolExpressionMethods, ExpressionMethods, JoinOnDsl, PgConnection, QueryDsl, RunQueryDsl,
-    SelectableHelper,
+    SelectableHelper, sql_types::Oid,
 };
 
-use crate::models::{PgAttribute, PgEnum, PgExtension, PgType};
+use crate::models::{CheckConstraint, PgAttribute, PgEnum, PgExtension, PgType};
 
 /// Returns the enum variants for the type, if it is an enum.
 pub(super) fn variants(
@@ -61,3 +61,25 @@ pub(super) fn from_oid(oid: u32, conn: &mut PgConnection) -> Result<PgType, dies
         .select(PgType::as_select())
         .first::<PgType>(conn)
 }
+
+/// Returns the check constraints of the domain.
+pub(super) fn domain_check_constraints(
+    pg_type: &PgType,
+    conn: &mut PgConnection,
+) -> Result<Vec<CheckConstraint>, diesel::result::Error> {
+    diesel::sql_query(
+        r"
+        SELECT
+            current_database()::text AS constraint_catalog,
+            n.nspname::text AS constraint_schema,
+            c.conname::text AS constraint_name,
+            pg_get_expr(c.conbin, 0) AS check_clause
+        FROM pg_constraint c
+        JOIN pg_namespace n ON n.oid = c.connamespace
+        WHERE c.contypid = $1 AND c.contype = 'c'
+        ORDER BY c.conname
+        ",
+    )
+    .bind::<Oid, _>(pg_type.oid)
+    .load::<CheckConstraint>(conn)
+}
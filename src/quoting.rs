@@ -0,0 +1,83 @@
+//! Submodule providing the helpers quoting and escaping names and text for
+//! the SQL, HTML, diagrams and Rust code generated by the crate.
+
+/// The strict and reserved keywords of Rust, which cannot be used as
+/// identifiers without a suffix.
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+    "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Returns the identifier quoted for use in SQL.
+pub(crate) fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Returns the identifiers quoted for use in SQL and separated by commas.
+pub(crate) fn quote_identifiers(identifiers: &[String]) -> String {
+    identifiers
+        .iter()
+        .map(|identifier| quote_identifier(identifier))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns the text quoted as a SQL string literal.
+pub(crate) fn quote_literal(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Returns the text escaped for HTML, including Graphviz HTML-like labels.
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Returns the Rust identifier of the table, column or schema, replacing the
+/// characters not allowed in identifiers and suffixing keywords with `_` as
+/// `diesel print-schema` does.
+pub(crate) fn rust_identifier(name: &str) -> String {
+    let mut identifier: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if identifier.is_empty() || identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    if RUST_KEYWORDS.contains(&identifier.as_str()) {
+        identifier.push('_');
+    }
+    identifier
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(quote_identifier("a\"b"), "\"a\"\"b\"");
+        assert_eq!(
+            quote_identifiers(&["id".to_owned(), "name".to_owned()]),
+            "\"id\", \"name\""
+        );
+        assert_eq!(quote_literal("it's"), "'it''s'");
+        assert_eq!(escape_html("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+    }
+
+    #[test]
+    fn test_rust_identifier() {
+        assert_eq!(rust_identifier("age"), "age");
+        assert_eq!(rust_identifier("type"), "type_");
+        assert_eq!(rust_identifier("crate"), "crate_");
+        assert_eq!(rust_identifier("self"), "self_");
+        assert_eq!(rust_identifier("Self"), "Self_");
+        assert_eq!(rust_identifier("first name"), "first_name");
+        assert_eq!(rust_identifier("1st"), "_1st");
+    }
+}
@@ -0,0 +1,1254 @@
+//! Submodule modelling the logical and physical replication topology of a
+//! server on top of the rows of the replication catalogs.
+//!
+//! A [`ReplicationTopology`] gathers:
+//!
+//! * the [`Publication`]s of the database, with the tables they publish,
+//!   whether listed explicitly, through `FOR TABLES IN SCHEMA` or through
+//!   `FOR ALL TABLES`, along with their column lists and parsed row filters;
+//! * the [`Subscription`]s of the database, with the synchronization state of
+//!   each of their tables;
+//! * the replication slots of the server, with the WAL they retain in bytes,
+//!   and the standbys connected to it.
+//!
+//! Its [`ReplicationTopology::findings`] warn about published tables whose
+//! updates and deletes cannot be replicated for lack of a primary key or
+//! replica identity, row filters which cannot be parsed and slots which are
+//! inactive or lost.
+
+use std::{collections::HashMap, fmt::Display};
+
+use diesel::{
+    PgConnection, QueryDsl, QueryableByName, RunQueryDsl, SelectableHelper,
+    data_types::PgLsn,
+    sql_types::{Bool, Nullable, Oid, Text},
+};
+use sqlparser::{ast::Expr, dialect::PostgreSqlDialect, parser::Parser};
+
+use crate::{
+    identity::RelOid,
+    models::{
+        PgPublication, PgPublicationNamespace, PgPublicationRel, PgPublicationTable,
+        PgReplicationSlot, PgStatReplication, PgStatWalReceiver, PgSubscription, PgSubscriptionRel,
+    },
+};
+
+/// Returns the textual representation of the LSN, as in `16/B374D848`.
+#[must_use]
+pub fn format_lsn(lsn: PgLsn) -> String {
+    format!("{:X}/{:X}", lsn.0 >> 32, lsn.0 & 0xFFFF_FFFF)
+}
+
+/// Returns the number of bytes of WAL from the provided LSN up to the current
+/// one, or zero if the LSN is ahead.
+#[must_use]
+pub fn lsn_lag(current: PgLsn, lsn: PgLsn) -> u64 {
+    current.0.saturating_sub(lsn.0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The columns logged to identify the rows updated or deleted in a table, as
+/// set by `ALTER TABLE ... REPLICA IDENTITY`.
+pub enum ReplicaIdentity {
+    /// The columns of the primary key, if any.
+    Default,
+    /// No column: updates and deletes cannot be replicated.
+    Nothing,
+    /// All the columns.
+    Full,
+    /// The columns of a unique index.
+    Index,
+}
+
+impl ReplicaIdentity {
+    /// Returns the replica identity encoded in `pg_class.relreplident`.
+    #[must_use]
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "d" => Some(Self::Default),
+            "n" => Some(Self::Nothing),
+            "f" => Some(Self::Full),
+            "i" => Some(Self::Index),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ReplicaIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Default => "DEFAULT",
+            Self::Nothing => "NOTHING",
+            Self::Full => "FULL",
+            Self::Index => "USING INDEX",
+        })
+    }
+}
+
+#[derive(QueryableByName, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A table of the database, with what identifies its rows to logical
+/// replication.
+pub struct ReplicatedRelation {
+    #[diesel(sql_type = Oid, deserialize_as = u32)]
+    /// The OID of the table.
+    pub relation_oid: RelOid,
+    #[diesel(sql_type = Oid)]
+    /// The OID of the schema of the table.
+    pub schema_oid: u32,
+    #[diesel(sql_type = Text)]
+    /// The name of the schema of the table.
+    pub schema_name: String,
+    #[diesel(sql_type = Text)]
+    /// The name of the table.
+    pub table_name: String,
+    #[diesel(sql_type = Text)]
+    /// The replica identity of the table, as encoded in
+    /// `pg_class.relreplident`.
+    pub replica_identity: String,
+    #[diesel(sql_type = Bool)]
+    /// Whether the table has a primary key.
+    pub has_primary_key: bool,
+    #[diesel(sql_type = Nullable<Text>)]
+    /// The name of the index marked as replica identity
+    /// (`pg_index.indisreplident`), if any.
+    pub replica_identity_index: Option<String>,
+}
+
+impl ReplicatedRelation {
+    /// Loads the tables of the database, partitioned or not, outside of the
+    /// system schemas.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        diesel::sql_query(
+            r"
+            SELECT
+                c.oid AS relation_oid,
+                n.oid AS schema_oid,
+                n.nspname::text AS schema_name,
+                c.relname::text AS table_name,
+                c.relreplident::text AS replica_identity,
+                EXISTS (
+                    SELECT 1 FROM pg_index i WHERE i.indrelid = c.oid AND i.indisprimary
+                ) AS has_primary_key,
+                (
+                    SELECT ic.relname::text
+                    FROM pg_index i
+                    JOIN pg_class ic ON ic.oid = i.indexrelid
+                    WHERE i.indrelid = c.oid AND i.indisreplident
+                ) AS replica_identity_index
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE c.relkind IN ('r', 'p')
+                AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+                AND n.nspname NOT LIKE 'pg\_toast%'
+                AND n.nspname NOT LIKE 'pg\_temp\_%'
+            ORDER BY n.nspname, c.relname
+            ",
+        )
+        .load(conn)
+    }
+
+    #[must_use]
+    /// Returns the replica identity of the table, if its code is known.
+    pub fn identity(&self) -> Option<ReplicaIdentity> {
+        ReplicaIdentity::from_code(&self.replica_identity)
+    }
+
+    #[must_use]
+    /// Returns whether the updates and deletes of the table can be published:
+    /// its replica identity must be `FULL`, its primary key or an existing
+    /// replica identity index.
+    pub fn identifies_rows(&self) -> bool {
+        match self.identity() {
+            Some(ReplicaIdentity::Full) => true,
+            Some(ReplicaIdentity::Default) => self.has_primary_key,
+            Some(ReplicaIdentity::Index) => self.replica_identity_index.is_some(),
+            Some(ReplicaIdentity::Nothing) | None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// How a table came to be part of a publication.
+pub enum PublicationSource {
+    /// The publication is `FOR ALL TABLES`.
+    AllTables,
+    /// The publication includes the schema of the table, through
+    /// `FOR TABLES IN SCHEMA`.
+    Schema,
+    /// The publication lists the table explicitly.
+    Table,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A table published by a [`Publication`].
+pub struct PublishedTable {
+    /// The schema of the table.
+    schema: String,
+    /// The name of the table.
+    table: String,
+    /// The OID of the table, if it is known.
+    relation_oid: Option<RelOid>,
+    /// How the table came to be part of the publication.
+    source: PublicationSource,
+    /// The published columns, if restricted by a column list.
+    columns: Option<Vec<String>>,
+    /// The row filter, as SQL.
+    row_filter: Option<String>,
+    /// The parsed row filter, if it could be parsed.
+    row_filter_expression: Option<Expr>,
+}
+
+impl PublishedTable {
+    /// Creates a new `PublishedTable` from its row of `pg_publication_tables`
+    /// and, if listed explicitly, of `pg_publication_rel`.
+    #[cfg_attr(feature = "postgres-14", allow(unused_variables))]
+    fn new(
+        schema: &str,
+        table: &str,
+        relation_oid: Option<RelOid>,
+        source: PublicationSource,
+        row: &PgPublicationTable,
+        rel: Option<&PgPublicationRel>,
+    ) -> Self {
+        #[cfg(not(feature = "postgres-14"))]
+        let (columns, row_filter) = (
+            rel.and_then(|rel| rel.prattrs.as_ref())
+                .and(row.attnames.clone()),
+            row.rowfilter.clone(),
+        );
+        #[cfg(feature = "postgres-14")]
+        let (columns, row_filter): (Option<Vec<String>>, Option<String>) = (None, None);
+        let row_filter_expression = row_filter.as_deref().and_then(|row_filter| {
+            Parser::new(&PostgreSqlDialect {})
+                .try_with_sql(row_filter)
+                .and_then(|mut parser| parser.parse_expr())
+                .ok()
+        });
+        Self {
+            schema: schema.to_owned(),
+            table: table.to_owned(),
+            relation_oid,
+            source,
+            columns,
+            row_filter,
+            row_filter_expression,
+        }
+    }
+
+    /// Returns the schema of the table.
+    #[must_use]
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// Returns the name of the table.
+    #[must_use]
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Returns the OID of the table, if it is known.
+    #[must_use]
+    pub fn relation_oid(&self) -> Option<RelOid> {
+        self.relation_oid
+    }
+
+    /// Returns how the table came to be part of the publication.
+    #[must_use]
+    pub fn source(&self) -> PublicationSource {
+        self.source
+    }
+
+    /// Returns the published columns, if restricted by a column list.
+    #[must_use]
+    pub fn columns(&self) -> Option<&[String]> {
+        self.columns.as_deref()
+    }
+
+    /// Returns the row filter, as SQL.
+    #[must_use]
+    pub fn row_filter(&self) -> Option<&str> {
+        self.row_filter.as_deref()
+    }
+
+    /// Returns the parsed row filter, if it could be parsed.
+    #[must_use]
+    pub fn row_filter_expression(&self) -> Option<&Expr> {
+        self.row_filter_expression.as_ref()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::struct_excessive_bools)]
+/// A publication, with the tables it publishes.
+pub struct Publication {
+    /// The OID of the publication.
+    oid: u32,
+    /// The name of the publication.
+    name: String,
+    /// Whether the publication is `FOR ALL TABLES`.
+    all_tables: bool,
+    /// Whether inserts are published.
+    inserts: bool,
+    /// Whether updates are published.
+    updates: bool,
+    /// Whether deletes are published.
+    deletes: bool,
+    /// Whether truncates are published.
+    truncates: bool,
+    /// The schemas included through `FOR TABLES IN SCHEMA`.
+    schemas: Vec<String>,
+    /// The published tables.
+    tables: Vec<PublishedTable>,
+}
+
+impl Publication {
+    /// Resolves the publications of the database from the rows of the
+    /// publication catalogs.
+    ///
+    /// # Arguments
+    ///
+    /// * `publications` - The rows of `pg_publication`.
+    /// * `publication_rels` - The rows of `pg_publication_rel`.
+    /// * `publication_namespaces` - The rows of `pg_publication_namespace`.
+    /// * `publication_tables` - The rows of `pg_publication_tables`.
+    /// * `relations` - The tables of the database.
+    #[must_use]
+    pub fn resolve(
+        publications: &[PgPublication],
+        publication_rels: &[PgPublicationRel],
+        publication_namespaces: &[PgPublicationNamespace],
+        publication_tables: &[PgPublicationTable],
+        relations: &[ReplicatedRelation],
+    ) -> Vec<Self> {
+        let by_name: HashMap<(&str, &str), &ReplicatedRelation> = relations
+            .iter()
+            .map(|relation| {
+                (
+                    (relation.schema_name.as_str(), relation.table_name.as_str()),
+                    relation,
+                )
+            })
+            .collect();
+        let schema_names: HashMap<u32, &str> = relations
+            .iter()
+            .map(|relation| (relation.schema_oid, relation.schema_name.as_str()))
+            .collect();
+        publications
+            .iter()
+            .map(|publication| {
+                let namespaces: Vec<u32> = publication_namespaces
+                    .iter()
+                    .filter(|namespace| namespace.pnpubid == publication.oid)
+                    .map(|namespace| namespace.pnnspid)
+                    .collect();
+                let tables = publication_tables
+                    .iter()
+                    .filter(|row| row.pubname.as_deref() == Some(publication.pubname.as_str()))
+                    .filter_map(|row| {
+                        let schema = row.schemaname.as_deref()?;
+                        let table = row.tablename.as_deref()?;
+                        let relation = by_name.get(&(schema, table)).copied();
+                        let rel = relation.and_then(|relation| {
+                            publication_rels.iter().find(|rel| {
+                                rel.prpubid == publication.oid
+                                    && RelOid::new(rel.prrelid) == relation.relation_oid
+                            })
+                        });
+                        let source = if rel.is_some() {
+                            PublicationSource::Table
+                        } else if publication.puballtables {
+                            PublicationSource::AllTables
+                        } else {
+                            PublicationSource::Schema
+                        };
+                        Some(PublishedTable::new(
+                            schema,
+                            table,
+                            relation.map(|relation| relation.relation_oid),
+                            source,
+                            row,
+                            rel,
+                        ))
+                    })
+                    .collect();
+                let mut schemas: Vec<String> = namespaces
+                    .iter()
+                    .filter_map(|oid| schema_names.get(oid))
+                    .map(|name| (*name).to_owned())
+                    .collect();
+                schemas.sort_unstable();
+                Self {
+                    oid: publication.oid,
+                    name: publication.pubname.clone(),
+                    all_tables: publication.puballtables,
+                    inserts: publication.pubinsert,
+                    updates: publication.pubupdate,
+                    deletes: publication.pubdelete,
+                    truncates: publication.pubtruncate,
+                    schemas,
+                    tables,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the OID of the publication.
+    #[must_use]
+    pub fn oid(&self) -> u32 {
+        self.oid
+    }
+
+    /// Returns the name of the publication.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns whether the publication is `FOR ALL TABLES`.
+    #[must_use]
+    pub fn all_tables(&self) -> bool {
+        self.all_tables
+    }
+
+    /// Returns whether inserts are published.
+    #[must_use]
+    pub fn publishes_inserts(&self) -> bool {
+        self.inserts
+    }
+
+    /// Returns whether updates are published.
+    #[must_use]
+    pub fn publishes_updates(&self) -> bool {
+        self.updates
+    }
+
+    /// Returns whether deletes are published.
+    #[must_use]
+    pub fn publishes_deletes(&self) -> bool {
+        self.deletes
+    }
+
+    /// Returns whether truncates are published.
+    #[must_use]
+    pub fn publishes_truncates(&self) -> bool {
+        self.truncates
+    }
+
+    /// Returns the schemas included through `FOR TABLES IN SCHEMA`.
+    #[must_use]
+    pub fn schemas(&self) -> &[String] {
+        &self.schemas
+    }
+
+    /// Returns the published tables.
+    #[must_use]
+    pub fn tables(&self) -> &[PublishedTable] {
+        &self.tables
+    }
+
+    /// Returns the published table with the provided schema and name, if any.
+    #[must_use]
+    pub fn table(&self, schema: &str, table: &str) -> Option<&PublishedTable> {
+        self.tables
+            .iter()
+            .find(|published| published.schema == schema && published.table == table)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The synchronization state of a table of a subscription, as recorded in
+/// `pg_subscription_rel.srsubstate`.
+pub enum SyncState {
+    /// The synchronization is starting.
+    Initialize,
+    /// The initial data is being copied.
+    DataCopy,
+    /// The initial copy is over, the table is catching up.
+    FinishedCopy,
+    /// The table is synchronized with the apply worker.
+    Synchronized,
+    /// The table is replicated normally.
+    Ready,
+}
+
+impl SyncState {
+    /// Returns the state encoded in `pg_subscription_rel.srsubstate`.
+    #[must_use]
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "i" => Some(Self::Initialize),
+            "d" => Some(Self::DataCopy),
+            "f" => Some(Self::FinishedCopy),
+            "s" => Some(Self::Synchronized),
+            "r" => Some(Self::Ready),
+            _ => None,
+        }
+    }
+}
+
+impl Display for SyncState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Initialize => "initialize",
+            Self::DataCopy => "data copy",
+            Self::FinishedCopy => "finished copy",
+            Self::Synchronized => "synchronized",
+            Self::Ready => "ready",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A table replicated by a [`Subscription`].
+pub struct SubscribedTable {
+    /// The OID of the table.
+    relation_oid: RelOid,
+    /// The schema and name of the table, if it is known.
+    name: Option<(String, String)>,
+    /// The synchronization state of the table, if its code is known.
+    state: Option<SyncState>,
+    /// The LSN at which the state was reached, if any.
+    lsn: Option<PgLsn>,
+}
+
+impl SubscribedTable {
+    /// Returns the OID of the table.
+    #[must_use]
+    pub fn relation_oid(&self) -> RelOid {
+        self.relation_oid
+    }
+
+    /// Returns the schema of the table, if it is known.
+    #[must_use]
+    pub fn schema(&self) -> Option<&str> {
+        self.name.as_ref().map(|(schema, _)| schema.as_str())
+    }
+
+    /// Returns the name of the table, if it is known.
+    #[must_use]
+    pub fn table(&self) -> Option<&str> {
+        self.name.as_ref().map(|(_, table)| table.as_str())
+    }
+
+    /// Returns the synchronization state of the table, if its code is known.
+    #[must_use]
+    pub fn state(&self) -> Option<SyncState> {
+        self.state
+    }
+
+    /// Returns the LSN at which the state was reached, if any.
+    #[must_use]
+    pub fn lsn(&self) -> Option<PgLsn> {
+        self.lsn
+    }
+
+    #[must_use]
+    /// Returns whether the table is replicated normally.
+    pub fn is_ready(&self) -> bool {
+        self.state == Some(SyncState::Ready)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A subscription, with the synchronization state of its tables.
+pub struct Subscription {
+    /// The OID of the subscription.
+    oid: u32,
+    /// The name of the subscription.
+    name: String,
+    /// Whether the subscription is enabled.
+    enabled: bool,
+    /// The publications subscribed to.
+    publications: Vec<String>,
+    /// The name of the replication slot on the publisher, if any.
+    slot_name: Option<String>,
+    /// The replicated tables.
+    tables: Vec<SubscribedTable>,
+}
+
+impl Subscription {
+    /// Resolves the subscriptions of the database from the rows of the
+    /// subscription catalogs.
+    ///
+    /// # Arguments
+    ///
+    /// * `subscriptions` - The rows of `pg_subscription`.
+    /// * `subscription_rels` - The rows of `pg_subscription_rel`.
+    /// * `relations` - The tables of the database.
+    #[must_use]
+    pub fn resolve(
+        subscriptions: &[PgSubscription],
+        subscription_rels: &[PgSubscriptionRel],
+        relations: &[ReplicatedRelation],
+    ) -> Vec<Self> {
+        subscriptions
+            .iter()
+            .map(|subscription| Self {
+                oid: subscription.oid,
+                name: subscription.subname.clone(),
+                enabled: subscription.subenabled,
+                publications: subscription.subpublications.clone(),
+                slot_name: subscription.subslotname.clone(),
+                tables: subscription_rels
+                    .iter()
+                    .filter(|rel| rel.srsubid == subscription.oid)
+                    .map(|rel| SubscribedTable {
+                        relation_oid: RelOid::new(rel.srrelid),
+                        name: relations
+                            .iter()
+                            .find(|relation| relation.relation_oid == RelOid::new(rel.srrelid))
+                            .map(|relation| {
+                                (relation.schema_name.clone(), relation.table_name.clone())
+                            }),
+                        state: SyncState::from_code(&rel.srsubstate),
+                        lsn: rel.srsublsn,
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Returns the OID of the subscription.
+    #[must_use]
+    pub fn oid(&self) -> u32 {
+        self.oid
+    }
+
+    /// Returns the name of the subscription.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns whether the subscription is enabled.
+    #[must_use]
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the publications subscribed to.
+    #[must_use]
+    pub fn publications(&self) -> &[String] {
+        &self.publications
+    }
+
+    /// Returns the name of the replication slot on the publisher, if any.
+    #[must_use]
+    pub fn slot_name(&self) -> Option<&str> {
+        self.slot_name.as_deref()
+    }
+
+    /// Returns the replicated tables.
+    #[must_use]
+    pub fn tables(&self) -> &[SubscribedTable] {
+        &self.tables
+    }
+
+    /// Returns the tables which are still being synchronized.
+    pub fn pending_tables(&self) -> impl Iterator<Item = &SubscribedTable> {
+        self.tables.iter().filter(|table| !table.is_ready())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A replication slot, with the WAL it retains.
+pub struct SlotLag {
+    /// The row of `pg_replication_slots`.
+    slot: PgReplicationSlot,
+    /// The bytes of WAL retained from the restart LSN of the slot.
+    retained_bytes: Option<u64>,
+    /// The bytes of WAL not yet confirmed by the consumer of a logical slot.
+    confirmed_lag_bytes: Option<u64>,
+}
+
+impl SlotLag {
+    /// Creates a new `SlotLag` measuring the slot against the current LSN of
+    /// the server.
+    #[must_use]
+    pub fn new(slot: PgReplicationSlot, current_lsn: PgLsn) -> Self {
+        Self {
+            retained_bytes: slot.restart_lsn.map(|lsn| lsn_lag(current_lsn, lsn)),
+            confirmed_lag_bytes: slot
+                .confirmed_flush_lsn
+                .map(|lsn| lsn_lag(current_lsn, lsn)),
+            slot,
+        }
+    }
+
+    /// Returns the row of `pg_replication_slots`.
+    #[must_use]
+    pub fn slot(&self) -> &PgReplicationSlot {
+        &self.slot
+    }
+
+    /// Returns the name of the slot.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.slot.slot_name.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the bytes of WAL retained from the restart LSN of the slot.
+    #[must_use]
+    pub fn retained_bytes(&self) -> Option<u64> {
+        self.retained_bytes
+    }
+
+    /// Returns the bytes of WAL not yet confirmed by the consumer of a
+    /// logical slot.
+    #[must_use]
+    pub fn confirmed_lag_bytes(&self) -> Option<u64> {
+        self.confirmed_lag_bytes
+    }
+
+    #[must_use]
+    /// Returns whether a process is consuming the slot.
+    pub fn is_active(&self) -> bool {
+        self.slot.active == Some(true)
+    }
+
+    #[must_use]
+    /// Returns whether the WAL required by the slot was removed, so that the
+    /// slot can no longer be used.
+    pub fn is_lost(&self) -> bool {
+        self.slot.wal_status.as_deref() == Some("lost")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A standby streaming from the server, with its lag in bytes.
+pub struct StandbyLag {
+    /// The row of `pg_stat_replication`.
+    standby: PgStatReplication,
+    /// The bytes of WAL not yet sent to the standby.
+    sent_lag_bytes: Option<u64>,
+    /// The bytes of WAL not yet flushed by the standby.
+    flush_lag_bytes: Option<u64>,
+    /// The bytes of WAL not yet replayed by the standby.
+    replay_lag_bytes: Option<u64>,
+}
+
+impl StandbyLag {
+    /// Creates a new `StandbyLag` measuring the standby against the current
+    /// LSN of the server.
+    #[must_use]
+    pub fn new(standby: PgStatReplication, current_lsn: PgLsn) -> Self {
+        Self {
+            sent_lag_bytes: standby.sent_lsn.map(|lsn| lsn_lag(current_lsn, lsn)),
+            flush_lag_bytes: standby.flush_lsn.map(|lsn| lsn_lag(current_lsn, lsn)),
+            replay_lag_bytes: standby.replay_lsn.map(|lsn| lsn_lag(current_lsn, lsn)),
+            standby,
+        }
+    }
+
+    /// Returns the row of `pg_stat_replication`.
+    #[must_use]
+    pub fn standby(&self) -> &PgStatReplication {
+        &self.standby
+    }
+
+    /// Returns the bytes of WAL not yet sent to the standby.
+    #[must_use]
+    pub fn sent_lag_bytes(&self) -> Option<u64> {
+        self.sent_lag_bytes
+    }
+
+    /// Returns the bytes of WAL not yet flushed by the standby.
+    #[must_use]
+    pub fn flush_lag_bytes(&self) -> Option<u64> {
+        self.flush_lag_bytes
+    }
+
+    /// Returns the bytes of WAL not yet replayed by the standby.
+    #[must_use]
+    pub fn replay_lag_bytes(&self) -> Option<u64> {
+        self.replay_lag_bytes
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// An issue found in the replication topology.
+pub enum ReplicationFinding {
+    /// A table whose updates or deletes are published has neither a primary
+    /// key nor another usable replica identity, so that its updates and
+    /// deletes fail on the publisher.
+    MissingReplicaIdentity {
+        /// The name of the publication.
+        publication: String,
+        /// The schema of the table.
+        schema: String,
+        /// The name of the table.
+        table: String,
+        /// The replica identity of the table, if its code is known.
+        identity: Option<ReplicaIdentity>,
+    },
+    /// The row filter of a published table could not be parsed.
+    UnparsableRowFilter {
+        /// The name of the publication.
+        publication: String,
+        /// The schema of the table.
+        schema: String,
+        /// The name of the table.
+        table: String,
+        /// The row filter, as SQL.
+        row_filter: String,
+    },
+    /// A replication slot is not consumed, while retaining WAL.
+    InactiveSlot {
+        /// The name of the slot.
+        slot: String,
+        /// The bytes of WAL retained by the slot, if known.
+        retained_bytes: Option<u64>,
+    },
+    /// The WAL required by a replication slot was removed.
+    LostSlot {
+        /// The name of the slot.
+        slot: String,
+    },
+}
+
+impl Display for ReplicationFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingReplicaIdentity {
+                publication,
+                schema,
+                table,
+                identity,
+            } => {
+                write!(
+                    f,
+                    "{schema}.{table} is published by `{publication}` but its updates and deletes cannot be replicated: "
+                )?;
+                match identity {
+                    Some(ReplicaIdentity::Nothing) => {
+                        f.write_str("its replica identity is NOTHING")
+                    }
+                    Some(ReplicaIdentity::Index) => {
+                        f.write_str("its replica identity index does not exist")
+                    }
+                    _ => f.write_str("it has no primary key nor replica identity"),
+                }
+            }
+            Self::UnparsableRowFilter {
+                publication,
+                schema,
+                table,
+                row_filter,
+            } => write!(
+                f,
+                "the row filter `{row_filter}` of {schema}.{table} in `{publication}` could not be parsed"
+            ),
+            Self::InactiveSlot {
+                slot,
+                retained_bytes,
+            } => {
+                write!(f, "replication slot `{slot}` is inactive")?;
+                if let Some(retained_bytes) = retained_bytes {
+                    write!(f, " and retains {retained_bytes} bytes of WAL")?;
+                }
+                Ok(())
+            }
+            Self::LostSlot { slot } => write!(
+                f,
+                "replication slot `{slot}` lost the WAL it requires and must be recreated"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The replication topology of a server, as seen from one of its databases.
+pub struct ReplicationTopology {
+    /// The tables of the database.
+    relations: Vec<ReplicatedRelation>,
+    /// The publications of the database.
+    publications: Vec<Publication>,
+    /// The subscriptions of the database.
+    subscriptions: Vec<Subscription>,
+    /// The replication slots of the server.
+    slots: Vec<SlotLag>,
+    /// The standbys streaming from the server.
+    standbys: Vec<StandbyLag>,
+    /// The WAL receiver of the server, if it is a standby.
+    receiver: Option<PgStatWalReceiver>,
+}
+
+#[derive(QueryableByName)]
+/// The current LSN of the server.
+struct CurrentLsn {
+    #[diesel(sql_type = diesel::sql_types::PgLsn)]
+    /// The current write LSN of a primary, or the last replayed LSN of a
+    /// standby.
+    lsn: PgLsn,
+}
+
+impl ReplicationTopology {
+    /// Loads the replication topology of the server the connection is to.
+    ///
+    /// Reading `pg_subscription` requires superuser privileges, as access to
+    /// its connection strings is revoked from other roles.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load(conn: &mut PgConnection) -> Result<Self, diesel::result::Error> {
+        use crate::schema::pg_catalog::{
+            pg_publication::pg_publication, pg_publication_rel::pg_publication_rel,
+            pg_publication_tables::pg_publication_tables,
+            pg_replication_slots::pg_replication_slots, pg_stat_replication::pg_stat_replication,
+            pg_stat_wal_receiver::pg_stat_wal_receiver, pg_subscription::pg_subscription,
+            pg_subscription_rel::pg_subscription_rel,
+        };
+
+        let relations = ReplicatedRelation::load_all(conn)?;
+        let current_lsn = diesel::sql_query(
+            r"
+            SELECT CASE WHEN pg_is_in_recovery()
+                THEN pg_last_wal_replay_lsn()
+                ELSE pg_current_wal_lsn()
+            END AS lsn
+            ",
+        )
+        .get_result::<CurrentLsn>(conn)?
+        .lsn;
+        #[cfg(not(feature = "postgres-14"))]
+        let publication_namespaces = {
+            use crate::schema::pg_catalog::pg_publication_namespace::pg_publication_namespace;
+            pg_publication_namespace::table
+                .select(PgPublicationNamespace::as_select())
+                .load(conn)?
+        };
+        #[cfg(feature = "postgres-14")]
+        let publication_namespaces = Vec::new();
+        let publications = Publication::resolve(
+            &pg_publication::table
+                .select(PgPublication::as_select())
+                .load(conn)?,
+            &pg_publication_rel::table
+                .select(PgPublicationRel::as_select())
+                .load(conn)?,
+            &publication_namespaces,
+            &pg_publication_tables::table
+                .select(PgPublicationTable::as_select())
+                .load(conn)?,
+            &relations,
+        );
+        let subscriptions = Subscription::resolve(
+            &pg_subscription::table
+                .select(PgSubscription::as_select())
+                .load(conn)?,
+            &pg_subscription_rel::table
+                .select(PgSubscriptionRel::as_select())
+                .load(conn)?,
+            &relations,
+        );
+        let slots = pg_replication_slots::table
+            .select(PgReplicationSlot::as_select())
+            .load(conn)?
+            .into_iter()
+            .map(|slot| SlotLag::new(slot, current_lsn))
+            .collect();
+        let standbys = pg_stat_replication::table
+            .select(PgStatReplication::as_select())
+            .load(conn)?
+            .into_iter()
+            .map(|standby| StandbyLag::new(standby, current_lsn))
+            .collect();
+        let receiver = pg_stat_wal_receiver::table
+            .select(PgStatWalReceiver::as_select())
+            .load(conn)?
+            .into_iter()
+            .next();
+        Ok(Self::new(
+            relations,
+            publications,
+            subscriptions,
+            slots,
+            standbys,
+            receiver,
+        ))
+    }
+
+    /// Creates a new `ReplicationTopology` from its resolved parts.
+    ///
+    /// # Arguments
+    ///
+    /// * `relations` - The tables of the database.
+    /// * `publications` - The publications, as resolved by
+    ///   [`Publication::resolve`].
+    /// * `subscriptions` - The subscriptions, as resolved by
+    ///   [`Subscription::resolve`].
+    /// * `slots` - The replication slots of the server.
+    /// * `standbys` - The standbys streaming from the server.
+    /// * `receiver` - The WAL receiver of the server, if it is a standby.
+    #[must_use]
+    pub fn new(
+        relations: Vec<ReplicatedRelation>,
+        publications: Vec<Publication>,
+        subscriptions: Vec<Subscription>,
+        slots: Vec<SlotLag>,
+        standbys: Vec<StandbyLag>,
+        receiver: Option<PgStatWalReceiver>,
+    ) -> Self {
+        Self {
+            relations,
+            publications,
+            subscriptions,
+            slots,
+            standbys,
+            receiver,
+        }
+    }
+
+    /// Returns the tables of the database.
+    #[must_use]
+    pub fn relations(&self) -> &[ReplicatedRelation] {
+        &self.relations
+    }
+
+    /// Returns the publications of the database.
+    #[must_use]
+    pub fn publications(&self) -> &[Publication] {
+        &self.publications
+    }
+
+    /// Returns the subscriptions of the database.
+    #[must_use]
+    pub fn subscriptions(&self) -> &[Subscription] {
+        &self.subscriptions
+    }
+
+    /// Returns the replication slots of the server.
+    #[must_use]
+    pub fn slots(&self) -> &[SlotLag] {
+        &self.slots
+    }
+
+    /// Returns the standbys streaming from the server.
+    #[must_use]
+    pub fn standbys(&self) -> &[StandbyLag] {
+        &self.standbys
+    }
+
+    /// Returns the WAL receiver of the server, if it is a standby.
+    #[must_use]
+    pub fn receiver(&self) -> Option<&PgStatWalReceiver> {
+        self.receiver.as_ref()
+    }
+
+    /// Returns the publications publishing the table with the provided schema
+    /// and name.
+    pub fn publications_of<'a>(
+        &'a self,
+        schema: &'a str,
+        table: &'a str,
+    ) -> impl Iterator<Item = &'a Publication> + 'a {
+        self.publications
+            .iter()
+            .filter(move |publication| publication.table(schema, table).is_some())
+    }
+
+    #[must_use]
+    /// Returns the issues found in the replication topology.
+    pub fn findings(&self) -> Vec<ReplicationFinding> {
+        let mut findings = Vec::new();
+        for publication in &self.publications {
+            for table in &publication.tables {
+                if let Some(row_filter) = &table.row_filter
+                    && table.row_filter_expression.is_none()
+                {
+                    findings.push(ReplicationFinding::UnparsableRowFilter {
+                        publication: publication.name.clone(),
+                        schema: table.schema.clone(),
+                        table: table.table.clone(),
+                        row_filter: row_filter.clone(),
+                    });
+                }
+                if !publication.updates && !publication.deletes {
+                    continue;
+                }
+                let Some(relation) = self.relations.iter().find(|relation| {
+                    relation.schema_name == table.schema && relation.table_name == table.table
+                }) else {
+                    continue;
+                };
+                if !relation.identifies_rows() {
+                    findings.push(ReplicationFinding::MissingReplicaIdentity {
+                        publication: publication.name.clone(),
+                        schema: table.schema.clone(),
+                        table: table.table.clone(),
+                        identity: relation.identity(),
+                    });
+                }
+            }
+        }
+        for slot in &self.slots {
+            if slot.is_lost() {
+                findings.push(ReplicationFinding::LostSlot {
+                    slot: slot.name().to_owned(),
+                });
+            } else if !slot.is_active() {
+                findings.push(ReplicationFinding::InactiveSlot {
+                    slot: slot.name().to_owned(),
+                    retained_bytes: slot.retained_bytes,
+                });
+            }
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relation(
+        oid: u32,
+        table_name: &str,
+        identity: &str,
+        primary_key: bool,
+    ) -> ReplicatedRelation {
+        ReplicatedRelation {
+            relation_oid: RelOid::new(oid),
+            schema_oid: 2200,
+            schema_name: "public".to_owned(),
+            table_name: table_name.to_owned(),
+            replica_identity: identity.to_owned(),
+            has_primary_key: primary_key,
+            replica_identity_index: None,
+        }
+    }
+
+    fn publication_table(table: &str, row_filter: Option<&str>) -> PgPublicationTable {
+        PgPublicationTable {
+            pubname: Some("orders_pub".to_owned()),
+            schemaname: Some("public".to_owned()),
+            tablename: Some(table.to_owned()),
+            #[cfg(not(feature = "postgres-14"))]
+            attnames: Some(vec!["id".to_owned(), "total".to_owned()]),
+            #[cfg(not(feature = "postgres-14"))]
+            rowfilter: row_filter.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn test_lsn() {
+        assert_eq!(format_lsn(PgLsn(0x16_B374_D848)), "16/B374D848");
+        assert_eq!(format_lsn(PgLsn(0)), "0/0");
+        assert_eq!(lsn_lag(PgLsn(0x2_0000_0000), PgLsn(0x1_FFFF_FF00)), 256);
+        assert_eq!(lsn_lag(PgLsn(1), PgLsn(2)), 0);
+    }
+
+    #[test]
+    fn test_topology() {
+        let relations = vec![
+            relation(1, "orders", "d", true),
+            relation(2, "order_events", "d", false),
+            relation(3, "audit_log", "f", false),
+        ];
+        let publications = Publication::resolve(
+            &[PgPublication {
+                oid: 10,
+                pubname: "orders_pub".to_owned(),
+                pubowner: 10,
+                puballtables: false,
+                pubinsert: true,
+                pubupdate: true,
+                pubdelete: true,
+                pubtruncate: true,
+                pubviaroot: false,
+                #[cfg(feature = "postgres-18")]
+                pubgencols: false,
+            }],
+            &[PgPublicationRel {
+                oid: 20,
+                prpubid: 10,
+                prrelid: 1,
+                #[cfg(not(feature = "postgres-14"))]
+                prattrs: Some(vec![1, 2]),
+                #[cfg(not(feature = "postgres-14"))]
+                prqual: None,
+            }],
+            &[PgPublicationNamespace {
+                oid: 30,
+                pnpubid: 10,
+                pnnspid: 2200,
+            }],
+            &[
+                publication_table("orders", Some("(total > (100)::numeric)")),
+                publication_table("order_events", None),
+                publication_table("audit_log", Some("status = ANY (")),
+            ],
+            &relations,
+        );
+        let publication = &publications[0];
+        assert_eq!(publication.schemas(), ["public"]);
+        let orders = publication.table("public", "orders").expect("published");
+        assert_eq!(orders.source(), PublicationSource::Table);
+        assert_eq!(
+            publication
+                .table("public", "order_events")
+                .map(PublishedTable::source),
+            Some(PublicationSource::Schema)
+        );
+        #[cfg(not(feature = "postgres-14"))]
+        {
+            assert_eq!(orders.columns().map(<[String]>::len), Some(2));
+            assert!(orders.row_filter_expression().is_some());
+            assert_eq!(
+                publication
+                    .table("public", "order_events")
+                    .and_then(PublishedTable::columns),
+                None
+            );
+        }
+
+        let subscriptions = Subscription::resolve(&[], &[], &relations);
+        let topology = ReplicationTopology::new(
+            relations,
+            publications,
+            subscriptions,
+            Vec::new(),
+            Vec::new(),
+            None,
+        );
+        assert_eq!(topology.publications_of("public", "audit_log").count(), 1);
+        let findings = topology.findings();
+        assert!(
+            findings.contains(&ReplicationFinding::MissingReplicaIdentity {
+                publication: "orders_pub".to_owned(),
+                schema: "public".to_owned(),
+                table: "order_events".to_owned(),
+                identity: Some(ReplicaIdentity::Default),
+            })
+        );
+        assert!(!findings.iter().any(|finding| matches!(
+            finding,
+            ReplicationFinding::MissingReplicaIdentity { table, .. } if table != "order_events"
+        )));
+        #[cfg(not(feature = "postgres-14"))]
+        assert!(findings.iter().any(|finding| matches!(
+            finding,
+            ReplicationFinding::UnparsableRowFilter { table, .. } if table == "audit_log"
+        )));
+    }
+}
@@ -0,0 +1,717 @@
+//! Submodule auditing the roles, grants, row-level security and functions of
+//! a database for common security issues.
+//!
+//! The [`SecurityAudit`] reports:
+//!
+//! - relations and columns granted to `PUBLIC`;
+//! - tables with policies but row-level security disabled;
+//! - tables with row-level security enabled but not forced;
+//! - `SECURITY DEFINER` functions whose `search_path` is not pinned to
+//!   schemas that only trusted roles can write to, with `pg_temp` last;
+//! - superuser and `BYPASSRLS` roles which can log in;
+//! - objects owned by roles which can log in.
+//!
+//! The audit covers the objects loaded by the
+//! [`PgDieselDatabaseBuilder`](crate::database::PgDieselDatabaseBuilder):
+//! the tables and views of its schemas, their grants to `PUBLIC` and
+//! policies, the functions of its schemas and those they reference outside
+//! of the system schemas, and the roles of the cluster.
+//!
+//! Each [`SecurityFinding`] carries the [`SecurityRule`] it violates, whose
+//! identifier is stable across releases: known exceptions can be suppressed
+//! by rule, or by rule and object, with [`SecurityAudit::suppress`] and
+//! [`SecurityAudit::suppress_rule`], so that a CI job only fails on new
+//! findings.
+
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use sql_traits::traits::DatabaseLike;
+
+use crate::{PgDieselDatabase, models::PgRole, severity::Severity, traits::HasQualifiedName};
+
+/// The schemas of the server itself, whose objects are not audited.
+const SYSTEM_SCHEMAS: [&str; 2] = ["pg_catalog", "information_schema"];
+
+/// The schemas of a `search_path` which untrusted roles may create objects
+/// in: `public`, writable by every role before `PostgreSQL` 15, and `$user`,
+/// which resolves to the schema named after the calling role.
+const WRITABLE_SCHEMAS: [&str; 2] = ["public", "$user"];
+
+/// The name of the pseudo-role of the grants to every role.
+const PUBLIC: &str = "PUBLIC";
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+/// Errors that can occur when parsing a [`SecurityRule`].
+pub enum SecurityRuleError {
+    #[error("Unknown security rule `{0}`")]
+    /// The identifier does not belong to any rule.
+    UnknownRule(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A check of the [`SecurityAudit`], identified by a stable identifier.
+pub enum SecurityRule {
+    /// `SEC001`: a relation or some of its columns are granted to `PUBLIC`.
+    PublicGrant,
+    /// `SEC002`: a table has policies, but row-level security is disabled,
+    /// so that the policies are not enforced.
+    PoliciesWithoutRowSecurity,
+    /// `SEC003`: a table has row-level security enabled but not forced, so
+    /// that its owner bypasses the policies.
+    RowSecurityNotForced,
+    /// `SEC004`: a `SECURITY DEFINER` function does not pin its
+    /// `search_path` to schemas that only trusted roles can write to, with
+    /// `pg_temp` last, so that its callers may hijack the objects it uses.
+    UnpinnedSecurityDefiner,
+    /// `SEC005`: a superuser or `BYPASSRLS` role can log in.
+    PrivilegedLoginRole,
+    /// `SEC006`: an object is owned by a role which can log in, rather than
+    /// by a dedicated `NOLOGIN` role.
+    OwnedByLoginRole,
+}
+
+impl SecurityRule {
+    /// All the rules, in the order of their identifiers.
+    pub const ALL: [Self; 6] = [
+        Self::PublicGrant,
+        Self::PoliciesWithoutRowSecurity,
+        Self::RowSecurityNotForced,
+        Self::UnpinnedSecurityDefiner,
+        Self::PrivilegedLoginRole,
+        Self::OwnedByLoginRole,
+    ];
+
+    #[must_use]
+    /// Returns the stable identifier of the rule, as in `SEC001`.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::PublicGrant => "SEC001",
+            Self::PoliciesWithoutRowSecurity => "SEC002",
+            Self::RowSecurityNotForced => "SEC003",
+            Self::UnpinnedSecurityDefiner => "SEC004",
+            Self::PrivilegedLoginRole => "SEC005",
+            Self::OwnedByLoginRole => "SEC006",
+        }
+    }
+
+    #[must_use]
+    /// Returns how urgently the findings of the rule should be acted upon.
+    pub fn severity(self) -> Severity {
+        match self {
+            Self::PoliciesWithoutRowSecurity | Self::UnpinnedSecurityDefiner => Severity::Critical,
+            Self::PublicGrant | Self::PrivilegedLoginRole => Severity::Warning,
+            Self::RowSecurityNotForced | Self::OwnedByLoginRole => Severity::Info,
+        }
+    }
+}
+
+impl Display for SecurityRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl FromStr for SecurityRule {
+    type Err = SecurityRuleError;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|rule| rule.id().eq_ignore_ascii_case(id.trim()))
+            .ok_or_else(|| SecurityRuleError::UnknownRule(id.to_owned()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The ownership, privileges and row-level security of a table or view.
+struct RelationSecurity {
+    /// The name of the relation, qualified by its schema.
+    name: String,
+    /// Whether the relation is a table, which row-level security applies
+    /// to.
+    is_table: bool,
+    /// The OID of the owner of the relation.
+    owner: u32,
+    /// Whether row-level security is enabled.
+    row_security: bool,
+    /// Whether row-level security also applies to the owner.
+    forced_row_security: bool,
+    /// The number of policies defined on the relation.
+    policies: usize,
+    /// The privileges granted to `PUBLIC` on the relation, followed by those
+    /// granted on some of its columns only, as in `UPDATE (email)`.
+    public_privileges: Vec<String>,
+}
+
+impl RelationSecurity {
+    /// Returns the security of the tables and views of the database.
+    ///
+    /// # Panics
+    ///
+    /// * If the metadata of a table of the database is missing, which should
+    ///   not happen if the database was built by
+    ///   [`PgDieselDatabaseBuilder`](crate::database::PgDieselDatabaseBuilder).
+    fn load_all(database: &PgDieselDatabase) -> Vec<Self> {
+        database
+            .tables()
+            .map(|table| {
+                let metadata = database
+                    .table_metadata(table)
+                    .expect("Table must exist in database");
+                let granted_to_public =
+                    |grantee: Option<&str>, schema: Option<&str>, name: Option<&str>| {
+                        grantee == Some(PUBLIC)
+                            && schema == Some(table.table_schema.as_str())
+                            && name == Some(table.table_name.as_str())
+                    };
+                let mut public_privileges: Vec<String> = database
+                    .table_grants()
+                    .filter(|grant| {
+                        granted_to_public(
+                            grant.grantee.as_deref(),
+                            grant.table_schema.as_deref(),
+                            grant.table_name.as_deref(),
+                        )
+                    })
+                    .filter_map(|grant| grant.privilege_type.clone())
+                    .collect();
+                public_privileges.sort();
+                public_privileges.dedup();
+                // The column grants also list the privileges granted on the
+                // whole table, once per column.
+                let mut public_column_privileges: Vec<String> = database
+                    .column_grants()
+                    .filter(|grant| {
+                        granted_to_public(
+                            grant.grantee.as_deref(),
+                            grant.table_schema.as_deref(),
+                            grant.table_name.as_deref(),
+                        )
+                    })
+                    .filter_map(|grant| {
+                        let privilege = grant.privilege_type.as_deref()?;
+                        let column = grant.column_name.as_deref()?;
+                        (!public_privileges.iter().any(|granted| granted == privilege))
+                            .then(|| format!("{privilege} ({column})"))
+                    })
+                    .collect();
+                public_column_privileges.sort();
+                public_column_privileges.dedup();
+                public_privileges.extend(public_column_privileges);
+                Self {
+                    name: table.qualified_name().to_string(),
+                    is_table: table.table_type == "BASE TABLE",
+                    owner: metadata.owner(),
+                    row_security: metadata.row_security(),
+                    forced_row_security: metadata.forced_row_security(),
+                    policies: metadata.policies().count(),
+                    public_privileges,
+                }
+            })
+            .collect()
+    }
+
+    /// Reports the violations of the rules by the relation.
+    fn audit(
+        &self,
+        login_roles: &HashMap<u32, &str>,
+        report: &mut impl FnMut(SecurityRule, String, String),
+    ) {
+        if !self.public_privileges.is_empty() {
+            report(
+                SecurityRule::PublicGrant,
+                self.name.clone(),
+                format!("{} granted to PUBLIC", self.public_privileges.join(", ")),
+            );
+        }
+        if self.is_table && self.policies > 0 && !self.row_security {
+            report(
+                SecurityRule::PoliciesWithoutRowSecurity,
+                self.name.clone(),
+                format!(
+                    "{} policies defined, but row-level security is disabled",
+                    self.policies
+                ),
+            );
+        }
+        if self.is_table && self.row_security && !self.forced_row_security {
+            report(
+                SecurityRule::RowSecurityNotForced,
+                self.name.clone(),
+                "row-level security is not forced, so the owner bypasses the policies".to_owned(),
+            );
+        }
+        if let Some(owner) = login_roles.get(&self.owner) {
+            report(
+                SecurityRule::OwnedByLoginRole,
+                self.name.clone(),
+                format!("owned by the login role `{owner}`"),
+            );
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The ownership and execution context of a function.
+struct FunctionSecurity {
+    /// The name of the function, qualified by its schema and followed by the
+    /// types of its arguments, which tell overloads apart.
+    name: String,
+    /// The OID of the owner of the function.
+    owner: u32,
+    /// Whether the function runs with the privileges of its owner.
+    security_definer: bool,
+    /// The `search_path` set on the function, if any, as in
+    /// `pg_catalog, pg_temp`.
+    search_path: Option<String>,
+}
+
+impl FunctionSecurity {
+    /// Returns the security of the functions of the database, outside of the
+    /// system schemas.
+    ///
+    /// # Panics
+    ///
+    /// * If the metadata of a function of the database is missing, which
+    ///   should not happen if the database was built by
+    ///   [`PgDieselDatabaseBuilder`](crate::database::PgDieselDatabaseBuilder).
+    fn load_all(database: &PgDieselDatabase) -> Vec<Self> {
+        database
+            .functions()
+            .filter_map(|function| {
+                let metadata = database
+                    .function_metadata(function)
+                    .expect("Function must exist in database");
+                let name = metadata.qualified_name();
+                if SYSTEM_SCHEMAS.contains(&name.schema()) || name.schema().starts_with("pg_toast")
+                {
+                    return None;
+                }
+                let arguments: Vec<&str> = metadata
+                    .argument_types()
+                    .iter()
+                    .map(|argument| argument.typname.as_str())
+                    .collect();
+                Some(Self {
+                    name: format!("{name}({})", arguments.join(", ")),
+                    owner: function.proowner,
+                    security_definer: function.prosecdef,
+                    search_path: function
+                        .proconfig
+                        .iter()
+                        .flatten()
+                        .find_map(|setting| setting.strip_prefix("search_path="))
+                        .map(str::to_owned),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns whether the `search_path` of the function is pinned: set,
+    /// without schemas which untrusted roles may write to, and with
+    /// `pg_temp` last, as the temporary schema of the caller is otherwise
+    /// searched first.
+    fn pins_search_path(&self) -> bool {
+        let Some(search_path) = &self.search_path else {
+            return false;
+        };
+        let schemas: Vec<&str> = search_path
+            .split(',')
+            .map(|schema| schema.trim().trim_matches('"'))
+            .collect();
+        schemas.last() == Some(&"pg_temp")
+            && !schemas
+                .iter()
+                .any(|schema| WRITABLE_SCHEMAS.contains(schema))
+    }
+
+    /// Reports the violations of the rules by the function.
+    fn audit(
+        &self,
+        login_roles: &HashMap<u32, &str>,
+        report: &mut impl FnMut(SecurityRule, String, String),
+    ) {
+        if self.security_definer && !self.pins_search_path() {
+            report(
+                SecurityRule::UnpinnedSecurityDefiner,
+                self.name.clone(),
+                match &self.search_path {
+                    None => "SECURITY DEFINER function without a pinned search_path".to_owned(),
+                    Some(search_path) => format!(
+                        "SECURITY DEFINER function with the search_path `{search_path}`, \
+                         which has writable schemas or does not end with pg_temp"
+                    ),
+                },
+            );
+        }
+        if let Some(owner) = login_roles.get(&self.owner) {
+            report(
+                SecurityRule::OwnedByLoginRole,
+                self.name.clone(),
+                format!("owned by the login role `{owner}`"),
+            );
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A violation of a [`SecurityRule`] by an object of the database.
+pub struct SecurityFinding {
+    /// The violated rule.
+    rule: SecurityRule,
+    /// The name of the object, qualified by its schema unless it is a role,
+    /// and followed by its arguments if it is a function, as in
+    /// `app.login(text)`.
+    object: String,
+    /// The description of the violation.
+    message: String,
+}
+
+impl SecurityFinding {
+    /// Returns the violated rule.
+    #[must_use]
+    pub fn rule(&self) -> SecurityRule {
+        self.rule
+    }
+
+    /// Returns the stable identifier of the violated rule, as in `SEC001`.
+    #[must_use]
+    pub fn rule_id(&self) -> &'static str {
+        self.rule.id()
+    }
+
+    /// Returns how urgently the finding should be acted upon.
+    #[must_use]
+    pub fn severity(&self) -> Severity {
+        self.rule.severity()
+    }
+
+    /// Returns the name of the object, qualified by its schema unless it is
+    /// a role, and followed by its arguments if it is a function, as in
+    /// `app.login(text)`.
+    #[must_use]
+    pub fn object(&self) -> &str {
+        &self.object
+    }
+
+    /// Returns the description of the violation.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for SecurityFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} [{}] {}: {}",
+            self.rule,
+            self.severity(),
+            self.object,
+            self.message
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The security findings of a database, with their suppressions.
+pub struct SecurityAudit {
+    /// All the findings, sorted by rule and object.
+    findings: Vec<SecurityFinding>,
+    /// The suppressed rules, for any object when the object is absent.
+    suppressions: Vec<(SecurityRule, Option<String>)>,
+}
+
+impl SecurityAudit {
+    #[must_use]
+    /// Audits the roles, tables, views and functions of the database.
+    ///
+    /// # Panics
+    ///
+    /// * If the metadata of a table or function of the database is missing,
+    ///   which should not happen if the database was built by
+    ///   [`PgDieselDatabaseBuilder`](crate::database::PgDieselDatabaseBuilder).
+    pub fn new(database: &PgDieselDatabase) -> Self {
+        let roles: Vec<&PgRole> = database.roles().collect();
+        Self::from_parts(
+            &roles,
+            &RelationSecurity::load_all(database),
+            &FunctionSecurity::load_all(database),
+        )
+    }
+
+    /// Audits the provided roles, relations and functions.
+    fn from_parts(
+        roles: &[&PgRole],
+        relations: &[RelationSecurity],
+        functions: &[FunctionSecurity],
+    ) -> Self {
+        let login_roles: HashMap<u32, &str> = roles
+            .iter()
+            .filter(|role| role.rolcanlogin == Some(true))
+            .filter_map(|role| Some((role.oid?, role.name())))
+            .collect();
+        let mut findings = Vec::new();
+        let mut report = |rule, object: String, message: String| {
+            findings.push(SecurityFinding {
+                rule,
+                object,
+                message,
+            });
+        };
+
+        for relation in relations {
+            relation.audit(&login_roles, &mut report);
+        }
+
+        for function in functions {
+            function.audit(&login_roles, &mut report);
+        }
+
+        for role in roles.iter().filter(|role| role.rolcanlogin == Some(true)) {
+            let privileges: Vec<&str> = [
+                (role.rolsuper, "SUPERUSER"),
+                (role.rolbypassrls, "BYPASSRLS"),
+            ]
+            .into_iter()
+            .filter(|(granted, _)| *granted == Some(true))
+            .map(|(_, privilege)| privilege)
+            .collect();
+            if !privileges.is_empty() {
+                report(
+                    SecurityRule::PrivilegedLoginRole,
+                    role.name().to_owned(),
+                    format!("{} role can log in", privileges.join(" and ")),
+                );
+            }
+        }
+
+        findings.sort_by(|left, right| (left.rule, &left.object).cmp(&(right.rule, &right.object)));
+        Self {
+            findings,
+            suppressions: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    /// Suppresses the findings of the rule for the provided object, as
+    /// reported by [`SecurityFinding::object`].
+    pub fn suppress<S: AsRef<str>>(mut self, rule: SecurityRule, object: S) -> Self {
+        self.suppressions
+            .push((rule, Some(object.as_ref().to_string())));
+        self
+    }
+
+    #[must_use]
+    /// Suppresses all the findings of the rule.
+    pub fn suppress_rule(mut self, rule: SecurityRule) -> Self {
+        self.suppressions.push((rule, None));
+        self
+    }
+
+    /// Returns whether the finding is suppressed.
+    fn is_suppressed(&self, finding: &SecurityFinding) -> bool {
+        self.suppressions.iter().any(|(rule, object)| {
+            *rule == finding.rule
+                && object
+                    .as_ref()
+                    .is_none_or(|object| *object == finding.object)
+        })
+    }
+
+    /// Returns the findings which are not suppressed, sorted by rule and
+    /// object.
+    pub fn findings(&self) -> impl Iterator<Item = &SecurityFinding> {
+        self.findings
+            .iter()
+            .filter(|finding| !self.is_suppressed(finding))
+    }
+
+    /// Returns the suppressed findings, sorted by rule and object.
+    pub fn suppressed(&self) -> impl Iterator<Item = &SecurityFinding> {
+        self.findings
+            .iter()
+            .filter(|finding| self.is_suppressed(finding))
+    }
+
+    /// Returns the suppressions matching no finding, which may be removed.
+    pub fn unused_suppressions(&self) -> impl Iterator<Item = (SecurityRule, Option<&str>)> {
+        self.suppressions
+            .iter()
+            .filter(|(rule, object)| {
+                !self.findings.iter().any(|finding| {
+                    finding.rule == *rule
+                        && object
+                            .as_ref()
+                            .is_none_or(|object| *object == finding.object)
+                })
+            })
+            .map(|(rule, object)| (*rule, object.as_deref()))
+    }
+
+    #[must_use]
+    /// Returns whether any finding which is not suppressed has at least the
+    /// provided severity, as a CI job would check.
+    pub fn fails(&self, severity: Severity) -> bool {
+        self.findings()
+            .any(|finding| finding.severity() >= severity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a role with the provided OID, name and attributes.
+    fn role(oid: u32, name: &str, can_login: bool, superuser: bool, bypass_rls: bool) -> PgRole {
+        PgRole {
+            rolname: Some(name.to_owned()),
+            rolsuper: Some(superuser),
+            rolinherit: Some(true),
+            rolcreaterole: Some(false),
+            rolcreatedb: Some(false),
+            rolcanlogin: Some(can_login),
+            rolreplication: Some(false),
+            rolconnlimit: Some(-1),
+            rolpassword: None,
+            rolvaliduntil: None,
+            rolbypassrls: Some(bypass_rls),
+            rolconfig: None,
+            oid: Some(oid),
+        }
+    }
+
+    /// Returns a table of the `public` schema owned by the provided role.
+    fn table(
+        name: &str,
+        owner: u32,
+        policies: usize,
+        row_security: (bool, bool),
+    ) -> RelationSecurity {
+        RelationSecurity {
+            name: format!("public.{name}"),
+            is_table: true,
+            owner,
+            row_security: row_security.0,
+            forced_row_security: row_security.1,
+            policies,
+            public_privileges: Vec::new(),
+        }
+    }
+
+    /// Returns a function of the `app` schema owned by the provided role.
+    fn function(
+        name: &str,
+        owner: u32,
+        security_definer: bool,
+        search_path: Option<&str>,
+    ) -> FunctionSecurity {
+        FunctionSecurity {
+            name: format!("app.{name}"),
+            owner,
+            security_definer,
+            search_path: search_path.map(str::to_owned),
+        }
+    }
+
+    /// Returns the audit of the test database.
+    fn audit() -> SecurityAudit {
+        let roles = [
+            role(10, "postgres", true, true, false),
+            role(20, "owner", false, false, false),
+            role(30, "alice", true, false, false),
+            role(40, "etl", true, false, true),
+        ];
+        let mut accounts = table("accounts", 20, 2, (false, false));
+        accounts.public_privileges = vec!["SELECT".to_owned(), "UPDATE (email)".to_owned()];
+        let relations = [
+            accounts,
+            table("invoices", 20, 1, (true, false)),
+            table("orders", 30, 1, (true, true)),
+        ];
+        let functions = [
+            function("audit_login()", 20, true, None),
+            function("audit_login(int4)", 20, true, None),
+            function("rotate_keys()", 20, true, Some("pg_catalog, pg_temp")),
+            function("cleanup()", 30, false, None),
+            function("version()", 10, true, None),
+        ];
+        SecurityAudit::from_parts(&roles.iter().collect::<Vec<_>>(), &relations, &functions)
+    }
+
+    #[test]
+    fn test_findings() {
+        let findings: Vec<String> = audit().findings().map(ToString::to_string).collect();
+        assert_eq!(
+            findings,
+            [
+                "SEC001 [warning] public.accounts: SELECT, UPDATE (email) granted to PUBLIC",
+                "SEC002 [critical] public.accounts: 2 policies defined, but row-level security is disabled",
+                "SEC003 [info] public.invoices: row-level security is not forced, so the owner bypasses the policies",
+                "SEC004 [critical] app.audit_login(): SECURITY DEFINER function without a pinned search_path",
+                "SEC004 [critical] app.audit_login(int4): SECURITY DEFINER function without a pinned search_path",
+                "SEC004 [critical] app.version(): SECURITY DEFINER function without a pinned search_path",
+                "SEC005 [warning] etl: BYPASSRLS role can log in",
+                "SEC005 [warning] postgres: SUPERUSER role can log in",
+                "SEC006 [info] app.cleanup(): owned by the login role `alice`",
+                "SEC006 [info] app.version(): owned by the login role `postgres`",
+                "SEC006 [info] public.orders: owned by the login role `alice`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suppressions() {
+        let audit = audit()
+            .suppress("SEC005".parse().unwrap(), "postgres")
+            .suppress(SecurityRule::UnpinnedSecurityDefiner, "app.missing")
+            .suppress_rule(SecurityRule::OwnedByLoginRole);
+        assert_eq!(audit.findings().count(), 7);
+        assert_eq!(audit.suppressed().count(), 4);
+        assert_eq!(
+            audit.unused_suppressions().collect::<Vec<_>>(),
+            [(SecurityRule::UnpinnedSecurityDefiner, Some("app.missing"))]
+        );
+        assert!(audit.fails(Severity::Critical));
+        let audit = audit
+            .suppress_rule(SecurityRule::PoliciesWithoutRowSecurity)
+            .suppress_rule(SecurityRule::UnpinnedSecurityDefiner);
+        assert!(!audit.fails(Severity::Critical));
+        assert!(audit.fails(Severity::Warning));
+        assert!(matches!(
+            "SEC999".parse::<SecurityRule>(),
+            Err(SecurityRuleError::UnknownRule(_))
+        ));
+    }
+
+    #[test]
+    fn test_pinned_search_path() {
+        let pins =
+            |search_path| function("login()", 20, true, Some(search_path)).pins_search_path();
+        assert!(pins("pg_catalog, pg_temp"));
+        assert!(pins("app, pg_catalog, \"pg_temp\""));
+        assert!(!pins("public"));
+        assert!(!pins("pg_catalog, public, pg_temp"));
+        assert!(!pins("\"$user\", pg_catalog, pg_temp"));
+        assert!(!pins("pg_temp, pg_catalog"));
+        assert!(!pins("pg_catalog"));
+        assert!(!function("login()", 20, true, None).pins_search_path());
+
+        let audit =
+            SecurityAudit::from_parts(&[], &[], &[function("login()", 20, true, Some("public"))]);
+        assert_eq!(
+            audit
+                .findings()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "SEC004 [critical] app.login(): SECURITY DEFINER function with the search_path \
+                 `public`, which has writable schemas or does not end with pg_temp"
+            ]
+        );
+    }
+}
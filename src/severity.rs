@@ -0,0 +1,27 @@
+//! Submodule providing the [`Severity`] of the findings reported by the
+//! [`IndexAdvisor`](crate::index_advisor::IndexAdvisor) and the
+//! [`SecurityAudit`](crate::security_audit::SecurityAudit).
+
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// How urgently a finding should be acted upon.
+pub enum Severity {
+    /// Worth a look, but possibly intended.
+    Info,
+    /// Most likely wasting resources or slowing queries down.
+    Warning,
+    /// Broken, and costing resources without any benefit.
+    Critical,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        })
+    }
+}
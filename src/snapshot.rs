@@ -0,0 +1,1187 @@
+//! Submodule capturing the tables of a [`PgDieselDatabase`] in an offline
+//! snapshot.
+//!
+//! A [`SchemaSnapshot`] holds, for each table and view, its columns with
+//! their types, nullability, defaults and comments, its primary key,
+//! unique, check and foreign key constraints and its rewrite rules, along
+//! with the event triggers of the database when taken from a
+//! [`PgDieselDatabaseWithEventTriggers`]. Unlike the database it is taken
+//! from, a snapshot owns plain data which, with the `serde` feature, can be
+//! written to disk and read back to work without a connection: two snapshots,
+//! of two databases or of one database over time, are compared with
+//! [`SchemaSnapshot::diff`], and Diesel schemas and models are generated
+//! from a snapshot with [`SchemaSnapshot::diesel_schema`] and
+//! [`SchemaSnapshot::diesel_models`].
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use sql_traits::traits::DatabaseLike;
+
+use crate::{
+    PgDieselDatabase,
+    database::PgDieselDatabaseWithEventTriggers,
+    erd::type_name,
+    model_metadata::{EventTriggerMetadata, RuleMetadata},
+    models::{Column, Table},
+    quoting::{quote_identifier, quote_identifiers},
+};
+
+mod codegen;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A column of a [`TableSnapshot`].
+pub struct ColumnSnapshot {
+    /// The name of the column.
+    name: String,
+    /// The type of the column, with arrays written as `element[]`.
+    data_type: String,
+    /// Whether the column is nullable.
+    nullable: bool,
+    /// The default expression of the column, if any.
+    default: Option<String>,
+    /// The kind of identity of the column, `ALWAYS` or `BY DEFAULT`, if it
+    /// is an identity column.
+    identity: Option<String>,
+    /// The generation expression of the column, if it is a generated
+    /// column.
+    generated: Option<String>,
+    /// The comment on the column.
+    description: Option<String>,
+}
+
+impl ColumnSnapshot {
+    /// Returns the name of the column.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the type of the column, with arrays written as `element[]`.
+    #[must_use]
+    pub fn data_type(&self) -> &str {
+        &self.data_type
+    }
+
+    /// Returns whether the column is nullable.
+    #[must_use]
+    pub fn is_nullable(&self) -> bool {
+        self.nullable
+    }
+
+    /// Returns the default expression of the column, if any.
+    #[must_use]
+    pub fn default(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    /// Returns the kind of identity of the column, `ALWAYS` or `BY DEFAULT`,
+    /// if it is an identity column.
+    #[must_use]
+    pub fn identity(&self) -> Option<&str> {
+        self.identity.as_deref()
+    }
+
+    /// Returns the generation expression of the column, if it is a
+    /// generated column.
+    #[must_use]
+    pub fn generated(&self) -> Option<&str> {
+        self.generated.as_deref()
+    }
+
+    /// Returns the comment on the column.
+    #[must_use]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the attributes of the column compared by
+    /// [`SchemaSnapshot::diff`], by name.
+    fn attributes(&self) -> [(&'static str, Option<String>); 6] {
+        [
+            ("type", Some(self.data_type.clone())),
+            ("nullable", Some(self.nullable.to_string())),
+            ("default", self.default.clone()),
+            ("identity", self.identity.clone()),
+            ("generated", self.generated.clone()),
+            ("description", self.description.clone()),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A foreign key of a [`TableSnapshot`].
+pub struct ForeignKeySnapshot {
+    /// The name of the constraint.
+    name: String,
+    /// The referencing columns.
+    columns: Vec<String>,
+    /// The schema and name of the referenced table.
+    referenced: (String, String),
+    /// The referenced columns.
+    referenced_columns: Vec<String>,
+    /// The action taken when a referenced row is updated.
+    update_rule: String,
+    /// The action taken when a referenced row is deleted.
+    delete_rule: String,
+}
+
+impl ForeignKeySnapshot {
+    /// Returns the name of the constraint.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the referencing columns.
+    #[must_use]
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Returns the schema and name of the referenced table.
+    #[must_use]
+    pub fn referenced(&self) -> (&str, &str) {
+        (&self.referenced.0, &self.referenced.1)
+    }
+
+    /// Returns the referenced columns.
+    #[must_use]
+    pub fn referenced_columns(&self) -> &[String] {
+        &self.referenced_columns
+    }
+
+    /// Returns the SQL definition of the constraint.
+    #[must_use]
+    pub fn definition(&self) -> String {
+        format!(
+            "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {}.{} ({}) ON UPDATE {} ON DELETE {}",
+            quote_identifier(&self.name),
+            quote_identifiers(&self.columns),
+            quote_identifier(&self.referenced.0),
+            quote_identifier(&self.referenced.1),
+            quote_identifiers(&self.referenced_columns),
+            self.update_rule,
+            self.delete_rule
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A rewrite rule of a [`TableSnapshot`].
+pub struct RuleSnapshot {
+    /// The name of the rule.
+    name: String,
+    /// The event the rule applies to, as in `INSERT`.
+    event: String,
+    /// Whether the rule replaces the original statement.
+    instead: bool,
+    /// When the rule fires, as in `origin` or `disabled`.
+    firing_mode: String,
+    /// The definition of the rule, as returned by `pg_get_ruledef`.
+    definition: String,
+}
+
+impl From<&RuleMetadata> for RuleSnapshot {
+    fn from(rule: &RuleMetadata) -> Self {
+        Self {
+            name: rule.name().to_owned(),
+            event: rule.event().to_string(),
+            instead: rule.is_instead(),
+            firing_mode: rule.firing_mode().to_string(),
+            definition: rule.definition().to_owned(),
+        }
+    }
+}
+
+impl RuleSnapshot {
+    /// Returns the name of the rule.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the event the rule applies to, as in `INSERT`.
+    #[must_use]
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+
+    /// Returns whether the rule replaces the original statement rather than
+    /// running alongside it.
+    #[must_use]
+    pub fn is_instead(&self) -> bool {
+        self.instead
+    }
+
+    /// Returns when the rule fires, as in `origin` or `disabled`.
+    #[must_use]
+    pub fn firing_mode(&self) -> &str {
+        &self.firing_mode
+    }
+
+    /// Returns the definition of the rule, as returned by `pg_get_ruledef`.
+    #[must_use]
+    pub fn definition(&self) -> &str {
+        &self.definition
+    }
+
+    /// Returns the attributes of the rule compared by
+    /// [`SchemaSnapshot::diff`], by name.
+    fn attributes(&self) -> [(&'static str, String); 2] {
+        [
+            ("firing mode", self.firing_mode.clone()),
+            ("definition", self.definition.clone()),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An event trigger of a [`SchemaSnapshot`].
+pub struct EventTriggerSnapshot {
+    /// The name of the event trigger.
+    name: String,
+    /// The event firing the event trigger, as in `ddl_command_start`.
+    event: String,
+    /// The command tags the event trigger is restricted to.
+    tags: Vec<String>,
+    /// The qualified name of the function executed by the event trigger.
+    function: String,
+    /// When the event trigger fires, as in `origin` or `disabled`.
+    firing_mode: String,
+}
+
+impl From<&EventTriggerMetadata> for EventTriggerSnapshot {
+    fn from(event_trigger: &EventTriggerMetadata) -> Self {
+        Self {
+            name: event_trigger.name().to_owned(),
+            event: event_trigger.event().to_owned(),
+            tags: event_trigger.tags().to_vec(),
+            function: event_trigger.function_name().to_string(),
+            firing_mode: event_trigger.firing_mode().to_string(),
+        }
+    }
+}
+
+impl EventTriggerSnapshot {
+    /// Returns the name of the event trigger.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the event firing the event trigger, as in
+    /// `ddl_command_start`.
+    #[must_use]
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+
+    /// Returns the command tags the event trigger is restricted to, empty if
+    /// it fires for every command.
+    #[must_use]
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns the qualified name of the function executed by the event
+    /// trigger.
+    #[must_use]
+    pub fn function(&self) -> &str {
+        &self.function
+    }
+
+    /// Returns when the event trigger fires, as in `origin` or `disabled`.
+    #[must_use]
+    pub fn firing_mode(&self) -> &str {
+        &self.firing_mode
+    }
+
+    /// Returns the attributes of the event trigger compared by
+    /// [`SchemaSnapshot::diff`], by name.
+    fn attributes(&self) -> [(&'static str, String); 4] {
+        [
+            ("event", self.event.clone()),
+            (
+                "tags",
+                if self.tags.is_empty() {
+                    "(all)".to_owned()
+                } else {
+                    self.tags.join(", ")
+                },
+            ),
+            ("function", self.function.clone()),
+            ("firing mode", self.firing_mode.clone()),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A table or view of a [`SchemaSnapshot`].
+pub struct TableSnapshot {
+    /// The schema of the table.
+    schema: String,
+    /// The name of the table.
+    name: String,
+    /// The type of the table, as in `BASE TABLE` or `VIEW`.
+    kind: String,
+    /// The comment on the table.
+    description: Option<String>,
+    /// The columns of the table, in order.
+    columns: Vec<ColumnSnapshot>,
+    /// The columns of the primary key, in order.
+    primary_key: Vec<String>,
+    /// The columns of the unique indexes other than the primary key.
+    unique: Vec<Vec<String>>,
+    /// The names and clauses of the check constraints.
+    checks: Vec<(String, String)>,
+    /// The foreign keys of the table, sorted by name.
+    foreign_keys: Vec<ForeignKeySnapshot>,
+    /// The rewrite rules of the table, sorted by name.
+    #[cfg_attr(feature = "serde", serde(default))]
+    rules: Vec<RuleSnapshot>,
+}
+
+impl TableSnapshot {
+    /// Returns the snapshot of the table.
+    fn new(database: &PgDieselDatabase, table: &Table) -> Self {
+        let metadata = database
+            .table_metadata(table)
+            .expect("Table must exist in database");
+        let mut columns: Vec<&Column> = metadata.columns().collect();
+        columns.sort_by_key(|column| column.ordinal_position);
+        let column_name = |attnum: &i16| {
+            columns
+                .iter()
+                .find(|column| i32::from(*attnum) == column.ordinal_position)
+                .map(|column| column.column_name.clone())
+        };
+        let mut unique: Vec<Vec<String>> = metadata
+            .unique_indices()
+            .filter(|index| !index.indisprimary)
+            .filter_map(|index| index.indkey.iter().map(column_name).collect())
+            .collect();
+        unique.sort();
+        let mut checks: Vec<(String, String)> = metadata
+            .check_constraints()
+            .filter(|check| {
+                !(check.constraint_name.ends_with("_not_null")
+                    && check.check_clause.ends_with(" IS NOT NULL"))
+            })
+            .map(|check| {
+                (
+                    check.constraint_name.clone(),
+                    check.check_clause.trim().to_owned(),
+                )
+            })
+            .collect();
+        checks.sort();
+        let mut foreign_keys: Vec<ForeignKeySnapshot> = metadata
+            .foreign_keys()
+            .map(|foreign_key| {
+                let foreign_key_metadata = database
+                    .foreign_key_metadata(foreign_key)
+                    .expect("Foreign key must exist in database");
+                let referenced = foreign_key_metadata.referenced_table();
+                let rule = foreign_key_metadata.referential_constraint();
+                ForeignKeySnapshot {
+                    name: foreign_key.constraint_name.clone(),
+                    columns: foreign_key_metadata
+                        .host_columns()
+                        .iter()
+                        .map(|column| column.column_name.clone())
+                        .collect(),
+                    referenced: (
+                        referenced.table_schema.clone(),
+                        referenced.table_name.clone(),
+                    ),
+                    referenced_columns: foreign_key_metadata
+                        .referenced_columns()
+                        .iter()
+                        .map(|column| column.column_name.clone())
+                        .collect(),
+                    update_rule: rule.update_rule.clone(),
+                    delete_rule: rule.delete_rule.clone(),
+                }
+            })
+            .collect();
+        foreign_keys.sort_by(|left, right| left.name.cmp(&right.name));
+        Self {
+            schema: table.table_schema.clone(),
+            name: table.table_name.clone(),
+            kind: table.table_type.clone(),
+            description: metadata
+                .description()
+                .map(|description| description.description.clone()),
+            primary_key: metadata
+                .primary_key_columns()
+                .map(|column| column.column_name.clone())
+                .collect(),
+            columns: columns
+                .into_iter()
+                .map(|column| {
+                    let column_metadata = database
+                        .column_metadata(column)
+                        .expect("Column must exist in database");
+                    ColumnSnapshot {
+                        name: column.column_name.clone(),
+                        data_type: type_name(column_metadata.pg_type()),
+                        nullable: column.__is_nullable == "YES",
+                        default: column.column_default.clone(),
+                        identity: column
+                            .identity_generation
+                            .clone()
+                            .filter(|_| column.is_identity.as_deref() == Some("YES")),
+                        generated: column
+                            .generation_expression
+                            .clone()
+                            .filter(|_| column.is_generated == "ALWAYS"),
+                        description: column_metadata
+                            .description()
+                            .map(|description| description.description.clone()),
+                    }
+                })
+                .collect(),
+            unique,
+            checks,
+            foreign_keys,
+            rules: metadata.rules().map(RuleSnapshot::from).collect(),
+        }
+    }
+
+    /// Returns the schema of the table.
+    #[must_use]
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// Returns the name of the table.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the type of the table, as in `BASE TABLE` or `VIEW`.
+    #[must_use]
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Returns the comment on the table.
+    #[must_use]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the columns of the table, in order.
+    #[must_use]
+    pub fn columns(&self) -> &[ColumnSnapshot] {
+        &self.columns
+    }
+
+    /// Returns the column with the provided name, if any.
+    #[must_use]
+    pub fn column(&self, name: &str) -> Option<&ColumnSnapshot> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// Returns the columns of the primary key, in order.
+    #[must_use]
+    pub fn primary_key(&self) -> &[String] {
+        &self.primary_key
+    }
+
+    /// Returns the foreign keys of the table, sorted by name.
+    #[must_use]
+    pub fn foreign_keys(&self) -> &[ForeignKeySnapshot] {
+        &self.foreign_keys
+    }
+
+    /// Returns the rewrite rules of the table, sorted by name.
+    #[must_use]
+    pub fn rules(&self) -> &[RuleSnapshot] {
+        &self.rules
+    }
+
+    /// Returns the rewrite rule with the provided name, if any.
+    #[must_use]
+    pub fn rule(&self, name: &str) -> Option<&RuleSnapshot> {
+        self.rules.iter().find(|rule| rule.name == name)
+    }
+
+    /// Returns the qualified name of the table, as in `public.users`.
+    #[must_use]
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.schema, self.name)
+    }
+
+    /// Returns the SQL definitions of the constraints of the table: the
+    /// primary key, then the unique, check and foreign key constraints.
+    #[must_use]
+    pub fn constraints(&self) -> Vec<String> {
+        let primary_key = (!self.primary_key.is_empty())
+            .then(|| format!("PRIMARY KEY ({})", quote_identifiers(&self.primary_key)));
+        primary_key
+            .into_iter()
+            .chain(
+                self.unique
+                    .iter()
+                    .map(|columns| format!("UNIQUE ({})", quote_identifiers(columns))),
+            )
+            .chain(self.checks.iter().map(|(name, clause)| {
+                if clause.starts_with('(') {
+                    format!("CONSTRAINT {} CHECK {clause}", quote_identifier(name))
+                } else {
+                    format!("CONSTRAINT {} CHECK ({clause})", quote_identifier(name))
+                }
+            }))
+            .chain(self.foreign_keys.iter().map(ForeignKeySnapshot::definition))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A difference between two [`SchemaSnapshot`]s, from the left one to the
+/// right one.
+pub enum SchemaChange {
+    /// The table is only in the right snapshot.
+    TableAdded {
+        /// The schema of the table.
+        schema: String,
+        /// The name of the table.
+        table: String,
+    },
+    /// The table is only in the left snapshot.
+    TableRemoved {
+        /// The schema of the table.
+        schema: String,
+        /// The name of the table.
+        table: String,
+    },
+    /// An attribute of the table, its `kind` or `description`, differs.
+    TableChanged {
+        /// The schema of the table.
+        schema: String,
+        /// The name of the table.
+        table: String,
+        /// The name of the attribute.
+        attribute: String,
+        /// The value of the attribute in the left snapshot.
+        left: Option<String>,
+        /// The value of the attribute in the right snapshot.
+        right: Option<String>,
+    },
+    /// The column is only in the right snapshot.
+    ColumnAdded {
+        /// The schema of the table.
+        schema: String,
+        /// The name of the table.
+        table: String,
+        /// The name of the column.
+        column: String,
+    },
+    /// The column is only in the left snapshot.
+    ColumnRemoved {
+        /// The schema of the table.
+        schema: String,
+        /// The name of the table.
+        table: String,
+        /// The name of the column.
+        column: String,
+    },
+    /// An attribute of the column, such as its `type` or `default`, differs.
+    ColumnChanged {
+        /// The schema of the table.
+        schema: String,
+        /// The name of the table.
+        table: String,
+        /// The name of the column.
+        column: String,
+        /// The name of the attribute.
+        attribute: String,
+        /// The value of the attribute in the left snapshot.
+        left: Option<String>,
+        /// The value of the attribute in the right snapshot.
+        right: Option<String>,
+    },
+    /// The constraint is only in the right snapshot.
+    ConstraintAdded {
+        /// The schema of the table.
+        schema: String,
+        /// The name of the table.
+        table: String,
+        /// The SQL definition of the constraint.
+        definition: String,
+    },
+    /// The constraint is only in the left snapshot.
+    ConstraintRemoved {
+        /// The schema of the table.
+        schema: String,
+        /// The name of the table.
+        table: String,
+        /// The SQL definition of the constraint.
+        definition: String,
+    },
+    /// The rewrite rule is only in the right snapshot.
+    RuleAdded {
+        /// The schema of the table.
+        schema: String,
+        /// The name of the table.
+        table: String,
+        /// The name of the rule.
+        rule: String,
+    },
+    /// The rewrite rule is only in the left snapshot.
+    RuleRemoved {
+        /// The schema of the table.
+        schema: String,
+        /// The name of the table.
+        table: String,
+        /// The name of the rule.
+        rule: String,
+    },
+    /// An attribute of the rewrite rule, its `firing mode` or `definition`,
+    /// differs.
+    RuleChanged {
+        /// The schema of the table.
+        schema: String,
+        /// The name of the table.
+        table: String,
+        /// The name of the rule.
+        rule: String,
+        /// The name of the attribute.
+        attribute: String,
+        /// The value of the attribute in the left snapshot.
+        left: String,
+        /// The value of the attribute in the right snapshot.
+        right: String,
+    },
+    /// The event trigger is only in the right snapshot.
+    EventTriggerAdded {
+        /// The name of the event trigger.
+        name: String,
+    },
+    /// The event trigger is only in the left snapshot.
+    EventTriggerRemoved {
+        /// The name of the event trigger.
+        name: String,
+    },
+    /// An attribute of the event trigger, such as its `event` or
+    /// `function`, differs.
+    EventTriggerChanged {
+        /// The name of the event trigger.
+        name: String,
+        /// The name of the attribute.
+        attribute: String,
+        /// The value of the attribute in the left snapshot.
+        left: String,
+        /// The value of the attribute in the right snapshot.
+        right: String,
+    },
+}
+
+impl Display for SchemaChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "(none)".to_owned());
+        match self {
+            Self::TableAdded { schema, table } => write!(f, "+ table {schema}.{table}"),
+            Self::TableRemoved { schema, table } => write!(f, "- table {schema}.{table}"),
+            Self::TableChanged {
+                schema,
+                table,
+                attribute,
+                left,
+                right,
+            } => write!(
+                f,
+                "~ table {schema}.{table}: {attribute} {} -> {}",
+                value(left),
+                value(right)
+            ),
+            Self::ColumnAdded {
+                schema,
+                table,
+                column,
+            } => write!(f, "+ column {schema}.{table}.{column}"),
+            Self::ColumnRemoved {
+                schema,
+                table,
+                column,
+            } => write!(f, "- column {schema}.{table}.{column}"),
+            Self::ColumnChanged {
+                schema,
+                table,
+                column,
+                attribute,
+                left,
+                right,
+            } => write!(
+                f,
+                "~ column {schema}.{table}.{column}: {attribute} {} -> {}",
+                value(left),
+                value(right)
+            ),
+            Self::ConstraintAdded {
+                schema,
+                table,
+                definition,
+            } => write!(f, "+ constraint {schema}.{table}: {definition}"),
+            Self::ConstraintRemoved {
+                schema,
+                table,
+                definition,
+            } => write!(f, "- constraint {schema}.{table}: {definition}"),
+            Self::RuleAdded {
+                schema,
+                table,
+                rule,
+            } => write!(f, "+ rule {schema}.{table}.{rule}"),
+            Self::RuleRemoved {
+                schema,
+                table,
+                rule,
+            } => write!(f, "- rule {schema}.{table}.{rule}"),
+            Self::RuleChanged {
+                schema,
+                table,
+                rule,
+                attribute,
+                left,
+                right,
+            } => write!(
+                f,
+                "~ rule {schema}.{table}.{rule}: {attribute} {left} -> {right}"
+            ),
+            Self::EventTriggerAdded { name } => write!(f, "+ event trigger {name}"),
+            Self::EventTriggerRemoved { name } => write!(f, "- event trigger {name}"),
+            Self::EventTriggerChanged {
+                name,
+                attribute,
+                left,
+                right,
+            } => write!(f, "~ event trigger {name}: {attribute} {left} -> {right}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An offline snapshot of the tables of a database.
+pub struct SchemaSnapshot {
+    /// The tables of the snapshot, sorted by schema and name.
+    tables: Vec<TableSnapshot>,
+    /// The event triggers of the database, sorted by name, or `None` if the
+    /// snapshot was taken without them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    event_triggers: Option<Vec<EventTriggerSnapshot>>,
+}
+
+impl From<&PgDieselDatabaseWithEventTriggers> for SchemaSnapshot {
+    /// Takes the snapshot of the tables and views of the database and of its
+    /// event triggers.
+    fn from(database: &PgDieselDatabaseWithEventTriggers) -> Self {
+        Self {
+            event_triggers: Some(
+                database
+                    .event_triggers()
+                    .iter()
+                    .map(EventTriggerSnapshot::from)
+                    .collect(),
+            ),
+            ..Self::new(database)
+        }
+    }
+}
+
+impl SchemaSnapshot {
+    #[must_use]
+    /// Takes the snapshot of the tables and views of the database, without
+    /// its event triggers, which the database does not load.
+    pub fn new(database: &PgDieselDatabase) -> Self {
+        let mut tables: Vec<TableSnapshot> = database
+            .tables()
+            .map(|table| TableSnapshot::new(database, table))
+            .collect();
+        tables.sort_by(|left, right| (&left.schema, &left.name).cmp(&(&right.schema, &right.name)));
+        Self {
+            tables,
+            event_triggers: None,
+        }
+    }
+
+    /// Returns the tables of the snapshot, sorted by schema and name.
+    #[must_use]
+    pub fn tables(&self) -> &[TableSnapshot] {
+        &self.tables
+    }
+
+    /// Returns the table with the provided schema and name, if any.
+    #[must_use]
+    pub fn table(&self, schema: &str, name: &str) -> Option<&TableSnapshot> {
+        self.tables
+            .iter()
+            .find(|table| table.schema == schema && table.name == name)
+    }
+
+    /// Returns the event triggers of the snapshot, sorted by name, or `None`
+    /// if the snapshot was taken without them.
+    #[must_use]
+    pub fn event_triggers(&self) -> Option<&[EventTriggerSnapshot]> {
+        self.event_triggers.as_deref()
+    }
+
+    #[must_use]
+    /// Returns the differences from this snapshot to the other one, sorted by
+    /// table: added and removed tables, changed table attributes, added,
+    /// removed and changed columns, added and removed constraints, and
+    /// added, removed and changed rewrite rules, followed by the added,
+    /// removed and changed event triggers.
+    ///
+    /// The event triggers are only compared when both snapshots were taken
+    /// with them, so that a snapshot taken without them does not report
+    /// every event trigger as removed or added.
+    ///
+    /// A constraint whose definition changed is reported as removed and
+    /// added.
+    pub fn diff(&self, other: &Self) -> Vec<SchemaChange> {
+        let left: BTreeMap<(&str, &str), &TableSnapshot> = self
+            .tables
+            .iter()
+            .map(|table| ((table.schema(), table.name()), table))
+            .collect();
+        let right: BTreeMap<(&str, &str), &TableSnapshot> = other
+            .tables
+            .iter()
+            .map(|table| ((table.schema(), table.name()), table))
+            .collect();
+        let keys: BTreeSet<(&str, &str)> = left.keys().chain(right.keys()).copied().collect();
+        let mut changes = Vec::new();
+        for (schema, table) in keys {
+            let (schema_name, table_name) = (schema.to_owned(), table.to_owned());
+            match (left.get(&(schema, table)), right.get(&(schema, table))) {
+                (Some(_), None) => changes.push(SchemaChange::TableRemoved {
+                    schema: schema_name,
+                    table: table_name,
+                }),
+                (None, Some(_)) => changes.push(SchemaChange::TableAdded {
+                    schema: schema_name,
+                    table: table_name,
+                }),
+                (Some(left), Some(right)) => left.diff(right, &mut changes),
+                (None, None) => {}
+            }
+        }
+        if let (Some(left), Some(right)) = (&self.event_triggers, &other.event_triggers) {
+            EventTriggerSnapshot::diff_all(left, right, &mut changes);
+        }
+        changes
+    }
+}
+
+impl EventTriggerSnapshot {
+    /// Pushes the differences from the event triggers to the other ones.
+    fn diff_all(left: &[Self], right: &[Self], changes: &mut Vec<SchemaChange>) {
+        for event_trigger in left {
+            let Some(other_trigger) = right
+                .iter()
+                .find(|other_trigger| other_trigger.name == event_trigger.name)
+            else {
+                changes.push(SchemaChange::EventTriggerRemoved {
+                    name: event_trigger.name.clone(),
+                });
+                continue;
+            };
+            for ((attribute, left), (_, right)) in event_trigger
+                .attributes()
+                .into_iter()
+                .zip(other_trigger.attributes())
+            {
+                if left != right {
+                    changes.push(SchemaChange::EventTriggerChanged {
+                        name: event_trigger.name.clone(),
+                        attribute: attribute.to_owned(),
+                        left,
+                        right,
+                    });
+                }
+            }
+        }
+        for event_trigger in right {
+            if !left
+                .iter()
+                .any(|own_trigger| own_trigger.name == event_trigger.name)
+            {
+                changes.push(SchemaChange::EventTriggerAdded {
+                    name: event_trigger.name.clone(),
+                });
+            }
+        }
+    }
+}
+
+impl TableSnapshot {
+    /// Pushes the differences from this table to the other one.
+    #[allow(clippy::too_many_lines)]
+    fn diff(&self, other: &Self, changes: &mut Vec<SchemaChange>) {
+        let schema = || self.schema.clone();
+        let table = || self.name.clone();
+        for (attribute, left, right) in [
+            ("kind", Some(&self.kind), Some(&other.kind)),
+            (
+                "description",
+                self.description.as_ref(),
+                other.description.as_ref(),
+            ),
+        ] {
+            if left != right {
+                changes.push(SchemaChange::TableChanged {
+                    schema: schema(),
+                    table: table(),
+                    attribute: attribute.to_owned(),
+                    left: left.cloned(),
+                    right: right.cloned(),
+                });
+            }
+        }
+        for column in &self.columns {
+            let Some(other_column) = other.column(&column.name) else {
+                changes.push(SchemaChange::ColumnRemoved {
+                    schema: schema(),
+                    table: table(),
+                    column: column.name.clone(),
+                });
+                continue;
+            };
+            for ((attribute, left), (_, right)) in column
+                .attributes()
+                .into_iter()
+                .zip(other_column.attributes())
+            {
+                if left != right {
+                    changes.push(SchemaChange::ColumnChanged {
+                        schema: schema(),
+                        table: table(),
+                        column: column.name.clone(),
+                        attribute: attribute.to_owned(),
+                        left,
+                        right,
+                    });
+                }
+            }
+        }
+        for column in &other.columns {
+            if self.column(&column.name).is_none() {
+                changes.push(SchemaChange::ColumnAdded {
+                    schema: schema(),
+                    table: table(),
+                    column: column.name.clone(),
+                });
+            }
+        }
+        let left: BTreeSet<String> = self.constraints().into_iter().collect();
+        let right: BTreeSet<String> = other.constraints().into_iter().collect();
+        for definition in left.difference(&right) {
+            changes.push(SchemaChange::ConstraintRemoved {
+                schema: schema(),
+                table: table(),
+                definition: definition.clone(),
+            });
+        }
+        for definition in right.difference(&left) {
+            changes.push(SchemaChange::ConstraintAdded {
+                schema: schema(),
+                table: table(),
+                definition: definition.clone(),
+            });
+        }
+        for rule in &self.rules {
+            let Some(other_rule) = other.rule(&rule.name) else {
+                changes.push(SchemaChange::RuleRemoved {
+                    schema: schema(),
+                    table: table(),
+                    rule: rule.name.clone(),
+                });
+                continue;
+            };
+            for ((attribute, left), (_, right)) in
+                rule.attributes().into_iter().zip(other_rule.attributes())
+            {
+                if left != right {
+                    changes.push(SchemaChange::RuleChanged {
+                        schema: schema(),
+                        table: table(),
+                        rule: rule.name.clone(),
+                        attribute: attribute.to_owned(),
+                        left,
+                        right,
+                    });
+                }
+            }
+        }
+        for rule in &other.rules {
+            if self.rule(&rule.name).is_none() {
+                changes.push(SchemaChange::RuleAdded {
+                    schema: schema(),
+                    table: table(),
+                    rule: rule.name.clone(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(super) fn column(name: &str, data_type: &str, nullable: bool) -> ColumnSnapshot {
+        ColumnSnapshot {
+            name: name.to_owned(),
+            data_type: data_type.to_owned(),
+            nullable,
+            default: None,
+            identity: None,
+            generated: None,
+            description: None,
+        }
+    }
+
+    pub(super) fn snapshot() -> SchemaSnapshot {
+        let mut id = column("id", "int4", false);
+        id.identity = Some("ALWAYS".to_owned());
+        let users = TableSnapshot {
+            schema: "public".to_owned(),
+            name: "users".to_owned(),
+            kind: "BASE TABLE".to_owned(),
+            description: Some("Registered users.".to_owned()),
+            columns: vec![
+                id,
+                column("email", "text", false),
+                column("tags", "text[]", true),
+            ],
+            primary_key: vec!["id".to_owned()],
+            unique: vec![vec!["email".to_owned()]],
+            checks: vec![("email_check".to_owned(), "(email <> ''::text)".to_owned())],
+            foreign_keys: Vec::new(),
+            rules: vec![RuleSnapshot {
+                name: "protect_users".to_owned(),
+                event: "DELETE".to_owned(),
+                instead: true,
+                firing_mode: "origin".to_owned(),
+                definition: "CREATE RULE protect_users AS\n    ON DELETE TO public.users DO \
+                             INSTEAD NOTHING;"
+                    .to_owned(),
+            }],
+        };
+        let posts = TableSnapshot {
+            schema: "public".to_owned(),
+            name: "posts".to_owned(),
+            kind: "BASE TABLE".to_owned(),
+            description: None,
+            columns: vec![
+                column("id", "int8", false),
+                column("author_id", "int4", false),
+                column("type", "varchar", true),
+            ],
+            primary_key: vec!["id".to_owned()],
+            unique: Vec::new(),
+            checks: Vec::new(),
+            foreign_keys: vec![ForeignKeySnapshot {
+                name: "posts_author_id_fkey".to_owned(),
+                columns: vec!["author_id".to_owned()],
+                referenced: ("public".to_owned(), "users".to_owned()),
+                referenced_columns: vec!["id".to_owned()],
+                update_rule: "NO ACTION".to_owned(),
+                delete_rule: "CASCADE".to_owned(),
+            }],
+            rules: Vec::new(),
+        };
+        SchemaSnapshot {
+            tables: vec![posts, users],
+            event_triggers: Some(vec![EventTriggerSnapshot {
+                name: "audit_ddl".to_owned(),
+                event: "ddl_command_end".to_owned(),
+                tags: vec!["CREATE TABLE".to_owned()],
+                function: "audit.log_ddl".to_owned(),
+                firing_mode: "origin".to_owned(),
+            }]),
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let left = snapshot();
+        assert!(left.diff(&left).is_empty());
+
+        let mut right = snapshot();
+        right.tables.remove(0);
+        let users = &mut right.tables[0];
+        users.columns[1].data_type = "varchar".to_owned();
+        users.columns[2].default = Some("'{}'::text[]".to_owned());
+        users.columns.push(column("name", "text", true));
+        users.unique.clear();
+        users.description = None;
+        users.rules[0].firing_mode = "disabled".to_owned();
+        users.rules.push(RuleSnapshot {
+            name: "log_users".to_owned(),
+            event: "INSERT".to_owned(),
+            instead: false,
+            firing_mode: "origin".to_owned(),
+            definition: "CREATE RULE log_users AS\n    ON INSERT TO public.users DO  INSERT INTO \
+                         log (id) VALUES (new.id);"
+                .to_owned(),
+        });
+        let event_triggers = right
+            .event_triggers
+            .as_mut()
+            .expect("Missing event triggers");
+        event_triggers[0].tags.clear();
+        event_triggers.push(EventTriggerSnapshot {
+            name: "forbid_drop".to_owned(),
+            event: "sql_drop".to_owned(),
+            tags: Vec::new(),
+            function: "audit.forbid_drop".to_owned(),
+            firing_mode: "always".to_owned(),
+        });
+
+        let changes: Vec<String> = left.diff(&right).iter().map(ToString::to_string).collect();
+        assert_eq!(
+            changes,
+            [
+                "- table public.posts",
+                "~ table public.users: description Registered users. -> (none)",
+                "~ column public.users.email: type text -> varchar",
+                "~ column public.users.tags: default (none) -> '{}'::text[]",
+                "+ column public.users.name",
+                "- constraint public.users: UNIQUE (\"email\")",
+                "~ rule public.users.protect_users: firing mode origin -> disabled",
+                "+ rule public.users.log_users",
+                "~ event trigger audit_ddl: tags CREATE TABLE -> (all)",
+                "+ event trigger forbid_drop",
+            ]
+        );
+
+        let without_event_triggers = SchemaSnapshot {
+            event_triggers: None,
+            ..snapshot()
+        };
+        assert!(left.diff(&without_event_triggers).is_empty());
+        assert!(without_event_triggers.diff(&right).len() < changes.len());
+        assert_eq!(
+            left.tables()[0].constraints(),
+            [
+                "PRIMARY KEY (\"id\")",
+                "CONSTRAINT \"posts_author_id_fkey\" FOREIGN KEY (\"author_id\") REFERENCES \
+                 \"public\".\"users\" (\"id\") ON UPDATE NO ACTION ON DELETE CASCADE",
+            ]
+        );
+    }
+}
@@ -0,0 +1,445 @@
+//! Submodule generating Diesel schemas and models from a [`SchemaSnapshot`],
+//! in the layout of `diesel print-schema`.
+//!
+//! The tables and models of the `public` schema are written at the top level
+//! and those of the other schemas in a module named after their schema, so
+//! that tables of the same name in different schemas do not collide. Tables without
+//! a primary key, such as views, and columns of types without a Diesel
+//! mapping, such as user-defined enums, are left out with a comment. The
+//! enabled rewrite rules of a table are listed in the doc comment of its
+//! `table!` macro, as they change what its `INSERT`, `UPDATE` and `DELETE`
+//! statements do.
+
+use std::fmt::Write;
+
+use super::{ColumnSnapshot, SchemaSnapshot, TableSnapshot};
+use crate::quoting::rust_identifier;
+
+/// The schema whose tables are written at the top level.
+const DEFAULT_SCHEMA: &str = "public";
+
+/// Returns the Diesel SQL type and the Rust type of the provided type, or
+/// `None` if it has no Diesel mapping.
+fn scalar_types(data_type: &str) -> Option<(&'static str, &'static str)> {
+    Some(match data_type {
+        "bool" => ("Bool", "bool"),
+        "char" => ("CChar", "i8"),
+        "int2" => ("Int2", "i16"),
+        "int4" => ("Int4", "i32"),
+        "int8" => ("Int8", "i64"),
+        "oid" => ("Oid", "u32"),
+        "float4" => ("Float4", "f32"),
+        "float8" => ("Float8", "f64"),
+        "numeric" => ("Numeric", "bigdecimal::BigDecimal"),
+        "money" => ("Money", "diesel::data_types::PgMoney"),
+        "text" | "name" | "citext" => ("Text", "String"),
+        "varchar" => ("Varchar", "String"),
+        "bpchar" => ("Bpchar", "String"),
+        "bytea" => ("Bytea", "Vec<u8>"),
+        "date" => ("Date", "chrono::NaiveDate"),
+        "time" => ("Time", "chrono::NaiveTime"),
+        "timestamp" => ("Timestamp", "chrono::NaiveDateTime"),
+        "timestamptz" => ("Timestamptz", "chrono::DateTime<chrono::Utc>"),
+        "interval" => ("Interval", "diesel::pg::data_types::PgInterval"),
+        "uuid" => ("Uuid", "uuid::Uuid"),
+        "json" => ("Json", "serde_json::Value"),
+        "jsonb" => ("Jsonb", "serde_json::Value"),
+        "inet" => ("Inet", "ipnetwork::IpNetwork"),
+        "cidr" => ("Cidr", "ipnetwork::IpNetwork"),
+        "macaddr" => ("MacAddr", "[u8; 6]"),
+        _ => return None,
+    })
+}
+
+/// Returns the Diesel SQL type and the Rust type of the column, or `None` if
+/// its type has no Diesel mapping.
+fn column_types(column: &ColumnSnapshot) -> Option<(String, String)> {
+    let (sql_type, rust_type) = if let Some(element) = column.data_type.strip_suffix("[]") {
+        let (sql_type, rust_type) = scalar_types(element)?;
+        (
+            format!("Array<Nullable<{sql_type}>>"),
+            format!("Vec<Option<{rust_type}>>"),
+        )
+    } else {
+        let (sql_type, rust_type) = scalar_types(&column.data_type)?;
+        (sql_type.to_owned(), rust_type.to_owned())
+    };
+    Some(if column.nullable {
+        (
+            format!("Nullable<{sql_type}>"),
+            format!("Option<{rust_type}>"),
+        )
+    } else {
+        (sql_type, rust_type)
+    })
+}
+
+/// Returns the name of the struct of the rows of the table, in
+/// `PascalCase`.
+fn struct_name(table: &str) -> String {
+    let name: String = table
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("Table{name}")
+    } else {
+        name
+    }
+}
+
+/// Writes the doc comment, one line per line of the comment, with the
+/// provided indentation.
+fn write_doc(code: &mut String, indent: &str, description: Option<&str>) {
+    for line in description.into_iter().flat_map(str::lines) {
+        let _ = writeln!(
+            code,
+            "{indent}///{}{}",
+            if line.is_empty() { "" } else { " " },
+            line
+        );
+    }
+}
+
+impl TableSnapshot {
+    /// Returns whether a Diesel schema can be generated for the table.
+    fn is_generated(&self) -> bool {
+        !self.primary_key.is_empty()
+            && self.primary_key.iter().all(|name| {
+                self.column(name)
+                    .is_some_and(|column| column_types(column).is_some())
+            })
+    }
+
+    /// Returns the doc comment of the `table!` macro of the table: its
+    /// comment followed by its enabled rewrite rules.
+    fn table_doc(&self) -> Option<String> {
+        let rules: Vec<String> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.firing_mode != "disabled")
+            .map(|rule| {
+                format!(
+                    "Rewrite rule `{}`: `ON {} DO {}`.",
+                    rule.name,
+                    rule.event,
+                    if rule.instead { "INSTEAD" } else { "ALSO" }
+                )
+            })
+            .collect();
+        match (&self.description, rules.is_empty()) {
+            (description, true) => description.clone(),
+            (None, false) => Some(rules.join("\n")),
+            (Some(description), false) => Some(format!("{description}\n\n{}", rules.join("\n"))),
+        }
+    }
+
+    /// Writes the `table!` macro of the table, with the provided
+    /// indentation.
+    fn write_table(&self, code: &mut String, indent: &str) {
+        let _ = writeln!(code, "{indent}diesel::table! {{");
+        write_doc(code, &format!("{indent}    "), self.table_doc().as_deref());
+        let name = rust_identifier(&self.name);
+        if name != self.name {
+            let _ = writeln!(code, "{indent}    #[sql_name = {:?}]", self.name);
+        }
+        let schema = if self.schema == DEFAULT_SCHEMA {
+            String::new()
+        } else {
+            format!("{}.", rust_identifier(&self.schema))
+        };
+        let primary_key: Vec<String> = self
+            .primary_key
+            .iter()
+            .map(|column| rust_identifier(column))
+            .collect();
+        let _ = writeln!(
+            code,
+            "{indent}    {schema}{name} ({}) {{",
+            primary_key.join(", ")
+        );
+        for column in &self.columns {
+            let Some((sql_type, _)) = column_types(column) else {
+                let _ = writeln!(
+                    code,
+                    "{indent}        // `{}` of type `{}` has no Diesel mapping.",
+                    column.name, column.data_type
+                );
+                continue;
+            };
+            write_doc(
+                code,
+                &format!("{indent}        "),
+                column.description.as_deref(),
+            );
+            let field = rust_identifier(&column.name);
+            if field != column.name {
+                let _ = writeln!(code, "{indent}        #[sql_name = {:?}]", column.name);
+            }
+            let _ = writeln!(code, "{indent}        {field} -> {sql_type},");
+        }
+        let _ = writeln!(code, "{indent}    }}\n{indent}}}");
+    }
+
+    /// Writes the model struct of the rows of the table, whose schema is in
+    /// the provided module, with the provided indentation.
+    fn write_model(&self, code: &mut String, indent: &str, module: &str) {
+        let _ = writeln!(
+            code,
+            "{indent}#[derive(Debug, Clone, PartialEq, Queryable, Selectable, Identifiable)]\n\
+             {indent}#[diesel(table_name = {module}::{})]",
+            rust_identifier(&self.name)
+        );
+        if self.primary_key != ["id"] {
+            let primary_key: Vec<String> = self
+                .primary_key
+                .iter()
+                .map(|column| rust_identifier(column))
+                .collect();
+            let _ = writeln!(
+                code,
+                "{indent}#[diesel(primary_key({}))]",
+                primary_key.join(", ")
+            );
+        }
+        let _ = writeln!(code, "{indent}#[diesel(check_for_backend(diesel::pg::Pg))]");
+        write_doc(code, indent, self.description.as_deref());
+        let _ = writeln!(code, "{indent}pub struct {} {{", struct_name(&self.name));
+        for column in &self.columns {
+            let Some((_, rust_type)) = column_types(column) else {
+                continue;
+            };
+            write_doc(
+                code,
+                &format!("{indent}    "),
+                column.description.as_deref(),
+            );
+            let _ = writeln!(
+                code,
+                "{indent}    pub {}: {rust_type},",
+                rust_identifier(&column.name)
+            );
+        }
+        let _ = writeln!(code, "{indent}}}");
+    }
+}
+
+impl SchemaSnapshot {
+    /// Returns the generated tables, grouped by schema with the default
+    /// schema first, and the tables left out.
+    fn generated_tables(&self) -> (Vec<(&str, Vec<&TableSnapshot>)>, Vec<&TableSnapshot>) {
+        let mut schemas: Vec<(&str, Vec<&TableSnapshot>)> = Vec::new();
+        let mut skipped = Vec::new();
+        for table in &self.tables {
+            if !table.is_generated() {
+                skipped.push(table);
+            } else if let Some((_, tables)) = schemas
+                .iter_mut()
+                .find(|(schema, _)| *schema == table.schema)
+            {
+                tables.push(table);
+            } else {
+                schemas.push((&table.schema, vec![table]));
+            }
+        }
+        schemas.sort_by_key(|(schema, _)| (*schema != DEFAULT_SCHEMA, *schema));
+        (schemas, skipped)
+    }
+
+    #[must_use]
+    /// Returns the Diesel schema of the tables of the snapshot: a `table!`
+    /// macro per table, a `joinable!` macro per single-column foreign key
+    /// referencing the primary key of a table of the same schema, and an
+    /// `allow_tables_to_appear_in_same_query!` macro per schema.
+    pub fn diesel_schema(&self) -> String {
+        let (schemas, skipped) = self.generated_tables();
+        let mut code = String::from("// @generated by pg_diesel.\n");
+        for table in skipped {
+            let _ = writeln!(
+                code,
+                "// `{}` is left out as it has no primary key of supported types.",
+                table.qualified_name()
+            );
+        }
+        for (schema, tables) in schemas {
+            let indent = if schema == DEFAULT_SCHEMA { "" } else { "    " };
+            code.push('\n');
+            if schema != DEFAULT_SCHEMA {
+                let _ = writeln!(code, "pub mod {} {{", rust_identifier(schema));
+            }
+            for (position, table) in tables.iter().enumerate() {
+                if position > 0 {
+                    code.push('\n');
+                }
+                table.write_table(&mut code, indent);
+            }
+            let mut joinables: Vec<(String, String, String)> = Vec::new();
+            for table in &tables {
+                for foreign_key in &table.foreign_keys {
+                    let (referenced_schema, referenced_name) = foreign_key.referenced();
+                    let joinable = referenced_schema == schema
+                        && foreign_key.columns.len() == 1
+                        && tables.iter().any(|referenced| {
+                            referenced.name == referenced_name
+                                && referenced.primary_key == foreign_key.referenced_columns
+                        });
+                    let pair = (
+                        rust_identifier(&table.name),
+                        rust_identifier(referenced_name),
+                    );
+                    if joinable
+                        && !joinables
+                            .iter()
+                            .any(|(child, parent, _)| (child, parent) == (&pair.0, &pair.1))
+                    {
+                        joinables.push((pair.0, pair.1, rust_identifier(&foreign_key.columns[0])));
+                    }
+                }
+            }
+            if !joinables.is_empty() {
+                code.push('\n');
+            }
+            for (child, parent, column) in joinables {
+                let _ = writeln!(
+                    code,
+                    "{indent}diesel::joinable!({child} -> {parent} ({column}));"
+                );
+            }
+            if tables.len() > 1 {
+                let names: Vec<String> = tables
+                    .iter()
+                    .map(|table| rust_identifier(&table.name))
+                    .collect();
+                let _ = writeln!(
+                    code,
+                    "\n{indent}diesel::allow_tables_to_appear_in_same_query!({});",
+                    names.join(", ")
+                );
+            }
+            if schema != DEFAULT_SCHEMA {
+                code.push_str("}\n");
+            }
+        }
+        code
+    }
+
+    #[must_use]
+    /// Returns the Diesel models of the tables of the snapshot: a
+    /// `Queryable`, `Selectable` and `Identifiable` struct per table, nested
+    /// in a module per schema as in [`SchemaSnapshot::diesel_schema`], whose
+    /// schema is expected in the provided absolute module, as in
+    /// `crate::schema`.
+    pub fn diesel_models(&self, schema_module: &str) -> String {
+        let (schemas, _) = self.generated_tables();
+        let mut code = String::from("// @generated by pg_diesel.\n\nuse diesel::prelude::*;\n");
+        for (schema, tables) in schemas {
+            let (indent, module) = if schema == DEFAULT_SCHEMA {
+                ("", schema_module.to_owned())
+            } else {
+                let module = rust_identifier(schema);
+                let _ = writeln!(code, "\npub mod {module} {{\n    use diesel::prelude::*;");
+                ("    ", format!("{schema_module}::{module}"))
+            };
+            for table in tables {
+                code.push('\n');
+                table.write_model(&mut code, indent, &module);
+            }
+            if schema != DEFAULT_SCHEMA {
+                code.push_str("}\n");
+            }
+        }
+        code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::snapshot;
+
+    #[test]
+    fn test_diesel_schema() {
+        assert_eq!(
+            snapshot().diesel_schema(),
+            "// @generated by pg_diesel.\n\
+             \n\
+             diesel::table! {\n    \
+                 posts (id) {\n        \
+                     id -> Int8,\n        \
+                     author_id -> Int4,\n        \
+                     #[sql_name = \"type\"]\n        \
+                     type_ -> Nullable<Varchar>,\n    \
+                 }\n\
+             }\n\
+             \n\
+             diesel::table! {\n    \
+                 /// Registered users.\n    \
+                 ///\n    \
+                 /// Rewrite rule `protect_users`: `ON DELETE DO INSTEAD`.\n    \
+                 users (id) {\n        \
+                     id -> Int4,\n        \
+                     email -> Text,\n        \
+                     tags -> Nullable<Array<Nullable<Text>>>,\n    \
+                 }\n\
+             }\n\
+             \n\
+             diesel::joinable!(posts -> users (author_id));\n\
+             \n\
+             diesel::allow_tables_to_appear_in_same_query!(posts, users);\n"
+        );
+    }
+
+    #[test]
+    fn test_diesel_models() {
+        let models = snapshot().diesel_models("crate::schema");
+        assert!(models.contains(
+            "#[diesel(table_name = crate::schema::posts)]\n\
+             #[diesel(check_for_backend(diesel::pg::Pg))]\n\
+             pub struct Posts {\n    \
+                 pub id: i64,\n    \
+                 pub author_id: i32,\n    \
+                 pub type_: Option<String>,\n\
+             }\n"
+        ));
+        assert!(models.contains(
+            "/// Registered users.\n\
+             pub struct Users {\n    \
+                 pub id: i32,\n    \
+                 pub email: String,\n    \
+                 pub tags: Option<Vec<Option<String>>>,\n\
+             }\n"
+        ));
+    }
+
+    #[test]
+    fn test_diesel_models_of_tables_sharing_a_name() {
+        let mut snapshot = snapshot();
+        let mut audit_users = snapshot.tables[1].clone();
+        audit_users.schema = "audit".to_owned();
+        audit_users.description = None;
+        audit_users.rules.clear();
+        snapshot.tables.push(audit_users);
+        let models = snapshot.diesel_models("crate::schema");
+        assert!(models.contains("#[diesel(table_name = crate::schema::users)]\n"));
+        assert!(models.contains(
+            "\npub mod audit {\n    \
+                 use diesel::prelude::*;\n\
+             \n    \
+                 #[derive(Debug, Clone, PartialEq, Queryable, Selectable, Identifiable)]\n    \
+                 #[diesel(table_name = crate::schema::audit::users)]\n    \
+                 #[diesel(check_for_backend(diesel::pg::Pg))]\n    \
+                 pub struct Users {\n        \
+                     pub id: i32,\n        \
+                     pub email: String,\n        \
+                     pub tags: Option<Vec<Option<String>>>,\n    \
+                 }\n\
+             }\n"
+        ));
+        assert_eq!(models.matches("pub struct Users {").count(), 2);
+    }
+}
@@ -0,0 +1,518 @@
+//! Submodule analyzing the statistics of `pg_stat_statements`.
+//!
+//! A [`StatementSnapshot`] captures the view at a point in time, whose
+//! statements are ranked by time, I/O or rows with [`StatementRanking`].
+//! Since the counters of the view are cumulative, comparing two snapshots
+//! with [`StatementSnapshot::deltas`] yields what happened in between, from
+//! which [`StatementSnapshot::regressions`] reports the statements that
+//! became slower. The [`TableAttributor`] parses the text of the statements to
+//! attribute them, and their cost, to the tables of a [`PgDieselDatabase`].
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::ControlFlow,
+    time::{Duration, SystemTime},
+};
+
+use bigdecimal::ToPrimitive;
+use diesel::{PgConnection, RunQueryDsl, dsl::now};
+use sqlparser::{
+    ast::{Ident, ObjectName, visit_relations},
+    dialect::PostgreSqlDialect,
+    parser::Parser,
+};
+
+use crate::{
+    PgDieselDatabase,
+    database::ObjectIndex,
+    identity::QualifiedName,
+    models::{PgStatStatement, StatementRanking, Table},
+};
+
+/// The columns identifying an entry of `pg_stat_statements`: the user, the
+/// database, the query and whether it was executed at top level.
+type StatementKey = (u32, u32, i64, bool);
+
+/// Returns the key identifying the entry of the statement.
+fn key(statement: &PgStatStatement) -> StatementKey {
+    (
+        statement.userid,
+        statement.dbid,
+        statement.queryid,
+        statement.toplevel,
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The content of `pg_stat_statements` at a point in time.
+pub struct StatementSnapshot {
+    /// When the snapshot was taken, according to the server clock.
+    taken_at: SystemTime,
+    /// The statements of the snapshot.
+    statements: Vec<PgStatStatement>,
+}
+
+impl StatementSnapshot {
+    /// Takes a snapshot of `pg_stat_statements`.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database, including when the
+    ///   `pg_stat_statements` extension is not installed
+    pub fn load(conn: &mut PgConnection) -> Result<Self, diesel::result::Error> {
+        let taken_at: SystemTime = diesel::select(now).get_result(conn)?;
+        Ok(Self::new(PgStatStatement::load_all(conn)?, taken_at))
+    }
+
+    #[must_use]
+    /// Creates a snapshot from rows of `pg_stat_statements`.
+    ///
+    /// # Arguments
+    ///
+    /// * `statements` - The rows of `pg_stat_statements`.
+    /// * `taken_at` - When the rows were read.
+    pub fn new(statements: Vec<PgStatStatement>, taken_at: SystemTime) -> Self {
+        Self {
+            taken_at,
+            statements,
+        }
+    }
+
+    #[must_use]
+    /// Returns when the snapshot was taken.
+    pub fn taken_at(&self) -> SystemTime {
+        self.taken_at
+    }
+
+    #[must_use]
+    /// Returns the statements of the snapshot.
+    pub fn statements(&self) -> &[PgStatStatement] {
+        &self.statements
+    }
+
+    #[must_use]
+    /// Returns the statements of the snapshot, highest ranked first.
+    pub fn ranked(&self, ranking: StatementRanking) -> Vec<&PgStatStatement> {
+        let mut statements: Vec<&PgStatStatement> = self.statements.iter().collect();
+        statements.sort_by(|left, right| ranking.compare(left, right));
+        statements
+    }
+
+    #[must_use]
+    /// Returns what each statement did since the provided earlier snapshot,
+    /// omitting the statements which were not executed in between.
+    ///
+    /// A statement missing from the earlier snapshot, or whose counters went
+    /// backwards, was evicted from the view or had its statistics reset in
+    /// between: its delta is then its whole current statistics, and is
+    /// flagged with [`StatementDelta::was_reset`].
+    ///
+    /// # Arguments
+    ///
+    /// * `earlier` - The snapshot taken before this one.
+    pub fn deltas(&self, earlier: &Self) -> Vec<StatementDelta> {
+        let previous: HashMap<StatementKey, &PgStatStatement> = earlier
+            .statements
+            .iter()
+            .map(|statement| (key(statement), statement))
+            .collect();
+        let interval = self.taken_at.duration_since(earlier.taken_at).ok();
+        self.statements
+            .iter()
+            .map(|statement| {
+                StatementDelta::new(statement, previous.get(&key(statement)).copied(), interval)
+            })
+            .filter(|delta| delta.calls > 0)
+            .collect()
+    }
+
+    #[must_use]
+    /// Returns the statements whose mean execution time since the earlier
+    /// snapshot is at least `min_ratio` times their mean execution time up
+    /// to it, sorted by decreasing ratio.
+    ///
+    /// # Arguments
+    ///
+    /// * `earlier` - The snapshot taken before this one.
+    /// * `min_ratio` - The ratio of the mean execution times from which a
+    ///   statement has regressed, as in `2.0`.
+    /// * `min_calls` - The number of calls since the earlier snapshot below
+    ///   which a statement is too noisy to be reported.
+    pub fn regressions(
+        &self,
+        earlier: &Self,
+        min_ratio: f64,
+        min_calls: i64,
+    ) -> Vec<StatementDelta> {
+        let mut regressions: Vec<StatementDelta> = self
+            .deltas(earlier)
+            .into_iter()
+            .filter(|delta| delta.calls >= min_calls)
+            .filter(|delta| {
+                delta
+                    .regression_ratio()
+                    .is_some_and(|ratio| ratio >= min_ratio)
+            })
+            .collect();
+        regressions.sort_by(|left, right| {
+            right
+                .regression_ratio()
+                .unwrap_or_default()
+                .total_cmp(&left.regression_ratio().unwrap_or_default())
+        });
+        regressions
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// What a statement did between two snapshots.
+pub struct StatementDelta {
+    /// The statement, as of the later snapshot.
+    statement: PgStatStatement,
+    /// Whether the statistics of the statement were reset in between.
+    was_reset: bool,
+    /// The time elapsed between the two snapshots.
+    interval: Option<Duration>,
+    /// The number of calls in between.
+    calls: i64,
+    /// The execution time in between, in milliseconds.
+    total_exec_time: f64,
+    /// The rows retrieved or affected in between.
+    rows: i64,
+    /// The shared blocks read from outside the buffer cache in between.
+    shared_blks_read: i64,
+    /// The time spent reading shared blocks in between, in milliseconds.
+    shared_blk_read_time: f64,
+    /// The mean execution time up to the earlier snapshot, in milliseconds.
+    previous_mean_exec_time: Option<f64>,
+}
+
+impl StatementDelta {
+    /// Returns the difference between the statement and its earlier state.
+    fn new(
+        statement: &PgStatStatement,
+        previous: Option<&PgStatStatement>,
+        interval: Option<Duration>,
+    ) -> Self {
+        let previous = previous.filter(|previous| {
+            previous.calls <= statement.calls
+                && previous.total_exec_time <= statement.total_exec_time
+        });
+        let was_reset = previous.is_none();
+        Self {
+            statement: statement.clone(),
+            was_reset,
+            interval,
+            calls: statement.calls - previous.map_or(0, |previous| previous.calls),
+            total_exec_time: statement.total_exec_time
+                - previous.map_or(0.0, |previous| previous.total_exec_time),
+            rows: statement.rows - previous.map_or(0, |previous| previous.rows),
+            shared_blks_read: statement.shared_blks_read
+                - previous.map_or(0, |previous| previous.shared_blks_read),
+            shared_blk_read_time: statement.shared_blk_read_time
+                - previous.map_or(0.0, |previous| previous.shared_blk_read_time),
+            previous_mean_exec_time: previous
+                .filter(|previous| previous.calls > 0)
+                .map(|previous| previous.mean_exec_time),
+        }
+    }
+
+    #[must_use]
+    /// Returns the statement, as of the later snapshot.
+    pub fn statement(&self) -> &PgStatStatement {
+        &self.statement
+    }
+
+    #[must_use]
+    /// Returns whether the statement was missing from the earlier snapshot,
+    /// or had its statistics reset between the two snapshots.
+    pub fn was_reset(&self) -> bool {
+        self.was_reset
+    }
+
+    #[must_use]
+    /// Returns the time elapsed between the two snapshots, unless the clock
+    /// of the server went backwards.
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    #[must_use]
+    /// Returns the number of calls between the two snapshots.
+    pub fn calls(&self) -> i64 {
+        self.calls
+    }
+
+    #[must_use]
+    /// Returns the execution time between the two snapshots, in
+    /// milliseconds.
+    pub fn total_exec_time(&self) -> f64 {
+        self.total_exec_time
+    }
+
+    #[must_use]
+    /// Returns the rows retrieved or affected between the two snapshots.
+    pub fn rows(&self) -> i64 {
+        self.rows
+    }
+
+    #[must_use]
+    /// Returns the shared blocks read from outside the buffer cache between
+    /// the two snapshots.
+    pub fn shared_blks_read(&self) -> i64 {
+        self.shared_blks_read
+    }
+
+    #[must_use]
+    /// Returns the time spent reading shared blocks between the two
+    /// snapshots, in milliseconds.
+    pub fn shared_blk_read_time(&self) -> f64 {
+        self.shared_blk_read_time
+    }
+
+    #[must_use]
+    /// Returns the mean execution time between the two snapshots, in
+    /// milliseconds.
+    pub fn mean_exec_time(&self) -> Option<f64> {
+        let calls = self.calls.to_f64()?;
+        (calls > 0.0).then(|| self.total_exec_time / calls)
+    }
+
+    #[must_use]
+    /// Returns the mean execution time up to the earlier snapshot, in
+    /// milliseconds.
+    pub fn previous_mean_exec_time(&self) -> Option<f64> {
+        self.previous_mean_exec_time
+    }
+
+    #[must_use]
+    /// Returns the ratio between the mean execution time between the two
+    /// snapshots and the one up to the earlier snapshot.
+    pub fn regression_ratio(&self) -> Option<f64> {
+        let previous = self
+            .previous_mean_exec_time
+            .filter(|previous| *previous > 0.0)?;
+        Some(self.mean_exec_time()? / previous)
+    }
+
+    #[must_use]
+    /// Returns the number of calls per second between the two snapshots.
+    pub fn calls_per_second(&self) -> Option<f64> {
+        let seconds = self.interval?.as_secs_f64();
+        (seconds > 0.0).then(|| self.calls.to_f64().unwrap_or_default() / seconds)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The tables a statement touches.
+pub struct StatementTables<'db> {
+    /// The query identifier of the statement.
+    queryid: i64,
+    /// The tables of the database the statement touches.
+    tables: Vec<&'db Table>,
+    /// The relations which matched no table of the database, such as
+    /// common table expressions or tables outside of the loaded schemas.
+    unresolved: Vec<String>,
+    /// The error raised while parsing the statement, if any.
+    parse_error: Option<String>,
+}
+
+impl<'db> StatementTables<'db> {
+    #[must_use]
+    /// Returns the query identifier of the statement.
+    pub fn queryid(&self) -> i64 {
+        self.queryid
+    }
+
+    #[must_use]
+    /// Returns the tables of the database the statement touches.
+    pub fn tables(&self) -> &[&'db Table] {
+        &self.tables
+    }
+
+    #[must_use]
+    /// Returns the relations which matched no table of the database, such as
+    /// common table expressions or tables outside of the loaded schemas.
+    pub fn unresolved(&self) -> &[String] {
+        &self.unresolved
+    }
+
+    #[must_use]
+    /// Returns the error raised while parsing the statement, if any.
+    ///
+    /// Statements the parser does not understand touch no table.
+    pub fn parse_error(&self) -> Option<&str> {
+        self.parse_error.as_deref()
+    }
+}
+
+/// Attributes statements to the tables of a [`PgDieselDatabase`] by parsing
+/// their text.
+pub struct TableAttributor<'db> {
+    /// The index of the database whose tables the statements are attributed
+    /// to.
+    index: ObjectIndex<'db>,
+    /// The schemas in which unqualified table names are looked up, in order.
+    search_path: Vec<String>,
+}
+
+impl<'db> TableAttributor<'db> {
+    #[must_use]
+    /// Creates an attributor looking unqualified table names up in the
+    /// `public` schema.
+    pub fn new(database: &'db PgDieselDatabase) -> Self {
+        Self {
+            index: ObjectIndex::new(database),
+            search_path: vec!["public".to_owned()],
+        }
+    }
+
+    /// Sets the schemas in which unqualified table names are looked up, in
+    /// order, as with the `search_path` setting of the sessions.
+    #[must_use]
+    pub fn search_path<I, S>(mut self, search_path: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.search_path = search_path
+            .into_iter()
+            .map(|schema| schema.as_ref().to_owned())
+            .collect();
+        self
+    }
+
+    #[must_use]
+    /// Returns the tables the provided statement touches.
+    pub fn attribute(&self, statement: &PgStatStatement) -> StatementTables<'db> {
+        let mut attribution = StatementTables {
+            queryid: statement.queryid,
+            tables: Vec::new(),
+            unresolved: Vec::new(),
+            parse_error: None,
+        };
+        let statements = match Parser::parse_sql(&PostgreSqlDialect {}, &statement.query) {
+            Ok(statements) => statements,
+            Err(error) => {
+                attribution.parse_error = Some(error.to_string());
+                return attribution;
+            }
+        };
+        let _ = visit_relations(&statements, |relation| {
+            match self.resolve(relation) {
+                Some(table) => {
+                    if !attribution.tables.contains(&table) {
+                        attribution.tables.push(table);
+                    }
+                }
+                None => {
+                    let relation = relation.to_string();
+                    if !attribution.unresolved.contains(&relation) {
+                        attribution.unresolved.push(relation);
+                    }
+                }
+            }
+            ControlFlow::<()>::Continue(())
+        });
+        attribution
+    }
+
+    #[must_use]
+    /// Returns the execution time of the provided statements attributed to
+    /// each table, sorted by decreasing time.
+    ///
+    /// The whole time of a statement is attributed to each of the tables it
+    /// touches, so the times of the tables do not add up to the total.
+    pub fn exec_time_by_table(&self, statements: &[PgStatStatement]) -> Vec<(&'db Table, f64)> {
+        let mut times: BTreeMap<(&str, &str), (&'db Table, f64)> = BTreeMap::new();
+        for statement in statements {
+            for table in self.attribute(statement).tables {
+                times
+                    .entry((table.table_schema.as_str(), table.table_name.as_str()))
+                    .or_insert((table, 0.0))
+                    .1 += statement.total_exec_time;
+            }
+        }
+        let mut times: Vec<(&'db Table, f64)> = times.into_values().collect();
+        times.sort_by(|left, right| right.1.total_cmp(&left.1));
+        times
+    }
+
+    /// Returns the table of the database the relation refers to.
+    fn resolve(&self, relation: &ObjectName) -> Option<&'db Table> {
+        let parts: Vec<String> = relation
+            .0
+            .iter()
+            .map(|part| part.as_ident().map(normalize))
+            .collect::<Option<_>>()?;
+        let (schemas, name) = match parts.as_slice() {
+            [name] => (self.search_path.clone(), name),
+            [schema, name] | [_, schema, name] => (vec![schema.clone()], name),
+            _ => return None,
+        };
+        schemas
+            .iter()
+            .find_map(|schema| self.index.table_by_name(&QualifiedName::new(schema, name)))
+    }
+}
+
+/// Returns the identifier as stored in the catalogs: unquoted identifiers
+/// are folded to lower case.
+fn normalize(ident: &Ident) -> String {
+    if ident.quote_style.is_some() {
+        ident.value.clone()
+    } else {
+        ident.value.to_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deltas_and_regressions() {
+        let start = SystemTime::UNIX_EPOCH;
+        let earlier = StatementSnapshot::new(
+            vec![
+                PgStatStatement::fixture(1, 100, 100.0),
+                PgStatStatement::fixture(2, 100, 100.0),
+                PgStatStatement::fixture(3, 50, 500.0),
+            ],
+            start,
+        );
+        let later = StatementSnapshot::new(
+            vec![
+                PgStatStatement::fixture(1, 200, 1_100.0),
+                PgStatStatement::fixture(2, 150, 150.0),
+                PgStatStatement::fixture(3, 10, 20.0),
+                PgStatStatement::fixture(4, 1, 1.0),
+            ],
+            start + Duration::from_mins(1),
+        );
+
+        let deltas = later.deltas(&earlier);
+        assert_eq!(deltas.len(), 4);
+        assert_eq!(deltas[0].calls(), 100);
+        assert_eq!(deltas[0].mean_exec_time(), Some(10.0));
+        assert_eq!(deltas[0].regression_ratio(), Some(10.0));
+        assert!(deltas[2].was_reset());
+        assert_eq!(deltas[2].calls(), 10);
+        assert_eq!(deltas[2].regression_ratio(), None);
+        assert_eq!(deltas[1].calls_per_second(), Some(50.0 / 60.0));
+
+        let regressions = later.regressions(&earlier, 2.0, 10);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].statement().queryid, 1);
+    }
+
+    #[test]
+    fn test_identifier_normalization() {
+        assert_eq!(normalize(&Ident::new("Users")), "users");
+        assert_eq!(normalize(&Ident::with_quote('"', "Users")), "Users");
+    }
+}
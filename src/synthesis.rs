@@ -0,0 +1,739 @@
+//! Submodule generating realistic fake data from the loaded schema.
+//!
+//! The [`DataSynthesizer`] produces rows for every base table of a
+//! [`PgDieselDatabase`], generating parent tables before the tables
+//! referencing them. The generated rows respect:
+//!
+//! - the types of the columns, including enums and domains once
+//!   [`DataSynthesizer::resolve_types`] was called;
+//! - `NOT NULL` constraints;
+//! - column defaults, identities and generated columns, which are left to the
+//!   database unless another table references them;
+//! - unique indexes on columns, not on expressions;
+//! - foreign keys, by copying the values of a row of the referenced table;
+//! - the simple check constraints understood by [`ColumnBounds`].
+//!
+//! Optionally, [`DataSynthesizer::follow_statistics`] makes the values follow
+//! the distributions recorded in `pg_stats`. The resulting
+//! [`SyntheticDataset`] is rendered as `INSERT` statements or `COPY` text.
+//!
+//! Check constraints involving several columns, exclusion constraints and
+//! triggers are not taken into account, so the rows may still be rejected by
+//! them.
+
+use std::collections::{HashMap, HashSet};
+
+use diesel::PgConnection;
+use sql_traits::traits::{CheckConstraintLike, DatabaseLike};
+
+use crate::{
+    PgDieselDatabase,
+    constraint_bounds::ColumnBounds,
+    model_metadata::ColumnStatistics,
+    models::{Column, PgType, Table},
+};
+
+mod column_plan;
+mod dataset;
+mod random;
+mod value;
+
+use column_plan::{ColumnPlan, ValueKind};
+pub use dataset::{OutputFormat, SyntheticDataset, SyntheticTable};
+use random::SplitMix64;
+pub use value::SyntheticValue;
+
+#[derive(Debug, thiserror::Error)]
+/// Errors that can occur when synthesizing data.
+pub enum SynthesisError {
+    #[error("Column {column} has type `{type_name}`, which cannot be synthesized")]
+    /// A non-nullable column without default has an unsupported type.
+    UnsupportedType {
+        /// The column, as `schema.table.column`.
+        column: String,
+        /// The name of the type of the column.
+        type_name: String,
+    },
+    #[error("The check constraints on column {0} admit no value")]
+    /// The check constraints on a column admit no value.
+    UnsatisfiableConstraint(String),
+    #[error("Foreign key `{foreign_key}` of table {table} references no generated row")]
+    /// A non-nullable foreign key references a table without generated rows,
+    /// or columns left to their default value.
+    MissingParentRows {
+        /// The referencing table, as `schema.table`.
+        table: String,
+        /// The name of the foreign key constraint.
+        foreign_key: String,
+    },
+    #[error(
+        "Could not generate a row of table {table} satisfying its constraints in {attempts} attempts"
+    )]
+    /// No row satisfying the constraints of a table was found, usually because
+    /// a unique index ran out of distinct values.
+    ExhaustedAttempts {
+        /// The table, as `schema.table`.
+        table: String,
+        /// The number of attempts made.
+        attempts: usize,
+    },
+}
+
+/// How the values of a column are obtained.
+enum ColumnSource<'a> {
+    /// The column is left to its default value.
+    Omitted,
+    /// The column is always `NULL`, since its type cannot be synthesized.
+    Null,
+    /// The column is filled by a foreign key.
+    ForeignKey,
+    /// The column is generated according to a plan.
+    Generated {
+        /// The plan generating the values.
+        plan: ColumnPlan<'a>,
+        /// Whether the column admits `NULL`.
+        nullable: bool,
+    },
+}
+
+/// A foreign key of the table being generated.
+struct ForeignKeyPlan {
+    /// The name of the foreign key constraint.
+    name: String,
+    /// The positions of the host columns among the explicit columns.
+    host_positions: Vec<usize>,
+    /// Whether all the host columns admit `NULL`.
+    nullable: bool,
+    /// The referenced table, as schema and name.
+    referenced_table: (String, String),
+    /// The names of the referenced columns.
+    referenced_columns: Vec<String>,
+}
+
+#[derive(Clone)]
+/// Generator of realistic fake data for a [`PgDieselDatabase`].
+///
+/// The generation is deterministic: the same database, options and seed
+/// always produce the same rows.
+pub struct DataSynthesizer<'db> {
+    /// The database to generate data for.
+    database: &'db PgDieselDatabase,
+    /// The number of rows generated for tables without a specific count.
+    rows_per_table: usize,
+    /// The number of rows generated for specific tables.
+    table_rows: HashMap<(String, String), usize>,
+    /// The seed of the pseudo-random generator.
+    seed: u64,
+    /// The probability of generating `NULL` in nullable columns.
+    null_probability: f64,
+    /// Whether columns with a default value are left to the database.
+    use_defaults: bool,
+    /// The maximum number of attempts to generate each row.
+    max_attempts: usize,
+    /// The labels of the enum types used by the columns, by type OID.
+    enum_labels: HashMap<u32, Vec<String>>,
+    /// The base types of the domains used by the columns, by domain OID.
+    domain_base_types: HashMap<u32, PgType>,
+    /// The planner statistics of the columns, if they are to be followed.
+    statistics: HashMap<&'db Column, ColumnStatistics>,
+}
+
+impl<'db> DataSynthesizer<'db> {
+    /// Creates a new `DataSynthesizer` for the provided database.
+    ///
+    /// By default, 100 rows are generated for each table, nullable columns are
+    /// `NULL` in 10% of the rows and columns with a default value are left to
+    /// the database.
+    #[must_use]
+    pub fn new(database: &'db PgDieselDatabase) -> Self {
+        Self {
+            database,
+            rows_per_table: 100,
+            table_rows: HashMap::new(),
+            seed: 0,
+            null_probability: 0.1,
+            use_defaults: true,
+            max_attempts: 100,
+            enum_labels: HashMap::new(),
+            domain_base_types: HashMap::new(),
+            statistics: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    /// Sets the number of rows generated for tables without a specific count.
+    pub fn rows_per_table(mut self, rows: usize) -> Self {
+        self.rows_per_table = rows;
+        self
+    }
+
+    #[must_use]
+    /// Sets the number of rows generated for the provided table.
+    pub fn table_rows<S: AsRef<str>, T: AsRef<str>>(
+        mut self,
+        schema: S,
+        table: T,
+        rows: usize,
+    ) -> Self {
+        self.table_rows.insert(
+            (schema.as_ref().to_owned(), table.as_ref().to_owned()),
+            rows,
+        );
+        self
+    }
+
+    #[must_use]
+    /// Sets the seed of the pseudo-random generator.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    #[must_use]
+    /// Sets the probability of generating `NULL` in nullable columns without
+    /// statistics.
+    pub fn null_probability(mut self, probability: f64) -> Self {
+        self.null_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    #[must_use]
+    /// Sets whether columns with a default value are left to the database.
+    ///
+    /// Columns referenced by a foreign key are always generated explicitly, so
+    /// that the referencing rows can point to them. Note that explicit values
+    /// do not advance the sequences backing serial and identity columns.
+    pub fn use_defaults(mut self, use_defaults: bool) -> Self {
+        self.use_defaults = use_defaults;
+        self
+    }
+
+    #[must_use]
+    /// Sets the maximum number of attempts to generate each row before giving
+    /// up.
+    pub fn max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Loads the labels of the enum types and the base types of the domains
+    /// used by the columns, which are otherwise generated as `NULL` or
+    /// rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn resolve_types(mut self, conn: &mut PgConnection) -> Result<Self, diesel::result::Error> {
+        for column in self.columns() {
+            let pg_type = self.column_type(column);
+            let base_type = if pg_type.typbasetype == 0 {
+                pg_type.clone()
+            } else {
+                let base_type = PgType::from_oid(pg_type.typbasetype, conn)?;
+                self.domain_base_types
+                    .insert(pg_type.oid, base_type.clone());
+                base_type
+            };
+            if base_type.is_enum() && !self.enum_labels.contains_key(&base_type.oid) {
+                let labels = base_type
+                    .variants(conn)?
+                    .into_iter()
+                    .map(|variant| variant.enumlabel)
+                    .collect();
+                self.enum_labels.insert(base_type.oid, labels);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Loads the planner statistics of the columns, so that the generated
+    /// values follow the null fraction, most common values and histograms
+    /// recorded in `pg_stats`.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn follow_statistics(
+        mut self,
+        conn: &mut PgConnection,
+    ) -> Result<Self, diesel::result::Error> {
+        for column in self.columns() {
+            if let Some(statistics) = column.statistics(conn)? {
+                self.statistics.insert(column, statistics);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Returns the base tables of the database.
+    fn tables(&self) -> Vec<&'db Table> {
+        self.database
+            .tables()
+            .filter(|table| table.table_type == "BASE TABLE")
+            .collect()
+    }
+
+    /// Returns the columns of the base tables of the database.
+    fn columns(&self) -> Vec<&'db Column> {
+        let database = self.database;
+        self.tables()
+            .into_iter()
+            .flat_map(|table| {
+                database
+                    .table_metadata(table)
+                    .expect("Table must exist in database")
+                    .columns()
+            })
+            .collect()
+    }
+
+    /// Returns the type of the column, as loaded with the database.
+    fn column_type(&self, column: &Column) -> &'db PgType {
+        self.database
+            .column_metadata(column)
+            .expect("Column must exist in database")
+            .pg_type()
+    }
+
+    /// Returns the tables in insertion order: every table follows the tables
+    /// it references, except when foreign keys form a cycle.
+    fn insertion_order(&self) -> Vec<&'db Table> {
+        let tables = self.tables();
+        let parents: Vec<HashSet<usize>> = tables
+            .iter()
+            .map(|table| {
+                self.database
+                    .table_metadata(table)
+                    .expect("Table must exist in database")
+                    .foreign_keys()
+                    .filter_map(|foreign_key| {
+                        let referenced = self
+                            .database
+                            .foreign_key_metadata(foreign_key)
+                            .expect("Foreign key must exist in database")
+                            .referenced_table();
+                        tables.iter().position(|candidate| *candidate == referenced)
+                    })
+                    .filter(|parent| tables[*parent] != *table)
+                    .collect()
+            })
+            .collect();
+
+        let mut order = Vec::with_capacity(tables.len());
+        let mut placed = vec![false; tables.len()];
+        while order.len() < tables.len() {
+            let next = (0..tables.len())
+                .find(|&index| {
+                    !placed[index] && parents[index].iter().all(|parent| placed[*parent])
+                })
+                .or_else(|| (0..tables.len()).find(|&index| !placed[index]));
+            let Some(next) = next else {
+                break;
+            };
+            placed[next] = true;
+            order.push(tables[next]);
+        }
+        order
+    }
+
+    /// Returns the columns referenced by some foreign key.
+    fn referenced_columns(&self) -> HashSet<(String, String, String)> {
+        self.tables()
+            .into_iter()
+            .flat_map(|table| {
+                self.database
+                    .table_metadata(table)
+                    .expect("Table must exist in database")
+                    .foreign_keys()
+            })
+            .flat_map(|foreign_key| {
+                self.database
+                    .foreign_key_metadata(foreign_key)
+                    .expect("Foreign key must exist in database")
+                    .referenced_columns()
+                    .iter()
+                    .map(|column| {
+                        (
+                            column.table_schema.clone(),
+                            column.table_name.clone(),
+                            column.column_name.clone(),
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Generates the rows of every base table of the database.
+    ///
+    /// # Errors
+    ///
+    /// * If a non-nullable column without default has an unsupported type.
+    /// * If the check constraints on a column admit no value.
+    /// * If a non-nullable foreign key cannot reference any generated row.
+    /// * If no row satisfying the unique indexes of a table can be found.
+    pub fn generate(&self) -> Result<SyntheticDataset, SynthesisError> {
+        let mut rng = SplitMix64::new(self.seed);
+        let referenced_columns = self.referenced_columns();
+        let mut generated: Vec<SyntheticTable> = Vec::new();
+        for table in self.insertion_order() {
+            let synthetic_table =
+                self.generate_table(table, &generated, &referenced_columns, &mut rng)?;
+            generated.push(synthetic_table);
+        }
+        Ok(SyntheticDataset::new(generated))
+    }
+
+    /// Returns how the values of the column are obtained.
+    fn column_source(
+        &self,
+        table: &'db Table,
+        column: &'db Column,
+        referenced: bool,
+        foreign_key_columns: &HashSet<&Column>,
+        unique_alone: bool,
+    ) -> Result<ColumnSource<'_>, SynthesisError> {
+        if column.is_generated == "ALWAYS" || column.generation_expression.is_some() {
+            return Ok(ColumnSource::Omitted);
+        }
+        if foreign_key_columns.contains(&column) {
+            return Ok(ColumnSource::ForeignKey);
+        }
+        let has_default =
+            column.column_default.is_some() || column.is_identity.as_deref() == Some("YES");
+        if has_default && self.use_defaults && !referenced {
+            return Ok(ColumnSource::Omitted);
+        }
+
+        let mut bounds = ColumnBounds::default();
+        for check_constraint in self
+            .database
+            .table_metadata(table)
+            .expect("Table must exist in database")
+            .check_constraints()
+        {
+            bounds.restrict(
+                CheckConstraintLike::expression(check_constraint, self.database),
+                &column.column_name,
+            );
+        }
+
+        let pg_type = self.column_type(column);
+        let base_type = self.domain_base_types.get(&pg_type.oid).unwrap_or(pg_type);
+        let kind = if base_type.is_enum() {
+            self.enum_labels
+                .get(&base_type.oid)
+                .map(|labels| ValueKind::Enum(labels.clone()))
+        } else {
+            ValueKind::from_type(&base_type.typname, &base_type.typcategory, column)
+        };
+        let nullable = column.__is_nullable == "YES" && !bounds.not_null();
+
+        let Some(kind) = kind else {
+            return if nullable {
+                Ok(ColumnSource::Null)
+            } else if has_default {
+                Ok(ColumnSource::Omitted)
+            } else {
+                Err(SynthesisError::UnsupportedType {
+                    column: column.to_string(),
+                    type_name: pg_type.typname.clone(),
+                })
+            };
+        };
+
+        let plan = ColumnPlan::new(
+            column,
+            kind,
+            bounds,
+            self.statistics.get(column),
+            unique_alone,
+        )
+        .ok_or_else(|| SynthesisError::UnsatisfiableConstraint(column.to_string()))?;
+        Ok(ColumnSource::Generated { plan, nullable })
+    }
+
+    /// Plans how the rows of a table are generated.
+    fn plan_table(
+        &self,
+        table: &'db Table,
+        referenced_columns: &HashSet<(String, String, String)>,
+    ) -> Result<TablePlan<'_>, SynthesisError> {
+        let metadata = self
+            .database
+            .table_metadata(table)
+            .expect("Table must exist in database");
+        let columns: Vec<&Column> = metadata.columns().collect();
+        let foreign_key_columns: HashSet<&Column> = metadata
+            .foreign_keys()
+            .flat_map(|foreign_key| {
+                self.database
+                    .foreign_key_metadata(foreign_key)
+                    .expect("Foreign key must exist in database")
+                    .host_columns()
+            })
+            .collect();
+        let unique_keys: Vec<&[i16]> = metadata
+            .unique_indices()
+            .filter(|index| index.indkey.iter().all(|attnum| *attnum > 0))
+            .map(|index| index.indkey.as_slice())
+            .collect();
+
+        let mut sources = Vec::with_capacity(columns.len());
+        for column in &columns {
+            let referenced = referenced_columns.contains(&(
+                column.table_schema.clone(),
+                column.table_name.clone(),
+                column.column_name.clone(),
+            ));
+            let attnum = i16::try_from(column.ordinal_position).unwrap_or_default();
+            let unique_alone = unique_keys.iter().any(|key| *key == [attnum]);
+            sources.push(self.column_source(
+                table,
+                column,
+                referenced,
+                &foreign_key_columns,
+                unique_alone,
+            )?);
+        }
+
+        let explicit: Vec<usize> = (0..columns.len())
+            .filter(|&index| !matches!(sources[index], ColumnSource::Omitted))
+            .collect();
+        let position_of =
+            |column: &Column| explicit.iter().position(|&index| columns[index] == column);
+
+        let foreign_keys = metadata
+            .foreign_keys()
+            .filter_map(|foreign_key| {
+                let foreign_key_metadata = self
+                    .database
+                    .foreign_key_metadata(foreign_key)
+                    .expect("Foreign key must exist in database");
+                let host_columns = foreign_key_metadata.host_columns();
+                let referenced_table = foreign_key_metadata.referenced_table();
+                Some(ForeignKeyPlan {
+                    name: foreign_key.constraint_name.clone(),
+                    host_positions: host_columns
+                        .iter()
+                        .map(position_of)
+                        .collect::<Option<Vec<usize>>>()?,
+                    nullable: host_columns
+                        .iter()
+                        .all(|column| column.__is_nullable == "YES"),
+                    referenced_table: (
+                        referenced_table.table_schema.clone(),
+                        referenced_table.table_name.clone(),
+                    ),
+                    referenced_columns: foreign_key_metadata
+                        .referenced_columns()
+                        .iter()
+                        .map(|column| column.column_name.clone())
+                        .collect(),
+                })
+            })
+            .collect();
+
+        let unique_positions = unique_keys
+            .iter()
+            .filter_map(|key| {
+                key.iter()
+                    .map(|attnum| {
+                        columns
+                            .iter()
+                            .find(|column| column.ordinal_position == i32::from(*attnum))
+                            .and_then(|column| position_of(column))
+                    })
+                    .collect::<Option<Vec<usize>>>()
+            })
+            .collect();
+
+        let overriding_system_value = explicit
+            .iter()
+            .any(|&index| columns[index].identity_generation.as_deref() == Some("ALWAYS"));
+        let names = explicit
+            .iter()
+            .map(|&index| columns[index].column_name.clone())
+            .collect();
+        let mut sources: Vec<Option<ColumnSource>> = sources.into_iter().map(Some).collect();
+        let sources = explicit
+            .iter()
+            .filter_map(|&index| sources[index].take())
+            .collect();
+
+        Ok(TablePlan {
+            table,
+            names,
+            sources,
+            foreign_keys,
+            unique_positions,
+            overriding_system_value,
+        })
+    }
+
+    /// Generates the rows of a table.
+    fn generate_table(
+        &self,
+        table: &'db Table,
+        generated: &[SyntheticTable],
+        referenced_columns: &HashSet<(String, String, String)>,
+        rng: &mut SplitMix64,
+    ) -> Result<SyntheticTable, SynthesisError> {
+        let plan = self.plan_table(table, referenced_columns)?;
+        let mut seen_keys: Vec<HashSet<Vec<SyntheticValue>>> =
+            vec![HashSet::new(); plan.unique_positions.len()];
+        let row_count = self
+            .table_rows
+            .get(&(table.table_schema.clone(), table.table_name.clone()))
+            .copied()
+            .unwrap_or(self.rows_per_table);
+        let mut rows: Vec<Vec<SyntheticValue>> = Vec::with_capacity(row_count);
+
+        for row_index in 0..row_count {
+            let mut attempt = 0;
+            let row = loop {
+                if attempt == self.max_attempts {
+                    return Err(SynthesisError::ExhaustedAttempts {
+                        table: table.to_string(),
+                        attempts: self.max_attempts,
+                    });
+                }
+                attempt += 1;
+                let Some(row) = plan.candidate_row(self, rng, row_index, &rows, generated)? else {
+                    continue;
+                };
+                let keys: Vec<Vec<SyntheticValue>> = plan
+                    .unique_positions
+                    .iter()
+                    .map(|positions| {
+                        positions
+                            .iter()
+                            .map(|&position| row[position].clone())
+                            .collect()
+                    })
+                    .collect();
+                let duplicate = keys.iter().zip(&seen_keys).any(|(key, seen)| {
+                    !key.iter().any(SyntheticValue::is_null) && seen.contains(key)
+                });
+                if duplicate {
+                    continue;
+                }
+                for (key, seen) in keys.into_iter().zip(&mut seen_keys) {
+                    seen.insert(key);
+                }
+                break row;
+            };
+            rows.push(row);
+        }
+
+        Ok(SyntheticTable::new(
+            table.table_schema.clone(),
+            table.table_name.clone(),
+            plan.names,
+            rows,
+            plan.overriding_system_value,
+        ))
+    }
+}
+
+/// The plan to generate the rows of a table.
+struct TablePlan<'a> {
+    /// The table to generate the rows of.
+    table: &'a Table,
+    /// The names of the explicitly generated columns.
+    names: Vec<String>,
+    /// How the values of the explicitly generated columns are obtained.
+    sources: Vec<ColumnSource<'a>>,
+    /// The foreign keys of the table.
+    foreign_keys: Vec<ForeignKeyPlan>,
+    /// The positions of the columns of each unique index.
+    unique_positions: Vec<Vec<usize>>,
+    /// Whether some of the explicit columns are `GENERATED ALWAYS AS
+    /// IDENTITY`.
+    overriding_system_value: bool,
+}
+
+impl TablePlan<'_> {
+    /// Generates a candidate row, or `None` if a generated value does not
+    /// satisfy the bounds of its column and another attempt is needed.
+    fn candidate_row(
+        &self,
+        synthesizer: &DataSynthesizer<'_>,
+        rng: &mut SplitMix64,
+        row_index: usize,
+        rows: &[Vec<SyntheticValue>],
+        generated: &[SyntheticTable],
+    ) -> Result<Option<Vec<SyntheticValue>>, SynthesisError> {
+        let mut row = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            let value = match source {
+                ColumnSource::Generated { plan, nullable } => {
+                    if *nullable && rng.chance(plan.null_probability(synthesizer.null_probability))
+                    {
+                        SyntheticValue::Null
+                    } else {
+                        let value = plan.generate(rng, row_index);
+                        if !plan.admits(&value) {
+                            return Ok(None);
+                        }
+                        value
+                    }
+                }
+                ColumnSource::Omitted | ColumnSource::Null | ColumnSource::ForeignKey => {
+                    SyntheticValue::Null
+                }
+            };
+            row.push(value);
+        }
+
+        for foreign_key in &self.foreign_keys {
+            let (schema, name) = &foreign_key.referenced_table;
+            let parent = if *schema == self.table.table_schema && *name == self.table.table_name {
+                Some((self.names.as_slice(), rows))
+            } else {
+                generated
+                    .iter()
+                    .find(|candidate| candidate.schema() == schema && candidate.name() == name)
+                    .map(|candidate| (candidate.columns(), candidate.rows()))
+            };
+            let parent = parent.and_then(|(names, parent_rows)| {
+                let positions = foreign_key
+                    .referenced_columns
+                    .iter()
+                    .map(|column| names.iter().position(|name| name == column))
+                    .collect::<Option<Vec<usize>>>()?;
+                (!parent_rows.is_empty()).then_some((positions, parent_rows))
+            });
+
+            let set_null = foreign_key.nullable && rng.chance(synthesizer.null_probability);
+            match parent {
+                Some((positions, parent_rows)) if !set_null => {
+                    let parent_row = &parent_rows[rng.below(parent_rows.len())];
+                    for (host, position) in foreign_key.host_positions.iter().zip(positions) {
+                        row[*host] = parent_row[position].clone();
+                    }
+                }
+                _ if foreign_key.nullable => {
+                    for host in &foreign_key.host_positions {
+                        row[*host] = SyntheticValue::Null;
+                    }
+                }
+                _ => {
+                    return Err(SynthesisError::MissingParentRows {
+                        table: self.table.to_string(),
+                        foreign_key: foreign_key.name.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(Some(row))
+    }
+}
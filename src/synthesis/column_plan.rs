@@ -0,0 +1,638 @@
+//! Submodule providing the [`ColumnPlan`] struct, which generates the values
+//! of a single column.
+
+use bigdecimal::ToPrimitive;
+
+use super::{SyntheticValue, random::SplitMix64};
+use crate::{
+    constraint_bounds::{ColumnBounds, NumericBound},
+    model_metadata::ColumnStatistics,
+    models::Column,
+};
+
+/// Width of the default window of generated numbers.
+const DEFAULT_SPAN: i64 = 1_000_000;
+/// Days since the Unix epoch of `2000-01-01`.
+const FIRST_DAY: i64 = 10_957;
+/// Days since the Unix epoch of `2030-12-31`.
+const LAST_DAY: i64 = 22_279;
+/// Default maximum length of generated text.
+const DEFAULT_MAX_LENGTH: usize = 64;
+
+/// First names used for name-like columns.
+const FIRST_NAMES: &[&str] = &[
+    "Ada",
+    "Alan",
+    "Barbara",
+    "Claude",
+    "Donald",
+    "Edsger",
+    "Frances",
+    "Grace",
+    "John",
+    "Katherine",
+    "Leslie",
+    "Margaret",
+    "Niklaus",
+    "Radia",
+    "Tim",
+    "Vint",
+];
+/// Last names used for name-like columns.
+const LAST_NAMES: &[&str] = &[
+    "Allen",
+    "Berners-Lee",
+    "Cerf",
+    "Dijkstra",
+    "Hamilton",
+    "Hopper",
+    "Johnson",
+    "Knuth",
+    "Lamport",
+    "Liskov",
+    "Lovelace",
+    "McCarthy",
+    "Perlman",
+    "Shannon",
+    "Turing",
+    "Wirth",
+];
+/// Cities used for city-like columns.
+const CITIES: &[&str] = &[
+    "Amsterdam",
+    "Berlin",
+    "Buenos Aires",
+    "Cairo",
+    "Lisbon",
+    "Montreal",
+    "Nairobi",
+    "Osaka",
+    "Seoul",
+    "Sydney",
+    "Zurich",
+];
+/// Countries used for country-like columns.
+const COUNTRIES: &[&str] = &[
+    "Argentina",
+    "Canada",
+    "Egypt",
+    "Germany",
+    "Japan",
+    "Kenya",
+    "Netherlands",
+    "Portugal",
+    "South Korea",
+    "Switzerland",
+];
+/// Words used for free text.
+const WORDS: &[&str] = &[
+    "alpha", "amber", "basin", "cedar", "delta", "ember", "fable", "glade", "harbor", "iris",
+    "juniper", "kernel", "lumen", "meadow", "nectar", "orbit", "pebble", "quartz", "river",
+    "summit", "tundra", "umber", "valley", "willow", "zephyr",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+/// The kind of values a column holds.
+pub(super) enum ValueKind {
+    /// `bool`.
+    Boolean,
+    /// Integer types, with the range admitted by the type.
+    Integer {
+        /// The smallest value admitted by the type.
+        min: i64,
+        /// The largest value admitted by the type.
+        max: i64,
+    },
+    /// `numeric` and `money`.
+    Decimal {
+        /// The exclusive bound on the magnitude of the values, if any.
+        max_magnitude: Option<f64>,
+        /// The number of fractional digits.
+        scale: usize,
+    },
+    /// `float4` and `float8`.
+    Float,
+    /// Character types.
+    Text,
+    /// `uuid`.
+    Uuid,
+    /// `date`.
+    Date,
+    /// `timestamp`.
+    Timestamp,
+    /// `timestamptz`.
+    TimestampTz,
+    /// `time`.
+    Time,
+    /// `timetz`.
+    TimeTz,
+    /// `interval`.
+    Interval,
+    /// `json` and `jsonb`.
+    Json,
+    /// `bytea`.
+    Bytea,
+    /// `inet`.
+    Inet,
+    /// `cidr`.
+    Cidr,
+    /// `macaddr`.
+    MacAddr,
+    /// `xml`.
+    Xml,
+    /// `tsvector`.
+    TsVector,
+    /// Any array type, generated as the empty array.
+    Array,
+    /// An enum type, with its labels.
+    Enum(Vec<String>),
+}
+
+impl ValueKind {
+    /// Returns the kind of the values of a type, if supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `type_name` - The name of the (base) type of the column.
+    /// * `type_category` - The category of the (base) type of the column.
+    /// * `column` - The column, providing the numeric precision and scale.
+    pub(super) fn from_type(type_name: &str, type_category: &str, column: &Column) -> Option<Self> {
+        if type_category == "A" {
+            return Some(Self::Array);
+        }
+        Some(match type_name {
+            "bool" => Self::Boolean,
+            "int2" => Self::Integer {
+                min: i64::from(i16::MIN),
+                max: i64::from(i16::MAX),
+            },
+            "int4" => Self::Integer {
+                min: i64::from(i32::MIN),
+                max: i64::from(i32::MAX),
+            },
+            "int8" => Self::Integer {
+                min: i64::MIN,
+                max: i64::MAX,
+            },
+            "numeric" => {
+                let scale = column
+                    .numeric_scale
+                    .and_then(|scale| usize::try_from(scale).ok())
+                    .unwrap_or(2);
+                let max_magnitude = column.numeric_precision.map(|precision| {
+                    10f64.powi(precision - column.numeric_scale.unwrap_or_default())
+                });
+                Self::Decimal {
+                    max_magnitude,
+                    scale,
+                }
+            }
+            "money" => Self::Decimal {
+                max_magnitude: None,
+                scale: 2,
+            },
+            "float4" | "float8" => Self::Float,
+            "text" | "varchar" | "bpchar" | "name" | "citext" => Self::Text,
+            "uuid" => Self::Uuid,
+            "date" => Self::Date,
+            "timestamp" => Self::Timestamp,
+            "timestamptz" => Self::TimestampTz,
+            "time" => Self::Time,
+            "timetz" => Self::TimeTz,
+            "interval" => Self::Interval,
+            "json" | "jsonb" => Self::Json,
+            "bytea" => Self::Bytea,
+            "inet" => Self::Inet,
+            "cidr" => Self::Cidr,
+            "macaddr" => Self::MacAddr,
+            "xml" => Self::Xml,
+            "tsvector" => Self::TsVector,
+            _ => return None,
+        })
+    }
+
+    /// Returns whether the values of this kind are rendered as numbers.
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Self::Integer { .. } | Self::Decimal { .. } | Self::Float
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The plan to generate the values of a column.
+pub(super) struct ColumnPlan<'a> {
+    /// The name of the column, used to pick realistic text.
+    name: String,
+    /// The kind of the values of the column.
+    kind: ValueKind,
+    /// The restrictions extracted from the check constraints of the table.
+    bounds: ColumnBounds,
+    /// The planner statistics to follow, if any.
+    statistics: Option<&'a ColumnStatistics>,
+    /// The range of generated integers.
+    integer_range: (i64, i64),
+    /// The range of generated decimals and floats.
+    float_range: (f64, f64),
+    /// The range of lengths of generated text.
+    length_range: (usize, usize),
+    /// Whether the column is unique on its own, and values are derived from
+    /// the row index.
+    sequential: bool,
+}
+
+impl<'a> ColumnPlan<'a> {
+    /// Creates a new `ColumnPlan`, or `None` if the bounds admit no value.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - The column to generate the values of.
+    /// * `kind` - The kind of the values of the column.
+    /// * `bounds` - The restrictions extracted from the check constraints.
+    /// * `statistics` - The planner statistics to follow, if any.
+    /// * `sequential` - Whether the column is unique on its own.
+    pub(super) fn new(
+        column: &Column,
+        kind: ValueKind,
+        bounds: ColumnBounds,
+        statistics: Option<&'a ColumnStatistics>,
+        sequential: bool,
+    ) -> Option<Self> {
+        if bounds.allowed_values().is_some_and(<[String]>::is_empty) {
+            return None;
+        }
+
+        let (type_min, type_max) = match &kind {
+            ValueKind::Integer { min, max } => (*min, *max),
+            _ => (i64::MIN, i64::MAX),
+        };
+        let lower = bounds.lower().and_then(NumericBound::smallest_integer);
+        let upper = bounds.upper().and_then(NumericBound::largest_integer);
+        let low = lower
+            .unwrap_or(match upper {
+                Some(upper) if upper < 1 => upper.saturating_sub(DEFAULT_SPAN),
+                _ => 1,
+            })
+            .max(type_min);
+        let high = upper
+            .unwrap_or(if sequential {
+                type_max
+            } else {
+                low.saturating_add(DEFAULT_SPAN)
+            })
+            .min(type_max);
+
+        let max_magnitude = match &kind {
+            ValueKind::Decimal { max_magnitude, .. } => max_magnitude.unwrap_or(f64::MAX),
+            _ => f64::MAX,
+        };
+        let lower = bounds.lower().and_then(|bound| bound.value.to_f64());
+        let upper = bounds.upper().and_then(|bound| bound.value.to_f64());
+        let float_low = lower
+            .unwrap_or(match upper {
+                Some(upper) if upper < 0.0 => upper - 1000.0,
+                _ => 0.0,
+            })
+            .max(-max_magnitude);
+        let float_high = upper.unwrap_or(float_low + 1000.0).min(max_magnitude);
+
+        let min_length = bounds.min_length().unwrap_or(1);
+        let max_length = column
+            .character_maximum_length
+            .and_then(|length| usize::try_from(length).ok())
+            .into_iter()
+            .chain(bounds.max_length())
+            .min()
+            .unwrap_or(DEFAULT_MAX_LENGTH.max(min_length));
+
+        let unsatisfiable = match &kind {
+            ValueKind::Integer { .. } => low > high,
+            ValueKind::Decimal { .. } | ValueKind::Float => float_low > float_high,
+            ValueKind::Text => min_length > max_length,
+            _ => false,
+        };
+        if unsatisfiable && bounds.allowed_values().is_none() {
+            return None;
+        }
+
+        Some(Self {
+            name: column.column_name.to_lowercase(),
+            kind,
+            bounds,
+            statistics,
+            integer_range: (low, high),
+            float_range: (float_low, float_high),
+            length_range: (min_length, max_length),
+            sequential,
+        })
+    }
+
+    /// Returns the probability of generating `NULL`, given the default one.
+    pub(super) fn null_probability(&self, default: f64) -> f64 {
+        self.statistics
+            .map_or(default, |statistics| f64::from(statistics.null_fraction()))
+    }
+
+    /// Returns whether the generated value satisfies the bounds of the column.
+    pub(super) fn admits(&self, value: &SyntheticValue) -> bool {
+        let Some(text) = value.as_text() else {
+            return true;
+        };
+        let (_, max_length) = self.length_range;
+        if matches!(self.kind, ValueKind::Text) && text.chars().count() > max_length {
+            return false;
+        }
+        self.bounds.admits(text)
+    }
+
+    /// Generates a non-null value for the column.
+    pub(super) fn generate(&self, rng: &mut SplitMix64, row_index: usize) -> SyntheticValue {
+        if let Some(values) = self.bounds.allowed_values() {
+            let value = rng.choose(values).cloned().unwrap_or_default();
+            return self.typed(value);
+        }
+        if !self.sequential
+            && let Some(statistics) = self.statistics
+            && let Some(value) = self.sample_statistics(statistics, rng)
+        {
+            return value;
+        }
+        self.generate_from_kind(rng, row_index)
+    }
+
+    /// Converts a textual value to a value of the kind of the column.
+    fn typed(&self, text: String) -> SyntheticValue {
+        match self.kind {
+            ValueKind::Boolean => SyntheticValue::Boolean(matches!(text.as_str(), "true" | "t")),
+            _ if self.kind.is_numeric() && text.parse::<f64>().is_ok_and(f64::is_finite) => {
+                SyntheticValue::Number(text)
+            }
+            _ => SyntheticValue::Text(text),
+        }
+    }
+
+    /// Samples a value following the distribution described by the planner
+    /// statistics, or `None` if they do not describe one.
+    fn sample_statistics(
+        &self,
+        statistics: &ColumnStatistics,
+        rng: &mut SplitMix64,
+    ) -> Option<SyntheticValue> {
+        let mut draw = rng.next_f64() * (1.0 - f64::from(statistics.null_fraction()));
+        for most_common_value in statistics.most_common_values() {
+            let frequency = f64::from(most_common_value.frequency);
+            if draw < frequency {
+                return Some(self.typed(most_common_value.value.to_text()));
+            }
+            draw -= frequency;
+        }
+
+        let bounds = statistics.histogram_bounds();
+        if bounds.len() < 2 {
+            return None;
+        }
+        let bucket = rng.below(bounds.len() - 1);
+        let (low, high) = (&bounds[bucket], &bounds[bucket + 1]);
+        match (&self.kind, low.as_f64(), high.as_f64()) {
+            (ValueKind::Integer { .. }, Some(low), Some(high)) => Some(SyntheticValue::Number(
+                format!("{:.0}", rng.range_f64(low, high)),
+            )),
+            (ValueKind::Decimal { scale, .. }, Some(low), Some(high)) => Some(
+                SyntheticValue::Number(format!("{:.scale$}", rng.range_f64(low, high))),
+            ),
+            (ValueKind::Float, Some(low), Some(high)) => Some(SyntheticValue::Number(format!(
+                "{}",
+                rng.range_f64(low, high)
+            ))),
+            _ => {
+                let bound = if rng.chance(0.5) { low } else { high };
+                Some(self.typed(bound.to_text()))
+            }
+        }
+    }
+
+    /// Generates a value from the kind of the column alone.
+    fn generate_from_kind(&self, rng: &mut SplitMix64, row_index: usize) -> SyntheticValue {
+        match &self.kind {
+            ValueKind::Boolean => SyntheticValue::Boolean(rng.chance(0.5)),
+            ValueKind::Integer { .. } => {
+                let (low, high) = self.integer_range;
+                let sequential = i64::try_from(row_index)
+                    .ok()
+                    .and_then(|index| low.checked_add(index))
+                    .filter(|value| self.sequential && *value <= high);
+                let value = sequential.unwrap_or_else(|| rng.range_i64(low, high));
+                SyntheticValue::Number(value.to_string())
+            }
+            ValueKind::Decimal { scale, .. } => {
+                let (low, high) = self.float_range;
+                SyntheticValue::Number(format!("{:.scale$}", rng.range_f64(low, high)))
+            }
+            ValueKind::Float => {
+                let (low, high) = self.float_range;
+                SyntheticValue::Number(format!("{:.4}", rng.range_f64(low, high)))
+            }
+            ValueKind::Text => SyntheticValue::Text(self.text(rng, row_index)),
+            ValueKind::Uuid => {
+                let high = rng.next_u64();
+                let low = rng.next_u64();
+                SyntheticValue::Text(format!(
+                    "{:08x}-{:04x}-4{:03x}-{:04x}-{:012x}",
+                    high >> 32,
+                    (high >> 16) & 0xFFFF,
+                    high & 0x0FFF,
+                    ((low >> 48) & 0x3FFF) | 0x8000,
+                    low & 0xFFFF_FFFF_FFFF
+                ))
+            }
+            ValueKind::Date => SyntheticValue::Text(date(rng)),
+            ValueKind::Timestamp => SyntheticValue::Text(format!("{} {}", date(rng), time(rng))),
+            ValueKind::TimestampTz => {
+                SyntheticValue::Text(format!("{} {}+00", date(rng), time(rng)))
+            }
+            ValueKind::Time => SyntheticValue::Text(time(rng)),
+            ValueKind::TimeTz => SyntheticValue::Text(format!("{}+00", time(rng))),
+            ValueKind::Interval => SyntheticValue::Text(format!(
+                "{} days {} hours",
+                rng.range_i64(0, 365),
+                rng.range_i64(0, 23)
+            )),
+            ValueKind::Json => {
+                SyntheticValue::Text(format!("{{\"{}\": {}}}", word(rng), rng.range_i64(0, 1000)))
+            }
+            ValueKind::Bytea => SyntheticValue::Text(format!("\\x{:016x}", rng.next_u64())),
+            ValueKind::Inet => SyntheticValue::Text(format!(
+                "10.{}.{}.{}",
+                rng.range_i64(0, 255),
+                rng.range_i64(0, 255),
+                rng.range_i64(1, 254)
+            )),
+            ValueKind::Cidr => SyntheticValue::Text(format!(
+                "10.{}.{}.0/24",
+                rng.range_i64(0, 255),
+                rng.range_i64(0, 255)
+            )),
+            ValueKind::MacAddr => {
+                let bytes = rng.next_u64().to_le_bytes();
+                let parts: Vec<String> = bytes[2..]
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect();
+                SyntheticValue::Text(parts.join(":"))
+            }
+            ValueKind::Xml => SyntheticValue::Text(format!("<value>{}</value>", word(rng))),
+            ValueKind::TsVector => SyntheticValue::Text(format!("{} {}", word(rng), word(rng))),
+            ValueKind::Array => SyntheticValue::Text("{}".to_owned()),
+            ValueKind::Enum(labels) => {
+                SyntheticValue::Text(rng.choose(labels).cloned().unwrap_or_default())
+            }
+        }
+    }
+
+    /// Generates text fitting the length range of the column, picking a
+    /// realistic shape from the name of the column.
+    fn text(&self, rng: &mut SplitMix64, row_index: usize) -> String {
+        let (min_length, max_length) = self.length_range;
+        let suffix = if self.sequential {
+            format!("{row_index}")
+        } else {
+            String::new()
+        };
+        let name = self.name.as_str();
+        let first = rng.choose(FIRST_NAMES).copied().unwrap_or_default();
+        let last = rng.choose(LAST_NAMES).copied().unwrap_or_default();
+
+        let text = if name.contains("email") {
+            format!(
+                "{}.{}{suffix}@example.com",
+                first.to_lowercase(),
+                last.to_lowercase().replace('-', "")
+            )
+        } else if name.contains("first_name") || name.contains("firstname") {
+            format!("{first}{suffix}")
+        } else if name.contains("last_name") || name.contains("lastname") || name == "surname" {
+            format!("{last}{suffix}")
+        } else if name.contains("name") {
+            format!("{first} {last}{suffix}")
+        } else if name.contains("url") || name.contains("website") || name.contains("link") {
+            format!("https://example.com/{}/{suffix}", word(rng))
+        } else if name.contains("phone") {
+            format!("+1-555-{:04}{suffix}", rng.range_i64(0, 9999))
+        } else if name.contains("city") {
+            format!(
+                "{}{suffix}",
+                rng.choose(CITIES).copied().unwrap_or_default()
+            )
+        } else if name.contains("country") {
+            format!(
+                "{}{suffix}",
+                rng.choose(COUNTRIES).copied().unwrap_or_default()
+            )
+        } else if name.contains("description")
+            || name.contains("comment")
+            || name.contains("note")
+            || name.contains("body")
+        {
+            let count = rng.range_i64(6, 12);
+            let words: Vec<&str> = (0..count).map(|_| word(rng)).collect();
+            format!("{}{suffix}", words.join(" "))
+        } else {
+            format!("{}{suffix}", word(rng))
+        };
+
+        fit_length(text, &suffix, min_length, max_length, rng)
+    }
+}
+
+/// Truncates or pads the text to fit the length range, keeping the suffix
+/// that makes it unique whenever possible.
+fn fit_length(
+    text: String,
+    suffix: &str,
+    min_length: usize,
+    max_length: usize,
+    rng: &mut SplitMix64,
+) -> String {
+    let mut text = if text.chars().count() > max_length {
+        let suffix_length = suffix.chars().count();
+        if suffix_length <= max_length {
+            let head: String = text.chars().take(max_length - suffix_length).collect();
+            format!("{head}{suffix}")
+        } else {
+            text.chars().take(max_length).collect()
+        }
+    } else {
+        text
+    };
+    while text.chars().count() < min_length {
+        let letter = u8::try_from(rng.range_i64(0, 25)).unwrap_or_default();
+        text.push(char::from(b'a' + letter));
+    }
+    text
+}
+
+/// Returns a random word.
+fn word(rng: &mut SplitMix64) -> &'static str {
+    rng.choose(WORDS).copied().unwrap_or_default()
+}
+
+/// Returns a random date between `2000-01-01` and `2030-12-31`.
+fn date(rng: &mut SplitMix64) -> String {
+    let (year, month, day) = civil_from_days(rng.range_i64(FIRST_DAY, LAST_DAY));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Returns a random time of the day.
+fn time(rng: &mut SplitMix64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        rng.range_i64(0, 23),
+        rng.range_i64(0, 59),
+        rng.range_i64(0, 59)
+    )
+}
+
+/// Converts a number of days since the Unix epoch to a proleptic Gregorian
+/// calendar date.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(FIRST_DAY), (2000, 1, 1));
+        assert_eq!(civil_from_days(LAST_DAY), (2030, 12, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+    }
+
+    #[test]
+    fn test_fit_length() {
+        let mut rng = SplitMix64::new(0);
+        assert_eq!(
+            fit_length("abcdef7".to_owned(), "7", 1, 4, &mut rng),
+            "abc7"
+        );
+        assert_eq!(fit_length("abcdef".to_owned(), "", 1, 3, &mut rng), "abc");
+        assert_eq!(fit_length("ab".to_owned(), "", 4, 8, &mut rng).len(), 4);
+    }
+}
@@ -0,0 +1,308 @@
+//! Submodule providing the [`SyntheticDataset`] and [`SyntheticTable`] structs
+//! and their rendering as `INSERT` statements or `COPY` text.
+
+use std::fmt::Write;
+
+use super::SyntheticValue;
+use crate::quoting::quote_identifier;
+
+/// Maximum number of rows rendered in a single `INSERT` statement.
+const INSERT_BATCH_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The format a [`SyntheticDataset`] is rendered in.
+pub enum OutputFormat {
+    /// Multi-row `INSERT` statements.
+    #[default]
+    Insert,
+    /// `COPY ... FROM stdin` blocks, as produced by `pg_dump`.
+    Copy,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The rows generated for a table.
+pub struct SyntheticTable {
+    /// The schema of the table.
+    schema: String,
+    /// The name of the table.
+    name: String,
+    /// The names of the columns the rows provide values for.
+    columns: Vec<String>,
+    /// The generated rows, with one value per column.
+    rows: Vec<Vec<SyntheticValue>>,
+    /// Whether some of the columns are `GENERATED ALWAYS AS IDENTITY`.
+    overriding_system_value: bool,
+}
+
+impl SyntheticTable {
+    /// Creates a new `SyntheticTable` instance.
+    pub(crate) fn new(
+        schema: String,
+        name: String,
+        columns: Vec<String>,
+        rows: Vec<Vec<SyntheticValue>>,
+        overriding_system_value: bool,
+    ) -> Self {
+        Self {
+            schema,
+            name,
+            columns,
+            rows,
+            overriding_system_value,
+        }
+    }
+
+    /// Returns the schema of the table.
+    #[must_use]
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// Returns the name of the table.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the names of the columns the rows provide values for.
+    ///
+    /// Columns left to their default value are not listed.
+    #[must_use]
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Returns the generated rows, with one value per column.
+    #[must_use]
+    pub fn rows(&self) -> &[Vec<SyntheticValue>] {
+        &self.rows
+    }
+
+    /// Returns the values of the provided column, if the rows provide it.
+    #[must_use]
+    pub fn column_values<'a>(
+        &'a self,
+        column_name: &str,
+    ) -> Option<impl Iterator<Item = &'a SyntheticValue> + 'a> {
+        let position = self
+            .columns
+            .iter()
+            .position(|column| column == column_name)?;
+        Some(self.rows.iter().map(move |row| &row[position]))
+    }
+
+    /// Returns the quoted, schema-qualified name of the table.
+    #[must_use]
+    pub fn qualified_name(&self) -> String {
+        format!(
+            "{}.{}",
+            quote_identifier(&self.schema),
+            quote_identifier(&self.name)
+        )
+    }
+
+    /// Returns the quoted column list of the table.
+    fn column_list(&self) -> String {
+        self.columns
+            .iter()
+            .map(|column| quote_identifier(column))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Returns the rows rendered as `INSERT` statements.
+    #[must_use]
+    pub fn to_insert_statements(&self) -> String {
+        let mut sql = String::new();
+        if self.columns.is_empty() {
+            for _ in &self.rows {
+                let _ = writeln!(sql, "INSERT INTO {} DEFAULT VALUES;", self.qualified_name());
+            }
+            return sql;
+        }
+        let overriding = if self.overriding_system_value {
+            " OVERRIDING SYSTEM VALUE"
+        } else {
+            ""
+        };
+        for batch in self.rows.chunks(INSERT_BATCH_SIZE) {
+            let _ = writeln!(
+                sql,
+                "INSERT INTO {} ({}){overriding} VALUES",
+                self.qualified_name(),
+                self.column_list()
+            );
+            let tuples = batch
+                .iter()
+                .map(|row| {
+                    let values = row
+                        .iter()
+                        .map(SyntheticValue::to_sql_literal)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("    ({values})")
+                })
+                .collect::<Vec<_>>()
+                .join(",\n");
+            let _ = writeln!(sql, "{tuples};");
+        }
+        sql
+    }
+
+    /// Returns the rows rendered as a `COPY ... FROM stdin` block.
+    ///
+    /// Tables whose columns are all left to their default value cannot be
+    /// expressed as `COPY` and are rendered as `INSERT` statements instead.
+    #[must_use]
+    pub fn to_copy_text(&self) -> String {
+        if self.columns.is_empty() {
+            return self.to_insert_statements();
+        }
+        let mut text = format!(
+            "COPY {} ({}) FROM stdin;\n",
+            self.qualified_name(),
+            self.column_list()
+        );
+        for row in &self.rows {
+            let fields = row
+                .iter()
+                .map(SyntheticValue::to_copy_field)
+                .collect::<Vec<_>>()
+                .join("\t");
+            text.push_str(&fields);
+            text.push('\n');
+        }
+        text.push_str("\\.\n");
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The rows generated for a database, with parent tables preceding the tables
+/// referencing them.
+pub struct SyntheticDataset {
+    /// The generated tables, in insertion order.
+    tables: Vec<SyntheticTable>,
+}
+
+impl SyntheticDataset {
+    /// Creates a new `SyntheticDataset` instance.
+    pub(crate) fn new(tables: Vec<SyntheticTable>) -> Self {
+        Self { tables }
+    }
+
+    /// Returns the generated tables, in insertion order.
+    #[must_use]
+    pub fn tables(&self) -> &[SyntheticTable] {
+        &self.tables
+    }
+
+    /// Returns the rows generated for the provided table, if any.
+    #[must_use]
+    pub fn table(&self, schema: &str, name: &str) -> Option<&SyntheticTable> {
+        self.tables
+            .iter()
+            .find(|table| table.schema == schema && table.name == name)
+    }
+
+    /// Returns the dataset rendered as `INSERT` statements.
+    #[must_use]
+    pub fn to_insert_statements(&self) -> String {
+        self.render(OutputFormat::Insert)
+    }
+
+    /// Returns the dataset rendered as `COPY` text.
+    #[must_use]
+    pub fn to_copy_text(&self) -> String {
+        self.render(OutputFormat::Copy)
+    }
+
+    /// Returns the dataset rendered in the provided format.
+    #[must_use]
+    pub fn render(&self, format: OutputFormat) -> String {
+        self.tables
+            .iter()
+            .filter(|table| !table.rows.is_empty())
+            .map(|table| match format {
+                OutputFormat::Insert => table.to_insert_statements(),
+                OutputFormat::Copy => table.to_copy_text(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dummy_table() -> SyntheticTable {
+        SyntheticTable::new(
+            "public".to_owned(),
+            "users".to_owned(),
+            vec!["id".to_owned(), "name".to_owned()],
+            vec![
+                vec![
+                    SyntheticValue::Number("1".to_owned()),
+                    SyntheticValue::Text("Ada".to_owned()),
+                ],
+                vec![SyntheticValue::Number("2".to_owned()), SyntheticValue::Null],
+            ],
+            false,
+        )
+    }
+
+    #[test]
+    fn test_insert_statements() {
+        assert_eq!(
+            dummy_table().to_insert_statements(),
+            "INSERT INTO \"public\".\"users\" (\"id\", \"name\") VALUES\n    (1, 'Ada'),\n    (2, NULL);\n"
+        );
+    }
+
+    #[test]
+    fn test_copy_text() {
+        assert_eq!(
+            dummy_table().to_copy_text(),
+            "COPY \"public\".\"users\" (\"id\", \"name\") FROM stdin;\n1\tAda\n2\t\\N\n\\.\n"
+        );
+    }
+
+    #[test]
+    fn test_default_values() {
+        let table = SyntheticTable::new(
+            "public".to_owned(),
+            "counters".to_owned(),
+            Vec::new(),
+            vec![Vec::new()],
+            false,
+        );
+        assert_eq!(
+            table.to_copy_text(),
+            "INSERT INTO \"public\".\"counters\" DEFAULT VALUES;\n"
+        );
+    }
+
+    #[test]
+    fn test_dataset() {
+        let dataset = SyntheticDataset::new(vec![dummy_table()]);
+        assert!(dataset.table("public", "users").is_some());
+        assert!(dataset.table("public", "posts").is_none());
+        assert_eq!(
+            dataset
+                .table("public", "users")
+                .unwrap()
+                .column_values("id")
+                .unwrap()
+                .count(),
+            2
+        );
+        assert_eq!(
+            dataset.render(OutputFormat::Copy),
+            dummy_table().to_copy_text()
+        );
+    }
+}
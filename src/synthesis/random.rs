@@ -0,0 +1,108 @@
+//! Submodule providing the deterministic pseudo-random generator used by the
+//! [`DataSynthesizer`](super::DataSynthesizer).
+
+#[derive(Debug, Clone)]
+/// A `SplitMix64` pseudo-random number generator.
+///
+/// The synthesizer must produce the same rows for the same seed across
+/// platforms and releases, so it does not rely on an external source of
+/// entropy.
+pub(crate) struct SplitMix64 {
+    /// The internal state of the generator.
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Creates a new generator from the provided seed.
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next pseudo-random 64-bit integer.
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a pseudo-random float in `[0, 1)`.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        let high = u32::try_from(self.next_u64() >> 32).unwrap_or(u32::MAX);
+        f64::from(high) / 4_294_967_296.0
+    }
+
+    /// Returns a pseudo-random integer in `[low, high]`.
+    pub(crate) fn range_i64(&mut self, low: i64, high: i64) -> i64 {
+        debug_assert!(low <= high);
+        let span = high.abs_diff(low).wrapping_add(1);
+        let offset = if span == 0 {
+            self.next_u64()
+        } else {
+            self.next_u64() % span
+        };
+        low.wrapping_add_unsigned(offset)
+    }
+
+    /// Returns a pseudo-random float in `[low, high)`.
+    pub(crate) fn range_f64(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.next_f64()
+    }
+
+    /// Returns a pseudo-random index in `[0, len)`.
+    ///
+    /// # Panics
+    ///
+    /// * If `len` is zero.
+    pub(crate) fn below(&mut self, len: usize) -> usize {
+        assert!(len > 0, "Cannot draw an index from an empty range");
+        let len = u64::try_from(len).unwrap_or(u64::MAX);
+        usize::try_from(self.next_u64() % len).unwrap_or_default()
+    }
+
+    /// Returns `true` with the provided probability.
+    pub(crate) fn chance(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+
+    /// Returns a pseudo-randomly chosen element of the slice, if any.
+    pub(crate) fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            items.get(self.below(items.len()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic() {
+        let mut first = SplitMix64::new(42);
+        let mut second = SplitMix64::new(42);
+        for _ in 0..16 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut rng = SplitMix64::new(7);
+        for _ in 0..1000 {
+            let value = rng.range_i64(-3, 3);
+            assert!((-3..=3).contains(&value));
+            let float = rng.next_f64();
+            assert!((0.0..1.0).contains(&float));
+            assert!(rng.below(5) < 5);
+        }
+        assert_eq!(rng.range_i64(5, 5), 5);
+        let full = rng.range_i64(i64::MIN, i64::MAX);
+        assert!((i64::MIN..=i64::MAX).contains(&full));
+        assert!(rng.choose::<u8>(&[]).is_none());
+        assert_eq!(rng.choose(&[1]), Some(&1));
+    }
+}
@@ -0,0 +1,107 @@
+//! Submodule providing the [`SyntheticValue`] enum.
+
+use crate::quoting::quote_literal;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A value generated for a column.
+///
+/// Values are kept in their textual form and rendered either as SQL literals
+/// or as fields of the `COPY` text format: `PostgreSQL` coerces untyped
+/// literals to the type of the target column.
+pub enum SyntheticValue {
+    /// The `NULL` value.
+    Null,
+    /// A boolean value.
+    Boolean(bool),
+    /// A numeric value, rendered without quotes.
+    Number(String),
+    /// Any other value, rendered as a quoted string literal.
+    Text(String),
+}
+
+impl SyntheticValue {
+    /// Returns whether the value is `NULL`.
+    #[must_use]
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Returns the textual representation of the value, or `None` for `NULL`.
+    #[must_use]
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Null => None,
+            Self::Boolean(true) => Some("true"),
+            Self::Boolean(false) => Some("false"),
+            Self::Number(text) | Self::Text(text) => Some(text),
+        }
+    }
+
+    /// Returns the value as a SQL literal.
+    #[must_use]
+    pub fn to_sql_literal(&self) -> String {
+        match self {
+            Self::Null => "NULL".to_owned(),
+            Self::Boolean(true) => "TRUE".to_owned(),
+            Self::Boolean(false) => "FALSE".to_owned(),
+            Self::Number(number) => number.clone(),
+            Self::Text(text) => quote_literal(text),
+        }
+    }
+
+    /// Returns the value as a field of the `COPY` text format.
+    #[must_use]
+    pub fn to_copy_field(&self) -> String {
+        match self {
+            Self::Null => "\\N".to_owned(),
+            Self::Boolean(true) => "t".to_owned(),
+            Self::Boolean(false) => "f".to_owned(),
+            Self::Number(number) => number.clone(),
+            Self::Text(text) => {
+                let mut field = String::with_capacity(text.len());
+                for character in text.chars() {
+                    match character {
+                        '\\' => field.push_str("\\\\"),
+                        '\t' => field.push_str("\\t"),
+                        '\n' => field.push_str("\\n"),
+                        '\r' => field.push_str("\\r"),
+                        _ => field.push(character),
+                    }
+                }
+                field
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sql_literal() {
+        assert_eq!(SyntheticValue::Null.to_sql_literal(), "NULL");
+        assert_eq!(SyntheticValue::Boolean(true).to_sql_literal(), "TRUE");
+        assert_eq!(
+            SyntheticValue::Number("-1.5".into()).to_sql_literal(),
+            "-1.5"
+        );
+        assert_eq!(
+            SyntheticValue::Text("O'Brien".into()).to_sql_literal(),
+            "'O''Brien'"
+        );
+    }
+
+    #[test]
+    fn test_copy_field() {
+        assert_eq!(SyntheticValue::Null.to_copy_field(), "\\N");
+        assert_eq!(SyntheticValue::Boolean(false).to_copy_field(), "f");
+        assert_eq!(
+            SyntheticValue::Text("a\tb\\c\n".into()).to_copy_field(),
+            "a\\tb\\\\c\\n"
+        );
+        assert_eq!(SyntheticValue::Boolean(true).as_text(), Some("true"));
+        assert!(SyntheticValue::Null.as_text().is_none());
+    }
+}
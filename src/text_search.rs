@@ -0,0 +1,865 @@
+//! Submodule introspecting the text search configurations of the database,
+//! and the `tsvector` columns and indexes built with them.
+//!
+//! A [`TextSearchConfig`] maps each token type of its parser, as in
+//! `asciiword` or `email`, to the dictionaries consulted in order to turn
+//! the tokens into lexemes. Matching a `tsquery` against a `tsvector` only
+//! works if both were built with the same configuration: a query normalized
+//! by the `simple` configuration does not find the stemmed lexemes of an
+//! index built with the `english` one, and the index silently returns
+//! nothing.
+//!
+//! A [`TextSearchUsage`] links a generated `tsvector` column, or an index on
+//! `tsvector` values such as a GIN index, to the configurations named in its
+//! expression, so that a search service can check with
+//! [`TextSearchUsage::is_built_with`] that it queries with the same
+//! configuration. An index on a generated column inherits the
+//! configurations of the column.
+
+use std::collections::HashMap;
+
+use diesel::{
+    PgConnection, QueryDsl, QueryableByName, RunQueryDsl, SelectableHelper,
+    sql_types::{Array, Integer, Nullable, Oid, Text},
+};
+
+use crate::{
+    identity::{QualifiedName, TsConfigOid},
+    models::{PgTsConfig, PgTsConfigMap, PgTsDict, PgTsParser, PgTsTemplate},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A text search dictionary, as consulted by a [`TokenTypeMapping`].
+pub struct TextSearchDictionary {
+    /// The underlying dictionary model.
+    model: PgTsDict,
+    /// The qualified name of the dictionary.
+    name: QualifiedName,
+    /// The template the dictionary is built from.
+    template: PgTsTemplate,
+}
+
+impl TextSearchDictionary {
+    /// Returns the underlying dictionary model.
+    #[must_use]
+    pub fn model(&self) -> &PgTsDict {
+        &self.model
+    }
+
+    /// Returns the qualified name of the dictionary, as in
+    /// `pg_catalog.english_stem`.
+    #[must_use]
+    pub fn name(&self) -> &QualifiedName {
+        &self.name
+    }
+
+    /// Returns the template the dictionary is built from, as in `snowball`.
+    #[must_use]
+    pub fn template(&self) -> &PgTsTemplate {
+        &self.template
+    }
+
+    /// Returns the options the dictionary was created with, as in
+    /// `language = 'english'`.
+    #[must_use]
+    pub fn options(&self) -> Option<&str> {
+        self.model.dictinitoption.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The dictionaries a [`TextSearchConfig`] consults, in order, for the
+/// tokens of a type.
+pub struct TokenTypeMapping {
+    /// The identifier of the token type in its parser.
+    token_type: i32,
+    /// The alias of the token type, as in `asciiword`.
+    alias: String,
+    /// The description of the token type, as in `Word, all ASCII`.
+    description: String,
+    /// The dictionaries consulted for the tokens, in order.
+    dictionaries: Vec<TextSearchDictionary>,
+}
+
+impl TokenTypeMapping {
+    /// Returns the identifier of the token type in its parser.
+    #[must_use]
+    pub fn token_type(&self) -> i32 {
+        self.token_type
+    }
+
+    /// Returns the alias of the token type, as in `asciiword`.
+    #[must_use]
+    pub fn alias(&self) -> &str {
+        &self.alias
+    }
+
+    /// Returns the description of the token type, as in `Word, all ASCII`.
+    #[must_use]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns the dictionaries consulted for the tokens, in order: the
+    /// first one recognizing a token produces its lexemes.
+    #[must_use]
+    pub fn dictionaries(&self) -> &[TextSearchDictionary] {
+        &self.dictionaries
+    }
+}
+
+#[derive(QueryableByName)]
+/// A text search configuration along with the name of its schema.
+struct ConfigRow {
+    /// The configuration.
+    #[diesel(embed)]
+    config: PgTsConfig,
+    /// The schema of the configuration.
+    #[diesel(sql_type = Text)]
+    schema_name: String,
+}
+
+#[derive(QueryableByName)]
+/// A text search dictionary along with the name of its schema.
+struct DictionaryRow {
+    /// The dictionary.
+    #[diesel(embed)]
+    dictionary: PgTsDict,
+    /// The schema of the dictionary.
+    #[diesel(sql_type = Text)]
+    schema_name: String,
+}
+
+#[derive(QueryableByName)]
+/// A token type of a text search parser, as returned by `ts_token_type`.
+struct TokenTypeRow {
+    /// The identifier of the token type.
+    #[diesel(sql_type = Integer)]
+    tokid: i32,
+    /// The alias of the token type.
+    #[diesel(sql_type = Text)]
+    alias: String,
+    /// The description of the token type.
+    #[diesel(sql_type = Text)]
+    description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A text search configuration, with its parser and the dictionaries it
+/// consults for each token type.
+pub struct TextSearchConfig {
+    /// The underlying configuration model.
+    model: PgTsConfig,
+    /// The qualified name of the configuration.
+    name: QualifiedName,
+    /// The parser splitting text into tokens.
+    parser: PgTsParser,
+    /// The mappings of the token types, sorted by token type.
+    mappings: Vec<TokenTypeMapping>,
+}
+
+impl TextSearchConfig {
+    /// Loads the text search configurations of the database, including the
+    /// built-in ones of `pg_catalog`, sorted by qualified name.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::pg_catalog::{
+            pg_ts_config_map::pg_ts_config_map, pg_ts_parser::pg_ts_parser,
+            pg_ts_template::pg_ts_template,
+        };
+
+        let configs: Vec<ConfigRow> = diesel::sql_query(
+            r"
+            SELECT
+                c.oid,
+                c.cfgname::text AS cfgname,
+                c.cfgnamespace,
+                c.cfgowner,
+                c.cfgparser,
+                n.nspname::text AS schema_name
+            FROM pg_catalog.pg_ts_config c
+            JOIN pg_catalog.pg_namespace n ON n.oid = c.cfgnamespace
+            ORDER BY n.nspname, c.cfgname
+            ",
+        )
+        .load(conn)?;
+        let dictionaries: Vec<DictionaryRow> = diesel::sql_query(
+            r"
+            SELECT
+                d.oid,
+                d.dictname::text AS dictname,
+                d.dictnamespace,
+                d.dictowner,
+                d.dicttemplate,
+                d.dictinitoption,
+                n.nspname::text AS schema_name
+            FROM pg_catalog.pg_ts_dict d
+            JOIN pg_catalog.pg_namespace n ON n.oid = d.dictnamespace
+            ",
+        )
+        .load(conn)?;
+        let templates: HashMap<u32, PgTsTemplate> = pg_ts_template::table
+            .select(PgTsTemplate::as_select())
+            .load::<PgTsTemplate>(conn)?
+            .into_iter()
+            .map(|template| (template.oid, template))
+            .collect();
+        let parsers: HashMap<u32, PgTsParser> = pg_ts_parser::table
+            .select(PgTsParser::as_select())
+            .load::<PgTsParser>(conn)?
+            .into_iter()
+            .map(|parser| (parser.oid, parser))
+            .collect();
+        let maps = pg_ts_config_map::table
+            .order_by((
+                pg_ts_config_map::mapcfg,
+                pg_ts_config_map::maptokentype,
+                pg_ts_config_map::mapseqno,
+            ))
+            .select(PgTsConfigMap::as_select())
+            .load::<PgTsConfigMap>(conn)?;
+
+        let mut token_types: HashMap<u32, HashMap<i32, TokenTypeRow>> = HashMap::new();
+        for parser in parsers.keys() {
+            let rows: Vec<TokenTypeRow> = diesel::sql_query(
+                "SELECT tokid, alias, description FROM pg_catalog.ts_token_type($1)",
+            )
+            .bind::<Oid, _>(*parser)
+            .load(conn)?;
+            token_types.insert(
+                *parser,
+                rows.into_iter().map(|row| (row.tokid, row)).collect(),
+            );
+        }
+        let dictionaries: HashMap<u32, TextSearchDictionary> = dictionaries
+            .into_iter()
+            .filter_map(|dictionary| {
+                let template = templates.get(&dictionary.dictionary.dicttemplate)?.clone();
+                Some((
+                    dictionary.dictionary.oid,
+                    TextSearchDictionary {
+                        name: QualifiedName::new(
+                            &dictionary.schema_name,
+                            &dictionary.dictionary.dictname,
+                        ),
+                        model: dictionary.dictionary,
+                        template,
+                    },
+                ))
+            })
+            .collect();
+
+        Ok(configs
+            .into_iter()
+            .filter_map(|config| {
+                let parser = parsers.get(&config.config.cfgparser)?.clone();
+                let mappings = token_type_mappings(
+                    maps.iter().filter(|map| map.mapcfg == config.config.oid),
+                    token_types.get(&parser.oid),
+                    &dictionaries,
+                );
+                Some(Self {
+                    name: QualifiedName::new(&config.schema_name, &config.config.cfgname),
+                    model: config.config,
+                    parser,
+                    mappings,
+                })
+            })
+            .collect())
+    }
+
+    /// Returns the underlying configuration model.
+    #[must_use]
+    pub fn model(&self) -> &PgTsConfig {
+        &self.model
+    }
+
+    /// Returns the OID of the configuration.
+    #[must_use]
+    pub fn oid(&self) -> TsConfigOid {
+        TsConfigOid::new(self.model.oid)
+    }
+
+    /// Returns the qualified name of the configuration, as in
+    /// `pg_catalog.english`.
+    #[must_use]
+    pub fn name(&self) -> &QualifiedName {
+        &self.name
+    }
+
+    /// Returns the parser splitting text into tokens.
+    #[must_use]
+    pub fn parser(&self) -> &PgTsParser {
+        &self.parser
+    }
+
+    /// Returns the mappings of the token types, sorted by token type. Token
+    /// types without a mapping are dropped from the documents.
+    #[must_use]
+    pub fn mappings(&self) -> &[TokenTypeMapping] {
+        &self.mappings
+    }
+
+    /// Returns the mapping of the token type with the provided alias, as in
+    /// `asciiword`, if any.
+    #[must_use]
+    pub fn mapping(&self, alias: &str) -> Option<&TokenTypeMapping> {
+        self.mappings.iter().find(|mapping| mapping.alias == alias)
+    }
+}
+
+/// Groups the map entries of a configuration, sorted by token type and
+/// sequence number, into the mappings of its token types.
+///
+/// # Arguments
+///
+/// * `maps` - The map entries of the configuration.
+/// * `token_types` - The token types of the parser of the configuration, by
+///   identifier.
+/// * `dictionaries` - The dictionaries of the database, by OID.
+fn token_type_mappings<'a>(
+    maps: impl Iterator<Item = &'a PgTsConfigMap>,
+    token_types: Option<&HashMap<i32, TokenTypeRow>>,
+    dictionaries: &HashMap<u32, TextSearchDictionary>,
+) -> Vec<TokenTypeMapping> {
+    let mut mappings: Vec<TokenTypeMapping> = Vec::new();
+    for map in maps {
+        if mappings
+            .last()
+            .is_none_or(|mapping| mapping.token_type != map.maptokentype)
+        {
+            let token_type = token_types.and_then(|rows| rows.get(&map.maptokentype));
+            mappings.push(TokenTypeMapping {
+                token_type: map.maptokentype,
+                alias: token_type.map(|row| row.alias.clone()).unwrap_or_default(),
+                description: token_type
+                    .map(|row| row.description.clone())
+                    .unwrap_or_default(),
+                dictionaries: Vec::new(),
+            });
+        }
+        if let (Some(mapping), Some(dictionary)) =
+            (mappings.last_mut(), dictionaries.get(&map.mapdict))
+        {
+            mapping.dictionaries.push(dictionary.clone());
+        }
+    }
+    mappings
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The configuration an expression building a `tsvector` uses.
+pub enum TextSearchConfigRef {
+    /// A configuration named by a constant, as in
+    /// `to_tsvector('english', body)`.
+    Named {
+        /// The name of the configuration, qualified if it is not on the
+        /// search path.
+        name: String,
+        /// The OID of the configuration, or `None` if no configuration of
+        /// that name exists.
+        oid: Option<TsConfigOid>,
+    },
+    /// A configuration computed by an expression, as in
+    /// `to_tsvector(language, body)`, which varies from row to row.
+    Expression(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The object building `tsvector` values of a [`TextSearchUsage`].
+pub enum TextSearchObject {
+    /// A generated column of type `tsvector`.
+    Column(String),
+    /// An index on `tsvector` values.
+    Index {
+        /// The name of the index.
+        name: String,
+        /// The access method of the index, as in `gin`.
+        access_method: String,
+    },
+}
+
+#[derive(QueryableByName)]
+/// A generated `tsvector` column.
+struct TsvectorColumnRow {
+    /// The schema of the table.
+    #[diesel(sql_type = Text)]
+    schema_name: String,
+    /// The name of the table.
+    #[diesel(sql_type = Text)]
+    table_name: String,
+    /// The name of the column.
+    #[diesel(sql_type = Text)]
+    column_name: String,
+    /// The name of the column, quoted as needed.
+    #[diesel(sql_type = Text)]
+    quoted_name: String,
+    /// The generation expression of the column.
+    #[diesel(sql_type = Text)]
+    expression: String,
+}
+
+#[derive(QueryableByName)]
+/// An index on `tsvector` values.
+struct TsvectorIndexRow {
+    /// The schema of the table.
+    #[diesel(sql_type = Text)]
+    schema_name: String,
+    /// The name of the table.
+    #[diesel(sql_type = Text)]
+    table_name: String,
+    /// The name of the index.
+    #[diesel(sql_type = Text)]
+    index_name: String,
+    /// The access method of the index.
+    #[diesel(sql_type = Text)]
+    access_method: String,
+    /// The key columns or expressions of the index, quoted as needed.
+    #[diesel(sql_type = Array<Text>)]
+    key_columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A generated column or an index building `tsvector` values, with the text
+/// search configurations it uses.
+pub struct TextSearchUsage {
+    /// The table of the column or index.
+    table: QualifiedName,
+    /// The column or index.
+    object: TextSearchObject,
+    /// The generation expression of the column, or the key columns and
+    /// expressions of the index.
+    expression: String,
+    /// The configurations used by the expression.
+    configs: Vec<TextSearchConfigRef>,
+}
+
+impl TextSearchUsage {
+    /// Loads the generated `tsvector` columns and the indexes on `tsvector`
+    /// values of the tables outside of the system schemas, sorted by table.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        let columns: Vec<TsvectorColumnRow> = diesel::sql_query(
+            r"
+            SELECT
+                n.nspname::text AS schema_name,
+                t.relname::text AS table_name,
+                a.attname::text AS column_name,
+                quote_ident(a.attname) AS quoted_name,
+                pg_get_expr(d.adbin, d.adrelid, true) AS expression
+            FROM pg_attribute a
+            JOIN pg_class t ON t.oid = a.attrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+            WHERE a.attgenerated = 's'
+                AND a.atttypid = 'pg_catalog.tsvector'::regtype
+                AND NOT a.attisdropped
+                AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+            ORDER BY n.nspname, t.relname, a.attnum
+            ",
+        )
+        .load(conn)?;
+        let indexes: Vec<TsvectorIndexRow> = diesel::sql_query(
+            r"
+            SELECT
+                n.nspname::text AS schema_name,
+                t.relname::text AS table_name,
+                c.relname::text AS index_name,
+                am.amname::text AS access_method,
+                ARRAY(
+                    SELECT pg_get_indexdef(i.indexrelid, k, true)
+                    FROM generate_series(1, i.indnkeyatts) AS k
+                    ORDER BY k
+                ) AS key_columns
+            FROM pg_index i
+            JOIN pg_class c ON c.oid = i.indexrelid
+            JOIN pg_class t ON t.oid = i.indrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            JOIN pg_am am ON am.oid = c.relam
+            WHERE EXISTS (
+                    SELECT 1
+                    FROM unnest(i.indclass::oid[]) AS k(opclass)
+                    JOIN pg_opclass o ON o.oid = k.opclass
+                    WHERE o.opcintype = 'pg_catalog.tsvector'::regtype
+                )
+                AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+            ORDER BY n.nspname, t.relname, c.relname
+            ",
+        )
+        .load(conn)?;
+
+        let mut oids: HashMap<String, Option<TsConfigOid>> = HashMap::new();
+        let mut usages = Vec::new();
+        for column in &columns {
+            usages.push(Self {
+                table: QualifiedName::new(&column.schema_name, &column.table_name),
+                object: TextSearchObject::Column(column.column_name.clone()),
+                expression: column.expression.clone(),
+                configs: resolve_configs(&column.expression, &mut oids, conn)?,
+            });
+        }
+        for index in indexes {
+            let mut configs = Vec::new();
+            for key in &index.key_columns {
+                let column = columns.iter().find(|column| {
+                    column.schema_name == index.schema_name
+                        && column.table_name == index.table_name
+                        && &column.quoted_name == key
+                });
+                let expression = column.map_or(key.as_str(), |column| column.expression.as_str());
+                for config in resolve_configs(expression, &mut oids, conn)? {
+                    if !configs.contains(&config) {
+                        configs.push(config);
+                    }
+                }
+            }
+            usages.push(Self {
+                table: QualifiedName::new(&index.schema_name, &index.table_name),
+                object: TextSearchObject::Index {
+                    name: index.index_name,
+                    access_method: index.access_method,
+                },
+                expression: index.key_columns.join(", "),
+                configs,
+            });
+        }
+        usages.sort_by(|left, right| left.table.cmp(&right.table));
+        Ok(usages)
+    }
+
+    /// Returns the table of the column or index.
+    #[must_use]
+    pub fn table(&self) -> &QualifiedName {
+        &self.table
+    }
+
+    /// Returns the column or index.
+    #[must_use]
+    pub fn object(&self) -> &TextSearchObject {
+        &self.object
+    }
+
+    /// Returns the generation expression of the column, or the key columns
+    /// and expressions of the index.
+    #[must_use]
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Returns the configurations used by the expression, which is empty if
+    /// it does not call `to_tsvector`, as when the `tsvector` values are
+    /// maintained by a trigger.
+    #[must_use]
+    pub fn configs(&self) -> &[TextSearchConfigRef] {
+        &self.configs
+    }
+
+    #[must_use]
+    /// Returns whether the `tsvector` values are known to be built with the
+    /// provided configuration only, so that querying them with it is
+    /// consistent.
+    pub fn is_built_with(&self, config: &TextSearchConfig) -> bool {
+        !self.configs.is_empty()
+            && self.configs.iter().all(|reference| {
+                matches!(reference, TextSearchConfigRef::Named { oid: Some(oid), .. } if *oid == config.oid())
+            })
+    }
+}
+
+#[derive(QueryableByName)]
+/// The OID of a text search configuration, as returned by `to_regconfig`.
+struct ConfigOidRow {
+    /// The OID of the configuration, or `None` if it does not exist.
+    #[diesel(sql_type = Nullable<Oid>)]
+    oid: Option<u32>,
+}
+
+/// Returns the configurations used by the expression, resolving their names
+/// with the search path of the connection.
+///
+/// # Arguments
+///
+/// * `expression` - The expression building `tsvector` values.
+/// * `oids` - The OIDs of the names resolved so far, to resolve each name
+///   once.
+/// * `conn` - A mutable reference to a `PgConnection`
+fn resolve_configs(
+    expression: &str,
+    oids: &mut HashMap<String, Option<TsConfigOid>>,
+    conn: &mut PgConnection,
+) -> Result<Vec<TextSearchConfigRef>, diesel::result::Error> {
+    let mut configs = Vec::new();
+    for config in config_references(expression) {
+        configs.push(match config {
+            ParsedConfig::Literal(name) => {
+                let oid = if let Some(oid) = oids.get(&name) {
+                    *oid
+                } else {
+                    let oid = diesel::sql_query("SELECT pg_catalog.to_regconfig($1)::oid AS oid")
+                        .bind::<Text, _>(&name)
+                        .get_result::<ConfigOidRow>(conn)?
+                        .oid
+                        .map(TsConfigOid::new);
+                    oids.insert(name.clone(), oid);
+                    oid
+                };
+                TextSearchConfigRef::Named { name, oid }
+            }
+            ParsedConfig::Expression(expression) => TextSearchConfigRef::Expression(expression),
+        });
+    }
+    Ok(configs)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The configuration argument of a call to `to_tsvector` in an expression.
+enum ParsedConfig {
+    /// A constant, as in `'english'::regconfig`.
+    Literal(String),
+    /// Any other expression.
+    Expression(String),
+}
+
+/// Returns the configurations passed to the `to_tsvector`,
+/// `json_to_tsvector` and `jsonb_to_tsvector` calls of the expression, as
+/// printed by `pg_get_expr`, in order and without duplicates.
+///
+/// Calls without a configuration argument are skipped, as they depend on
+/// the `default_text_search_config` setting and are thus not immutable,
+/// which generated columns and indexes require.
+fn config_references(expression: &str) -> Vec<ParsedConfig> {
+    const FUNCTION: &str = "to_tsvector(";
+    let mut configs = Vec::new();
+    let mut rest = expression;
+    while let Some(position) = rest.find(FUNCTION) {
+        let called = &rest[..position];
+        rest = &rest[position + FUNCTION.len()..];
+        if called.ends_with("array_") {
+            continue;
+        }
+        let Some(arguments) = top_level_arguments(rest) else {
+            break;
+        };
+        let [config, _, ..] = arguments.as_slice() else {
+            continue;
+        };
+        let config = match literal(config) {
+            Some(name) => ParsedConfig::Literal(name),
+            None => ParsedConfig::Expression((*config).to_owned()),
+        };
+        if !configs.contains(&config) {
+            configs.push(config);
+        }
+    }
+    configs
+}
+
+/// Splits the arguments of a call, from the text following its opening
+/// parenthesis, at the commas outside of nested parentheses and string
+/// literals, or returns `None` if the call is not closed.
+fn top_level_arguments(text: &str) -> Option<Vec<&str>> {
+    let mut arguments = Vec::new();
+    let mut depth = 0_usize;
+    let mut in_literal = false;
+    let mut start = 0;
+    for (position, c) in text.char_indices() {
+        match c {
+            '\'' => in_literal = !in_literal,
+            _ if in_literal => {}
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                arguments.push(text[start..position].trim());
+                return Some(arguments);
+            }
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push(text[start..position].trim());
+                start = position + 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the value of a `regconfig` constant, as in
+/// `'english'::regconfig`, or `None` if the argument is not one.
+fn literal(argument: &str) -> Option<String> {
+    let quoted = argument
+        .strip_suffix("::regconfig")?
+        .strip_prefix('\'')?
+        .strip_suffix('\'')?;
+    Some(quoted.replace("''", "'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(configs: Vec<TextSearchConfigRef>) -> TextSearchUsage {
+        TextSearchUsage {
+            table: QualifiedName::new("public", "posts"),
+            object: TextSearchObject::Index {
+                name: "posts_search_idx".to_owned(),
+                access_method: "gin".to_owned(),
+            },
+            expression: "search".to_owned(),
+            configs,
+        }
+    }
+
+    fn config(oid: u32, name: &str) -> TextSearchConfig {
+        TextSearchConfig {
+            model: PgTsConfig {
+                oid,
+                cfgname: name.to_owned(),
+                cfgnamespace: 11,
+                cfgowner: 10,
+                cfgparser: 3722,
+            },
+            name: QualifiedName::new("pg_catalog", name),
+            parser: PgTsParser {
+                oid: 3722,
+                prsname: "default".to_owned(),
+                prsnamespace: 11,
+                prsstart: 3717,
+                prstoken: 3718,
+                prsend: 3719,
+                prsheadline: 3720,
+                prslextype: 3721,
+            },
+            mappings: Vec::new(),
+        }
+    }
+
+    fn dictionary(oid: u32, name: &str) -> TextSearchDictionary {
+        TextSearchDictionary {
+            model: PgTsDict {
+                oid,
+                dictname: name.to_owned(),
+                dictnamespace: 11,
+                dictowner: 10,
+                dicttemplate: 3727,
+                dictinitoption: None,
+            },
+            name: QualifiedName::new("pg_catalog", name),
+            template: PgTsTemplate {
+                oid: 3727,
+                tmplname: "simple".to_owned(),
+                tmplnamespace: 11,
+                tmplinit: 3725,
+                tmpllexize: 3726,
+            },
+        }
+    }
+
+    #[test]
+    fn test_token_type_mappings() {
+        let map = |maptokentype, mapseqno, mapdict| PgTsConfigMap {
+            mapcfg: 13_000,
+            maptokentype,
+            mapseqno,
+            mapdict,
+        };
+        let maps = [map(1, 1, 20), map(1, 2, 10), map(2, 1, 10), map(3, 1, 99)];
+        let token_types: HashMap<i32, TokenTypeRow> = [(1, "asciiword"), (2, "word")]
+            .into_iter()
+            .map(|(tokid, alias)| {
+                (
+                    tokid,
+                    TokenTypeRow {
+                        tokid,
+                        alias: alias.to_owned(),
+                        description: String::new(),
+                    },
+                )
+            })
+            .collect();
+        let dictionaries: HashMap<u32, TextSearchDictionary> = [
+            (10, dictionary(10, "simple")),
+            (20, dictionary(20, "english_stem")),
+        ]
+        .into();
+
+        let mappings = token_type_mappings(maps.iter(), Some(&token_types), &dictionaries);
+        let names = |mapping: &TokenTypeMapping| -> Vec<String> {
+            mapping
+                .dictionaries()
+                .iter()
+                .map(|dictionary| dictionary.name().to_string())
+                .collect()
+        };
+        assert_eq!(mappings.len(), 3);
+        assert_eq!(mappings[0].alias(), "asciiword");
+        assert_eq!(
+            names(&mappings[0]),
+            ["pg_catalog.english_stem", "pg_catalog.simple"]
+        );
+        assert_eq!(names(&mappings[1]), ["pg_catalog.simple"]);
+        assert_eq!(mappings[2].alias(), "");
+        assert!(mappings[2].dictionaries().is_empty());
+    }
+
+    #[test]
+    fn test_config_references() {
+        assert_eq!(
+            config_references(
+                "(setweight(to_tsvector('english'::regconfig, COALESCE(title, ''::text)), 'A'::\"char\") \
+                 || setweight(to_tsvector('english'::regconfig, body), 'B'::\"char\"))"
+            ),
+            [ParsedConfig::Literal("english".to_owned())]
+        );
+        assert_eq!(
+            config_references(
+                "jsonb_to_tsvector('search.\"it''s\"'::regconfig, data, '[\"string\"]'::jsonb)"
+            ),
+            [ParsedConfig::Literal("search.\"it's\"".to_owned())]
+        );
+        assert_eq!(
+            config_references("to_tsvector(language, body)"),
+            [ParsedConfig::Expression("language".to_owned())]
+        );
+        assert!(config_references("array_to_tsvector(tags)").is_empty());
+        assert!(config_references("to_tsvector(body)").is_empty());
+    }
+
+    #[test]
+    fn test_is_built_with() {
+        let english = config(13_000, "english");
+        let simple = config(3748, "simple");
+        let named = |oid| TextSearchConfigRef::Named {
+            name: "english".to_owned(),
+            oid,
+        };
+
+        let english_oid = Some(TsConfigOid::new(13_000));
+        assert!(usage(vec![named(english_oid)]).is_built_with(&english));
+        assert!(!usage(vec![named(english_oid)]).is_built_with(&simple));
+        assert!(!usage(vec![named(None)]).is_built_with(&english));
+        assert!(!usage(Vec::new()).is_built_with(&english));
+        assert!(
+            !usage(vec![
+                named(english_oid),
+                TextSearchConfigRef::Expression("language".to_owned()),
+            ])
+            .is_built_with(&english)
+        );
+    }
+}
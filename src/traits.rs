@@ -4,12 +4,19 @@
 //! to provide common functionality:
 //!
 //! - [`HasOid`]: For types that have a `PostgreSQL` Object Identifier (OID)
+//! - [`HasRelOid`], [`HasTypeOid`], [`HasProcOid`] and [`HasQualifiedName`]:
+//!   For types carrying the typed identifiers of [`crate::identity`]
 //! - [`PostgresType`]: For types that can resolve their `PostgreSQL` type
 //!   information
+//! - [`HasAcl`]: For catalog types carrying an access control list
 //!
 //! These traits are used by the caching infrastructure and type resolution
 //! logic.
 
+pub mod acl;
+pub use acl::HasAcl;
+pub mod identity;
+pub use identity::{HasProcOid, HasQualifiedName, HasRelOid, HasTypeOid};
 pub mod oid;
 pub use oid::HasOid;
 pub mod postgres_type;
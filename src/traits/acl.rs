@@ -0,0 +1,32 @@
+//! Trait for catalog types carrying an access control list.
+//!
+//! This module defines the [`HasAcl`] trait for the system catalog models
+//! with an `*acl` column, such as `pg_class.relacl` or `pg_proc.proacl`,
+//! whose `aclitem`s are parsed into an [`Acl`].
+
+use crate::acl::{Acl, AclError};
+
+/// Trait for types carrying the access control list of a `PostgreSQL`
+/// object.
+///
+/// ## Implementors
+///
+/// This trait is implemented for catalog types such as:
+/// - `PGClass`, `PgProc`, `PgType` and `PgNamespace`
+/// - `PgAttribute`, for the privileges granted on a column
+/// - `PgDefaultAcl`, whose list is never `NULL`
+pub trait HasAcl {
+    /// Returns the raw `aclitem`s of the object, or `None` if the list is
+    /// `NULL` and the object has the default privileges of its kind.
+    fn acl_items(&self) -> Option<&[String]>;
+
+    /// Returns the parsed access control list of the object, or `None` if
+    /// it is `NULL`, which [`Acl::resolve`] expands to the default one.
+    ///
+    /// # Errors
+    ///
+    /// * If an item is not a valid `aclitem`.
+    fn acl(&self) -> Result<Option<Acl>, AclError> {
+        self.acl_items().map(Acl::parse).transpose()
+    }
+}
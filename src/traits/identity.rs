@@ -0,0 +1,56 @@
+//! Traits for models carrying typed identifiers.
+//!
+//! This module defines traits exposing the typed identifiers of
+//! [`crate::identity`]: the OIDs of the relations, types and functions a model
+//! refers to, and the qualified names of the information schema objects,
+//! which carry no OID.
+
+use crate::identity::{ProcOid, QualifiedName, RelOid, TypeOid};
+
+/// Trait for models identifying a relation of `pg_class`.
+///
+/// ## Implementors
+///
+/// This trait is implemented for catalog types such as:
+/// - `PGClass` (using its `oid` field)
+/// - `PgIndex` (using its `indexrelid` field, the OID of the index itself)
+/// - `PgAttribute`, `PgTrigger`, `PgPolicyTable`, `PgForeignTable` and other
+///   rows belonging to a relation (using the OID of that relation)
+pub trait HasRelOid {
+    /// Returns the OID of the relation.
+    fn rel_oid(&self) -> RelOid;
+}
+
+/// Trait for models identifying a type of `pg_type`.
+///
+/// ## Implementors
+///
+/// This trait is implemented for `PgType`, for the rows describing a type
+/// such as `PgEnum` and `PgRange`, and for `PgAttribute` and
+/// `ColumnMetadata` (using the type of the column).
+pub trait HasTypeOid {
+    /// Returns the OID of the type.
+    fn type_oid(&self) -> TypeOid;
+}
+
+/// Trait for models identifying a function of `pg_proc`.
+///
+/// ## Implementors
+///
+/// This trait is implemented for `PgProc`, `PgAggregate` and for the
+/// triggers executing a function, `PgTrigger` and `PgEventTrigger`.
+pub trait HasProcOid {
+    /// Returns the OID of the function.
+    fn proc_oid(&self) -> ProcOid;
+}
+
+/// Trait for models identified by a schema-qualified name.
+///
+/// ## Implementors
+///
+/// This trait is implemented for the information schema models `Table`,
+/// `TableConstraint`, `CheckConstraint` and `KeyColumnUsage`.
+pub trait HasQualifiedName {
+    /// Returns the schema-qualified name of the object.
+    fn qualified_name(&self) -> QualifiedName;
+}
@@ -0,0 +1,701 @@
+//! Submodule reporting on the health of the vacuum of the user tables.
+//!
+//! The [`VacuumReport`] combines `pg_stat_user_tables`, `pg_class`,
+//! `pg_database` and `pg_stat_progress_vacuum` to report, for each table
+//! other than the partitioned ones, which are vacuumed through their
+//! partitions:
+//!
+//! - the ratio of dead tuples and an estimate of the bloat of the heap;
+//! - whether autovacuum is due to vacuum or analyze it, honoring the
+//!   per-table storage parameters, read through
+//!   [`StorageParameters`](crate::model_metadata::StorageParameters);
+//! - the risk of transaction ID wraparound, from the age of `relfrozenxid`;
+//!
+//! along with the wraparound risk of each database and the vacuums in
+//! progress. All the structs are serializable with the `serde` feature, so
+//! they can be exported as they are.
+
+use std::time::SystemTime;
+
+use bigdecimal::ToPrimitive;
+use diesel::{
+    PgConnection, QueryableByName, RunQueryDsl,
+    sql_types::{Array, BigInt, Double, Float, Integer, Nullable, Oid, Text, Timestamp},
+};
+
+use crate::{model_metadata::StorageParameters, models::PgStatProgressVacuum};
+
+/// Size of the header of a heap page, in bytes.
+const PAGE_HEADER_BYTES: f64 = 24.0;
+
+/// Size of the header of a heap tuple and of its line pointer, in bytes.
+const TUPLE_OVERHEAD_BYTES: f64 = 28.0;
+
+/// Age from which a transaction ID wraparound is considered high risk.
+const HIGH_WRAPAROUND_AGE: i64 = 1_000_000_000;
+
+/// Age from which a transaction ID wraparound is considered critical: the
+/// server starts warning about it at 40 million transactions from the
+/// 2^31 limit, and stops assigning transaction IDs at 3 million.
+const CRITICAL_WRAPAROUND_AGE: i64 = 2_000_000_000;
+
+#[derive(QueryableByName, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The server-wide autovacuum settings.
+pub struct VacuumSettings {
+    #[diesel(sql_type = BigInt)]
+    /// The `autovacuum_vacuum_threshold` setting.
+    pub vacuum_threshold: i64,
+    #[diesel(sql_type = Double)]
+    /// The `autovacuum_vacuum_scale_factor` setting.
+    pub vacuum_scale_factor: f64,
+    #[diesel(sql_type = BigInt)]
+    /// The `autovacuum_analyze_threshold` setting.
+    pub analyze_threshold: i64,
+    #[diesel(sql_type = Double)]
+    /// The `autovacuum_analyze_scale_factor` setting.
+    pub analyze_scale_factor: f64,
+    #[diesel(sql_type = BigInt)]
+    /// The `autovacuum_freeze_max_age` setting.
+    pub freeze_max_age: i64,
+    #[diesel(sql_type = BigInt)]
+    /// The `autovacuum_multixact_freeze_max_age` setting.
+    pub multixact_freeze_max_age: i64,
+    #[diesel(sql_type = BigInt)]
+    /// The `block_size` setting, in bytes.
+    pub block_size: i64,
+}
+
+impl Default for VacuumSettings {
+    fn default() -> Self {
+        Self {
+            vacuum_threshold: 50,
+            vacuum_scale_factor: 0.2,
+            analyze_threshold: 50,
+            analyze_scale_factor: 0.1,
+            freeze_max_age: 200_000_000,
+            multixact_freeze_max_age: 400_000_000,
+            block_size: 8192,
+        }
+    }
+}
+
+impl VacuumSettings {
+    /// Loads the settings of the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load(conn: &mut PgConnection) -> Result<Self, diesel::result::Error> {
+        diesel::sql_query(
+            r"
+            SELECT
+                current_setting('autovacuum_vacuum_threshold')::bigint AS vacuum_threshold,
+                current_setting('autovacuum_vacuum_scale_factor')::float8 AS vacuum_scale_factor,
+                current_setting('autovacuum_analyze_threshold')::bigint AS analyze_threshold,
+                current_setting('autovacuum_analyze_scale_factor')::float8 AS analyze_scale_factor,
+                current_setting('autovacuum_freeze_max_age')::bigint AS freeze_max_age,
+                current_setting('autovacuum_multixact_freeze_max_age')::bigint
+                    AS multixact_freeze_max_age,
+                current_setting('block_size')::bigint AS block_size
+            ",
+        )
+        .get_result(conn)
+    }
+
+    /// Returns the settings overridden by the storage parameters of a table,
+    /// as in `autovacuum_vacuum_scale_factor=0.01`.
+    fn with_overrides(mut self, parameters: &StorageParameters) -> Self {
+        for (name, value) in parameters.autovacuum_overrides() {
+            match name {
+                "autovacuum_vacuum_threshold" => {
+                    self.vacuum_threshold = value.as_integer().unwrap_or(self.vacuum_threshold);
+                }
+                "autovacuum_vacuum_scale_factor" => {
+                    self.vacuum_scale_factor = value.as_real().unwrap_or(self.vacuum_scale_factor);
+                }
+                "autovacuum_analyze_threshold" => {
+                    self.analyze_threshold = value.as_integer().unwrap_or(self.analyze_threshold);
+                }
+                "autovacuum_analyze_scale_factor" => {
+                    self.analyze_scale_factor =
+                        value.as_real().unwrap_or(self.analyze_scale_factor);
+                }
+                "autovacuum_freeze_max_age" => {
+                    self.freeze_max_age = value.as_integer().unwrap_or(self.freeze_max_age);
+                }
+                "autovacuum_multixact_freeze_max_age" => {
+                    self.multixact_freeze_max_age =
+                        value.as_integer().unwrap_or(self.multixact_freeze_max_age);
+                }
+                _ => {}
+            }
+        }
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The risk of transaction ID or multixact ID wraparound of a table or of a
+/// database.
+pub enum WraparoundRisk {
+    /// The oldest unfrozen ID is younger than the freeze maximum age.
+    Low,
+    /// The freeze maximum age is exceeded, so an aggressive autovacuum is
+    /// forced, even if autovacuum is disabled.
+    AutovacuumForced,
+    /// Over a billion IDs are unfrozen: the forced autovacuum is not keeping
+    /// up, or is blocked by a long transaction or a stale replication slot.
+    High,
+    /// Over two billion IDs are unfrozen: the server is about to stop
+    /// assigning new IDs to protect the data.
+    Critical,
+}
+
+impl WraparoundRisk {
+    #[must_use]
+    /// Returns the risk associated to the age of the oldest unfrozen ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `age` - The age of the oldest unfrozen ID.
+    /// * `freeze_max_age` - The age from which autovacuum is forced.
+    pub fn from_age(age: i64, freeze_max_age: i64) -> Self {
+        if age >= CRITICAL_WRAPAROUND_AGE {
+            Self::Critical
+        } else if age >= HIGH_WRAPAROUND_AGE {
+            Self::High
+        } else if age >= freeze_max_age {
+            Self::AutovacuumForced
+        } else {
+            Self::Low
+        }
+    }
+}
+
+#[derive(QueryableByName, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The vacuum-related statistics of a user table.
+pub struct TableVacuumStatistics {
+    #[diesel(sql_type = Oid)]
+    /// The OID of the table.
+    pub relid: u32,
+    #[diesel(sql_type = Text)]
+    /// The schema of the table.
+    pub schema_name: String,
+    #[diesel(sql_type = Text)]
+    /// The name of the table.
+    pub table_name: String,
+    #[diesel(sql_type = BigInt)]
+    /// The estimated number of live tuples.
+    pub n_live_tup: i64,
+    #[diesel(sql_type = BigInt)]
+    /// The estimated number of dead tuples.
+    pub n_dead_tup: i64,
+    #[diesel(sql_type = BigInt)]
+    /// The estimated number of tuples modified since the last analyze.
+    pub n_mod_since_analyze: i64,
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    /// When the table was last vacuumed manually.
+    pub last_vacuum: Option<SystemTime>,
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    /// When the table was last vacuumed by autovacuum.
+    pub last_autovacuum: Option<SystemTime>,
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    /// When the table was last analyzed manually.
+    pub last_analyze: Option<SystemTime>,
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    /// When the table was last analyzed by autovacuum.
+    pub last_autoanalyze: Option<SystemTime>,
+    #[diesel(sql_type = Integer)]
+    /// The size of the heap in pages, as of the last vacuum or analyze.
+    pub relpages: i32,
+    #[diesel(sql_type = Float)]
+    /// The number of live tuples, as of the last vacuum or analyze, or `-1`
+    /// if the table was never vacuumed nor analyzed.
+    pub reltuples: f32,
+    #[diesel(sql_type = BigInt)]
+    /// The current size of the heap, in bytes.
+    pub table_bytes: i64,
+    #[diesel(sql_type = Nullable<BigInt>)]
+    /// The sum of the average widths of the columns, from `pg_stats`.
+    pub row_width: Option<i64>,
+    #[diesel(sql_type = Nullable<Array<Text>>)]
+    /// The storage parameters of the table, as in `fillfactor=70`.
+    pub reloptions: Option<Vec<String>>,
+    #[diesel(sql_type = Integer)]
+    /// The age of the oldest unfrozen transaction ID of the table.
+    pub xid_age: i32,
+    #[diesel(sql_type = Integer)]
+    /// The age of the oldest unfrozen multixact ID of the table.
+    pub mxid_age: i32,
+}
+
+impl TableVacuumStatistics {
+    /// Loads the statistics of the user tables, leaving out the partitioned
+    /// tables, which hold no rows themselves and whose `relfrozenxid` and
+    /// `relminmxid` are `0`, so that their partitions are reported instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        diesel::sql_query(
+            r"
+            SELECT
+                s.relid,
+                s.schemaname::text AS schema_name,
+                s.relname::text AS table_name,
+                s.n_live_tup,
+                s.n_dead_tup,
+                s.n_mod_since_analyze,
+                s.last_vacuum,
+                s.last_autovacuum,
+                s.last_analyze,
+                s.last_autoanalyze,
+                c.relpages,
+                c.reltuples,
+                pg_relation_size(c.oid) AS table_bytes,
+                (
+                    SELECT SUM(st.avg_width)::bigint FROM pg_stats st
+                    WHERE st.schemaname = s.schemaname AND st.tablename = s.relname
+                        AND NOT st.inherited
+                ) AS row_width,
+                c.reloptions::text[] AS reloptions,
+                age(c.relfrozenxid) AS xid_age,
+                mxid_age(c.relminmxid) AS mxid_age
+            FROM pg_stat_user_tables s
+            JOIN pg_class c ON c.oid = s.relid
+            WHERE c.relkind <> 'p'
+            ORDER BY s.schemaname, s.relname
+            ",
+        )
+        .load(conn)
+    }
+
+    #[must_use]
+    /// Returns the storage parameters of the table.
+    pub fn storage_parameters(&self) -> StorageParameters {
+        StorageParameters::parse(self.reloptions.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The vacuum health of a user table.
+pub struct TableVacuumHealth {
+    /// The statistics the health derives from.
+    statistics: TableVacuumStatistics,
+    /// The settings in effect for the table.
+    settings: VacuumSettings,
+    /// Whether autovacuum is enabled for the table.
+    autovacuum_enabled: bool,
+    /// The `fillfactor` of the table, as a percentage.
+    fillfactor: u8,
+}
+
+impl TableVacuumHealth {
+    #[must_use]
+    /// Creates the health of the table from its statistics and the
+    /// server-wide settings, which its storage parameters override.
+    pub fn new(statistics: TableVacuumStatistics, settings: VacuumSettings) -> Self {
+        let parameters = statistics.storage_parameters();
+        Self {
+            statistics,
+            settings: settings.with_overrides(&parameters),
+            autovacuum_enabled: parameters.autovacuum_enabled().unwrap_or(true),
+            fillfactor: parameters.fillfactor().unwrap_or(100),
+        }
+    }
+
+    #[must_use]
+    /// Returns the statistics the health derives from.
+    pub fn statistics(&self) -> &TableVacuumStatistics {
+        &self.statistics
+    }
+
+    #[must_use]
+    /// Returns the autovacuum settings in effect for the table.
+    pub fn settings(&self) -> &VacuumSettings {
+        &self.settings
+    }
+
+    #[must_use]
+    /// Returns whether autovacuum is enabled for the table.
+    pub fn autovacuum_enabled(&self) -> bool {
+        self.autovacuum_enabled
+    }
+
+    #[must_use]
+    /// Returns the ratio of dead tuples among all the tuples of the table,
+    /// between 0 and 1.
+    pub fn dead_tuple_ratio(&self) -> f64 {
+        let dead = self.statistics.n_dead_tup.to_f64().unwrap_or_default();
+        let live = self.statistics.n_live_tup.to_f64().unwrap_or_default();
+        if dead + live > 0.0 {
+            dead / (dead + live)
+        } else {
+            0.0
+        }
+    }
+
+    #[must_use]
+    /// Returns the estimated number of bytes of the heap which hold no live
+    /// tuple.
+    ///
+    /// The estimate compares the size of the heap with the size the live
+    /// tuples would take when densely packed, from the average width of the
+    /// columns recorded in `pg_stats` and the fill factor of the table. For
+    /// tables never analyzed, the size of the heap is prorated by the ratio
+    /// of dead tuples instead.
+    pub fn estimated_bloat_bytes(&self) -> i64 {
+        let table_bytes = self.statistics.table_bytes.to_f64().unwrap_or_default();
+        let expected_bytes = self.expected_bytes();
+        let bloat = match expected_bytes {
+            Some(expected_bytes) => (table_bytes - expected_bytes).max(0.0),
+            None => table_bytes * self.dead_tuple_ratio(),
+        };
+        bloat.round().to_i64().unwrap_or_default()
+    }
+
+    #[must_use]
+    /// Returns the estimated ratio of the heap which holds no live tuple,
+    /// between 0 and 1.
+    pub fn estimated_bloat_ratio(&self) -> f64 {
+        let table_bytes = self.statistics.table_bytes.to_f64().unwrap_or_default();
+        if table_bytes > 0.0 {
+            self.estimated_bloat_bytes().to_f64().unwrap_or_default() / table_bytes
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the bytes the live tuples would take when densely packed.
+    fn expected_bytes(&self) -> Option<f64> {
+        let tuples = f64::from(self.statistics.reltuples);
+        let row_width = self.statistics.row_width?.to_f64()?;
+        if tuples < 0.0 {
+            return None;
+        }
+        let fillfactor = f64::from(self.fillfactor);
+        let block_size = self.settings.block_size.to_f64()?;
+        let usable_bytes = block_size * fillfactor / 100.0 - PAGE_HEADER_BYTES;
+        let pages = (tuples * (row_width + TUPLE_OVERHEAD_BYTES) / usable_bytes).ceil();
+        Some(pages * block_size)
+    }
+
+    #[must_use]
+    /// Returns whether the dead tuples exceed the threshold from which
+    /// autovacuum vacuums the table.
+    pub fn needs_vacuum(&self) -> bool {
+        let threshold = self.settings.vacuum_threshold.to_f64().unwrap_or_default()
+            + self.settings.vacuum_scale_factor * f64::from(self.statistics.reltuples.max(0.0));
+        self.statistics.n_dead_tup.to_f64().unwrap_or_default() > threshold
+    }
+
+    #[must_use]
+    /// Returns whether the statistics of the table are stale: the tuples
+    /// modified since the last analyze exceed the threshold from which
+    /// autovacuum analyzes the table, or the table holds tuples but was
+    /// never analyzed.
+    pub fn has_stale_statistics(&self) -> bool {
+        let never_analyzed =
+            self.statistics.last_analyze.is_none() && self.statistics.last_autoanalyze.is_none();
+        let threshold = self.settings.analyze_threshold.to_f64().unwrap_or_default()
+            + self.settings.analyze_scale_factor * f64::from(self.statistics.reltuples.max(0.0));
+        (never_analyzed && self.statistics.n_live_tup > 0)
+            || self
+                .statistics
+                .n_mod_since_analyze
+                .to_f64()
+                .unwrap_or_default()
+                > threshold
+    }
+
+    #[must_use]
+    /// Returns when the table was last vacuumed, manually or not.
+    pub fn last_vacuumed(&self) -> Option<SystemTime> {
+        self.statistics
+            .last_vacuum
+            .max(self.statistics.last_autovacuum)
+    }
+
+    #[must_use]
+    /// Returns when the table was last analyzed, manually or not.
+    pub fn last_analyzed(&self) -> Option<SystemTime> {
+        self.statistics
+            .last_analyze
+            .max(self.statistics.last_autoanalyze)
+    }
+
+    #[must_use]
+    /// Returns the risk of wraparound of the table, the highest of the
+    /// transaction ID and multixact ID ones.
+    pub fn wraparound_risk(&self) -> WraparoundRisk {
+        WraparoundRisk::from_age(self.statistics.xid_age.into(), self.settings.freeze_max_age).max(
+            WraparoundRisk::from_age(
+                self.statistics.mxid_age.into(),
+                self.settings.multixact_freeze_max_age,
+            ),
+        )
+    }
+}
+
+#[derive(QueryableByName, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The age of the oldest unfrozen IDs of a database.
+pub struct DatabaseFreezeAge {
+    #[diesel(sql_type = Text)]
+    /// The name of the database.
+    pub datname: String,
+    #[diesel(sql_type = Integer)]
+    /// The age of the oldest unfrozen transaction ID of the database.
+    pub xid_age: i32,
+    #[diesel(sql_type = Integer)]
+    /// The age of the oldest unfrozen multixact ID of the database.
+    pub mxid_age: i32,
+}
+
+impl DatabaseFreezeAge {
+    /// Loads the freeze ages of the databases accepting connections.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_all(conn: &mut PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        diesel::sql_query(
+            r"
+            SELECT
+                datname::text AS datname,
+                age(datfrozenxid) AS xid_age,
+                mxid_age(datminmxid) AS mxid_age
+            FROM pg_database
+            WHERE datallowconn
+            ORDER BY datname
+            ",
+        )
+        .load(conn)
+    }
+
+    #[must_use]
+    /// Returns the risk of wraparound of the database, the highest of the
+    /// transaction ID and multixact ID ones.
+    pub fn wraparound_risk(&self, settings: &VacuumSettings) -> WraparoundRisk {
+        WraparoundRisk::from_age(self.xid_age.into(), settings.freeze_max_age).max(
+            WraparoundRisk::from_age(self.mxid_age.into(), settings.multixact_freeze_max_age),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The vacuum health of the user tables and of the databases of a server.
+pub struct VacuumReport {
+    /// The server-wide autovacuum settings.
+    settings: VacuumSettings,
+    /// The health of the user tables of the current database.
+    tables: Vec<TableVacuumHealth>,
+    /// The freeze ages of the databases.
+    databases: Vec<DatabaseFreezeAge>,
+    /// The vacuums in progress.
+    in_progress: Vec<PgStatProgressVacuum>,
+}
+
+impl VacuumReport {
+    /// Loads the report for the current database.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load(conn: &mut PgConnection) -> Result<Self, diesel::result::Error> {
+        let settings = VacuumSettings::load(conn)?;
+        Ok(Self::new(
+            settings,
+            TableVacuumStatistics::load_all(conn)?,
+            DatabaseFreezeAge::load_all(conn)?,
+            PgStatProgressVacuum::load_all(conn)?,
+        ))
+    }
+
+    #[must_use]
+    /// Creates the report from the provided settings and statistics.
+    pub fn new(
+        settings: VacuumSettings,
+        tables: Vec<TableVacuumStatistics>,
+        databases: Vec<DatabaseFreezeAge>,
+        in_progress: Vec<PgStatProgressVacuum>,
+    ) -> Self {
+        Self {
+            settings,
+            tables: tables
+                .into_iter()
+                .map(|statistics| TableVacuumHealth::new(statistics, settings))
+                .collect(),
+            databases,
+            in_progress,
+        }
+    }
+
+    #[must_use]
+    /// Returns the server-wide autovacuum settings.
+    pub fn settings(&self) -> &VacuumSettings {
+        &self.settings
+    }
+
+    #[must_use]
+    /// Returns the health of the user tables of the current database.
+    pub fn tables(&self) -> &[TableVacuumHealth] {
+        &self.tables
+    }
+
+    #[must_use]
+    /// Returns the freeze ages of the databases.
+    pub fn databases(&self) -> &[DatabaseFreezeAge] {
+        &self.databases
+    }
+
+    #[must_use]
+    /// Returns the vacuums in progress, including on other databases.
+    pub fn in_progress(&self) -> &[PgStatProgressVacuum] {
+        &self.in_progress
+    }
+
+    #[must_use]
+    /// Returns the health of the table being vacuumed, if it belongs to the
+    /// current database.
+    pub fn vacuumed_table(&self, progress: &PgStatProgressVacuum) -> Option<&TableVacuumHealth> {
+        self.tables
+            .iter()
+            .find(|table| Some(table.statistics.relid) == progress.relid)
+    }
+
+    /// Returns the tables whose statistics are stale.
+    pub fn stale_statistics(&self) -> impl Iterator<Item = &TableVacuumHealth> {
+        self.tables
+            .iter()
+            .filter(|table| table.has_stale_statistics())
+    }
+
+    #[must_use]
+    /// Returns the tables, sorted by decreasing estimated bloat in bytes.
+    pub fn most_bloated(&self) -> Vec<&TableVacuumHealth> {
+        let mut tables: Vec<&TableVacuumHealth> = self.tables.iter().collect();
+        tables.sort_by_key(|table| std::cmp::Reverse(table.estimated_bloat_bytes()));
+        tables
+    }
+
+    /// Returns the tables and databases whose wraparound risk is at least
+    /// the provided one, as `(schema, table)` or `(database, None)` pairs.
+    pub fn wraparound_risks(
+        &self,
+        at_least: WraparoundRisk,
+    ) -> impl Iterator<Item = (String, Option<String>, WraparoundRisk)> + '_ {
+        let databases = self.databases.iter().filter_map(move |database| {
+            let risk = database.wraparound_risk(&self.settings);
+            (risk >= at_least).then(|| (database.datname.clone(), None, risk))
+        });
+        let tables = self.tables.iter().filter_map(move |table| {
+            let risk = table.wraparound_risk();
+            (risk >= at_least).then(|| {
+                (
+                    table.statistics.schema_name.clone(),
+                    Some(table.statistics.table_name.clone()),
+                    risk,
+                )
+            })
+        });
+        databases.chain(tables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the statistics of a table of 10 000 tuples of 100 bytes.
+    fn statistics(n_dead_tup: i64, relpages: i32) -> TableVacuumStatistics {
+        TableVacuumStatistics {
+            relid: 1,
+            schema_name: "public".to_owned(),
+            table_name: "events".to_owned(),
+            n_live_tup: 10_000,
+            n_dead_tup,
+            n_mod_since_analyze: 500,
+            last_vacuum: None,
+            last_autovacuum: Some(SystemTime::UNIX_EPOCH),
+            last_analyze: None,
+            last_autoanalyze: Some(SystemTime::UNIX_EPOCH),
+            relpages,
+            reltuples: 10_000.0,
+            table_bytes: i64::from(relpages) * 8192,
+            row_width: Some(100),
+            reloptions: None,
+            xid_age: 1_000,
+            mxid_age: 0,
+        }
+    }
+
+    #[test]
+    fn test_table_health() {
+        // 10 000 tuples of 128 bytes with their overhead fit in 157 pages.
+        let health = TableVacuumHealth::new(statistics(3_000, 314), VacuumSettings::default());
+        assert!(health.needs_vacuum());
+        assert!(!health.has_stale_statistics());
+        assert_eq!(health.estimated_bloat_bytes(), 157 * 8192);
+        assert!((health.estimated_bloat_ratio() - 0.5).abs() < f64::EPSILON);
+        assert!((health.dead_tuple_ratio() - 3_000.0 / 13_000.0).abs() < f64::EPSILON);
+        assert_eq!(health.wraparound_risk(), WraparoundRisk::Low);
+
+        let mut tuned = statistics(3_000, 157);
+        tuned.reloptions = Some(vec![
+            "autovacuum_vacuum_scale_factor=0.5".to_owned(),
+            "autovacuum_analyze_scale_factor=0.01".to_owned(),
+            "autovacuum_enabled=false".to_owned(),
+        ]);
+        tuned.xid_age = 250_000_000;
+        let health = TableVacuumHealth::new(tuned, VacuumSettings::default());
+        assert!(!health.needs_vacuum());
+        assert!(health.has_stale_statistics());
+        assert!(!health.autovacuum_enabled());
+        assert_eq!(health.estimated_bloat_bytes(), 0);
+        assert_eq!(health.wraparound_risk(), WraparoundRisk::AutovacuumForced);
+    }
+
+    #[test]
+    fn test_report() {
+        let databases = vec![DatabaseFreezeAge {
+            datname: "app".to_owned(),
+            xid_age: 1_200_000_000,
+            mxid_age: 0,
+        }];
+        let report = VacuumReport::new(
+            VacuumSettings::default(),
+            vec![statistics(0, 157)],
+            databases,
+            Vec::new(),
+        );
+        let risks: Vec<_> = report
+            .wraparound_risks(WraparoundRisk::AutovacuumForced)
+            .collect();
+        assert_eq!(risks, vec![("app".to_owned(), None, WraparoundRisk::High)]);
+        assert_eq!(report.stale_statistics().count(), 0);
+        assert_eq!(report.most_bloated().len(), 1);
+    }
+}
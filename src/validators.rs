@@ -0,0 +1,440 @@
+//! Submodule generating Rust validation code from check constraints.
+//!
+//! The [`ValidatorGenerator`] translates the check constraints of a table,
+//! and the check constraints of the domains its columns use, into a
+//! `validate` method for the struct holding a row to be inserted. This way,
+//! most constraint violations are caught before the row reaches the database.
+//!
+//! The generated struct is expected to have a field named after each column,
+//! with keywords suffixed by `_` as `diesel print-schema` does, holding an
+//! `Option` when the column is nullable, with the following types:
+//!
+//! - integers as `i16`, `i32` or `i64`;
+//! - floating point numbers as `f32` or `f64`;
+//! - numerics as `bigdecimal::BigDecimal`;
+//! - strings as `String`;
+//! - booleans as `bool`.
+//!
+//! The translation covers comparisons, `BETWEEN`, `IN`, `= ANY` with array
+//! literals, `IS [NOT] NULL`, `LIKE`, regular expression matches, boolean
+//! logic, arithmetic and the immutable functions `length`, `char_length`,
+//! `octet_length`, `lower`, `upper`, `trim` and `abs`. Regular expressions
+//! are compiled with the `regex` crate, whose syntax matches the common subset
+//! of the `PostgreSQL` one, so the generated code depends on the `regex` and
+//! `bigdecimal` crates when such constraints are present. The constraints
+//! using anything else are reported as [`UntranslatableConstraint`] rather
+//! than silently skipped.
+
+use std::{collections::HashMap, fmt::Write};
+
+use diesel::PgConnection;
+use sql_traits::traits::{CheckConstraintLike, DatabaseLike};
+use sqlparser::{ast::Expr, dialect::PostgreSqlDialect, parser::Parser};
+
+use crate::{
+    PgDieselDatabase,
+    models::{CheckConstraint, Column, PgType, Table},
+    quoting::rust_identifier,
+};
+
+mod translator;
+
+use translator::{Field, Translator, ValueType};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A check constraint translated into Rust code.
+pub struct TranslatedConstraint {
+    /// The name of the constraint.
+    constraint_name: String,
+    /// The SQL expression of the constraint.
+    expression: String,
+    /// The Rust expression evaluating to `Some(false)` when the constraint is
+    /// violated.
+    code: String,
+}
+
+impl TranslatedConstraint {
+    /// Returns the name of the constraint.
+    #[must_use]
+    pub fn constraint_name(&self) -> &str {
+        &self.constraint_name
+    }
+
+    /// Returns the SQL expression of the constraint.
+    #[must_use]
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Returns the Rust expression of the constraint, of type `Option<bool>`
+    /// and evaluating to `Some(false)` when the constraint is violated.
+    #[must_use]
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A check constraint which could not be translated into Rust code.
+pub struct UntranslatableConstraint {
+    /// The name of the constraint.
+    constraint_name: String,
+    /// The SQL expression of the constraint.
+    expression: String,
+    /// Why the constraint could not be translated.
+    reason: String,
+}
+
+impl UntranslatableConstraint {
+    /// Returns the name of the constraint.
+    #[must_use]
+    pub fn constraint_name(&self) -> &str {
+        &self.constraint_name
+    }
+
+    /// Returns the SQL expression of the constraint.
+    #[must_use]
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Returns why the constraint could not be translated.
+    #[must_use]
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The validation code generated for a table.
+pub struct TableValidator {
+    /// The schema of the table.
+    schema: String,
+    /// The name of the table.
+    table: String,
+    /// The constraints translated into Rust code.
+    constraints: Vec<TranslatedConstraint>,
+    /// The constraints which could not be translated.
+    untranslatable: Vec<UntranslatableConstraint>,
+}
+
+impl TableValidator {
+    /// Returns the schema of the table.
+    #[must_use]
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// Returns the name of the table.
+    #[must_use]
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Returns the constraints translated into Rust code.
+    #[must_use]
+    pub fn constraints(&self) -> &[TranslatedConstraint] {
+        &self.constraints
+    }
+
+    /// Returns the constraints which could not be translated.
+    #[must_use]
+    pub fn untranslatable(&self) -> &[UntranslatableConstraint] {
+        &self.untranslatable
+    }
+
+    /// Returns the `impl` block providing the `validate` method for the
+    /// struct with the provided name.
+    ///
+    /// The method returns the name of the first violated constraint. The
+    /// constraints which could not be translated are listed as comments.
+    #[must_use]
+    pub fn to_rust(&self, struct_name: &str) -> String {
+        let mut code = format!(
+            "impl {struct_name} {{\n    /// Validates the row against the check constraints of `{}.{}`.\n    ///\n    /// # Errors\n    ///\n    /// Returns the name of the first violated constraint.\n    pub fn validate(&self) -> Result<(), &'static str> {{\n",
+            self.schema, self.table
+        );
+        for untranslatable in &self.untranslatable {
+            let _ = writeln!(
+                code,
+                "        // Not translated: `{}` ({}): {}",
+                untranslatable.constraint_name,
+                untranslatable.reason,
+                untranslatable.expression.replace('\n', " ")
+            );
+        }
+        for constraint in &self.constraints {
+            let _ = write!(
+                code,
+                "        // {}: {}\n        if ({}) == Some(false) {{\n            return Err({:?});\n        }}\n",
+                constraint.constraint_name,
+                constraint.expression.replace('\n', " "),
+                constraint.code,
+                constraint.constraint_name
+            );
+        }
+        code.push_str("        Ok(())\n    }\n}\n");
+        code
+    }
+
+    /// Translates the constraint and records it as translated or not.
+    fn add(&mut self, constraint_name: &str, expression: &Expr, translator: &Translator<'_>) {
+        match translator.translate_check(expression) {
+            Ok(code) => self.constraints.push(TranslatedConstraint {
+                constraint_name: constraint_name.to_owned(),
+                expression: expression.to_string(),
+                code,
+            }),
+            Err(reason) => self.untranslatable.push(UntranslatableConstraint {
+                constraint_name: constraint_name.to_owned(),
+                expression: expression.to_string(),
+                reason,
+            }),
+        }
+    }
+}
+
+#[derive(Clone)]
+/// Generator of Rust validators for the tables of a [`PgDieselDatabase`].
+pub struct ValidatorGenerator<'db> {
+    /// The database to generate validators for.
+    database: &'db PgDieselDatabase,
+    /// Whether the columns with a default value are `Option` fields.
+    optional_default_columns: bool,
+    /// The check constraints of the domains used by the columns, by domain
+    /// OID, including those of the domains they are based on.
+    domain_constraints: HashMap<u32, Vec<CheckConstraint>>,
+    /// The base types of the domains used by the columns, by domain OID.
+    domain_base_types: HashMap<u32, PgType>,
+}
+
+impl<'db> ValidatorGenerator<'db> {
+    /// Creates a new `ValidatorGenerator` for the provided database.
+    #[must_use]
+    pub fn new(database: &'db PgDieselDatabase) -> Self {
+        Self {
+            database,
+            optional_default_columns: false,
+            domain_constraints: HashMap::new(),
+            domain_base_types: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    /// Sets whether the columns with a default value, including identities,
+    /// are `Option` fields in the validated structs, as is common for
+    /// insertable structs leaving them to the database.
+    pub fn optional_default_columns(mut self, optional: bool) -> Self {
+        self.optional_default_columns = optional;
+        self
+    }
+
+    /// Loads the check constraints and base types of the domains used by the
+    /// columns, which are otherwise neither validated nor typed.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to a `PgConnection`
+    ///
+    /// # Errors
+    ///
+    /// * If an error occurs while querying the database
+    pub fn load_domain_constraints(
+        mut self,
+        conn: &mut PgConnection,
+    ) -> Result<Self, diesel::result::Error> {
+        let database = self.database;
+        for table in database.tables() {
+            for column in database
+                .table_metadata(table)
+                .expect("Table must exist in database")
+                .columns()
+            {
+                let pg_type = self.column_type(column);
+                if pg_type.typbasetype == 0 || self.domain_base_types.contains_key(&pg_type.oid) {
+                    continue;
+                }
+                let mut constraints = Vec::new();
+                let mut base_type = pg_type.clone();
+                while base_type.typbasetype != 0 {
+                    constraints.extend(base_type.domain_check_constraints(conn)?);
+                    base_type = PgType::from_oid(base_type.typbasetype, conn)?;
+                }
+                self.domain_constraints.insert(pg_type.oid, constraints);
+                self.domain_base_types.insert(pg_type.oid, base_type);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Returns the validators of the base tables of the database.
+    #[must_use]
+    pub fn validators(&self) -> Vec<TableValidator> {
+        self.database
+            .tables()
+            .filter(|table| table.table_type == "BASE TABLE")
+            .map(|table| self.table_validator(table))
+            .collect()
+    }
+
+    /// Returns the validator of the provided table.
+    ///
+    /// # Panics
+    ///
+    /// * If the table does not belong to the database.
+    #[must_use]
+    pub fn table_validator(&self, table: &Table) -> TableValidator {
+        let metadata = self
+            .database
+            .table_metadata(table)
+            .expect("Table must exist in database");
+        let columns: Vec<&Column> = metadata
+            .columns()
+            .filter(|column| column.is_generated != "ALWAYS")
+            .collect();
+        let fields: HashMap<String, Field> = columns
+            .iter()
+            .map(|column| (column.column_name.clone(), self.field(column)))
+            .collect();
+
+        let mut validator = TableValidator {
+            schema: table.table_schema.clone(),
+            table: table.table_name.clone(),
+            constraints: Vec::new(),
+            untranslatable: Vec::new(),
+        };
+        for check_constraint in metadata.check_constraints() {
+            validator.add(
+                &check_constraint.constraint_name,
+                CheckConstraintLike::expression(check_constraint, self.database),
+                &Translator::new(&fields),
+            );
+        }
+        for column in columns {
+            let pg_type = self.column_type(column);
+            for check_constraint in self
+                .domain_constraints
+                .get(&pg_type.oid)
+                .into_iter()
+                .flatten()
+            {
+                let translator = Translator::for_domain(&fields, &column.column_name);
+                match Parser::new(&PostgreSqlDialect {})
+                    .try_with_sql(&check_constraint.check_clause)
+                    .and_then(|mut parser| parser.parse_expr())
+                {
+                    Ok(expression) => {
+                        validator.add(&check_constraint.constraint_name, &expression, &translator);
+                    }
+                    Err(error) => validator.untranslatable.push(UntranslatableConstraint {
+                        constraint_name: check_constraint.constraint_name.clone(),
+                        expression: check_constraint.check_clause.clone(),
+                        reason: format!("the expression could not be parsed: {error}"),
+                    }),
+                }
+            }
+        }
+        validator
+    }
+
+    /// Returns the type of the column, as loaded with the database.
+    fn column_type(&self, column: &Column) -> &'db PgType {
+        self.database
+            .column_metadata(column)
+            .expect("Column must exist in database")
+            .pg_type()
+    }
+
+    /// Returns the field of the validated struct corresponding to the column.
+    fn field(&self, column: &Column) -> Field {
+        let pg_type = self.column_type(column);
+        let base_type = self.domain_base_types.get(&pg_type.oid).unwrap_or(pg_type);
+        let has_default =
+            column.column_default.is_some() || column.is_identity.as_deref() == Some("YES");
+        Field {
+            rust_name: rust_identifier(&column.column_name),
+            value_type: ValueType::from_type_name(&base_type.typname),
+            optional: column.__is_nullable == "YES"
+                || (self.optional_default_columns && has_default),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_rust() {
+        let validator = TableValidator {
+            schema: "public".to_owned(),
+            table: "users".to_owned(),
+            constraints: vec![TranslatedConstraint {
+                constraint_name: "users_age_check".to_owned(),
+                expression: "age >= 0".to_owned(),
+                code: "Some(true)".to_owned(),
+            }],
+            untranslatable: vec![UntranslatableConstraint {
+                constraint_name: "users_created_check".to_owned(),
+                expression: "created_at <= now()".to_owned(),
+                reason: "function `now` is not supported with 0 arguments".to_owned(),
+            }],
+        };
+        let code = validator.to_rust("NewUser");
+        assert!(code.starts_with("impl NewUser {\n"));
+        assert!(code.contains(
+            "if (Some(true)) == Some(false) {\n            return Err(\"users_age_check\");"
+        ));
+        assert!(code.contains("// Not translated: `users_created_check`"));
+        assert!(code.ends_with("        Ok(())\n    }\n}\n"));
+        assert_eq!(
+            validator.untranslatable()[0].reason(),
+            "function `now` is not supported with 0 arguments"
+        );
+    }
+
+    #[test]
+    fn test_to_rust_parses() {
+        let fields: HashMap<String, Field> = [
+            ("type", ValueType::Text, false),
+            ("self", ValueType::Integer, true),
+        ]
+        .into_iter()
+        .map(|(name, value_type, optional)| {
+            (
+                name.to_owned(),
+                Field {
+                    rust_name: rust_identifier(name),
+                    value_type: Some(value_type),
+                    optional,
+                },
+            )
+        })
+        .collect();
+        let mut validator = TableValidator {
+            schema: "public".to_owned(),
+            table: "items".to_owned(),
+            constraints: Vec::new(),
+            untranslatable: Vec::new(),
+        };
+        for (name, sql) in [
+            ("items_type_check", "\"type\" IN ('a', 'b')"),
+            ("items_self_check", "\"self\" > 0"),
+        ] {
+            let expression = Parser::new(&PostgreSqlDialect {})
+                .try_with_sql(sql)
+                .unwrap()
+                .parse_expr()
+                .unwrap();
+            validator.add(name, &expression, &Translator::new(&fields));
+        }
+        assert_eq!(validator.constraints().len(), 2);
+        let code = validator.to_rust("NewItem");
+        assert!(code.contains("self.type_"));
+        assert!(code.contains("self.self_"));
+        syn::parse_file(&code).expect("the generated validator must be valid Rust");
+    }
+}
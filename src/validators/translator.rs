@@ -0,0 +1,970 @@
+//! Submodule translating check constraint expressions into Rust expressions.
+//!
+//! Every translated expression evaluates to an `Option`, where `None` stands
+//! for the SQL `NULL`, so that the three-valued logic of SQL is preserved: a
+//! check constraint is only violated when its expression evaluates to
+//! `Some(false)`.
+
+use std::{collections::HashMap, str::FromStr};
+
+use bigdecimal::BigDecimal;
+use sqlparser::ast::{
+    BinaryOperator, Expr, FunctionArg, FunctionArgExpr, FunctionArguments, UnaryOperator, Value,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The Rust type of a translated expression, wrapped in an `Option`.
+pub(super) enum ValueType {
+    /// A `bool`.
+    Boolean,
+    /// An `i64`, to which all the integer fields are widened.
+    Integer,
+    /// An `f64`, to which all the floating point fields are widened.
+    Float,
+    /// An owned `bigdecimal::BigDecimal`.
+    Decimal,
+    /// A `&str`.
+    Text,
+}
+
+impl ValueType {
+    /// Returns the type corresponding to the provided `PostgreSQL` type name,
+    /// as found in `pg_type` or in a cast.
+    pub(super) fn from_type_name(type_name: &str) -> Option<Self> {
+        let type_name = type_name.to_lowercase();
+        let type_name = type_name
+            .split('(')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .trim_start_matches("pg_catalog.");
+        Some(match type_name {
+            "bool" | "boolean" => Self::Boolean,
+            "int2" | "int4" | "int8" | "smallint" | "integer" | "int" | "bigint" => Self::Integer,
+            "float4" | "float8" | "real" | "double precision" | "float" => Self::Float,
+            "numeric" | "decimal" => Self::Decimal,
+            "text" | "varchar" | "character varying" | "bpchar" | "char" | "character" | "name"
+            | "citext" => Self::Text,
+            _ => return None,
+        })
+    }
+
+    /// Returns the name of the type, as used in the reported reasons.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Boolean => "boolean",
+            Self::Integer => "integer",
+            Self::Float => "floating point",
+            Self::Decimal => "numeric",
+            Self::Text => "text",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A field of the validated struct.
+pub(super) struct Field {
+    /// The Rust name of the field.
+    pub(super) rust_name: String,
+    /// The type of the field, if it has a Rust counterpart.
+    pub(super) value_type: Option<ValueType>,
+    /// Whether the field is an `Option`.
+    pub(super) optional: bool,
+}
+
+#[derive(Clone)]
+/// A translated Rust expression.
+struct Translated {
+    /// The Rust code, evaluating to an `Option` of the type.
+    code: String,
+    /// The type of the expression, or `None` for the `NULL` literal.
+    value_type: Option<ValueType>,
+    /// The Rust code of the value without the `Option`, for literals.
+    bare: Option<String>,
+}
+
+impl Translated {
+    /// Creates a translated expression which is not a literal.
+    fn new(code: String, value_type: ValueType) -> Self {
+        Self {
+            code,
+            value_type: Some(value_type),
+            bare: None,
+        }
+    }
+
+    /// Creates the translation of the `NULL` literal.
+    fn null() -> Self {
+        Self {
+            code: "None".to_owned(),
+            value_type: None,
+            bare: None,
+        }
+    }
+
+    /// Creates a translated literal.
+    fn literal(bare: String, value_type: ValueType) -> Self {
+        Self {
+            code: format!("Some({bare})"),
+            value_type: Some(value_type),
+            bare: Some(bare),
+        }
+    }
+
+    /// Returns whether the expression is the `NULL` literal.
+    fn is_null(&self) -> bool {
+        self.value_type.is_none()
+    }
+}
+
+/// Translator of check constraint expressions over the fields of a struct.
+pub(super) struct Translator<'a> {
+    /// The fields of the struct, by column name.
+    fields: &'a HashMap<String, Field>,
+    /// The column the `VALUE` keyword refers to, for domain constraints.
+    value_column: Option<&'a str>,
+}
+
+impl<'a> Translator<'a> {
+    /// Creates a translator over the provided fields.
+    pub(super) fn new(fields: &'a HashMap<String, Field>) -> Self {
+        Self {
+            fields,
+            value_column: None,
+        }
+    }
+
+    /// Creates a translator for a domain constraint, where `VALUE` refers to
+    /// the provided column.
+    pub(super) fn for_domain(fields: &'a HashMap<String, Field>, column: &'a str) -> Self {
+        Self {
+            fields,
+            value_column: Some(column),
+        }
+    }
+
+    /// Translates a check constraint expression into Rust code evaluating to an
+    /// `Option<bool>`, or returns the reason why it cannot be translated.
+    pub(super) fn translate_check(&self, expr: &Expr) -> Result<String, String> {
+        let translated = self.translate(expr, Some(ValueType::Boolean))?;
+        match translated.value_type {
+            None | Some(ValueType::Boolean) => Ok(translated.code),
+            Some(other) => Err(format!("the expression is {}, not boolean", other.name())),
+        }
+    }
+
+    /// Translates the expression, typing literals as `expected` when provided.
+    fn translate(&self, expr: &Expr, expected: Option<ValueType>) -> Result<Translated, String> {
+        match expr {
+            Expr::Nested(inner) => self.translate(inner, expected),
+            Expr::Identifier(ident) => self.field(&ident.value),
+            Expr::CompoundIdentifier(idents) => self.field(
+                idents
+                    .last()
+                    .map(|ident| ident.value.as_str())
+                    .unwrap_or_default(),
+            ),
+            Expr::Value(value) => literal(&value.value, false, expected),
+            Expr::UnaryOp { op, expr: inner } => self.unary(op, inner, expected),
+            Expr::Cast {
+                expr: inner,
+                data_type,
+                ..
+            } => self.cast(inner, &data_type.to_string()),
+            Expr::BinaryOp { left, op, right } => self.binary(left, op, right),
+            Expr::IsNull(inner) => self.null_test(inner, "is_none"),
+            Expr::IsNotNull(inner) => self.null_test(inner, "is_some"),
+            Expr::IsTrue(inner) => self.truth_test(inner, "==", true),
+            Expr::IsNotTrue(inner) => self.truth_test(inner, "!=", true),
+            Expr::IsFalse(inner) => self.truth_test(inner, "==", false),
+            Expr::IsNotFalse(inner) => self.truth_test(inner, "!=", false),
+            Expr::Between {
+                expr: inner,
+                negated,
+                low,
+                high,
+            } => {
+                let lower = self.compare(low, &BinaryOperator::LtEq, inner)?;
+                let upper = self.compare(inner, &BinaryOperator::LtEq, high)?;
+                let between = and(&lower.code, &upper.code);
+                Ok(Translated::new(
+                    if *negated { not(&between) } else { between },
+                    ValueType::Boolean,
+                ))
+            }
+            Expr::InList {
+                expr: inner,
+                list,
+                negated,
+            } => self.membership(inner, list, *negated),
+            Expr::AnyOp {
+                left,
+                compare_op: BinaryOperator::Eq,
+                right,
+                ..
+            } => self.membership(left, array_elements(right)?, false),
+            Expr::AllOp {
+                left,
+                compare_op: BinaryOperator::NotEq,
+                right,
+            } => self.membership(left, array_elements(right)?, true),
+            Expr::Like {
+                negated,
+                expr: inner,
+                pattern,
+                escape_char: None,
+                any: false,
+            } => self.like(inner, pattern, *negated, false),
+            Expr::ILike {
+                negated,
+                expr: inner,
+                pattern,
+                escape_char: None,
+                any: false,
+            } => self.like(inner, pattern, *negated, true),
+            Expr::Function(function) => {
+                let name = function.name.to_string().to_lowercase();
+                let FunctionArguments::List(list) = &function.args else {
+                    return Err(format!("function `{name}` is called without argument list"));
+                };
+                let arguments = list
+                    .args
+                    .iter()
+                    .map(|argument| match argument {
+                        FunctionArg::Unnamed(FunctionArgExpr::Expr(argument)) => Ok(argument),
+                        _ => Err(format!("function `{name}` is called with named arguments")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.function(name.trim_start_matches("pg_catalog."), &arguments)
+            }
+            _ => Err(format!("expression `{expr}` is not supported")),
+        }
+    }
+
+    /// Returns the translation of a reference to a column.
+    fn field(&self, name: &str) -> Result<Translated, String> {
+        let name = match self.value_column {
+            Some(column) if name.eq_ignore_ascii_case("value") => column,
+            _ => name,
+        };
+        let field = self
+            .fields
+            .get(name)
+            .ok_or_else(|| format!("column `{name}` is not a field"))?;
+        let value_type = field
+            .value_type
+            .ok_or_else(|| format!("column `{name}` has a type without Rust counterpart"))?;
+        let access = format!("self.{}", field.rust_name);
+        let code = match (value_type, field.optional) {
+            (ValueType::Integer, true) => format!("{access}.map(i64::from)"),
+            (ValueType::Integer, false) => format!("Some(i64::from({access}))"),
+            (ValueType::Float, true) => format!("{access}.map(f64::from)"),
+            (ValueType::Float, false) => format!("Some(f64::from({access}))"),
+            (ValueType::Decimal, true) => format!("{access}.clone()"),
+            (ValueType::Decimal, false) => format!("Some({access}.clone())"),
+            (ValueType::Text, true) => format!("{access}.as_deref()"),
+            (ValueType::Text, false) => format!("Some({access}.as_str())"),
+            (ValueType::Boolean, true) => access,
+            (ValueType::Boolean, false) => format!("Some({access})"),
+        };
+        Ok(Translated::new(code, value_type))
+    }
+
+    /// Translates a unary operation, folding the negation of a literal.
+    fn unary(
+        &self,
+        op: &UnaryOperator,
+        inner: &Expr,
+        expected: Option<ValueType>,
+    ) -> Result<Translated, String> {
+        if *op == UnaryOperator::Minus
+            && let Expr::Value(value) = inner
+        {
+            return literal(&value.value, true, expected);
+        }
+        let operand = self.translate(inner, None)?;
+        let Some(value_type) = operand.value_type else {
+            return Ok(operand);
+        };
+        let code = match (op, value_type) {
+            (UnaryOperator::Not, ValueType::Boolean) => not(&operand.code),
+            (UnaryOperator::Plus, ValueType::Integer | ValueType::Float | ValueType::Decimal) => {
+                operand.code
+            }
+            (UnaryOperator::Minus, ValueType::Integer) => {
+                format!("({}).and_then(i64::checked_neg)", operand.code)
+            }
+            (UnaryOperator::Minus, ValueType::Float | ValueType::Decimal) => {
+                format!("({}).map(|value| -value)", operand.code)
+            }
+            _ => {
+                return Err(format!(
+                    "operator `{op}` is not supported on {} values",
+                    value_type.name()
+                ));
+            }
+        };
+        Ok(Translated::new(code, value_type))
+    }
+
+    /// Translates a cast to the provided type.
+    fn cast(&self, inner: &Expr, type_name: &str) -> Result<Translated, String> {
+        let target = ValueType::from_type_name(type_name)
+            .ok_or_else(|| format!("cast to `{type_name}` is not supported"))?;
+        if is_literal(inner) {
+            return self.translate(inner, Some(target));
+        }
+        let operand = self.translate(inner, None)?;
+        match operand.value_type {
+            None => Ok(operand),
+            Some(source) if source == target => Ok(operand),
+            Some(ValueType::Integer) if target == ValueType::Decimal => Ok(Translated::new(
+                format!("({}).map(bigdecimal::BigDecimal::from)", operand.code),
+                target,
+            )),
+            Some(source) => Err(format!(
+                "cast from {} to {} is not supported",
+                source.name(),
+                target.name()
+            )),
+        }
+    }
+
+    /// Translates a binary operation.
+    fn binary(&self, left: &Expr, op: &BinaryOperator, right: &Expr) -> Result<Translated, String> {
+        match op {
+            BinaryOperator::And | BinaryOperator::Or => {
+                let left = self.translate(left, Some(ValueType::Boolean))?;
+                let right = self.translate(right, Some(ValueType::Boolean))?;
+                for operand in [&left, &right] {
+                    if let Some(value_type) = operand.value_type
+                        && value_type != ValueType::Boolean
+                    {
+                        return Err(format!("operand of `{op}` is {}", value_type.name()));
+                    }
+                }
+                let code = if *op == BinaryOperator::And {
+                    and(&left.code, &right.code)
+                } else {
+                    or(&left.code, &right.code)
+                };
+                Ok(Translated::new(code, ValueType::Boolean))
+            }
+            BinaryOperator::Eq
+            | BinaryOperator::NotEq
+            | BinaryOperator::Lt
+            | BinaryOperator::LtEq
+            | BinaryOperator::Gt
+            | BinaryOperator::GtEq => self.compare(left, op, right),
+            BinaryOperator::Plus
+            | BinaryOperator::Minus
+            | BinaryOperator::Multiply
+            | BinaryOperator::Divide
+            | BinaryOperator::Modulo => self.arithmetic(left, op, right),
+            BinaryOperator::StringConcat => {
+                let (left, right) = self.operands(left, right)?;
+                if left.is_null() || right.is_null() {
+                    return Ok(Translated::null());
+                }
+                Ok(Translated::new(
+                    format!(
+                        "(match ({}, {}) {{ (Some(left), Some(right)) => Some(format!(\"{{left}}{{right}}\")), _ => None }}).as_deref()",
+                        text_operand(&left)?,
+                        text_operand(&right)?
+                    ),
+                    ValueType::Text,
+                ))
+            }
+            BinaryOperator::PGRegexMatch => self.regex(left, right, false, false),
+            BinaryOperator::PGRegexIMatch => self.regex(left, right, false, true),
+            BinaryOperator::PGRegexNotMatch => self.regex(left, right, true, false),
+            BinaryOperator::PGRegexNotIMatch => self.regex(left, right, true, true),
+            _ => Err(format!("operator `{op}` is not supported")),
+        }
+    }
+
+    /// Translates both operands of a binary operation, typing literals after
+    /// the other operand and widening integers compared to numerics.
+    fn operands(&self, left: &Expr, right: &Expr) -> Result<(Translated, Translated), String> {
+        let (left, right) = if is_literal(left) && !is_literal(right) {
+            let right = self.translate(right, None)?;
+            (self.translate(left, right.value_type)?, right)
+        } else {
+            let left = self.translate(left, None)?;
+            let right = self.translate(right, left.value_type)?;
+            (left, right)
+        };
+        match (left.value_type, right.value_type) {
+            (Some(ValueType::Integer), Some(ValueType::Decimal)) => Ok((widen(left), right)),
+            (Some(ValueType::Decimal), Some(ValueType::Integer)) => Ok((left, widen(right))),
+            (Some(left_type), Some(right_type)) if left_type != right_type => Err(format!(
+                "{} and {} values cannot be combined",
+                left_type.name(),
+                right_type.name()
+            )),
+            _ => Ok((left, right)),
+        }
+    }
+
+    /// Translates a comparison.
+    fn compare(
+        &self,
+        left: &Expr,
+        op: &BinaryOperator,
+        right: &Expr,
+    ) -> Result<Translated, String> {
+        let (left, right) = self.operands(left, right)?;
+        if left.is_null() || right.is_null() {
+            return Ok(Translated::new(
+                "None::<bool>".to_owned(),
+                ValueType::Boolean,
+            ));
+        }
+        let operator = match op {
+            BinaryOperator::Eq => "==",
+            BinaryOperator::NotEq => "!=",
+            BinaryOperator::Lt => "<",
+            BinaryOperator::LtEq => "<=",
+            BinaryOperator::Gt => ">",
+            _ => ">=",
+        };
+        Ok(Translated::new(
+            format!(
+                "match ({}, {}) {{ (Some(left), Some(right)) => Some(left {operator} right), _ => None }}",
+                left.code, right.code
+            ),
+            ValueType::Boolean,
+        ))
+    }
+
+    /// Translates an arithmetic operation, with checked integer operations.
+    fn arithmetic(
+        &self,
+        left: &Expr,
+        op: &BinaryOperator,
+        right: &Expr,
+    ) -> Result<Translated, String> {
+        let (left, right) = self.operands(left, right)?;
+        let value_type = left
+            .value_type
+            .or(right.value_type)
+            .unwrap_or(ValueType::Integer);
+        if left.is_null() || right.is_null() {
+            return Ok(Translated {
+                value_type: Some(value_type),
+                ..Translated::null()
+            });
+        }
+        let (checked, operator) = match op {
+            BinaryOperator::Plus => ("checked_add", "+"),
+            BinaryOperator::Minus => ("checked_sub", "-"),
+            BinaryOperator::Multiply => ("checked_mul", "*"),
+            BinaryOperator::Divide => ("checked_div", "/"),
+            _ => ("checked_rem", "%"),
+        };
+        let body = match value_type {
+            ValueType::Integer => format!("(Some(left), Some(right)) => left.{checked}(right)"),
+            ValueType::Float => format!("(Some(left), Some(right)) => Some(left {operator} right)"),
+            ValueType::Decimal if matches!(operator, "/" | "%") => format!(
+                "(Some(left), Some(right)) if right != bigdecimal::BigDecimal::from(0) => Some(left {operator} right)"
+            ),
+            ValueType::Decimal => {
+                format!("(Some(left), Some(right)) => Some(left {operator} right)")
+            }
+            ValueType::Boolean | ValueType::Text => {
+                return Err(format!(
+                    "operator `{op}` is not supported on {} values",
+                    value_type.name()
+                ));
+            }
+        };
+        Ok(Translated::new(
+            format!(
+                "match ({}, {}) {{ {body}, _ => None }}",
+                left.code, right.code
+            ),
+            value_type,
+        ))
+    }
+
+    /// Translates a `IS [NOT] NULL` test.
+    fn null_test(&self, inner: &Expr, method: &str) -> Result<Translated, String> {
+        let operand = self.translate(inner, None)?;
+        let code = if operand.is_null() {
+            format!("Some(None::<bool>.{method}())")
+        } else {
+            format!("Some(({}).{method}())", operand.code)
+        };
+        Ok(Translated::new(code, ValueType::Boolean))
+    }
+
+    /// Translates a `IS [NOT] TRUE` or `IS [NOT] FALSE` test.
+    fn truth_test(&self, inner: &Expr, operator: &str, value: bool) -> Result<Translated, String> {
+        let operand = self.translate(inner, Some(ValueType::Boolean))?;
+        if let Some(value_type) = operand.value_type
+            && value_type != ValueType::Boolean
+        {
+            return Err(format!("truth test on {} value", value_type.name()));
+        }
+        let code = if operand.is_null() {
+            "None::<bool>".to_owned()
+        } else {
+            operand.code
+        };
+        Ok(Translated::new(
+            format!("Some(({code}) {operator} Some({value}))"),
+            ValueType::Boolean,
+        ))
+    }
+
+    /// Translates a membership test, as in `IN`, `= ANY` and `<> ALL`.
+    fn membership(&self, inner: &Expr, list: &[Expr], negated: bool) -> Result<Translated, String> {
+        let operand = self.translate(inner, None)?;
+        let items = list
+            .iter()
+            .map(|item| self.operands_with(&operand, item))
+            .collect::<Result<Vec<_>, String>>()?;
+        let operand = items
+            .first()
+            .map_or(operand, |(_, operand)| operand.clone());
+        let bare_items = items
+            .iter()
+            .map(|(item, _)| item.bare.clone())
+            .collect::<Option<Vec<_>>>()
+            .filter(|_| {
+                matches!(
+                    operand.value_type,
+                    Some(ValueType::Integer | ValueType::Text | ValueType::Boolean)
+                )
+            });
+        if let Some(bare_items) = bare_items {
+            let negation = if negated { "!" } else { "" };
+            return Ok(Translated::new(
+                format!(
+                    "({}).map(|value| {negation}[{}].contains(&value))",
+                    operand.code,
+                    bare_items.join(", ")
+                ),
+                ValueType::Boolean,
+            ));
+        }
+        let membership = items
+            .iter()
+            .map(|(item, operand)| {
+                if item.is_null() || operand.is_null() {
+                    "None::<bool>".to_owned()
+                } else {
+                    format!(
+                        "match ({}, {}) {{ (Some(left), Some(right)) => Some(left == right), _ => None }}",
+                        operand.code, item.code
+                    )
+                }
+            })
+            .reduce(|left, right| or(&left, &right))
+            .unwrap_or_else(|| "Some(false)".to_owned());
+        Ok(Translated::new(
+            if negated {
+                not(&membership)
+            } else {
+                membership
+            },
+            ValueType::Boolean,
+        ))
+    }
+
+    /// Translates an item of a membership test against the already translated
+    /// operand, returning the item and the possibly widened operand.
+    fn operands_with(
+        &self,
+        operand: &Translated,
+        item: &Expr,
+    ) -> Result<(Translated, Translated), String> {
+        let item = self.translate(item, operand.value_type)?;
+        match (operand.value_type, item.value_type) {
+            (Some(ValueType::Integer), Some(ValueType::Decimal)) => {
+                Ok((item, widen(operand.clone())))
+            }
+            (Some(ValueType::Decimal), Some(ValueType::Integer)) => {
+                Ok((widen(item), operand.clone()))
+            }
+            (Some(left), Some(right)) if left != right => Err(format!(
+                "{} and {} values cannot be compared",
+                left.name(),
+                right.name()
+            )),
+            _ => Ok((item, operand.clone())),
+        }
+    }
+
+    /// Translates a regular expression match against a literal pattern.
+    fn regex(
+        &self,
+        left: &Expr,
+        right: &Expr,
+        negated: bool,
+        case_insensitive: bool,
+    ) -> Result<Translated, String> {
+        let Some(pattern) = string_literal(right) else {
+            return Err(format!("pattern `{right}` is not a string literal"));
+        };
+        let pattern = if case_insensitive {
+            format!("(?i){pattern}")
+        } else {
+            pattern
+        };
+        self.pattern_match(left, &pattern, negated)
+    }
+
+    /// Translates a `LIKE` or `ILIKE` match against a literal pattern.
+    fn like(
+        &self,
+        left: &Expr,
+        right: &Expr,
+        negated: bool,
+        case_insensitive: bool,
+    ) -> Result<Translated, String> {
+        let Some(pattern) = string_literal(right) else {
+            return Err(format!("pattern `{right}` is not a string literal"));
+        };
+        let mut regex = String::from(if case_insensitive { "(?si)^" } else { "(?s)^" });
+        let mut characters = pattern.chars();
+        while let Some(character) = characters.next() {
+            match character {
+                '%' => regex.push_str(".*"),
+                '_' => regex.push('.'),
+                '\\' => {
+                    if let Some(escaped) = characters.next() {
+                        push_escaped(&mut regex, escaped);
+                    }
+                }
+                _ => push_escaped(&mut regex, character),
+            }
+        }
+        regex.push('$');
+        self.pattern_match(left, &regex, negated)
+    }
+
+    /// Translates a match of the left operand against a Rust regular
+    /// expression, compiled once in a static.
+    fn pattern_match(
+        &self,
+        left: &Expr,
+        pattern: &str,
+        negated: bool,
+    ) -> Result<Translated, String> {
+        let operand = self.translate(left, Some(ValueType::Text))?;
+        if operand.is_null() {
+            return Ok(Translated::new(
+                "None::<bool>".to_owned(),
+                ValueType::Boolean,
+            ));
+        }
+        text_operand(&operand)?;
+        let negation = if negated { "!" } else { "" };
+        Ok(Translated::new(
+            format!(
+                "({}).map(|value| {{ static PATTERN: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| regex::Regex::new({pattern:?}).expect(\"valid regular expression\")); {negation}PATTERN.is_match(value) }})",
+                operand.code
+            ),
+            ValueType::Boolean,
+        ))
+    }
+
+    /// Translates a call to one of the supported immutable functions.
+    fn function(&self, name: &str, arguments: &[&Expr]) -> Result<Translated, String> {
+        let [argument] = arguments else {
+            return Err(format!(
+                "function `{name}` is not supported with {} arguments",
+                arguments.len()
+            ));
+        };
+        let operand = self.translate(argument, None)?;
+        let Some(value_type) = operand.value_type else {
+            return Ok(operand);
+        };
+        let code = &operand.code;
+        let (code, result_type) = match (name, value_type) {
+            ("length" | "char_length" | "character_length", ValueType::Text) => (
+                format!("({code}).and_then(|value| i64::try_from(value.chars().count()).ok())"),
+                ValueType::Integer,
+            ),
+            ("octet_length", ValueType::Text) => (
+                format!("({code}).and_then(|value| i64::try_from(value.len()).ok())"),
+                ValueType::Integer,
+            ),
+            ("lower", ValueType::Text) => (
+                format!("({code}).map(str::to_lowercase).as_deref()"),
+                ValueType::Text,
+            ),
+            ("upper", ValueType::Text) => (
+                format!("({code}).map(str::to_uppercase).as_deref()"),
+                ValueType::Text,
+            ),
+            ("btrim" | "trim", ValueType::Text) => {
+                (format!("({code}).map(str::trim)"), ValueType::Text)
+            }
+            ("ltrim", ValueType::Text) => {
+                (format!("({code}).map(str::trim_start)"), ValueType::Text)
+            }
+            ("rtrim", ValueType::Text) => (format!("({code}).map(str::trim_end)"), ValueType::Text),
+            ("abs", ValueType::Integer) => (
+                format!("({code}).and_then(i64::checked_abs)"),
+                ValueType::Integer,
+            ),
+            ("abs", ValueType::Float) => (format!("({code}).map(f64::abs)"), ValueType::Float),
+            ("abs", ValueType::Decimal) => (
+                format!("({code}).map(|value| value.abs())"),
+                ValueType::Decimal,
+            ),
+            _ => {
+                return Err(format!(
+                    "function `{name}` is not supported on {} values",
+                    value_type.name()
+                ));
+            }
+        };
+        Ok(Translated::new(code, result_type))
+    }
+}
+
+/// Returns the translation of a literal, typed as `expected` when provided.
+fn literal(
+    value: &Value,
+    negative: bool,
+    expected: Option<ValueType>,
+) -> Result<Translated, String> {
+    let (text, quoted) = match value {
+        Value::Null => return Ok(Translated::null()),
+        Value::Boolean(value) => {
+            return Ok(Translated::literal(value.to_string(), ValueType::Boolean));
+        }
+        Value::Number(number, _) => (number.clone(), false),
+        Value::SingleQuotedString(text)
+        | Value::EscapedStringLiteral(text)
+        | Value::NationalStringLiteral(text) => (text.clone(), true),
+        _ => return Err(format!("literal `{value}` is not supported")),
+    };
+    let text = if negative { format!("-{text}") } else { text };
+    let number = text.trim();
+    match expected {
+        Some(ValueType::Text) => Ok(Translated::literal(format!("{text:?}"), ValueType::Text)),
+        None if quoted => Ok(Translated::literal(format!("{text:?}"), ValueType::Text)),
+        Some(ValueType::Boolean) => {
+            let value = match number.to_lowercase().as_str() {
+                "t" | "true" | "y" | "yes" | "on" | "1" => true,
+                "f" | "false" | "n" | "no" | "off" | "0" => false,
+                _ => return Err(format!("`{text}` is not a boolean")),
+            };
+            Ok(Translated::literal(value.to_string(), ValueType::Boolean))
+        }
+        Some(ValueType::Decimal) => {
+            BigDecimal::from_str(number).map_err(|_| format!("`{text}` is not a number"))?;
+            Ok(Translated {
+                code: format!("{number:?}.parse::<bigdecimal::BigDecimal>().ok()"),
+                value_type: Some(ValueType::Decimal),
+                bare: None,
+            })
+        }
+        Some(ValueType::Integer) => {
+            let value = number
+                .parse::<i64>()
+                .map_err(|_| format!("`{text}` is not an integer"))?;
+            Ok(Translated::literal(
+                format!("{value}_i64"),
+                ValueType::Integer,
+            ))
+        }
+        None if number.parse::<i64>().is_ok() => Ok(Translated::literal(
+            format!("{number}_i64"),
+            ValueType::Integer,
+        )),
+        Some(ValueType::Float) | None => match number.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(Translated::literal(
+                format!("{value:?}_f64"),
+                ValueType::Float,
+            )),
+            _ => Err(format!("`{text}` is not a finite number")),
+        },
+    }
+}
+
+/// Returns whether the expression is an untyped literal.
+fn is_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Nested(inner) => is_literal(inner),
+        Expr::Value(_) => true,
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => matches!(expr.as_ref(), Expr::Value(_)),
+        _ => false,
+    }
+}
+
+/// Returns the content of a string literal, possibly cast.
+fn string_literal(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Nested(inner) | Expr::Cast { expr: inner, .. } => string_literal(inner),
+        Expr::Value(value) => match &value.value {
+            Value::SingleQuotedString(text) | Value::EscapedStringLiteral(text) => {
+                Some(text.clone())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the elements of an array expression, possibly cast.
+fn array_elements(expr: &Expr) -> Result<&[Expr], String> {
+    match expr {
+        Expr::Nested(inner) | Expr::Cast { expr: inner, .. } => array_elements(inner),
+        Expr::Array(array) => Ok(&array.elem),
+        _ => Err(format!("`{expr}` is not an array literal")),
+    }
+}
+
+/// Returns the Rust code of a text operand, or an error if it is not text.
+fn text_operand(operand: &Translated) -> Result<&str, String> {
+    match operand.value_type {
+        Some(ValueType::Text) => Ok(&operand.code),
+        Some(other) => Err(format!("{} value used as text", other.name())),
+        None => Ok("None::<&str>"),
+    }
+}
+
+/// Widens an integer expression to a `BigDecimal`.
+fn widen(operand: Translated) -> Translated {
+    if operand.is_null() {
+        return operand;
+    }
+    match operand.bare {
+        Some(bare) => Translated {
+            code: format!("Some(bigdecimal::BigDecimal::from({bare}))"),
+            value_type: Some(ValueType::Decimal),
+            bare: None,
+        },
+        None => Translated::new(
+            format!("({}).map(bigdecimal::BigDecimal::from)", operand.code),
+            ValueType::Decimal,
+        ),
+    }
+}
+
+/// Appends the character to the regular expression, escaping it if needed.
+fn push_escaped(regex: &mut String, character: char) {
+    if "\\.+*?()|[]{}^$#&-~".contains(character) {
+        regex.push('\\');
+    }
+    regex.push(character);
+}
+
+/// Returns the three-valued conjunction of two boolean expressions.
+fn and(left: &str, right: &str) -> String {
+    format!(
+        "match ({left}, {right}) {{ (Some(false), _) | (_, Some(false)) => Some(false), (Some(true), Some(true)) => Some(true), _ => None }}"
+    )
+}
+
+/// Returns the three-valued disjunction of two boolean expressions.
+fn or(left: &str, right: &str) -> String {
+    format!(
+        "match ({left}, {right}) {{ (Some(true), _) | (_, Some(true)) => Some(true), (Some(false), Some(false)) => Some(false), _ => None }}"
+    )
+}
+
+/// Returns the three-valued negation of a boolean expression.
+fn not(operand: &str) -> String {
+    format!("({operand}).map(|value| !value)")
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::{dialect::PostgreSqlDialect, parser::Parser};
+
+    use super::*;
+
+    fn fields() -> HashMap<String, Field> {
+        [
+            ("age", ValueType::Integer, false),
+            ("price", ValueType::Decimal, true),
+            ("email", ValueType::Text, true),
+            ("type", ValueType::Text, false),
+        ]
+        .into_iter()
+        .map(|(name, value_type, optional)| {
+            let rust_name = if name == "type" {
+                format!("{name}_")
+            } else {
+                name.to_owned()
+            };
+            (
+                name.to_owned(),
+                Field {
+                    rust_name,
+                    value_type: Some(value_type),
+                    optional,
+                },
+            )
+        })
+        .collect()
+    }
+
+    fn translate(sql: &str) -> Result<String, String> {
+        let expr = Parser::new(&PostgreSqlDialect {})
+            .try_with_sql(sql)
+            .unwrap()
+            .parse_expr()
+            .unwrap();
+        Translator::new(&fields()).translate_check(&expr)
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let code = translate("(age >= 0) AND (age <= 150)").unwrap();
+        assert!(code.contains("Some(i64::from(self.age))"));
+        assert!(code.contains("Some(0_i64)"));
+        assert!(code.contains("Some(left >= right)"));
+        assert!(code.starts_with("match ("));
+
+        let code = translate("price > 0").unwrap();
+        assert!(code.contains("self.price.clone()"));
+        assert!(code.contains("\"0\".parse::<bigdecimal::BigDecimal>().ok()"));
+    }
+
+    #[test]
+    fn test_membership_and_text() {
+        let code = translate(
+            "(\"type\")::text = ANY ((ARRAY['a'::character varying, 'b'::character varying])::text[])",
+        )
+        .unwrap();
+        assert_eq!(
+            code,
+            "(Some(self.type_.as_str())).map(|value| [\"a\", \"b\"].contains(&value))"
+        );
+
+        let code = translate("char_length(email) BETWEEN 3 AND 254").unwrap();
+        assert!(code.contains("self.email.as_deref()"));
+        assert!(code.contains("chars().count()"));
+
+        let code = translate("email ~* '^[a-z]+@'").unwrap();
+        assert!(code.contains("regex::Regex::new(\"(?i)^[a-z]+@\")"));
+
+        let code = translate("email IS NOT NULL").unwrap();
+        assert_eq!(code, "Some((self.email.as_deref()).is_some())");
+    }
+
+    #[test]
+    fn test_untranslatable() {
+        assert_eq!(
+            translate("missing > 0").unwrap_err(),
+            "column `missing` is not a field"
+        );
+        assert!(translate("age > now()").unwrap_err().contains("now"));
+        assert!(
+            translate("email > age")
+                .unwrap_err()
+                .contains("cannot be combined")
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Test loading the object grants and default privileges of a database
+
+mod test_utils;
+
+use diesel::{PgConnection, RunQueryDsl};
+use pg_diesel::{
+    acl::{AclObjectKind, DefaultPrivileges, Grantee, Privilege},
+    database::{PgDieselDatabaseBuilder, PgDieselDatabaseWithEventTriggers},
+};
+use test_utils::{establish_connection, reference_docker};
+
+#[tokio::test]
+async fn test_object_grants() {
+    let database_name = "test_acl";
+    let port = 35438;
+    let _docker = reference_docker(port, database_name)
+        .await
+        .expect("Failed to start docker");
+    let mut conn: PgConnection = establish_connection(port, database_name)
+        .expect("Failed to establish connection to database");
+
+    for statement in [
+        "CREATE ROLE reporting",
+        "CREATE SCHEMA app",
+        "GRANT USAGE ON SCHEMA app TO reporting",
+        "CREATE SEQUENCE app.orders_id_seq",
+        "GRANT USAGE ON SEQUENCE app.orders_id_seq TO reporting",
+        "CREATE FUNCTION app.total(amount INTEGER) RETURNS INTEGER LANGUAGE sql AS 'SELECT amount'",
+        "REVOKE EXECUTE ON FUNCTION app.total(INTEGER) FROM PUBLIC",
+        "ALTER DEFAULT PRIVILEGES IN SCHEMA app GRANT SELECT ON TABLES TO reporting",
+    ] {
+        diesel::sql_query(statement)
+            .execute(&mut conn)
+            .expect("Failed to set up the grants");
+    }
+
+    let database: PgDieselDatabaseWithEventTriggers = PgDieselDatabaseBuilder::default()
+        .connection(&mut conn)
+        .catalog(database_name)
+        .schema("app")
+        .try_into()
+        .expect("Failed to build the database");
+    let grants = |kind| {
+        database
+            .object_grants()
+            .iter()
+            .filter(|grant| grant.kind() == kind)
+            .map(|grant| {
+                format!(
+                    "{} {} {}",
+                    grant.object().name(),
+                    grant.grantee(),
+                    grant.privilege()
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let schema_grants = grants(AclObjectKind::Schema);
+    assert!(schema_grants.contains(&"app reporting USAGE".to_owned()));
+    assert!(!schema_grants.contains(&"app reporting CREATE".to_owned()));
+    assert!(grants(AclObjectKind::Sequence).contains(&"orders_id_seq reporting USAGE".to_owned()));
+    assert_eq!(
+        grants(AclObjectKind::Function),
+        ["total(amount integer) user EXECUTE"]
+    );
+    assert!(!database.object_grants().iter().any(|grant| {
+        grant.kind() == AclObjectKind::Function
+            && *grant.grantee() == Grantee::Public
+            && grant.privilege() == Privilege::Execute
+    }));
+
+    let default_privileges =
+        DefaultPrivileges::load_all(&mut conn).expect("Failed to load the default privileges");
+    let [default_privileges] = default_privileges.as_slice() else {
+        panic!("Expected exactly one default privileges entry");
+    };
+    assert_eq!(default_privileges.schema(), Some("app"));
+    assert_eq!(default_privileges.kind(), AclObjectKind::Table);
+    assert_eq!(
+        default_privileges.grant_statements(),
+        [
+            "ALTER DEFAULT PRIVILEGES FOR ROLE user IN SCHEMA app GRANT SELECT ON TABLES TO reporting"
+        ]
+    );
+}
@@ -0,0 +1,56 @@
+//! Test checking an extension manifest against a database
+
+mod test_utils;
+
+use diesel::{PgConnection, RunQueryDsl};
+use pg_diesel::extensions::{ExtensionAction, ExtensionInfo, ExtensionManifest};
+use test_utils::{establish_connection, reference_docker};
+
+#[tokio::test]
+async fn test_extension_plan() {
+    let database_name = "test_extensions";
+    let port = 35439;
+    let _docker = reference_docker(port, database_name)
+        .await
+        .expect("Failed to start docker");
+    let mut conn: PgConnection = establish_connection(port, database_name)
+        .expect("Failed to establish connection to database");
+
+    diesel::sql_query("CREATE EXTENSION IF NOT EXISTS citext")
+        .execute(&mut conn)
+        .expect("Failed to install citext");
+
+    let extensions = ExtensionInfo::load_all(&mut conn).expect("Failed to load the extensions");
+    let citext = extensions
+        .iter()
+        .find(|extension| extension.name() == "citext")
+        .expect("citext must be available");
+    assert!(citext.is_installed());
+    assert_eq!(citext.installed_schema(), Some("public"));
+    assert_eq!(citext.installed_version(), citext.default_version());
+    assert!(!citext.is_outdated());
+    let pgcrypto = extensions
+        .iter()
+        .find(|extension| extension.name() == "pgcrypto")
+        .expect("pgcrypto must be available");
+    assert!(pgcrypto.is_available());
+    assert!(!pgcrypto.is_installed());
+
+    let manifest: ExtensionManifest = "citext, pgcrypto\nno_such_extension # not shipped"
+        .parse()
+        .expect("Failed to parse the manifest");
+    let plan = manifest
+        .check_database(&mut conn)
+        .expect("Failed to check the manifest");
+    let [citext, pgcrypto, missing] = plan.actions() else {
+        panic!("Expected one action per requirement, got {plan}");
+    };
+    assert!(matches!(citext, ExtensionAction::Satisfied { name, .. } if name == "citext"));
+    assert!(matches!(pgcrypto, ExtensionAction::Install { name, .. } if name == "pgcrypto"));
+    assert!(matches!(missing, ExtensionAction::Unsatisfiable { .. }));
+    assert!(plan.has_unsatisfiable());
+    assert_eq!(plan.statements().len(), 1);
+    assert!(
+        plan.statements()[0].starts_with("CREATE EXTENSION IF NOT EXISTS \"pgcrypto\" VERSION ")
+    );
+}
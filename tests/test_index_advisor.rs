@@ -0,0 +1,131 @@
+//! Test advising on the indexes of a database
+
+mod test_utils;
+
+use diesel::{PgConnection, RunQueryDsl};
+use pg_diesel::{
+    index_advisor::{ForeignKeyColumns, IndexAdvisor, IndexFindingKind, IndexUsage},
+    severity::Severity,
+};
+use test_utils::{establish_connection, reference_docker};
+
+#[tokio::test]
+async fn test_index_findings() {
+    let database_name = "test_index_advisor";
+    let port = 35443;
+    let _docker = reference_docker(port, database_name)
+        .await
+        .expect("Failed to start docker");
+    let mut conn: PgConnection = establish_connection(port, database_name)
+        .expect("Failed to establish connection to database");
+
+    for statement in [
+        "CREATE SCHEMA app",
+        "CREATE TABLE app.customers (id INTEGER PRIMARY KEY)",
+        "CREATE TABLE app.orders (
+            id INTEGER PRIMARY KEY,
+            customer_id INTEGER NOT NULL REFERENCES app.customers (id),
+            placed_at TIMESTAMP NOT NULL
+        )",
+        "CREATE TABLE app.order_lines (
+            order_id INTEGER NOT NULL REFERENCES app.orders (id),
+            sku TEXT NOT NULL
+        )",
+        "CREATE INDEX ON app.orders (placed_at)",
+        "CREATE INDEX ON app.orders (placed_at)",
+        "CREATE INDEX ON app.orders (customer_id)",
+        "CREATE INDEX ON app.orders (customer_id, placed_at)",
+    ] {
+        diesel::sql_query(statement)
+            .execute(&mut conn)
+            .expect("Failed to set up the tables");
+    }
+
+    let indexes = IndexUsage::load_all(&mut conn).expect("Failed to load the indexes");
+    let orders_indexes: Vec<&str> = indexes
+        .iter()
+        .filter(|index| index.schema_name == "app" && index.table_name == "orders")
+        .map(|index| index.index_name.as_str())
+        .collect();
+    assert_eq!(
+        orders_indexes,
+        [
+            "orders_customer_id_idx",
+            "orders_customer_id_placed_at_idx",
+            "orders_pkey",
+            "orders_placed_at_idx",
+            "orders_placed_at_idx1",
+        ]
+    );
+    let composite = indexes
+        .iter()
+        .find(|index| index.index_name == "orders_customer_id_placed_at_idx")
+        .expect("Missing the composite index");
+    assert_eq!(composite.key_columns, ["customer_id", "placed_at"]);
+    assert_eq!(composite.access_method, "btree");
+    assert!(!composite.is_required());
+    assert!(
+        indexes
+            .iter()
+            .find(|index| index.index_name == "orders_pkey")
+            .is_some_and(|index| index.backs_constraint && index.is_required())
+    );
+
+    let foreign_keys =
+        ForeignKeyColumns::load_all(&mut conn).expect("Failed to load the foreign keys");
+    let line_order = foreign_keys
+        .iter()
+        .find(|foreign_key| foreign_key.constraint_name == "order_lines_order_id_fkey")
+        .expect("Missing the foreign key of the order lines");
+    assert_eq!(line_order.columns, ["order_id"]);
+    assert_eq!(line_order.referenced_table, "app.orders");
+
+    let findings = IndexAdvisor::default()
+        .advise(&mut conn)
+        .expect("Failed to advise on the indexes");
+    let finding = |index: &str| {
+        findings
+            .iter()
+            .find(|finding| finding.schema() == "app" && finding.index() == Some(index))
+    };
+
+    let duplicate = finding("orders_placed_at_idx1").expect("Missing the duplicate index");
+    assert_eq!(
+        duplicate.kind(),
+        &IndexFindingKind::Duplicate {
+            of: "orders_placed_at_idx".to_owned()
+        }
+    );
+    assert_eq!(
+        duplicate.suggestion(),
+        Some("DROP INDEX CONCURRENTLY \"app\".\"orders_placed_at_idx1\";")
+    );
+
+    let prefix = finding("orders_customer_id_idx").expect("Missing the prefix index");
+    assert_eq!(
+        prefix.kind(),
+        &IndexFindingKind::Prefix {
+            of: "orders_customer_id_placed_at_idx".to_owned()
+        }
+    );
+    assert_eq!(prefix.severity(), Severity::Info);
+
+    assert!(finding("orders_pkey").is_none());
+
+    let unindexed: Vec<_> = findings
+        .iter()
+        .filter(|finding| {
+            finding.schema() == "app"
+                && matches!(finding.kind(), IndexFindingKind::UnindexedForeignKey { .. })
+        })
+        .collect();
+    let [unindexed] = unindexed.as_slice() else {
+        panic!("Expected a single unindexed foreign key, got {unindexed:?}");
+    };
+    assert_eq!(unindexed.table(), "order_lines");
+    assert_eq!(unindexed.severity(), Severity::Warning);
+    assert_eq!(
+        unindexed.suggestion(),
+        Some("CREATE INDEX CONCURRENTLY ON \"app\".\"order_lines\" (order_id);")
+    );
+}
@@ -756,6 +756,13 @@ async fn test_load_all_models() {
             .select(PgMatview::as_select())
             .load::<PgMatview>(&mut conn);
     }
+    {
+        use pg_diesel::models::PgNamespace;
+        use pg_diesel::schema::pg_catalog::pg_namespace::pg_namespace::dsl::*;
+        let _ = pg_namespace
+            .select(PgNamespace::as_select())
+            .load::<PgNamespace>(&mut conn);
+    }
     {
         use pg_diesel::models::PgOpclass;
         use pg_diesel::schema::pg_catalog::pg_opclass::pg_opclass::dsl::*;
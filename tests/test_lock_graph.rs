@@ -0,0 +1,82 @@
+//! Test building the wait-for graph of a server with a blocked process
+
+mod test_utils;
+
+use std::{thread, time::Duration};
+
+use diesel::{PgConnection, RunQueryDsl, dsl::sql, sql_types::Integer};
+use pg_diesel::lock_graph::LockWaitGraph;
+use test_utils::{establish_connection, reference_docker};
+
+/// Returns the process ID of the connection.
+fn backend_pid(conn: &mut PgConnection) -> i32 {
+    diesel::select(sql::<Integer>("pg_backend_pid()"))
+        .get_result(conn)
+        .expect("Failed to get the backend PID")
+}
+
+#[tokio::test]
+async fn test_blocked_process() {
+    let database_name = "test_lock_graph";
+    let port = 35442;
+    let _docker = reference_docker(port, database_name)
+        .await
+        .expect("Failed to start docker");
+    let mut conn: PgConnection = establish_connection(port, database_name)
+        .expect("Failed to establish connection to database");
+    let mut holder: PgConnection = establish_connection(port, database_name)
+        .expect("Failed to establish connection to database");
+    let mut waiter: PgConnection = establish_connection(port, database_name)
+        .expect("Failed to establish connection to database");
+
+    diesel::sql_query("CREATE TABLE accounts (id INTEGER PRIMARY KEY)")
+        .execute(&mut conn)
+        .expect("Failed to create the table");
+    let graph = LockWaitGraph::load(&mut conn).expect("Failed to load the graph");
+    assert!(graph.waits().is_empty());
+    assert!(graph.root_blockers().is_empty());
+
+    let holder_pid = backend_pid(&mut holder);
+    let waiter_pid = backend_pid(&mut waiter);
+    for statement in ["BEGIN", "LOCK TABLE accounts IN ACCESS EXCLUSIVE MODE"] {
+        diesel::sql_query(statement)
+            .execute(&mut holder)
+            .expect("Failed to lock the table");
+    }
+    let blocked = thread::spawn(move || {
+        diesel::sql_query("SELECT * FROM accounts")
+            .execute(&mut waiter)
+            .expect("Failed to read the table");
+    });
+
+    let mut graph = LockWaitGraph::load(&mut conn).expect("Failed to load the graph");
+    for _ in 0..50 {
+        if graph.is_waiting(waiter_pid) {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+        graph = LockWaitGraph::load(&mut conn).expect("Failed to load the graph");
+    }
+
+    let [wait] = graph.waits() else {
+        panic!("Expected a single wait, got {:?}", graph.waits());
+    };
+    assert_eq!(wait.waiting_pid(), waiter_pid);
+    assert_eq!(wait.blocking_pid(), holder_pid);
+    assert_eq!(wait.locktype(), "relation");
+    assert_eq!(
+        wait.relation().map(ToString::to_string).as_deref(),
+        Some("public.accounts")
+    );
+    assert_eq!(wait.requested_mode(), "AccessShareLock");
+    assert_eq!(wait.held_mode(), "AccessExclusiveLock");
+    assert_eq!(wait.waiting_query(), Some("SELECT * FROM accounts"));
+    assert_eq!(graph.root_blockers(), [holder_pid]);
+    assert_eq!(graph.blocked_by(holder_pid), [waiter_pid]);
+    assert!(graph.deadlocks().is_empty());
+
+    diesel::sql_query("ROLLBACK")
+        .execute(&mut holder)
+        .expect("Failed to release the lock");
+    blocked.join().expect("The blocked query panicked");
+}
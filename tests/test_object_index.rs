@@ -0,0 +1,55 @@
+//! Test looking up the objects of a database with an `ObjectIndex`
+
+mod test_utils;
+
+use diesel::{PgConnection, RunQueryDsl};
+use pg_diesel::{
+    PgDieselDatabase,
+    database::{ConstraintRef, ObjectIndex, PgDieselDatabaseBuilder},
+    identity::QualifiedName,
+};
+use test_utils::{establish_connection, reference_docker};
+
+#[tokio::test]
+async fn test_constraints_with_the_same_name() {
+    let database_name = "test_object_index";
+    let port = 35435;
+    let _docker = reference_docker(port, database_name)
+        .await
+        .expect("Failed to start docker");
+    let mut conn: PgConnection = establish_connection(port, database_name)
+        .expect("Failed to establish connection to database");
+
+    diesel::sql_query(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, age INTEGER CONSTRAINT positive CHECK (age > 0))",
+    )
+    .execute(&mut conn)
+    .expect("Failed to create the users table");
+    diesel::sql_query(
+        "CREATE TABLE items (id INTEGER PRIMARY KEY, price INTEGER CONSTRAINT positive CHECK (price > 0))",
+    )
+    .execute(&mut conn)
+    .expect("Failed to create the items table");
+
+    let database: PgDieselDatabase = PgDieselDatabaseBuilder::default()
+        .connection(&mut conn)
+        .catalog(database_name)
+        .schema("public")
+        .try_into()
+        .expect("Failed to build the database");
+    let index = ObjectIndex::new(&database);
+
+    for (table, column) in [("users", "age"), ("items", "price")] {
+        let Some(ConstraintRef::Check(constraint)) =
+            index.constraint(&QualifiedName::new("public", table), "positive")
+        else {
+            panic!("The `positive` constraint of `{table}` must be indexed");
+        };
+        assert_eq!(constraint.check_clause, format!("(({column} > 0))"));
+    }
+    assert!(
+        index
+            .constraint(&QualifiedName::new("public", "users"), "missing")
+            .is_none()
+    );
+}
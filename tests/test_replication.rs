@@ -0,0 +1,61 @@
+//! Test loading the replication topology of a server
+
+mod test_utils;
+
+use diesel::{PgConnection, RunQueryDsl};
+use pg_diesel::replication::{
+    PublicationSource, ReplicaIdentity, ReplicationFinding, ReplicationTopology,
+};
+use test_utils::{establish_connection, reference_docker};
+
+#[tokio::test]
+async fn test_publication_without_replica_identity() {
+    let database_name = "test_replication";
+    let port = 35436;
+    let _docker = reference_docker(port, database_name)
+        .await
+        .expect("Failed to start docker");
+    let mut conn: PgConnection = establish_connection(port, database_name)
+        .expect("Failed to establish connection to database");
+
+    for statement in [
+        "CREATE TABLE orders (id INTEGER PRIMARY KEY, total NUMERIC)",
+        "CREATE TABLE events (payload TEXT)",
+        "CREATE PUBLICATION orders_pub FOR TABLE orders, events",
+    ] {
+        diesel::sql_query(statement)
+            .execute(&mut conn)
+            .expect("Failed to set up the publication");
+    }
+
+    let topology = ReplicationTopology::load(&mut conn).expect("Failed to load the topology");
+    let [publication] = topology.publications() else {
+        panic!("Expected exactly one publication");
+    };
+    assert_eq!(publication.name(), "orders_pub");
+    assert!(!publication.all_tables());
+    let orders = publication
+        .table("public", "orders")
+        .expect("The orders table must be published");
+    assert_eq!(orders.source(), PublicationSource::Table);
+    assert_eq!(
+        orders.relation_oid(),
+        topology
+            .relations()
+            .iter()
+            .find(|relation| relation.table_name == "orders")
+            .map(|relation| relation.relation_oid)
+    );
+    assert_eq!(topology.publications_of("public", "events").count(), 1);
+    assert!(topology.slots().is_empty());
+    assert!(topology.receiver().is_none());
+    assert_eq!(
+        topology.findings(),
+        [ReplicationFinding::MissingReplicaIdentity {
+            publication: "orders_pub".to_owned(),
+            schema: "public".to_owned(),
+            table: "events".to_owned(),
+            identity: Some(ReplicaIdentity::Default),
+        }]
+    );
+}
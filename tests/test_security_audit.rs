@@ -0,0 +1,87 @@
+//! Test auditing the security of a database
+
+mod test_utils;
+
+use diesel::{PgConnection, RunQueryDsl};
+use pg_diesel::{
+    PgDieselDatabase,
+    database::PgDieselDatabaseBuilder,
+    security_audit::{SecurityAudit, SecurityRule},
+};
+use test_utils::{establish_connection, reference_docker};
+
+#[tokio::test]
+async fn test_security_audit() {
+    let database_name = "test_security_audit";
+    let port = 35437;
+    let _docker = reference_docker(port, database_name)
+        .await
+        .expect("Failed to start docker");
+    let mut conn: PgConnection = establish_connection(port, database_name)
+        .expect("Failed to establish connection to database");
+
+    // The objects live in their own schema, away from those of PostGIS in
+    // `public`.
+    for statement in [
+        "CREATE SCHEMA app",
+        "SET search_path = app",
+        "CREATE TABLE accounts (id INTEGER PRIMARY KEY, email TEXT)",
+        "CREATE POLICY own_accounts ON accounts USING (id > 0)",
+        "GRANT SELECT ON accounts TO PUBLIC",
+        "GRANT UPDATE (email) ON accounts TO PUBLIC",
+        "CREATE TABLE invoices (id INTEGER PRIMARY KEY)",
+        "ALTER TABLE invoices ENABLE ROW LEVEL SECURITY",
+        "CREATE FUNCTION unpinned(id INTEGER) RETURNS INTEGER LANGUAGE sql SECURITY DEFINER \
+         SET search_path = app, public AS 'SELECT id'",
+        "CREATE FUNCTION pinned() RETURNS INTEGER LANGUAGE sql SECURITY DEFINER \
+         SET search_path = pg_catalog, pg_temp AS 'SELECT 1'",
+        "RESET search_path",
+    ] {
+        diesel::sql_query(statement)
+            .execute(&mut conn)
+            .expect("Failed to set up the database");
+    }
+
+    let database: PgDieselDatabase = PgDieselDatabaseBuilder::default()
+        .connection(&mut conn)
+        .catalog(database_name)
+        .schema("app")
+        .try_into()
+        .expect("Failed to build the database");
+    let audit = SecurityAudit::new(&database);
+    let findings = |rule| {
+        audit
+            .findings()
+            .filter(|finding| finding.rule() == rule)
+            .map(|finding| format!("{}: {}", finding.object(), finding.message()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        findings(SecurityRule::PublicGrant),
+        ["app.accounts: SELECT, UPDATE (email) granted to PUBLIC"]
+    );
+    assert_eq!(
+        findings(SecurityRule::PoliciesWithoutRowSecurity),
+        ["app.accounts: 1 policies defined, but row-level security is disabled"]
+    );
+    assert_eq!(
+        findings(SecurityRule::RowSecurityNotForced),
+        ["app.invoices: row-level security is not forced, so the owner bypasses the policies"]
+    );
+    assert_eq!(
+        findings(SecurityRule::UnpinnedSecurityDefiner),
+        [
+            "app.unpinned(int4): SECURITY DEFINER function with the search_path `app, public`, \
+             which has writable schemas or does not end with pg_temp"
+        ]
+    );
+    assert_eq!(
+        findings(SecurityRule::PrivilegedLoginRole),
+        ["user: SUPERUSER and BYPASSRLS role can log in"]
+    );
+    assert!(
+        findings(SecurityRule::OwnedByLoginRole)
+            .contains(&"app.pinned(): owned by the login role `user`".to_owned())
+    );
+}
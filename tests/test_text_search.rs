@@ -0,0 +1,95 @@
+//! Test loading the text search configurations of a database and the
+//! `tsvector` columns and indexes built with them
+
+mod test_utils;
+
+use diesel::{PgConnection, RunQueryDsl};
+use pg_diesel::text_search::{TextSearchConfig, TextSearchObject, TextSearchUsage};
+use test_utils::{establish_connection, reference_docker};
+
+#[tokio::test]
+async fn test_text_search_usages() {
+    let database_name = "test_text_search";
+    let port = 35440;
+    let _docker = reference_docker(port, database_name)
+        .await
+        .expect("Failed to start docker");
+    let mut conn: PgConnection = establish_connection(port, database_name)
+        .expect("Failed to establish connection to database");
+
+    for statement in [
+        "CREATE SCHEMA app",
+        "CREATE TEXT SEARCH CONFIGURATION app.english_copy (COPY = pg_catalog.english)",
+        "CREATE TABLE app.documents (
+            id INTEGER PRIMARY KEY,
+            title TEXT NOT NULL,
+            body TEXT NOT NULL,
+            search TSVECTOR GENERATED ALWAYS AS (
+                to_tsvector('english', title) || to_tsvector('english', body)
+            ) STORED
+        )",
+        "CREATE INDEX documents_search_idx ON app.documents USING gin (search)",
+        "CREATE INDEX documents_copy_idx ON app.documents
+            USING gin (to_tsvector('app.english_copy', body))",
+    ] {
+        diesel::sql_query(statement)
+            .execute(&mut conn)
+            .expect("Failed to set up the text search objects");
+    }
+
+    let configs = TextSearchConfig::load_all(&mut conn).expect("Failed to load configurations");
+    let config = |name: &str| {
+        configs
+            .iter()
+            .find(|config| config.name().to_string() == name)
+            .unwrap_or_else(|| panic!("Missing configuration {name}"))
+    };
+    let english = config("pg_catalog.english");
+    let copy = config("app.english_copy");
+    assert_eq!(english.parser().prsname, "default");
+    assert_eq!(copy.parser().prsname, "default");
+    let stemmer = |config: &TextSearchConfig| {
+        config
+            .mapping("asciiword")
+            .expect("Missing asciiword mapping")
+            .dictionaries()
+            .iter()
+            .map(|dictionary| dictionary.name().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(stemmer(english), ["pg_catalog.english_stem"]);
+    assert_eq!(stemmer(copy), stemmer(english));
+
+    let usages: Vec<TextSearchUsage> = TextSearchUsage::load_all(&mut conn)
+        .expect("Failed to load usages")
+        .into_iter()
+        .filter(|usage| usage.table().to_string() == "app.documents")
+        .collect();
+    assert_eq!(usages.len(), 3);
+
+    let [column, copy_index, search_index] = usages.as_slice() else {
+        unreachable!();
+    };
+    assert_eq!(
+        column.object(),
+        &TextSearchObject::Column("search".to_owned())
+    );
+    assert_eq!(column.configs().len(), 1);
+    assert!(column.is_built_with(english));
+
+    assert!(matches!(
+        copy_index.object(),
+        TextSearchObject::Index { name, access_method }
+            if name == "documents_copy_idx" && access_method == "gin"
+    ));
+    assert!(copy_index.is_built_with(copy));
+    assert!(!copy_index.is_built_with(english));
+
+    assert!(matches!(
+        search_index.object(),
+        TextSearchObject::Index { name, .. } if name == "documents_search_idx"
+    ));
+    assert_eq!(search_index.configs(), column.configs());
+    assert!(search_index.is_built_with(english));
+    assert!(!search_index.is_built_with(copy));
+}
@@ -0,0 +1,72 @@
+//! Test loading the vacuum report of a database
+
+mod test_utils;
+
+use diesel::{PgConnection, RunQueryDsl};
+use pg_diesel::vacuum_report::{VacuumReport, WraparoundRisk};
+use test_utils::{establish_connection, reference_docker};
+
+#[tokio::test]
+async fn test_vacuum_report() {
+    let database_name = "test_vacuum_report";
+    let port = 35441;
+    let _docker = reference_docker(port, database_name)
+        .await
+        .expect("Failed to start docker");
+    let mut conn: PgConnection = establish_connection(port, database_name)
+        .expect("Failed to establish connection to database");
+
+    for statement in [
+        "CREATE SCHEMA app",
+        "CREATE TABLE app.events (id INTEGER PRIMARY KEY, payload TEXT NOT NULL)
+            WITH (
+                fillfactor = 70,
+                autovacuum_enabled = false,
+                autovacuum_vacuum_scale_factor = 0.01
+            )",
+        "CREATE TABLE app.logs (id INTEGER, line TEXT) PARTITION BY RANGE (id)",
+        "CREATE TABLE app.logs_0 PARTITION OF app.logs FOR VALUES FROM (0) TO (1000)",
+        "INSERT INTO app.events SELECT i, md5(i::text) FROM generate_series(1, 1000) AS i",
+        "ANALYZE app.events",
+    ] {
+        diesel::sql_query(statement)
+            .execute(&mut conn)
+            .expect("Failed to set up the tables");
+    }
+
+    let report = VacuumReport::load(&mut conn).expect("Failed to load the vacuum report");
+    assert!(
+        report
+            .databases()
+            .iter()
+            .any(|database| database.datname == database_name)
+    );
+
+    let tables: Vec<&str> = report
+        .tables()
+        .iter()
+        .filter(|table| table.statistics().schema_name == "app")
+        .map(|table| table.statistics().table_name.as_str())
+        .collect();
+    assert_eq!(tables, ["events", "logs_0"]);
+
+    let events = report
+        .tables()
+        .iter()
+        .find(|table| table.statistics().table_name == "events")
+        .expect("Missing app.events");
+    assert!(!events.autovacuum_enabled());
+    assert!((events.settings().vacuum_scale_factor - 0.01).abs() < f64::EPSILON);
+    assert_eq!(
+        events.settings().vacuum_threshold,
+        report.settings().vacuum_threshold
+    );
+    assert_eq!(
+        events.statistics().storage_parameters().fillfactor(),
+        Some(70)
+    );
+    assert!((events.statistics().reltuples - 1000.0).abs() < f32::EPSILON);
+    assert!(events.statistics().row_width.is_some());
+    assert!(events.statistics().table_bytes > 0);
+    assert_eq!(events.wraparound_risk(), WraparoundRisk::Low);
+}